@@ -0,0 +1,36 @@
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum CliError {
+    /// The arguments don't form a valid command
+    #[error("{0}\nRun `satisflow help` for usage")]
+    Usage(String),
+
+    /// A file could not be read or written
+    #[error("{path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    /// `new` would overwrite an existing file
+    #[error("{0} already exists, pass --force to overwrite it")]
+    AlreadyExists(PathBuf),
+
+    /// The engine refused the save file or the change
+    #[error("{0}")]
+    Engine(String),
+}
+
+impl CliError {
+    pub(crate) fn io(path: impl Into<PathBuf>, source: std::io::Error) -> Self {
+        CliError::Io {
+            path: path.into(),
+            source,
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, CliError>;
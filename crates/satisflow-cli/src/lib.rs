@@ -0,0 +1,534 @@
+//! Command-line companion for the Satisflow engine
+//!
+//! Works on save files directly, without a server, for scripting and CI:
+//!
+//! ```text
+//! satisflow new plan.json
+//! satisflow add-factory plan.json "Iron Works" --tag iron
+//! satisflow balance plan.json
+//! satisflow export plan.json --format csv --section items > items.csv
+//! satisflow validate plan.json
+//! ```
+//!
+//! Every number comes from `satisflow-engine`, so the output matches the
+//! server and the web app.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use satisflow_engine::{
+    export,
+    models::{item_name, Item, Severity},
+    rate, CsvSection, NumberFormat, RateUnit, SatisflowEngine,
+};
+use serde_json::json;
+
+pub mod error;
+
+pub use error::{CliError, Result};
+
+/// Exit status of a check that found problems, e.g. `validate`
+pub const EXIT_CHECK_FAILED: i32 = 1;
+/// Exit status when the command could not run
+pub const EXIT_ERROR: i32 = 2;
+
+pub const USAGE: &str = "\
+Satisflow command-line companion
+
+Usage: satisflow <command> <save_file> [options]
+
+Commands:
+  new <save_file> [--force]
+      Create an empty save file
+  add-factory <save_file> <name> [--description <text>] [--tag <tag>]...
+      Add a factory and print its id
+  balance <save_file> [--item <item>] [--per-second] [--json]
+      Print the net rate of every item across all factories
+  export <save_file> [--format csv|json] [--section <section>]
+      Print a CSV table (factories, production_lines, items or logistics;
+      factories by default) or the whole save as JSON
+  validate <save_file> [--strict]
+      List the problems of a save; exits with 1 on errors, or on warnings
+      too with --strict
+  help
+      Show this message
+
+Items are named as in the game (\"Iron Ore\") or the API (IronOre).
+";
+
+/// Output format of `export`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+/// A parsed command line
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    New {
+        save: PathBuf,
+        force: bool,
+    },
+    AddFactory {
+        save: PathBuf,
+        name: String,
+        description: Option<String>,
+        tags: Vec<String>,
+    },
+    Balance {
+        save: PathBuf,
+        item: Option<Item>,
+        rate_unit: RateUnit,
+        json: bool,
+    },
+    Export {
+        save: PathBuf,
+        format: ExportFormat,
+        section: CsvSection,
+    },
+    Validate {
+        save: PathBuf,
+        strict: bool,
+    },
+    Help,
+}
+
+/// Positional arguments and options of a command
+struct Arguments {
+    positional: Vec<String>,
+    flags: Vec<String>,
+    values: HashMap<String, Vec<String>>,
+}
+
+impl Arguments {
+    /// Split `args` into positional arguments, `flags` and options taking a
+    /// value, given as `--name value` or `--name=value`
+    fn split(args: &[String], flags: &[&str], valued: &[&str]) -> Result<Self> {
+        let mut parsed = Arguments {
+            positional: Vec::new(),
+            flags: Vec::new(),
+            values: HashMap::new(),
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let Some(option) = arg.strip_prefix("--") else {
+                parsed.positional.push(arg.clone());
+                continue;
+            };
+            let (name, inline) = match option.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (option, None),
+            };
+            if flags.contains(&name) && inline.is_none() {
+                parsed.flags.push(name.to_string());
+            } else if valued.contains(&name) {
+                let value = match inline {
+                    Some(value) => value,
+                    None => args
+                        .next()
+                        .cloned()
+                        .ok_or_else(|| CliError::Usage(format!("--{} needs a value", name)))?,
+                };
+                parsed
+                    .values
+                    .entry(name.to_string())
+                    .or_default()
+                    .push(value);
+            } else {
+                return Err(CliError::Usage(format!("Unknown option --{}", name)));
+            }
+        }
+        Ok(parsed)
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|flag| flag == name)
+    }
+
+    /// Last value given for an option
+    fn value(&self, name: &str) -> Option<&str> {
+        self.values
+            .get(name)
+            .and_then(|values| values.last())
+            .map(String::as_str)
+    }
+
+    fn all_values(&self, name: &str) -> Vec<String> {
+        self.values.get(name).cloned().unwrap_or_default()
+    }
+
+    /// The positional arguments, which must be exactly `names`
+    fn expect<const N: usize>(&self, command: &str, names: [&str; N]) -> Result<&[String; N]> {
+        self.positional.as_slice().try_into().map_err(|_| {
+            let expected: Vec<String> = names.iter().map(|name| format!("<{}>", name)).collect();
+            CliError::Usage(format!(
+                "Usage: satisflow {} {}",
+                command,
+                expected.join(" ")
+            ))
+        })
+    }
+}
+
+/// Parse an item by its game name ("Iron Ore") or API name (IronOre)
+pub fn parse_item(name: &str) -> Result<Item> {
+    name.parse::<Item>()
+        .ok()
+        .or_else(|| serde_json::from_value(json!(name)).ok())
+        .ok_or_else(|| CliError::Usage(format!("Unknown item: {}", name)))
+}
+
+fn parse_section(name: &str) -> Result<CsvSection> {
+    serde_json::from_value(json!(name)).map_err(|_| {
+        CliError::Usage(format!(
+            "Unknown section {}, expected factories, production_lines, items or logistics",
+            name
+        ))
+    })
+}
+
+impl Command {
+    /// Parse the arguments following the program name
+    pub fn parse(args: &[String]) -> Result<Self> {
+        let Some((command, rest)) = args.split_first() else {
+            return Ok(Command::Help);
+        };
+
+        match command.as_str() {
+            "new" => {
+                let args = Arguments::split(rest, &["force"], &[])?;
+                let [save] = args.expect("new", ["save_file"])?;
+                Ok(Command::New {
+                    save: PathBuf::from(save),
+                    force: args.flag("force"),
+                })
+            }
+            "add-factory" => {
+                let args = Arguments::split(rest, &[], &["description", "tag"])?;
+                let [save, name] = args.expect("add-factory", ["save_file", "name"])?;
+                Ok(Command::AddFactory {
+                    save: PathBuf::from(save),
+                    name: name.clone(),
+                    description: args.value("description").map(str::to_string),
+                    tags: args.all_values("tag"),
+                })
+            }
+            "balance" => {
+                let args = Arguments::split(rest, &["per-second", "json"], &["item"])?;
+                let [save] = args.expect("balance", ["save_file"])?;
+                Ok(Command::Balance {
+                    save: PathBuf::from(save),
+                    item: args.value("item").map(parse_item).transpose()?,
+                    rate_unit: if args.flag("per-second") {
+                        RateUnit::PerSecond
+                    } else {
+                        RateUnit::PerMinute
+                    },
+                    json: args.flag("json"),
+                })
+            }
+            "export" => {
+                let args = Arguments::split(rest, &[], &["format", "section"])?;
+                let [save] = args.expect("export", ["save_file"])?;
+                let format = match args.value("format").unwrap_or("csv") {
+                    "csv" => ExportFormat::Csv,
+                    "json" => ExportFormat::Json,
+                    other => {
+                        return Err(CliError::Usage(format!(
+                            "Unknown format {}, expected csv or json",
+                            other
+                        )))
+                    }
+                };
+                Ok(Command::Export {
+                    save: PathBuf::from(save),
+                    format,
+                    section: args
+                        .value("section")
+                        .map(parse_section)
+                        .transpose()?
+                        .unwrap_or(CsvSection::Factories),
+                })
+            }
+            "validate" => {
+                let args = Arguments::split(rest, &["strict"], &[])?;
+                let [save] = args.expect("validate", ["save_file"])?;
+                Ok(Command::Validate {
+                    save: PathBuf::from(save),
+                    strict: args.flag("strict"),
+                })
+            }
+            "help" | "--help" | "-h" => Ok(Command::Help),
+            other => Err(CliError::Usage(format!("Unknown command {}", other))),
+        }
+    }
+}
+
+fn load(path: &Path) -> Result<SatisflowEngine> {
+    let json = fs::read_to_string(path).map_err(|e| CliError::io(path, e))?;
+    SatisflowEngine::load_from_json(&json)
+        .map_err(|e| CliError::Engine(format!("{}: {}", path.display(), e)))
+}
+
+fn store(engine: &SatisflowEngine, path: &Path) -> Result<()> {
+    let json = engine
+        .save_to_json()
+        .map_err(|e| CliError::Engine(e.to_string()))?;
+    fs::write(path, json).map_err(|e| CliError::io(path, e))
+}
+
+fn write_out(out: &mut impl Write, text: &str) -> Result<()> {
+    out.write_all(text.as_bytes())
+        .map_err(|e| CliError::io("<stdout>", e))
+}
+
+/// Same states as the dashboard's item balances
+fn balance_state(balance: f64) -> &'static str {
+    match rate::balance_sign(balance) {
+        Ordering::Greater => "overflow",
+        Ordering::Less => "underflow",
+        Ordering::Equal => "balanced",
+    }
+}
+
+/// Run a command, writing its output to `out`
+///
+/// # Returns
+///
+/// The exit status: 0, or [`EXIT_CHECK_FAILED`] when `validate` found problems
+pub fn run(command: Command, out: &mut impl Write) -> Result<i32> {
+    match command {
+        Command::New { save, force } => {
+            if save.exists() && !force {
+                return Err(CliError::AlreadyExists(save));
+            }
+            store(&SatisflowEngine::new(), &save)?;
+            write_out(out, &format!("Created {}\n", save.display()))?;
+        }
+        Command::AddFactory {
+            save,
+            name,
+            description,
+            tags,
+        } => {
+            if name.trim().is_empty() {
+                return Err(CliError::Usage("Factory name cannot be empty".to_string()));
+            }
+            let mut engine = load(&save)?;
+            let id = engine.create_factory(name, description);
+            if let Some(factory) = engine.get_factory_mut(id) {
+                factory.set_tags(tags);
+            }
+            store(&engine, &save)?;
+            write_out(out, &format!("{}\n", id))?;
+        }
+        Command::Balance {
+            save,
+            item,
+            rate_unit,
+            json,
+        } => {
+            let mut engine = load(&save)?;
+            let balances = engine.update();
+            let mut rows: Vec<(Item, f64)> = match item {
+                Some(item) => vec![(item, balances.get(&item).copied().unwrap_or(0.0))],
+                None => balances.into_iter().collect(),
+            };
+            rows.sort_by(|a, b| item_name(a.0).cmp(item_name(b.0)));
+
+            if json {
+                let rows: Vec<_> = rows
+                    .iter()
+                    .map(|(item, balance)| {
+                        json!({
+                            "item": item,
+                            "item_name": item_name(*item),
+                            "balance": balance,
+                            "state": balance_state(*balance),
+                        })
+                    })
+                    .collect();
+                let text = serde_json::to_string_pretty(&rows)
+                    .map_err(|e| CliError::Engine(e.to_string()))?;
+                write_out(out, &format!("{}\n", text))?;
+            } else {
+                let format = NumberFormat::default().with_rate_unit(rate_unit);
+                let mut text = String::new();
+                for (item, balance) in rows {
+                    text += &format!(
+                        "{:<32} {:>14}  {}\n",
+                        item_name(item),
+                        format.rate(balance),
+                        balance_state(balance)
+                    );
+                }
+                write_out(out, &text)?;
+            }
+        }
+        Command::Export {
+            save,
+            format,
+            section,
+        } => {
+            let engine = load(&save)?;
+            match format {
+                ExportFormat::Csv => write_out(out, &export::to_csv(&engine, section))?,
+                ExportFormat::Json => {
+                    let json = engine
+                        .save_to_json()
+                        .map_err(|e| CliError::Engine(e.to_string()))?;
+                    write_out(out, &format!("{}\n", json))?;
+                }
+            }
+        }
+        Command::Validate { save, strict } => {
+            let engine = load(&save)?;
+            let diagnostics = engine.validate_all();
+            let count = |severity: Severity| {
+                diagnostics
+                    .iter()
+                    .filter(|d| d.severity == severity)
+                    .count()
+            };
+            let (errors, warnings) = (count(Severity::Error), count(Severity::Warning));
+
+            let mut text = String::new();
+            for diagnostic in &diagnostics {
+                text += &format!(
+                    "{}: {}\n",
+                    format!("{:?}", diagnostic.severity).to_lowercase(),
+                    diagnostic.message
+                );
+            }
+            if diagnostics.is_empty() {
+                text += "No problems found\n";
+            } else {
+                text += &format!("{} errors, {} warnings\n", errors, warnings);
+            }
+            write_out(out, &text)?;
+
+            if errors > 0 || (strict && warnings > 0) {
+                return Ok(EXIT_CHECK_FAILED);
+            }
+        }
+        Command::Help => write_out(out, USAGE)?,
+    }
+    Ok(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &[&str]) -> Vec<String> {
+        line.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    /// Run a command line, returning its exit status and output
+    fn run_line(line: &[&str]) -> Result<(i32, String)> {
+        let mut out = Vec::new();
+        let status = run(Command::parse(&args(line))?, &mut out)?;
+        Ok((status, String::from_utf8(out).unwrap()))
+    }
+
+    fn temp_save(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "satisflow-cli-{}-{}.json",
+            std::process::id(),
+            name
+        ));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Command::parse(&[]).unwrap(), Command::Help);
+        assert_eq!(
+            Command::parse(&args(&[
+                "add-factory",
+                "plan.json",
+                "Iron Works",
+                "--tag=iron"
+            ]))
+            .unwrap(),
+            Command::AddFactory {
+                save: PathBuf::from("plan.json"),
+                name: "Iron Works".to_string(),
+                description: None,
+                tags: vec!["iron".to_string()],
+            }
+        );
+        assert_eq!(
+            Command::parse(&args(&["balance", "plan.json", "--item", "Iron Ore"])).unwrap(),
+            Command::Balance {
+                save: PathBuf::from("plan.json"),
+                item: Some(Item::IronOre),
+                rate_unit: RateUnit::PerMinute,
+                json: false,
+            }
+        );
+        assert_eq!(parse_item("IronOre").unwrap(), Item::IronOre);
+
+        for line in [
+            &["frobnicate"][..],
+            &["new"],
+            &["new", "plan.json", "--force=yes"],
+            &["balance", "plan.json", "--item"],
+            &["balance", "plan.json", "--item", "Unobtainium"],
+            &["export", "plan.json", "--format", "xml"],
+            &["export", "plan.json", "--section", "recipes"],
+        ] {
+            assert!(
+                matches!(Command::parse(&args(line)), Err(CliError::Usage(_))),
+                "{:?} should be refused",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn test_commands_on_a_save_file() {
+        let path = temp_save("commands");
+        let save = path.to_str().unwrap();
+
+        run_line(&["new", save]).unwrap();
+        assert!(matches!(
+            run_line(&["new", save]),
+            Err(CliError::AlreadyExists(_))
+        ));
+
+        let (_, id) = run_line(&["add-factory", save, "Iron Works", "--tag", "iron"]).unwrap();
+        let engine = load(&path).unwrap();
+        let factory = engine.get_factory(id.trim().parse().unwrap()).unwrap();
+        assert_eq!(factory.name, "Iron Works");
+        assert_eq!(factory.tags, vec!["iron"]);
+
+        let (_, csv) = run_line(&["export", save]).unwrap();
+        assert!(csv.starts_with("id,name,"));
+        assert!(csv.contains("Iron Works"));
+
+        let (_, json) = run_line(&["export", save, "--format", "json"]).unwrap();
+        assert!(SatisflowEngine::load_from_json(&json).is_ok());
+
+        let (_, balance) = run_line(&["balance", save, "--item", "IronOre", "--json"]).unwrap();
+        let rows: serde_json::Value = serde_json::from_str(&balance).unwrap();
+        assert_eq!(rows[0]["item"], "IronOre");
+        assert_eq!(rows[0]["state"], "balanced");
+
+        let (status, report) = run_line(&["validate", save]).unwrap();
+        assert_eq!(status, 0);
+        assert_eq!(report, "No problems found\n");
+
+        fs::write(&path, "not a save").unwrap();
+        assert!(matches!(
+            run_line(&["validate", save]),
+            Err(CliError::Engine(_))
+        ));
+        fs::remove_file(&path).unwrap();
+    }
+}
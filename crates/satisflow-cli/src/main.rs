@@ -0,0 +1,23 @@
+//! `satisflow` binary
+//!
+//! Parses the command line and runs it with [`satisflow_cli::run`]. Exits
+//! with 0 on success, 1 when a check such as `validate` fails and 2 when the
+//! command could not run.
+
+use std::env;
+use std::io;
+use std::process;
+
+use satisflow_cli::{Command, EXIT_ERROR};
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let status = Command::parse(&args)
+        .and_then(|command| satisflow_cli::run(command, &mut io::stdout().lock()))
+        .unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            EXIT_ERROR
+        });
+    process::exit(status);
+}
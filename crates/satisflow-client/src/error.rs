@@ -0,0 +1,36 @@
+use serde::Deserialize;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ClientError {
+    /// The request could not be sent or the response could not be decoded
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// The server answered with an error status
+    #[error("API error ({status}): {message}")]
+    Api { status: u16, message: String },
+}
+
+impl ClientError {
+    /// HTTP status returned by the server, if the server answered
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            ClientError::Http(e) => e.status().map(|status| status.as_u16()),
+            ClientError::Api { status, .. } => Some(*status),
+        }
+    }
+
+    /// Check if the server reported that the resource does not exist
+    pub fn is_not_found(&self) -> bool {
+        self.status() == Some(404)
+    }
+}
+
+/// Error body returned by the server: `{ "error": ..., "status": ... }`
+#[derive(Deserialize)]
+pub(crate) struct ErrorBody {
+    pub error: String,
+}
+
+pub type Result<T> = std::result::Result<T, ClientError>;
@@ -0,0 +1,417 @@
+//! Typed Rust client for the Satisflow REST API
+//!
+//! Lets Rust tooling, bots and integration tests talk to a running
+//! `satisflow-server` without hand-writing JSON:
+//!
+//! ```no_run
+//! use satisflow_client::{types::CreateFactory, SatisflowClient};
+//!
+//! # async fn run() -> satisflow_client::Result<()> {
+//! let client = SatisflowClient::new("http://localhost:3000");
+//! let factory = client.create_factory(&CreateFactory::new("Iron Works")).await?;
+//! let summary = client.dashboard_summary().await?;
+//! println!("{} has {} factories", factory.name, summary.total_factories);
+//! # Ok(())
+//! # }
+//! ```
+
+use reqwest::{Method, RequestBuilder, Response};
+use serde::{de::DeserializeOwned, Serialize};
+
+use satisflow_engine::models::{
+    Diagnostic, FactoryId, FlowNetwork, GoalId, Item, LogisticsId, MetricsPoint, PowerGeneratorId,
+    ProductionLineId, RecentChange, ResourceInventory, SearchHit, TagGroupStats, TrainSchedule,
+    TrainThroughput,
+};
+use satisflow_engine::CsvSection;
+
+pub mod error;
+pub mod types;
+
+pub use error::{ClientError, Result};
+
+use error::ErrorBody;
+use types::{
+    CreateFactory, CreateGoal, CreateLogistics, CreatePowerGenerator, CreateProductionLine,
+    CreateStorageBuffer, CreateTask, DashboardSummary, Factory, GoalStatus, ItemBalance,
+    LoadRequest, LoadResponse, Logistics, MessageResponse, PowerStatistics, ReportCardResponse,
+    SaveResponse, SetEnabled, UpdateFactory,
+};
+
+/// Client for a single Satisflow server
+#[derive(Debug, Clone)]
+pub struct SatisflowClient {
+    base_url: String,
+    http: reqwest::Client,
+}
+
+impl SatisflowClient {
+    /// Create a client for the server at `base_url`, e.g. `http://localhost:3000`
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::with_http_client(base_url, reqwest::Client::new())
+    }
+
+    /// Create a client reusing an existing HTTP client (timeouts, proxies, ...)
+    pub fn with_http_client(base_url: impl Into<String>, http: reqwest::Client) -> Self {
+        let base_url = base_url.into().trim_end_matches('/').to_string();
+        Self { base_url, http }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Check that the server is up
+    pub async fn health(&self) -> Result<()> {
+        let response = self
+            .http
+            .get(format!("{}/health", self.base_url))
+            .send()
+            .await?;
+        check(response).await.map(drop)
+    }
+
+    // ========== Factories ==========
+
+    pub async fn list_factories(&self) -> Result<Vec<Factory>> {
+        self.get("/factories").await
+    }
+
+    /// Factories carrying `tag`, ignoring case
+    pub async fn list_factories_with_tag(&self, tag: &str) -> Result<Vec<Factory>> {
+        let request = self
+            .request(Method::GET, "/factories")
+            .query(&[("tag", tag)]);
+        json(request.send().await?).await
+    }
+
+    pub async fn get_factory(&self, id: FactoryId) -> Result<Factory> {
+        self.get(&format!("/factories/{}", id)).await
+    }
+
+    pub async fn create_factory(&self, request: &CreateFactory) -> Result<Factory> {
+        self.send_json(Method::POST, "/factories", request).await
+    }
+
+    pub async fn update_factory(&self, id: FactoryId, request: &UpdateFactory) -> Result<Factory> {
+        self.send_json(Method::PUT, &format!("/factories/{}", id), request)
+            .await
+    }
+
+    /// Delete a factory along with the logistics lines attached to it
+    pub async fn delete_factory(&self, id: FactoryId) -> Result<()> {
+        self.delete(&format!("/factories/{}", id)).await
+    }
+
+    /// Add a production line; returns the updated factory
+    pub async fn create_production_line(
+        &self,
+        factory_id: FactoryId,
+        request: &CreateProductionLine,
+    ) -> Result<Factory> {
+        self.send_json(
+            Method::POST,
+            &format!("/factories/{}/production-lines", factory_id),
+            request,
+        )
+        .await
+    }
+
+    /// Add a power generator; returns the updated factory
+    pub async fn create_power_generator(
+        &self,
+        factory_id: FactoryId,
+        request: &CreatePowerGenerator,
+    ) -> Result<Factory> {
+        self.send_json(
+            Method::POST,
+            &format!("/factories/{}/power-generators", factory_id),
+            request,
+        )
+        .await
+    }
+
+    /// Add a storage buffer; returns the updated factory
+    pub async fn create_storage_buffer(
+        &self,
+        factory_id: FactoryId,
+        request: &CreateStorageBuffer,
+    ) -> Result<Factory> {
+        self.send_json(
+            Method::POST,
+            &format!("/factories/{}/storage-buffers", factory_id),
+            request,
+        )
+        .await
+    }
+
+    /// Add a task to a factory's checklist; returns the updated factory
+    pub async fn create_task(
+        &self,
+        factory_id: FactoryId,
+        request: &CreateTask,
+    ) -> Result<Factory> {
+        self.send_json(
+            Method::POST,
+            &format!("/factories/{}/tasks", factory_id),
+            request,
+        )
+        .await
+    }
+
+    /// Enable or disable a production line; returns the updated factory
+    pub async fn set_production_line_enabled(
+        &self,
+        factory_id: FactoryId,
+        line_id: ProductionLineId,
+        enabled: bool,
+    ) -> Result<Factory> {
+        self.send_json(
+            Method::PATCH,
+            &format!(
+                "/factories/{}/production-lines/{}/enabled",
+                factory_id, line_id
+            ),
+            &SetEnabled { enabled },
+        )
+        .await
+    }
+
+    /// Enable or disable a power generator; returns the updated factory
+    pub async fn set_power_generator_enabled(
+        &self,
+        factory_id: FactoryId,
+        generator_id: PowerGeneratorId,
+        enabled: bool,
+    ) -> Result<Factory> {
+        self.send_json(
+            Method::PATCH,
+            &format!(
+                "/factories/{}/power-generators/{}/enabled",
+                factory_id, generator_id
+            ),
+            &SetEnabled { enabled },
+        )
+        .await
+    }
+
+    // ========== Logistics ==========
+
+    pub async fn list_logistics(&self) -> Result<Vec<Logistics>> {
+        self.get("/logistics").await
+    }
+
+    pub async fn get_logistics(&self, id: LogisticsId) -> Result<Logistics> {
+        self.get(&format!("/logistics/{}", id)).await
+    }
+
+    pub async fn create_logistics(&self, request: &CreateLogistics) -> Result<Logistics> {
+        self.send_json(Method::POST, "/logistics", request).await
+    }
+
+    pub async fn delete_logistics(&self, id: LogisticsId) -> Result<()> {
+        self.delete(&format!("/logistics/{}", id)).await
+    }
+
+    /// What each wagon of a train line can move per minute
+    ///
+    /// Without `schedule`, the route runs between the factories' map positions
+    /// at the usual speed and docking time.
+    pub async fn train_throughput(
+        &self,
+        id: LogisticsId,
+        schedule: Option<&TrainSchedule>,
+    ) -> Result<TrainThroughput> {
+        let mut request = self.request(Method::GET, &format!("/logistics/{}/train-throughput", id));
+        if let Some(schedule) = schedule {
+            request = request.query(schedule);
+        }
+        json(request.send().await?).await
+    }
+
+    // ========== Goals ==========
+
+    /// List goals with their progress, least complete first
+    pub async fn list_goals(&self) -> Result<Vec<GoalStatus>> {
+        self.get("/goals").await
+    }
+
+    pub async fn create_goal(&self, request: &CreateGoal) -> Result<GoalStatus> {
+        self.send_json(Method::POST, "/goals", request).await
+    }
+
+    pub async fn delete_goal(&self, id: GoalId) -> Result<()> {
+        self.delete(&format!("/goals/{}", id)).await
+    }
+
+    // ========== Dashboard ==========
+
+    pub async fn dashboard_summary(&self) -> Result<DashboardSummary> {
+        self.get("/dashboard/summary").await
+    }
+
+    /// Global balance of every item, sorted by item
+    pub async fn item_balances(&self) -> Result<Vec<ItemBalance>> {
+        self.get("/dashboard/items").await
+    }
+
+    /// Dashboard totals for each factory tag, untagged factories last
+    pub async fn tag_groups(&self) -> Result<Vec<TagGroupStats>> {
+        self.get("/dashboard/tags").await
+    }
+
+    pub async fn power_statistics(&self) -> Result<PowerStatistics> {
+        self.get("/dashboard/power").await
+    }
+
+    pub async fn report_card(&self) -> Result<ReportCardResponse> {
+        self.get("/dashboard/report-card").await
+    }
+
+    /// Somersloops and power shards installed across all factories
+    pub async fn resource_inventory(&self) -> Result<ResourceInventory> {
+        self.get("/dashboard/resources").await
+    }
+
+    /// Power balance and item rates recorded after each change, oldest first
+    ///
+    /// With `item`, each point only carries that item's rate.
+    pub async fn metrics_history(&self, item: Option<Item>) -> Result<Vec<MetricsPoint>> {
+        let mut request = self.request(Method::GET, "/dashboard/history");
+        if let Some(item) = item {
+            request = request.query(&[("item", item)]);
+        }
+        json(request.send().await?).await
+    }
+
+    /// Most recently created or updated entities; `None` uses the server default
+    pub async fn recent_changes(&self, limit: Option<usize>) -> Result<Vec<RecentChange>> {
+        let mut request = self.request(Method::GET, "/recent-changes");
+        if let Some(limit) = limit {
+            request = request.query(&[("limit", limit)]);
+        }
+        json(request.send().await?).await
+    }
+
+    /// Factories, lines, logistics, items and recipes matching `query`, best first
+    pub async fn search(&self, query: &str, limit: Option<usize>) -> Result<Vec<SearchHit>> {
+        let mut request = self.request(Method::GET, "/search").query(&[("q", query)]);
+        if let Some(limit) = limit {
+            request = request.query(&[("limit", limit)]);
+        }
+        json(request.send().await?).await
+    }
+
+    // ========== Analysis ==========
+
+    /// Every problem found in the save, most severe first
+    pub async fn diagnostics(&self) -> Result<Vec<Diagnostic>> {
+        self.get("/analysis/diagnostics").await
+    }
+
+    /// The logistics network as Sankey nodes and weighted links
+    pub async fn flows(&self) -> Result<FlowNetwork> {
+        self.get("/analysis/flows").await
+    }
+
+    // ========== Export ==========
+
+    /// One table of the save as CSV text, header row included
+    pub async fn export_csv(&self, section: CsvSection) -> Result<String> {
+        let response = self
+            .request(Method::GET, "/export/csv")
+            .query(&[("section", section)])
+            .send()
+            .await?;
+        Ok(check(response).await?.text().await?)
+    }
+
+    /// Markdown report of one factory, or of every factory when `factory_id` is `None`
+    pub async fn markdown_report(&self, factory_id: Option<FactoryId>) -> Result<String> {
+        let mut request = self.request(Method::GET, "/export/report.md");
+        if let Some(factory_id) = factory_id {
+            request = request.query(&[("factory_id", factory_id)]);
+        }
+        Ok(check(request.send().await?).await?.text().await?)
+    }
+
+    // ========== Save / Load ==========
+
+    /// Save the engine state; `target_version` writes an older save format
+    pub async fn save(&self, target_version: Option<&str>) -> Result<SaveResponse> {
+        let mut request = self.request(Method::GET, "/save");
+        if let Some(target_version) = target_version {
+            request = request.query(&[("target_version", target_version)]);
+        }
+        json(request.send().await?).await
+    }
+
+    /// Replace the engine state with a save file's content
+    pub async fn load(&self, save_data: &str) -> Result<LoadResponse> {
+        self.send_json(Method::POST, "/load", &LoadRequest { save_data })
+            .await
+    }
+
+    /// Clear every factory and logistics line
+    pub async fn reset(&self) -> Result<String> {
+        let response = self.request(Method::POST, "/reset").send().await?;
+        json::<MessageResponse>(response)
+            .await
+            .map(|response| response.message)
+    }
+
+    // ========== Helpers ==========
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        self.http
+            .request(method, format!("{}/api{}", self.base_url, path))
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        json(self.request(Method::GET, path).send().await?).await
+    }
+
+    async fn send_json<B: Serialize + ?Sized, T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: &B,
+    ) -> Result<T> {
+        json(self.request(method, path).json(body).send().await?).await
+    }
+
+    async fn delete(&self, path: &str) -> Result<()> {
+        let response = self.request(Method::DELETE, path).send().await?;
+        check(response).await.map(drop)
+    }
+}
+
+/// Turn error statuses into [`ClientError::Api`], using the server's message
+async fn check(response: Response) -> Result<Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let body = response.text().await.unwrap_or_default();
+    let message = serde_json::from_str::<ErrorBody>(&body)
+        .map(|body| body.error)
+        .unwrap_or_else(|_| {
+            if body.is_empty() {
+                status
+                    .canonical_reason()
+                    .unwrap_or("Unknown error")
+                    .to_string()
+            } else {
+                body
+            }
+        });
+    Err(ClientError::Api {
+        status: status.as_u16(),
+        message,
+    })
+}
+
+async fn json<T: DeserializeOwned>(response: Response) -> Result<T> {
+    Ok(check(response).await?.json().await?)
+}
@@ -0,0 +1,498 @@
+//! Request and response bodies of the REST API
+//!
+//! These mirror the DTOs of `satisflow-server`. Engine models are reused as-is
+//! wherever the server sends them unchanged, so the two cannot drift apart;
+//! the server's integration tests exercise every type through this client.
+
+use serde::{Deserialize, Serialize};
+
+use satisflow_engine::models::{
+    factory_task::FactoryTask, internal_transfer::InternalTransfer,
+    power_generator::GenerationRange, power_storage::PowerStorage, production_line::ProductionLine,
+    BufferStatus, FactoryId, FuelShortfall, GeneratorType, Goal, GoalProgress, GridPowerStats,
+    Item, LogisticsId, Position, PowerGenerator, PowerGridId, ProductionLineId, Purity, RawInput,
+    ReportCard, RouteEstimate, StorageStatus, Timestamps, TransportDetails,
+};
+use satisflow_engine::{DowngradeReport, SaveFileSummary};
+
+// ========== Factories ==========
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateFactory {
+    pub name: String,
+    pub description: Option<String>,
+    pub notes: Option<String>,
+    pub tags: Vec<String>,
+    pub archived: bool,
+    pub position: Option<Position>,
+}
+
+impl CreateFactory {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            notes: None,
+            tags: Vec::new(),
+            archived: false,
+            position: None,
+        }
+    }
+
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn with_notes(mut self, notes: impl Into<String>) -> Self {
+        self.notes = Some(notes.into());
+        self
+    }
+
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    pub fn with_position(mut self, position: Position) -> Self {
+        self.position = Some(position);
+        self
+    }
+}
+
+/// Fields left to `None` are not changed
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UpdateFactory {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archived: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<Position>,
+    /// Take the factory off the map
+    pub clear_position: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemQuantity {
+    pub item: Item,
+    pub quantity: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProductionLineDetails {
+    #[serde(flatten)]
+    pub production_line: ProductionLine,
+    #[serde(flatten)]
+    pub timestamps: Timestamps,
+    pub total_power_consumption: f64,
+    pub total_machines: u32,
+    pub total_somersloop: u32,
+    pub input_rate: Vec<ItemQuantity>,
+    /// Every output, byproducts included
+    pub output_rate: Vec<ItemQuantity>,
+    /// Part of `output_rate` that is not the main product of its recipe
+    pub byproduct_rate: Vec<ItemQuantity>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RawInputDetails {
+    #[serde(flatten)]
+    pub raw_input: RawInput,
+    pub power_consumption: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PowerGeneratorDetails {
+    #[serde(flatten)]
+    pub power_generator: PowerGenerator,
+    pub total_power_generation: f64,
+    pub generation_range: GenerationRange,
+    pub total_fuel_consumption: f64,
+    pub total_water_consumption: f64,
+    pub waste_production_rate: f64,
+    pub waste_product: Option<Item>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Factory {
+    pub id: FactoryId,
+    pub name: String,
+    pub description: Option<String>,
+    pub notes: Option<String>,
+    pub tags: Vec<String>,
+    pub archived: bool,
+    pub position: Option<Position>,
+    pub power_grid: Option<PowerGridId>,
+    pub power_storage: Option<PowerStorage>,
+    pub production_lines: Vec<ProductionLineDetails>,
+    pub raw_inputs: Vec<RawInputDetails>,
+    pub power_generators: Vec<PowerGeneratorDetails>,
+    pub internal_transfers: Vec<InternalTransfer>,
+    pub storage_buffers: Vec<BufferStatus>,
+    pub tasks: Vec<FactoryTask>,
+    /// Net balance of every item in the factory, logistics included
+    pub items: Vec<ItemQuantity>,
+    pub total_power_consumption: f64,
+    pub total_power_generation: f64,
+    pub power_balance: f64,
+    pub fuel_shortfalls: Vec<FuelShortfall>,
+    pub warnings: Vec<String>,
+    #[serde(flatten)]
+    pub timestamps: Timestamps,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProductionLineType {
+    Recipe,
+    Blueprint,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MachineGroupRequest {
+    pub number_of_machine: u32,
+    pub oc_value: f64,
+    pub somersloop: u8,
+}
+
+impl MachineGroupRequest {
+    pub fn new(number_of_machine: u32, oc_value: f64, somersloop: u8) -> Self {
+        Self {
+            number_of_machine,
+            oc_value,
+            somersloop,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BlueprintSubLineRequest {
+    pub name: String,
+    pub description: Option<String>,
+    /// Recipe display name, e.g. "Iron Ingot"
+    pub recipe: String,
+    pub machine_groups: Vec<MachineGroupRequest>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateProductionLine {
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(rename = "type")]
+    pub line_type: ProductionLineType,
+    /// Recipe display name, e.g. "Iron Ingot"; recipe lines only
+    pub recipe: Option<String>,
+    pub machine_groups: Vec<MachineGroupRequest>,
+    /// Sub-lines of a blueprint line
+    pub production_lines: Vec<BlueprintSubLineRequest>,
+}
+
+impl CreateProductionLine {
+    /// A production line running a single recipe
+    pub fn recipe(
+        name: impl Into<String>,
+        recipe: impl Into<String>,
+        machine_groups: Vec<MachineGroupRequest>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            line_type: ProductionLineType::Recipe,
+            recipe: Some(recipe.into()),
+            machine_groups,
+            production_lines: Vec::new(),
+        }
+    }
+
+    /// A blueprint grouping several recipe lines
+    pub fn blueprint(
+        name: impl Into<String>,
+        production_lines: Vec<BlueprintSubLineRequest>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            line_type: ProductionLineType::Blueprint,
+            recipe: None,
+            machine_groups: Vec::new(),
+            production_lines,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GeneratorGroupRequest {
+    pub number_of_generators: u32,
+    pub clock_speed: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatePowerGenerator {
+    pub generator_type: GeneratorType,
+    /// Required for every generator type except geothermal
+    pub fuel_type: Option<Item>,
+    /// Geyser purity, geothermal generators only
+    pub purity: Option<Purity>,
+    pub groups: Vec<GeneratorGroupRequest>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateStorageBuffer {
+    pub item: Item,
+    /// Slots of all containers together
+    pub capacity_stacks: u32,
+    /// Items currently stored
+    pub stored: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateTask {
+    pub text: String,
+    pub done: bool,
+    /// Production line of the same factory the task is about
+    pub production_line: Option<ProductionLineId>,
+}
+
+/// Body of the endpoints enabling or disabling an entry of a factory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct SetEnabled {
+    pub enabled: bool,
+}
+
+// ========== Logistics ==========
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BusConveyorRequest {
+    pub line_id: Option<String>,
+    /// Conveyor tier, e.g. "Mk5"
+    pub conveyor_type: String,
+    pub item: Item,
+    pub quantity_per_min: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BusLiftRequest {
+    pub lift_id: Option<String>,
+    /// Lift tier, e.g. "Mk6"
+    pub lift_type: String,
+    pub item: Item,
+    pub quantity_per_min: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BusPipelineRequest {
+    pub pipeline_id: Option<String>,
+    /// Pipeline tier, e.g. "Mk2"
+    pub pipeline_type: String,
+    pub item: Item,
+    pub quantity_per_min: f64,
+    pub pumps_mk1: u32,
+    pub pumps_mk2: u32,
+    /// Height the fluid climbs, in meters
+    pub vertical_rise: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrainWagonRequest {
+    pub wagon_id: Option<String>,
+    /// "Cargo" or "Fluid"
+    pub wagon_type: String,
+    pub item: Item,
+    pub quantity_per_min: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "transport_type")]
+pub enum LogisticsTransport {
+    Truck {
+        item: Item,
+        quantity_per_min: f64,
+        truck_id: Option<String>,
+    },
+    Drone {
+        item: Item,
+        quantity_per_min: f64,
+        drone_id: Option<String>,
+    },
+    Bus {
+        bus_name: Option<String>,
+        conveyors: Vec<BusConveyorRequest>,
+        lifts: Vec<BusLiftRequest>,
+        pipelines: Vec<BusPipelineRequest>,
+    },
+    Train {
+        train_name: Option<String>,
+        wagons: Vec<TrainWagonRequest>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateLogistics {
+    pub from_factory: FactoryId,
+    pub to_factory: FactoryId,
+    #[serde(flatten)]
+    pub transport: LogisticsTransport,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ItemFlow {
+    pub item: Item,
+    pub quantity_per_min: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Logistics {
+    pub id: LogisticsId,
+    pub from_factory: FactoryId,
+    pub to_factory: FactoryId,
+    pub transport_type: String,
+    pub transport_id: String,
+    pub transport_name: Option<String>,
+    pub transport_details: TransportDetails,
+    pub items: Vec<ItemFlow>,
+    pub total_quantity_per_min: f64,
+    pub route: Option<RouteEstimate>,
+    #[serde(flatten)]
+    pub timestamps: Timestamps,
+}
+
+// ========== Goals ==========
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CreateGoal {
+    pub item: Item,
+    /// Wanted production, in items/min
+    pub target_rate: f64,
+    /// Factory the goal applies to; `None` for a global goal
+    pub factory_id: Option<FactoryId>,
+    pub description: Option<String>,
+}
+
+impl CreateGoal {
+    /// Goal on the production of all factories
+    pub fn global(item: Item, target_rate: f64) -> Self {
+        Self {
+            item,
+            target_rate,
+            factory_id: None,
+            description: None,
+        }
+    }
+
+    /// Goal on the production of one factory
+    pub fn for_factory(item: Item, target_rate: f64, factory_id: FactoryId) -> Self {
+        Self {
+            factory_id: Some(factory_id),
+            ..Self::global(item, target_rate)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct GoalStatus {
+    #[serde(flatten)]
+    pub goal: Goal,
+    pub actual_rate: f64,
+    pub completion_percent: f64,
+    pub is_met: bool,
+}
+
+// ========== Dashboard ==========
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DashboardSummary {
+    pub total_factories: usize,
+    pub total_production_lines: usize,
+    pub total_logistics_lines: usize,
+    pub total_power_consumption: f64,
+    pub total_power_generation: f64,
+    pub net_power: f64,
+    pub total_tasks: usize,
+    /// Tasks not done yet
+    pub open_tasks: usize,
+    /// Least complete goals first
+    pub goal_progress: Vec<GoalProgress>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ItemBalance {
+    pub item: Item,
+    pub balance: f64,
+    /// "overflow", "underflow" or "balanced"
+    pub state: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FactoryPowerStatistics {
+    pub factory_id: FactoryId,
+    pub factory_name: String,
+    pub generation: f64,
+    pub generation_range: GenerationRange,
+    pub consumption: f64,
+    pub balance: f64,
+    pub generator_count: u32,
+    pub generator_types: Vec<GeneratorType>,
+    pub power_grid: Option<PowerGridId>,
+    pub storage: Option<StorageStatus>,
+    pub fuel_shortfalls: Vec<FuelShortfall>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PowerStatistics {
+    pub total_generation: f64,
+    pub total_consumption: f64,
+    pub power_balance: f64,
+    pub generation_range: GenerationRange,
+    /// Balance when every geyser is at its lowest output
+    pub worst_case_balance: f64,
+    pub has_surplus: bool,
+    pub has_deficit: bool,
+    pub is_balanced: bool,
+    pub factory_stats: Vec<FactoryPowerStatistics>,
+    pub grid_stats: Vec<GridPowerStats>,
+    pub storage: Option<StorageStatus>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReportCardResponse {
+    #[serde(flatten)]
+    pub report: ReportCard,
+    pub power_margin_percent: Option<f64>,
+    pub summary: String,
+}
+
+// ========== Save / Load ==========
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SaveResponse {
+    /// The save file as a JSON string
+    pub save_data: String,
+    pub summary: SaveFileSummary,
+    /// What was dropped to save as an older version
+    #[serde(default)]
+    pub downgrade_report: Option<DowngradeReport>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct LoadRequest<'a> {
+    pub save_data: &'a str,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LoadResponse {
+    pub message: String,
+    pub summary: SaveFileSummary,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MessageResponse {
+    pub message: String,
+}
@@ -0,0 +1,409 @@
+//! Coverage audit of the built-in item and recipe tables
+//!
+//! Compares [`all_recipes`] against a community dataset in the format of the
+//! SatisfactoryTools `data.json` export, so the tables can be re-checked after
+//! each game update instead of being verified by hand.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{
+    all_recipes, game_data::MachineType, item_by_name, item_name, recipe_by_name, recipe_info, Item,
+};
+
+/// Relative difference under which two rates or powers are considered equal
+const TOLERANCE: f64 = 1e-3;
+
+/// Item entry of the reference dataset
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReferenceItem {
+    pub name: String,
+}
+
+/// Ingredient or product of a reference recipe
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReferenceAmount {
+    /// Class name of the item, e.g. `Desc_IronIngot_C`
+    pub item: String,
+    /// Amount per craft; fluids in m³
+    pub amount: f64,
+}
+
+/// Recipe entry of the reference dataset
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReferenceRecipe {
+    pub name: String,
+    /// Duration of one craft, in seconds
+    pub time: f64,
+    pub ingredients: Vec<ReferenceAmount>,
+    pub products: Vec<ReferenceAmount>,
+    /// Class names of the buildings running the recipe
+    #[serde(default)]
+    pub produced_in: Vec<String>,
+    /// Whether the recipe runs in a production building
+    #[serde(default)]
+    pub in_machine: bool,
+    /// Whether the recipe builds a building rather than an item
+    #[serde(default)]
+    pub for_building: bool,
+}
+
+/// Building metadata of the reference dataset
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReferenceBuildingMetadata {
+    /// Base power in MW; absent or 0 for variable-power buildings
+    #[serde(default)]
+    pub power_consumption: Option<f64>,
+}
+
+/// Building entry of the reference dataset
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReferenceBuilding {
+    pub name: String,
+    #[serde(default)]
+    pub metadata: ReferenceBuildingMetadata,
+}
+
+/// Community dataset the built-in tables are compared against
+///
+/// Entries are keyed by class name, as in SatisfactoryTools' `data.json`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ReferenceDataset {
+    #[serde(default)]
+    pub items: HashMap<String, ReferenceItem>,
+    #[serde(default)]
+    pub recipes: HashMap<String, ReferenceRecipe>,
+    #[serde(default)]
+    pub buildings: HashMap<String, ReferenceBuilding>,
+}
+
+impl ReferenceDataset {
+    /// Parse a dataset from its JSON export
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    fn item_name<'a>(&'a self, class_name: &'a str) -> &'a str {
+        self.items
+            .get(class_name)
+            .map(|item| item.name.as_str())
+            .unwrap_or(class_name)
+    }
+
+    /// Items per minute of each ingredient or product, by item name
+    fn rates<'a>(
+        &'a self,
+        recipe: &ReferenceRecipe,
+        amounts: &'a [ReferenceAmount],
+    ) -> Vec<(&'a str, f64)> {
+        amounts
+            .iter()
+            .map(|amount| {
+                (
+                    self.item_name(&amount.item),
+                    amount.amount * 60.0 / recipe.time,
+                )
+            })
+            .collect()
+    }
+}
+
+/// A value of the built-in tables that differs from the reference
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditMismatch {
+    /// Recipe or machine name
+    pub subject: String,
+    /// What differs, e.g. `output Iron Ingot` or `machine`
+    pub field: String,
+    /// Value in the reference dataset
+    pub expected: String,
+    /// Value in the built-in tables
+    pub actual: String,
+}
+
+/// Differences between the built-in tables and a reference dataset
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AuditReport {
+    /// Reference items used by machine recipes but missing from [`Item`]
+    pub missing_items: Vec<String>,
+    /// Reference machine recipes missing from the built-in recipes
+    pub missing_recipes: Vec<String>,
+    /// Built-in machine recipes that the reference does not know
+    pub unknown_recipes: Vec<String>,
+    pub mismatches: Vec<AuditMismatch>,
+}
+
+impl AuditReport {
+    /// Audit the built-in tables against a reference dataset
+    pub fn build(reference: &ReferenceDataset) -> Self {
+        let mut missing_items = BTreeSet::new();
+        let mut missing_recipes = BTreeSet::new();
+        let mut referenced_recipes = BTreeSet::new();
+        let mut mismatches = Vec::new();
+        let mut machines: HashMap<MachineType, &ReferenceBuilding> = HashMap::new();
+
+        for recipe in reference.recipes.values() {
+            if !recipe.in_machine || recipe.for_building {
+                continue;
+            }
+
+            for amount in recipe.ingredients.iter().chain(&recipe.products) {
+                let name = reference.item_name(&amount.item);
+                if item_by_name(name).is_none() {
+                    missing_items.insert(name.to_string());
+                }
+            }
+
+            let Some(built_in) = recipe_by_name(&recipe.name) else {
+                missing_recipes.insert(recipe.name.clone());
+                continue;
+            };
+            referenced_recipes.insert(recipe.name.clone());
+            let info = recipe_info(built_in);
+
+            if let Some(building) = recipe
+                .produced_in
+                .first()
+                .and_then(|class_name| reference.buildings.get(class_name))
+            {
+                if machine_matches(info.machine, &building.name) {
+                    machines.insert(info.machine, building);
+                } else {
+                    mismatches.push(AuditMismatch {
+                        subject: recipe.name.clone(),
+                        field: "machine".to_string(),
+                        expected: building.name.clone(),
+                        actual: format!("{:?}", info.machine),
+                    });
+                }
+            }
+
+            compare_rates(
+                &recipe.name,
+                "input",
+                &reference.rates(recipe, &recipe.ingredients),
+                info.inputs,
+                &mut mismatches,
+            );
+            compare_rates(
+                &recipe.name,
+                "output",
+                &reference.rates(recipe, &recipe.products),
+                info.outputs,
+                &mut mismatches,
+            );
+        }
+
+        for (machine, building) in machines {
+            let Some(expected) = building
+                .metadata
+                .power_consumption
+                .filter(|power| *power > 0.0)
+            else {
+                continue;
+            };
+            if !approx_eq(expected, machine.base_power_mw()) {
+                mismatches.push(AuditMismatch {
+                    subject: building.name.clone(),
+                    field: "power".to_string(),
+                    expected: format!("{} MW", expected),
+                    actual: format!("{} MW", machine.base_power_mw()),
+                });
+            }
+        }
+
+        let unknown_recipes = all_recipes()
+            .iter()
+            .filter(|info| info.machine != MachineType::Manual)
+            .filter(|info| !referenced_recipes.contains(info.name))
+            .map(|info| info.name.to_string())
+            .collect::<BTreeSet<_>>();
+
+        mismatches.sort_by(|a, b| a.subject.cmp(&b.subject).then(a.field.cmp(&b.field)));
+
+        Self {
+            missing_items: missing_items.into_iter().collect(),
+            missing_recipes: missing_recipes.into_iter().collect(),
+            unknown_recipes: unknown_recipes.into_iter().collect(),
+            mismatches,
+        }
+    }
+
+    /// Check if the built-in tables match the reference
+    pub fn is_clean(&self) -> bool {
+        self.missing_items.is_empty()
+            && self.missing_recipes.is_empty()
+            && self.unknown_recipes.is_empty()
+            && self.mismatches.is_empty()
+    }
+}
+
+impl fmt::Display for AuditReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_clean() {
+            return writeln!(f, "Item and recipe tables match the reference dataset");
+        }
+        for (title, entries) in [
+            ("Missing items", &self.missing_items),
+            ("Missing recipes", &self.missing_recipes),
+            ("Recipes unknown to the reference", &self.unknown_recipes),
+        ] {
+            if entries.is_empty() {
+                continue;
+            }
+            writeln!(f, "{} ({}):", title, entries.len())?;
+            for entry in entries {
+                writeln!(f, "  {}", entry)?;
+            }
+        }
+        if !self.mismatches.is_empty() {
+            writeln!(f, "Mismatches ({}):", self.mismatches.len())?;
+            for mismatch in &self.mismatches {
+                writeln!(
+                    f,
+                    "  {} - {}: expected {}, found {}",
+                    mismatch.subject, mismatch.field, mismatch.expected, mismatch.actual
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reference building names are spaced ("Particle Accelerator"), ours are not
+fn machine_matches(machine: MachineType, building_name: &str) -> bool {
+    format!("{:?}", machine) == building_name.replace(' ', "")
+}
+
+fn approx_eq(expected: f64, actual: f64) -> bool {
+    (expected - actual).abs() <= TOLERANCE * expected.abs().max(1.0)
+}
+
+fn compare_rates(
+    recipe: &str,
+    direction: &str,
+    expected: &[(&str, f64)],
+    actual: &[(Item, f64)],
+    mismatches: &mut Vec<AuditMismatch>,
+) {
+    let mut mismatch = |item: &str, expected: String, actual: String| {
+        mismatches.push(AuditMismatch {
+            subject: recipe.to_string(),
+            field: format!("{} {}", direction, item),
+            expected,
+            actual,
+        });
+    };
+
+    for (name, expected_rate) in expected {
+        match actual.iter().find(|(item, _)| item_name(*item) == *name) {
+            Some((_, actual_rate)) if approx_eq(*expected_rate, *actual_rate) => {}
+            Some((_, actual_rate)) => mismatch(
+                name,
+                format!("{}/min", expected_rate),
+                format!("{}/min", actual_rate),
+            ),
+            None => mismatch(name, format!("{}/min", expected_rate), "absent".to_string()),
+        }
+    }
+    for (item, actual_rate) in actual {
+        if !expected.iter().any(|(name, _)| *name == item_name(*item)) {
+            mismatch(
+                item_name(*item),
+                "absent".to_string(),
+                format!("{}/min", actual_rate),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REFERENCE: &str = r#"{
+        "items": {
+            "Desc_OreIron_C": { "name": "Iron Ore" },
+            "Desc_IronIngot_C": { "name": "Iron Ingot" },
+            "Desc_IronPlate_C": { "name": "Iron Plate" },
+            "Desc_Unobtainium_C": { "name": "Unobtainium" }
+        },
+        "recipes": {
+            "Recipe_IngotIron_C": {
+                "name": "Iron Ingot", "time": 2, "inMachine": true,
+                "ingredients": [{ "item": "Desc_OreIron_C", "amount": 1 }],
+                "products": [{ "item": "Desc_IronIngot_C", "amount": 1 }],
+                "producedIn": ["Desc_SmelterMk1_C"]
+            },
+            "Recipe_IronPlate_C": {
+                "name": "Iron Plate", "time": 6, "inMachine": true,
+                "ingredients": [{ "item": "Desc_IronIngot_C", "amount": 3 }],
+                "products": [{ "item": "Desc_IronPlate_C", "amount": 3 }],
+                "producedIn": ["Desc_ConstructorMk1_C"]
+            },
+            "Recipe_Unobtainium_C": {
+                "name": "Unobtainium", "time": 1, "inMachine": true,
+                "ingredients": [{ "item": "Desc_OreIron_C", "amount": 1 }],
+                "products": [{ "item": "Desc_Unobtainium_C", "amount": 1 }],
+                "producedIn": ["Desc_SmelterMk1_C"]
+            },
+            "Recipe_Wall_C": {
+                "name": "Wall", "time": 1, "forBuilding": true,
+                "ingredients": [], "products": []
+            }
+        },
+        "buildings": {
+            "Desc_SmelterMk1_C": { "name": "Smelter", "metadata": { "powerConsumption": 4 } },
+            "Desc_ConstructorMk1_C": { "name": "Constructor", "metadata": { "powerConsumption": 5 } }
+        }
+    }"#;
+
+    #[test]
+    fn test_audit_against_reference() {
+        let reference = ReferenceDataset::from_json(REFERENCE).unwrap();
+        let report = AuditReport::build(&reference);
+
+        assert_eq!(report.missing_items, vec!["Unobtainium"]);
+        assert_eq!(report.missing_recipes, vec!["Unobtainium"]);
+        assert!(report.unknown_recipes.contains(&"Iron Rod".to_string()));
+        assert!(!report.unknown_recipes.contains(&"Iron Ingot".to_string()));
+        assert!(!report.is_clean());
+
+        // Iron Plate: 30/min of ingots in, 30/min of plates out instead of 20
+        assert_eq!(
+            report.mismatches,
+            vec![
+                AuditMismatch {
+                    subject: "Constructor".to_string(),
+                    field: "power".to_string(),
+                    expected: "5 MW".to_string(),
+                    actual: "4 MW".to_string(),
+                },
+                AuditMismatch {
+                    subject: "Iron Plate".to_string(),
+                    field: "output Iron Plate".to_string(),
+                    expected: "30/min".to_string(),
+                    actual: "20/min".to_string(),
+                },
+            ]
+        );
+
+        let text = report.to_string();
+        assert!(text.contains("Missing recipes (1):\n  Unobtainium"));
+        assert!(text.contains("Iron Plate - output Iron Plate: expected 30/min, found 20/min"));
+    }
+
+    #[test]
+    fn test_clean_audit() {
+        let report = AuditReport::default();
+        assert!(report.is_clean());
+        assert_eq!(
+            report.to_string(),
+            "Item and recipe tables match the reference dataset\n"
+        );
+    }
+}
@@ -0,0 +1,66 @@
+//! Data Audit Binary
+//!
+//! Compares the built-in item and recipe tables against a community dataset
+//! (SatisfactoryTools `data.json` format) and reports missing or mismatched
+//! entries. Exits with status 1 when differences are found, so it can run in CI.
+
+use satisflow_engine::{AuditReport, ReferenceDataset};
+use std::env;
+use std::process;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let json_output = args.iter().any(|arg| arg == "--json");
+    let paths: Vec<&String> = args.iter().filter(|arg| *arg != "--json").collect();
+
+    let [path] = paths.as_slice() else {
+        print_usage();
+        process::exit(2);
+    };
+
+    let reference = match std::fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|json| ReferenceDataset::from_json(&json).map_err(|e| e.to_string()))
+    {
+        Ok(reference) => reference,
+        Err(e) => {
+            eprintln!("Error: Failed to load {}: {}", path, e);
+            process::exit(2);
+        }
+    };
+
+    let report = AuditReport::build(&reference);
+    if json_output {
+        match serde_json::to_string_pretty(&report) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("Error: Failed to serialize report: {}", e);
+                process::exit(2);
+            }
+        }
+    } else {
+        print!("{}", report);
+    }
+
+    if !report.is_clean() {
+        process::exit(1);
+    }
+}
+
+fn print_usage() {
+    println!("Satisflow Data Audit");
+    println!("Usage: data_audit [--json] <reference.json>");
+    println!();
+    println!("Compares the built-in items and recipes against a community dataset");
+    println!("in the SatisfactoryTools data.json format.");
+    println!();
+    println!("Options:");
+    println!("  --json  - Print the report as JSON");
+    println!();
+    println!("Exit status: 0 when everything matches, 1 on differences, 2 on errors");
+    println!();
+    println!("Examples:");
+    println!("  data_audit data.json");
+    println!("  data_audit --json data.json > audit.json");
+}
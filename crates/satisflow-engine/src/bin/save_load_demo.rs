@@ -163,7 +163,7 @@ fn create_sample_engine() -> SatisflowEngine {
     if let Some(factory) = engine.get_factory_mut(iron_factory_id) {
         // Add raw input - Iron Ore mining
         let iron_miner = RawInput::new(
-            Uuid::new_v4(),
+            Uuid::new_v4().into(),
             ExtractorType::MinerMk2,
             Item::IronOre,
             Some(Purity::Pure),
@@ -177,7 +177,7 @@ fn create_sample_engine() -> SatisflowEngine {
 
         // Add production line - Iron Ingot smelting
         let mut iron_ingot_line = ProductionLineRecipe::new(
-            Uuid::new_v4(),
+            Uuid::new_v4().into(),
             "Iron Ingot Production".to_string(),
             Some("Smelting iron ore".to_string()),
             Recipe::IronIngot,
@@ -192,8 +192,9 @@ fn create_sample_engine() -> SatisflowEngine {
         factory.add_production_line(ProductionLine::ProductionLineRecipe(iron_ingot_line));
 
         // Add power generator - Coal
-        let mut coal_gen = PowerGenerator::new(Uuid::new_v4(), GeneratorType::Coal, Item::Coal)
-            .expect("Failed to create generator");
+        let mut coal_gen =
+            PowerGenerator::new(Uuid::new_v4().into(), GeneratorType::Coal, Item::Coal)
+                .expect("Failed to create generator");
         coal_gen
             .add_group(GeneratorGroup {
                 number_of_generators: 8,
@@ -214,7 +215,7 @@ fn create_sample_engine() -> SatisflowEngine {
     if let Some(factory) = engine.get_factory_mut(steel_factory_id) {
         // Add coal input
         let coal_miner = RawInput::new(
-            Uuid::new_v4(),
+            Uuid::new_v4().into(),
             ExtractorType::MinerMk1,
             Item::Coal,
             Some(Purity::Normal),
@@ -228,7 +229,7 @@ fn create_sample_engine() -> SatisflowEngine {
 
         // Add steel ingot production
         let mut steel_ingot_line = ProductionLineRecipe::new(
-            Uuid::new_v4(),
+            Uuid::new_v4().into(),
             "Steel Ingot Production".to_string(),
             Some("Making steel from iron and coal".to_string()),
             Recipe::SteelIngot,
@@ -243,8 +244,9 @@ fn create_sample_engine() -> SatisflowEngine {
         factory.add_production_line(ProductionLine::ProductionLineRecipe(steel_ingot_line));
 
         // Add fuel generator
-        let mut fuel_gen = PowerGenerator::new(Uuid::new_v4(), GeneratorType::Fuel, Item::Fuel)
-            .expect("Failed to create generator");
+        let mut fuel_gen =
+            PowerGenerator::new(Uuid::new_v4().into(), GeneratorType::Fuel, Item::Fuel)
+                .expect("Failed to create generator");
         fuel_gen
             .add_group(GeneratorGroup {
                 number_of_generators: 4,
@@ -0,0 +1,71 @@
+//! Season Overview Binary
+//!
+//! Loads several save files of the same world (e.g. weekly snapshots), oldest
+//! first, and prints trend statistics as JSON or CSV for progress tracking.
+
+use satisflow_engine::{SeasonOverview, SeasonSnapshot};
+use std::env;
+use std::path::Path;
+use std::process;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let csv = args.iter().any(|arg| arg == "--csv");
+    let paths: Vec<&String> = args.iter().filter(|arg| *arg != "--csv").collect();
+
+    if paths.is_empty() {
+        print_usage();
+        process::exit(1);
+    }
+
+    let mut snapshots = Vec::with_capacity(paths.len());
+    for path in paths {
+        let path = Path::new(path);
+        let label = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+
+        let snapshot = std::fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| {
+                SeasonSnapshot::from_save_json(label, &json).map_err(|e| e.to_string())
+            });
+        match snapshot {
+            Ok(snapshot) => snapshots.push(snapshot),
+            Err(e) => {
+                eprintln!("Error: Failed to load {}: {}", path.display(), e);
+                process::exit(1);
+            }
+        }
+    }
+
+    let overview = SeasonOverview::build(snapshots);
+    if csv {
+        print!("{}", overview.to_csv());
+    } else {
+        match serde_json::to_string_pretty(&overview) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("Error: Failed to serialize overview: {}", e);
+                process::exit(1);
+            }
+        }
+    }
+}
+
+fn print_usage() {
+    println!("Satisflow Season Overview");
+    println!("Usage: season_overview [--csv] <save_file>...");
+    println!();
+    println!("Save files are compared in the order given, oldest first.");
+    println!("Each snapshot is labelled with its file name.");
+    println!();
+    println!("Options:");
+    println!("  --csv  - Print one CSV row per save instead of JSON");
+    println!();
+    println!("Examples:");
+    println!("  season_overview week1.json week2.json week3.json");
+    println!("  season_overview --csv saves/*.json > season.csv");
+}
@@ -0,0 +1,127 @@
+//! Gzip compression of save files
+//!
+//! Pretty-printed saves of large worlds reach several megabytes of very
+//! repetitive JSON, which gzip shrinks to a small fraction.
+
+use std::io::{Read, Write};
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Whether `bytes` start like a gzip file
+pub fn is_gzip(bytes: &[u8]) -> bool {
+    bytes.starts_with(&GZIP_MAGIC)
+}
+
+/// Compress `data` into a gzip file
+pub fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(data)
+        .expect("writing to a Vec doesn't fail");
+    encoder.finish().expect("writing to a Vec doesn't fail")
+}
+
+/// Decompress a gzip file
+///
+/// # Returns
+///
+/// Result containing the original bytes, or an error if the file is not gzip,
+/// is corrupt or fails its checksum
+pub fn gunzip(bytes: &[u8]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    if !is_gzip(bytes) {
+        return Err("Not a gzip file".into());
+    }
+    let mut data = Vec::new();
+    GzDecoder::new(bytes)
+        .read_to_end(&mut data)
+        .map_err(|e| format!("Corrupt gzip file: {}", e))?;
+    Ok(data)
+}
+
+/// Save file content as JSON text, decompressing it if it is gzip
+pub fn decode_save(bytes: &[u8]) -> Result<String, Box<dyn std::error::Error>> {
+    let bytes = if is_gzip(bytes) {
+        gunzip(bytes)?
+    } else {
+        bytes.to_vec()
+    };
+    Ok(String::from_utf8(bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let json = (0..2000)
+            .map(|index| {
+                format!(
+                    "{{\n  \"name\": \"Line {}\",\n  \"enabled\": true\n}},\n",
+                    index
+                )
+            })
+            .collect::<String>();
+        for data in [
+            &b""[..],
+            b"a",
+            b"aaaaaaaaaaaaaaaaaaaaaaaaaa",
+            json.as_bytes(),
+        ] {
+            let compressed = gzip(data);
+            assert!(is_gzip(&compressed));
+            assert_eq!(gunzip(&compressed).unwrap(), data);
+        }
+        assert!(gzip(json.as_bytes()).len() < json.len() / 5);
+    }
+
+    #[test]
+    fn test_gunzip_external_file() {
+        // Written by zlib at level 9
+        let file = [
+            0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0xed, 0xd3, 0x2b, 0x0a,
+            0xc3, 0x50, 0x10, 0x46, 0xe1, 0xad, 0xdc, 0x05, 0x54, 0xcc, 0xdb, 0x64, 0x05, 0x71,
+            0xa5, 0x3b, 0x08, 0xa4, 0xae, 0x34, 0x50, 0xb2, 0x7f, 0x42, 0x88, 0xb9, 0x57, 0x1c,
+            0x5d, 0x13, 0x37, 0xc3, 0x31, 0x9f, 0xf9, 0xe7, 0xdf, 0xf6, 0x6d, 0xcf, 0xcf, 0xb2,
+            0xbf, 0x9b, 0x3c, 0xda, 0x7c, 0x7e, 0xaf, 0x6d, 0x6d, 0x32, 0x5d, 0xf7, 0x55, 0xb4,
+            0x2b, 0x3a, 0x14, 0xeb, 0x8a, 0x0d, 0xc5, 0xbb, 0xe2, 0x43, 0x89, 0xae, 0xc4, 0x50,
+            0x12, 0x05, 0x85, 0x02, 0x41, 0x81, 0xa2, 0xc0, 0x50, 0xe0, 0x28, 0x08, 0x14, 0x24,
+            0x0a, 0x0a, 0x05, 0x82, 0x02, 0x45, 0x81, 0xa1, 0xc0, 0x51, 0x10, 0x28, 0x48, 0x14,
+            0x14, 0x0a, 0x04, 0x05, 0x8a, 0x02, 0x43, 0x81, 0xa3, 0x20, 0x50, 0x90, 0x28, 0x28,
+            0x14, 0x08, 0x0a, 0x14, 0x05, 0x86, 0x02, 0x47, 0x41, 0xa0, 0x20, 0x51, 0x50, 0x28,
+            0xb8, 0xd7, 0x78, 0xaf, 0xf1, 0x8f, 0x6b, 0x3c, 0x00, 0x63, 0x10, 0xed, 0xd8, 0x18,
+            0x06, 0x00, 0x00,
+        ];
+        let expected: String = (0..60)
+            .map(|index| format!("Iron Plate {}, Iron Rod {}; ", index % 7, index % 5))
+            .collect();
+        assert_eq!(gunzip(&file).unwrap(), expected.as_bytes());
+    }
+
+    #[test]
+    fn test_gunzip_rejects_corrupt_files() {
+        assert!(gunzip(b"{\"version\": \"0.2.0\"}").is_err());
+
+        let mut compressed = gzip(b"some save data");
+        let last = compressed.len() - 5;
+        compressed[last] ^= 0xff;
+        assert!(gunzip(&compressed).is_err());
+    }
+
+    #[test]
+    fn test_gunzip_rejects_truncated_headers() {
+        // FEXTRA set, with the extra field length cut off
+        assert!(gunzip(&[0x1f, 0x8b, 0x08, 0x04, 0, 0, 0, 0, 0, 0xff, 0x05]).is_err());
+        // FNAME set, without its terminating zero
+        assert!(gunzip(&[0x1f, 0x8b, 0x08, 0x08, 0, 0, 0, 0, 0, 0xff, b'a']).is_err());
+        assert!(gunzip(&GZIP_MAGIC).is_err());
+        assert!(decode_save(&[0x1f, 0x8b, 0x08, 0x04]).is_err());
+
+        let compressed = gzip(b"some save data");
+        for len in 0..compressed.len() {
+            assert!(gunzip(&compressed[..len]).is_err(), "{} bytes", len);
+        }
+    }
+}
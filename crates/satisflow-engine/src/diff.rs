@@ -0,0 +1,377 @@
+//! Changes between two saves of the same world
+//!
+//! [`SaveDiff`] matches factories, production lines, extractors, generators
+//! and logistics lines by id, so renamed entities show up as modified rather
+//! than removed and added again. Edit timestamps are ignored: an entity only
+//! counts as modified when its content changed.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::hash::Hash;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::models::{factory::Factory, item_name, logistics::LogisticsFlux, FactoryId, Item};
+use crate::{rate, SatisflowEngine};
+
+/// Factory fields compared entity by entity or recomputed, so left out of
+/// `fields_changed`
+const FACTORY_FIELDS_COMPARED_APART: [&str; 7] = [
+    "id",
+    "production_lines",
+    "raw_inputs",
+    "power_generators",
+    "items",
+    "timestamps",
+    "production_line_timestamps",
+];
+
+/// An entity named for display
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntityRef {
+    pub id: Uuid,
+    pub name: String,
+}
+
+/// Entities of one kind added, removed or changed, each sorted by name
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EntityChanges {
+    pub added: Vec<EntityRef>,
+    pub removed: Vec<EntityRef>,
+    pub modified: Vec<EntityRef>,
+}
+
+impl EntityChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// Change of an item's net rate, in items/min
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemDelta {
+    pub item: Item,
+    pub before: f64,
+    pub after: f64,
+    pub delta: f64,
+}
+
+/// What changed inside a factory present in both saves
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FactoryChange {
+    pub id: FactoryId,
+    /// Name in the newer save
+    pub name: String,
+    /// Name in the older save, when the factory was renamed
+    pub renamed_from: Option<String>,
+    /// Other settings that changed, e.g. `description` or `tags`
+    pub fields_changed: Vec<String>,
+    pub production_lines: EntityChanges,
+    pub raw_inputs: EntityChanges,
+    pub power_generators: EntityChanges,
+    /// Changes of the factory's net item rates, largest first
+    pub item_balance: Vec<ItemDelta>,
+}
+
+/// Structured changeset between two saves
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SaveDiff {
+    pub factories_added: Vec<EntityRef>,
+    pub factories_removed: Vec<EntityRef>,
+    /// Factories present in both saves that changed, sorted by name
+    pub factories_modified: Vec<FactoryChange>,
+    pub logistics: EntityChanges,
+    /// Changes of the world's net item rates, largest first
+    pub item_balance: Vec<ItemDelta>,
+}
+
+impl SaveDiff {
+    /// Compare two engine states
+    ///
+    /// # Arguments
+    ///
+    /// * `before` - The older state
+    /// * `after` - The newer state
+    pub fn between(before: &SatisflowEngine, after: &SatisflowEngine) -> Self {
+        // Item balances are derived data, recompute them on both sides
+        let mut before = before.clone();
+        let mut after = after.clone();
+        let before_items = before.update();
+        let after_items = after.update();
+
+        let factories_before = before.get_all_factories();
+        let factories_after = after.get_all_factories();
+
+        let mut factories_added = added(factories_after, factories_before, factory_ref);
+        let mut factories_removed = added(factories_before, factories_after, factory_ref);
+        sort_refs(&mut factories_added);
+        sort_refs(&mut factories_removed);
+
+        let mut factories_modified: Vec<FactoryChange> = factories_after
+            .values()
+            .filter_map(|factory| {
+                let previous = factories_before.get(&factory.id)?;
+                let change = diff_factory(previous, factory);
+                (!change.is_empty()).then_some(change)
+            })
+            .collect();
+        factories_modified.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+
+        let logistics = diff_entities(
+            before.get_all_logistics(),
+            after.get_all_logistics(),
+            |line: &LogisticsFlux| format!("{} {}", line.transport_type, line.transport_details),
+        );
+
+        Self {
+            factories_added,
+            factories_removed,
+            factories_modified,
+            logistics,
+            item_balance: item_deltas(&before_items, &after_items),
+        }
+    }
+
+    /// Whether the two saves hold the same world
+    pub fn is_empty(&self) -> bool {
+        self.factories_added.is_empty()
+            && self.factories_removed.is_empty()
+            && self.factories_modified.is_empty()
+            && self.logistics.is_empty()
+            && self.item_balance.is_empty()
+    }
+}
+
+impl FactoryChange {
+    fn is_empty(&self) -> bool {
+        self.renamed_from.is_none()
+            && self.fields_changed.is_empty()
+            && self.production_lines.is_empty()
+            && self.raw_inputs.is_empty()
+            && self.power_generators.is_empty()
+            && self.item_balance.is_empty()
+    }
+}
+
+fn diff_factory(before: &Factory, after: &Factory) -> FactoryChange {
+    let before_value = without_timestamps(before);
+    let after_value = without_timestamps(after);
+    let mut fields: BTreeSet<&String> = BTreeSet::new();
+    if let (Value::Object(before_fields), Value::Object(after_fields)) =
+        (&before_value, &after_value)
+    {
+        fields.extend(before_fields.keys().chain(after_fields.keys()));
+    }
+    let fields_changed = fields
+        .into_iter()
+        .filter(|field| {
+            field.as_str() != "name"
+                && !FACTORY_FIELDS_COMPARED_APART.contains(&field.as_str())
+                && before_value.get(field.as_str()) != after_value.get(field.as_str())
+        })
+        .cloned()
+        .collect();
+
+    FactoryChange {
+        id: after.id,
+        name: after.name.clone(),
+        renamed_from: (before.name != after.name).then(|| before.name.clone()),
+        fields_changed,
+        production_lines: diff_entities(
+            &before.production_lines,
+            &after.production_lines,
+            |line| line.name().to_string(),
+        ),
+        raw_inputs: diff_entities(&before.raw_inputs, &after.raw_inputs, |raw_input| {
+            format!(
+                "{:?} ({})",
+                raw_input.extractor_type,
+                item_name(raw_input.item)
+            )
+        }),
+        power_generators: diff_entities(
+            &before.power_generators,
+            &after.power_generators,
+            |generator| {
+                format!(
+                    "{:?} ({})",
+                    generator.generator_type,
+                    item_name(generator.fuel_type)
+                )
+            },
+        ),
+        item_balance: item_deltas(&before.items, &after.items),
+    }
+}
+
+fn diff_entities<K: EntityKey, T: Serialize>(
+    before: &HashMap<K, T>,
+    after: &HashMap<K, T>,
+    name: impl Fn(&T) -> String,
+) -> EntityChanges {
+    let entity_ref = |id: &K, entity: &T| EntityRef {
+        id: (*id).into(),
+        name: name(entity),
+    };
+    let mut changes = EntityChanges {
+        added: added(after, before, entity_ref),
+        removed: added(before, after, entity_ref),
+        modified: after
+            .iter()
+            .filter(|(id, entity)| {
+                before.get(id).is_some_and(|previous| {
+                    without_timestamps(previous) != without_timestamps(*entity)
+                })
+            })
+            .map(|(id, entity)| entity_ref(id, entity))
+            .collect(),
+    };
+    sort_refs(&mut changes.added);
+    sort_refs(&mut changes.removed);
+    sort_refs(&mut changes.modified);
+    changes
+}
+
+/// Entities of `of` missing from `from`
+/// IDs of the entities compared by a diff
+trait EntityKey: Copy + Eq + Hash + Into<Uuid> {}
+
+impl<K: Copy + Eq + Hash + Into<Uuid>> EntityKey for K {}
+
+fn added<K: EntityKey, T>(
+    of: &HashMap<K, T>,
+    from: &HashMap<K, T>,
+    entity_ref: impl Fn(&K, &T) -> EntityRef,
+) -> Vec<EntityRef> {
+    of.iter()
+        .filter(|(id, _)| !from.contains_key(id))
+        .map(|(id, entity)| entity_ref(id, entity))
+        .collect()
+}
+
+fn factory_ref(id: &FactoryId, factory: &Factory) -> EntityRef {
+    EntityRef {
+        id: (*id).into(),
+        name: factory.name.clone(),
+    }
+}
+
+fn sort_refs(refs: &mut [EntityRef]) {
+    refs.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+}
+
+fn without_timestamps<T: Serialize>(entity: &T) -> Value {
+    let mut value = serde_json::to_value(entity).unwrap_or(Value::Null);
+    if let Value::Object(fields) = &mut value {
+        fields.remove("timestamps");
+    }
+    value
+}
+
+fn item_deltas(before: &HashMap<Item, f64>, after: &HashMap<Item, f64>) -> Vec<ItemDelta> {
+    let items: HashSet<Item> = before.keys().chain(after.keys()).copied().collect();
+    let mut deltas: Vec<ItemDelta> = items
+        .into_iter()
+        .filter_map(|item| {
+            let before = before.get(&item).copied().unwrap_or(0.0);
+            let after = after.get(&item).copied().unwrap_or(0.0);
+            let delta = after - before;
+            (!rate::is_zero(delta)).then_some(ItemDelta {
+                item,
+                before,
+                after,
+                delta,
+            })
+        })
+        .collect();
+    deltas.sort_by(|a, b| {
+        b.delta
+            .abs()
+            .total_cmp(&a.delta.abs())
+            .then_with(|| item_name(a.item).cmp(item_name(b.item)))
+    });
+    deltas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        logistics::{TransportType, TruckTransport},
+        production_line::{MachineGroup, ProductionLine, ProductionLineRecipe},
+        Recipe,
+    };
+
+    fn add_line(engine: &mut SatisflowEngine, factory: FactoryId, id: u128, recipe: Recipe) {
+        let mut line = ProductionLineRecipe::new(
+            Uuid::from_u128(id).into(),
+            format!("Line {}", id),
+            None,
+            recipe,
+        );
+        line.add_machine_group(MachineGroup::new(2, 100.0, 0))
+            .unwrap();
+        engine
+            .get_factory_mut(factory)
+            .unwrap()
+            .add_production_line(ProductionLine::ProductionLineRecipe(line));
+    }
+
+    #[test]
+    fn test_save_diff() {
+        let mut before = SatisflowEngine::new();
+        let mine = before.create_factory("Mine".to_string(), None);
+        let smelter = before.create_factory("Smelter".to_string(), None);
+        let old = before.create_factory("Old Outpost".to_string(), None);
+        add_line(&mut before, smelter, 1, Recipe::IronIngot);
+        add_line(&mut before, smelter, 2, Recipe::CopperIngot);
+        before
+            .create_logistics_line(
+                mine,
+                smelter,
+                TransportType::Truck(TruckTransport::new(1, Item::IronOre, 60.0)),
+                "Ore run".to_string(),
+            )
+            .unwrap();
+
+        let mut after = before.clone();
+        assert!(SaveDiff::between(&before, &after).is_empty());
+
+        after.delete_factory(old).unwrap();
+        let steel = after.create_factory("Steel".to_string(), None);
+        {
+            let factory = after.get_factory_mut(smelter).unwrap();
+            factory.name = "Smelter East".to_string();
+            factory.tags = vec!["east".to_string()];
+            factory.production_lines.remove(&Uuid::from_u128(2).into());
+        }
+        add_line(&mut after, smelter, 3, Recipe::IronIngot);
+
+        let diff = SaveDiff::between(&before, &after);
+        assert_eq!(diff.factories_added[0].id, steel.as_uuid());
+        assert_eq!(diff.factories_removed[0].name, "Old Outpost");
+        assert!(diff.logistics.is_empty());
+
+        assert_eq!(diff.factories_modified.len(), 1);
+        let change = &diff.factories_modified[0];
+        assert_eq!(change.name, "Smelter East");
+        assert_eq!(change.renamed_from.as_deref(), Some("Smelter"));
+        assert_eq!(change.fields_changed, ["tags"]);
+        assert_eq!(change.production_lines.added[0].name, "Line 3");
+        assert_eq!(change.production_lines.removed[0].name, "Line 2");
+        assert!(change.production_lines.modified.is_empty());
+
+        let iron = diff
+            .item_balance
+            .iter()
+            .find(|delta| delta.item == Item::IronIngot)
+            .unwrap();
+        assert!((iron.delta - 60.0).abs() < 1e-3);
+        assert!(diff
+            .item_balance
+            .iter()
+            .any(|delta| delta.item == Item::CopperIngot && delta.delta < 0.0));
+        assert_eq!(change.item_balance.len(), diff.item_balance.len());
+    }
+}
@@ -0,0 +1,139 @@
+//! Change notifications
+//!
+//! Observers registered with [`SatisflowEngine::subscribe`] are called after
+//! every successful change, so a server can push updates to clients or
+//! invalidate caches without hooking each endpoint. Observers run while the
+//! engine is borrowed mutably: they must not block, and can't call back into
+//! the engine.
+//!
+//! A copy made by [`SatisflowEngine::next_version`] holds its events back
+//! until [`SatisflowEngine::dispatch_pending_events`], so changes to a copy
+//! that is dropped instead of replacing the engine are never announced.
+//!
+//! [`SatisflowEngine::subscribe`]: crate::SatisflowEngine::subscribe
+//! [`SatisflowEngine::next_version`]: crate::SatisflowEngine::next_version
+//! [`SatisflowEngine::dispatch_pending_events`]: crate::SatisflowEngine::dispatch_pending_events
+
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use serde::Serialize;
+
+use crate::models::{
+    CustomRecipeId, FactoryId, FactoryTemplateId, GoalId, LogisticsId, PowerGridId,
+    ProductionLineId,
+};
+
+/// A change made to the engine
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum EngineEvent {
+    FactoryCreated(FactoryId),
+    /// Anything in the factory may have changed, including its production
+    /// lines, raw inputs and generators
+    FactoryUpdated(FactoryId),
+    FactoryDeleted(FactoryId),
+    LogisticsCreated(LogisticsId),
+    LogisticsUpdated(LogisticsId),
+    LogisticsDeleted(LogisticsId),
+    PowerGridChanged(PowerGridId),
+    GoalChanged(GoalId),
+    CustomRecipeChanged(CustomRecipeId),
+    BlueprintTemplateChanged(ProductionLineId),
+    FactoryTemplateChanged(FactoryTemplateId),
+    PinsChanged,
+    TierProgressChanged,
+    TransportNamingChanged,
+    ResourceNodesChanged,
+    /// The game data version or the FICSMAS toggle changed
+    GameDataChanged,
+    /// The overclock, purity or generator constants changed
+    BalanceChanged,
+    /// The whole state was replaced, e.g. by loading a save
+    StateReplaced,
+}
+
+/// Handle to cancel a subscription with [`SatisflowEngine::unsubscribe`]
+///
+/// [`SatisflowEngine::unsubscribe`]: crate::SatisflowEngine::unsubscribe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+type Observer = Arc<dyn Fn(&EngineEvent) + Send + Sync>;
+
+/// Observers of an engine
+///
+/// Clones start without observers: a copy of the engine, such as a what-if
+/// sandbox, must not notify the original's observers. A copy that replaces
+/// the original takes them over with [`Observers::share`].
+#[derive(Default)]
+pub(crate) struct Observers {
+    next_id: u64,
+    observers: Vec<(SubscriptionId, Observer)>,
+    /// Events held back until [`Observers::dispatch_pending`], `None` when
+    /// they are sent right away
+    pending: Option<Mutex<Vec<EngineEvent>>>,
+}
+
+impl Observers {
+    pub(crate) fn subscribe(&mut self, observer: Observer) -> SubscriptionId {
+        self.next_id += 1;
+        let id = SubscriptionId(self.next_id);
+        self.observers.push((id, observer));
+        id
+    }
+
+    pub(crate) fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        let count = self.observers.len();
+        self.observers.retain(|(existing, _)| *existing != id);
+        self.observers.len() != count
+    }
+
+    /// The same observers, for a new version of the engine, holding events
+    /// back until the new version is dispatched
+    pub(crate) fn share(&self) -> Self {
+        Self {
+            next_id: self.next_id,
+            observers: self.observers.clone(),
+            pending: Some(Mutex::default()),
+        }
+    }
+
+    pub(crate) fn emit(&self, event: EngineEvent) {
+        match &self.pending {
+            Some(pending) => pending
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(event),
+            None => self.notify(&event),
+        }
+    }
+
+    /// Send the events held back since [`Observers::share`]
+    pub(crate) fn dispatch_pending(&mut self) {
+        let Some(pending) = &mut self.pending else {
+            return;
+        };
+        let events = std::mem::take(pending.get_mut().unwrap_or_else(PoisonError::into_inner));
+        for event in &events {
+            self.notify(event);
+        }
+    }
+
+    fn notify(&self, event: &EngineEvent) {
+        for (_, observer) in &self.observers {
+            observer(event);
+        }
+    }
+}
+
+impl Clone for Observers {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl fmt::Debug for Observers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} observers", self.observers.len())
+    }
+}
@@ -14,20 +14,21 @@ use uuid::Uuid;
 
 use crate::{
     models::{
+        factory::Factory,
         logistics::{
             Bus, Conveyor, ConveyorSpeed, Pipeline, PipelineCapacity, Train, TransportType,
             TruckTransport, Wagon, WagonType,
         },
-        power_generator::{GeneratorGroup, GeneratorType, PowerGenerator},
-        production_line::{MachineGroup, ProductionLine, ProductionLineRecipe},
+        power_generator::{GeneratorType, PowerGenerator, PowerGeneratorBuilder},
+        production_line::{MachineGroup, ProductionLineBuilder},
         raw_input::{ExtractorType, Purity, RawInput},
         FactoryId, Item, Recipe,
     },
     SatisflowEngine,
 };
 
-fn uuid_from_u64(value: u64) -> Uuid {
-    Uuid::from_u128(value as u128)
+fn uuid_from_u64<T: From<Uuid>>(value: u64) -> T {
+    Uuid::from_u128(value as u128).into()
 }
 
 /// Create a comprehensive factory setup for testing and demonstration
@@ -36,30 +37,50 @@ pub fn create_sample_factory_setup() -> SatisflowEngine {
     let mut engine = SatisflowEngine::new();
 
     // Create all 5 factories
-    let northern_forest_id = engine.create_factory(
-        "Northern Forest - Smelting Hub".to_string(),
-        Some("Northern Forest - Smelting Hub for iron and copper processing".to_string()),
-    );
-
-    let central_assembly_id = engine.create_factory(
-        "Central Assembly - Manufacturing Hub".to_string(),
-        Some("Central Assembly - Manufacturing Hub for complex components".to_string()),
-    );
-
-    let oil_refinery_id = engine.create_factory(
-        "Oil Refinery - Petroleum Hub".to_string(),
-        Some("Oil Refinery - Petroleum Hub for fuel and plastics".to_string()),
-    );
-
-    let steel_mill_id = engine.create_factory(
-        "Steel Mill - Heavy Industry".to_string(),
-        Some("Steel Mill - Heavy Industry for construction materials".to_string()),
-    );
-
-    let electronics_lab_id = engine.create_factory(
-        "Electronics Lab - High-Tech Hub".to_string(),
-        Some("Electronics Lab - High-Tech Hub for computers and circuits".to_string()),
-    );
+    let northern_forest_id = engine
+        .add_factory(
+            Factory::builder("Northern Forest - Smelting Hub")
+                .description("Northern Forest - Smelting Hub for iron and copper processing")
+                .build()
+                .expect("Should build valid factory"),
+        )
+        .expect("Should add factory");
+
+    let central_assembly_id = engine
+        .add_factory(
+            Factory::builder("Central Assembly - Manufacturing Hub")
+                .description("Central Assembly - Manufacturing Hub for complex components")
+                .build()
+                .expect("Should build valid factory"),
+        )
+        .expect("Should add factory");
+
+    let oil_refinery_id = engine
+        .add_factory(
+            Factory::builder("Oil Refinery - Petroleum Hub")
+                .description("Oil Refinery - Petroleum Hub for fuel and plastics")
+                .build()
+                .expect("Should build valid factory"),
+        )
+        .expect("Should add factory");
+
+    let steel_mill_id = engine
+        .add_factory(
+            Factory::builder("Steel Mill - Heavy Industry")
+                .description("Steel Mill - Heavy Industry for construction materials")
+                .build()
+                .expect("Should build valid factory"),
+        )
+        .expect("Should add factory");
+
+    let electronics_lab_id = engine
+        .add_factory(
+            Factory::builder("Electronics Lab - High-Tech Hub")
+                .description("Electronics Lab - High-Tech Hub for computers and circuits")
+                .build()
+                .expect("Should build valid factory"),
+        )
+        .expect("Should add factory");
 
     // Set up each factory with production lines, raw inputs, and power
     setup_northern_forest_factory(&mut engine, northern_forest_id);
@@ -995,22 +1016,19 @@ fn add_production_line(
     recipe: Recipe,
     machine_groups: Vec<MachineGroup>,
 ) {
-    let line_id = uuid_from_u64(line_id);
-    let mut production_line = ProductionLineRecipe::new(
-        line_id,
-        name.to_string(),
-        Some(format!("Production line for {}", name)),
-        recipe,
-    );
-
-    for group in machine_groups {
-        production_line
-            .add_machine_group(group)
-            .expect("Should add machine group");
-    }
+    let production_line = machine_groups
+        .into_iter()
+        .fold(
+            ProductionLineBuilder::recipe(name, recipe)
+                .id(uuid_from_u64(line_id))
+                .description(format!("Production line for {}", name)),
+            ProductionLineBuilder::machine_group,
+        )
+        .build()
+        .expect("Should build valid production line");
 
     if let Some(factory) = engine.get_factory_mut(factory_id) {
-        factory.add_production_line(ProductionLine::ProductionLineRecipe(production_line));
+        factory.add_production_line(production_line);
     }
 }
 
@@ -1020,23 +1038,16 @@ fn add_coal_power_generator(
     factory_id: FactoryId,
     generator_id: u64,
     num_generators: u32,
-    clock_speed: f32,
+    clock_speed: f64,
 ) {
-    let mut generator =
-        PowerGenerator::new(uuid_from_u64(generator_id), GeneratorType::Coal, Item::Coal)
-            .expect("Should create valid coal generator");
-
-    let group = GeneratorGroup::new(num_generators, clock_speed)
-        .expect("Should create valid generator group");
-    generator
-        .add_group(group)
-        .expect("Should add generator group");
-
-    if let Some(factory) = engine.get_factory_mut(factory_id) {
-        factory
-            .add_power_generator(generator)
-            .expect("Should add power generator");
-    }
+    add_power_generator(
+        engine,
+        factory_id,
+        PowerGenerator::builder(GeneratorType::Coal)
+            .id(uuid_from_u64(generator_id))
+            .fuel(Item::Coal)
+            .group(num_generators, clock_speed),
+    );
 }
 
 /// Helper function to add fuel power generators to a factory
@@ -1045,17 +1056,26 @@ fn add_fuel_power_generator(
     factory_id: FactoryId,
     generator_id: u64,
     num_generators: u32,
-    clock_speed: f32,
+    clock_speed: f64,
 ) {
-    let mut generator =
-        PowerGenerator::new(uuid_from_u64(generator_id), GeneratorType::Fuel, Item::Fuel)
-            .expect("Should create valid fuel generator");
+    add_power_generator(
+        engine,
+        factory_id,
+        PowerGenerator::builder(GeneratorType::Fuel)
+            .id(uuid_from_u64(generator_id))
+            .fuel(Item::Fuel)
+            .group(num_generators, clock_speed),
+    );
+}
 
-    let group = GeneratorGroup::new(num_generators, clock_speed)
-        .expect("Should create valid generator group");
-    generator
-        .add_group(group)
-        .expect("Should add generator group");
+fn add_power_generator(
+    engine: &mut SatisflowEngine,
+    factory_id: FactoryId,
+    generator: PowerGeneratorBuilder,
+) {
+    let generator = generator
+        .build()
+        .expect("Should build valid power generator");
 
     if let Some(factory) = engine.get_factory_mut(factory_id) {
         factory
@@ -1067,6 +1087,7 @@ fn add_fuel_power_generator(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::production_line::ProductionLine;
 
     #[test]
     fn test_sample_factory_creation() {
@@ -1329,7 +1350,7 @@ mod tests {
                 .production_lines
                 .values()
                 .map(|line| line.total_power_consumption())
-                .sum::<f32>();
+                .sum::<f64>();
 
             assert!(
                 production_power > 0.0,
@@ -11,3 +11,40 @@ pub mod test_program;
 pub use example_usage::run_factory_demo;
 pub use factory_example::create_sample_factory_setup;
 pub use test_program::run_test_program;
+
+use crate::SatisflowEngine;
+
+/// Names of the bundled example setups, as accepted by [`load_example`]
+pub const EXAMPLE_NAMES: [&str; 1] = ["five-factory"];
+
+/// Build a bundled example setup by name
+///
+/// # Returns
+///
+/// The populated engine, or `None` if no example has this name
+pub fn load_example(name: &str) -> Option<SatisflowEngine> {
+    match name {
+        "five-factory" => Some(create_sample_factory_setup()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_example_by_name() {
+        for name in EXAMPLE_NAMES {
+            assert!(load_example(name).is_some(), "{} should load", name);
+        }
+        assert_eq!(
+            load_example("five-factory")
+                .unwrap()
+                .get_all_factories()
+                .len(),
+            5
+        );
+        assert!(load_example("unknown").is_none());
+    }
+}
@@ -0,0 +1,296 @@
+//! Spreadsheet exports of the whole save
+//!
+//! Each [`CsvSection`] is one table with a header row. Rates are in items per
+//! minute and power in MW, unformatted, so spreadsheets can compute with them.
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{
+    factory::Factory, item_name, production_line::ProductionLine, Item, ItemFlowEntry,
+    ItemFlowSource,
+};
+use crate::SatisflowEngine;
+
+/// Table exported by [`to_csv`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CsvSection {
+    Factories,
+    ProductionLines,
+    Items,
+    Logistics,
+}
+
+impl CsvSection {
+    /// Suggested file name for the exported table
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            CsvSection::Factories => "factories.csv",
+            CsvSection::ProductionLines => "production_lines.csv",
+            CsvSection::Items => "items.csv",
+            CsvSection::Logistics => "logistics.csv",
+        }
+    }
+}
+
+/// Export one table of the save as CSV
+///
+/// Rows are sorted by name so repeated exports diff cleanly.
+pub fn to_csv(engine: &SatisflowEngine, section: CsvSection) -> String {
+    let mut factories: Vec<&Factory> = engine.get_all_factories().values().collect();
+    factories.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+
+    match section {
+        CsvSection::Factories => factories_csv(&factories),
+        CsvSection::ProductionLines => production_lines_csv(&factories),
+        CsvSection::Items => items_csv(engine),
+        CsvSection::Logistics => logistics_csv(engine),
+    }
+}
+
+fn factories_csv(factories: &[&Factory]) -> String {
+    let mut csv = String::from(
+        "id,name,tags,archived,production_lines,raw_inputs,power_generators,\
+         power_generation_mw,power_consumption_mw,power_balance_mw\n",
+    );
+    for factory in factories {
+        csv += &format!(
+            "{},{},{},{},{},{},{},{},{},{}\n",
+            factory.id,
+            csv_field(&factory.name),
+            csv_field(&factory.tags.join(";")),
+            factory.archived,
+            factory.production_lines.len(),
+            factory.raw_inputs.len(),
+            factory.power_generators.len(),
+            factory.total_power_generation(),
+            factory.total_power_consumption(),
+            factory.power_balance(),
+        );
+    }
+    csv
+}
+
+fn production_lines_csv(factories: &[&Factory]) -> String {
+    let mut csv = String::from(
+        "factory,id,name,kind,recipe,machines,enabled,power_consumption_mw,inputs,outputs\n",
+    );
+    for factory in factories {
+        let mut lines: Vec<&ProductionLine> = factory.production_lines.values().collect();
+        lines.sort_by(|a, b| a.name().cmp(b.name()).then(a.id().cmp(&b.id())));
+        for line in lines {
+            let (kind, recipe) = match line {
+                ProductionLine::ProductionLineRecipe(recipe_line) => {
+                    ("recipe", recipe_line.recipe.name().to_string())
+                }
+                ProductionLine::ProductionLineBlueprint(blueprint) => {
+                    let recipes: Vec<&str> = blueprint
+                        .production_lines
+                        .iter()
+                        .map(|inner| inner.recipe.name())
+                        .collect();
+                    ("blueprint", recipes.join(";"))
+                }
+            };
+            csv += &format!(
+                "{},{},{},{},{},{},{},{},{},{}\n",
+                csv_field(&factory.name),
+                line.id(),
+                csv_field(line.name()),
+                kind,
+                csv_field(&recipe),
+                line.total_machines(),
+                line.is_enabled(),
+                line.total_power_consumption_with(factory.balance()),
+                csv_field(&rates_field(line.input_rate())),
+                csv_field(&rates_field(line.output_rate())),
+            );
+        }
+    }
+    csv
+}
+
+fn items_csv(engine: &SatisflowEngine) -> String {
+    let mut items: Vec<Item> = engine
+        .get_all_factories()
+        .values()
+        .flat_map(|factory| {
+            let mut factory = factory.clone();
+            factory.calculate_item(engine.get_all_logistics());
+            factory.items.into_keys()
+        })
+        .collect();
+    items.sort_by_key(|item| item_name(*item));
+    items.dedup();
+
+    let mut csv = String::from("item,produced_per_min,consumed_per_min,net_per_min\n");
+    for item in items {
+        let report = engine.item_balance_report(item);
+        // Logistics move items between factories without changing the world total
+        let total = |entries: &[ItemFlowEntry]| -> f64 {
+            entries
+                .iter()
+                .filter(|entry| {
+                    !matches!(
+                        entry.source,
+                        ItemFlowSource::LogisticsImport { .. }
+                            | ItemFlowSource::LogisticsExport { .. }
+                    )
+                })
+                // An empty f64 sum is -0, which would print as "-0"
+                .fold(0.0, |total, entry| total + entry.quantity_per_min)
+        };
+        csv += &format!(
+            "{},{},{},{}\n",
+            csv_field(item_name(item)),
+            total(&report.producers),
+            total(&report.consumers),
+            report.net,
+        );
+    }
+    csv
+}
+
+fn logistics_csv(engine: &SatisflowEngine) -> String {
+    let factory_name = |id| {
+        engine
+            .get_factory(id)
+            .map(|factory| factory.name.clone())
+            .unwrap_or_else(|| id.to_string())
+    };
+    let mut lines: Vec<_> = engine.get_all_logistics().values().collect();
+    lines.sort_by_cached_key(|line| (line.transport_details.to_string(), line.id));
+
+    let mut csv =
+        String::from("id,from_factory,to_factory,transport,details,items,total_per_min\n");
+    for line in lines {
+        let items: Vec<(Item, f64)> = line
+            .get_items()
+            .into_iter()
+            .map(|flow| (flow.item, flow.quantity_per_min))
+            .collect();
+        csv += &format!(
+            "{},{},{},{},{},{},{}\n",
+            line.id,
+            csv_field(&factory_name(line.from_factory)),
+            csv_field(&factory_name(line.to_factory)),
+            line.transport_type,
+            csv_field(&line.transport_details.to_string()),
+            csv_field(&rates_field(items)),
+            line.total_quantity_per_min(),
+        );
+    }
+    csv
+}
+
+/// List rates in one cell, e.g. `Iron Ore: 30;Coal: 15`
+fn rates_field(rates: Vec<(Item, f64)>) -> String {
+    rates
+        .into_iter()
+        .map(|(item, rate)| format!("{}: {}", item_name(item), rate))
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        logistics::{TransportType, TruckTransport},
+        production_line::{MachineGroup, ProductionLineRecipe},
+        raw_input::{ExtractorType, Purity, RawInput},
+        Recipe,
+    };
+    use uuid::Uuid;
+
+    fn uuid_from_u64<T: From<Uuid>>(value: u64) -> T {
+        Uuid::from_u128(value as u128).into()
+    }
+
+    fn sample_engine() -> SatisflowEngine {
+        let mut engine = SatisflowEngine::new();
+        let mine = engine.create_factory("Mine, North".to_string(), None);
+        let smelter = engine.create_factory("Smelter".to_string(), None);
+
+        engine
+            .get_factory_mut(mine)
+            .unwrap()
+            .add_raw_input(
+                RawInput::new(
+                    uuid_from_u64(1),
+                    ExtractorType::MinerMk1,
+                    Item::IronOre,
+                    Some(Purity::Normal),
+                    100.0,
+                    1,
+                )
+                .unwrap(),
+            )
+            .unwrap();
+        let mut line = ProductionLineRecipe::new(
+            uuid_from_u64(2),
+            "Ingots".to_string(),
+            None,
+            Recipe::IronIngot,
+        );
+        line.add_machine_group(MachineGroup::new(2, 100.0, 0))
+            .unwrap();
+        engine
+            .get_factory_mut(smelter)
+            .unwrap()
+            .add_production_line(ProductionLine::ProductionLineRecipe(line));
+        engine
+            .create_logistics_line(
+                mine,
+                smelter,
+                TransportType::Truck(TruckTransport::new(1, Item::IronOre, 60.0)),
+                "Ore run".to_string(),
+            )
+            .unwrap();
+        engine
+    }
+
+    #[test]
+    fn test_items_csv_ignores_logistics() {
+        let csv = to_csv(&sample_engine(), CsvSection::Items);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                "item,produced_per_min,consumed_per_min,net_per_min",
+                "Iron Ingot,60,0,60",
+                "Iron Ore,60,60,0",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_factory_and_logistics_csv() {
+        let engine = sample_engine();
+
+        let factories = to_csv(&engine, CsvSection::Factories);
+        let rows: Vec<&str> = factories.lines().collect();
+        assert_eq!(rows.len(), 3);
+        assert!(rows[1].contains(",\"Mine, North\","));
+        assert!(rows[2].contains(",Smelter,"));
+
+        let lines = to_csv(&engine, CsvSection::ProductionLines);
+        let row = lines.lines().nth(1).unwrap();
+        assert!(row.starts_with("Smelter,"));
+        assert!(row.ends_with(",Ingots,recipe,Iron Ingot,2,true,8,Iron Ore: 60,Iron Ingot: 60"));
+
+        let logistics = to_csv(&engine, CsvSection::Logistics);
+        let row = logistics.lines().nth(1).unwrap();
+        assert!(row.ends_with(",\"Mine, North\",Smelter,Truck,Ore run,Iron Ore: 60,60"));
+    }
+}
@@ -0,0 +1,173 @@
+//! Display formatting for rates, power, energy and durations
+//!
+//! The engine stores rates in items per minute, power in MW and energy in MWh.
+//! Every textual surface (report cards, exports, command-line output) should go
+//! through [`NumberFormat`] so numbers read the same everywhere.
+
+use serde::{Deserialize, Serialize};
+
+/// SI prefixes used for item rates, from smallest to largest
+const RATE_PREFIXES: [&str; 4] = ["", "k", "M", "G"];
+/// Power units, starting from the internal MW unit
+const POWER_UNITS: [&str; 4] = ["MW", "GW", "TW", "PW"];
+/// Energy units, starting from the internal MWh unit
+const ENERGY_UNITS: [&str; 4] = ["MWh", "GWh", "TWh", "PWh"];
+
+/// Time base used when displaying item rates
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateUnit {
+    #[default]
+    PerMinute,
+    PerSecond,
+}
+
+impl RateUnit {
+    /// Convert a rate in items per minute to this unit
+    pub fn convert(&self, quantity_per_min: f64) -> f64 {
+        match self {
+            RateUnit::PerMinute => quantity_per_min,
+            RateUnit::PerSecond => quantity_per_min / 60.0,
+        }
+    }
+
+    /// Suffix appended to formatted rates
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            RateUnit::PerMinute => "/min",
+            RateUnit::PerSecond => "/s",
+        }
+    }
+}
+
+/// Formatting options shared by every textual output
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NumberFormat {
+    pub rate_unit: RateUnit,
+    /// Number of decimals after scaling
+    pub precision: usize,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self {
+            rate_unit: RateUnit::default(),
+            precision: 1,
+        }
+    }
+}
+
+impl NumberFormat {
+    /// Use the given time base for item rates
+    pub fn with_rate_unit(mut self, rate_unit: RateUnit) -> Self {
+        self.rate_unit = rate_unit;
+        self
+    }
+
+    /// Use the given number of decimals
+    pub fn with_precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Format an item rate given in items per minute, e.g. `1.2k/min`
+    pub fn rate(&self, quantity_per_min: f64) -> String {
+        let (value, prefix) = scale(
+            self.rate_unit.convert(quantity_per_min),
+            &RATE_PREFIXES,
+            self.precision,
+        );
+        format!(
+            "{:.*}{}{}",
+            self.precision,
+            value,
+            prefix,
+            self.rate_unit.suffix()
+        )
+    }
+
+    /// Format power given in MW, e.g. `2.4 GW`
+    pub fn power(&self, mw: f64) -> String {
+        let (value, unit) = scale(mw, &POWER_UNITS, self.precision);
+        format!("{:.*} {}", self.precision, value, unit)
+    }
+
+    /// Format energy given in MWh, e.g. `1.5 GWh`
+    pub fn energy(&self, mwh: f64) -> String {
+        let (value, unit) = scale(mwh, &ENERGY_UNITS, self.precision);
+        format!("{:.*} {}", self.precision, value, unit)
+    }
+
+    /// Format a duration given in minutes, rounded to the minute, e.g. `2 h 5 min`
+    ///
+    /// Durations are never shown with decimals, whatever the precision.
+    pub fn duration(&self, minutes: f64) -> String {
+        let minutes = minutes.max(0.0).round() as u64;
+        match (minutes / 60, minutes % 60) {
+            (0, 0) => "under 1 min".to_string(),
+            (0, minutes) => format!("{} min", minutes),
+            (hours, 0) => format!("{} h", hours),
+            (hours, minutes) => format!("{} h {} min", hours, minutes),
+        }
+    }
+}
+
+/// Divide the value by 1000 until it fits the unit, checking the rounded value
+/// so that 999.96 is shown as `1.0k` rather than `1000.0`
+fn scale(value: f64, units: &[&'static str], precision: usize) -> (f64, &'static str) {
+    let factor = 10f64.powi(precision as i32);
+    // Adding 0 turns -0 (e.g. an empty sum) into 0, which prints without a sign
+    let mut scaled = value + 0.0;
+    let mut index = 0;
+    while index + 1 < units.len() && (scaled * factor).round().abs() >= 1000.0 * factor {
+        scaled /= 1000.0;
+        index += 1;
+    }
+    (scaled, units[index])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_prefixes() {
+        let format = NumberFormat::default();
+
+        assert_eq!(format.rate(60.0), "60.0/min");
+        assert_eq!(format.rate(1200.0), "1.2k/min");
+        assert_eq!(format.rate(-2_500_000.0), "-2.5M/min");
+        assert_eq!(format.rate(-0.0), "0.0/min");
+        assert_eq!(format.rate(999.96), "1.0k/min");
+    }
+
+    #[test]
+    fn test_per_second_rates() {
+        let format = NumberFormat::default().with_rate_unit(RateUnit::PerSecond);
+
+        assert_eq!(format.rate(60.0), "1.0/s");
+        assert_eq!(format.rate(90_000.0), "1.5k/s");
+        assert_eq!(format.with_precision(2).rate(45.0), "0.75/s");
+    }
+
+    #[test]
+    fn test_power_and_energy_units() {
+        let format = NumberFormat::default();
+
+        assert_eq!(format.power(8.0), "8.0 MW");
+        assert_eq!(format.power(2400.0), "2.4 GW");
+        assert_eq!(format.power(-1_500_000.0), "-1.5 TW");
+        assert_eq!(format.energy(1500.0), "1.5 GWh");
+        assert_eq!(format.with_precision(0).power(30.0), "30 MW");
+    }
+
+    #[test]
+    fn test_durations() {
+        let format = NumberFormat::default();
+
+        assert_eq!(format.duration(34.2), "34 min");
+        assert_eq!(format.duration(125.0), "2 h 5 min");
+        assert_eq!(format.duration(120.4), "2 h");
+        assert_eq!(format.duration(0.2), "under 1 min");
+    }
+}
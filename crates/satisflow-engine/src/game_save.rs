@@ -0,0 +1,1278 @@
+//! Reading Satisfactory game saves (`.sav`)
+//!
+//! A save starts with an uncompressed header (session name, build, play time)
+//! followed by the world data, split into zlib-compressed chunks. The world
+//! data holds the objects of every level: first a header per object with its
+//! class and instance name, then the properties of each object in the same
+//! order. Production buildings keep their recipe and clock speed there,
+//! generators their fuel, and the power connections of both the circuit they
+//! are wired to, which is what an imported factory is made of.
+//!
+//! Only the world data layout of Satisfactory 1.0 and later is read; older
+//! saves are rejected once their header is known.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::Read;
+
+use flate2::read::ZlibDecoder;
+use serde::{Deserialize, Serialize};
+
+use crate::import::{
+    create_factories, item_by_class_name, recipe_by_class_name, recipe_line, ImportedFactory,
+    ImportedFactorySummary,
+};
+use crate::models::{
+    production_line::{MachineGroup, ProductionLineRecipe},
+    recipe_info, GeneratorGroup, GeneratorType, PowerGenerator, PowerGeneratorId,
+};
+use crate::SatisflowEngine;
+
+/// Marks the start of every compressed chunk
+const PACKAGE_FILE_TAG: u32 = 0x9E2A_83C1;
+
+/// Marks the chunk header layout used since Update 6
+const ARCHIVE_V2_HEADER: u32 = 0x2222_2222;
+
+/// Compression algorithm byte of zlib chunks, the only one the game writes
+const ZLIB_ALGORITHM: u8 = 3;
+
+/// .NET ticks (100 ns since 0001-01-01) at the Unix epoch
+const UNIX_EPOCH_TICKS: i64 = 621_355_968_000_000_000;
+
+/// Header version that put the save name in front of the map name
+const SAVE_NAME_HEADER_VERSION: i32 = 14;
+
+/// First save version written by Satisfactory 1.0, whose world data is read
+const FIRST_WORLD_SAVE_VERSION: i32 = 46;
+
+/// Largest world data decompressed, well above the biggest late-game saves
+const MAX_WORLD_DATA_SIZE: u64 = 1 << 30;
+
+/// Object header type of actors; every other object is a component
+const ACTOR_OBJECT: i32 = 1;
+
+/// Class of the components wiring a building to a power circuit
+const POWER_CONNECTION_CLASS: &str = "/Script/FactoryGame.FGPowerConnectionComponent";
+
+/// Buildings that run a recipe
+const PRODUCTION_BUILDINGS: &[&str] = &[
+    "Build_SmelterMk1_C",
+    "Build_FoundryMk1_C",
+    "Build_ConstructorMk1_C",
+    "Build_AssemblerMk1_C",
+    "Build_ManufacturerMk1_C",
+    "Build_OilRefinery_C",
+    "Build_Blender_C",
+    "Build_Packager_C",
+    "Build_HadronCollider_C",
+    "Build_QuantumEncoder_C",
+    "Build_Converter_C",
+];
+
+/// Generator buildings; the biomass burner of the HUB isn't one of them
+const GENERATORS: &[(&str, GeneratorType)] = &[
+    ("Build_GeneratorBiomass_Automated_C", GeneratorType::Biomass),
+    ("Build_GeneratorBiomass_C", GeneratorType::Biomass),
+    ("Build_GeneratorCoal_C", GeneratorType::Coal),
+    ("Build_GeneratorFuel_C", GeneratorType::Fuel),
+    ("Build_GeneratorNuclear_C", GeneratorType::Nuclear),
+    ("Build_GeneratorGeoThermal_C", GeneratorType::Geothermal),
+];
+
+/// Extractors, whose node purity isn't in the save; reported, not imported
+const EXTRACTORS: &[&str] = &[
+    "Build_MinerMk1_C",
+    "Build_MinerMk2_C",
+    "Build_MinerMk3_C",
+    "Build_WaterPump_C",
+    "Build_OilPump_C",
+    "Build_FrackingExtractor_C",
+];
+
+/// Header of a game save
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SaveHeader {
+    pub header_version: i32,
+    pub save_version: i32,
+    pub build_version: i32,
+    /// Name of the save slot, empty before Satisfactory 1.0
+    #[serde(default)]
+    pub save_name: String,
+    pub map_name: String,
+    pub map_options: String,
+    pub session_name: String,
+    pub play_duration_secs: i32,
+    /// When the game was saved, in seconds since the Unix epoch
+    pub saved_at: i64,
+    /// Mods listed by the game, empty for unmodded saves
+    pub mod_metadata: Option<String>,
+    pub is_modded: bool,
+}
+
+/// What could be read from a save file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameSaveSummary {
+    pub header: SaveHeader,
+    /// Number of compressed chunks holding the world data
+    pub chunk_count: usize,
+    /// Size of the world data once decompressed, in bytes
+    pub world_data_size: u64,
+}
+
+impl GameSaveSummary {
+    /// Read the header and chunk table of a save file
+    ///
+    /// # Returns
+    ///
+    /// Result containing the summary, or an error if the file isn't a
+    /// Satisfactory save or is truncated
+    pub fn read(bytes: &[u8]) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut reader = Reader::new(bytes);
+        let header = read_header(&mut reader)?;
+        let chunks = read_chunks(&mut reader)?;
+        Ok(Self::new(header, &chunks))
+    }
+
+    fn new(header: SaveHeader, chunks: &[Chunk]) -> Self {
+        Self {
+            header,
+            chunk_count: chunks.len(),
+            world_data_size: chunks.iter().map(|chunk| chunk.uncompressed_size).sum(),
+        }
+    }
+}
+
+/// Factories rebuilt from the buildings of a game save, one per power circuit
+#[derive(Debug, Clone)]
+pub struct GameSavePlan {
+    pub save: GameSaveSummary,
+    pub factories: Vec<ImportedFactory>,
+    /// Buildings that couldn't be imported or were approximated
+    pub warnings: Vec<String>,
+}
+
+impl GameSavePlan {
+    /// Read the buildings of a save file
+    ///
+    /// Buildings wired to the same power circuit make up one factory, with a
+    /// production line per recipe and a generator per generator type and fuel.
+    /// Buildings that aren't connected to any circuit make up a last factory.
+    ///
+    /// # Returns
+    ///
+    /// Result containing the plan, or an error if the file isn't a save from
+    /// Satisfactory 1.0 or later, or its world data is corrupt
+    pub fn read(bytes: &[u8]) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut reader = Reader::new(bytes);
+        let header = read_header(&mut reader)?;
+        if header.save_version < FIRST_WORLD_SAVE_VERSION {
+            return Err(format!(
+                "Saves from before Satisfactory 1.0 (save version {}) can't be imported",
+                header.save_version
+            )
+            .into());
+        }
+        let chunks = read_chunks(&mut reader)?;
+        let world = read_world(&decompress(&chunks)?)?;
+        Ok(world.plan(GameSaveSummary::new(header, &chunks)))
+    }
+}
+
+/// Outcome of [`import_game_save`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameSaveImportReport {
+    pub save: GameSaveSummary,
+    /// When set, nothing was created and `factories` is a preview
+    pub dry_run: bool,
+    pub factories: Vec<ImportedFactorySummary>,
+    pub warnings: Vec<String>,
+}
+
+/// Create the factories of a game save, each on a power grid named after its
+/// circuit, unless `dry_run` is set
+///
+/// # Returns
+///
+/// Result containing the report, or an error if a generator of the plan is
+/// invalid; factories created before it are kept
+pub fn import_game_save(
+    engine: &mut SatisflowEngine,
+    plan: GameSavePlan,
+    dry_run: bool,
+) -> Result<GameSaveImportReport, Box<dyn std::error::Error>> {
+    Ok(GameSaveImportReport {
+        save: plan.save,
+        dry_run,
+        factories: create_factories(engine, plan.factories, dry_run)?,
+        warnings: plan.warnings,
+    })
+}
+
+fn read_header(reader: &mut Reader) -> Result<SaveHeader, Box<dyn std::error::Error>> {
+    let header_version = reader.i32()?;
+    let save_version = reader.i32()?;
+    let build_version = reader.i32()?;
+    if !(1..=64).contains(&header_version) || save_version < 0 || build_version < 0 {
+        return Err("Not a Satisfactory save file".into());
+    }
+    let save_name = if header_version >= SAVE_NAME_HEADER_VERSION {
+        reader.string()?
+    } else {
+        String::new()
+    };
+    let map_name = reader.string()?;
+    let map_options = reader.string()?;
+    let session_name = reader.string()?;
+    let play_duration_secs = reader.i32()?;
+    let ticks = reader.i64()?;
+    if header_version >= 5 {
+        reader.take(1)?; // session visibility
+    }
+    if header_version >= 7 {
+        reader.i32()?; // editor object version
+    }
+    let (mod_metadata, is_modded) = if header_version >= 8 {
+        let metadata = reader.string()?;
+        let is_modded = reader.i32()? != 0;
+        (
+            Some(metadata).filter(|metadata| !metadata.is_empty()),
+            is_modded,
+        )
+    } else {
+        (None, false)
+    };
+    // Newer headers add fields we don't use; the world data starts at the
+    // first chunk tag
+    reader.skip_to(PACKAGE_FILE_TAG)?;
+
+    Ok(SaveHeader {
+        header_version,
+        save_version,
+        build_version,
+        save_name,
+        map_name,
+        map_options,
+        session_name,
+        play_duration_secs,
+        saved_at: (ticks - UNIX_EPOCH_TICKS) / 10_000_000,
+        mod_metadata,
+        is_modded,
+    })
+}
+
+/// A compressed chunk of the world data
+struct Chunk<'a> {
+    /// Compression algorithm, only stored since Satisfactory 1.0
+    algorithm: Option<u8>,
+    data: &'a [u8],
+    uncompressed_size: u64,
+}
+
+fn read_chunks<'a>(reader: &mut Reader<'a>) -> Result<Vec<Chunk<'a>>, Box<dyn std::error::Error>> {
+    let mut chunks = Vec::new();
+    while !reader.is_empty() {
+        let (algorithm, compressed, uncompressed_size) = read_chunk_header(reader)?;
+        chunks.push(Chunk {
+            algorithm,
+            data: reader.take(compressed as usize)?,
+            uncompressed_size,
+        });
+    }
+    if chunks.is_empty() {
+        return Err("Save file has no world data".into());
+    }
+    Ok(chunks)
+}
+
+/// Read a chunk header and return the compression algorithm and the
+/// compressed and uncompressed sizes
+fn read_chunk_header(
+    reader: &mut Reader,
+) -> Result<(Option<u8>, u64, u64), Box<dyn std::error::Error>> {
+    if reader.u32()? != PACKAGE_FILE_TAG {
+        return Err(format!("Corrupt chunk header at byte {}", reader.offset - 4).into());
+    }
+    let v2 = reader.u32()? == ARCHIVE_V2_HEADER;
+    if !v2 {
+        return Err("Saves from before Update 6 aren't supported".into());
+    }
+    reader.i64()?; // maximum chunk size
+
+    // 1.0 saves add a compression algorithm byte; the chunk sizes are stored
+    // twice, which tells the two layouts apart
+    let start = reader.offset;
+    for algorithm_byte in [false, true] {
+        reader.offset = start;
+        let algorithm = if algorithm_byte {
+            Some(reader.u8()?)
+        } else {
+            None
+        };
+        let sizes = [reader.i64()?, reader.i64()?, reader.i64()?, reader.i64()?];
+        if sizes[0] == sizes[2] && sizes[1] == sizes[3] && sizes[0] >= 0 && sizes[1] >= 0 {
+            return Ok((algorithm, sizes[0] as u64, sizes[1] as u64));
+        }
+    }
+    Err(format!("Corrupt chunk header at byte {}", start).into())
+}
+
+/// Inflate and join the chunks of the world data
+fn decompress(chunks: &[Chunk]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let size: u64 = chunks.iter().map(|chunk| chunk.uncompressed_size).sum();
+    if size > MAX_WORLD_DATA_SIZE {
+        return Err(format!("World data of {} bytes is too large to import", size).into());
+    }
+    let mut world = Vec::new();
+    for chunk in chunks {
+        if let Some(algorithm) = chunk
+            .algorithm
+            .filter(|algorithm| *algorithm != ZLIB_ALGORITHM)
+        {
+            return Err(format!("Unsupported chunk compression {}", algorithm).into());
+        }
+        let start = world.len();
+        ZlibDecoder::new(chunk.data)
+            .take(chunk.uncompressed_size)
+            .read_to_end(&mut world)
+            .map_err(|e| format!("Corrupt world data: {}", e))?;
+        if (world.len() - start) as u64 != chunk.uncompressed_size {
+            return Err("Corrupt world data: a chunk is shorter than its header says".into());
+        }
+    }
+    Ok(world)
+}
+
+/// A production building or generator read from the save
+#[derive(Debug, Clone, PartialEq)]
+struct Building {
+    instance: String,
+    /// Class name, e.g. `Build_SmelterMk1_C`
+    class: String,
+    properties: HashMap<String, Property>,
+}
+
+impl Building {
+    /// Class name of an object property, e.g. `Recipe_IngotIron_C`
+    fn class_of(&self, property: &str) -> Option<&str> {
+        match self.properties.get(property)? {
+            Property::Object(path) => path.rsplit('.').next(),
+            _ => None,
+        }
+    }
+
+    fn float(&self, property: &str) -> Option<f64> {
+        match self.properties.get(property)? {
+            Property::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Clock speed in percent, 100 unless changed
+    ///
+    /// The game stores it as an `f32` fraction, and sets it to four decimals
+    /// of a percent at most; the noise beyond that is dropped.
+    fn clock_speed(&self) -> f64 {
+        let potential = self.float("mCurrentPotential").unwrap_or(1.0);
+        (potential * 1e6).round() / 1e4
+    }
+}
+
+/// Property values the import reads
+#[derive(Debug, Clone, PartialEq)]
+enum Property {
+    Int(i32),
+    Float(f64),
+    /// Path name of the referenced object
+    Object(String),
+}
+
+/// Objects of the world data the import cares about
+#[derive(Debug, Default)]
+struct World {
+    /// Production buildings and generators, in save order
+    buildings: Vec<Building>,
+    /// Power circuit of the actors with a connected power connection
+    circuits: HashMap<String, i32>,
+    extractors: usize,
+    /// Buildings and power connections whose data couldn't be read
+    unreadable: usize,
+}
+
+/// Header of an object, followed in the save by the headers of the other
+/// objects of its level
+struct ObjectHeader {
+    class_path: String,
+    instance: String,
+    /// Instance name of the owning actor, components only
+    parent: Option<String>,
+}
+
+impl ObjectHeader {
+    /// Class name without its package, e.g. `Build_SmelterMk1_C`
+    fn class(&self) -> &str {
+        self.class_path.rsplit('.').next().unwrap_or_default()
+    }
+}
+
+fn read_world(bytes: &[u8]) -> Result<World, Box<dyn std::error::Error>> {
+    let mut reader = Reader::new(bytes);
+    let mut world = World::default();
+    reader.i64()?; // size of the rest of the world data
+
+    // World partition grids: name, cell size and hash, then each cell's hash
+    for _ in 0..reader.count()? {
+        reader.string()?;
+        reader.i32()?;
+        reader.u32()?;
+        for _ in 0..reader.count()? {
+            reader.string()?;
+            reader.u32()?;
+        }
+    }
+    for _ in 0..reader.count()? {
+        reader.string()?; // sublevel name
+        read_level(&mut reader, &mut world)?;
+    }
+    // The persistent level comes last, without a name
+    read_level(&mut reader, &mut world)?;
+    Ok(world)
+}
+
+fn read_level(reader: &mut Reader, world: &mut World) -> Result<(), Box<dyn std::error::Error>> {
+    // Object headers, followed by collectables that are listed again below
+    let headers_size = reader.size()?;
+    let mut headers_reader = Reader::new(reader.take(headers_size)?);
+    let headers = (0..headers_reader.count()?)
+        .map(|_| read_object_header(&mut headers_reader))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let objects_size = reader.size()?;
+    let mut objects = Reader::new(reader.take(objects_size)?);
+    let count = objects.count()?;
+    if count != headers.len() {
+        return Err(format!(
+            "Corrupt world data: a level has {} objects for {} headers",
+            count,
+            headers.len()
+        )
+        .into());
+    }
+    for header in headers {
+        objects.i32()?; // object version
+        objects.i32()?; // whether references move to the persistent level
+        let size = objects.i32()?;
+        let data = objects.take(usize::try_from(size)?)?;
+        world.add(header, data);
+    }
+
+    // Collectables picked up in the level
+    for _ in 0..reader.count()? {
+        reader.string()?;
+        reader.string()?;
+    }
+    Ok(())
+}
+
+fn read_object_header(reader: &mut Reader) -> Result<ObjectHeader, Box<dyn std::error::Error>> {
+    let kind = reader.i32()?;
+    let class_path = reader.string()?;
+    reader.string()?; // root object
+    let instance = reader.string()?;
+    let parent = if kind == ACTOR_OBJECT {
+        reader.i32()?; // whether the transform is needed
+        reader.take(10 * 4)?; // rotation, translation and scale, as f32
+        reader.i32()?; // whether the actor was placed in the level
+        None
+    } else {
+        Some(reader.string()?)
+    };
+    Ok(ObjectHeader {
+        class_path,
+        instance,
+        parent,
+    })
+}
+
+impl World {
+    /// Keep the object if it's a building or power connection; the data of
+    /// any other object is left unread
+    fn add(&mut self, header: ObjectHeader, data: &[u8]) {
+        let class = header.class();
+        if EXTRACTORS.contains(&class) {
+            self.extractors += 1;
+            return;
+        }
+        let is_building = header.parent.is_none()
+            && (PRODUCTION_BUILDINGS.contains(&class)
+                || GENERATORS.iter().any(|(name, _)| *name == class));
+        let is_power_connection = header.class_path == POWER_CONNECTION_CLASS;
+        if !is_building && !is_power_connection {
+            return;
+        }
+
+        let class = class.to_string();
+        let mut reader = Reader::new(data);
+        let properties = match header.parent {
+            None => read_actor_properties(&mut reader),
+            Some(_) => read_properties(&mut reader),
+        };
+        let Ok(properties) = properties else {
+            self.unreadable += 1;
+            return;
+        };
+        match header.parent {
+            Some(parent) => {
+                if let Some(Property::Int(circuit)) = properties.get("mCircuitID") {
+                    self.circuits.entry(parent).or_insert(*circuit);
+                }
+            }
+            None => self.buildings.push(Building {
+                class,
+                instance: header.instance,
+                properties,
+            }),
+        }
+    }
+
+    /// Group the buildings by power circuit into factories
+    fn plan(self, save: GameSaveSummary) -> GameSavePlan {
+        let mut warnings = Vec::new();
+        let mut circuits: BTreeMap<Option<i32>, ImportedFactory> = BTreeMap::new();
+        let mut unknown_recipes: BTreeMap<String, usize> = BTreeMap::new();
+        let mut without_recipe = 0;
+        let mut unknown_fuels: BTreeMap<String, usize> = BTreeMap::new();
+        let mut without_fuel = 0;
+        let mut geothermal = false;
+
+        for building in &self.buildings {
+            let circuit = self.circuits.get(&building.instance).copied();
+            let factory = circuits.entry(circuit).or_insert_with(|| {
+                let name = match circuit {
+                    Some(circuit) => format!("Power circuit {}", circuit),
+                    None => "Unpowered buildings".to_string(),
+                };
+                ImportedFactory {
+                    power_grid: circuit.map(|_| name.clone()),
+                    name,
+                    ..Default::default()
+                }
+            });
+            let clock_speed = building.clock_speed();
+            if !(0.0..=250.0).contains(&clock_speed) || clock_speed == 0.0 {
+                warnings.push(format!(
+                    "{} has clock speed {}%, outside 0-250%; ignored",
+                    building.instance, clock_speed
+                ));
+                continue;
+            }
+
+            if let Some((_, generator_type)) =
+                GENERATORS.iter().find(|(name, _)| *name == building.class)
+            {
+                let generator = match generator_type {
+                    GeneratorType::Geothermal => {
+                        geothermal = true;
+                        Some(PowerGenerator::new_geothermal(PowerGeneratorId::new_v4()))
+                    }
+                    generator_type => match building.class_of("mCurrentFuelClass") {
+                        None => {
+                            without_fuel += 1;
+                            None
+                        }
+                        Some(fuel) => {
+                            let generator = item_by_class_name(fuel).and_then(|item| {
+                                PowerGenerator::new(
+                                    PowerGeneratorId::new_v4(),
+                                    *generator_type,
+                                    item,
+                                )
+                                .ok()
+                            });
+                            if generator.is_none() {
+                                *unknown_fuels.entry(fuel.to_string()).or_default() += 1;
+                            }
+                            generator
+                        }
+                    },
+                };
+                // The clock speed was checked above, so the group is valid
+                if let (Some(generator), Ok(group)) =
+                    (generator, GeneratorGroup::new(1, clock_speed))
+                {
+                    add_generator(&mut factory.power_generators, generator, group);
+                }
+                continue;
+            }
+
+            let Some(recipe_class) = building.class_of("mCurrentRecipe") else {
+                without_recipe += 1;
+                continue;
+            };
+            let Some(recipe) = recipe_by_class_name(recipe_class) else {
+                *unknown_recipes.entry(recipe_class.to_string()).or_default() += 1;
+                continue;
+            };
+            // Somersloops multiply the output; each filled slot adds its share
+            let max_somersloop = recipe_info(recipe).machine.max_somersloop();
+            let boost = building.float("mCurrentProductionBoost").unwrap_or(1.0);
+            let somersloop = ((boost - 1.0) * max_somersloop as f64)
+                .round()
+                .clamp(0.0, max_somersloop as f64) as u8;
+            add_machine(
+                &mut factory.production_lines,
+                recipe_line(recipe, Vec::new()),
+                MachineGroup::new(1, clock_speed, somersloop),
+            );
+        }
+
+        for (recipe, count) in unknown_recipes {
+            warnings.push(format!(
+                "{} buildings use unknown recipe '{}'; ignored",
+                count, recipe
+            ));
+        }
+        if without_recipe > 0 {
+            warnings.push(format!(
+                "{} production buildings have no recipe set; ignored",
+                without_recipe
+            ));
+        }
+        for (fuel, count) in unknown_fuels {
+            warnings.push(format!(
+                "{} generators burn unknown fuel '{}'; ignored",
+                count, fuel
+            ));
+        }
+        if without_fuel > 0 {
+            warnings.push(format!(
+                "{} generators have no fuel loaded; ignored",
+                without_fuel
+            ));
+        }
+        if geothermal {
+            warnings.push(
+                "Geyser purity isn't in the save; geothermal generators assume normal geysers"
+                    .to_string(),
+            );
+        }
+        if self.extractors > 0 {
+            warnings.push(format!(
+                "{} miners, pumps and extractors aren't imported; node purity isn't in the save, \
+                 add them as raw inputs",
+                self.extractors
+            ));
+        }
+        if self.unreadable > 0 {
+            warnings.push(format!(
+                "{} buildings or power connections couldn't be read; ignored",
+                self.unreadable
+            ));
+        }
+
+        GameSavePlan {
+            save,
+            factories: circuits
+                .into_values()
+                .filter(|factory| {
+                    !factory.production_lines.is_empty() || !factory.power_generators.is_empty()
+                })
+                .collect(),
+            warnings,
+        }
+    }
+}
+
+/// Add a machine to the line running its recipe, in the group with the same
+/// settings
+fn add_machine(
+    lines: &mut Vec<ProductionLineRecipe>,
+    line: ProductionLineRecipe,
+    machine: MachineGroup,
+) {
+    let position = match lines
+        .iter()
+        .position(|existing| existing.recipe == line.recipe)
+    {
+        Some(position) => position,
+        None => {
+            lines.push(line);
+            lines.len() - 1
+        }
+    };
+    let line = &mut lines[position];
+    match line.machine_groups.iter_mut().find(|group| {
+        (group.oc_value - machine.oc_value).abs() < 1e-3 && group.somersloop == machine.somersloop
+    }) {
+        Some(group) => group.number_of_machine += machine.number_of_machine,
+        None => line.machine_groups.push(machine),
+    }
+}
+
+/// Add generators to the one of the same type and fuel, in the group with
+/// the same clock speed
+fn add_generator(
+    generators: &mut Vec<PowerGenerator>,
+    generator: PowerGenerator,
+    group: GeneratorGroup,
+) {
+    let position = match generators.iter().position(|existing| {
+        existing.generator_type == generator.generator_type
+            && existing.fuel_type == generator.fuel_type
+    }) {
+        Some(position) => position,
+        None => {
+            generators.push(generator);
+            generators.len() - 1
+        }
+    };
+    let generator = &mut generators[position];
+    match generator
+        .groups
+        .iter_mut()
+        .find(|existing| (existing.clock_speed - group.clock_speed).abs() < 1e-3)
+    {
+        Some(existing) => existing.number_of_generators += group.number_of_generators,
+        None => generator.groups.push(group),
+    }
+}
+
+/// Properties of an actor, which come after its parent and components
+fn read_actor_properties(
+    reader: &mut Reader,
+) -> Result<HashMap<String, Property>, Box<dyn std::error::Error>> {
+    for _ in 0..2 {
+        reader.string()?; // parent level and path
+    }
+    for _ in 0..reader.count()? {
+        reader.string()?; // component level and path
+        reader.string()?;
+    }
+    read_properties(reader)
+}
+
+/// Read a property list up to its `None` terminator, keeping the integer,
+/// float and object values
+fn read_properties(
+    reader: &mut Reader,
+) -> Result<HashMap<String, Property>, Box<dyn std::error::Error>> {
+    let mut properties = HashMap::new();
+    loop {
+        let name = reader.string()?;
+        if name == "None" {
+            return Ok(properties);
+        }
+        let kind = reader.string()?;
+        let size = reader.i32()?;
+        let index = reader.i32()?;
+        // Type details in the tag, before the value
+        match kind.as_str() {
+            "StructProperty" => {
+                reader.string()?;
+                reader.take(16)?;
+            }
+            "BoolProperty" => {
+                reader.u8()?;
+            }
+            "ByteProperty" | "EnumProperty" | "ArrayProperty" | "SetProperty" => {
+                reader.string()?;
+            }
+            "MapProperty" => {
+                reader.string()?;
+                reader.string()?;
+            }
+            _ => {}
+        }
+        if reader.u8()? != 0 {
+            reader.take(16)?; // property GUID
+        }
+        let mut value = Reader::new(reader.take(usize::try_from(size)?)?);
+        let value = match kind.as_str() {
+            "IntProperty" => Property::Int(value.i32()?),
+            "FloatProperty" => Property::Float(value.f32()? as f64),
+            "DoubleProperty" => Property::Float(value.f64()?),
+            "ObjectProperty" => {
+                value.string()?; // level name
+                Property::Object(value.string()?)
+            }
+            _ => continue,
+        };
+        if index == 0 {
+            properties.insert(name, value);
+        }
+    }
+}
+
+/// Little-endian reader over the save bytes
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, offset: 0 }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.offset >= self.bytes.len()
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Box<dyn std::error::Error>> {
+        let end = self
+            .offset
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or("Save file is truncated")?;
+        let slice = &self.bytes[self.offset..end];
+        self.offset = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, Box<dyn std::error::Error>> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, Box<dyn std::error::Error>> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into()?))
+    }
+
+    fn i32(&mut self) -> Result<i32, Box<dyn std::error::Error>> {
+        Ok(i32::from_le_bytes(self.take(4)?.try_into()?))
+    }
+
+    fn i64(&mut self) -> Result<i64, Box<dyn std::error::Error>> {
+        Ok(i64::from_le_bytes(self.take(8)?.try_into()?))
+    }
+
+    fn f32(&mut self) -> Result<f32, Box<dyn std::error::Error>> {
+        Ok(f32::from_le_bytes(self.take(4)?.try_into()?))
+    }
+
+    fn f64(&mut self) -> Result<f64, Box<dyn std::error::Error>> {
+        Ok(f64::from_le_bytes(self.take(8)?.try_into()?))
+    }
+
+    /// Number of entries that follow; every entry takes at least a byte, so
+    /// a corrupt count fails here instead of looping
+    fn count(&mut self) -> Result<usize, Box<dyn std::error::Error>> {
+        let count = self.i32()?;
+        usize::try_from(count)
+            .ok()
+            .filter(|count| *count <= self.bytes.len() - self.offset)
+            .ok_or_else(|| format!("Corrupt count {} at byte {}", count, self.offset - 4).into())
+    }
+
+    /// Size of the block that follows, in bytes
+    fn size(&mut self) -> Result<usize, Box<dyn std::error::Error>> {
+        let size = self.i64()?;
+        usize::try_from(size)
+            .map_err(|_| format!("Corrupt size {} at byte {}", size, self.offset - 8).into())
+    }
+
+    /// Unreal string: a length including the terminator, negative for UTF-16
+    fn string(&mut self) -> Result<String, Box<dyn std::error::Error>> {
+        let len = self.i32()?;
+        if len == 0 {
+            return Ok(String::new());
+        }
+        let text = if len > 0 {
+            String::from_utf8_lossy(self.take(len as usize)?).into_owned()
+        } else {
+            let units: Vec<u16> = self
+                .take(len.unsigned_abs() as usize * 2)?
+                .chunks_exact(2)
+                .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+                .collect();
+            String::from_utf16_lossy(&units)
+        };
+        Ok(text.trim_end_matches('\0').to_string())
+    }
+
+    fn skip_to(&mut self, tag: u32) -> Result<(), Box<dyn std::error::Error>> {
+        let tag = tag.to_le_bytes();
+        let position = self.bytes[self.offset..]
+            .windows(4)
+            .position(|window| window == tag)
+            .ok_or("Save file has no world data")?;
+        self.offset += position;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Item, LineRecipe, Recipe};
+    use flate2::{write::ZlibEncoder, Compression};
+    use std::io::Write;
+
+    fn string(out: &mut Vec<u8>, text: &str) {
+        out.extend((text.len() as i32 + 1).to_le_bytes());
+        out.extend(text.as_bytes());
+        out.push(0);
+    }
+
+    fn chunk(out: &mut Vec<u8>, data: &[u8], uncompressed: i64, algorithm_byte: bool) {
+        out.extend(PACKAGE_FILE_TAG.to_le_bytes());
+        out.extend(ARCHIVE_V2_HEADER.to_le_bytes());
+        out.extend(131_072i64.to_le_bytes());
+        if algorithm_byte {
+            out.push(ZLIB_ALGORITHM);
+        }
+        for _ in 0..2 {
+            out.extend((data.len() as i64).to_le_bytes());
+            out.extend(uncompressed.to_le_bytes());
+        }
+        out.extend(data);
+    }
+
+    fn header(header_version: i32, save_version: i32) -> Vec<u8> {
+        let mut out = Vec::new();
+        for value in [header_version, save_version, 365_306] {
+            out.extend(value.to_le_bytes());
+        }
+        if header_version >= SAVE_NAME_HEADER_VERSION {
+            string(&mut out, "Iron Valley autosave");
+        }
+        string(&mut out, "Persistent_Level");
+        string(&mut out, "?startloc=Grass Fields");
+        string(&mut out, "Iron Valley");
+        out.extend(7_200i32.to_le_bytes());
+        out.extend((UNIX_EPOCH_TICKS + 1_700_000_000 * 10_000_000).to_le_bytes());
+        out.push(1);
+        out.extend(40i32.to_le_bytes());
+        string(&mut out, "");
+        out.extend(0i32.to_le_bytes());
+        string(&mut out, "save-id");
+        out.extend(1i32.to_le_bytes());
+        out
+    }
+
+    fn save(algorithm_byte: bool) -> Vec<u8> {
+        let mut out = header(13, 46);
+        chunk(&mut out, &[0xAB; 10], 131_072, algorithm_byte);
+        chunk(&mut out, &[0xAB; 4], 512, algorithm_byte);
+        out
+    }
+
+    /// A property tag without array index or GUID, then the value
+    fn property(out: &mut Vec<u8>, name: &str, kind: &str, value: &[u8]) {
+        string(out, name);
+        string(out, kind);
+        out.extend((value.len() as i32).to_le_bytes());
+        out.extend(0i32.to_le_bytes());
+        out.push(0);
+        out.extend(value);
+    }
+
+    fn float(out: &mut Vec<u8>, name: &str, value: f32) {
+        property(out, name, "FloatProperty", &value.to_le_bytes());
+    }
+
+    fn object(out: &mut Vec<u8>, name: &str, path: &str) {
+        let mut value = Vec::new();
+        string(&mut value, "");
+        string(&mut value, path);
+        property(out, name, "ObjectProperty", &value);
+    }
+
+    /// Objects of a level: class path, instance name, owning actor for
+    /// components, and the property list
+    type TestObject<'a> = (&'a str, String, Option<String>, Vec<u8>);
+
+    fn level(out: &mut Vec<u8>, objects: &[TestObject]) {
+        let mut headers = Vec::new();
+        headers.extend((objects.len() as i32).to_le_bytes());
+        for (class_path, instance, parent, _) in objects {
+            headers.extend((parent.is_none() as i32).to_le_bytes());
+            string(&mut headers, class_path);
+            string(&mut headers, "Persistent_Level");
+            string(&mut headers, instance);
+            match parent {
+                None => {
+                    headers.extend(1i32.to_le_bytes());
+                    headers.extend([0u8; 40]);
+                    headers.extend(0i32.to_le_bytes());
+                }
+                Some(parent) => string(&mut headers, parent),
+            }
+        }
+        // Collectables repeated after the headers
+        headers.extend(0i32.to_le_bytes());
+        out.extend((headers.len() as i64).to_le_bytes());
+        out.extend(headers);
+
+        let mut contents = Vec::new();
+        contents.extend((objects.len() as i32).to_le_bytes());
+        for (_, _, parent, properties) in objects {
+            let mut data = Vec::new();
+            if parent.is_none() {
+                string(&mut data, "");
+                string(&mut data, "");
+                data.extend(1i32.to_le_bytes());
+                string(&mut data, "Persistent_Level");
+                string(&mut data, "Persistent_Level:PersistentLevel.Component");
+            }
+            data.extend(properties);
+            string(&mut data, "None");
+            data.extend(0i32.to_le_bytes()); // trailing data the import skips
+            contents.extend(46i32.to_le_bytes());
+            contents.extend(0i32.to_le_bytes());
+            contents.extend((data.len() as i32).to_le_bytes());
+            contents.extend(data);
+        }
+        out.extend((contents.len() as i64).to_le_bytes());
+        out.extend(contents);
+        out.extend(0i32.to_le_bytes());
+    }
+
+    fn building<'a>(
+        objects: &mut Vec<TestObject<'a>>,
+        class_path: &'a str,
+        circuit: Option<i32>,
+        properties: Vec<u8>,
+    ) {
+        let instance = format!("{}:PersistentLevel.Building_{}", class_path, objects.len());
+        if let Some(circuit) = circuit {
+            let mut connection = Vec::new();
+            property(
+                &mut connection,
+                "mCircuitID",
+                "IntProperty",
+                &circuit.to_le_bytes(),
+            );
+            objects.push((
+                POWER_CONNECTION_CLASS,
+                format!("{}.PowerInput", instance),
+                Some(instance.clone()),
+                connection,
+            ));
+        }
+        objects.push((class_path, instance, None, properties));
+    }
+
+    fn world_save(header_version: i32, save_version: i32) -> Vec<u8> {
+        const SMELTER: &str =
+            "/Game/FactoryGame/Buildable/Factory/SmelterMk1/Build_SmelterMk1.Build_SmelterMk1_C";
+        const CONSTRUCTOR: &str = "/Game/FactoryGame/Buildable/Factory/ConstructorMk1/Build_ConstructorMk1.Build_ConstructorMk1_C";
+        const COAL: &str = "/Game/FactoryGame/Buildable/Factory/GeneratorCoal/Build_GeneratorCoal.Build_GeneratorCoal_C";
+        const MINER: &str =
+            "/Game/FactoryGame/Buildable/Factory/MinerMk1/Build_MinerMk1.Build_MinerMk1_C";
+        const FOUNDATION: &str = "/Game/FactoryGame/Buildable/Building/Foundation/Build_Foundation_8x4_01.Build_Foundation_8x4_01_C";
+        let iron_ingot = |out: &mut Vec<u8>| {
+            object(
+                out,
+                "mCurrentRecipe",
+                "/Game/FactoryGame/Recipes/Smelter/Recipe_IngotIron.Recipe_IngotIron_C",
+            )
+        };
+
+        let mut sublevel = Vec::new();
+        let mut properties = Vec::new();
+        iron_ingot(&mut properties);
+        building(&mut sublevel, SMELTER, Some(1), properties);
+
+        let mut persistent = Vec::new();
+        let mut properties = Vec::new();
+        iron_ingot(&mut properties);
+        building(&mut persistent, SMELTER, Some(1), properties);
+        let mut properties = Vec::new();
+        float(&mut properties, "mCurrentPotential", 0.5);
+        iron_ingot(&mut properties);
+        building(&mut persistent, SMELTER, Some(1), properties);
+        // Properties the import doesn't read are skipped by their size
+        let mut properties = Vec::new();
+        string(&mut properties, "mIsProducing");
+        string(&mut properties, "BoolProperty");
+        properties.extend([0u8; 8]);
+        properties.extend([1, 0]);
+        string(&mut properties, "mProductivityMonitor");
+        string(&mut properties, "StructProperty");
+        properties.extend(4i32.to_le_bytes());
+        properties.extend(0i32.to_le_bytes());
+        string(&mut properties, "Vector");
+        properties.extend([0u8; 17]);
+        properties.extend([0u8; 4]);
+        object(
+            &mut properties,
+            "mCurrentRecipe",
+            "/Game/FactoryGame/Recipes/Constructor/Recipe_IronPlate.Recipe_IronPlate_C",
+        );
+        float(&mut properties, "mCurrentProductionBoost", 2.0);
+        float(&mut properties, "mCurrentPotential", 1.5);
+        building(&mut persistent, CONSTRUCTOR, Some(2), properties);
+        let mut properties = Vec::new();
+        object(
+            &mut properties,
+            "mCurrentFuelClass",
+            "/Game/FactoryGame/Resource/RawResources/Coal/Desc_Coal.Desc_Coal_C",
+        );
+        building(&mut persistent, COAL, Some(1), properties);
+        building(&mut persistent, COAL, Some(1), Vec::new());
+        let mut properties = Vec::new();
+        object(
+            &mut properties,
+            "mCurrentRecipe",
+            "/Game/FactoryGame/Recipes/Constructor/Recipe_Unobtainium.Recipe_Unobtainium_C",
+        );
+        building(&mut persistent, CONSTRUCTOR, None, properties);
+        let mut properties = Vec::new();
+        iron_ingot(&mut properties);
+        building(&mut persistent, SMELTER, None, properties);
+        building(&mut persistent, MINER, Some(1), Vec::new());
+        building(&mut persistent, FOUNDATION, None, Vec::new());
+
+        let mut world = Vec::new();
+        // One partition grid with one cell
+        world.extend(1i32.to_le_bytes());
+        string(&mut world, "MainGrid");
+        world.extend(25_600i32.to_le_bytes());
+        world.extend(7u32.to_le_bytes());
+        world.extend(1i32.to_le_bytes());
+        string(&mut world, "MainGrid_L0_X0_Y0");
+        world.extend(9u32.to_le_bytes());
+        world.extend(1i32.to_le_bytes());
+        string(&mut world, "Sublevel");
+        level(&mut world, &sublevel);
+        level(&mut world, &persistent);
+        let mut body = (world.len() as i64).to_le_bytes().to_vec();
+        body.extend(world);
+
+        let mut out = header(header_version, save_version);
+        for part in body.chunks(body.len() / 2 + 1) {
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(part).unwrap();
+            chunk(
+                &mut out,
+                &encoder.finish().unwrap(),
+                part.len() as i64,
+                true,
+            );
+        }
+        out
+    }
+
+    #[test]
+    fn test_read_save_summary() {
+        for algorithm_byte in [false, true] {
+            let summary = GameSaveSummary::read(&save(algorithm_byte)).unwrap();
+            assert_eq!(summary.header.session_name, "Iron Valley");
+            assert_eq!(summary.header.build_version, 365_306);
+            assert_eq!(summary.header.play_duration_secs, 7_200);
+            assert_eq!(summary.header.saved_at, 1_700_000_000);
+            assert_eq!(summary.header.mod_metadata, None);
+            assert_eq!(summary.chunk_count, 2);
+            assert_eq!(summary.world_data_size, 131_584);
+        }
+    }
+
+    #[test]
+    fn test_rejects_invalid_files() {
+        assert!(GameSaveSummary::read(b"not a save").is_err());
+
+        let mut truncated = save(true);
+        truncated.truncate(truncated.len() - 2);
+        assert!(GameSaveSummary::read(&truncated).is_err());
+    }
+
+    #[test]
+    fn test_read_buildings_per_power_circuit() {
+        let plan = GameSavePlan::read(&world_save(14, 46)).unwrap();
+        assert_eq!(plan.save.header.save_name, "Iron Valley autosave");
+        assert_eq!(plan.save.header.session_name, "Iron Valley");
+        assert_eq!(plan.save.chunk_count, 2);
+
+        let names: Vec<&str> = plan
+            .factories
+            .iter()
+            .map(|factory| factory.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["Unpowered buildings", "Power circuit 1", "Power circuit 2"]
+        );
+        let [unpowered, circuit_1, circuit_2] = &plan.factories[..] else {
+            panic!("expected three factories");
+        };
+        assert_eq!(unpowered.power_grid, None);
+        assert_eq!(circuit_1.power_grid.as_deref(), Some("Power circuit 1"));
+
+        // Both levels' smelters share a line, grouped by clock speed
+        assert_eq!(circuit_1.production_lines.len(), 1);
+        let smelters = &circuit_1.production_lines[0];
+        assert_eq!(smelters.recipe, LineRecipe::Standard(Recipe::IronIngot));
+        let groups: Vec<(u32, f64)> = smelters
+            .machine_groups
+            .iter()
+            .map(|group| (group.number_of_machine, group.oc_value))
+            .collect();
+        assert_eq!(groups, vec![(2, 100.0), (1, 50.0)]);
+
+        let [coal] = &circuit_1.power_generators[..] else {
+            panic!("expected one coal generator");
+        };
+        assert_eq!(coal.generator_type, GeneratorType::Coal);
+        assert_eq!(coal.fuel_type, Item::Coal);
+        assert_eq!(coal.groups[0].number_of_generators, 1);
+
+        let plates = &circuit_2.production_lines[0];
+        assert_eq!(plates.recipe, LineRecipe::Standard(Recipe::IronPlate));
+        assert_eq!(plates.machine_groups[0].oc_value, 150.0);
+        assert_eq!(plates.machine_groups[0].somersloop, 1);
+
+        assert_eq!(unpowered.production_lines.len(), 1);
+        assert_eq!(
+            plan.warnings,
+            vec![
+                "1 buildings use unknown recipe 'Recipe_Unobtainium_C'; ignored".to_string(),
+                "1 generators have no fuel loaded; ignored".to_string(),
+                "1 miners, pumps and extractors aren't imported; node purity isn't in the \
+                 save, add them as raw inputs"
+                    .to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_game_save_creates_factories_on_grids() {
+        let mut engine = SatisflowEngine::new();
+        let plan = GameSavePlan::read(&world_save(14, 46)).unwrap();
+
+        let preview = import_game_save(&mut engine, plan.clone(), true).unwrap();
+        assert!(engine.get_all_factories().is_empty());
+        assert_eq!(preview.factories.len(), 3);
+        assert_eq!(preview.factories[1].power_generators, 1);
+        assert_eq!(preview.factories[1].power_generation, 75.0);
+
+        let report = import_game_save(&mut engine, plan, false).unwrap();
+        assert_eq!(engine.get_all_factories().len(), 3);
+        assert_eq!(engine.get_all_power_grids().len(), 2);
+        let circuit_1 = engine
+            .get_factory(report.factories[1].factory_id.unwrap())
+            .unwrap();
+        let grid = engine
+            .get_power_grid(circuit_1.power_grid.unwrap())
+            .unwrap();
+        assert_eq!(grid.name, "Power circuit 1");
+        assert_eq!(circuit_1.total_power_generation(), 75.0);
+        let unpowered = engine
+            .get_factory(report.factories[0].factory_id.unwrap())
+            .unwrap();
+        assert_eq!(unpowered.power_grid, None);
+    }
+
+    #[test]
+    fn test_rejects_old_and_corrupt_world_data() {
+        let error = GameSavePlan::read(&world_save(13, 42)).unwrap_err();
+        assert!(error.to_string().contains("before Satisfactory 1.0"));
+
+        let mut corrupt = header(14, 46);
+        chunk(&mut corrupt, &[0xAB; 10], 512, true);
+        let error = GameSavePlan::read(&corrupt).unwrap_err();
+        assert!(error.to_string().contains("Corrupt world data"));
+
+        let mut save = world_save(14, 46);
+        save.truncate(save.len() - 2);
+        assert!(GameSavePlan::read(&save).is_err());
+    }
+}
@@ -0,0 +1,672 @@
+//! Import plans made in other Satisfactory planners
+//!
+//! Two JSON formats are understood:
+//!
+//! - `satisfactory_tools`: the production tab export of satisfactorytools.com.
+//!   It stores production targets, not machines, so the chain is expanded here
+//!   with the standard recipe of each product, falling back to an allowed
+//!   alternate. Expansion stops at extractable resources and declared inputs.
+//! - `satisfactory_calculator`: a building list, either an array or
+//!   `{"buildings": [...]}`, where each entry has a `recipe`, an optional
+//!   `clockSpeed` in percent, `count` and `factory` name.
+//!
+//! Items and recipes may be given by game class name (`Desc_IronPlate_C`,
+//! `Recipe_Alternate_PureIronIngot_C`) or display name. Anything that can't be
+//! mapped is reported as a warning instead of being dropped silently.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::{
+    all_items, all_recipes,
+    game_data::MachineType,
+    item_by_name, item_name,
+    power_generator::PowerGenerator,
+    production_line::{MachineGroup, ProductionLine, ProductionLineRecipe},
+    raw_input::is_extractable,
+    recipe_by_name, recipe_info, recipe_name, FactoryId, Item, LineRecipe, ProductionLineId,
+    Recipe,
+};
+use crate::SatisflowEngine;
+
+/// Planner a plan was exported from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExternalFormat {
+    SatisfactoryTools,
+    SatisfactoryCalculator,
+}
+
+/// Factory described by an external plan or a game save
+#[derive(Debug, Clone, Default)]
+pub struct ImportedFactory {
+    pub name: String,
+    pub production_lines: Vec<ProductionLineRecipe>,
+    pub power_generators: Vec<PowerGenerator>,
+    /// Name of a power grid to create and assign the factory to
+    pub power_grid: Option<String>,
+}
+
+/// External plan translated to factories and production lines
+#[derive(Debug, Clone)]
+pub struct ExternalPlan {
+    pub format: ExternalFormat,
+    pub factories: Vec<ImportedFactory>,
+    /// Entries that couldn't be mapped or were approximated
+    pub warnings: Vec<String>,
+}
+
+/// Deepest production chain followed before giving up
+const MAX_CHAIN_DEPTH: usize = 32;
+
+const DEFAULT_FACTORY_NAME: &str = "Imported plan";
+
+impl ExternalPlan {
+    /// Translate an exported plan
+    ///
+    /// # Returns
+    ///
+    /// Result containing the plan, or an error if the JSON doesn't have the
+    /// shape of the format
+    pub fn parse(format: ExternalFormat, data: &Value) -> Result<Self, Box<dyn std::error::Error>> {
+        match format {
+            ExternalFormat::SatisfactoryTools => from_satisfactory_tools(data),
+            ExternalFormat::SatisfactoryCalculator => from_satisfactory_calculator(data),
+        }
+    }
+}
+
+fn from_satisfactory_tools(data: &Value) -> Result<ExternalPlan, Box<dyn std::error::Error>> {
+    let request = data
+        .get("request")
+        .ok_or("Missing 'request' object in Satisfactory Tools plan")?;
+    let production = request
+        .get("production")
+        .and_then(Value::as_array)
+        .ok_or("Missing 'request.production' array in Satisfactory Tools plan")?;
+    let mut warnings = Vec::new();
+
+    let allowed_alternates = recipe_list(request.get("allowedAlternateRecipes"), &mut warnings);
+    let blocked = recipe_list(request.get("blockedRecipes"), &mut warnings);
+    let mut supplied: HashMap<Item, f64> = HashMap::new();
+    for input in request
+        .get("input")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let Some(class_name) = input.get("item").and_then(Value::as_str) else {
+            continue;
+        };
+        match item_by_class_name(class_name) {
+            Some(item) => {
+                let amount = input.get("amount").and_then(Value::as_f64).unwrap_or(0.0);
+                *supplied.entry(item).or_insert(0.0) += amount;
+            }
+            None => warnings.push(format!("Unknown input item '{}', ignored", class_name)),
+        }
+    }
+
+    let mut expansion = Expansion {
+        allowed_alternates,
+        blocked,
+        supplied,
+        recipes: Vec::new(),
+        warnings,
+    };
+    for target in production {
+        let Some(class_name) = target.get("item").and_then(Value::as_str) else {
+            continue;
+        };
+        let Some(item) = item_by_class_name(class_name) else {
+            expansion
+                .warnings
+                .push(format!("Unknown product '{}', ignored", class_name));
+            continue;
+        };
+        if target.get("type").and_then(Value::as_str) == Some("max") {
+            expansion.warnings.push(format!(
+                "Target '{}' maximizes production, which needs the optimizer; ignored",
+                item_name(item)
+            ));
+            continue;
+        }
+        let amount = target.get("amount").and_then(Value::as_f64).unwrap_or(0.0);
+        if amount > 0.0 {
+            expansion.expand(item, amount, &mut Vec::new());
+        }
+    }
+
+    let name = data
+        .pointer("/metadata/name")
+        .and_then(Value::as_str)
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or(DEFAULT_FACTORY_NAME)
+        .to_string();
+    let production_lines = expansion
+        .recipes
+        .iter()
+        .map(|(recipe, rate)| {
+            let per_machine = recipe_info(*recipe).outputs[0].1;
+            recipe_line(
+                *recipe,
+                MachineGroup::new(1, 100.0, 0).scaled(rate / per_machine),
+            )
+        })
+        .collect();
+
+    Ok(ExternalPlan {
+        format: ExternalFormat::SatisfactoryTools,
+        factories: vec![ImportedFactory {
+            name,
+            production_lines,
+            ..Default::default()
+        }],
+        warnings: expansion.warnings,
+    })
+}
+
+/// Recursive demand expansion for Satisfactory Tools targets
+struct Expansion {
+    allowed_alternates: HashSet<Recipe>,
+    blocked: HashSet<Recipe>,
+    /// Remaining rate of each declared input
+    supplied: HashMap<Item, f64>,
+    /// Rate of the main product needed from each recipe, in order of first use
+    recipes: Vec<(Recipe, f64)>,
+    warnings: Vec<String>,
+}
+
+impl Expansion {
+    fn expand(&mut self, item: Item, mut rate: f64, chain: &mut Vec<Item>) {
+        if let Some(available) = self.supplied.get_mut(&item) {
+            let used = available.min(rate);
+            *available -= used;
+            rate -= used;
+        }
+        if rate <= 1e-4 || is_extractable(item) {
+            return;
+        }
+        if chain.contains(&item) || chain.len() >= MAX_CHAIN_DEPTH {
+            self.warn(format!(
+                "Production chain of {} loops back on itself; it must be imported",
+                item_name(item)
+            ));
+            return;
+        }
+        let Some(recipe) = self.recipe_for(item) else {
+            self.warn(format!(
+                "No allowed recipe produces {}; it must be imported",
+                item_name(item)
+            ));
+            return;
+        };
+
+        let info = recipe_info(recipe);
+        let machines = rate / info.outputs[0].1;
+        match self
+            .recipes
+            .iter_mut()
+            .find(|(existing, _)| *existing == recipe)
+        {
+            Some((_, total)) => *total += rate,
+            None => self.recipes.push((recipe, rate)),
+        }
+        chain.push(item);
+        for (input, quantity) in info.inputs {
+            self.expand(*input, quantity * machines, chain);
+        }
+        chain.pop();
+    }
+
+    /// Record a warning once, however many branches of the chain hit it
+    fn warn(&mut self, warning: String) {
+        if !self.warnings.contains(&warning) {
+            self.warnings.push(warning);
+        }
+    }
+
+    /// Standard recipe whose main product is `item`, else an allowed alternate
+    fn recipe_for(&self, item: Item) -> Option<Recipe> {
+        let mut candidates = all_recipes().iter().filter(|info| {
+            info.machine != MachineType::Manual
+                && info.outputs.first().map(|(output, _)| *output) == Some(item)
+                && !self.blocked.contains(&info.recipe)
+        });
+        let standard = candidates.clone().find(|info| !info.is_alternate());
+        standard
+            .or_else(|| candidates.find(|info| self.allowed_alternates.contains(&info.recipe)))
+            .map(|info| info.recipe)
+    }
+}
+
+fn recipe_list(value: Option<&Value>, warnings: &mut Vec<String>) -> HashSet<Recipe> {
+    let mut recipes = HashSet::new();
+    for class_name in value
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+    {
+        match recipe_by_class_name(class_name) {
+            Some(recipe) => {
+                recipes.insert(recipe);
+            }
+            None => warnings.push(format!("Unknown recipe '{}', ignored", class_name)),
+        }
+    }
+    recipes
+}
+
+fn from_satisfactory_calculator(data: &Value) -> Result<ExternalPlan, Box<dyn std::error::Error>> {
+    let buildings = data
+        .as_array()
+        .or_else(|| data.get("buildings").and_then(Value::as_array))
+        .ok_or("Expected a building list or a 'buildings' array in Satisfactory Calculator plan")?;
+    let mut warnings = Vec::new();
+
+    // One factory per name and one line per recipe, in order of first appearance
+    let mut factories: Vec<ImportedFactory> = Vec::new();
+    for (index, building) in buildings.iter().enumerate() {
+        let Some(class_name) = building.get("recipe").and_then(Value::as_str) else {
+            warnings.push(format!("Building {} has no recipe, ignored", index));
+            continue;
+        };
+        let Some(recipe) = recipe_by_class_name(class_name) else {
+            warnings.push(format!(
+                "Building {} uses unknown recipe '{}', ignored",
+                index, class_name
+            ));
+            continue;
+        };
+        let clock_speed = building
+            .get("clockSpeed")
+            .and_then(Value::as_f64)
+            .unwrap_or(100.0);
+        if !(0.0..=250.0).contains(&clock_speed) || clock_speed == 0.0 {
+            warnings.push(format!(
+                "Building {} has clock speed {}%, outside 0-250%; ignored",
+                index, clock_speed
+            ));
+            continue;
+        }
+        let count = building.get("count").and_then(Value::as_u64).unwrap_or(1) as u32;
+        if count == 0 {
+            continue;
+        }
+        let factory_name = building
+            .get("factory")
+            .and_then(Value::as_str)
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or(DEFAULT_FACTORY_NAME);
+
+        let position = match factories
+            .iter()
+            .position(|factory| factory.name == factory_name)
+        {
+            Some(position) => position,
+            None => {
+                factories.push(ImportedFactory {
+                    name: factory_name.to_string(),
+                    ..Default::default()
+                });
+                factories.len() - 1
+            }
+        };
+        let lines = &mut factories[position].production_lines;
+        let position = match lines
+            .iter()
+            .position(|line| line.recipe == LineRecipe::Standard(recipe))
+        {
+            Some(position) => position,
+            None => {
+                lines.push(recipe_line(recipe, Vec::new()));
+                lines.len() - 1
+            }
+        };
+        let groups = &mut lines[position].machine_groups;
+        match groups
+            .iter_mut()
+            .find(|group| (group.oc_value - clock_speed).abs() < 1e-3)
+        {
+            Some(group) => group.number_of_machine += count,
+            None => groups.push(MachineGroup::new(count, clock_speed, 0)),
+        }
+    }
+
+    Ok(ExternalPlan {
+        format: ExternalFormat::SatisfactoryCalculator,
+        factories,
+        warnings,
+    })
+}
+
+pub(crate) fn recipe_line(recipe: Recipe, groups: Vec<MachineGroup>) -> ProductionLineRecipe {
+    let mut line = ProductionLineRecipe::new(
+        ProductionLineId::new_v4(),
+        recipe_name(recipe).to_string(),
+        None,
+        recipe,
+    );
+    line.machine_groups = groups;
+    line
+}
+
+/// Reduce a class or display name to lowercase letters and digits
+fn normalize(name: &str) -> String {
+    let name = name.trim();
+    let name = ["Desc_", "Recipe_", "Build_", "BP_"]
+        .iter()
+        .find_map(|prefix| name.strip_prefix(prefix))
+        .unwrap_or(name);
+    let name = name.strip_suffix("_C").unwrap_or(name);
+    name.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Game class names that don't match the item's display name
+const ITEM_CLASS_ALIASES: &[(&str, Item)] = &[
+    ("oreiron", Item::IronOre),
+    ("orecopper", Item::CopperOre),
+    ("oregold", Item::CateriumOre),
+    ("orebauxite", Item::Bauxite),
+    ("oreuranium", Item::Uranium),
+    ("stone", Item::Limestone),
+    ("liquidoil", Item::CrudeOil),
+    ("ironingot", Item::IronIngot),
+    ("ingotiron", Item::IronIngot),
+    ("ingotcopper", Item::CopperIngot),
+    ("ingotsteel", Item::SteelIngot),
+    ("goldingot", Item::CateriumIngot),
+    ("genericbiomass", Item::Biomass),
+    ("liquidfuel", Item::Fuel),
+    ("liquidturbofuel", Item::Turbofuel),
+    ("nuclearfuelrod", Item::UraniumFuelRod),
+];
+
+/// Find an item by game class name or display name
+pub fn item_by_class_name(name: &str) -> Option<Item> {
+    if let Some(item) = item_by_name(name) {
+        return Some(item);
+    }
+    let key = normalize(name);
+    if let Some((_, item)) = ITEM_CLASS_ALIASES.iter().find(|(alias, _)| *alias == key) {
+        return Some(*item);
+    }
+    all_items()
+        .iter()
+        .find(|(item, display)| {
+            normalize(display) == key || format!("{:?}", item).to_ascii_lowercase() == key
+        })
+        .map(|(item, _)| *item)
+}
+
+/// Find a recipe by game class name or display name
+pub fn recipe_by_class_name(name: &str) -> Option<Recipe> {
+    if let Some(recipe) = recipe_by_name(name) {
+        return Some(recipe);
+    }
+    let mut key = normalize(name);
+    // Ingot recipes are named after the material first, e.g. Recipe_IngotIron_C
+    if let Some(material) = key.strip_prefix("ingot") {
+        key = format!("{}ingot", material);
+    }
+    all_recipes()
+        .iter()
+        .find(|info| {
+            normalize(info.name) == key || format!("{:?}", info.recipe).to_ascii_lowercase() == key
+        })
+        .map(|info| info.recipe)
+}
+
+/// Summary of one imported production line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportedLineSummary {
+    pub name: String,
+    pub recipe: String,
+    pub machines: u32,
+    pub outputs: Vec<(Item, f64)>,
+    pub power_consumption: f64,
+}
+
+/// Summary of one imported factory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportedFactorySummary {
+    /// ID of the created factory, `None` on a dry run
+    pub factory_id: Option<FactoryId>,
+    pub name: String,
+    pub production_lines: Vec<ImportedLineSummary>,
+    /// Number of power generators, across all generator groups
+    #[serde(default)]
+    pub power_generators: u32,
+    #[serde(default)]
+    pub power_generation: f64,
+    #[serde(default)]
+    pub power_grid: Option<String>,
+}
+
+/// Outcome of [`import_plan`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub format: ExternalFormat,
+    /// When set, nothing was created and `factories` is a preview
+    pub dry_run: bool,
+    pub factories: Vec<ImportedFactorySummary>,
+    pub warnings: Vec<String>,
+}
+
+/// Create one factory per factory of the plan, unless `dry_run` is set
+///
+/// # Returns
+///
+/// Result containing the report, or an error if a generator of the plan is
+/// invalid; factories created before it are kept
+pub fn import_plan(
+    engine: &mut SatisflowEngine,
+    plan: ExternalPlan,
+    dry_run: bool,
+) -> Result<ImportReport, Box<dyn std::error::Error>> {
+    Ok(ImportReport {
+        format: plan.format,
+        dry_run,
+        factories: create_factories(engine, plan.factories, dry_run)?,
+        warnings: plan.warnings,
+    })
+}
+
+/// Create the imported factories, with their power grids, unless `dry_run`
+/// is set
+pub(crate) fn create_factories(
+    engine: &mut SatisflowEngine,
+    imported: Vec<ImportedFactory>,
+    dry_run: bool,
+) -> Result<Vec<ImportedFactorySummary>, Box<dyn std::error::Error>> {
+    let mut factories = Vec::new();
+    for imported in imported {
+        let production_lines = imported
+            .production_lines
+            .iter()
+            .map(|line| {
+                let recipe = line.recipe.name().to_string();
+                let line = ProductionLine::ProductionLineRecipe(line.clone());
+                ImportedLineSummary {
+                    name: line.name().to_string(),
+                    recipe,
+                    machines: line.total_machines(),
+                    outputs: line.output_rate(),
+                    power_consumption: line.total_power_consumption(),
+                }
+            })
+            .collect();
+        let power_generators = imported
+            .power_generators
+            .iter()
+            .flat_map(|generator| &generator.groups)
+            .map(|group| group.number_of_generators)
+            .sum();
+        let power_generation = imported
+            .power_generators
+            .iter()
+            .map(PowerGenerator::total_power_generation)
+            .sum();
+
+        let factory_id = if dry_run {
+            None
+        } else {
+            let id = engine.create_factory(imported.name.clone(), None);
+            let factory = engine
+                .get_factory_mut(id)
+                .ok_or_else(|| format!("Factory with id {} not found", id))?;
+            for line in imported.production_lines {
+                factory.add_production_line(ProductionLine::ProductionLineRecipe(line));
+            }
+            for generator in imported.power_generators {
+                factory.add_power_generator(generator)?;
+            }
+            if let Some(grid) = &imported.power_grid {
+                let grid_id = engine.create_power_grid(grid.clone(), None);
+                engine.assign_factory_to_power_grid(id, Some(grid_id))?;
+            }
+            Some(id)
+        };
+        factories.push(ImportedFactorySummary {
+            factory_id,
+            name: imported.name,
+            production_lines,
+            power_generators,
+            power_generation,
+            power_grid: imported.power_grid,
+        });
+    }
+    Ok(factories)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_class_names() {
+        assert_eq!(
+            item_by_class_name("Desc_IronPlate_C"),
+            Some(Item::IronPlate)
+        );
+        assert_eq!(
+            item_by_class_name("Desc_OreGold_C"),
+            Some(Item::CateriumOre)
+        );
+        assert_eq!(item_by_class_name("Iron Plate"), Some(Item::IronPlate));
+        assert_eq!(
+            item_by_class_name("Desc_NuclearFuelRod_C"),
+            Some(Item::UraniumFuelRod)
+        );
+        assert_eq!(item_by_class_name("Desc_LiquidFuel_C"), Some(Item::Fuel));
+        assert_eq!(item_by_class_name("Desc_Nonsense_C"), None);
+
+        assert_eq!(
+            recipe_by_class_name("Recipe_IngotIron_C"),
+            Some(Recipe::IronIngot)
+        );
+        assert_eq!(
+            recipe_by_class_name("Recipe_Alternate_PureIronIngot_C"),
+            Some(Recipe::AlternatePureIronIngot)
+        );
+        assert_eq!(recipe_by_class_name("Iron Plate"), Some(Recipe::IronPlate));
+    }
+
+    #[test]
+    fn test_satisfactory_tools_expands_chain() {
+        let data = json!({
+            "metadata": { "name": "Plates" },
+            "request": {
+                "production": [
+                    { "item": "Desc_IronPlate_C", "type": "perMinute", "amount": 60 },
+                    { "item": "Desc_Rotor_C", "type": "max", "amount": 1 },
+                    { "item": null, "type": "perMinute", "amount": 10 }
+                ],
+                "input": [],
+                "allowedAlternateRecipes": ["Recipe_Alternate_Nonsense_C"],
+                "blockedRecipes": []
+            }
+        });
+
+        let plan = ExternalPlan::parse(ExternalFormat::SatisfactoryTools, &data).unwrap();
+        assert_eq!(plan.factories.len(), 1);
+        let factory = &plan.factories[0];
+        assert_eq!(factory.name, "Plates");
+
+        let lines: Vec<(&str, u32)> = factory
+            .production_lines
+            .iter()
+            .map(|line| {
+                let machines = line
+                    .machine_groups
+                    .iter()
+                    .map(|group| group.number_of_machine);
+                (line.name.as_str(), machines.sum())
+            })
+            .collect();
+        assert_eq!(lines, vec![("Iron Plate", 3), ("Iron Ingot", 3)]);
+        assert_eq!(plan.warnings.len(), 2);
+        assert!(plan.warnings[0].contains("Recipe_Alternate_Nonsense_C"));
+        assert!(plan.warnings[1].contains("Rotor"));
+    }
+
+    #[test]
+    fn test_satisfactory_tools_uses_inputs() {
+        let data = json!({
+            "request": {
+                "production": [{ "item": "Desc_IronPlate_C", "type": "perMinute", "amount": 30 }],
+                "input": [{ "item": "Desc_IronIngot_C", "amount": 30 }]
+            }
+        });
+
+        let plan = ExternalPlan::parse(ExternalFormat::SatisfactoryTools, &data).unwrap();
+        let factory = &plan.factories[0];
+        assert_eq!(factory.name, "Imported plan");
+        assert_eq!(factory.production_lines.len(), 2);
+        // 45 ingots needed, 30 supplied: one smelter at 50%
+        let smelter = &factory.production_lines[1];
+        assert_eq!(smelter.recipe, LineRecipe::Standard(Recipe::IronIngot));
+        assert_eq!(smelter.machine_groups.len(), 1);
+        assert!((smelter.machine_groups[0].oc_value - 50.0).abs() < 1e-3);
+
+        assert!(ExternalPlan::parse(ExternalFormat::SatisfactoryTools, &json!({})).is_err());
+    }
+
+    #[test]
+    fn test_satisfactory_calculator_buildings() {
+        let data = json!({
+            "buildings": [
+                { "className": "Build_SmelterMk1_C", "recipe": "Recipe_IngotIron_C", "clockSpeed": 100, "factory": "Smelting" },
+                { "recipe": "Recipe_IngotIron_C", "clockSpeed": 100, "count": 2, "factory": "Smelting" },
+                { "recipe": "Recipe_IngotIron_C", "clockSpeed": 50, "factory": "Smelting" },
+                { "recipe": "Recipe_IronPlate_C" },
+                { "recipe": "Recipe_Nonsense_C" },
+                { "recipe": "Recipe_IronPlate_C", "clockSpeed": 300 }
+            ]
+        });
+
+        let mut engine = SatisflowEngine::new();
+        let plan = ExternalPlan::parse(ExternalFormat::SatisfactoryCalculator, &data).unwrap();
+        assert_eq!(plan.warnings.len(), 2);
+
+        let report = import_plan(&mut engine, plan.clone(), true).unwrap();
+        assert!(engine.get_all_factories().is_empty());
+        assert_eq!(report.factories.len(), 2);
+        assert_eq!(report.factories[0].name, "Smelting");
+        assert_eq!(report.factories[0].production_lines[0].machines, 4);
+        assert_eq!(report.factories[1].name, "Imported plan");
+
+        let report = import_plan(&mut engine, plan, false).unwrap();
+        let smelting = report.factories[0].factory_id.unwrap();
+        let factory = engine.get_factory(smelting).unwrap();
+        assert_eq!(factory.production_lines.len(), 1);
+        assert_eq!(engine.get_all_factories().len(), 2);
+    }
+}
@@ -1,31 +1,190 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
+use std::sync::Arc;
 
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
-use uuid::Uuid;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
+use events::Observers;
+
+pub mod audit;
+pub mod compression;
+pub mod diff;
+pub mod events;
 pub mod examples;
+pub mod export;
+pub mod format;
+pub mod game_save;
+pub mod import;
+pub mod load;
 pub mod models;
+pub mod rate;
+pub mod repair;
+pub mod report;
+pub mod season;
 pub mod version;
 
 use models::{
+    balance_suggestions::BalanceSuggestion,
+    custom_recipe::{CustomRecipe, LineRecipe},
+    dependency::{FactoryDependencies, FactoryDependency},
+    diagnostics::{self, Diagnostic, DiagnosticKind},
     factory::Factory,
-    logistics::{LogisticsFlux, TransportType},
-    production_line::{ProductionLine, ProductionLineBlueprint},
-    FactoryId, Item, LogisticsId, PowerStats, ProductionLineId,
+    factory_delete::{DownstreamDeficit, FactoryDeletePreview, RemovedLogisticsLine},
+    factory_merge::{FactoryMergePreview, MergedItemBalance},
+    factory_split::{FactorySplitResult, FactorySplitSelection},
+    factory_task::FactoryTask,
+    factory_template::FactoryTemplate,
+    ficsmas::{self, FICSMAS_ITEMS},
+    flows::FlowNetwork,
+    game_balance::GameBalance,
+    game_data::MachineType,
+    game_data_set::GameDataSet,
+    goals::{Goal, GoalProgress},
+    internal_transfer::InternalTransfer,
+    inventory::ResourceInventory,
+    item_balance::ItemBalanceReport,
+    logistics::{
+        Bus, Conveyor, ConveyorSpeed, LogisticsFlux, Pipeline, PipelineCapacity, Transport,
+        TransportType,
+    },
+    logistics_lines::{FactoryLogistics, LogisticsLines},
+    map::{Position, RouteEstimate},
+    metrics::MetricsPoint,
+    nuclear_waste::NuclearWasteReport,
+    pins::PinnedEntities,
+    power_generator::PowerGenerator,
+    power_grid::{GridPowerStats, PowerGrid},
+    power_storage::{PowerStorage, StorageStatus},
+    production_line::{ProductionLine, ProductionLineBlueprint, ProductionLineRecipe},
+    raw_input::ExtractorType,
+    recipes::{recipe_by_name, Recipe},
+    report_card::ReportCard,
+    resource_nodes::{NodeCounts, ResourceNodeRegistry, ResourceNodeReport},
+    search::{self, SearchHit},
+    tag_groups::TagGroupStats,
+    template_propagation::{
+        updated_instance, FactoryPropagation, InstanceUpdate, TemplateInstance, TemplatePropagation,
+    },
+    template_revision::{TemplateRevision, TemplateVersion},
+    tier_progress::TierProgress,
+    timestamps::{ChangedEntity, RecentChange, Timestamps},
+    train_schedule::{TrainSchedule, TrainThroughput},
+    transport_details::TransportDetails,
+    validation::ValidationReport,
+    CustomRecipeId, FactoryId, FactoryTaskId, FactoryTemplateId, GoalId, InternalTransferId, Item,
+    LogisticsId, PowerGridId, PowerStats, ProductionLineId, TransportKind, TransportNaming,
+    UnknownFields,
 };
 
-pub use version::{SaveVersion, VersionError};
+pub use audit::{AuditMismatch, AuditReport, ReferenceDataset};
+pub use diff::SaveDiff;
+pub use events::{EngineEvent, SubscriptionId};
+pub use export::CsvSection;
+pub use format::{NumberFormat, RateUnit};
+pub use game_save::{GameSaveImportReport, GameSavePlan, GameSaveSummary, SaveHeader};
+pub use import::{ExternalFormat, ExternalPlan, ImportReport};
+pub use load::LoadIssue;
+pub use repair::{RegeneratedId, RepairReport};
+pub use season::{SeasonOverview, SeasonSnapshot};
+pub use version::{
+    DowngradeReport, LostFeature, SaveFeature, SaveVersion, VersionError, SAVE_FEATURES,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SatisflowEngine {
     factories: HashMap<FactoryId, Factory>,
-    logistics_lines: HashMap<LogisticsId, LogisticsFlux>,
+    logistics_lines: LogisticsLines,
     #[serde(default)]
     blueprint_templates: HashMap<ProductionLineId, ProductionLineBlueprint>,
+    #[serde(default)]
+    factory_templates: HashMap<FactoryTemplateId, FactoryTemplate>,
+    #[serde(default)]
+    transport_naming: TransportNaming,
+    #[serde(default)]
+    resource_nodes: ResourceNodeRegistry,
+    #[serde(default)]
+    power_grids: HashMap<PowerGridId, PowerGrid>,
+    #[serde(default)]
+    pinned: PinnedEntities,
+    #[serde(default)]
+    goals: HashMap<GoalId, Goal>,
+    /// Progress towards each goal as of the last `update()`
+    #[serde(skip)]
+    goal_progress: HashMap<GoalId, GoalProgress>,
+    /// Unlocked HUB tiers and research; `None` until the player records it
+    #[serde(default)]
+    tier_progress: Option<TierProgress>,
+    #[serde(default)]
+    custom_recipes: HashMap<CustomRecipeId, CustomRecipe>,
+    /// Game data the plan is made against; saved as [`SaveFile::game_version`]
+    #[serde(skip, default = "GameDataSet::latest")]
+    game_data: &'static GameDataSet,
+    /// Whether the seasonal FICSMAS items and recipes are planned
+    #[serde(default)]
+    ficsmas: bool,
+    /// Game constants the plan is calculated with; only saved when they
+    /// differ from the game's
+    #[serde(default, skip_serializing_if = "GameBalance::is_vanilla")]
+    balance: GameBalance,
+    /// Bumped by every method that can change the state; not saved
+    #[serde(skip)]
+    revision: u64,
+    /// Called after every successful change; not saved nor cloned
+    #[serde(skip)]
+    observers: Observers,
+    /// Top-level fields from a newer save this engine doesn't know
+    #[serde(flatten)]
+    unknown_fields: UnknownFields,
 }
 
+// The server shares immutable versions of the engine across request handlers
+// behind an `Arc`, so the engine and everything it hands out must be
+// `Send + Sync`. Nothing in the engine uses interior mutability: `&self`
+// methods are safe for concurrent readers and all mutation goes through
+// `&mut self`, on the copy that becomes the next version. These assertions fail to compile if a
+// model ever picks up an `Rc`, `Cell` or `RefCell`.
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<SatisflowEngine>();
+    assert_send_sync::<SaveFile>();
+    assert_send_sync::<SaveFileRef<'static>>();
+    assert_send_sync::<SaveFileSummary>();
+    assert_send_sync::<VersionedExport>();
+    assert_send_sync::<Factory>();
+    assert_send_sync::<LogisticsFlux>();
+    assert_send_sync::<ProductionLine>();
+    assert_send_sync::<ProductionLineBlueprint>();
+    assert_send_sync::<models::PowerGenerator>();
+    assert_send_sync::<PowerGrid>();
+    assert_send_sync::<Goal>();
+    assert_send_sync::<GoalProgress>();
+    assert_send_sync::<TierProgress>();
+    assert_send_sync::<CustomRecipe>();
+    assert_send_sync::<PowerStats>();
+    assert_send_sync::<GridPowerStats>();
+    assert_send_sync::<TagGroupStats>();
+    assert_send_sync::<RouteEstimate>();
+    assert_send_sync::<StorageStatus>();
+    assert_send_sync::<ItemBalanceReport>();
+    assert_send_sync::<NuclearWasteReport>();
+    assert_send_sync::<ResourceInventory>();
+    assert_send_sync::<BalanceSuggestion>();
+    assert_send_sync::<ReportCard>();
+    assert_send_sync::<Diagnostic>();
+    assert_send_sync::<RecentChange>();
+    assert_send_sync::<ResourceNodeReport>();
+    assert_send_sync::<FactoryDependencies>();
+    assert_send_sync::<FactoryMergePreview>();
+    assert_send_sync::<FactorySplitResult>();
+    assert_send_sync::<DowngradeReport>();
+    assert_send_sync::<LoadedSave>();
+    assert_send_sync::<LoadIssue>();
+    assert_send_sync::<RepairReport>();
+    assert_send_sync::<VersionError>();
+};
+
 /// Wrapper struct for save files with versioning and metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SaveFile {
@@ -41,34 +200,287 @@ pub struct SaveFile {
     pub engine: SatisflowEngine,
 }
 
+/// Borrowed form of [`SaveFile`], used to write a save without cloning the engine
+///
+/// Serializes exactly like a [`SaveFile`] holding the same engine.
+#[derive(Debug, Clone, Serialize)]
+pub struct SaveFileRef<'a> {
+    pub version: String,
+    pub created_at: DateTime<Utc>,
+    pub last_modified: DateTime<Utc>,
+    pub game_version: Option<&'a str>,
+    pub engine: &'a SatisflowEngine,
+}
+
+impl<'a> SaveFileRef<'a> {
+    /// Wrap an engine for saving, like [`SaveFile::new`]
+    pub fn new(engine: &'a SatisflowEngine) -> Self {
+        let now = Utc::now();
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            created_at: now,
+            last_modified: now,
+            game_version: Some(engine.game_data.version),
+            engine,
+        }
+    }
+}
+
+/// Encoding of a save file
+///
+/// Every format holds the same versioned [`SaveFile`], so loading detects the
+/// format and applies the same version checks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SaveFormat {
+    /// Indented JSON, easy to read and diff
+    #[default]
+    JsonPretty,
+    /// JSON without whitespace
+    JsonCompact,
+    /// MessagePack, the most compact encoding
+    MessagePack,
+}
+
+/// Options controlling how the engine state is written to a save file
+#[derive(Debug, Clone, PartialEq)]
+pub struct SaveOptions {
+    /// Save format version to write; fields introduced after it are left out
+    pub target_version: SaveVersion,
+    pub format: SaveFormat,
+}
+
+impl Default for SaveOptions {
+    fn default() -> Self {
+        Self {
+            target_version: SaveVersion::current(),
+            format: SaveFormat::default(),
+        }
+    }
+}
+
+/// Options controlling how a save file is read
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoadOptions {
+    /// Reject unknown items, recipes and fields, and saves from a newer
+    /// version, instead of working around them
+    pub strict: bool,
+}
+
+/// A save read with [`SatisflowEngine::load_from_json_with_options`]
+#[derive(Debug, Clone)]
+pub struct LoadedSave {
+    pub engine: SatisflowEngine,
+    pub summary: SaveFileSummary,
+    /// What a lenient load worked around; always empty for a strict one
+    pub warnings: Vec<LoadIssue>,
+}
+
+/// A save exported for a specific version, with what was dropped to get there
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionedExport {
+    pub save_data: String,
+    pub report: DowngradeReport,
+}
+
+/// Factories combined by a merge, with their logistics lines split by whether
+/// they stay inside the merged selection
+struct MergePlan {
+    factory: Factory,
+    internal: Vec<LogisticsId>,
+    external: Vec<LogisticsId>,
+}
+
 impl Default for SatisflowEngine {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// The version of a save file, read before the rest to check it can be loaded
+#[derive(Deserialize)]
+struct SaveFileVersion {
+    version: Option<String>,
+}
+
+/// Encode a save as MessagePack
+///
+/// IDs and dates are written as the same strings as in JSON, so saves encoded
+/// from the engine and from a downgraded JSON tree read back alike.
+fn to_msgpack(value: &impl Serialize) -> Result<Vec<u8>, rmp_serde::encode::Error> {
+    let mut bytes = Vec::new();
+    value.serialize(
+        &mut rmp_serde::Serializer::new(&mut bytes)
+            .with_struct_map()
+            .with_human_readable(),
+    )?;
+    Ok(bytes)
+}
+
+/// Decode a save encoded by [`to_msgpack`]
+fn from_msgpack<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, rmp_serde::decode::Error> {
+    T::deserialize(&mut rmp_serde::Deserializer::new(bytes).with_human_readable())
+}
+
+/// Whether `bytes` look like a MessagePack map, the top level of a binary save
+fn is_msgpack_map(bytes: &[u8]) -> bool {
+    matches!(bytes.first(), Some(0x80..=0x8f | 0xde | 0xdf))
+}
+
+/// Add item rates into a running total
+fn add_items(total: &mut HashMap<Item, f64>, items: &HashMap<Item, f64>) {
+    for (item, qty) in items {
+        *total.entry(*item).or_insert(0.0) += qty;
+    }
+}
+
 impl SatisflowEngine {
     pub fn new() -> Self {
         Self {
             factories: HashMap::new(),
-            logistics_lines: HashMap::new(),
+            logistics_lines: LogisticsLines::new(),
             blueprint_templates: HashMap::new(),
+            factory_templates: HashMap::new(),
+            transport_naming: TransportNaming::default(),
+            resource_nodes: ResourceNodeRegistry::default(),
+            power_grids: HashMap::new(),
+            pinned: PinnedEntities::default(),
+            goals: HashMap::new(),
+            goal_progress: HashMap::new(),
+            tier_progress: None,
+            custom_recipes: HashMap::new(),
+            game_data: GameDataSet::latest(),
+            ficsmas: false,
+            balance: GameBalance::default(),
+            revision: 0,
+            observers: Observers::default(),
+            unknown_fields: UnknownFields::new(),
+        }
+    }
+
+    /// Revision of the state, for optimistic concurrency
+    ///
+    /// Every method taking `&mut self` other than [`update`](Self::update)
+    /// increases it, even when it then fails, so two equal revisions mean
+    /// nothing was changed in between. It starts at 0 and isn't saved.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Replace the whole state, e.g. with a loaded save, keeping the revision
+    /// increasing
+    pub fn replace(&mut self, other: SatisflowEngine) {
+        let revision = self.revision.max(other.revision) + 1;
+        let observers = std::mem::take(&mut self.observers);
+        *self = other;
+        self.revision = revision;
+        self.observers = observers;
+        self.emit(EngineEvent::StateReplaced);
+    }
+
+    /// Copy of the engine to build its next version on
+    ///
+    /// Unlike [`Clone`], the copy keeps the observers: it is meant to replace
+    /// this engine, not to be a sandbox forked from it. Its events are held
+    /// back until [`dispatch_pending_events`](Self::dispatch_pending_events),
+    /// so a copy dropped instead notifies nobody.
+    pub fn next_version(&self) -> Self {
+        Self {
+            observers: self.observers.share(),
+            ..self.clone()
         }
     }
 
+    /// Notify the observers of the changes made since
+    /// [`next_version`](Self::next_version) made this copy, once it replaces
+    /// the engine it was copied from
+    pub fn dispatch_pending_events(&mut self) {
+        self.observers.dispatch_pending();
+    }
+
+    /// Call `observer` after every successful change to the engine
+    ///
+    /// Observers aren't saved, and copies of the engine start without any,
+    /// except the ones made by [`next_version`](Self::next_version).
+    ///
+    /// # Returns
+    ///
+    /// The handle to cancel the subscription with
+    pub fn subscribe(
+        &mut self,
+        observer: impl Fn(&EngineEvent) + Send + Sync + 'static,
+    ) -> SubscriptionId {
+        self.observers.subscribe(Arc::new(observer))
+    }
+
+    /// Stop calling an observer
+    ///
+    /// # Returns
+    ///
+    /// Whether the subscription existed
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        self.observers.unsubscribe(id)
+    }
+
+    fn emit(&self, event: EngineEvent) {
+        self.observers.emit(event);
+    }
+
     pub fn create_factory(&mut self, name: String, description: Option<String>) -> FactoryId {
-        let id = Uuid::new_v4();
-        let factory = Factory::new(id, name, description);
+        self.revision += 1;
+        let id = FactoryId::new_v4();
+        let mut factory = Factory::new(id, name, description);
+        factory.set_balance(self.balance.clone());
         self.factories.insert(id, factory);
+        self.emit(EngineEvent::FactoryCreated(id));
         id
     }
 
+    /// Add a factory made with [`FactoryBuilder`](models::factory::FactoryBuilder)
+    ///
+    /// # Arguments
+    ///
+    /// * `factory` - The factory to add
+    ///
+    /// # Returns
+    ///
+    /// The factory's ID, or an error if its ID is taken or its power grid does
+    /// not exist
+    pub fn add_factory(
+        &mut self,
+        mut factory: Factory,
+    ) -> Result<FactoryId, Box<dyn std::error::Error>> {
+        self.revision += 1;
+        if self.factories.contains_key(&factory.id) {
+            return Err(format!("Factory with id {} already exists", factory.id).into());
+        }
+        if let Some(grid_id) = factory.power_grid {
+            if !self.power_grids.contains_key(&grid_id) {
+                return Err(format!("Power grid with id {} does not exist", grid_id).into());
+            }
+        }
+        let id = factory.id;
+        factory.set_balance(self.balance.clone());
+        self.factories.insert(id, factory);
+        self.emit(EngineEvent::FactoryCreated(id));
+        Ok(id)
+    }
+
     pub fn get_factory(&self, id: FactoryId) -> Option<&Factory> {
         self.factories.get(&id)
     }
 
+    /// Get a mutable reference to a factory, marking it as modified
+    ///
+    /// Observers are told the factory was updated before the caller changes it.
     pub fn get_factory_mut(&mut self, id: FactoryId) -> Option<&mut Factory> {
-        self.factories.get_mut(&id)
+        self.revision += 1;
+        if self.factories.contains_key(&id) {
+            self.emit(EngineEvent::FactoryUpdated(id));
+        }
+        let factory = self.factories.get_mut(&id)?;
+        factory.touch();
+        Some(factory)
     }
 
     pub fn create_logistics_line(
@@ -76,26 +488,25 @@ impl SatisflowEngine {
         from: FactoryId,
         to: FactoryId,
         transport_type: TransportType,
-        transport_detail: String,
+        transport_details: impl Into<TransportDetails>,
     ) -> Result<LogisticsId, Box<dyn std::error::Error>> {
-        let id = Uuid::new_v4();
+        self.revision += 1;
+        let transport_details = transport_details.into();
+        self.check_logistics_line(from, to, &transport_type, &transport_details)
+            .into_result()?;
+        let id = LogisticsId::new_v4();
         let line = LogisticsFlux {
             id,
             from_factory: from,
             to_factory: to,
             transport_type,
-            transport_details: transport_detail,
+            transport_details,
+            timestamps: Timestamps::now(),
+            unknown_fields: UnknownFields::new(),
         };
 
-        //check that from and to factories exist
-        if !self.factories.contains_key(&from) {
-            return Err(format!("Factory with id {} does not exist", from).into());
-        }
-        if !self.factories.contains_key(&to) {
-            return Err(format!("Factory with id {} does not exist", to).into());
-        }
-
         self.logistics_lines.insert(id, line);
+        self.emit(EngineEvent::LogisticsCreated(id));
         Ok(id)
     }
 
@@ -105,25 +516,24 @@ impl SatisflowEngine {
         from: FactoryId,
         to: FactoryId,
         transport_type: TransportType,
-        transport_detail: String,
+        transport_details: impl Into<TransportDetails>,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        if !self.factories.contains_key(&from) {
-            return Err(format!("Factory with id {} does not exist", from).into());
-        }
-        if !self.factories.contains_key(&to) {
-            return Err(format!("Factory with id {} does not exist", to).into());
-        }
+        self.revision += 1;
+        let transport_details = transport_details.into();
+        self.check_logistics_line(from, to, &transport_type, &transport_details)
+            .into_result()?;
 
-        let logistics = self
-            .logistics_lines
-            .get_mut(&id)
+        self.logistics_lines
+            .update(&id, |logistics| {
+                logistics.from_factory = from;
+                logistics.to_factory = to;
+                logistics.transport_type = transport_type;
+                logistics.transport_details = transport_details;
+                logistics.timestamps.touch();
+            })
             .ok_or_else(|| format!("Logistics line with id {} not found", id))?;
 
-        logistics.from_factory = from;
-        logistics.to_factory = to;
-        logistics.transport_type = transport_type;
-        logistics.transport_details = transport_detail;
-
+        self.emit(EngineEvent::LogisticsUpdated(id));
         Ok(())
     }
 
@@ -131,637 +541,5616 @@ impl SatisflowEngine {
         self.logistics_lines.get(&id)
     }
 
-    pub fn update(&mut self) -> HashMap<Item, f32> {
-        let mut global_items = HashMap::new();
-        self.factories.iter_mut().for_each(|(_id, factory)| {
-            // Update each factory
-            factory.calculate_item(&self.logistics_lines);
-            // Aggregate items
-            factory.items.iter().for_each(|(item, qty)| {
-                *global_items.entry(*item).or_insert(0.0) += qty;
-            });
-        });
-        global_items
+    /// Reverse the direction of a logistics line by swapping its source and destination
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the logistics line to reverse
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or an error if the line doesn't exist
+    pub fn reverse_logistics_line(
+        &mut self,
+        id: LogisticsId,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.revision += 1;
+        self.logistics_lines
+            .update(&id, |logistics| {
+                std::mem::swap(&mut logistics.from_factory, &mut logistics.to_factory);
+                logistics.timestamps.touch();
+            })
+            .ok_or_else(|| format!("Logistics line with id {} not found", id))?;
+        self.emit(EngineEvent::LogisticsUpdated(id));
+        Ok(())
     }
 
-    /// Get global power statistics for all factories
-    pub fn global_power_stats(&self) -> PowerStats {
-        let mut total_generation = 0.0;
-        let mut total_consumption = 0.0;
-        let mut factory_stats = Vec::new();
+    /// Split a multi-item logistics line into one line per item
+    ///
+    /// The original line keeps its ID and transport number and carries the first
+    /// item; every other item moves to a new line of the same transport kind with
+    /// a freshly allocated transport number. Each line keeps the detail labels of
+    /// the segments it still carries.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the logistics line to split
+    ///
+    /// # Returns
+    ///
+    /// The IDs of the resulting lines, starting with the original one, or an error
+    /// if the line doesn't exist or only carries a single item
+    pub fn split_logistics_line(
+        &mut self,
+        id: LogisticsId,
+    ) -> Result<Vec<LogisticsId>, Box<dyn std::error::Error>> {
+        self.revision += 1;
+        let original = self
+            .logistics_lines
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| format!("Logistics line with id {} not found", id))?;
 
-        for (factory_id, factory) in &self.factories {
-            let generation = factory.total_power_generation();
-            let consumption = factory.total_power_consumption();
-            let generator_count = factory.power_generators.len() as u32;
+        let items = original.transport_type.distinct_items();
+        if items.len() < 2 {
+            return Err(format!(
+                "Logistics line with id {} carries a single item and cannot be split",
+                id
+            )
+            .into());
+        }
 
-            // Collect unique generator types
-            let mut generator_types = std::collections::HashSet::new();
-            for generator in factory.power_generators.values() {
-                generator_types.insert(generator.generator_type);
+        let kind = original.transport_type.kind();
+        let mut next_number = self.next_transport_number(kind);
+        let mut resulting_ids = vec![id];
+
+        for (index, item) in items.into_iter().enumerate() {
+            let mut transport_type = original.transport_type.retain_item(item);
+            let transport_details = original
+                .transport_details
+                .retain_item(&original.transport_type, item);
+            if index == 0 {
+                self.logistics_lines.update(&id, |line| {
+                    line.transport_type = transport_type;
+                    line.transport_details = transport_details;
+                    line.timestamps.touch();
+                });
+                continue;
             }
-            let generator_types: Vec<_> = generator_types.into_iter().collect();
 
-            let factory_stat = models::FactoryPowerStats::new(
-                *factory_id,
-                factory.name.clone(),
-                generation,
-                consumption,
-                generator_count,
-                generator_types,
-            );
+            transport_type.set_transport_number(next_number);
+            next_number += 1;
+            match &mut transport_type {
+                TransportType::Bus(bus) => {
+                    bus.bus_name = format!("{} - {}", bus.bus_name, models::item_name(item))
+                }
+                TransportType::Train(train) => {
+                    train.train_name = format!("{} - {}", train.train_name, models::item_name(item))
+                }
+                TransportType::Truck(_) | TransportType::Drone(_) => {}
+            }
 
-            total_generation += generation;
-            total_consumption += consumption;
-            factory_stats.push(factory_stat);
+            let new_id = LogisticsId::new_v4();
+            self.logistics_lines.insert(
+                new_id,
+                LogisticsFlux {
+                    id: new_id,
+                    from_factory: original.from_factory,
+                    to_factory: original.to_factory,
+                    transport_type,
+                    transport_details,
+                    timestamps: Timestamps::now(),
+                    unknown_fields: UnknownFields::new(),
+                },
+            );
+            resulting_ids.push(new_id);
         }
 
-        PowerStats::new(total_generation, total_consumption, factory_stats)
-    }
-
-    /// Get all factories
-    pub fn get_all_factories(&self) -> &HashMap<FactoryId, Factory> {
-        &self.factories
-    }
-
-    /// Get all logistics lines
-    pub fn get_all_logistics(&self) -> &HashMap<LogisticsId, LogisticsFlux> {
-        &self.logistics_lines
-    }
-
-    /// Delete a factory and its connected logistics lines
-    pub fn delete_factory(&mut self, id: FactoryId) -> Result<(), Box<dyn std::error::Error>> {
-        // Check if factory exists
-        if !self.factories.contains_key(&id) {
-            return Err(format!("Factory with id {} does not exist", id).into());
+        self.emit(EngineEvent::LogisticsUpdated(id));
+        for new_id in &resulting_ids[1..] {
+            self.emit(EngineEvent::LogisticsCreated(*new_id));
         }
+        Ok(resulting_ids)
+    }
 
-        // Remove all logistics lines connected to this factory
-        self.logistics_lines
-            .retain(|_, logistics| logistics.from_factory != id && logistics.to_factory != id);
-
-        // Remove the factory
-        self.factories.remove(&id).ok_or("Factory not found")?;
+    // ========== Transport Naming ==========
 
-        Ok(())
+    /// Get the naming patterns used for auto-generated transport labels
+    pub fn transport_naming(&self) -> &TransportNaming {
+        &self.transport_naming
     }
 
-    /// Delete a logistics line
-    pub fn delete_logistics_line(
+    /// Set the naming pattern for a transport kind
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The transport kind to configure
+    /// * `pattern` - Pattern using `{prefix}`, `{n}` or `{n:0W}` placeholders
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or an error if the pattern is invalid
+    pub fn set_transport_naming_pattern(
         &mut self,
-        id: LogisticsId,
+        kind: TransportKind,
+        pattern: impl Into<String>,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        // Check if logistics line exists
-        if !self.logistics_lines.contains_key(&id) {
-            return Err(format!("Logistics line with id {} does not exist", id).into());
-        }
+        self.revision += 1;
+        self.transport_naming.set_pattern(kind, pattern)?;
+        self.emit(EngineEvent::TransportNamingChanged);
+        Ok(())
+    }
 
+    /// Get the next free transport number for a transport kind
+    ///
+    /// The number is one past the highest number in use by that kind, so it is
+    /// unique among the current lines. Deleting the highest-numbered line frees
+    /// its number again.
+    pub fn next_transport_number(&self, kind: TransportKind) -> u64 {
         self.logistics_lines
-            .remove(&id)
-            .ok_or("Logistics line not found")?;
+            .values()
+            .filter(|line| line.transport_type.kind() == kind)
+            .map(|line| line.transport_type.transport_number())
+            .max()
+            .unwrap_or(0)
+            + 1
+    }
 
-        Ok(())
+    /// Render the default label for a transport number using the configured pattern
+    pub fn default_transport_name(&self, kind: TransportKind, number: u64) -> String {
+        self.transport_naming.format(kind, number)
     }
 
-    /// Reset the engine to an empty state (clear all factories and logistics)
-    ///
-    /// # Returns
-    ///
-    /// Result indicating success or failure
+    /// Allocate the next transport number and its default label
     ///
     /// # Example
     ///
     /// ```
-    /// use satisflow_engine::SatisflowEngine;
-    ///
-    /// let mut engine = SatisflowEngine::new();
-    /// engine.create_factory("Test Factory".to_string(), None);
-    /// assert_eq!(engine.get_all_factories().len(), 1);
+    /// use satisflow_engine::{models::TransportKind, SatisflowEngine};
     ///
-    /// engine.reset().unwrap();
-    /// assert_eq!(engine.get_all_factories().len(), 0);
+    /// let engine = SatisflowEngine::new();
+    /// let (number, name) = engine.allocate_transport_name(TransportKind::Truck);
+    /// assert_eq!(number, 1);
+    /// assert_eq!(name, "TRK-001");
     /// ```
-    pub fn reset(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        self.factories.clear();
-        self.logistics_lines.clear();
-        self.blueprint_templates.clear();
-        Ok(())
+    pub fn allocate_transport_name(&self, kind: TransportKind) -> (u64, String) {
+        let number = self.next_transport_number(kind);
+        (number, self.default_transport_name(kind, number))
     }
 
-    // ========== Blueprint Template Management ==========
+    /// Recalculate every factory's item balance and the goal progress
+    ///
+    /// Factories are recalculated in parallel with the `parallel` feature.
+    ///
+    /// # Returns
+    ///
+    /// The net rate of every item across all factories, see [`Self::global_items`]
+    pub fn update(&mut self) -> HashMap<Item, f64> {
+        let logistics_lines = &self.logistics_lines;
 
-    /// Add a blueprint template to the library
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+
+            self.factories
+                .par_iter_mut()
+                .for_each(|(_id, factory)| factory.calculate_item(logistics_lines));
+        }
+
+        #[cfg(not(feature = "parallel"))]
+        for factory in self.factories.values_mut() {
+            factory.calculate_item(logistics_lines);
+        }
+
+        // Compare production against the goals
+        self.goal_progress = self
+            .goals
+            .values()
+            .map(|goal| {
+                let actual_rate = goal.actual_rate(self.factories.values());
+                (goal.id, GoalProgress::new(goal, actual_rate))
+            })
+            .collect();
+        self.global_items()
+    }
+
+    /// Get the net rate of every item across all factories, as of the last
+    /// `update()`
+    ///
+    /// FICSMAS items are left out unless the save plans them.
+    pub fn global_items(&self) -> HashMap<Item, f64> {
+        let hidden_items = self.hidden_items();
+        self.factories
+            .values()
+            .fold(HashMap::new(), |mut items, factory| {
+                add_items(&mut items, &factory.items);
+                items
+            })
+            .into_iter()
+            .filter(|(item, _)| !hidden_items.contains(item))
+            .map(|(item, quantity)| (item, rate::round_rate(quantity)))
+            .collect()
+    }
+
+    /// Get the global balance for an item with every producer and consumer listed
+    ///
+    /// Unlike `update()`, which only returns net quantities, the report breaks the
+    /// balance down per factory into production lines, raw inputs, power
+    /// generators and logistics imports/exports.
     ///
     /// # Arguments
     ///
-    /// * `blueprint` - The blueprint template to add
+    /// * `item` - The item to report on
     ///
     /// # Returns
     ///
-    /// The ID of the added blueprint template
-    pub fn add_blueprint_template(
-        &mut self,
-        blueprint: ProductionLineBlueprint,
-    ) -> ProductionLineId {
-        let id = blueprint.id;
-        self.blueprint_templates.insert(id, blueprint);
-        id
+    /// The item balance report (empty if nothing produces or consumes the item,
+    /// or it is a FICSMAS item and the save doesn't plan them)
+    pub fn item_balance_report(&self, item: Item) -> ItemBalanceReport {
+        if self.hidden_items().contains(&item) {
+            return ItemBalanceReport::build(item, [], &self.logistics_lines);
+        }
+        ItemBalanceReport::build(item, self.factories.values(), &self.logistics_lines)
     }
 
-    /// Get a blueprint template by ID
+    /// Suggest machine counts and underclocks so a factory's lines do not starve
     ///
     /// # Arguments
     ///
-    /// * `id` - The ID of the blueprint template
+    /// * `factory_id` - The factory to balance
     ///
     /// # Returns
     ///
-    /// Reference to the blueprint template if found
-    pub fn get_blueprint_template(&self, id: ProductionLineId) -> Option<&ProductionLineBlueprint> {
-        self.blueprint_templates.get(&id)
+    /// Result containing one suggestion per production line with inputs, or an
+    /// error if the factory does not exist
+    pub fn balance_suggestions(
+        &self,
+        factory_id: FactoryId,
+    ) -> Result<Vec<BalanceSuggestion>, Box<dyn std::error::Error>> {
+        let factory = self
+            .factories
+            .get(&factory_id)
+            .ok_or_else(|| format!("Factory with id {} not found", factory_id))?;
+        Ok(BalanceSuggestion::for_factory(
+            factory,
+            &self.logistics_lines,
+        ))
     }
 
-    /// Get all blueprint templates
+    /// Find factories, production lines, logistics lines, items and recipes by text
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - Text to look for, case-insensitively
+    /// * `limit` - Maximum number of hits to return
     ///
     /// # Returns
     ///
-    /// Reference to the hash map of all blueprint templates
-    pub fn get_all_blueprint_templates(
-        &self,
-    ) -> &HashMap<ProductionLineId, ProductionLineBlueprint> {
-        &self.blueprint_templates
+    /// The best hits first, see [`search::search`]
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchHit> {
+        let mut hits = search::search(
+            query,
+            self.factories.values(),
+            self.logistics_lines.values(),
+            self.custom_recipes.values(),
+        );
+        hits.truncate(limit);
+        hits
     }
 
-    /// Remove a blueprint template from the library
+    /// Track un-sunk uranium and plutonium waste per factory
+    pub fn nuclear_waste_report(&self) -> NuclearWasteReport {
+        NuclearWasteReport::build(self.factories.values(), &self.logistics_lines)
+    }
+
+    /// Count the somersloops and power shards installed across all factories
+    pub fn resource_inventory(&self) -> ResourceInventory {
+        ResourceInventory::build(self.factories.values())
+    }
+
+    /// Measure the power balance and item rates of the plan
+    ///
+    /// Item rates are those of the last `update()`.
+    pub fn metrics_point(&self) -> MetricsPoint {
+        let items = self
+            .global_items()
+            .into_iter()
+            .filter(|(_, balance)| !rate::is_zero(*balance))
+            .collect();
+        let power = self.global_power_stats();
+
+        MetricsPoint {
+            revision: self.revision,
+            recorded_at: Utc::now(),
+            power_generation: power.total_generation,
+            power_consumption: power.total_consumption,
+            power_balance: power.power_balance,
+            items,
+        }
+    }
+
+    /// Get global power statistics for all factories
+    pub fn global_power_stats(&self) -> PowerStats {
+        let mut total_generation = 0.0;
+        let mut total_consumption = 0.0;
+        let mut generation_range = models::GenerationRange::default();
+        let mut factory_stats = Vec::new();
+
+        for (factory_id, factory) in &self.factories {
+            let generation = factory.total_power_generation();
+            let consumption = factory.total_power_consumption();
+            let generator_count = factory.active_power_generators().count() as u32;
+
+            // Collect unique generator types
+            let mut generator_types = std::collections::HashSet::new();
+            for generator in factory.active_power_generators() {
+                generator_types.insert(generator.generator_type);
+            }
+            let generator_types: Vec<_> = generator_types.into_iter().collect();
+
+            let mut factory_stat = models::FactoryPowerStats::new(
+                *factory_id,
+                factory.name.clone(),
+                generation,
+                consumption,
+                generator_count,
+                generator_types,
+            );
+            factory_stat.power_grid = factory.power_grid;
+            factory_stat.generation_range = factory.power_generation_range();
+            generation_range.add(factory_stat.generation_range);
+            factory_stat.storage = factory.storage_status();
+            factory_stat.fuel_shortfalls = factory.fuel_shortfalls(&self.logistics_lines);
+
+            total_generation += generation;
+            total_consumption += consumption;
+            factory_stats.push(factory_stat);
+        }
+
+        let mut stats = PowerStats::new(total_generation, total_consumption, factory_stats);
+        stats.generation_range = generation_range;
+        stats.grid_stats = self.grid_power_stats();
+        stats.storage = self.combined_storage_status(stats.power_balance);
+        stats
+    }
+
+    /// Sum the batteries of every factory into a single charge state
+    fn combined_storage_status(&self, balance: f64) -> Option<StorageStatus> {
+        let mut battery_count = 0;
+        let mut stored_mwh = 0.0;
+        let mut capacity_mwh = 0.0;
+        let mut charging_draw = 0.0;
+        for factory in self.factories.values() {
+            if let Some(storage) = &factory.power_storage {
+                battery_count += storage.battery_count;
+                stored_mwh += storage.stored_mwh;
+                capacity_mwh += storage.total_capacity();
+                charging_draw += factory.storage_charging_draw();
+            }
+        }
+        (battery_count > 0).then(|| {
+            StorageStatus::new(
+                battery_count,
+                stored_mwh,
+                capacity_mwh,
+                charging_draw,
+                balance,
+            )
+        })
+    }
+
+    /// Summarize the save as a report card with a short status paragraph
+    pub fn report_card(&self) -> ReportCard {
+        ReportCard::build(self.factories.values(), self.logistics_lines.len())
+    }
+
+    /// Check the whole save for problems
+    ///
+    /// Looks for logistics lines pointing at deleted factories, item and power
+    /// deficits, overloaded conveyors and pipelines, and generators burning a
+    /// fuel they cannot use. Deficits and buffers of FICSMAS items are only
+    /// checked when the save plans them.
+    ///
+    /// # Returns
+    ///
+    /// Every problem found, most severe first
+    pub fn validate_all(&self) -> Vec<Diagnostic> {
+        Diagnostic::collect(
+            &self.factories,
+            &self.logistics_lines,
+            &self.grid_power_stats(),
+            self.hidden_items(),
+        )
+    }
+
+    /// Check a logistics line before creating or changing it
     ///
     /// # Arguments
     ///
-    /// * `id` - The ID of the blueprint template to remove
+    /// * `from` - The factory shipping the items
+    /// * `to` - The factory receiving them
+    /// * `transport_type` - What carries the items
+    /// * `transport_details` - Labels of the transport
     ///
     /// # Returns
     ///
-    /// Result indicating success or an error if the template doesn't exist
-    pub fn remove_blueprint_template(
-        &mut self,
-        id: ProductionLineId,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        if !self.blueprint_templates.contains_key(&id) {
-            return Err(format!("Blueprint template with id {} does not exist", id).into());
+    /// Errors for details that don't fit the transport or missing factories,
+    /// and warnings for conveyors, lifts and pipelines over capacity
+    pub fn check_logistics_line(
+        &self,
+        from: FactoryId,
+        to: FactoryId,
+        transport_type: &TransportType,
+        transport_details: &TransportDetails,
+    ) -> ValidationReport {
+        let mut report = ValidationReport::new();
+        if let Err(e) = transport_details.validate(transport_type) {
+            report.error(e);
         }
-        self.blueprint_templates.remove(&id);
-        Ok(())
+        for id in [from, to] {
+            if !self.factories.contains_key(&id) {
+                report.error(format!("Factory with id {} does not exist", id));
+            }
+        }
+
+        let line = LogisticsFlux {
+            id: LogisticsId::from_uuid(uuid::Uuid::nil()),
+            from_factory: from,
+            to_factory: to,
+            transport_type: transport_type.clone(),
+            transport_details: transport_details.clone(),
+            timestamps: Timestamps::default(),
+            unknown_fields: UnknownFields::new(),
+        };
+        for diagnostic in diagnostics::check_logistics(&line, &self.factories) {
+            if diagnostic.kind != DiagnosticKind::DanglingLogistics {
+                report.warn(diagnostic.message);
+            }
+        }
+        report
     }
 
-    /// Instantiate a blueprint template into a factory as a new production line.
-    ///
-    /// Creates an independent deep copy of the blueprint with new UUIDs assigned.
+    /// Check a power generator before adding it to a factory
     ///
     /// # Arguments
     ///
-    /// * `factory_id` - The ID of the target factory
-    /// * `blueprint_id` - The ID of the blueprint template to instantiate
-    /// * `custom_name` - Optional custom name for the instance (defaults to blueprint name)
+    /// * `factory_id` - The factory the generator goes to
+    /// * `generator` - The generator to check
     ///
     /// # Returns
     ///
-    /// Result containing (instance_id, instance_name) on success
-    pub fn instantiate_blueprint_into_factory(
-        &mut self,
+    /// Errors for a missing factory or an invalid generator, and warnings for
+    /// fuel or water the factory has to import because it doesn't make any
+    pub fn check_power_generator(
+        &self,
         factory_id: FactoryId,
-        blueprint_id: ProductionLineId,
-        custom_name: Option<String>,
-    ) -> Result<(ProductionLineId, String), Box<dyn std::error::Error>> {
-        // Get the blueprint template
-        let blueprint = self
-            .get_blueprint_template(blueprint_id)
-            .ok_or_else(|| format!("Blueprint template {} not found", blueprint_id))?
-            .clone();
+        generator: &PowerGenerator,
+    ) -> ValidationReport {
+        let mut report = ValidationReport::new();
+        if let Err(e) = generator.validate() {
+            report.error(e.to_string());
+        }
+        let Some(factory) = self.factories.get(&factory_id) else {
+            report.error(format!("Factory with id {} does not exist", factory_id));
+            return report;
+        };
 
-        // Validate blueprint has at least one production line
-        if blueprint.production_lines.is_empty() {
-            return Err("Blueprint must have at least 1 production line".into());
+        let needs = [
+            (generator.fuel_type, generator.total_fuel_consumption()),
+            (Item::Water, generator.total_water_consumption()),
+        ];
+        for (item, quantity) in needs {
+            if !rate::is_zero(quantity) && rate::is_zero(factory.production_rate(item)) {
+                report.warn(format!(
+                    "{} is not produced in {}; its generators rely on imports",
+                    models::item_name(item),
+                    factory.name
+                ));
+            }
         }
+        report
+    }
 
-        // Get factory and add blueprint
-        let factory = self
-            .get_factory_mut(factory_id)
-            .ok_or_else(|| format!("Factory {} not found", factory_id))?;
+    /// Get the power balance of each grid
+    ///
+    /// Every grid is listed, even without factories. Factories that are not
+    /// assigned to a grid are grouped in a final "Unassigned" entry.
+    pub fn grid_power_stats(&self) -> Vec<GridPowerStats> {
+        let mut grids: Vec<GridPowerStats> = self
+            .power_grids
+            .values()
+            .map(|grid| GridPowerStats::new(Some(grid.id), grid.name.clone()))
+            .collect();
+        grids.sort_by(|a, b| a.grid_name.cmp(&b.grid_name));
+        let mut unassigned = GridPowerStats::new(None, "Unassigned".to_string());
+
+        for factory in self.factories.values() {
+            let stats = factory
+                .power_grid
+                .and_then(|grid_id| grids.iter_mut().find(|g| g.grid_id == Some(grid_id)))
+                .unwrap_or(&mut unassigned);
+            stats.add_factory(
+                factory.id,
+                factory.total_power_generation(),
+                factory.total_power_consumption(),
+            );
+        }
 
-        // Deep clone and regenerate UUIDs
-        let mut instance = blueprint.clone();
-        instance.id = Uuid::new_v4();
-        for line in &mut instance.production_lines {
-            line.id = Uuid::new_v4();
+        for stats in &mut grids {
+            stats.factory_ids.sort();
+        }
+        if !unassigned.factory_ids.is_empty() {
+            unassigned.factory_ids.sort();
+            grids.push(unassigned);
         }
+        grids
+    }
 
-        // Override name if custom name provided
-        let instance_name = custom_name.unwrap_or(blueprint.name.clone());
-        instance.name = instance_name.clone();
+    /// Get the dashboard totals of each tag
+    ///
+    /// Groups are sorted by tag name, ignoring case, and named after the first
+    /// spelling found going through factories by name. Factories without tags
+    /// are grouped in a final "untagged" entry.
+    pub fn tag_group_stats(&self) -> Vec<TagGroupStats> {
+        let mut groups: Vec<TagGroupStats> = Vec::new();
+        let mut untagged = TagGroupStats::new(None);
+
+        let mut factories: Vec<&Factory> = self.factories.values().collect();
+        factories.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+        for factory in factories {
+            if factory.tags.is_empty() {
+                untagged.add_factory(factory);
+            }
+            for tag in &factory.tags {
+                let existing = groups.iter_mut().find(|group| {
+                    group
+                        .tag
+                        .as_deref()
+                        .is_some_and(|name| name.eq_ignore_ascii_case(tag))
+                });
+                match existing {
+                    Some(group) => group.add_factory(factory),
+                    None => {
+                        let mut group = TagGroupStats::new(Some(tag.clone()));
+                        group.add_factory(factory);
+                        groups.push(group);
+                    }
+                }
+            }
+        }
 
-        let instance_id = instance.id;
-        factory.production_lines.insert(
-            instance_id,
-            ProductionLine::ProductionLineBlueprint(instance),
-        );
+        groups.sort_by_key(|group| group.tag.as_deref().map(str::to_lowercase));
+        if !untagged.factory_ids.is_empty() {
+            groups.push(untagged);
+        }
+        groups
+    }
 
-        Ok((instance_id, instance_name))
+    // ========== Map ==========
+
+    /// Place a factory on the world map, or remove it with `None`
+    pub fn set_factory_position(
+        &mut self,
+        id: FactoryId,
+        position: Option<Position>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.revision += 1;
+        if let Some(position) = &position {
+            position.validate()?;
+        }
+        let factory = self
+            .get_factory_mut(id)
+            .ok_or_else(|| format!("Factory with id {} does not exist", id))?;
+        factory.position = position;
+        Ok(())
     }
 
-    /// Save the engine state to a JSON file
+    /// Estimate travel for a vehicle logistics line
     ///
-    /// # Arguments
+    /// # Returns
     ///
-    /// * `path` - Path to the save file
+    /// `None` for buses, missing lines, and lines whose factories are not both
+    /// placed on the map
+    pub fn route_estimate(&self, logistics_id: LogisticsId) -> Option<RouteEstimate> {
+        let logistics = self.logistics_lines.get(&logistics_id)?;
+        let from = self.factories.get(&logistics.from_factory)?.position?;
+        let to = self.factories.get(&logistics.to_factory)?.position?;
+        RouteEstimate::new(logistics, &from, &to)
+    }
+
+    /// Estimate travel for every vehicle logistics line between placed factories,
+    /// longest round trip first
+    pub fn route_estimates(&self) -> Vec<RouteEstimate> {
+        let mut estimates: Vec<RouteEstimate> = self
+            .logistics_lines
+            .keys()
+            .filter_map(|id| self.route_estimate(*id))
+            .collect();
+        estimates.sort_by(|a, b| {
+            b.round_trip_secs
+                .total_cmp(&a.round_trip_secs)
+                .then(a.logistics_id.cmp(&b.logistics_id))
+        });
+        estimates
+    }
+
+    /// Default schedule of a train line, from the distance between its factories
     ///
     /// # Returns
     ///
-    /// Result indicating success or failure
+    /// `None` for lines that are missing, not trains, or whose factories are
+    /// not both placed on the map
+    pub fn train_schedule(&self, logistics_id: LogisticsId) -> Option<TrainSchedule> {
+        let logistics = self.logistics_lines.get(&logistics_id)?;
+        if !matches!(logistics.transport_type, TransportType::Train(_)) {
+            return None;
+        }
+        let from = self.factories.get(&logistics.from_factory)?.position?;
+        let to = self.factories.get(&logistics.to_factory)?.position?;
+        Some(TrainSchedule::new(from.distance_to(&to)))
+    }
+
+    /// Work out what each wagon of a train line can move on a schedule
     ///
-    /// # Example
+    /// # Arguments
     ///
-    /// ```no_run
-    /// use satisflow_engine::SatisflowEngine;
-    /// use std::path::Path;
+    /// * `logistics_id` - Train line to check
+    /// * `schedule` - Route the train runs, there and back
     ///
-    /// let engine = SatisflowEngine::new();
-    /// engine.save_to_file(Path::new("my_factory.json")).unwrap();
-    /// ```
-    pub fn save_to_file(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
-        let save_file = SaveFile::new(self.clone());
-        let json = serde_json::to_string_pretty(&save_file)?;
-        std::fs::write(path, json)?;
-        Ok(())
+    /// # Returns
+    ///
+    /// Sustainable rate of every wagon, flagged where the assigned rate is
+    /// higher, or an error if the line is missing, not a train, or the
+    /// schedule is invalid
+    pub fn train_throughput(
+        &self,
+        logistics_id: LogisticsId,
+        schedule: &TrainSchedule,
+    ) -> Result<TrainThroughput, Box<dyn std::error::Error>> {
+        let logistics = self
+            .logistics_lines
+            .get(&logistics_id)
+            .ok_or_else(|| format!("Logistics line with id {} not found", logistics_id))?;
+        let TransportType::Train(train) = &logistics.transport_type else {
+            return Err(format!("Logistics line {} is not a train", logistics_id).into());
+        };
+        Ok(train.throughput(schedule)?)
     }
 
-    /// Load the engine state from a JSON file
+    /// Get the logistics network as Sankey nodes and weighted links
+    ///
+    /// # Returns
+    ///
+    /// One node per factory and one link per source, target, item and
+    /// transport kind, with the rates of matching lines summed
+    pub fn flow_network(&self) -> FlowNetwork {
+        FlowNetwork::new(self.factories.values(), self.logistics_lines.values())
+    }
+
+    // ========== Power Grids ==========
+
+    /// Create a new power grid
     ///
     /// # Arguments
     ///
-    /// * `path` - Path to the save file
+    /// * `name` - The name of the grid
+    /// * `description` - Optional description
     ///
     /// # Returns
     ///
-    /// Result containing the loaded engine or an error
+    /// The ID of the created grid
+    pub fn create_power_grid(&mut self, name: String, description: Option<String>) -> PowerGridId {
+        self.revision += 1;
+        let grid = PowerGrid::new(PowerGridId::new_v4(), name, description);
+        let id = grid.id;
+        self.power_grids.insert(id, grid);
+        self.emit(EngineEvent::PowerGridChanged(id));
+        id
+    }
+
+    /// Get a power grid by ID
+    pub fn get_power_grid(&self, id: PowerGridId) -> Option<&PowerGrid> {
+        self.power_grids.get(&id)
+    }
+
+    /// Get a mutable power grid by ID
+    pub fn get_power_grid_mut(&mut self, id: PowerGridId) -> Option<&mut PowerGrid> {
+        self.revision += 1;
+        if self.power_grids.contains_key(&id) {
+            self.emit(EngineEvent::PowerGridChanged(id));
+        }
+        self.power_grids.get_mut(&id)
+    }
+
+    /// Get all power grids
+    pub fn get_all_power_grids(&self) -> &HashMap<PowerGridId, PowerGrid> {
+        &self.power_grids
+    }
+
+    /// Delete a power grid, leaving its factories unassigned
     ///
-    /// # Example
+    /// # Arguments
     ///
-    /// ```no_run
-    /// use satisflow_engine::SatisflowEngine;
-    /// use std::path::Path;
+    /// * `id` - The ID of the grid to delete
     ///
-    /// let engine = SatisflowEngine::load_from_file(Path::new("my_factory.json")).unwrap();
-    /// ```
-    pub fn load_from_file(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
-        let json = std::fs::read_to_string(path)?;
-        Self::load_from_json_with_version_check(&json)
+    /// # Returns
+    ///
+    /// The deleted grid, or an error if it does not exist
+    pub fn delete_power_grid(
+        &mut self,
+        id: PowerGridId,
+    ) -> Result<PowerGrid, Box<dyn std::error::Error>> {
+        self.revision += 1;
+        let grid = self
+            .power_grids
+            .remove(&id)
+            .ok_or_else(|| format!("Power grid with id {} does not exist", id))?;
+        for factory in self.factories.values_mut() {
+            if factory.power_grid == Some(id) {
+                factory.power_grid = None;
+                self.observers.emit(EngineEvent::FactoryUpdated(factory.id));
+            }
+        }
+        self.emit(EngineEvent::PowerGridChanged(id));
+        Ok(grid)
     }
 
-    /// Save to a JSON string (for API usage)
+    /// Assign a factory to a power grid, or unassign it with `None`
+    ///
+    /// # Arguments
+    ///
+    /// * `factory_id` - The factory to assign
+    /// * `grid_id` - The target grid, or `None` to unassign
     ///
     /// # Returns
     ///
-    /// Result containing the JSON string or an error
-    pub fn save_to_json(&self) -> Result<String, Box<dyn std::error::Error>> {
-        let save_file = SaveFile::new(self.clone());
-        let json = serde_json::to_string_pretty(&save_file)?;
-        Ok(json)
+    /// Result indicating success or an error if the factory or grid does not exist
+    pub fn assign_factory_to_power_grid(
+        &mut self,
+        factory_id: FactoryId,
+        grid_id: Option<PowerGridId>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.revision += 1;
+        if let Some(grid_id) = grid_id {
+            if !self.power_grids.contains_key(&grid_id) {
+                return Err(format!("Power grid with id {} does not exist", grid_id).into());
+            }
+        }
+        let factory = self
+            .factories
+            .get_mut(&factory_id)
+            .ok_or_else(|| format!("Factory with id {} does not exist", factory_id))?;
+        factory.power_grid = grid_id;
+        self.emit(EngineEvent::FactoryUpdated(factory_id));
+        Ok(())
     }
 
-    /// Load from a JSON string (for API usage)
+    // ========== Goals ==========
+
+    /// Create a production goal
     ///
     /// # Arguments
     ///
-    /// * `json` - JSON string containing the save file
+    /// * `item` - The item to produce
+    /// * `target_rate` - Wanted production, in items/min
+    /// * `factory_id` - Factory the goal applies to, or `None` for all factories
+    /// * `description` - Optional description
     ///
     /// # Returns
     ///
-    /// Result containing the loaded engine or an error
-    pub fn load_from_json(json: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        Self::load_from_json_with_version_check(json)
+    /// Result containing the ID of the created goal, or an error if the rate is
+    /// not positive or the factory does not exist
+    pub fn create_goal(
+        &mut self,
+        item: Item,
+        target_rate: f64,
+        factory_id: Option<FactoryId>,
+        description: Option<String>,
+    ) -> Result<GoalId, Box<dyn std::error::Error>> {
+        self.revision += 1;
+        let goal = Goal::new(GoalId::new_v4(), item, target_rate, factory_id, description);
+        let id = goal.id;
+        self.insert_goal(goal)?;
+        Ok(id)
     }
 
-    /// Internal method to load with version checking
-    fn load_from_json_with_version_check(json: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        // First, parse just to get the version
-        let value: serde_json::Value = serde_json::from_str(json)?;
-
-        let file_version_str = value["version"]
-            .as_str()
-            .ok_or("Missing version field in save file")?;
-
-        let file_version = SaveVersion::parse(file_version_str)?;
-        let engine_version = SaveVersion::current();
+    /// Replace an existing goal
+    ///
+    /// # Returns
+    ///
+    /// Result containing the previous goal, or an error if the goal does not
+    /// exist or the new one is invalid
+    pub fn replace_goal(&mut self, goal: Goal) -> Result<Goal, Box<dyn std::error::Error>> {
+        self.revision += 1;
+        let previous = self
+            .goals
+            .get(&goal.id)
+            .cloned()
+            .ok_or_else(|| format!("Goal with id {} does not exist", goal.id))?;
+        self.insert_goal(goal)?;
+        Ok(previous)
+    }
 
-        // Check version compatibility
-        if file_version == engine_version {
-            // Exact match - load directly
-            let save_file: SaveFile = serde_json::from_str(json)?;
-            return Ok(save_file.engine);
+    fn insert_goal(&mut self, goal: Goal) -> Result<(), Box<dyn std::error::Error>> {
+        goal.validate()?;
+        if let Some(factory_id) = goal.factory_id {
+            if !self.factories.contains_key(&factory_id) {
+                return Err(format!("Factory with id {} does not exist", factory_id).into());
+            }
         }
+        let actual_rate = goal.actual_rate(self.factories.values());
+        self.goal_progress
+            .insert(goal.id, GoalProgress::new(&goal, actual_rate));
+        self.emit(EngineEvent::GoalChanged(goal.id));
+        self.goals.insert(goal.id, goal);
+        Ok(())
+    }
 
-        if !file_version.is_compatible_with(&engine_version) {
-            // Incompatible major version
-            return Err(Box::new(VersionError::Incompatible {
-                save_version: file_version.to_string(),
-                engine_version: engine_version.to_string(),
-            }));
-        }
+    /// Get a goal by ID
+    pub fn get_goal(&self, id: GoalId) -> Option<&Goal> {
+        self.goals.get(&id)
+    }
 
-        if file_version.is_newer_than(&engine_version) {
-            // Save is from a newer version
-            return Err(Box::new(VersionError::SaveTooNew {
-                save_version: file_version.to_string(),
-                engine_version: engine_version.to_string(),
-            }));
+    /// Get all goals
+    pub fn get_all_goals(&self) -> &HashMap<GoalId, Goal> {
+        &self.goals
+    }
+
+    /// Delete a goal
+    ///
+    /// # Returns
+    ///
+    /// The deleted goal, or an error if it does not exist
+    pub fn delete_goal(&mut self, id: GoalId) -> Result<Goal, Box<dyn std::error::Error>> {
+        self.revision += 1;
+        self.goal_progress.remove(&id);
+        let goal = self
+            .goals
+            .remove(&id)
+            .ok_or_else(|| format!("Goal with id {} does not exist", id))?;
+        self.emit(EngineEvent::GoalChanged(id));
+        Ok(goal)
+    }
+
+    /// Progress towards a goal as of the last `update()`
+    pub fn goal_progress(&self, id: GoalId) -> Option<&GoalProgress> {
+        self.goal_progress.get(&id)
+    }
+
+    /// Progress towards every goal as of the last `update()`, least complete first
+    pub fn all_goal_progress(&self) -> Vec<GoalProgress> {
+        let mut progress: Vec<GoalProgress> = self.goal_progress.values().cloned().collect();
+        progress.sort_by(|a, b| {
+            a.completion_percent
+                .total_cmp(&b.completion_percent)
+                .then_with(|| models::item_name(a.item).cmp(models::item_name(b.item)))
+        });
+        progress
+    }
+
+    /// Get all factories
+    pub fn get_all_factories(&self) -> &HashMap<FactoryId, Factory> {
+        &self.factories
+    }
+
+    /// Get all logistics lines
+    pub fn get_all_logistics(&self) -> &HashMap<LogisticsId, LogisticsFlux> {
+        &self.logistics_lines
+    }
+
+    /// Get the logistics lines delivering into a factory
+    ///
+    /// Lines are looked up through an index, so this is cheap even on large saves.
+    pub fn incoming_logistics(
+        &self,
+        factory_id: FactoryId,
+    ) -> impl Iterator<Item = &LogisticsFlux> + '_ {
+        self.logistics_lines.inbound(factory_id)
+    }
+
+    /// Get the logistics lines shipping out of a factory
+    pub fn outgoing_logistics(
+        &self,
+        factory_id: FactoryId,
+    ) -> impl Iterator<Item = &LogisticsFlux> + '_ {
+        self.logistics_lines.outbound(factory_id)
+    }
+
+    /// Get the factories making an item with their production lines or raw inputs
+    ///
+    /// Disabled entries don't count; see [`Factory::production_rate`]. Factories
+    /// come in no particular order.
+    pub fn factories_producing(&self, item: Item) -> impl Iterator<Item = &Factory> + '_ {
+        self.factories
+            .values()
+            .filter(move |factory| !rate::is_zero(factory.production_rate(item)))
+    }
+
+    /// Get the factories using up an item in their production lines or generators
+    ///
+    /// Disabled entries don't count; see [`Factory::consumption_rate`]. Factories
+    /// come in no particular order.
+    pub fn factories_consuming(&self, item: Item) -> impl Iterator<Item = &Factory> + '_ {
+        self.factories
+            .values()
+            .filter(move |factory| !rate::is_zero(factory.consumption_rate(item)))
+    }
+
+    /// Get the logistics lines shipping from one factory to another
+    ///
+    /// Only lines going from `from` to `to`; swap the arguments for the lines
+    /// going back.
+    pub fn logistics_between(
+        &self,
+        from: FactoryId,
+        to: FactoryId,
+    ) -> impl Iterator<Item = &LogisticsFlux> + '_ {
+        self.logistics_lines
+            .outbound(from)
+            .filter(move |line| line.to_factory == to)
+    }
+
+    /// Get the production lines running a recipe, with the factory of each
+    ///
+    /// A blueprint is included when one of its inner lines runs the recipe.
+    /// Disabled lines are included too. Lines come in no particular order.
+    pub fn production_lines_using_recipe(
+        &self,
+        recipe: Recipe,
+    ) -> impl Iterator<Item = (&Factory, &ProductionLine)> + '_ {
+        self.factories.values().flat_map(move |factory| {
+            factory
+                .production_lines
+                .values()
+                .filter(move |line| line.uses_recipe(recipe))
+                .map(move |line| (factory, line))
+        })
+    }
+
+    /// Get the factories shipping items into a factory (its upstream suppliers)
+    ///
+    /// # Arguments
+    ///
+    /// * `factory_id` - The ID of the factory to inspect
+    ///
+    /// # Returns
+    ///
+    /// One entry per supplying factory with the items it sends, or an error if
+    /// the factory doesn't exist
+    pub fn dependencies_of(
+        &self,
+        factory_id: FactoryId,
+    ) -> Result<Vec<FactoryDependency>, Box<dyn std::error::Error>> {
+        self.linked_factories(
+            factory_id,
+            self.logistics_lines.inbound(factory_id),
+            |line| line.from_factory,
+        )
+    }
+
+    /// Get the factories receiving items from a factory (its downstream consumers)
+    ///
+    /// # Arguments
+    ///
+    /// * `factory_id` - The ID of the factory to inspect
+    ///
+    /// # Returns
+    ///
+    /// One entry per consuming factory with the items it receives, or an error if
+    /// the factory doesn't exist
+    pub fn dependents_of(
+        &self,
+        factory_id: FactoryId,
+    ) -> Result<Vec<FactoryDependency>, Box<dyn std::error::Error>> {
+        self.linked_factories(
+            factory_id,
+            self.logistics_lines.outbound(factory_id),
+            |line| line.to_factory,
+        )
+    }
+
+    /// Get both upstream and downstream factories for a factory
+    pub fn factory_dependencies(
+        &self,
+        factory_id: FactoryId,
+    ) -> Result<FactoryDependencies, Box<dyn std::error::Error>> {
+        Ok(FactoryDependencies {
+            factory_id,
+            dependencies: self.dependencies_of(factory_id)?,
+            dependents: self.dependents_of(factory_id)?,
+        })
+    }
+
+    /// Group logistics lines by the factory on the other end of the link
+    fn linked_factories<'a>(
+        &self,
+        factory_id: FactoryId,
+        lines: impl Iterator<Item = &'a LogisticsFlux>,
+        other_end: impl Fn(&LogisticsFlux) -> FactoryId,
+    ) -> Result<Vec<FactoryDependency>, Box<dyn std::error::Error>> {
+        if !self.factories.contains_key(&factory_id) {
+            return Err(format!("Factory with id {} does not exist", factory_id).into());
         }
 
-        // Save is older but compatible - for now, try to load it
-        // In the future, this is where we'll apply migrations
-        if file_version.is_older_than(&engine_version) {
-            // For now, we'll try to load it and let serde handle defaults
-            // TODO: Add proper migration system here when needed
-            println!(
-                "INFO: Loading save file from older version {} (current: {})",
-                file_version, engine_version
-            );
-            println!(
-                "INFO: Migration system not yet implemented - using default values for new fields"
+        let mut linked: HashMap<FactoryId, FactoryDependency> = HashMap::new();
+        for line in lines {
+            let other_id = other_end(line);
+            let other_name = self
+                .factories
+                .get(&other_id)
+                .map(|factory| factory.name.clone())
+                .unwrap_or_default();
+            linked
+                .entry(other_id)
+                .or_insert_with(|| FactoryDependency::new(other_id, other_name))
+                .add_line(line.id, line.get_items());
+        }
+
+        let mut linked: Vec<FactoryDependency> = linked.into_values().collect();
+        linked.sort_by(|a, b| {
+            a.factory_name
+                .cmp(&b.factory_name)
+                .then(a.factory_id.cmp(&b.factory_id))
+        });
+        Ok(linked)
+    }
+
+    // ========== Resource Nodes ==========
+
+    /// Get the registry of map resource nodes
+    pub fn resource_nodes(&self) -> &ResourceNodeRegistry {
+        &self.resource_nodes
+    }
+
+    /// Override the node counts for a resource
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - A resource extracted from nodes by miners or oil extractors
+    /// * `counts` - Nodes available per purity
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or an error if the item is not a node resource
+    pub fn set_resource_node_counts(
+        &mut self,
+        item: Item,
+        counts: NodeCounts,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.revision += 1;
+        let node_based = [ExtractorType::MinerMk1, ExtractorType::OilExtractor]
+            .iter()
+            .any(|extractor| extractor.is_compatible_with(&item));
+        if !node_based {
+            return Err(format!("Item {:?} is not extracted from resource nodes", item).into());
+        }
+        self.resource_nodes.set_counts(item, counts);
+        self.emit(EngineEvent::ResourceNodesChanged);
+        Ok(())
+    }
+
+    /// Check node claims from all raw inputs against world availability
+    pub fn resource_node_report(&self) -> ResourceNodeReport {
+        self.resource_nodes.validate_claims(self.factories.values())
+    }
+
+    // ========== Factory Merge ==========
+
+    /// Preview merging several factories into one without modifying the engine
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - The factories to merge (at least two, all distinct)
+    /// * `new_name` - Name of the merged factory
+    ///
+    /// # Returns
+    ///
+    /// The merge preview, or an error if the selection or name is invalid
+    pub fn preview_merge_factories(
+        &self,
+        ids: &[FactoryId],
+        new_name: &str,
+    ) -> Result<FactoryMergePreview, Box<dyn std::error::Error>> {
+        let MergePlan {
+            factory: mut merged,
+            internal,
+            external,
+        } = self.build_merged_factory(ids, new_name)?;
+
+        // Recalculate against the rewired logistics to get the merged balance
+        let rewired: HashMap<LogisticsId, LogisticsFlux> = external
+            .iter()
+            .filter_map(|id| self.logistics_lines.get(id))
+            .map(|line| {
+                let mut line = line.clone();
+                if ids.contains(&line.from_factory) {
+                    line.from_factory = merged.id;
+                }
+                if ids.contains(&line.to_factory) {
+                    line.to_factory = merged.id;
+                }
+                (line.id, line)
+            })
+            .collect();
+        merged.calculate_item(&rewired);
+
+        let mut items: Vec<MergedItemBalance> = merged
+            .items
+            .iter()
+            .filter(|(_, quantity)| !rate::is_zero(**quantity))
+            .map(|(item, quantity)| MergedItemBalance {
+                item: *item,
+                quantity_per_min: *quantity,
+            })
+            .collect();
+        items.sort_by(|a, b| models::item_name(a.item).cmp(models::item_name(b.item)));
+
+        Ok(FactoryMergePreview {
+            name: merged.name.clone(),
+            source_factories: ids.to_vec(),
+            production_line_count: merged.production_lines.len(),
+            raw_input_count: merged.raw_inputs.len(),
+            power_generator_count: merged.power_generators.len(),
+            internalized_logistics: internal,
+            external_logistics: external,
+            items,
+            total_power_consumption: merged.total_power_consumption(),
+            total_power_generation: merged.total_power_generation(),
+        })
+    }
+
+    /// Merge several factories into a new one
+    ///
+    /// Production lines, raw inputs, power generators and internal transfers move
+    /// to the new factory. Battery banks are combined, tags are united, and
+    /// descriptions and notes are joined in the order the factories are given;
+    /// the merged factory stands at the middle of their map positions.
+    /// Logistics lines between merged factories are removed; their flows stay
+    /// documented as internal transfers named after the source factories, which
+    /// leave the balance unchanged. Lines to or from other factories are
+    /// rewired to the merged factory. The source factories are
+    /// removed, and their pins and goals move to the merged factory.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - The factories to merge (at least two, all distinct)
+    /// * `new_name` - Name of the merged factory
+    ///
+    /// # Returns
+    ///
+    /// The ID of the merged factory
+    pub fn merge_factories(
+        &mut self,
+        ids: &[FactoryId],
+        new_name: &str,
+    ) -> Result<FactoryId, Box<dyn std::error::Error>> {
+        self.revision += 1;
+        let MergePlan {
+            factory: merged,
+            internal,
+            external,
+        } = self.build_merged_factory(ids, new_name)?;
+        let merged_id = merged.id;
+
+        for id in &internal {
+            self.logistics_lines.remove(id);
+        }
+        for id in &external {
+            self.logistics_lines.update(id, |line| {
+                if ids.contains(&line.from_factory) {
+                    line.from_factory = merged_id;
+                }
+                if ids.contains(&line.to_factory) {
+                    line.to_factory = merged_id;
+                }
+                line.timestamps.touch();
+            });
+        }
+        for id in ids {
+            self.factories.remove(id);
+        }
+        self.factories.insert(merged_id, merged);
+
+        for id in ids {
+            self.emit(EngineEvent::FactoryDeleted(*id));
+        }
+        self.emit(EngineEvent::FactoryCreated(merged_id));
+        for id in internal {
+            self.emit(EngineEvent::LogisticsDeleted(id));
+        }
+        for id in external {
+            self.emit(EngineEvent::LogisticsUpdated(id));
+        }
+        // Pins and goals of the source factories follow them into the merged one
+        for id in ids {
+            self.release_factory(*id, Some(merged_id));
+        }
+        Ok(merged_id)
+    }
+
+    /// Combine the selected factories into a new, unsaved factory and classify
+    /// their logistics lines as internal or external to the selection
+    fn build_merged_factory(
+        &self,
+        ids: &[FactoryId],
+        new_name: &str,
+    ) -> Result<MergePlan, Box<dyn std::error::Error>> {
+        if new_name.trim().is_empty() {
+            return Err("Merged factory name cannot be empty".into());
+        }
+        if ids.len() < 2 {
+            return Err("At least two factories are required to merge".into());
+        }
+        for (index, id) in ids.iter().enumerate() {
+            if ids[..index].contains(id) {
+                return Err(format!("Factory with id {} is listed more than once", id).into());
+            }
+            if !self.factories.contains_key(id) {
+                return Err(format!("Factory with id {} does not exist", id).into());
+            }
+        }
+
+        let mut merged = Factory::new(FactoryId::new_v4(), new_name.to_string(), None);
+        merged.set_balance(self.balance.clone());
+        // Keep the power grid only when every merged factory shares it
+        let grids: Vec<Option<PowerGridId>> = ids
+            .iter()
+            .filter_map(|id| self.factories.get(id))
+            .map(|factory| factory.power_grid)
+            .collect();
+        if grids.windows(2).all(|pair| pair[0] == pair[1]) {
+            merged.power_grid = grids.first().copied().flatten();
+        }
+        let sources: Vec<&Factory> = ids.iter().filter_map(|id| self.factories.get(id)).collect();
+        let join_texts = |text: fn(&Factory) -> &Option<String>| {
+            let mut texts: Vec<&str> = Vec::new();
+            for text in sources
+                .iter()
+                .filter_map(|factory| text(factory).as_deref())
+            {
+                let text = text.trim();
+                if !text.is_empty() && !texts.contains(&text) {
+                    texts.push(text);
+                }
+            }
+            (!texts.is_empty()).then(|| texts.join("\n\n"))
+        };
+        merged.description = join_texts(|factory| &factory.description);
+        merged.notes = join_texts(|factory| &factory.notes);
+        merged.set_tags(
+            sources
+                .iter()
+                .flat_map(|factory| factory.tags.iter().cloned())
+                .collect(),
+        );
+        merged.power_storage = sources
+            .iter()
+            .filter_map(|factory| factory.power_storage.as_ref())
+            .fold(None, |combined: Option<PowerStorage>, storage| {
+                Some(match combined {
+                    Some(combined) => combined.combine(storage),
+                    None => storage.clone(),
+                })
+            });
+        let positions: Vec<&Position> = sources
+            .iter()
+            .filter_map(|factory| factory.position.as_ref())
+            .collect();
+        if !positions.is_empty() {
+            let count = positions.len() as f64;
+            merged.position = Some(Position::new(
+                positions.iter().map(|position| position.x).sum::<f64>() / count,
+                positions.iter().map(|position| position.y).sum::<f64>() / count,
+                positions.iter().map(|position| position.z).sum::<f64>() / count,
+            ));
+        }
+        for factory in sources {
+            merged
+                .production_lines
+                .extend(factory.production_lines.clone());
+            merged
+                .production_line_timestamps
+                .extend(factory.production_line_timestamps.clone());
+            merged.raw_inputs.extend(factory.raw_inputs.clone());
+            merged
+                .power_generators
+                .extend(factory.power_generators.clone());
+            merged
+                .internal_transfers
+                .extend(factory.internal_transfers.clone());
+            merged
+                .storage_buffers
+                .extend(factory.storage_buffers.clone());
+            merged.tasks.extend(factory.tasks.iter().cloned());
+        }
+
+        let mut internal = Vec::new();
+        let mut external = Vec::new();
+        let connected: HashSet<LogisticsId> = ids
+            .iter()
+            .flat_map(|id| {
+                let lines = &self.logistics_lines;
+                lines.inbound_ids(*id).iter().chain(lines.outbound_ids(*id))
+            })
+            .copied()
+            .collect();
+        for line in connected
+            .iter()
+            .filter_map(|id| self.logistics_lines.get(id))
+        {
+            let from_inside = ids.contains(&line.from_factory);
+            let to_inside = ids.contains(&line.to_factory);
+            if from_inside && to_inside {
+                // Keep the former line documented as a transfer between the old factories
+                let area = |id: &FactoryId| self.factories[id].name.clone();
+                for flow in line.get_items() {
+                    let transfer = InternalTransfer {
+                        id: InternalTransferId::new_v4(),
+                        item: flow.item,
+                        quantity_per_min: flow.quantity_per_min,
+                        from_area: area(&line.from_factory),
+                        to_area: area(&line.to_factory),
+                    };
+                    merged.internal_transfers.insert(transfer.id, transfer);
+                }
+                internal.push(line.id);
+            } else if from_inside || to_inside {
+                external.push(line.id);
+            }
+        }
+        internal.sort();
+        external.sort();
+
+        Ok(MergePlan {
+            factory: merged,
+            internal,
+            external,
+        })
+    }
+
+    // ========== Factory Clone ==========
+
+    /// Copy a factory with everything it contains under fresh IDs
+    ///
+    /// Outbound logistics lines are not copied, since the copy has no
+    /// destination for them yet. With `clone_logistics`, each of them becomes
+    /// an open task on the copy instead, so nothing is counted in any balance
+    /// until the player connects the line.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The factory to copy
+    /// * `new_name` - Name of the copy
+    /// * `clone_logistics` - Add a task to the copy for each line leaving the factory
+    ///
+    /// # Returns
+    ///
+    /// The ID of the new factory
+    pub fn clone_factory(
+        &mut self,
+        id: FactoryId,
+        new_name: &str,
+        clone_logistics: bool,
+    ) -> Result<FactoryId, Box<dyn std::error::Error>> {
+        self.revision += 1;
+        if new_name.trim().is_empty() {
+            return Err("New factory name cannot be empty".into());
+        }
+        let source = self
+            .factories
+            .get(&id)
+            .ok_or_else(|| format!("Factory with id {} does not exist", id))?;
+
+        let mut copy = source.duplicate(FactoryId::new_v4(), new_name.to_string());
+        if clone_logistics {
+            let mut outbound: Vec<&LogisticsFlux> = self.logistics_lines.outbound(id).collect();
+            outbound.sort_by_key(|line| line.transport_type.transport_number());
+
+            for line in outbound {
+                let destination = self
+                    .factories
+                    .get(&line.to_factory)
+                    .map_or("an unknown factory", |factory| factory.name.as_str());
+                copy.add_task(FactoryTask::new(
+                    FactoryTaskId::new_v4(),
+                    format!(
+                        "Connect a copy of {} to {}",
+                        line.transport_details, destination
+                    ),
+                    None,
+                )?)?;
+            }
+        }
+
+        let copy_id = copy.id;
+        self.factories.insert(copy_id, copy);
+        self.emit(EngineEvent::FactoryCreated(copy_id));
+
+        Ok(copy_id)
+    }
+
+    // ========== Production Line Transfer ==========
+
+    /// Copy a production line into a factory, possibly the one it is in
+    ///
+    /// The copy gets new IDs and keeps the recipe, machine groups, clock
+    /// speeds and somersloops.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The factory the line is in
+    /// * `line_id` - The production line to copy
+    /// * `to` - The factory receiving the copy
+    ///
+    /// # Returns
+    ///
+    /// The ID of the copy, or an error if a factory or the line doesn't exist
+    pub fn copy_production_line(
+        &mut self,
+        from: FactoryId,
+        line_id: ProductionLineId,
+        to: FactoryId,
+    ) -> Result<ProductionLineId, Box<dyn std::error::Error>> {
+        self.revision += 1;
+        let copy = self.factory_production_line(from, line_id)?.with_new_ids();
+        let copy_id = copy.id();
+        self.get_factory_mut(to)
+            .ok_or_else(|| format!("Factory with id {} does not exist", to))?
+            .add_production_line(copy);
+        Ok(copy_id)
+    }
+
+    /// Move a production line to another factory
+    ///
+    /// The line keeps its ID and creation time, and the tasks about it move
+    /// with it, as when splitting a factory.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The factory the line is in
+    /// * `line_id` - The production line to move
+    /// * `to` - The factory receiving the line
+    ///
+    /// # Returns
+    ///
+    /// An error if a factory or the line doesn't exist, or both factories are
+    /// the same
+    pub fn move_production_line(
+        &mut self,
+        from: FactoryId,
+        line_id: ProductionLineId,
+        to: FactoryId,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.revision += 1;
+        self.factory_production_line(from, line_id)?;
+        if !self.factories.contains_key(&to) {
+            return Err(format!("Factory with id {} does not exist", to).into());
+        }
+        if from == to {
+            return Err(format!("Production line {} is already in factory {}", line_id, to).into());
+        }
+
+        let source = self.get_factory_mut(from).expect("checked above");
+        let line = source
+            .production_lines
+            .remove(&line_id)
+            .expect("checked above");
+        let timestamps = source.production_line_timestamps.remove(&line_id);
+        let (tasks, kept) = std::mem::take(&mut source.tasks)
+            .into_iter()
+            .partition(|task| task.production_line == Some(line_id));
+        source.tasks = kept;
+
+        let destination = self.get_factory_mut(to).expect("checked above");
+        destination.add_production_line(line);
+        if let Some(timestamps) = timestamps {
+            destination
+                .production_line_timestamps
+                .insert(line_id, timestamps);
+        }
+        destination.tasks.extend(tasks);
+        Ok(())
+    }
+
+    /// A production line of a factory
+    fn factory_production_line(
+        &self,
+        factory_id: FactoryId,
+        line_id: ProductionLineId,
+    ) -> Result<&ProductionLine, Box<dyn std::error::Error>> {
+        self.factories
+            .get(&factory_id)
+            .ok_or_else(|| format!("Factory with id {} does not exist", factory_id))?
+            .production_lines
+            .get(&line_id)
+            .ok_or_else(|| {
+                format!(
+                    "Production line with id {} not found in factory {}",
+                    line_id, factory_id
+                )
+                .into()
+            })
+    }
+
+    // ========== Factory Split ==========
+
+    /// Move a selection of production lines, raw inputs and power generators
+    /// out of a factory into a new one
+    ///
+    /// With `create_buses`, items the moved parts consume are shipped from the
+    /// source factory and items they produce are shipped back, on one
+    /// auto-named bus per direction, so the combined balance of both factories
+    /// is unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `factory_id` - The factory to split
+    /// * `selection` - The parts to move (at least one)
+    /// * `new_name` - Name of the new factory
+    /// * `create_buses` - Whether to create buses for the crossing flows
+    ///
+    /// # Returns
+    ///
+    /// The new factory ID and the bus lines created for the crossing flows
+    pub fn split_factory(
+        &mut self,
+        factory_id: FactoryId,
+        selection: &FactorySplitSelection,
+        new_name: &str,
+        create_buses: bool,
+    ) -> Result<FactorySplitResult, Box<dyn std::error::Error>> {
+        self.revision += 1;
+        if new_name.trim().is_empty() {
+            return Err("New factory name cannot be empty".into());
+        }
+        if selection.is_empty() {
+            return Err(
+                "At least one production line, raw input or power generator is required to split a factory"
+                    .into(),
             );
         }
 
-        let save_file: SaveFile = serde_json::from_str(json)?;
-        Ok(save_file.engine)
+        let source = self
+            .factories
+            .get(&factory_id)
+            .ok_or_else(|| format!("Factory with id {} does not exist", factory_id))?;
+        for line_id in &selection.production_line_ids {
+            if !source.production_lines.contains_key(line_id) {
+                return Err(format!(
+                    "Production line with id {} not found in factory {}",
+                    line_id, factory_id
+                )
+                .into());
+            }
+        }
+        for raw_input_id in &selection.raw_input_ids {
+            if !source.raw_inputs.contains_key(raw_input_id) {
+                return Err(format!(
+                    "Raw input with id {} not found in factory {}",
+                    raw_input_id, factory_id
+                )
+                .into());
+            }
+        }
+        for generator_id in &selection.power_generator_ids {
+            if !source.power_generators.contains_key(generator_id) {
+                return Err(format!(
+                    "Power generator with id {} not found in factory {}",
+                    generator_id, factory_id
+                )
+                .into());
+            }
+        }
+        let source_name = source.name.clone();
+        let production_line_ids = &selection.production_line_ids;
+
+        let mut new_factory = Factory::new(FactoryId::new_v4(), new_name.to_string(), None);
+        new_factory.set_balance(self.balance.clone());
+        if let Some(source) = self.factories.get_mut(&factory_id) {
+            source.touch();
+            new_factory.power_grid = source.power_grid;
+            for line_id in production_line_ids {
+                if let Some(line) = source.production_lines.remove(line_id) {
+                    new_factory.production_lines.insert(*line_id, line);
+                }
+                if let Some(timestamps) = source.production_line_timestamps.remove(line_id) {
+                    new_factory
+                        .production_line_timestamps
+                        .insert(*line_id, timestamps);
+                }
+            }
+            // Tasks about a moved line move with it
+            let (moved, kept) = std::mem::take(&mut source.tasks)
+                .into_iter()
+                .partition(|task| {
+                    task.production_line
+                        .is_some_and(|id| production_line_ids.contains(&id))
+                });
+            source.tasks = kept;
+            new_factory.tasks = moved;
+            for raw_input_id in &selection.raw_input_ids {
+                if let Some(raw_input) = source.raw_inputs.remove(raw_input_id) {
+                    new_factory.raw_inputs.insert(*raw_input_id, raw_input);
+                }
+            }
+            for generator_id in &selection.power_generator_ids {
+                if let Some(generator) = source.power_generators.remove(generator_id) {
+                    new_factory
+                        .power_generators
+                        .insert(*generator_id, generator);
+                }
+            }
+        }
+
+        // Net rate of every item across the moved parts, without logistics
+        let mut moved_parts = new_factory.clone();
+        moved_parts.calculate_item(&HashMap::new());
+        let mut moved_balance: Vec<(Item, f64)> = moved_parts
+            .items
+            .into_iter()
+            .filter(|(_, qty)| !rate::is_zero(*qty))
+            .collect();
+        moved_balance.sort_by(|a, b| models::item_name(a.0).cmp(models::item_name(b.0)));
+
+        let new_factory_id = new_factory.id;
+        self.factories.insert(new_factory_id, new_factory);
+        self.emit(EngineEvent::FactoryUpdated(factory_id));
+        self.emit(EngineEvent::FactoryCreated(new_factory_id));
+        if !create_buses {
+            return Ok(FactorySplitResult {
+                source_factory: factory_id,
+                new_factory: new_factory_id,
+                logistics_ids: Vec::new(),
+            });
+        }
+
+        let inbound: Vec<(Item, f64)> = moved_balance
+            .iter()
+            .filter(|(_, qty)| *qty < 0.0)
+            .map(|(item, qty)| (*item, -qty))
+            .collect();
+        let outbound: Vec<(Item, f64)> = moved_balance
+            .iter()
+            .filter(|(_, qty)| *qty > 0.0)
+            .map(|(item, qty)| (*item, *qty))
+            .collect();
+
+        let mut logistics_ids = Vec::new();
+        for (from, to, flows) in [
+            (factory_id, new_factory_id, inbound),
+            (new_factory_id, factory_id, outbound),
+        ] {
+            if flows.is_empty() {
+                continue;
+            }
+            let (bus_id, bus_name) = self.allocate_transport_name(TransportKind::Bus);
+            let bus = Self::bus_for_flows(bus_id, bus_name, &flows);
+            let logistics_id = self.create_logistics_line(
+                from,
+                to,
+                TransportType::Bus(bus),
+                format!("Created by splitting factory {}", source_name),
+            )?;
+            logistics_ids.push(logistics_id);
+        }
+
+        Ok(FactorySplitResult {
+            source_factory: factory_id,
+            new_factory: new_factory_id,
+            logistics_ids,
+        })
     }
-}
 
-impl SaveFile {
-    /// Create a new save file from an engine instance
-    pub fn new(engine: SatisflowEngine) -> Self {
-        let now = Utc::now();
-        Self {
-            version: env!("CARGO_PKG_VERSION").to_string(),
-            created_at: now,
-            last_modified: now,
-            game_version: None,
-            engine,
+    /// Build a bus carrying the given flows, using the smallest fitting conveyor
+    /// or pipeline tier and adding parallel segments when one is not enough
+    fn bus_for_flows(bus_id: u64, bus_name: String, flows: &[(Item, f64)]) -> Bus {
+        let mut bus = Bus::new(bus_id, bus_name);
+        for (item, quantity) in flows {
+            let mut remaining = *quantity;
+            while !rate::is_zero(remaining) && remaining > 0.0 {
+                if item.is_fluid() {
+                    let chunk = remaining.min(PipelineCapacity::MK2_CAPACITY);
+                    let capacity =
+                        PipelineCapacity::smallest_for(chunk).unwrap_or(PipelineCapacity::Mk2);
+                    let pipeline_id = bus.pipelines.len() as u64 + 1;
+                    bus.add_pipeline(Pipeline::new(pipeline_id, capacity, *item, chunk));
+                    remaining -= chunk;
+                } else {
+                    let chunk = remaining.min(ConveyorSpeed::MK6_SPEED);
+                    let speed = ConveyorSpeed::smallest_for(chunk).unwrap_or(ConveyorSpeed::Mk6);
+                    let line_id = bus.lines.len() as u64 + 1;
+                    bus.add_conveyor(Conveyor::new(line_id, speed, *item, chunk));
+                    remaining -= chunk;
+                }
+            }
         }
+        bus
     }
 
-    /// Update the last_modified timestamp
-    pub fn update_timestamp(&mut self) {
-        self.last_modified = Utc::now();
-    }
+    /// Preview deleting a factory without modifying the engine
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The factory to delete
+    ///
+    /// # Returns
+    ///
+    /// The logistics lines and goals that would go with the factory and the
+    /// deficits its missing shipments would cause elsewhere, or an error if the
+    /// factory doesn't exist
+    pub fn preview_delete_factory(
+        &self,
+        id: FactoryId,
+    ) -> Result<FactoryDeletePreview, Box<dyn std::error::Error>> {
+        let factory = self
+            .factories
+            .get(&id)
+            .ok_or_else(|| format!("Factory with id {} does not exist", id))?;
+
+        let mut remaining = self.logistics_lines.clone();
+        let mut removed = remaining.remove_factory(id);
+        removed.sort_by_key(|line| line.id);
+
+        let mut neighbours: Vec<FactoryId> = removed
+            .iter()
+            .flat_map(|line| [line.from_factory, line.to_factory])
+            .filter(|neighbour| *neighbour != id)
+            .collect();
+        neighbours.sort();
+        neighbours.dedup();
+
+        let mut deficits = Vec::new();
+        for neighbour in neighbours.iter().filter_map(|id| self.factories.get(id)) {
+            let mut before = neighbour.clone();
+            before.calculate_item(&self.logistics_lines);
+            let mut after = neighbour.clone();
+            after.calculate_item(&remaining);
+            for (item, balance_after) in &after.items {
+                let balance_before = before.items.get(item).copied().unwrap_or(0.0);
+                if *balance_after < 0.0
+                    && !rate::is_zero(*balance_after)
+                    && !rate::is_zero(balance_before - balance_after)
+                {
+                    deficits.push(DownstreamDeficit {
+                        factory_id: neighbour.id,
+                        factory_name: neighbour.name.clone(),
+                        item: *item,
+                        balance_before,
+                        balance_after: *balance_after,
+                    });
+                }
+            }
+        }
+        deficits.sort_by(|a, b| {
+            a.factory_name
+                .cmp(&b.factory_name)
+                .then(a.factory_id.cmp(&b.factory_id))
+                .then(models::item_name(a.item).cmp(models::item_name(b.item)))
+        });
+
+        let mut goals: Vec<GoalId> = self
+            .goals
+            .values()
+            .filter(|goal| goal.factory_id == Some(id))
+            .map(|goal| goal.id)
+            .collect();
+        goals.sort();
+
+        Ok(FactoryDeletePreview {
+            factory_id: id,
+            name: factory.name.clone(),
+            logistics: removed
+                .into_iter()
+                .map(|line| RemovedLogisticsLine {
+                    id: line.id,
+                    from_factory: line.from_factory,
+                    to_factory: line.to_factory,
+                    transport_name: line.transport_type.get_transport_name(),
+                    items: line.get_items(),
+                })
+                .collect(),
+            goals,
+            deficits,
+        })
+    }
+
+    /// Delete a factory and its connected logistics lines
+    pub fn delete_factory(&mut self, id: FactoryId) -> Result<(), Box<dyn std::error::Error>> {
+        self.revision += 1;
+        // Check if factory exists
+        if !self.factories.contains_key(&id) {
+            return Err(format!("Factory with id {} does not exist", id).into());
+        }
+
+        // Remove all logistics lines connected to this factory
+        let removed_lines = self.logistics_lines.remove_factory(id);
+
+        // Remove the factory
+        self.factories.remove(&id).ok_or("Factory not found")?;
+
+        for line in removed_lines {
+            self.emit(EngineEvent::LogisticsDeleted(line.id));
+        }
+        // Pins and goals scoped to the factory go with it
+        self.release_factory(id, None);
+        self.emit(EngineEvent::FactoryDeleted(id));
+        Ok(())
+    }
+
+    /// Hand the pins and goals of a removed factory to the factory replacing
+    /// it, or drop them when nothing replaces it
+    fn release_factory(&mut self, id: FactoryId, successor: Option<FactoryId>) {
+        let pins_changed = match successor {
+            Some(successor) => self.pinned.replace_factory(id, successor),
+            None => self.pinned.unpin_factory(id),
+        };
+        self.pins_changed(pins_changed);
+
+        let changed_goals: Vec<GoalId> = self
+            .goals
+            .values()
+            .filter(|goal| goal.factory_id == Some(id))
+            .map(|goal| goal.id)
+            .collect();
+        match successor {
+            Some(successor) => {
+                for goal_id in &changed_goals {
+                    if let Some(goal) = self.goals.get_mut(goal_id) {
+                        goal.factory_id = Some(successor);
+                    }
+                    if let Some(progress) = self.goal_progress.get_mut(goal_id) {
+                        progress.factory_id = Some(successor);
+                    }
+                }
+            }
+            None => {
+                self.goals.retain(|_, goal| goal.factory_id != Some(id));
+                let goals = &self.goals;
+                self.goal_progress.retain(|id, _| goals.contains_key(id));
+            }
+        }
+        for goal_id in changed_goals {
+            self.emit(EngineEvent::GoalChanged(goal_id));
+        }
+    }
+
+    /// Delete a logistics line
+    pub fn delete_logistics_line(
+        &mut self,
+        id: LogisticsId,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.revision += 1;
+        // Check if logistics line exists
+        if !self.logistics_lines.contains_key(&id) {
+            return Err(format!("Logistics line with id {} does not exist", id).into());
+        }
+
+        self.logistics_lines
+            .remove(&id)
+            .ok_or("Logistics line not found")?;
+
+        self.emit(EngineEvent::LogisticsDeleted(id));
+        Ok(())
+    }
+
+    /// Reset the engine to an empty state (clear all factories and logistics)
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or failure
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use satisflow_engine::SatisflowEngine;
+    ///
+    /// let mut engine = SatisflowEngine::new();
+    /// engine.create_factory("Test Factory".to_string(), None);
+    /// assert_eq!(engine.get_all_factories().len(), 1);
+    ///
+    /// engine.reset().unwrap();
+    /// assert_eq!(engine.get_all_factories().len(), 0);
+    /// ```
+    pub fn reset(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        self.revision += 1;
+        self.factories.clear();
+        self.logistics_lines.clear();
+        self.blueprint_templates.clear();
+        self.factory_templates.clear();
+        self.pinned = PinnedEntities::default();
+        self.goals.clear();
+        self.goal_progress.clear();
+        self.tier_progress = None;
+        self.custom_recipes.clear();
+        self.emit(EngineEvent::StateReplaced);
+        Ok(())
+    }
+
+    // ========== Pinned Entities ==========
+
+    /// Get the pinned factories and items
+    pub fn pinned(&self) -> &PinnedEntities {
+        &self.pinned
+    }
+
+    /// Pin a factory to the focused dashboard
+    ///
+    /// # Returns
+    ///
+    /// `true` if the factory was newly pinned, or an error if it doesn't exist
+    pub fn pin_factory(&mut self, id: FactoryId) -> Result<bool, Box<dyn std::error::Error>> {
+        self.revision += 1;
+        if !self.factories.contains_key(&id) {
+            return Err(format!("Factory with id {} does not exist", id).into());
+        }
+        let pinned = self.pinned.pin_factory(id);
+        self.pins_changed(pinned);
+        Ok(pinned)
+    }
+
+    /// Unpin a factory; returns `false` if it was not pinned
+    pub fn unpin_factory(&mut self, id: FactoryId) -> bool {
+        self.revision += 1;
+        let unpinned = self.pinned.unpin_factory(id);
+        self.pins_changed(unpinned)
+    }
+
+    /// Pin an item to the focused dashboard; returns `false` if it was already pinned
+    pub fn pin_item(&mut self, item: Item) -> bool {
+        self.revision += 1;
+        let pinned = self.pinned.pin_item(item);
+        self.pins_changed(pinned)
+    }
+
+    /// Unpin an item; returns `false` if it was not pinned
+    pub fn unpin_item(&mut self, item: Item) -> bool {
+        self.revision += 1;
+        let unpinned = self.pinned.unpin_item(item);
+        self.pins_changed(unpinned)
+    }
+
+    /// Tell observers about a pin or unpin when it `changed` anything
+    fn pins_changed(&self, changed: bool) -> bool {
+        if changed {
+            self.emit(EngineEvent::PinsChanged);
+        }
+        changed
+    }
+
+    // ========== Custom Recipes ==========
+
+    /// Create a user-defined recipe
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Unique name, distinct from every built-in recipe
+    /// * `machine` - Building running the recipe
+    /// * `inputs` - Items consumed per cycle
+    /// * `outputs` - Items produced per cycle
+    /// * `cycle_time` - Seconds per cycle at 100% clock speed
+    ///
+    /// # Returns
+    ///
+    /// Result containing the ID of the created recipe, or an error if it is invalid
+    /// or its name is taken
+    pub fn create_custom_recipe(
+        &mut self,
+        name: String,
+        machine: MachineType,
+        inputs: Vec<(Item, f64)>,
+        outputs: Vec<(Item, f64)>,
+        cycle_time: f64,
+    ) -> Result<CustomRecipeId, Box<dyn std::error::Error>> {
+        self.revision += 1;
+        let id = CustomRecipeId::new_v4();
+        let recipe = CustomRecipe::new(id, name, machine, inputs, outputs, cycle_time);
+        self.insert_custom_recipe(recipe)?;
+        self.emit(EngineEvent::CustomRecipeChanged(id));
+        Ok(id)
+    }
+
+    /// Replace an existing custom recipe
+    ///
+    /// Production lines and blueprint templates running the recipe pick up the
+    /// new definition.
+    pub fn replace_custom_recipe(
+        &mut self,
+        recipe: CustomRecipe,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.revision += 1;
+        if !self.custom_recipes.contains_key(&recipe.id) {
+            return Err(format!("Custom recipe with id {} does not exist", recipe.id).into());
+        }
+        self.insert_custom_recipe(recipe.clone())?;
+
+        for line in self.custom_recipe_lines_mut(recipe.id) {
+            line.recipe = LineRecipe::Custom(recipe.clone());
+        }
+        self.emit(EngineEvent::CustomRecipeChanged(recipe.id));
+        Ok(())
+    }
+
+    fn insert_custom_recipe(
+        &mut self,
+        recipe: CustomRecipe,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        recipe.validate()?;
+        if self
+            .custom_recipes
+            .values()
+            .any(|other| other.id != recipe.id && other.name == recipe.name)
+        {
+            return Err(format!("A custom recipe named '{}' already exists", recipe.name).into());
+        }
+        self.custom_recipes.insert(recipe.id, recipe);
+        Ok(())
+    }
+
+    /// Get a custom recipe by ID
+    pub fn get_custom_recipe(&self, id: CustomRecipeId) -> Option<&CustomRecipe> {
+        self.custom_recipes.get(&id)
+    }
+
+    /// Get all custom recipes
+    pub fn get_all_custom_recipes(&self) -> &HashMap<CustomRecipeId, CustomRecipe> {
+        &self.custom_recipes
+    }
+
+    /// Delete a custom recipe
+    ///
+    /// # Returns
+    ///
+    /// The deleted recipe, or an error if it does not exist or a production
+    /// line still runs it
+    pub fn delete_custom_recipe(
+        &mut self,
+        id: CustomRecipeId,
+    ) -> Result<CustomRecipe, Box<dyn std::error::Error>> {
+        self.revision += 1;
+        if !self.custom_recipes.contains_key(&id) {
+            return Err(format!("Custom recipe with id {} does not exist", id).into());
+        }
+        let users = self.custom_recipe_lines_mut(id).len();
+        if users > 0 {
+            return Err(format!(
+                "Custom recipe with id {} is used by {} production line(s)",
+                id, users
+            )
+            .into());
+        }
+        let recipe = self.custom_recipes.remove(&id).expect("checked above");
+        self.emit(EngineEvent::CustomRecipeChanged(id));
+        Ok(recipe)
+    }
+
+    /// Find a recipe by display name, built-in recipes first, then custom ones
+    pub fn resolve_recipe(&self, name: &str) -> Option<LineRecipe> {
+        recipe_by_name(name).map(LineRecipe::Standard).or_else(|| {
+            self.custom_recipes
+                .values()
+                .find(|recipe| recipe.name == name)
+                .cloned()
+                .map(LineRecipe::Custom)
+        })
+    }
+
+    /// Every recipe line, in factories or blueprint templates, running a custom recipe
+    fn custom_recipe_lines_mut(&mut self, id: CustomRecipeId) -> Vec<&mut ProductionLineRecipe> {
+        let factory_lines = self
+            .factories
+            .values_mut()
+            .chain(
+                self.factory_templates
+                    .values_mut()
+                    .map(|template| &mut template.factory),
+            )
+            .flat_map(|factory| factory.production_lines.values_mut());
+        let template_lines = self
+            .blueprint_templates
+            .values_mut()
+            .flat_map(|blueprint| blueprint.production_lines.iter_mut());
+
+        factory_lines
+            .flat_map(|line| match line {
+                ProductionLine::ProductionLineRecipe(recipe) => vec![recipe],
+                ProductionLine::ProductionLineBlueprint(blueprint) => {
+                    blueprint.production_lines.iter_mut().collect()
+                }
+            })
+            .chain(template_lines)
+            .filter(|line| line.recipe.custom_id() == Some(id))
+            .collect()
+    }
+
+    // ========== Game Data ==========
+
+    /// Get the game data set the plan is made against
+    pub fn game_data(&self) -> &'static GameDataSet {
+        self.game_data
+    }
+
+    /// Plan against another game data version, e.g. after a game patch
+    ///
+    /// # Returns
+    ///
+    /// An error if no data set of that version is available
+    pub fn set_game_data_version(
+        &mut self,
+        version: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.revision += 1;
+        self.game_data = GameDataSet::find(version)?;
+        self.emit(EngineEvent::GameDataChanged);
+        Ok(())
+    }
+
+    /// Whether the save plans the seasonal FICSMAS items and recipes
+    pub fn ficsmas_enabled(&self) -> bool {
+        self.ficsmas
+    }
+
+    /// Turn the FICSMAS items and recipes on or off for this save
+    ///
+    /// While off, FICSMAS items are left out of the item balance and
+    /// diagnostics, though lines making them are kept.
+    ///
+    /// # Returns
+    ///
+    /// An error when turning them on in a build without the `ficsmas` feature
+    pub fn set_ficsmas_enabled(&mut self, enabled: bool) -> Result<(), Box<dyn std::error::Error>> {
+        self.revision += 1;
+        if enabled && !ficsmas::is_available() {
+            return Err("FICSMAS is not available in this build".into());
+        }
+        self.ficsmas = enabled;
+        self.emit(EngineEvent::GameDataChanged);
+        Ok(())
+    }
+
+    /// Game constants the plan is calculated with
+    pub fn game_balance(&self) -> &GameBalance {
+        &self.balance
+    }
+
+    /// Calculate the plan with other game constants, e.g. to model a mod
+    ///
+    /// # Arguments
+    ///
+    /// * `balance` - Overclock exponent, purity multipliers and generator
+    ///   outputs; [`GameBalance::default`] restores the game's
+    ///
+    /// # Returns
+    ///
+    /// An error if a constant is negative or not a number
+    pub fn set_game_balance(
+        &mut self,
+        balance: GameBalance,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.revision += 1;
+        balance.validate()?;
+        self.balance = balance;
+        self.share_balance();
+        self.emit(EngineEvent::BalanceChanged);
+        Ok(())
+    }
+
+    /// Hand the engine's game constants to every factory
+    fn share_balance(&mut self) {
+        for factory in self.factories.values_mut() {
+            factory.set_balance(self.balance.clone());
+        }
+    }
+
+    /// Items left out of the balance and diagnostics
+    fn hidden_items(&self) -> &'static [Item] {
+        if self.ficsmas {
+            &[]
+        } else {
+            &FICSMAS_ITEMS
+        }
+    }
+
+    // ========== Tier Progress ==========
+
+    /// Get the recorded HUB tiers and MAM research, if any
+    pub fn tier_progress(&self) -> Option<&TierProgress> {
+        self.tier_progress.as_ref()
+    }
+
+    /// Record the save's progression, or forget it with `None`
+    ///
+    /// # Returns
+    ///
+    /// An error if the HUB tier is out of range or a listed alternate isn't one
+    pub fn set_tier_progress(
+        &mut self,
+        progress: Option<TierProgress>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.revision += 1;
+        if let Some(progress) = &progress {
+            progress.validate()?;
+        }
+        self.tier_progress = progress;
+        self.emit(EngineEvent::TierProgressChanged);
+        Ok(())
+    }
+
+    /// Describe the recipes of a production line that are beyond the recorded progression
+    ///
+    /// # Returns
+    ///
+    /// One message per missing unlock; always empty when no progression is recorded
+    pub fn progression_warnings(&self, line: &ProductionLine) -> Vec<String> {
+        let Some(progress) = &self.tier_progress else {
+            return Vec::new();
+        };
+        // Custom recipes are house rules, so only built-in ones are checked
+        let recipes: Vec<Recipe> = match line {
+            ProductionLine::ProductionLineRecipe(recipe) => {
+                recipe.recipe.standard().into_iter().collect()
+            }
+            ProductionLine::ProductionLineBlueprint(blueprint) => blueprint
+                .production_lines
+                .iter()
+                .filter_map(|recipe| recipe.recipe.standard())
+                .collect(),
+        };
+
+        let mut warnings: Vec<String> = Vec::new();
+        for recipe in recipes {
+            for warning in progress.missing_unlocks(recipe) {
+                if !warnings.contains(&warning) {
+                    warnings.push(warning);
+                }
+            }
+        }
+        warnings
+    }
+
+    // ========== Recent Changes ==========
+
+    /// List the most recently modified factories, production lines and logistics lines
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - Maximum number of entries to return
+    ///
+    /// # Returns
+    ///
+    /// Changes sorted by modification time, newest first
+    pub fn recent_changes(&self, limit: usize) -> Vec<RecentChange> {
+        let mut changes = Vec::new();
+        for factory in self.factories.values() {
+            changes.push(RecentChange::new(
+                ChangedEntity::Factory,
+                factory.id,
+                factory.name.clone(),
+                factory.id,
+                factory.timestamps,
+            ));
+            for line in factory.production_lines.values() {
+                changes.push(RecentChange::new(
+                    ChangedEntity::ProductionLine,
+                    line.id(),
+                    line.name().to_string(),
+                    factory.id,
+                    factory
+                        .production_line_timestamps(line.id())
+                        .unwrap_or_default(),
+                ));
+            }
+        }
+        for line in self.logistics_lines.values() {
+            changes.push(RecentChange::new(
+                ChangedEntity::Logistics,
+                line.id,
+                line.transport_type
+                    .get_transport_name()
+                    .unwrap_or_else(|| line.transport_type.get_transport_id()),
+                line.from_factory,
+                line.timestamps,
+            ));
+        }
+
+        changes.sort_by(|a, b| {
+            b.updated_at
+                .cmp(&a.updated_at)
+                .then_with(|| a.name.cmp(&b.name))
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        changes.truncate(limit);
+        changes
+    }
+
+    // ========== Factory Templates ==========
+
+    /// Save a copy of a factory as a template for new factories
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The factory to save
+    /// * `name` - Name of the template, defaults to the factory's
+    /// * `description` - Description of the template, defaults to the factory's
+    ///
+    /// # Returns
+    ///
+    /// The ID of the template, or an error if the factory doesn't exist or the
+    /// name is empty
+    pub fn save_factory_as_template(
+        &mut self,
+        id: FactoryId,
+        name: Option<&str>,
+        description: Option<String>,
+    ) -> Result<FactoryTemplateId, Box<dyn std::error::Error>> {
+        self.revision += 1;
+        let factory = self
+            .factories
+            .get(&id)
+            .ok_or_else(|| format!("Factory with id {} does not exist", id))?;
+        let name = name.unwrap_or(&factory.name).trim();
+        if name.is_empty() {
+            return Err("Template name cannot be empty".into());
+        }
+
+        let template = FactoryTemplate::from_factory(
+            FactoryTemplateId::new_v4(),
+            name.to_string(),
+            description.or_else(|| factory.description.clone()),
+            factory,
+        );
+        let template_id = template.id;
+        self.factory_templates.insert(template_id, template);
+        self.emit(EngineEvent::FactoryTemplateChanged(template_id));
+        Ok(template_id)
+    }
+
+    /// Get a factory template by ID
+    pub fn get_factory_template(&self, id: FactoryTemplateId) -> Option<&FactoryTemplate> {
+        self.factory_templates.get(&id)
+    }
+
+    /// Get all factory templates
+    pub fn get_all_factory_templates(&self) -> &HashMap<FactoryTemplateId, FactoryTemplate> {
+        &self.factory_templates
+    }
+
+    /// Delete a factory template; factories made from it are kept
+    ///
+    /// # Returns
+    ///
+    /// The deleted template, or an error if it does not exist
+    pub fn delete_factory_template(
+        &mut self,
+        id: FactoryTemplateId,
+    ) -> Result<FactoryTemplate, Box<dyn std::error::Error>> {
+        self.revision += 1;
+        let template = self
+            .factory_templates
+            .remove(&id)
+            .ok_or_else(|| format!("Factory template with id {} does not exist", id))?;
+        self.emit(EngineEvent::FactoryTemplateChanged(id));
+        Ok(template)
+    }
+
+    /// Create a factory from a template
+    ///
+    /// Every production line, raw input, generator and task of the template is
+    /// copied with new IDs. The factory starts without a power grid or map
+    /// position.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The template to instantiate
+    /// * `name` - Name of the new factory
+    ///
+    /// # Returns
+    ///
+    /// The ID of the new factory, or an error if the template doesn't exist or
+    /// the name is empty
+    pub fn instantiate_factory_template(
+        &mut self,
+        id: FactoryTemplateId,
+        name: &str,
+    ) -> Result<FactoryId, Box<dyn std::error::Error>> {
+        self.revision += 1;
+        if name.trim().is_empty() {
+            return Err("Factory name cannot be empty".into());
+        }
+        let template = self
+            .factory_templates
+            .get(&id)
+            .ok_or_else(|| format!("Factory template with id {} does not exist", id))?;
+
+        let mut factory = template.instantiate(FactoryId::new_v4(), name.trim().to_string());
+        factory.set_balance(self.balance.clone());
+        let factory_id = factory.id;
+        self.factories.insert(factory_id, factory);
+        self.emit(EngineEvent::FactoryCreated(factory_id));
+        Ok(factory_id)
+    }
+
+    // ========== Blueprint Template Management ==========
+
+    /// Add a blueprint template to the library
+    ///
+    /// # Arguments
+    ///
+    /// * `blueprint` - The blueprint template to add
+    ///
+    /// # Returns
+    ///
+    /// The ID of the added blueprint template
+    pub fn add_blueprint_template(
+        &mut self,
+        mut blueprint: ProductionLineBlueprint,
+    ) -> ProductionLineId {
+        self.revision += 1;
+        let id = blueprint.id;
+        blueprint
+            .revision
+            .get_or_insert_with(TemplateRevision::default);
+        self.blueprint_templates.insert(id, blueprint);
+        self.emit(EngineEvent::BlueprintTemplateChanged(id));
+        id
+    }
+
+    /// Add a new version of a blueprint template to the library
+    ///
+    /// The parent version stays in the library, so instances and the history
+    /// can still refer to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `parent_id` - The ID of the template being updated
+    /// * `blueprint` - The updated template, with its own ID
+    /// * `version` - Version number, defaults to the parent's next minor version
+    /// * `changelog` - What changed since the parent version
+    ///
+    /// # Returns
+    ///
+    /// Result containing the ID of the new version, or an error if the parent
+    /// doesn't exist or the version is not newer than the parent's
+    pub fn add_blueprint_template_version(
+        &mut self,
+        parent_id: ProductionLineId,
+        mut blueprint: ProductionLineBlueprint,
+        version: Option<TemplateVersion>,
+        changelog: Option<String>,
+    ) -> Result<ProductionLineId, Box<dyn std::error::Error>> {
+        self.revision += 1;
+        let parent_version = self
+            .get_blueprint_template(parent_id)
+            .ok_or_else(|| format!("Blueprint template {} not found", parent_id))?
+            .revision
+            .clone()
+            .unwrap_or_default()
+            .version;
+
+        let version = version.unwrap_or_else(|| parent_version.next_minor());
+        if version <= parent_version {
+            return Err(format!(
+                "Version {} must be newer than the parent's {}",
+                version, parent_version
+            )
+            .into());
+        }
+
+        blueprint.revision = Some(TemplateRevision {
+            version,
+            parent_id: Some(parent_id),
+            changelog,
+        });
+        Ok(self.add_blueprint_template(blueprint))
+    }
+
+    /// Get every version of a blueprint template
+    ///
+    /// Versions are linked through their parent; the history covers every
+    /// template descending from the same first version still in the library.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of any version of the template
+    ///
+    /// # Returns
+    ///
+    /// Result containing the versions, oldest first
+    pub fn blueprint_template_history(
+        &self,
+        id: ProductionLineId,
+    ) -> Result<Vec<&ProductionLineBlueprint>, Box<dyn std::error::Error>> {
+        if !self.blueprint_templates.contains_key(&id) {
+            return Err(format!("Blueprint template {} not found", id).into());
+        }
+
+        let root = self.blueprint_template_root(id);
+        let mut history: Vec<&ProductionLineBlueprint> = self
+            .blueprint_templates
+            .values()
+            .filter(|template| self.blueprint_template_root(template.id) == root)
+            .collect();
+        history.sort_by_key(|template| {
+            (
+                template.revision.clone().unwrap_or_default().version,
+                template.name.clone(),
+            )
+        });
+        Ok(history)
+    }
+
+    /// Oldest ancestor of a template still in the library
+    fn blueprint_template_root(&self, id: ProductionLineId) -> ProductionLineId {
+        let mut current = id;
+        let mut visited = std::collections::HashSet::from([id]);
+        while let Some(parent_id) = self
+            .blueprint_templates
+            .get(&current)
+            .and_then(|template| template.revision.as_ref())
+            .and_then(|revision| revision.parent_id)
+            .filter(|parent_id| self.blueprint_templates.contains_key(parent_id))
+        {
+            // Guard against cycles in hand-edited data
+            if !visited.insert(parent_id) {
+                break;
+            }
+            current = parent_id;
+        }
+        current
+    }
+
+    /// Get a blueprint template by ID
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the blueprint template
+    ///
+    /// # Returns
+    ///
+    /// Reference to the blueprint template if found
+    pub fn get_blueprint_template(&self, id: ProductionLineId) -> Option<&ProductionLineBlueprint> {
+        self.blueprint_templates.get(&id)
+    }
+
+    /// Get all blueprint templates
+    ///
+    /// # Returns
+    ///
+    /// Reference to the hash map of all blueprint templates
+    pub fn get_all_blueprint_templates(
+        &self,
+    ) -> &HashMap<ProductionLineId, ProductionLineBlueprint> {
+        &self.blueprint_templates
+    }
+
+    /// Remove a blueprint template from the library
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of the blueprint template to remove
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or an error if the template doesn't exist
+    pub fn remove_blueprint_template(
+        &mut self,
+        id: ProductionLineId,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.revision += 1;
+        if !self.blueprint_templates.contains_key(&id) {
+            return Err(format!("Blueprint template with id {} does not exist", id).into());
+        }
+        self.blueprint_templates.remove(&id);
+        self.emit(EngineEvent::BlueprintTemplateChanged(id));
+        Ok(())
+    }
+
+    /// Instantiate a blueprint template into a factory as a new production line.
+    ///
+    /// Creates an independent deep copy of the blueprint with new UUIDs assigned.
+    ///
+    /// # Arguments
+    ///
+    /// * `factory_id` - The ID of the target factory
+    /// * `blueprint_id` - The ID of the blueprint template to instantiate
+    /// * `custom_name` - Optional custom name for the instance (defaults to blueprint name)
+    ///
+    /// # Returns
+    ///
+    /// Result containing (instance_id, instance_name) on success
+    pub fn instantiate_blueprint_into_factory(
+        &mut self,
+        factory_id: FactoryId,
+        blueprint_id: ProductionLineId,
+        custom_name: Option<String>,
+    ) -> Result<(ProductionLineId, String), Box<dyn std::error::Error>> {
+        self.revision += 1;
+        // Get the blueprint template
+        let blueprint = self
+            .get_blueprint_template(blueprint_id)
+            .ok_or_else(|| format!("Blueprint template {} not found", blueprint_id))?
+            .clone();
+
+        // Validate blueprint has at least one production line
+        if blueprint.production_lines.is_empty() {
+            return Err("Blueprint must have at least 1 production line".into());
+        }
+
+        // Get factory and add blueprint
+        let factory = self
+            .get_factory_mut(factory_id)
+            .ok_or_else(|| format!("Factory {} not found", factory_id))?;
+
+        // Deep clone and regenerate UUIDs
+        let mut instance = blueprint.clone();
+        instance.id = ProductionLineId::new_v4();
+        for line in &mut instance.production_lines {
+            line.id = ProductionLineId::new_v4();
+        }
+
+        // Override name if custom name provided
+        let instance_name = custom_name.unwrap_or(blueprint.name.clone());
+        instance.name = instance_name.clone();
+        instance.template_id = Some(blueprint_id);
+
+        let instance_id = instance.id;
+        factory.add_production_line(ProductionLine::ProductionLineBlueprint(instance));
+
+        Ok((instance_id, instance_name))
+    }
+
+    /// Get every blueprint instance created from any version of a template
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of any version of the template
+    ///
+    /// # Returns
+    ///
+    /// Result containing the instances, sorted by factory then instance name
+    pub fn blueprint_template_instances(
+        &self,
+        id: ProductionLineId,
+    ) -> Result<Vec<TemplateInstance>, Box<dyn std::error::Error>> {
+        let history = self.blueprint_template_history(id)?;
+        let latest_id = history.last().map(|template| template.id);
+
+        let mut instances = Vec::new();
+        for factory in self.factories.values() {
+            for line in factory.production_lines.values() {
+                let ProductionLine::ProductionLineBlueprint(instance) = line else {
+                    continue;
+                };
+                let Some(template) = instance
+                    .template_id
+                    .and_then(|template_id| history.iter().find(|t| t.id == template_id))
+                else {
+                    continue;
+                };
+                instances.push(TemplateInstance {
+                    factory_id: factory.id,
+                    factory_name: factory.name.clone(),
+                    instance_id: instance.id,
+                    instance_name: instance.name.clone(),
+                    template_id: template.id,
+                    version: template.revision.clone().unwrap_or_default().version,
+                    up_to_date: Some(template.id) == latest_id,
+                });
+            }
+        }
+        instances.sort_by(|a, b| {
+            (
+                &a.factory_name,
+                a.factory_id,
+                &a.instance_name,
+                a.instance_id,
+            )
+                .cmp(&(
+                    &b.factory_name,
+                    b.factory_id,
+                    &b.instance_name,
+                    b.instance_id,
+                ))
+        });
+        Ok(instances)
+    }
+
+    /// Update every outdated instance of a template to its latest version
+    ///
+    /// Instances keep their ID, name and enabled flag; their lines are replaced
+    /// by the latest template's.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The ID of any version of the template
+    /// * `dry_run` - Only report what would change
+    ///
+    /// # Returns
+    ///
+    /// Result containing the changes per factory; factories with nothing to
+    /// update are left out
+    pub fn propagate_blueprint_template(
+        &mut self,
+        id: ProductionLineId,
+        dry_run: bool,
+    ) -> Result<TemplatePropagation, Box<dyn std::error::Error>> {
+        self.revision += 1;
+        let latest = self
+            .blueprint_template_history(id)?
+            .last()
+            .map(|template| (*template).clone())
+            .ok_or_else(|| format!("Blueprint template {} not found", id))?;
+        let outdated: Vec<TemplateInstance> = self
+            .blueprint_template_instances(id)?
+            .into_iter()
+            .filter(|instance| !instance.up_to_date)
+            .collect();
+
+        let mut factories: Vec<FactoryPropagation> = Vec::new();
+        for outdated_instance in outdated {
+            let factory = self
+                .factories
+                .get_mut(&outdated_instance.factory_id)
+                .ok_or_else(|| format!("Factory {} not found", outdated_instance.factory_id))?;
+            let Some(ProductionLine::ProductionLineBlueprint(instance)) = factory
+                .production_lines
+                .get(&outdated_instance.instance_id)
+                .cloned()
+            else {
+                continue;
+            };
+
+            let update = InstanceUpdate::new(&instance, &latest);
+            if !dry_run {
+                factory.replace_production_line(ProductionLine::ProductionLineBlueprint(
+                    updated_instance(&instance, &latest),
+                ));
+            }
+
+            match factories
+                .iter_mut()
+                .find(|entry| entry.factory_id == outdated_instance.factory_id)
+            {
+                Some(entry) => entry.updates.push(update),
+                None => factories.push(FactoryPropagation {
+                    factory_id: outdated_instance.factory_id,
+                    factory_name: outdated_instance.factory_name,
+                    updates: vec![update],
+                }),
+            }
+        }
+
+        if !dry_run {
+            for entry in &factories {
+                self.emit(EngineEvent::FactoryUpdated(entry.factory_id));
+            }
+        }
+        Ok(TemplatePropagation {
+            template_id: latest.id,
+            version: latest.revision.clone().unwrap_or_default().version,
+            dry_run,
+            factories,
+        })
+    }
+
+    /// Save the engine state to a JSON file
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the save file
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or failure
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use satisflow_engine::SatisflowEngine;
+    /// use std::path::Path;
+    ///
+    /// let engine = SatisflowEngine::new();
+    /// engine.save_to_file(Path::new("my_factory.json")).unwrap();
+    /// ```
+    pub fn save_to_file(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        self.save_to_file_with_options(path, &SaveOptions::default())
+    }
+
+    /// Save the engine state to a file targeting a specific save version and format
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the save file
+    /// * `options` - Save options, including the target save version
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or failure
+    pub fn save_to_file_with_options(
+        &self,
+        path: &Path,
+        options: &SaveOptions,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::write(path, self.save_to_bytes_with_options(options)?)?;
+        Ok(())
+    }
+
+    /// Save the engine state to a gzip-compressed JSON file
+    ///
+    /// [`SatisflowEngine::load_from_file`] detects compressed files by
+    /// themselves, so they load like plain ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the save file, conventionally ending in `.json.gz`
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success or failure
+    pub fn save_to_file_compressed(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::write(path, self.save_to_gzip()?)?;
+        Ok(())
+    }
+
+    /// Load the engine state from a JSON file
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the save file
+    ///
+    /// # Returns
+    ///
+    /// Result containing the loaded engine or an error
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use satisflow_engine::SatisflowEngine;
+    /// use std::path::Path;
+    ///
+    /// let engine = SatisflowEngine::load_from_file(Path::new("my_factory.json")).unwrap();
+    /// ```
+    pub fn load_from_file(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::load_from_bytes(&std::fs::read(path)?)
+    }
+
+    /// Load from the content of a save file in any [`SaveFormat`], optionally gzip-compressed
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The save file content; gzip and MessagePack are detected
+    ///   from their first bytes
+    ///
+    /// # Returns
+    ///
+    /// Result containing the loaded engine or an error
+    pub fn load_from_bytes(bytes: &[u8]) -> Result<Self, Box<dyn std::error::Error>> {
+        let decompressed;
+        let bytes = if compression::is_gzip(bytes) {
+            decompressed = compression::gunzip(bytes)?;
+            &decompressed
+        } else {
+            bytes
+        };
+
+        if is_msgpack_map(bytes) {
+            let header: SaveFileVersion = from_msgpack(bytes)?;
+            Self::check_version(header.version.as_deref(), false)?;
+            let save_file: SaveFile = from_msgpack(bytes)?;
+            save_file.into_engine()
+        } else {
+            Self::load_from_json_with_version_check(std::str::from_utf8(bytes)?)
+        }
+    }
+
+    /// Save to a JSON string (for API usage)
+    ///
+    /// # Returns
+    ///
+    /// Result containing the JSON string or an error
+    pub fn save_to_json(&self) -> Result<String, Box<dyn std::error::Error>> {
+        self.save_to_json_with_options(&SaveOptions::default())
+    }
+
+    /// Save to gzip-compressed JSON (for API usage)
+    ///
+    /// # Returns
+    ///
+    /// Result containing the compressed save file or an error
+    pub fn save_to_gzip(&self) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        Ok(compression::gzip(self.save_to_json()?.as_bytes()))
+    }
+
+    /// Save to a JSON string targeting a specific save version
+    ///
+    /// Fields gated behind a newer version than the target are left out and the
+    /// file is labelled with the target version, so older clients can load it.
+    /// Use [`SatisflowEngine::save_as_version`] to also learn what was dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Save options, including the target save version
+    ///
+    /// # Returns
+    ///
+    /// Result containing the JSON string, or an error if the target is newer than
+    /// the engine or from another major version
+    pub fn save_to_json_with_options(
+        &self,
+        options: &SaveOptions,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        if options.format == SaveFormat::MessagePack {
+            return Err("MessagePack saves are binary, use save_to_bytes_with_options".into());
+        }
+
+        Self::check_target_version(&options.target_version)?;
+
+        if Self::keeps_every_field(&options.target_version) {
+            let mut save_file = SaveFileRef::new(self);
+            save_file.version = options.target_version.to_string();
+            return match options.format {
+                SaveFormat::JsonCompact => Ok(serde_json::to_string(&save_file)?),
+                _ => Ok(serde_json::to_string_pretty(&save_file)?),
+            };
+        }
+
+        let (value, _) = self.save_value(&options.target_version)?;
+        match options.format {
+            SaveFormat::JsonCompact => Ok(serde_json::to_string(&value)?),
+            _ => Ok(serde_json::to_string_pretty(&value)?),
+        }
+    }
+
+    /// Save to the bytes of a save file in any format
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Save options, including the target save version and format
+    ///
+    /// # Returns
+    ///
+    /// Result containing the file content, or an error if the target version is
+    /// newer than the engine or from another major version
+    pub fn save_to_bytes_with_options(
+        &self,
+        options: &SaveOptions,
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        match options.format {
+            SaveFormat::MessagePack => {
+                Self::check_target_version(&options.target_version)?;
+                if Self::keeps_every_field(&options.target_version) {
+                    let mut save_file = SaveFileRef::new(self);
+                    save_file.version = options.target_version.to_string();
+                    return Ok(to_msgpack(&save_file)?);
+                }
+                let (value, _) = self.save_value(&options.target_version)?;
+                Ok(to_msgpack(&value)?)
+            }
+            SaveFormat::JsonPretty | SaveFormat::JsonCompact => {
+                Ok(self.save_to_json_with_options(options)?.into_bytes())
+            }
+        }
+    }
+
+    /// Export the engine for an older release, reporting what could not be kept
+    ///
+    /// # Arguments
+    ///
+    /// * `target_version` - The save version the older release understands
+    ///
+    /// # Returns
+    ///
+    /// The save JSON and a report of the features whose data was dropped, or an
+    /// error if the target is newer than the engine or from another major version
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use satisflow_engine::{SatisflowEngine, SaveVersion};
+    ///
+    /// let engine = SatisflowEngine::new();
+    /// let export = engine.save_as_version(&SaveVersion::new(0, 1, 0)).unwrap();
+    /// assert!(export.report.is_lossless());
+    /// ```
+    pub fn save_as_version(
+        &self,
+        target_version: &SaveVersion,
+    ) -> Result<VersionedExport, Box<dyn std::error::Error>> {
+        let (value, report) = self.save_value(target_version)?;
+        Ok(VersionedExport {
+            save_data: serde_json::to_string_pretty(&value)?,
+            report,
+        })
+    }
+
+    /// Check that a save can be written for `target_version`
+    fn check_target_version(target_version: &SaveVersion) -> Result<(), VersionError> {
+        let engine_version = SaveVersion::current();
+        if !target_version.is_compatible_with(&engine_version)
+            || target_version.is_newer_than(&engine_version)
+        {
+            return Err(VersionError::UnsupportedTarget {
+                target_version: target_version.to_string(),
+                engine_version: engine_version.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Whether a save for `target_version` has every feature, so there is
+    /// nothing to strip or flatten and the engine is written straight from its
+    /// fields
+    fn keeps_every_field(target_version: &SaveVersion) -> bool {
+        *target_version >= version::TYPED_TRANSPORT_DETAILS
+            && SAVE_FEATURES
+                .iter()
+                .all(|feature| feature.is_enabled_for(target_version))
+    }
+
+    /// Save file tree for a target version, with what was dropped to get there
+    fn save_value(
+        &self,
+        target_version: &SaveVersion,
+    ) -> Result<(serde_json::Value, DowngradeReport), Box<dyn std::error::Error>> {
+        Self::check_target_version(target_version)?;
+
+        let mut save_file = SaveFileRef::new(self);
+        save_file.version = target_version.to_string();
+        let mut value = serde_json::to_value(&save_file)?;
+        let stripped =
+            version::strip_gated_fields(&mut value["engine"], SAVE_FEATURES, target_version);
+        version::flatten_transport_details(&mut value["engine"], target_version);
+        let defaults = serde_json::to_value(SatisflowEngine::new())?;
+        let report = DowngradeReport::from_stripped(target_version, &stripped, &defaults);
+        Ok((value, report))
+    }
+
+    /// Load from a JSON string (for API usage)
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - JSON string containing the save file
+    ///
+    /// # Returns
+    ///
+    /// Result containing the loaded engine or an error
+    pub fn load_from_json(json: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::load_from_json_with_version_check(json)
+    }
+
+    /// Load from a JSON string, strictly or leniently
+    ///
+    /// A strict load rejects saves from a newer version, and unknown items,
+    /// recipes and fields with the JSON path of the first one. A lenient load
+    /// accepts saves from a newer minor version, removes the entries naming
+    /// unknown items or recipes, and keeps unknown fields so the next save
+    /// writes them back; each of those is reported as a warning. Saves from
+    /// another major version are rejected either way.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - JSON string containing the save file
+    /// * `options` - Whether to load strictly
+    ///
+    /// # Returns
+    ///
+    /// Result containing the loaded engine, the save summary and the
+    /// warnings, or an error
+    pub fn load_from_json_with_options(
+        json: &str,
+        options: &LoadOptions,
+    ) -> Result<LoadedSave, Box<dyn std::error::Error>> {
+        Self::load_from_value_with_options(serde_json::from_str(json)?, options)
+    }
+
+    /// Load a damaged save from a JSON string, fixing what can be fixed
+    ///
+    /// Logistics lines connecting a missing factory are removed, entities whose
+    /// ID is missing, doesn't match their key or is taken by an earlier entity
+    /// get a new one, rates saved as `null` (how NaN is written) become 0 and
+    /// missing required sections get their default. The result then loads like
+    /// a lenient [`load_from_json_with_options`](Self::load_from_json_with_options).
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - JSON string containing the save file
+    ///
+    /// # Returns
+    ///
+    /// The repaired engine and what was fixed, or an error if the save is not
+    /// JSON, is from another major version or is still unreadable
+    pub fn repair(json: &str) -> Result<(Self, RepairReport), Box<dyn std::error::Error>> {
+        let mut value: serde_json::Value = serde_json::from_str(json)?;
+        let mut report = repair::repair_save(&mut value)?;
+        let loaded = Self::load_from_value_with_options(value, &LoadOptions::default())?;
+        report.load_warnings = loaded.warnings;
+        Ok((loaded.engine, report))
+    }
+
+    fn load_from_value_with_options(
+        mut value: serde_json::Value,
+        options: &LoadOptions,
+    ) -> Result<LoadedSave, Box<dyn std::error::Error>> {
+        let file_version = Self::check_save_version(&value, !options.strict)?;
+
+        let mut warnings = Vec::new();
+        if file_version.is_newer_than(&SaveVersion::current()) {
+            warnings.push(LoadIssue {
+                path: "version".to_string(),
+                message: format!(
+                    "Saved by newer version {}; fields it added are kept",
+                    file_version
+                ),
+            });
+        }
+        if let Some(engine) = value.get_mut("engine") {
+            warnings.extend(load::check_names(engine, options.strict)?);
+        }
+
+        let save_file: SaveFile = serde_json::from_value(value)?;
+        let summary = save_file.summary();
+        let engine = save_file.into_engine()?;
+        warnings.extend(load::check_unknown_fields(&engine, options.strict)?);
+        Ok(LoadedSave {
+            engine,
+            summary,
+            warnings,
+        })
+    }
+
+    /// Internal method to load with version checking
+    fn load_from_json_with_version_check(json: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        // First, parse just to get the version
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        Self::check_save_version(&value, false)?;
+
+        let save_file: SaveFile = serde_json::from_str(json)?;
+        save_file.into_engine()
+    }
+
+    /// Check that a save file can be loaded by this engine version
+    ///
+    /// `lenient` accepts saves from a newer minor version of the same major.
+    ///
+    /// # Returns
+    ///
+    /// The version of the save file
+    fn check_save_version(
+        value: &serde_json::Value,
+        lenient: bool,
+    ) -> Result<SaveVersion, Box<dyn std::error::Error>> {
+        Self::check_version(value["version"].as_str(), lenient)
+    }
+
+    /// Check the `version` field of a save file, see [`Self::check_save_version`]
+    fn check_version(
+        file_version_str: Option<&str>,
+        lenient: bool,
+    ) -> Result<SaveVersion, Box<dyn std::error::Error>> {
+        let file_version_str = file_version_str.ok_or("Missing version field in save file")?;
+
+        let file_version = SaveVersion::parse(file_version_str)?;
+        let engine_version = SaveVersion::current();
+
+        // Check version compatibility
+        if file_version == engine_version {
+            // Exact match - load directly
+            return Ok(file_version);
+        }
+
+        if !file_version.is_compatible_with(&engine_version) {
+            // Incompatible major version
+            return Err(Box::new(VersionError::Incompatible {
+                save_version: file_version.to_string(),
+                engine_version: engine_version.to_string(),
+            }));
+        }
+
+        if file_version.is_newer_than(&engine_version) && lenient {
+            println!(
+                "INFO: Loading save file from newer version {} (current: {}) - keeping unknown fields",
+                file_version, engine_version
+            );
+            return Ok(file_version);
+        }
+
+        if file_version.is_newer_than(&engine_version) {
+            // Save is from a newer version
+            return Err(Box::new(VersionError::SaveTooNew {
+                save_version: file_version.to_string(),
+                engine_version: engine_version.to_string(),
+            }));
+        }
+
+        // Save is older but compatible - for now, try to load it
+        // In the future, this is where we'll apply migrations
+        if file_version.is_older_than(&engine_version) {
+            // For now, we'll try to load it and let serde handle defaults;
+            // items renamed since resolve through `ITEM_ALIASES` as they load
+            // TODO: Add proper migration system here when needed
+            println!(
+                "INFO: Loading save file from older version {} (current: {})",
+                file_version, engine_version
+            );
+            println!(
+                "INFO: Migration system not yet implemented - using default values for new fields"
+            );
+        }
+
+        Ok(file_version)
+    }
+}
+
+impl SaveFile {
+    /// Create a new save file from an engine instance
+    pub fn new(engine: SatisflowEngine) -> Self {
+        let now = Utc::now();
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            created_at: now,
+            last_modified: now,
+            game_version: Some(engine.game_data.version.to_string()),
+            engine,
+        }
+    }
+
+    /// Take the engine out of the save, planning against the recorded game version
+    ///
+    /// # Returns
+    ///
+    /// The engine, or an error if the save's game data version isn't available
+    /// or it plans FICSMAS in a build without the feature; saves without a
+    /// game version use the latest data
+    pub fn into_engine(self) -> Result<SatisflowEngine, Box<dyn std::error::Error>> {
+        let mut engine = self.engine;
+        engine.game_data = match &self.game_version {
+            Some(version) => GameDataSet::find(version)?,
+            None => GameDataSet::latest(),
+        };
+        if engine.ficsmas && !ficsmas::is_available() {
+            return Err("The save plans FICSMAS, which is not available in this build".into());
+        }
+        engine.balance.validate()?;
+        engine.share_balance();
+        Ok(engine)
+    }
+
+    /// Update the last_modified timestamp
+    pub fn update_timestamp(&mut self) {
+        self.last_modified = Utc::now();
+    }
+
+    /// Compare with a newer save of the same world
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The newer save
+    ///
+    /// # Returns
+    ///
+    /// Factories and logistics lines added, removed or modified, and the
+    /// change of every item's net rate
+    pub fn diff(&self, other: &SaveFile) -> SaveDiff {
+        SaveDiff::between(&self.engine, &other.engine)
+    }
+
+    /// Get a summary of the save file contents
+    pub fn summary(&self) -> SaveFileSummary {
+        SaveFileSummary {
+            version: self.version.clone(),
+            created_at: self.created_at,
+            last_modified: self.last_modified,
+            factory_count: self.engine.factories.len(),
+            logistics_count: self.engine.logistics_lines.len(),
+            blueprint_template_count: self.engine.blueprint_templates.len(),
+        }
+    }
+}
+
+/// Summary information about a save file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveFileSummary {
+    pub version: String,
+    pub created_at: DateTime<Utc>,
+    pub last_modified: DateTime<Utc>,
+    pub factory_count: usize,
+    pub logistics_count: usize,
+    pub blueprint_template_count: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        logistics::{DroneTransport, Train, TransportType, TruckTransport, Wagon, WagonType},
+        production_line::{
+            ProductionLine, ProductionLineBlueprint, ProductionLineBuilder, ProductionLineRecipe,
+        },
+        FactoryTask, GeneratorType, Item, PowerGenerator, Recipe,
+    };
+    use uuid::Uuid;
+
+    fn uuid_from_u64<T: From<Uuid>>(value: u64) -> T {
+        Uuid::from_u128(value as u128).into()
+    }
+
+    #[test]
+    fn test_get_all_factories() {
+        let mut engine = SatisflowEngine::new();
+
+        // Create some factories
+        let factory1_id = engine.create_factory("Factory 1".to_string(), None);
+        let factory2_id =
+            engine.create_factory("Factory 2".to_string(), Some("Test factory".to_string()));
+
+        // Get all factories
+        let all_factories = engine.get_all_factories();
+
+        // Verify all factories are returned
+        assert_eq!(all_factories.len(), 2);
+        assert!(all_factories.contains_key(&factory1_id));
+        assert!(all_factories.contains_key(&factory2_id));
+
+        // Verify factory details
+        let factory1 = all_factories.get(&factory1_id).unwrap();
+        assert_eq!(factory1.name, "Factory 1");
+        assert!(factory1.description.is_none());
+
+        let factory2 = all_factories.get(&factory2_id).unwrap();
+        assert_eq!(factory2.name, "Factory 2");
+        assert_eq!(factory2.description.as_ref().unwrap(), "Test factory");
+    }
+
+    #[test]
+    fn test_add_built_factory() {
+        let mut engine = SatisflowEngine::new();
+        let factory = Factory::builder("Smelters")
+            .id(uuid_from_u64(1))
+            .production_line(
+                ProductionLineBuilder::recipe("Ingots", Recipe::IronIngot).machines(2, 100.0, 0),
+            )
+            .build()
+            .unwrap();
+
+        let revision = engine.revision();
+        assert_eq!(
+            engine.add_factory(factory.clone()).unwrap(),
+            uuid_from_u64(1)
+        );
+        assert!(engine.revision() > revision);
+        assert!(engine.add_factory(factory).is_err());
+
+        engine.update();
+        let factory = engine.get_factory(uuid_from_u64(1)).unwrap();
+        assert_eq!(factory.items.get(&Item::IronIngot), Some(&60.0));
+
+        let orphan = Factory::builder("Orphan")
+            .power_grid(uuid_from_u64(9))
+            .build()
+            .unwrap();
+        assert!(engine.add_factory(orphan).is_err());
+        assert_eq!(engine.get_all_factories().len(), 1);
+    }
+
+    #[test]
+    fn test_engine_events() {
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut engine = SatisflowEngine::new();
+        let recorded = events.clone();
+        let subscription = engine.subscribe(move |event| recorded.lock().unwrap().push(*event));
+
+        let factory1_id = engine.create_factory("Factory 1".to_string(), None);
+        let factory2_id = engine.create_factory("Factory 2".to_string(), None);
+        let transport = TransportType::Truck(TruckTransport::new(1, Item::IronOre, 60.0));
+        let logistics_id = engine
+            .create_logistics_line(factory1_id, factory2_id, transport, "Truck".to_string())
+            .unwrap();
+        // Failed changes notify nobody
+        assert!(engine.delete_goal(uuid_from_u64(99)).is_err());
+        engine.delete_factory(factory2_id).unwrap();
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                EngineEvent::FactoryCreated(factory1_id),
+                EngineEvent::FactoryCreated(factory2_id),
+                EngineEvent::LogisticsCreated(logistics_id),
+                EngineEvent::LogisticsDeleted(logistics_id),
+                EngineEvent::FactoryDeleted(factory2_id),
+            ]
+        );
+
+        // Copies don't notify the original's observers, loading a save does
+        events.lock().unwrap().clear();
+        let mut copy = engine.clone();
+        copy.create_factory("Copy".to_string(), None);
+        engine.replace(copy);
+        engine.set_game_balance(GameBalance::default()).unwrap();
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![EngineEvent::StateReplaced, EngineEvent::BalanceChanged]
+        );
+
+        // The next version keeps them, but only notifies once dispatched
+        events.lock().unwrap().clear();
+        let mut dropped = engine.next_version();
+        dropped.create_factory("Dropped".to_string(), None);
+        drop(dropped);
+        let mut next = engine.next_version();
+        let id = next.create_factory("Next".to_string(), None);
+        assert!(events.lock().unwrap().is_empty());
+        next.dispatch_pending_events();
+        next.dispatch_pending_events();
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![EngineEvent::FactoryCreated(id)]
+        );
+
+        events.lock().unwrap().clear();
+        assert!(engine.unsubscribe(subscription));
+        assert!(!engine.unsubscribe(subscription));
+        engine.create_factory("Factory 3".to_string(), None);
+        assert!(events.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_get_all_logistics() {
+        let mut engine = SatisflowEngine::new();
+
+        // Create factories first
+        let factory1_id = engine.create_factory("Factory 1".to_string(), None);
+        let factory2_id = engine.create_factory("Factory 2".to_string(), None);
+
+        // Create some logistics lines
+        let transport1 = TransportType::Truck(TruckTransport::new(1, Item::IronOre, 60.0));
+        let logistics1_id = engine
+            .create_logistics_line(
+                factory1_id,
+                factory2_id,
+                transport1,
+                "Test truck".to_string(),
+            )
+            .unwrap();
+
+        let transport2 = TransportType::Truck(TruckTransport::new(2, Item::CopperOre, 120.0));
+        let logistics2_id = engine
+            .create_logistics_line(
+                factory2_id,
+                factory1_id,
+                transport2,
+                "Test truck 2".to_string(),
+            )
+            .unwrap();
+
+        // Get all logistics
+        let all_logistics = engine.get_all_logistics();
+
+        // Verify all logistics lines are returned
+        assert_eq!(all_logistics.len(), 2);
+        assert!(all_logistics.contains_key(&logistics1_id));
+        assert!(all_logistics.contains_key(&logistics2_id));
+
+        // Verify logistics details
+        let logistics1 = all_logistics.get(&logistics1_id).unwrap();
+        assert_eq!(logistics1.from_factory, factory1_id);
+        assert_eq!(logistics1.to_factory, factory2_id);
+        assert_eq!(logistics1.transport_details.to_string(), "Test truck");
+    }
+
+    #[test]
+    fn test_delete_factory() {
+        let mut engine = SatisflowEngine::new();
+
+        // Create factories
+        let factory1_id = engine.create_factory("Factory 1".to_string(), None);
+        let factory2_id = engine.create_factory("Factory 2".to_string(), None);
+
+        // Create logistics line between factories
+        let transport = TransportType::Truck(TruckTransport::new(1, Item::IronOre, 60.0));
+        let logistics_id = engine
+            .create_logistics_line(
+                factory1_id,
+                factory2_id,
+                transport,
+                "Test truck".to_string(),
+            )
+            .unwrap();
+
+        // Verify initial state
+        assert_eq!(engine.get_all_factories().len(), 2);
+        assert_eq!(engine.get_all_logistics().len(), 1);
+
+        // Delete factory
+        engine.delete_factory(factory1_id).unwrap();
+
+        // Verify factory is deleted
+        assert_eq!(engine.get_all_factories().len(), 1);
+        assert!(!engine.get_all_factories().contains_key(&factory1_id));
+        assert!(engine.get_all_factories().contains_key(&factory2_id));
+
+        // Verify connected logistics lines are also deleted
+        assert_eq!(engine.get_all_logistics().len(), 0);
+        assert!(!engine.get_all_logistics().contains_key(&logistics_id));
+    }
+
+    #[test]
+    fn test_preview_delete_factory() {
+        use crate::models::{production_line::MachineGroup, ExtractorType, Purity, RawInput};
+
+        let mut engine = SatisflowEngine::new();
+        let mine = engine.create_factory("Mine".into(), None);
+        let smelter = engine.create_factory("Smelter".into(), None);
+        engine
+            .get_factory_mut(mine)
+            .unwrap()
+            .add_raw_input(
+                RawInput::new(
+                    uuid_from_u64(1),
+                    ExtractorType::MinerMk1,
+                    Item::IronOre,
+                    Some(Purity::Normal),
+                    100.0,
+                    1,
+                )
+                .unwrap(),
+            )
+            .unwrap();
+        let mut ingots =
+            ProductionLineRecipe::new(uuid_from_u64(2), "Ingots".into(), None, Recipe::IronIngot);
+        ingots
+            .add_machine_group(MachineGroup::new(2, 100.0, 0))
+            .unwrap();
+        engine
+            .get_factory_mut(smelter)
+            .unwrap()
+            .add_production_line(ProductionLine::ProductionLineRecipe(ingots));
+        let logistics_id = engine
+            .create_logistics_line(
+                mine,
+                smelter,
+                TransportType::Truck(TruckTransport::new(1, Item::IronOre, 60.0)),
+                String::new(),
+            )
+            .unwrap();
+
+        let preview = engine.preview_delete_factory(mine).unwrap();
+        assert_eq!(preview.name, "Mine");
+        assert_eq!(preview.logistics.len(), 1);
+        assert_eq!(preview.logistics[0].id, logistics_id);
+        assert_eq!(preview.logistics[0].items[0].quantity_per_min, 60.0);
+        assert_eq!(preview.deficits.len(), 1);
+        let deficit = &preview.deficits[0];
+        assert_eq!(deficit.factory_id, smelter);
+        assert_eq!(deficit.item, Item::IronOre);
+        assert_eq!(deficit.balance_before, 0.0);
+        assert_eq!(deficit.balance_after, -60.0);
+        // Nothing was changed
+        assert_eq!(engine.get_all_logistics().len(), 1);
+
+        // The mine only loses a customer, which leaves a surplus, not a deficit
+        let preview = engine.preview_delete_factory(smelter).unwrap();
+        assert_eq!(preview.logistics.len(), 1);
+        assert!(preview.deficits.is_empty());
+
+        assert!(engine.preview_delete_factory(uuid_from_u64(999)).is_err());
+    }
+
+    #[test]
+    fn test_delete_factory_not_found() {
+        let mut engine = SatisflowEngine::new();
+
+        // Try to delete non-existent factory
+        let missing_id = uuid_from_u64(999);
+        let result = engine.delete_factory(missing_id);
+
+        // Verify error
+        assert!(result.is_err());
+        let error = result.unwrap_err().to_string();
+        assert!(
+            error.contains(&missing_id.to_string()),
+            "expected error to reference missing factory id, got: {error}"
+        );
+    }
+
+    #[test]
+    fn test_delete_logistics_line() {
+        let mut engine = SatisflowEngine::new();
+
+        // Create factories
+        let factory1_id = engine.create_factory("Factory 1".to_string(), None);
+        let factory2_id = engine.create_factory("Factory 2".to_string(), None);
+
+        // Create logistics line
+        let transport = TransportType::Truck(TruckTransport::new(1, Item::IronOre, 60.0));
+        let logistics_id = engine
+            .create_logistics_line(
+                factory1_id,
+                factory2_id,
+                transport,
+                "Test truck".to_string(),
+            )
+            .unwrap();
+
+        // Verify initial state
+        assert_eq!(engine.get_all_logistics().len(), 1);
+
+        // Delete logistics line
+        engine.delete_logistics_line(logistics_id).unwrap();
+
+        // Verify logistics line is deleted
+        assert_eq!(engine.get_all_logistics().len(), 0);
+        assert!(!engine.get_all_logistics().contains_key(&logistics_id));
+
+        // Verify factories are still there
+        assert_eq!(engine.get_all_factories().len(), 2);
+    }
+
+    #[test]
+    fn test_delete_logistics_line_not_found() {
+        let mut engine = SatisflowEngine::new();
+
+        // Try to delete non-existent logistics line
+        let missing_id = uuid_from_u64(9999);
+        let result = engine.delete_logistics_line(missing_id);
+
+        // Verify error
+        assert!(result.is_err());
+        let error = result.unwrap_err().to_string();
+        assert!(
+            error.contains(&missing_id.to_string()),
+            "expected error to reference missing logistics id, got: {error}"
+        );
+    }
+
+    #[test]
+    fn test_update_logistics_line() {
+        let mut engine = SatisflowEngine::new();
+
+        let factory_a = engine.create_factory("Factory A".into(), None);
+        let factory_b = engine.create_factory("Factory B".into(), None);
+        let factory_c = engine.create_factory("Factory C".into(), None);
+
+        let original_transport = TransportType::Truck(TruckTransport::new(1, Item::IronOre, 60.0));
+        let logistics_id = engine
+            .create_logistics_line(
+                factory_a,
+                factory_b,
+                original_transport,
+                "Initial truck route",
+            )
+            .expect("should create logistics line");
+
+        let updated_transport =
+            TransportType::Drone(DroneTransport::new(5, Item::CircuitBoard, 45.0));
+
+        engine
+            .update_logistics_line(
+                logistics_id,
+                factory_b,
+                factory_c,
+                updated_transport.clone(),
+                "Updated drone route",
+            )
+            .expect("should update logistics line");
+
+        let updated_line = engine
+            .get_logistics_line(logistics_id)
+            .expect("logistics line should exist");
+        assert_eq!(updated_line.from_factory, factory_b);
+        assert_eq!(updated_line.to_factory, factory_c);
+        assert_eq!(updated_line.transport_type, updated_transport);
+        assert_eq!(
+            updated_line.transport_details.to_string(),
+            "Updated drone route"
+        );
+    }
+
+    #[test]
+    fn test_next_transport_number_follows_highest_number() {
+        let mut engine = SatisflowEngine::new();
+        let factory_a = engine.create_factory("Factory A".into(), None);
+        let factory_b = engine.create_factory("Factory B".into(), None);
+
+        assert_eq!(
+            engine.allocate_transport_name(TransportKind::Truck),
+            (1, "TRK-001".to_string())
+        );
+
+        let first = engine
+            .create_logistics_line(
+                factory_a,
+                factory_b,
+                TransportType::Truck(TruckTransport::new(1, Item::IronOre, 60.0)),
+                String::new(),
+            )
+            .unwrap();
+        let second = engine
+            .create_logistics_line(
+                factory_a,
+                factory_b,
+                TransportType::Truck(TruckTransport::new(2, Item::IronOre, 60.0)),
+                String::new(),
+            )
+            .unwrap();
+        engine.delete_logistics_line(first).unwrap();
+
+        // Numbering continues past the highest id instead of reusing the line count
+        assert_eq!(engine.next_transport_number(TransportKind::Truck), 3);
+        // Other kinds keep their own sequence
+        assert_eq!(engine.next_transport_number(TransportKind::Drone), 1);
+
+        // The highest number is free again once its line is gone
+        engine.delete_logistics_line(second).unwrap();
+        assert_eq!(engine.next_transport_number(TransportKind::Truck), 1);
+    }
+
+    #[test]
+    fn test_transport_naming_pattern_survives_save_load() {
+        let mut engine = SatisflowEngine::new();
+        engine
+            .set_transport_naming_pattern(TransportKind::Train, "{prefix}-Alpha-{n:02}")
+            .unwrap();
+        assert!(engine
+            .set_transport_naming_pattern(TransportKind::Train, "no number")
+            .is_err());
+
+        let json = engine.save_to_json().unwrap();
+        let loaded = SatisflowEngine::load_from_json(&json).unwrap();
+
+        assert_eq!(
+            loaded.allocate_transport_name(TransportKind::Train),
+            (1, "TRN-Alpha-01".to_string())
+        );
+    }
+
+    #[test]
+    fn test_save_as_older_version_strips_gated_fields() {
+        use crate::models::InternalTransfer;
+
+        let mut engine = SatisflowEngine::new();
+        engine
+            .set_transport_naming_pattern(TransportKind::Bus, "{prefix}-{n}")
+            .unwrap();
+        let factory_id = engine.create_factory("Main".into(), None);
+        engine
+            .get_factory_mut(factory_id)
+            .unwrap()
+            .add_internal_transfer(
+                InternalTransfer::new(uuid_from_u64(1), Item::IronPlate, 60.0, "A", "B").unwrap(),
+            )
+            .unwrap();
+
+        let options = SaveOptions {
+            target_version: SaveVersion::new(0, 1, 0),
+            ..Default::default()
+        };
+        let json = engine.save_to_json_with_options(&options).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value["version"], "0.1.0");
+        assert!(value["engine"].get("transport_naming").is_none());
+        assert!(value["engine"].get("resource_nodes").is_none());
+        let factory = &value["engine"]["factories"][factory_id.to_string()];
+        assert_eq!(factory["name"], "Main");
+        assert!(factory.get("internal_transfers").is_none());
+
+        // Older saves still load, falling back to defaults for the stripped fields
+        let loaded = SatisflowEngine::load_from_json(&json).unwrap();
+        assert_eq!(loaded.get_all_factories().len(), 1);
+        assert_eq!(
+            loaded.transport_naming().pattern(TransportKind::Bus),
+            "Bus {n}"
+        );
+
+        // The default target keeps everything
+        let full: serde_json::Value =
+            serde_json::from_str(&engine.save_to_json().unwrap()).unwrap();
+        assert!(full["engine"].get("transport_naming").is_some());
+    }
+
+    #[test]
+    fn test_save_as_version_reports_lost_data() {
+        use crate::models::InternalTransfer;
+
+        let mut engine = SatisflowEngine::new();
+        let target = SaveVersion::new(0, 1, 0);
+        assert!(engine
+            .save_as_version(&target)
+            .unwrap()
+            .report
+            .is_lossless());
+
+        let factory_id = engine.create_factory("Main".into(), None);
+        for (id, from) in [(1, "A"), (2, "C")] {
+            engine
+                .get_factory_mut(factory_id)
+                .unwrap()
+                .add_internal_transfer(
+                    InternalTransfer::new(uuid_from_u64(id), Item::IronPlate, 60.0, from, "B")
+                        .unwrap(),
+                )
+                .unwrap();
+        }
+        engine
+            .set_transport_naming_pattern(TransportKind::Truck, "T-{n}")
+            .unwrap();
+        engine.create_power_grid("North".into(), None);
+
+        let export = engine.save_as_version(&target).unwrap();
+        let lost: Vec<(&str, usize)> = export
+            .report
+            .lost
+            .iter()
+            .map(|lost| (lost.feature.as_str(), lost.count))
+            .collect();
+        assert_eq!(
+            lost,
+            vec![
+                ("transport_naming", 1),
+                ("internal_transfers", 2),
+                ("power_grids", 1),
+                ("factory_timestamps", 1)
+            ]
+        );
+        assert_eq!(export.report.lost[0].introduced_in, "0.2.0");
+        assert!(SatisflowEngine::load_from_json(&export.save_data).is_ok());
+
+        // Exporting at the current version keeps everything
+        let current = engine.save_as_version(&SaveVersion::current()).unwrap();
+        assert!(current.report.is_lossless());
+    }
+
+    #[test]
+    fn test_recent_changes() {
+        use chrono::TimeZone;
+
+        let mut engine = SatisflowEngine::new();
+        let smelter = engine.create_factory("Smelter".into(), None);
+        let assembly = engine.create_factory("Assembly".into(), None);
+        engine
+            .get_factory_mut(smelter)
+            .unwrap()
+            .add_production_line(ProductionLine::ProductionLineRecipe(
+                ProductionLineRecipe::new(
+                    uuid_from_u64(1),
+                    "Plates".into(),
+                    None,
+                    Recipe::IronPlate,
+                ),
+            ));
+        let logistics_id = engine
+            .create_logistics_line(
+                smelter,
+                assembly,
+                TransportType::Truck(TruckTransport::new(1, Item::IronPlate, 60.0)),
+                "",
+            )
+            .unwrap();
+        assert_eq!(engine.recent_changes(50).len(), 4);
+
+        // Pin every entity in the past, then modify only the logistics line
+        let past = Timestamps {
+            created_at: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+            updated_at: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+        };
+        for factory in engine.factories.values_mut() {
+            factory.timestamps = past;
+            for timestamps in factory.production_line_timestamps.values_mut() {
+                *timestamps = past;
+            }
+        }
+        engine
+            .logistics_lines
+            .update(&logistics_id, |line| line.timestamps = past)
+            .unwrap();
+        engine.reverse_logistics_line(logistics_id).unwrap();
+
+        let changes = engine.recent_changes(2);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].entity, ChangedEntity::Logistics);
+        assert_eq!(changes[0].id, logistics_id.as_uuid());
+        assert_eq!(changes[0].factory_id, assembly);
+        assert!(changes[0].is_update());
+        // Ties are ordered by name
+        assert_eq!(changes[1].name, "Assembly");
+        assert!(!changes[1].is_update());
+    }
+
+    #[test]
+    fn test_pinned_entities_follow_factories() {
+        let mut engine = SatisflowEngine::new();
+        let mine = engine.create_factory("Mine".into(), None);
+        let smelter = engine.create_factory("Smelter".into(), None);
+        let outpost = engine.create_factory("Outpost".into(), None);
+
+        assert!(engine.pin_factory(mine).unwrap());
+        assert!(!engine.pin_factory(mine).unwrap());
+        assert!(engine.pin_factory(uuid_from_u64(99)).is_err());
+        engine.pin_factory(outpost).unwrap();
+        assert!(engine.pin_item(Item::IronPlate));
+
+        engine.delete_factory(outpost).unwrap();
+        assert_eq!(engine.pinned().factories, vec![mine]);
+
+        let merged = engine
+            .merge_factories(&[mine, smelter], "Iron Works")
+            .unwrap();
+        assert_eq!(engine.pinned().factories, vec![merged]);
+        assert_eq!(engine.pinned().items, vec![Item::IronPlate]);
+
+        assert!(engine.unpin_factory(merged));
+        assert!(engine.unpin_item(Item::IronPlate));
+        assert!(engine.pinned().is_empty());
+    }
+
+    #[test]
+    fn test_save_as_rejects_unsupported_targets() {
+        let engine = SatisflowEngine::new();
+        let current = SaveVersion::current();
+
+        for target in [
+            SaveVersion::new(current.major, current.minor + 1, 0),
+            SaveVersion::new(current.major + 1, 0, 0),
+        ] {
+            let options = SaveOptions {
+                target_version: target,
+                ..Default::default()
+            };
+            assert!(engine.save_to_json_with_options(&options).is_err());
+        }
+    }
+
+    #[test]
+    fn test_power_grids() {
+        use crate::models::{GeneratorGroup, GeneratorType, PowerGenerator};
+
+        let mut engine = SatisflowEngine::new();
+        let north = engine.create_power_grid("North".into(), None);
+        let south = engine.create_power_grid("South".into(), None);
+
+        let plant = engine.create_factory("Coal Plant".into(), None);
+        let mut generator =
+            PowerGenerator::new(uuid_from_u64(1), GeneratorType::Coal, Item::Coal).unwrap();
+        generator
+            .add_group(GeneratorGroup::new(2, 100.0).unwrap())
+            .unwrap();
+        engine
+            .get_factory_mut(plant)
+            .unwrap()
+            .add_power_generator(generator)
+            .unwrap();
+        let unassigned = engine.create_factory("Outpost".into(), None);
+
+        engine
+            .assign_factory_to_power_grid(plant, Some(north))
+            .unwrap();
+        assert!(engine
+            .assign_factory_to_power_grid(plant, Some(uuid_from_u64(99)))
+            .is_err());
+
+        let stats = engine.global_power_stats();
+        assert_eq!(stats.grid_stats.len(), 3);
+        assert_eq!(stats.grid_stats[0].grid_id, Some(north));
+        assert_eq!(stats.grid_stats[0].generation, 150.0);
+        assert_eq!(stats.grid_stats[0].factory_ids, vec![plant]);
+        assert_eq!(stats.grid_stats[1].grid_id, Some(south));
+        assert!(stats.grid_stats[1].factory_ids.is_empty());
+        assert_eq!(stats.grid_stats[2].grid_id, None);
+        assert_eq!(stats.grid_stats[2].factory_ids, vec![unassigned]);
+        // Cross-grid totals are unchanged
+        assert_eq!(stats.total_generation, 150.0);
+
+        engine.delete_power_grid(north).unwrap();
+        assert_eq!(engine.get_factory(plant).unwrap().power_grid, None);
+        assert!(engine.delete_power_grid(north).is_err());
+    }
+
+    #[test]
+    fn test_progression_warnings() {
+        use crate::models::TierProgress;
+
+        let mut engine = SatisflowEngine::new();
+        let mut blueprint = ProductionLineBlueprint::new(uuid_from_u64(1), "Steel".into(), None);
+        for (id, recipe) in [(2, Recipe::SteelIngot), (3, Recipe::SteelBeam)] {
+            blueprint.add_production_line(ProductionLineRecipe::new(
+                uuid_from_u64(id),
+                "Steel".into(),
+                None,
+                recipe,
+            ));
+        }
+        let line = ProductionLine::ProductionLineBlueprint(blueprint);
+
+        // Nothing is locked until the progression is recorded
+        assert!(engine.progression_warnings(&line).is_empty());
+
+        engine
+            .set_tier_progress(Some(TierProgress {
+                hub_tier: 2,
+                ..TierProgress::default()
+            }))
+            .unwrap();
+        assert_eq!(engine.progression_warnings(&line).len(), 2);
+        assert!(engine
+            .set_tier_progress(Some(TierProgress {
+                hub_tier: 12,
+                ..TierProgress::default()
+            }))
+            .is_err());
+
+        let json = engine.save_to_json().unwrap();
+        let mut loaded = SatisflowEngine::load_from_json(&json).unwrap();
+        assert_eq!(loaded.tier_progress().unwrap().hub_tier, 2);
+
+        loaded.reset().unwrap();
+        assert!(loaded.tier_progress().is_none());
+    }
+
+    #[test]
+    fn test_custom_recipes() {
+        use crate::models::{game_data::MachineType, production_line::MachineGroup};
+
+        let mut engine = SatisflowEngine::new();
+        let recipe_id = engine
+            .create_custom_recipe(
+                "Compressed Iron".into(),
+                MachineType::Constructor,
+                vec![(Item::IronIngot, 5.0)],
+                vec![(Item::IronPlate, 4.0)],
+                3.0,
+            )
+            .unwrap();
+        assert!(engine
+            .create_custom_recipe(
+                "Compressed Iron".into(),
+                MachineType::Constructor,
+                Vec::new(),
+                vec![(Item::IronPlate, 1.0)],
+                1.0,
+            )
+            .is_err());
+
+        let factory_id = engine.create_factory("Modded".into(), None);
+        let recipe = engine.resolve_recipe("Compressed Iron").unwrap();
+        let mut line = ProductionLineRecipe::new(uuid_from_u64(1), "Plates".into(), None, recipe);
+        line.add_machine_group(MachineGroup::new(1, 100.0, 0))
+            .unwrap();
+        engine
+            .get_factory_mut(factory_id)
+            .unwrap()
+            .add_production_line(ProductionLine::ProductionLineRecipe(line));
+
+        let items = engine.update();
+        assert_eq!(items[&Item::IronPlate], 80.0);
+        assert_eq!(items[&Item::IronIngot], -100.0);
+
+        // Lines pick up changes to the recipe
+        let mut updated = engine.get_custom_recipe(recipe_id).unwrap().clone();
+        updated.cycle_time = 6.0;
+        engine.replace_custom_recipe(updated).unwrap();
+        assert_eq!(engine.update()[&Item::IronPlate], 40.0);
+
+        let json = engine.save_to_json().unwrap();
+        let mut loaded = SatisflowEngine::load_from_json(&json).unwrap();
+        assert_eq!(loaded.update()[&Item::IronPlate], 40.0);
+
+        // A recipe in use cannot be deleted
+        assert!(loaded.delete_custom_recipe(recipe_id).is_err());
+        loaded.delete_factory(factory_id).unwrap();
+        assert!(loaded.delete_custom_recipe(recipe_id).is_ok());
+    }
+
+    #[test]
+    fn test_validate_all() {
+        use crate::models::{production_line::MachineGroup, DiagnosticKind, Severity};
+
+        let mut engine = SatisflowEngine::new();
+        assert!(engine.validate_all().is_empty());
+
+        let smelter = engine.create_factory("Smelter".into(), None);
+        let mut line =
+            ProductionLineRecipe::new(uuid_from_u64(10), "Ingots".into(), None, Recipe::IronIngot);
+        line.add_machine_group(MachineGroup::new(2, 100.0, 0))
+            .unwrap();
+        engine
+            .get_factory_mut(smelter)
+            .unwrap()
+            .add_production_line(ProductionLine::ProductionLineRecipe(line));
+
+        let diagnostics = engine.validate_all();
+        let kinds: Vec<(Severity, DiagnosticKind)> = diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.severity, diagnostic.kind))
+            .collect();
+        // No ore comes in and no generator feeds the smelters
+        assert_eq!(
+            kinds,
+            vec![
+                (Severity::Error, DiagnosticKind::PowerDeficit),
+                (Severity::Warning, DiagnosticKind::ItemDeficit),
+            ]
+        );
+        assert_eq!(diagnostics[1].factory_id, Some(smelter));
+        assert_eq!(
+            diagnostics[1].message,
+            "Smelter is short of 60.0 Iron Ore/min"
+        );
+    }
+
+    #[test]
+    fn test_goals_track_production() {
+        use crate::models::production_line::MachineGroup;
+
+        let mut engine = SatisflowEngine::new();
+        let smelter = engine.create_factory("Smelter".into(), None);
+        let outpost = engine.create_factory("Outpost".into(), None);
+        for (factory_id, id, machines) in [(smelter, 10, 2), (outpost, 11, 1)] {
+            let mut line = ProductionLineRecipe::new(
+                uuid_from_u64(id),
+                "Ingots".into(),
+                None,
+                Recipe::IronIngot,
+            );
+            line.add_machine_group(MachineGroup::new(machines, 100.0, 0))
+                .unwrap();
+            engine
+                .get_factory_mut(factory_id)
+                .unwrap()
+                .add_production_line(ProductionLine::ProductionLineRecipe(line));
+        }
+
+        let global = engine
+            .create_goal(Item::IronIngot, 120.0, None, None)
+            .unwrap();
+        let local = engine
+            .create_goal(
+                Item::IronIngot,
+                60.0,
+                Some(smelter),
+                Some("Main line".into()),
+            )
+            .unwrap();
+        assert!(engine
+            .create_goal(Item::IronIngot, 0.0, None, None)
+            .is_err());
+        assert!(engine
+            .create_goal(Item::IronIngot, 10.0, Some(uuid_from_u64(99)), None)
+            .is_err());
+
+        engine.update();
+        let progress = engine.goal_progress(global).unwrap();
+        assert_eq!(progress.actual_rate, 90.0);
+        assert_eq!(progress.completion_percent, 75.0);
+        assert!(!progress.is_met);
+        let progress = engine.goal_progress(local).unwrap();
+        assert_eq!(progress.actual_rate, 60.0);
+        assert!(progress.is_met);
+        let ordered: Vec<GoalId> = engine
+            .all_goal_progress()
+            .iter()
+            .map(|progress| progress.goal_id)
+            .collect();
+        assert_eq!(ordered, vec![global, local]);
+
+        let mut goal = engine.get_goal(global).unwrap().clone();
+        goal.target_rate = 90.0;
+        assert_eq!(engine.replace_goal(goal).unwrap().target_rate, 120.0);
+        assert!(engine.goal_progress(global).unwrap().is_met);
+
+        let merged = engine
+            .merge_factories(&[smelter, outpost], "Ironworks")
+            .unwrap();
+        assert_eq!(engine.get_goal(local).unwrap().factory_id, Some(merged));
+        engine.delete_factory(merged).unwrap();
+        assert!(engine.get_goal(local).is_none());
+        assert!(engine.goal_progress(local).is_none());
+        engine.delete_goal(global).unwrap();
+        assert!(engine.delete_goal(global).is_err());
+    }
+
+    #[test]
+    fn test_power_storage_stats() {
+        use crate::models::{
+            ExtractorType, GeneratorGroup, GeneratorType, PowerGenerator, PowerStorage, Purity,
+            RawInput,
+        };
+
+        let mut engine = SatisflowEngine::new();
+        let plant = engine.create_factory("Coal Plant".into(), None);
+        let mut generator =
+            PowerGenerator::new(uuid_from_u64(1), GeneratorType::Coal, Item::Coal).unwrap();
+        generator
+            .add_group(GeneratorGroup::new(2, 100.0).unwrap())
+            .unwrap();
+        let factory = engine.get_factory_mut(plant).unwrap();
+        factory.add_power_generator(generator).unwrap();
+        factory
+            .set_power_storage(PowerStorage::new(1).with_stored(50.0))
+            .unwrap();
+        assert!(factory
+            .set_power_storage(PowerStorage::new(1).with_stored(500.0))
+            .is_err());
+
+        let outpost = engine.create_factory("Outpost".into(), None);
+        let miner = RawInput::new(
+            uuid_from_u64(2),
+            ExtractorType::MinerMk1,
+            Item::IronOre,
+            Some(Purity::Normal),
+            100.0,
+            1,
+        )
+        .unwrap();
+        let factory = engine.get_factory_mut(outpost).unwrap();
+        factory.add_raw_input(miner).unwrap();
+        factory
+            .set_power_storage(PowerStorage::new(1).with_stored(20.0))
+            .unwrap();
+
+        let stats = engine.global_power_stats();
+        let plant_stats = stats
+            .factory_stats
+            .iter()
+            .find(|s| s.factory_id == plant)
+            .unwrap();
+        // Charging is capped at the battery's 100 MW rate
+        assert_eq!(plant_stats.consumption, 100.0);
+        assert_eq!(plant_stats.balance, 50.0);
+        let plant_storage = plant_stats.storage.as_ref().unwrap();
+        assert_eq!(plant_storage.hours_until_full, Some(0.5));
+        assert_eq!(plant_storage.hours_until_depleted, None);
+
+        let outpost_stats = stats
+            .factory_stats
+            .iter()
+            .find(|s| s.factory_id == outpost)
+            .unwrap();
+        let outpost_storage = outpost_stats.storage.as_ref().unwrap();
+        assert_eq!(outpost_storage.charging_draw, 0.0);
+        assert_eq!(outpost_storage.hours_until_depleted, Some(4.0));
+
+        let storage = stats.storage.unwrap();
+        assert_eq!(storage.battery_count, 2);
+        assert_eq!(storage.stored_mwh, 70.0);
+        assert_eq!(storage.capacity_mwh, 200.0);
+        assert_eq!(storage.charging_draw, 100.0);
+        assert_eq!(stats.total_consumption, 105.0);
+        assert_eq!(storage.hours_until_depleted, None);
+
+        engine.get_factory_mut(plant).unwrap().clear_power_storage();
+        engine
+            .get_factory_mut(outpost)
+            .unwrap()
+            .clear_power_storage();
+        assert!(engine.global_power_stats().storage.is_none());
+    }
+
+    #[test]
+    fn test_power_stats_generation_range() {
+        use crate::models::{GeneratorGroup, GeneratorType, PowerGenerator, Purity};
+
+        let mut engine = SatisflowEngine::new();
+        let geysers = engine.create_factory("Geysers".into(), None);
+        let mut geothermal = PowerGenerator::new_geothermal(uuid_from_u64(1));
+        geothermal.set_purity(Purity::Impure).unwrap();
+        geothermal
+            .add_group(GeneratorGroup::new(1, 100.0).unwrap())
+            .unwrap();
+        let mut coal =
+            PowerGenerator::new(uuid_from_u64(2), GeneratorType::Coal, Item::Coal).unwrap();
+        coal.add_group(GeneratorGroup::new(1, 100.0).unwrap())
+            .unwrap();
+        let factory = engine.get_factory_mut(geysers).unwrap();
+        factory.add_power_generator(geothermal).unwrap();
+        factory.add_power_generator(coal).unwrap();
+
+        let stats = engine.global_power_stats();
+        assert_eq!(stats.total_generation, 175.0);
+        assert_eq!(stats.generation_range.min, 125.0);
+        assert_eq!(stats.generation_range.max, 225.0);
+        assert_eq!(stats.factory_stats[0].generation_range.average, 175.0);
+        assert_eq!(stats.worst_case_balance(), 125.0);
+    }
+
+    #[test]
+    fn test_power_stats_fuel_warnings() {
+        use crate::models::{GeneratorGroup, GeneratorType, PowerGenerator};
+
+        let mut engine = SatisflowEngine::new();
+        let mine = engine.create_factory("Mine".into(), None);
+        let plant = engine.create_factory("Plant".into(), None);
+        let mut coal =
+            PowerGenerator::new(uuid_from_u64(1), GeneratorType::Coal, Item::Coal).unwrap();
+        coal.add_group(GeneratorGroup::new(1, 100.0).unwrap())
+            .unwrap();
+        engine
+            .get_factory_mut(plant)
+            .unwrap()
+            .add_power_generator(coal)
+            .unwrap();
+        engine
+            .create_logistics_line(
+                mine,
+                plant,
+                TransportType::Truck(TruckTransport::new(1, Item::Coal, 10.0)),
+                "",
+            )
+            .unwrap();
+
+        let stats = engine.global_power_stats();
+        let plant_stats = stats
+            .factory_stats
+            .iter()
+            .find(|stat| stat.factory_id == plant)
+            .unwrap();
+        assert_eq!(plant_stats.fuel_shortfalls[0].shortfall, 5.0);
+        assert_eq!(
+            stats.fuel_warnings(),
+            vec!["Plant: Generators burn 15.00/min of Coal but only 10.00/min is available"]
+        );
+    }
+
+    #[test]
+    fn test_concurrent_reads_with_periodic_writes() {
+        use crate::models::production_line::MachineGroup;
+        use std::sync::RwLock;
+
+        const WRITES: u64 = 50;
+        const READERS: usize = 4;
+
+        let engine = RwLock::new(SatisflowEngine::new());
+
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                for i in 0..WRITES {
+                    let mut engine = engine.write().unwrap();
+                    let factory_id = engine.create_factory(format!("Factory {}", i), None);
+                    let mut line = ProductionLineRecipe::new(
+                        uuid_from_u64(1000 + i),
+                        "Ingots".into(),
+                        None,
+                        Recipe::IronIngot,
+                    );
+                    line.add_machine_group(MachineGroup::new(1, 100.0, 0))
+                        .unwrap();
+                    engine
+                        .get_factory_mut(factory_id)
+                        .unwrap()
+                        .add_production_line(ProductionLine::ProductionLineRecipe(line));
+                    engine.update();
+                }
+            });
+
+            for _ in 0..READERS {
+                scope.spawn(|| {
+                    let mut last_count = 0;
+                    loop {
+                        let engine = engine.read().unwrap();
+                        let report = engine.report_card();
+                        // Every factory is written with its line under the same lock
+                        assert_eq!(report.factory_count, report.production_line_count);
+                        assert!(report.factory_count >= last_count);
+                        assert_eq!(
+                            engine.global_power_stats().total_consumption,
+                            report.factory_count as f64 * 4.0
+                        );
+                        last_count = report.factory_count;
+                        if last_count == WRITES as usize {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+
+        let engine = engine.into_inner().unwrap();
+        assert_eq!(engine.get_all_factories().len(), WRITES as usize);
+        assert_eq!(engine.recent_changes(usize::MAX).len(), WRITES as usize * 2);
+    }
+
+    #[test]
+    fn test_nuclear_waste_report() {
+        use crate::models::{
+            production_line::MachineGroup, GeneratorGroup, GeneratorType, PowerGenerator,
+        };
+
+        let mut engine = SatisflowEngine::new();
+        let plant = engine.create_factory("Plant".into(), None);
+        let reprocessing = engine.create_factory("Reprocessing".into(), None);
+        engine.create_factory("Smelter".into(), None);
+
+        let mut uranium = PowerGenerator::new(
+            uuid_from_u64(1),
+            GeneratorType::Nuclear,
+            Item::UraniumFuelRod,
+        )
+        .unwrap();
+        uranium
+            .add_group(GeneratorGroup::new(2, 100.0).unwrap())
+            .unwrap();
+        let mut plutonium = PowerGenerator::new(
+            uuid_from_u64(2),
+            GeneratorType::Nuclear,
+            Item::PlutoniumFuelRod,
+        )
+        .unwrap();
+        plutonium
+            .add_group(GeneratorGroup::new(1, 100.0).unwrap())
+            .unwrap();
+        let factory = engine.get_factory_mut(plant).unwrap();
+        factory.add_power_generator(uranium).unwrap();
+        factory.add_power_generator(plutonium).unwrap();
+        factory.calculate_item(&HashMap::new());
+        assert!(factory.items.contains_key(&Item::PlutoniumWaste));
+
+        let mut non_fissile = ProductionLineRecipe::new(
+            uuid_from_u64(3),
+            "Non-fissile".into(),
+            None,
+            Recipe::NonFissileUranium,
+        );
+        non_fissile
+            .add_machine_group(MachineGroup::new(1, 100.0, 0))
+            .unwrap();
+        engine
+            .get_factory_mut(reprocessing)
+            .unwrap()
+            .add_production_line(ProductionLine::ProductionLineRecipe(non_fissile));
+        engine
+            .create_logistics_line(
+                plant,
+                reprocessing,
+                TransportType::Truck(TruckTransport::new(1, Item::UraniumWaste, 30.0)),
+                "",
+            )
+            .unwrap();
+
+        let report = engine.nuclear_waste_report();
+        assert_eq!(report.factories.len(), 2);
+        assert_eq!(report.factories[0].factory_name, "Plant");
+        let plant_waste = &report.factories[0].waste;
+        assert_eq!(plant_waste[0].item, Item::UraniumWaste);
+        assert_eq!(plant_waste[0].exported, 30.0);
+        assert_eq!(plant_waste[1].item, Item::PlutoniumWaste);
+        assert!(report.factories[0].is_accumulating());
+
+        let reprocessing_waste = &report.factories[1].waste;
+        assert_eq!(reprocessing_waste.len(), 1);
+        assert_eq!(reprocessing_waste[0].imported, 30.0);
+        assert_eq!(reprocessing_waste[0].reprocessed, 37.5);
+        assert_eq!(reprocessing_waste[0].accumulation, -7.5);
+
+        // Logistics cancel out in the totals
+        let uranium_total = &report.totals[0];
+        assert_eq!(uranium_total.imported, uranium_total.exported);
+        assert!((uranium_total.accumulation - (0.05 - 37.5)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_resource_inventory() {
+        use crate::models::{
+            production_line::MachineGroup, ExtractorType, GeneratorGroup, GeneratorType,
+            PowerGenerator, Purity, RawInput,
+        };
+
+        let mut engine = SatisflowEngine::new();
+        let smelter = engine.create_factory("Smelter".into(), None);
+        let plant = engine.create_factory("Plant".into(), None);
+        engine.create_factory("Empty".into(), None);
+
+        let mut ingots =
+            ProductionLineRecipe::new(uuid_from_u64(1), "Ingots".into(), None, Recipe::IronIngot);
+        ingots
+            .add_machine_group(MachineGroup::new(2, 250.0, 1))
+            .unwrap();
+        ingots
+            .add_machine_group(MachineGroup::new(4, 100.0, 0))
+            .unwrap();
+        let factory = engine.get_factory_mut(smelter).unwrap();
+        factory.add_production_line(ProductionLine::ProductionLineRecipe(ingots));
+        factory
+            .add_raw_input(
+                RawInput::new(
+                    uuid_from_u64(2),
+                    ExtractorType::MinerMk3,
+                    Item::IronOre,
+                    Some(Purity::Pure),
+                    150.0,
+                    2,
+                )
+                .unwrap(),
+            )
+            .unwrap();
+
+        let mut coal =
+            PowerGenerator::new(uuid_from_u64(3), GeneratorType::Coal, Item::Coal).unwrap();
+        coal.add_group(GeneratorGroup::new(3, 200.0).unwrap())
+            .unwrap();
+        engine
+            .get_factory_mut(plant)
+            .unwrap()
+            .add_power_generator(coal)
+            .unwrap();
+
+        let inventory = engine.resource_inventory();
+        assert_eq!(inventory.total_somersloops, 2);
+        assert_eq!(inventory.total_power_shards, 6 + 2 + 6);
+        assert_eq!(inventory.factories.len(), 2);
+        assert_eq!(inventory.factories[0].factory_name, "Plant");
+        assert_eq!(inventory.factories[0].generator_power_shards, 6);
+        let smelter_inventory = &inventory.factories[1];
+        assert_eq!(smelter_inventory.somersloops, 2);
+        assert_eq!(smelter_inventory.production_power_shards, 6);
+        assert_eq!(smelter_inventory.extractor_power_shards, 2);
+        assert_eq!(smelter_inventory.power_shards(), 8);
+    }
+
+    #[test]
+    fn test_balance_suggestions() {
+        use crate::models::{production_line::MachineGroup, ExtractorType, Purity, RawInput};
+
+        let mut engine = SatisflowEngine::new();
+        let factory_id = engine.create_factory("Iron Works".into(), None);
+        let factory = engine.get_factory_mut(factory_id).unwrap();
+        for (id, extractor_item) in [(1, Item::IronOre), (2, Item::CopperOre)] {
+            factory
+                .add_raw_input(
+                    RawInput::new(
+                        uuid_from_u64(id),
+                        ExtractorType::MinerMk1,
+                        extractor_item,
+                        Some(Purity::Normal),
+                        100.0,
+                        1,
+                    )
+                    .unwrap(),
+                )
+                .unwrap();
+        }
+        // 4 smelters need 120 ore/min but only 60 is mined
+        for (id, name, recipe, machines) in [
+            (3, "Iron Ingots", Recipe::IronIngot, 4),
+            (4, "Iron Plates", Recipe::IronPlate, 3),
+            (5, "Copper Ingots", Recipe::CopperIngot, 1),
+        ] {
+            let mut line = ProductionLineRecipe::new(uuid_from_u64(id), name.into(), None, recipe);
+            line.add_machine_group(MachineGroup::new(machines, 100.0, 0))
+                .unwrap();
+            factory.add_production_line(ProductionLine::ProductionLineRecipe(line));
+        }
+
+        let suggestions = engine.balance_suggestions(factory_id).unwrap();
+        assert_eq!(suggestions.len(), 3);
+
+        let copper = &suggestions[0];
+        assert_eq!(copper.production_line_name, "Copper Ingots");
+        assert!(!copper.is_starved());
+        assert_eq!(copper.max_machines, 2);
+
+        let iron = &suggestions[1];
+        assert_eq!(iron.production_line_name, "Iron Ingots");
+        assert_eq!(iron.clock_multiplier, 0.5);
+        assert_eq!(iron.max_machines, 2);
+        assert_eq!(iron.limiting_item, Some(Item::IronOre));
+
+        // Plates get the 60 ingots/min the starved smelters still make
+        let plates = &suggestions[2];
+        assert!((plates.clock_multiplier - 60.0 / 90.0).abs() < 1e-4);
+        assert_eq!(plates.max_machines, 2);
+        assert_eq!(plates.limiting_item, Some(Item::IronIngot));
+
+        assert!(engine.balance_suggestions(uuid_from_u64(99)).is_err());
+    }
+
+    #[test]
+    fn test_resource_node_report() {
+        use crate::models::{ExtractorType, Purity, RawInput};
+
+        let mut engine = SatisflowEngine::new();
+        assert!(engine
+            .set_resource_node_counts(Item::Water, NodeCounts::new(1, 1, 1))
+            .is_err());
+        engine
+            .set_resource_node_counts(Item::CateriumOre, NodeCounts::new(0, 1, 1))
+            .unwrap();
+
+        let factory_id = engine.create_factory("Caterium".into(), None);
+        let mut raw_input = RawInput::new(
+            uuid_from_u64(1),
+            ExtractorType::MinerMk3,
+            Item::CateriumOre,
+            Some(Purity::Pure),
+            100.0,
+            2,
+        )
+        .unwrap();
+        raw_input.set_claims_node(true).unwrap();
+        engine
+            .get_factory_mut(factory_id)
+            .unwrap()
+            .add_raw_input(raw_input)
+            .unwrap();
+
+        let report = engine.resource_node_report();
+        assert!(!report.is_valid);
+        assert_eq!(report.violations().count(), 1);
+
+        // Registry overrides are saved with the engine
+        let json = engine.save_to_json().unwrap();
+        let loaded = SatisflowEngine::load_from_json(&json).unwrap();
+        assert_eq!(
+            loaded.resource_nodes().counts(Item::CateriumOre),
+            NodeCounts::new(0, 1, 1)
+        );
+    }
+
+    #[test]
+    fn test_reverse_logistics_line() {
+        let mut engine = SatisflowEngine::new();
+        let factory_a = engine.create_factory("Factory A".into(), None);
+        let factory_b = engine.create_factory("Factory B".into(), None);
+
+        let logistics_id = engine
+            .create_logistics_line(
+                factory_a,
+                factory_b,
+                TransportType::Truck(TruckTransport::new(1, Item::IronOre, 60.0)),
+                "Ore run",
+            )
+            .unwrap();
+
+        engine.reverse_logistics_line(logistics_id).unwrap();
+
+        let line = engine.get_logistics_line(logistics_id).unwrap();
+        assert_eq!(line.from_factory, factory_b);
+        assert_eq!(line.to_factory, factory_a);
+        assert_eq!(line.transport_details.to_string(), "Ore run");
+
+        assert!(engine.reverse_logistics_line(uuid_from_u64(999)).is_err());
+    }
+
+    #[test]
+    fn test_split_logistics_line() {
+        use crate::models::logistics::{Train, Transport, Wagon, WagonType};
+
+        let mut engine = SatisflowEngine::new();
+        let factory_a = engine.create_factory("Factory A".into(), None);
+        let factory_b = engine.create_factory("Factory B".into(), None);
+
+        let train = Train::new(4, "Express")
+            .with_wagon(Wagon::new(1, WagonType::Cargo, Item::IronPlate, 120.0))
+            .with_wagon(Wagon::new(2, WagonType::Cargo, Item::Coal, 60.0))
+            .with_wagon(Wagon::new(3, WagonType::Cargo, Item::IronPlate, 60.0));
+        let logistics_id = engine
+            .create_logistics_line(
+                factory_a,
+                factory_b,
+                TransportType::Train(train),
+                String::new(),
+            )
+            .unwrap();
+
+        let ids = engine.split_logistics_line(logistics_id).unwrap();
+        assert_eq!(ids.len(), 2);
+        assert_eq!(ids[0], logistics_id);
+
+        let kept = engine.get_logistics_line(logistics_id).unwrap();
+        assert_eq!(kept.transport_type.get_transport_id(), "TRN-4");
+        assert_eq!(kept.total_quantity_per_min(), 180.0);
+        assert_eq!(kept.transport_type.distinct_items(), vec![Item::IronPlate]);
+
+        let split = engine.get_logistics_line(ids[1]).unwrap();
+        assert_eq!(split.from_factory, factory_a);
+        assert_eq!(split.to_factory, factory_b);
+        assert_eq!(split.transport_type.get_transport_id(), "TRN-5");
+        assert_eq!(
+            split.transport_type.get_transport_name(),
+            Some("Express - Coal".to_string())
+        );
+        assert_eq!(split.total_quantity_per_min(), 60.0);
+
+        // Single-item lines cannot be split further
+        assert!(engine.split_logistics_line(logistics_id).is_err());
+    }
+
+    #[test]
+    fn test_split_logistics_line_details() {
+        use crate::models::logistics::{Train, Wagon, WagonType};
+
+        let mut engine = SatisflowEngine::new();
+        let factory_a = engine.create_factory("Factory A".into(), None);
+        let factory_b = engine.create_factory("Factory B".into(), None);
+
+        let train = TransportType::Train(
+            Train::new(4, "Express")
+                .with_wagon(Wagon::new(1, WagonType::Cargo, Item::IronPlate, 120.0))
+                .with_wagon(Wagon::new(2, WagonType::Cargo, Item::Coal, 60.0)),
+        );
+        let wagon_ids = |ids: &[&str]| TransportDetails::Train {
+            wagon_ids: ids.iter().map(|id| id.to_string()).collect(),
+        };
+        // One label per wagon, and only train labels on a train
+        assert!(engine
+            .create_logistics_line(factory_a, factory_b, train.clone(), wagon_ids(&["WG-1"]))
+            .is_err());
+        assert!(engine
+            .create_logistics_line(
+                factory_a,
+                factory_b,
+                train.clone(),
+                TransportDetails::Truck {
+                    truck_id: "TRK-1".into()
+                },
+            )
+            .is_err());
+
+        let logistics_id = engine
+            .create_logistics_line(factory_a, factory_b, train, wagon_ids(&["WG-1", "WG-2"]))
+            .unwrap();
+        let ids = engine.split_logistics_line(logistics_id).unwrap();
+        assert_eq!(
+            engine.get_logistics_line(ids[0]).unwrap().transport_details,
+            wagon_ids(&["WG-1"])
+        );
+        assert_eq!(
+            engine.get_logistics_line(ids[1]).unwrap().transport_details,
+            wagon_ids(&["WG-2"])
+        );
+    }
+
+    #[test]
+    fn test_item_balance_report() {
+        use crate::models::{
+            production_line::MachineGroup, ExtractorType, ItemFlowSource, Purity, RawInput,
+        };
+
+        let mut engine = SatisflowEngine::new();
+        let smelter = engine.create_factory("Smelter".into(), None);
+        let assembler = engine.create_factory("Assembler".into(), None);
+
+        let mut plates =
+            ProductionLineRecipe::new(uuid_from_u64(1), "Plates".into(), None, Recipe::IronPlate);
+        plates
+            .add_machine_group(MachineGroup::new(2, 100.0, 0))
+            .unwrap();
+        engine
+            .get_factory_mut(smelter)
+            .unwrap()
+            .add_production_line(ProductionLine::ProductionLineRecipe(plates));
+        engine
+            .get_factory_mut(smelter)
+            .unwrap()
+            .add_raw_input(
+                RawInput::new(
+                    uuid_from_u64(2),
+                    ExtractorType::MinerMk2,
+                    Item::IronOre,
+                    Some(Purity::Normal),
+                    100.0,
+                    1,
+                )
+                .unwrap(),
+            )
+            .unwrap();
+
+        let export_id = engine
+            .create_logistics_line(
+                smelter,
+                assembler,
+                TransportType::Truck(TruckTransport::new(1, Item::IronPlate, 25.0)),
+                String::new(),
+            )
+            .unwrap();
+
+        let report = engine.item_balance_report(Item::IronPlate);
+        assert_eq!(report.total_produced, 40.0 + 25.0);
+        assert_eq!(report.total_consumed, 25.0);
+        assert_eq!(report.net, 40.0);
+        assert_eq!(report.producers.len(), 2);
+        assert_eq!(report.producers[0].factory_id, smelter);
+        assert!(matches!(
+            report.producers[0].source,
+            ItemFlowSource::ProductionLine { .. }
+        ));
+        assert_eq!(report.consumers.len(), 1);
+        assert_eq!(
+            report.consumers[0].source,
+            ItemFlowSource::LogisticsExport {
+                logistics_id: export_id,
+                to_factory: assembler,
+            }
+        );
+
+        // Net matches the aggregated balance from update()
+        let global = engine.update();
+        assert_eq!(global.get(&Item::IronPlate), Some(&report.net));
+
+        let ore = engine.item_balance_report(Item::IronOre);
+        assert_eq!(ore.total_produced, 120.0);
+        assert!(engine
+            .item_balance_report(Item::Computer)
+            .producers
+            .is_empty());
+    }
+
+    #[test]
+    fn test_merge_factories() {
+        use crate::models::{ExtractorType, Purity, RawInput};
+
+        let mut engine = SatisflowEngine::new();
+        let mine = engine.create_factory("Mine".into(), None);
+        let smelter = engine.create_factory("Smelter".into(), None);
+        let outside = engine.create_factory("Outside".into(), None);
+
+        engine
+            .get_factory_mut(mine)
+            .unwrap()
+            .add_raw_input(
+                RawInput::new(
+                    uuid_from_u64(1),
+                    ExtractorType::MinerMk2,
+                    Item::IronOre,
+                    Some(Purity::Normal),
+                    100.0,
+                    1,
+                )
+                .unwrap(),
+            )
+            .unwrap();
+        engine
+            .get_factory_mut(smelter)
+            .unwrap()
+            .add_production_line(ProductionLine::ProductionLineRecipe(
+                ProductionLineRecipe::new(
+                    uuid_from_u64(2),
+                    "Ingots".into(),
+                    None,
+                    Recipe::IronIngot,
+                ),
+            ));
+
+        let internal = engine
+            .create_logistics_line(
+                mine,
+                smelter,
+                TransportType::Truck(TruckTransport::new(1, Item::IronOre, 120.0)),
+                String::new(),
+            )
+            .unwrap();
+        let external = engine
+            .create_logistics_line(
+                smelter,
+                outside,
+                TransportType::Truck(TruckTransport::new(2, Item::IronOre, 30.0)),
+                String::new(),
+            )
+            .unwrap();
+
+        let preview = engine
+            .preview_merge_factories(&[mine, smelter], "Iron Works")
+            .unwrap();
+        assert_eq!(preview.raw_input_count, 1);
+        assert_eq!(preview.production_line_count, 1);
+        assert_eq!(preview.internalized_logistics, vec![internal]);
+        assert_eq!(preview.external_logistics, vec![external]);
+        assert_eq!(preview.items.len(), 1);
+        assert_eq!(preview.items[0].item, Item::IronOre);
+        assert_eq!(preview.items[0].quantity_per_min, 90.0);
+        // Preview leaves the engine untouched
+        assert_eq!(engine.get_all_factories().len(), 3);
+
+        let merged_id = engine
+            .merge_factories(&[mine, smelter], "Iron Works")
+            .unwrap();
 
-    /// Get a summary of the save file contents
-    pub fn summary(&self) -> SaveFileSummary {
-        SaveFileSummary {
-            version: self.version.clone(),
-            created_at: self.created_at,
-            last_modified: self.last_modified,
-            factory_count: self.engine.factories.len(),
-            logistics_count: self.engine.logistics_lines.len(),
-            blueprint_template_count: self.engine.blueprint_templates.len(),
-        }
+        assert_eq!(engine.get_all_factories().len(), 2);
+        assert!(engine.get_factory(mine).is_none());
+        let merged = engine.get_factory(merged_id).unwrap();
+        assert_eq!(merged.name, "Iron Works");
+        assert_eq!(merged.raw_inputs.len(), 1);
+        assert_eq!(merged.production_lines.len(), 1);
+        let transfer = merged.internal_transfers.values().next().unwrap();
+        assert_eq!(merged.internal_transfers.len(), 1);
+        assert_eq!(transfer.from_area, "Mine");
+        assert_eq!(transfer.to_area, "Smelter");
+        assert_eq!(transfer.quantity_per_min, 120.0);
+
+        assert!(engine.get_logistics_line(internal).is_none());
+        let rewired = engine.get_logistics_line(external).unwrap();
+        assert_eq!(rewired.from_factory, merged_id);
+        assert_eq!(rewired.to_factory, outside);
     }
-}
-
-/// Summary information about a save file
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SaveFileSummary {
-    pub version: String,
-    pub created_at: DateTime<Utc>,
-    pub last_modified: DateTime<Utc>,
-    pub factory_count: usize,
-    pub logistics_count: usize,
-    pub blueprint_template_count: usize,
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::models::{
-        logistics::{DroneTransport, TransportType, TruckTransport},
-        production_line::{ProductionLine, ProductionLineBlueprint, ProductionLineRecipe},
-        Item, Recipe,
-    };
-    use uuid::Uuid;
+    #[test]
+    fn test_merge_two_factories_removes_lines_between_them() {
+        use crate::models::{ExtractorType, Purity, RawInput};
 
-    fn uuid_from_u64(value: u64) -> Uuid {
-        Uuid::from_u128(value as u128)
+        let mut engine = SatisflowEngine::new();
+        let mine = engine.create_factory("Mine".into(), None);
+        let smelter = engine.create_factory("Smelter".into(), None);
+        let outside = engine.create_factory("Outside".into(), None);
+        engine
+            .get_factory_mut(mine)
+            .unwrap()
+            .add_raw_input(
+                RawInput::new(
+                    uuid_from_u64(1),
+                    ExtractorType::MinerMk2,
+                    Item::IronOre,
+                    Some(Purity::Normal),
+                    100.0,
+                    1,
+                )
+                .unwrap(),
+            )
+            .unwrap();
+        let mut truck = |from, to, number, quantity| {
+            engine
+                .create_logistics_line(
+                    from,
+                    to,
+                    TransportType::Truck(TruckTransport::new(number, Item::IronOre, quantity)),
+                    String::new(),
+                )
+                .unwrap()
+        };
+        let there = truck(mine, smelter, 1, 40.0);
+        let back = truck(smelter, mine, 2, 10.0);
+        let export = truck(mine, outside, 3, 5.0);
+        let import = truck(outside, smelter, 4, 3.0);
+        engine.update();
+        let ore =
+            |engine: &SatisflowEngine, id| engine.get_factory(id).unwrap().items[&Item::IronOre];
+        let before = ore(&engine, mine) + ore(&engine, smelter);
+
+        let merged_id = engine
+            .merge_factories(&[mine, smelter], "Iron Works")
+            .unwrap();
+        engine.update();
+
+        // Both directions between the two factories are gone, the flows stay
+        // documented as internal transfers
+        assert!(engine.get_logistics_line(there).is_none());
+        assert!(engine.get_logistics_line(back).is_none());
+        assert_eq!(engine.get_all_logistics().len(), 2);
+        let merged = engine.get_factory(merged_id).unwrap();
+        assert_eq!(merged.internal_transfers.len(), 2);
+        assert_eq!(
+            engine.get_logistics_line(export).unwrap().from_factory,
+            merged_id
+        );
+        assert_eq!(
+            engine.get_logistics_line(import).unwrap().to_factory,
+            merged_id
+        );
+        // Internal lines cancelled out, so the merged balance is the sum
+        assert_eq!(ore(&engine, merged_id), before);
+        assert_eq!(ore(&engine, outside), 2.0);
     }
 
     #[test]
-    fn test_get_all_factories() {
+    fn test_merge_factories_keeps_user_data() {
         let mut engine = SatisflowEngine::new();
+        let mine = engine.create_factory("Mine".into(), Some("Iron ore".into()));
+        let smelter = engine.create_factory("Smelter".into(), None);
+        let outside = engine.create_factory("Outside".into(), None);
+        for (id, batteries, tags, x, notes) in [
+            (mine, 1, vec!["North", "Iron"], 0.0, "Pure node"),
+            (
+                smelter,
+                2,
+                vec!["iron", "Phase 2"],
+                1000.0,
+                "Needs a bigger belt",
+            ),
+        ] {
+            let factory = engine.get_factory_mut(id).unwrap();
+            factory
+                .set_power_storage(PowerStorage::new(batteries).with_stored(50.0))
+                .unwrap();
+            factory.set_tags(tags.into_iter().map(String::from).collect());
+            factory.position = Some(Position::new(x, 200.0, 0.0));
+            factory.notes = Some(notes.into());
+        }
+        engine.pin_factory(outside).unwrap();
+        engine.pin_factory(smelter).unwrap();
+        engine.pin_factory(mine).unwrap();
+        let mine_goal = engine
+            .create_goal(Item::IronOre, 60.0, Some(mine), None)
+            .unwrap();
+        let smelter_goal = engine
+            .create_goal(Item::IronIngot, 30.0, Some(smelter), None)
+            .unwrap();
+        engine.update();
 
-        // Create some factories
-        let factory1_id = engine.create_factory("Factory 1".to_string(), None);
-        let factory2_id =
-            engine.create_factory("Factory 2".to_string(), Some("Test factory".to_string()));
+        let merged_id = engine
+            .merge_factories(&[mine, smelter], "Iron Works")
+            .unwrap();
 
-        // Get all factories
-        let all_factories = engine.get_all_factories();
+        let merged = engine.get_factory(merged_id).unwrap();
+        let storage = merged.power_storage.as_ref().unwrap();
+        assert_eq!(storage.battery_count, 3);
+        assert_eq!(storage.stored_mwh, 100.0);
+        assert_eq!(merged.tags, vec!["North", "Iron", "Phase 2"]);
+        assert_eq!(merged.position, Some(Position::new(500.0, 200.0, 0.0)));
+        assert_eq!(merged.description.as_deref(), Some("Iron ore"));
+        assert_eq!(
+            merged.notes.as_deref(),
+            Some("Pure node\n\nNeeds a bigger belt")
+        );
 
-        // Verify all factories are returned
-        assert_eq!(all_factories.len(), 2);
-        assert!(all_factories.contains_key(&factory1_id));
-        assert!(all_factories.contains_key(&factory2_id));
+        assert_eq!(engine.pinned().factories, vec![outside, merged_id]);
+        for goal in [mine_goal, smelter_goal] {
+            assert_eq!(engine.get_goal(goal).unwrap().factory_id, Some(merged_id));
+            assert_eq!(
+                engine.goal_progress(goal).unwrap().factory_id,
+                Some(merged_id)
+            );
+        }
 
-        // Verify factory details
-        let factory1 = all_factories.get(&factory1_id).unwrap();
-        assert_eq!(factory1.name, "Factory 1");
-        assert!(factory1.description.is_none());
+        // Deleting the merged factory then clears them like any other factory
+        engine.delete_factory(merged_id).unwrap();
+        assert_eq!(engine.pinned().factories, vec![outside]);
+        assert!(engine.get_all_goals().is_empty());
+    }
 
-        let factory2 = all_factories.get(&factory2_id).unwrap();
-        assert_eq!(factory2.name, "Factory 2");
-        assert_eq!(factory2.description.as_ref().unwrap(), "Test factory");
+    #[test]
+    fn test_merge_factories_validation() {
+        let mut engine = SatisflowEngine::new();
+        let factory_a = engine.create_factory("A".into(), None);
+        let factory_b = engine.create_factory("B".into(), None);
+
+        assert!(engine.merge_factories(&[factory_a], "Merged").is_err());
+        assert!(engine
+            .merge_factories(&[factory_a, factory_a], "Merged")
+            .is_err());
+        assert!(engine
+            .merge_factories(&[factory_a, uuid_from_u64(999)], "Merged")
+            .is_err());
+        assert!(engine
+            .merge_factories(&[factory_a, factory_b], "  ")
+            .is_err());
+        assert_eq!(engine.get_all_factories().len(), 2);
     }
 
     #[test]
-    fn test_get_all_logistics() {
+    fn test_tag_group_stats() {
         let mut engine = SatisflowEngine::new();
+        let north = engine.create_factory("North Smelter".into(), None);
+        let both = engine.create_factory("Border Depot".into(), None);
+        let loose = engine.create_factory("Loose".into(), None);
+        engine.get_factory_mut(north).unwrap().set_tags(vec![
+            "North".into(),
+            " phase 1 ".into(),
+            "north".into(),
+        ]);
+        engine
+            .get_factory_mut(both)
+            .unwrap()
+            .set_tags(vec!["North".into(), "South".into()]);
+        engine.get_factory_mut(both).unwrap().archived = true;
 
-        // Create factories first
-        let factory1_id = engine.create_factory("Factory 1".to_string(), None);
-        let factory2_id = engine.create_factory("Factory 2".to_string(), None);
+        assert_eq!(
+            engine.get_factory(north).unwrap().tags,
+            vec!["North".to_string(), "phase 1".to_string()]
+        );
+        assert!(engine.get_factory(both).unwrap().has_tag("south"));
 
-        // Create some logistics lines
-        let transport1 = TransportType::Truck(TruckTransport::new(1, Item::IronOre, 60.0));
-        let logistics1_id = engine
+        let groups = engine.tag_group_stats();
+        let tags: Vec<Option<&str>> = groups.iter().map(|group| group.tag.as_deref()).collect();
+        assert_eq!(
+            tags,
+            vec![Some("North"), Some("phase 1"), Some("South"), None]
+        );
+        assert_eq!(groups[0].factory_ids.len(), 2);
+        assert_eq!(groups[0].archived_factories, 1);
+        assert_eq!(groups[3].factory_ids, vec![loose]);
+    }
+
+    #[test]
+    fn test_route_estimates() {
+        let mut engine = SatisflowEngine::new();
+        let mine = engine.create_factory("Mine".into(), None);
+        let smelter = engine.create_factory("Smelter".into(), None);
+        let truck = engine
             .create_logistics_line(
-                factory1_id,
-                factory2_id,
-                transport1,
-                "Test truck".to_string(),
+                mine,
+                smelter,
+                TransportType::Truck(TruckTransport::new(1, Item::IronOre, 120.0)),
+                String::new(),
             )
             .unwrap();
-
-        let transport2 = TransportType::Truck(TruckTransport::new(2, Item::CopperOre, 120.0));
-        let logistics2_id = engine
+        let bus = engine
             .create_logistics_line(
-                factory2_id,
-                factory1_id,
-                transport2,
-                "Test truck 2".to_string(),
+                mine,
+                smelter,
+                TransportType::Bus(Bus::new(1, "Main")),
+                String::new(),
             )
             .unwrap();
 
-        // Get all logistics
-        let all_logistics = engine.get_all_logistics();
-
-        // Verify all logistics lines are returned
-        assert_eq!(all_logistics.len(), 2);
-        assert!(all_logistics.contains_key(&logistics1_id));
-        assert!(all_logistics.contains_key(&logistics2_id));
+        // Nothing to estimate until both ends are on the map
+        engine
+            .set_factory_position(mine, Some(Position::new(0.0, 0.0, 0.0)))
+            .unwrap();
+        assert!(engine.route_estimate(truck).is_none());
 
-        // Verify logistics details
-        let logistics1 = all_logistics.get(&logistics1_id).unwrap();
-        assert_eq!(logistics1.from_factory, factory1_id);
-        assert_eq!(logistics1.to_factory, factory2_id);
-        assert_eq!(logistics1.transport_details, "Test truck");
+        engine
+            .set_factory_position(smelter, Some(Position::new(0.0, 840.0, 0.0)))
+            .unwrap();
+        let estimate = engine.route_estimate(truck).unwrap();
+        assert_eq!(estimate.distance, 840.0);
+        // 2 × 60 s of driving plus 2 × 12 s docking
+        assert_eq!(estimate.round_trip_secs, 144.0);
+        assert_eq!(estimate.cargo_per_trip[0].quantity, 288.0);
+        assert!(engine.route_estimate(bus).is_none());
+        assert_eq!(engine.route_estimates(), vec![estimate]);
+
+        assert!(engine
+            .set_factory_position(mine, Some(Position::new(f64::INFINITY, 0.0, 0.0)))
+            .is_err());
+        engine.set_factory_position(mine, None).unwrap();
+        assert!(engine.route_estimates().is_empty());
     }
 
     #[test]
-    fn test_delete_factory() {
+    fn test_train_throughput() {
         let mut engine = SatisflowEngine::new();
-
-        // Create factories
-        let factory1_id = engine.create_factory("Factory 1".to_string(), None);
-        let factory2_id = engine.create_factory("Factory 2".to_string(), None);
-
-        // Create logistics line between factories
-        let transport = TransportType::Truck(TruckTransport::new(1, Item::IronOre, 60.0));
-        let logistics_id = engine
+        let mine = engine.create_factory("Mine".into(), None);
+        let smelter = engine.create_factory("Smelter".into(), None);
+        let train = Train::new(1, "Ore Express").with_wagon(Wagon::new(
+            1,
+            WagonType::Cargo,
+            Item::IronOre,
+            2000.0,
+        ));
+        let train = engine
+            .create_logistics_line(mine, smelter, TransportType::Train(train), String::new())
+            .unwrap();
+        let truck = engine
             .create_logistics_line(
-                factory1_id,
-                factory2_id,
-                transport,
-                "Test truck".to_string(),
+                mine,
+                smelter,
+                TransportType::Truck(TruckTransport::new(1, Item::IronOre, 120.0)),
+                String::new(),
             )
             .unwrap();
 
-        // Verify initial state
-        assert_eq!(engine.get_all_factories().len(), 2);
-        assert_eq!(engine.get_all_logistics().len(), 1);
+        assert!(engine.train_schedule(train).is_none());
+        engine
+            .set_factory_position(mine, Some(Position::new(0.0, 0.0, 0.0)))
+            .unwrap();
+        engine
+            .set_factory_position(smelter, Some(Position::new(6000.0, 0.0, 0.0)))
+            .unwrap();
+        assert!(engine.train_schedule(truck).is_none());
 
-        // Delete factory
-        engine.delete_factory(factory1_id).unwrap();
+        // 2 × 240 s of travel plus 2 × 27 s at the stations
+        let schedule = engine.train_schedule(train).unwrap();
+        let throughput = engine.train_throughput(train, &schedule).unwrap();
+        assert_eq!(throughput.round_trip_secs, 534.0);
+        assert!(throughput.wagons[0].over_capacity);
 
-        // Verify factory is deleted
-        assert_eq!(engine.get_all_factories().len(), 1);
-        assert!(!engine.get_all_factories().contains_key(&factory1_id));
-        assert!(engine.get_all_factories().contains_key(&factory2_id));
+        // Faster trains keep up
+        let throughput = engine
+            .train_throughput(train, &schedule.with_speed(50.0))
+            .unwrap();
+        assert_eq!(throughput.round_trip_secs, 294.0);
+        assert!(!throughput.is_over_capacity());
 
-        // Verify connected logistics lines are also deleted
-        assert_eq!(engine.get_all_logistics().len(), 0);
-        assert!(!engine.get_all_logistics().contains_key(&logistics_id));
+        assert!(engine
+            .train_throughput(truck, &TrainSchedule::new(100.0))
+            .is_err());
     }
 
     #[test]
-    fn test_delete_factory_not_found() {
+    fn test_clone_factory() {
+        use crate::models::production_line::MachineGroup;
+
         let mut engine = SatisflowEngine::new();
+        let smelter = engine.create_factory("Smelter".into(), None);
+        let depot = engine.create_factory("Depot".into(), None);
+        let mut ingots =
+            ProductionLineRecipe::new(uuid_from_u64(1), "Ingots".into(), None, Recipe::IronIngot);
+        ingots
+            .add_machine_group(MachineGroup::new(2, 100.0, 0))
+            .unwrap();
+        engine
+            .get_factory_mut(smelter)
+            .unwrap()
+            .add_production_line(ProductionLine::ProductionLineRecipe(ingots));
+        engine
+            .create_logistics_line(
+                smelter,
+                depot,
+                TransportType::Truck(TruckTransport::new(1, Item::IronIngot, 60.0)),
+                "Ingots",
+            )
+            .unwrap();
 
-        // Try to delete non-existent factory
-        let missing_id = uuid_from_u64(999);
-        let result = engine.delete_factory(missing_id);
+        assert!(engine.clone_factory(smelter, " ", false).is_err());
+        assert!(engine
+            .clone_factory(uuid_from_u64(99), "Copy", false)
+            .is_err());
 
-        // Verify error
-        assert!(result.is_err());
-        let error = result.unwrap_err().to_string();
-        assert!(
-            error.contains(&missing_id.to_string()),
-            "expected error to reference missing factory id, got: {error}"
+        let plain = engine.clone_factory(smelter, "Smelter 2", false).unwrap();
+        let copy = engine.get_factory(plain).unwrap();
+        assert_eq!(copy.name, "Smelter 2");
+        assert_eq!(copy.production_lines.len(), 1);
+        assert!(!copy.production_lines.contains_key(&uuid_from_u64(1)));
+        assert_eq!(engine.get_all_logistics().len(), 1);
+
+        let with_tasks = engine.clone_factory(smelter, "Smelter 3", true).unwrap();
+        assert_eq!(engine.get_all_logistics().len(), 1);
+        assert!(engine
+            .get_all_logistics()
+            .values()
+            .all(|line| line.from_factory != with_tasks && line.to_factory != with_tasks));
+        let copy = engine.get_factory(with_tasks).unwrap();
+        assert_eq!(copy.tasks.len(), 1);
+        assert_eq!(copy.tasks[0].text, "Connect a copy of Ingots to Depot");
+        assert!(!copy.tasks[0].done);
+
+        // Nothing is shipped from the copy: the depot only receives the original truck
+        engine.update();
+        assert_eq!(
+            engine
+                .get_factory(depot)
+                .unwrap()
+                .items
+                .get(&Item::IronIngot),
+            Some(&60.0)
+        );
+        assert_eq!(
+            engine
+                .get_factory(with_tasks)
+                .unwrap()
+                .items
+                .get(&Item::IronIngot),
+            Some(&60.0)
         );
     }
 
     #[test]
-    fn test_delete_logistics_line() {
+    fn test_factory_templates() {
+        use crate::models::production_line::MachineGroup;
+
         let mut engine = SatisflowEngine::new();
+        let outpost = engine.create_factory("Outpost".into(), Some("Iron field".into()));
+        let grid = engine.create_power_grid("Main".into(), None);
+        engine
+            .assign_factory_to_power_grid(outpost, Some(grid))
+            .unwrap();
+        let mut ingots =
+            ProductionLineRecipe::new(uuid_from_u64(1), "Ingots".into(), None, Recipe::IronIngot);
+        ingots
+            .add_machine_group(MachineGroup::new(2, 100.0, 0))
+            .unwrap();
+        engine
+            .get_factory_mut(outpost)
+            .unwrap()
+            .add_production_line(ProductionLine::ProductionLineRecipe(ingots));
+
+        assert!(engine
+            .save_factory_as_template(outpost, Some(" "), None)
+            .is_err());
+        let template_id = engine
+            .save_factory_as_template(outpost, None, None)
+            .unwrap();
+        let template = engine.get_factory_template(template_id).unwrap();
+        assert_eq!(template.name, "Outpost");
+        assert_eq!(template.description.as_deref(), Some("Iron field"));
+        assert_eq!(template.factory.power_grid, None);
 
-        // Create factories
-        let factory1_id = engine.create_factory("Factory 1".to_string(), None);
-        let factory2_id = engine.create_factory("Factory 2".to_string(), None);
+        // Later changes to the factory don't reach the template
+        engine
+            .get_factory_mut(outpost)
+            .unwrap()
+            .production_lines
+            .clear();
+
+        assert!(engine
+            .instantiate_factory_template(template_id, "")
+            .is_err());
+        let copy_id = engine
+            .instantiate_factory_template(template_id, "Outpost 2")
+            .unwrap();
+        let copy = engine.get_factory(copy_id).unwrap();
+        assert_eq!(copy.name, "Outpost 2");
+        assert_eq!(copy.production_lines.len(), 1);
+        assert!(!copy.production_lines.contains_key(&uuid_from_u64(1)));
+        assert_eq!(copy.power_grid, None);
+
+        // Templates are saved, and factories made from them outlive them
+        let loaded = SatisflowEngine::load_from_json(&engine.save_to_json().unwrap()).unwrap();
+        assert!(loaded.get_factory_template(template_id).is_some());
+        engine.delete_factory_template(template_id).unwrap();
+        assert!(engine.delete_factory_template(template_id).is_err());
+        assert!(engine.get_factory(copy_id).is_some());
+    }
 
-        // Create logistics line
-        let transport = TransportType::Truck(TruckTransport::new(1, Item::IronOre, 60.0));
-        let logistics_id = engine
-            .create_logistics_line(
-                factory1_id,
-                factory2_id,
-                transport,
-                "Test truck".to_string(),
-            )
+    #[test]
+    fn test_copy_and_move_production_lines() {
+        use crate::models::production_line::MachineGroup;
+
+        let mut engine = SatisflowEngine::new();
+        let smelter = engine.create_factory("Smelter".into(), None);
+        let foundry = engine.create_factory("Foundry".into(), None);
+        let mut ingots =
+            ProductionLineRecipe::new(uuid_from_u64(1), "Ingots".into(), None, Recipe::IronIngot);
+        ingots
+            .add_machine_group(MachineGroup::new(4, 150.0, 1))
+            .unwrap();
+        engine
+            .get_factory_mut(smelter)
+            .unwrap()
+            .add_production_line(ProductionLine::ProductionLineRecipe(ingots));
+        let task = FactoryTask::new(uuid_from_u64(2), "Build it", Some(uuid_from_u64(1))).unwrap();
+        engine
+            .get_factory_mut(smelter)
+            .unwrap()
+            .add_task(task)
             .unwrap();
 
-        // Verify initial state
-        assert_eq!(engine.get_all_logistics().len(), 1);
+        assert!(engine
+            .copy_production_line(smelter, uuid_from_u64(99), foundry)
+            .is_err());
+        assert!(engine
+            .copy_production_line(smelter, uuid_from_u64(1), uuid_from_u64(99))
+            .is_err());
+        let copy_id = engine
+            .copy_production_line(smelter, uuid_from_u64(1), foundry)
+            .unwrap();
+        assert_ne!(copy_id, uuid_from_u64(1));
+        let copy = &engine.get_factory(foundry).unwrap().production_lines[&copy_id];
+        assert_eq!(copy.total_machines(), 4);
+        assert_eq!(copy.total_somersloop(), 4);
+        assert_eq!(
+            engine.get_factory(smelter).unwrap().production_lines.len(),
+            1
+        );
 
-        // Delete logistics line
-        engine.delete_logistics_line(logistics_id).unwrap();
+        assert!(engine
+            .move_production_line(smelter, uuid_from_u64(1), smelter)
+            .is_err());
+        engine
+            .move_production_line(smelter, uuid_from_u64(1), foundry)
+            .unwrap();
+        let smelter = engine.get_factory(smelter).unwrap();
+        let foundry = engine.get_factory(foundry).unwrap();
+        assert!(smelter.production_lines.is_empty());
+        assert!(smelter.tasks.is_empty());
+        assert!(foundry.production_lines.contains_key(&uuid_from_u64(1)));
+        assert_eq!(foundry.tasks.len(), 1);
+    }
 
-        // Verify logistics line is deleted
-        assert_eq!(engine.get_all_logistics().len(), 0);
-        assert!(!engine.get_all_logistics().contains_key(&logistics_id));
+    #[test]
+    fn test_split_factory_creates_boundary_buses() {
+        use crate::models::production_line::MachineGroup;
 
-        // Verify factories are still there
-        assert_eq!(engine.get_all_factories().len(), 2);
+        let mut engine = SatisflowEngine::new();
+        let factory_id = engine.create_factory("Iron Works".into(), None);
+
+        let mut ingots =
+            ProductionLineRecipe::new(uuid_from_u64(1), "Ingots".into(), None, Recipe::IronIngot);
+        ingots
+            .add_machine_group(MachineGroup::new(2, 100.0, 0))
+            .unwrap();
+        let mut plates =
+            ProductionLineRecipe::new(uuid_from_u64(2), "Plates".into(), None, Recipe::IronPlate);
+        plates
+            .add_machine_group(MachineGroup::new(2, 100.0, 0))
+            .unwrap();
+        {
+            let factory = engine.get_factory_mut(factory_id).unwrap();
+            factory.add_production_line(ProductionLine::ProductionLineRecipe(ingots));
+            factory.add_production_line(ProductionLine::ProductionLineRecipe(plates));
+            let task = FactoryTask::new(
+                uuid_from_u64(3),
+                "Build constructors",
+                Some(uuid_from_u64(2)),
+            );
+            factory.add_task(task.unwrap()).unwrap();
+        }
+
+        let before = engine.update();
+
+        let result = engine
+            .split_factory(
+                factory_id,
+                &FactorySplitSelection {
+                    production_line_ids: vec![uuid_from_u64(2)],
+                    ..Default::default()
+                },
+                "Plate Shop",
+                true,
+            )
+            .unwrap();
+
+        assert_eq!(result.source_factory, factory_id);
+        assert_eq!(result.logistics_ids.len(), 2);
+        let source = engine.get_factory(factory_id).unwrap();
+        assert_eq!(source.production_lines.len(), 1);
+        let new_factory = engine.get_factory(result.new_factory).unwrap();
+        assert_eq!(new_factory.name, "Plate Shop");
+        assert!(new_factory.production_lines.contains_key(&uuid_from_u64(2)));
+        assert!(source.tasks.is_empty());
+        assert_eq!(new_factory.tasks[0].id, uuid_from_u64(3));
+
+        // Ingots flow into the new factory, plates flow back
+        let inbound = engine.get_logistics_line(result.logistics_ids[0]).unwrap();
+        assert_eq!(inbound.from_factory, factory_id);
+        assert_eq!(inbound.to_factory, result.new_factory);
+        assert_eq!(inbound.get_items()[0].item, Item::IronIngot);
+        assert_eq!(inbound.total_quantity_per_min(), 60.0);
+        let outbound = engine.get_logistics_line(result.logistics_ids[1]).unwrap();
+        assert_eq!(outbound.get_items()[0].item, Item::IronPlate);
+        assert_eq!(outbound.total_quantity_per_min(), 40.0);
+
+        // The combined balance is unchanged
+        let after = engine.update();
+        assert_eq!(before.get(&Item::IronPlate), after.get(&Item::IronPlate));
+        assert_eq!(before.get(&Item::IronOre), after.get(&Item::IronOre));
+        assert_eq!(
+            engine
+                .get_factory(result.new_factory)
+                .unwrap()
+                .items
+                .get(&Item::IronIngot),
+            Some(&0.0)
+        );
     }
 
     #[test]
-    fn test_delete_logistics_line_not_found() {
+    fn test_split_factory_validation() {
         let mut engine = SatisflowEngine::new();
+        let factory_id = engine.create_factory("Iron Works".into(), None);
 
-        // Try to delete non-existent logistics line
-        let missing_id = uuid_from_u64(9999);
-        let result = engine.delete_logistics_line(missing_id);
+        let missing_line = FactorySplitSelection {
+            production_line_ids: vec![uuid_from_u64(5)],
+            ..Default::default()
+        };
+        let missing_generator = FactorySplitSelection {
+            power_generator_ids: vec![uuid_from_u64(6)],
+            ..Default::default()
+        };
+        assert!(engine
+            .split_factory(factory_id, &FactorySplitSelection::default(), "New", true)
+            .is_err());
+        assert!(engine
+            .split_factory(factory_id, &missing_line, "New", true)
+            .is_err());
+        assert!(engine
+            .split_factory(factory_id, &missing_generator, "New", true)
+            .is_err());
+        assert!(engine
+            .split_factory(uuid_from_u64(999), &missing_line, "New", true)
+            .is_err());
+        assert_eq!(engine.get_all_factories().len(), 1);
+    }
 
-        // Verify error
-        assert!(result.is_err());
-        let error = result.unwrap_err().to_string();
-        assert!(
-            error.contains(&missing_id.to_string()),
-            "expected error to reference missing logistics id, got: {error}"
+    #[test]
+    fn test_split_factory_moves_raw_inputs_and_generators() {
+        use crate::models::{
+            ExtractorType, GeneratorGroup, GeneratorType, PowerGenerator, Purity, RawInput,
+        };
+
+        let mut engine = SatisflowEngine::new();
+        let factory_id = engine.create_factory("Iron Works".into(), None);
+        let miner = RawInput::new(
+            uuid_from_u64(1),
+            ExtractorType::MinerMk1,
+            Item::IronOre,
+            Some(Purity::Normal),
+            100.0,
+            1,
+        )
+        .unwrap();
+        let mut generator =
+            PowerGenerator::new(uuid_from_u64(2), GeneratorType::Coal, Item::Coal).unwrap();
+        generator
+            .add_group(GeneratorGroup::new(1, 100.0).unwrap())
+            .unwrap();
+        {
+            let factory = engine.get_factory_mut(factory_id).unwrap();
+            factory.add_raw_input(miner).unwrap();
+            factory.add_power_generator(generator).unwrap();
+        }
+
+        let selection = FactorySplitSelection {
+            raw_input_ids: vec![uuid_from_u64(1)],
+            ..Default::default()
+        };
+        let result = engine
+            .split_factory(factory_id, &selection, "Mine", true)
+            .unwrap();
+        let new_factory = engine.get_factory(result.new_factory).unwrap();
+        assert!(new_factory.raw_inputs.contains_key(&uuid_from_u64(1)));
+        assert!(engine
+            .get_factory(factory_id)
+            .unwrap()
+            .raw_inputs
+            .is_empty());
+        // The ore is shipped back to the source factory
+        assert_eq!(result.logistics_ids.len(), 1);
+        let outbound = engine.get_logistics_line(result.logistics_ids[0]).unwrap();
+        assert_eq!(outbound.from_factory, result.new_factory);
+        assert_eq!(outbound.get_items()[0].item, Item::IronOre);
+        assert_eq!(outbound.total_quantity_per_min(), 60.0);
+
+        // Without buses only the generator moves
+        let selection = FactorySplitSelection {
+            power_generator_ids: vec![uuid_from_u64(2)],
+            ..Default::default()
+        };
+        let result = engine
+            .split_factory(factory_id, &selection, "Power", false)
+            .unwrap();
+        assert!(result.logistics_ids.is_empty());
+        assert_eq!(
+            engine
+                .get_factory(result.new_factory)
+                .unwrap()
+                .power_generators
+                .len(),
+            1
         );
+        assert!(engine
+            .get_factory(factory_id)
+            .unwrap()
+            .power_generators
+            .is_empty());
+        assert_eq!(engine.get_all_logistics().len(), 1);
     }
 
     #[test]
-    fn test_update_logistics_line() {
+    fn test_factory_dependencies() {
         let mut engine = SatisflowEngine::new();
+        let mine = engine.create_factory("Mine".into(), None);
+        let smelter = engine.create_factory("Smelter".into(), None);
+        let assembler = engine.create_factory("Assembler".into(), None);
+        let isolated = engine.create_factory("Isolated".into(), None);
 
-        let factory_a = engine.create_factory("Factory A".into(), None);
-        let factory_b = engine.create_factory("Factory B".into(), None);
-        let factory_c = engine.create_factory("Factory C".into(), None);
-
-        let original_transport = TransportType::Truck(TruckTransport::new(1, Item::IronOre, 60.0));
-        let logistics_id = engine
+        engine
             .create_logistics_line(
-                factory_a,
-                factory_b,
-                original_transport,
-                "Initial truck route".into(),
+                mine,
+                smelter,
+                TransportType::Truck(TruckTransport::new(1, Item::IronOre, 60.0)),
+                String::new(),
             )
-            .expect("should create logistics line");
-
-        let updated_transport =
-            TransportType::Drone(DroneTransport::new(5, Item::CircuitBoard, 45.0));
-
+            .unwrap();
         engine
-            .update_logistics_line(
-                logistics_id,
-                factory_b,
-                factory_c,
-                updated_transport.clone(),
-                "Updated drone route".into(),
+            .create_logistics_line(
+                mine,
+                smelter,
+                TransportType::Truck(TruckTransport::new(2, Item::IronOre, 30.0)),
+                String::new(),
             )
-            .expect("should update logistics line");
+            .unwrap();
+        engine
+            .create_logistics_line(
+                smelter,
+                assembler,
+                TransportType::Drone(DroneTransport::new(1, Item::IronPlate, 20.0)),
+                String::new(),
+            )
+            .unwrap();
 
-        let updated_line = engine
-            .get_logistics_line(logistics_id)
-            .expect("logistics line should exist");
-        assert_eq!(updated_line.from_factory, factory_b);
-        assert_eq!(updated_line.to_factory, factory_c);
-        assert_eq!(updated_line.transport_type, updated_transport);
-        assert_eq!(updated_line.transport_details, "Updated drone route");
+        let upstream = engine.dependencies_of(smelter).unwrap();
+        assert_eq!(upstream.len(), 1);
+        assert_eq!(upstream[0].factory_id, mine);
+        assert_eq!(upstream[0].logistics_ids.len(), 2);
+        assert_eq!(upstream[0].items.len(), 1);
+        assert_eq!(upstream[0].items[0].quantity_per_min, 90.0);
+
+        let downstream = engine.dependents_of(smelter).unwrap();
+        assert_eq!(downstream.len(), 1);
+        assert_eq!(downstream[0].factory_name, "Assembler");
+        assert_eq!(downstream[0].items[0].item, Item::IronPlate);
+
+        let all = engine.factory_dependencies(isolated).unwrap();
+        assert!(all.dependencies.is_empty());
+        assert!(all.dependents.is_empty());
+
+        assert!(engine.dependents_of(uuid_from_u64(999)).is_err());
     }
 
     #[test]
@@ -877,7 +6266,90 @@ mod tests {
         let loaded_logistics = loaded_engine.get_logistics_line(logistics_id).unwrap();
         assert_eq!(loaded_logistics.from_factory, factory1_id);
         assert_eq!(loaded_logistics.to_factory, factory2_id);
-        assert_eq!(loaded_logistics.transport_details, "Test truck");
+        assert_eq!(loaded_logistics.transport_details.to_string(), "Test truck");
+    }
+
+    #[test]
+    fn test_save_and_load_compressed_file() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let save_path = temp_dir.path().join("test_save.json.gz");
+
+        let mut engine = SatisflowEngine::new();
+        let factory_id = engine.create_factory("Compressed Factory".to_string(), None);
+        engine.save_to_file_compressed(&save_path).unwrap();
+
+        let bytes = std::fs::read(&save_path).unwrap();
+        assert!(compression::is_gzip(&bytes));
+        assert!(bytes.len() < engine.save_to_json().unwrap().len());
+
+        let loaded_engine = SatisflowEngine::load_from_file(&save_path).unwrap();
+        assert_eq!(
+            loaded_engine.get_factory(factory_id).unwrap().name,
+            "Compressed Factory"
+        );
+
+        // A truncated header with the FEXTRA flag set is an error, not a panic
+        std::fs::write(
+            &save_path,
+            [0x1f, 0x8b, 0x08, 0x04, 0, 0, 0, 0, 0, 0xff, 0x05],
+        )
+        .unwrap();
+        assert!(SatisflowEngine::load_from_file(&save_path).is_err());
+    }
+
+    #[test]
+    fn test_save_formats() {
+        let mut engine = SatisflowEngine::new();
+        let factory_id = engine.create_factory("Binary Factory".to_string(), None);
+
+        let save = |format| {
+            let options = SaveOptions {
+                format,
+                ..Default::default()
+            };
+            engine.save_to_bytes_with_options(&options).unwrap()
+        };
+        let pretty = save(SaveFormat::JsonPretty);
+        let compact = save(SaveFormat::JsonCompact);
+        let binary = save(SaveFormat::MessagePack);
+        assert!(compact.len() < pretty.len());
+        assert!(binary.len() < compact.len());
+
+        for bytes in [pretty, compact, binary.clone(), compression::gzip(&binary)] {
+            let loaded = SatisflowEngine::load_from_bytes(&bytes).unwrap();
+            assert_eq!(
+                loaded.get_factory(factory_id).unwrap().name,
+                "Binary Factory"
+            );
+        }
+
+        let options = SaveOptions {
+            format: SaveFormat::MessagePack,
+            ..Default::default()
+        };
+        assert!(engine.save_to_json_with_options(&options).is_err());
+
+        // The binary encoding keeps every entity, for the current version and
+        // for an older one alike
+        let example = examples::load_example("five-factory").unwrap();
+        let binary = example.save_to_bytes_with_options(&options).unwrap();
+        let loaded = SatisflowEngine::load_from_bytes(&binary).unwrap();
+        assert_eq!(
+            serde_json::to_value(&loaded).unwrap(),
+            serde_json::to_value(&example).unwrap()
+        );
+        let options = SaveOptions {
+            format: SaveFormat::MessagePack,
+            target_version: SaveVersion::new(0, 1, 0),
+        };
+        let binary = example.save_to_bytes_with_options(&options).unwrap();
+        let loaded = SatisflowEngine::load_from_bytes(&binary).unwrap();
+        assert_eq!(
+            loaded.get_all_factories().len(),
+            example.get_all_factories().len()
+        );
     }
 
     #[test]
@@ -922,7 +6394,225 @@ mod tests {
         assert_eq!(save_file.version, env!("CARGO_PKG_VERSION"));
         assert!(save_file.created_at <= Utc::now());
         assert!(save_file.last_modified <= Utc::now());
-        assert!(save_file.game_version.is_none());
+        assert_eq!(
+            save_file.game_version.as_deref(),
+            Some(models::game_data::GAME_VERSION)
+        );
+    }
+
+    #[test]
+    fn test_save_file_game_version() {
+        let mut engine = SatisflowEngine::new();
+        assert!(engine.set_game_data_version("0.8").is_err());
+        engine.set_game_data_version("1.2").unwrap();
+
+        let json = engine.save_to_json().unwrap();
+        let loaded = SatisflowEngine::load_from_json(&json).unwrap();
+        assert_eq!(loaded.game_data().version, "1.2");
+
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value["game_version"] = serde_json::json!("0.8");
+        let error = SatisflowEngine::load_from_json(&value.to_string()).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("Unknown game data version '0.8'"));
+
+        // Saves without a game version use the latest data
+        value["game_version"] = serde_json::Value::Null;
+        let loaded = SatisflowEngine::load_from_json(&value.to_string()).unwrap();
+        assert!(std::ptr::eq(loaded.game_data(), GameDataSet::latest()));
+    }
+
+    #[test]
+    fn test_ficsmas_toggle() {
+        use crate::models::{production_line::MachineGroup, DiagnosticKind};
+
+        let mut engine = SatisflowEngine::new();
+        let factory_id = engine.create_factory("Workshop".to_string(), None);
+        let mut line =
+            ProductionLineRecipe::new(uuid_from_u64(1), "Candy".into(), None, Recipe::CandyCane);
+        line.add_machine_group(MachineGroup::new(1, 100.0, 0))
+            .unwrap();
+        engine
+            .get_factory_mut(factory_id)
+            .unwrap()
+            .add_production_line(ProductionLine::ProductionLineRecipe(line));
+
+        // Off by default: the gifts the line lacks are nobody's problem
+        let items = engine.update();
+        assert!(!items.contains_key(&Item::CandyCane));
+        assert!(engine
+            .item_balance_report(Item::CandyCane)
+            .producers
+            .is_empty());
+        assert!(engine
+            .validate_all()
+            .iter()
+            .all(|diagnostic| diagnostic.kind != DiagnosticKind::ItemDeficit));
+
+        engine.set_ficsmas_enabled(true).unwrap();
+        let items = engine.update();
+        assert_eq!(items.get(&Item::CandyCane), Some(&5.0));
+        assert_eq!(items.get(&Item::FICSMASGift), Some(&-15.0));
+        assert!(engine
+            .validate_all()
+            .iter()
+            .any(|diagnostic| diagnostic.kind == DiagnosticKind::ItemDeficit));
+
+        let loaded = SatisflowEngine::load_from_json(&engine.save_to_json().unwrap()).unwrap();
+        assert!(loaded.ficsmas_enabled());
+    }
+
+    #[test]
+    fn test_game_balance_override() {
+        use crate::models::{production_line::MachineGroup, ExtractorType, Purity, RawInput};
+
+        let mut engine = SatisflowEngine::new();
+        let factory_id = engine.create_factory("Mine".to_string(), None);
+        let mut line =
+            ProductionLineRecipe::new(uuid_from_u64(1), "Ingots".into(), None, Recipe::IronIngot);
+        line.add_machine_group(MachineGroup::new(1, 200.0, 0))
+            .unwrap();
+        let factory = engine.get_factory_mut(factory_id).unwrap();
+        factory.add_production_line(ProductionLine::ProductionLineRecipe(line));
+        factory
+            .add_raw_input(
+                RawInput::new(
+                    uuid_from_u64(2),
+                    ExtractorType::MinerMk1,
+                    Item::IronOre,
+                    Some(Purity::Pure),
+                    100.0,
+                    1,
+                )
+                .unwrap(),
+            )
+            .unwrap();
+        assert!(!engine
+            .save_to_json()
+            .unwrap()
+            .contains("overclock_exponent"));
+
+        // Smelter at 200% with power linear in clock speed, and richer pure nodes
+        engine
+            .set_game_balance(GameBalance {
+                overclock_exponent: 1.0,
+                pure_multiplier: 3.0,
+                ..GameBalance::default()
+            })
+            .unwrap();
+        let items = engine.update();
+        assert_eq!(items.get(&Item::IronOre), Some(&120.0));
+        let factory = engine.get_factory(factory_id).unwrap();
+        assert_eq!(factory.machine_power_consumption(), 13.0);
+
+        // New factories and reloaded saves calculate with the same constants
+        let other = engine.create_factory("Other".to_string(), None);
+        assert_eq!(
+            engine.get_factory(other).unwrap().balance().pure_multiplier,
+            3.0
+        );
+        let loaded = SatisflowEngine::load_from_json(&engine.save_to_json().unwrap()).unwrap();
+        assert_eq!(loaded.game_balance().overclock_exponent, 1.0);
+        assert_eq!(
+            loaded
+                .get_factory(factory_id)
+                .unwrap()
+                .machine_power_consumption(),
+            13.0
+        );
+
+        assert!(engine
+            .set_game_balance(GameBalance {
+                overclock_exponent: 0.0,
+                ..GameBalance::default()
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn test_validation_reports() {
+        use crate::models::{GeneratorGroup, GeneratorType, PowerGenerator};
+
+        let mut engine = SatisflowEngine::new();
+        let mine = engine.create_factory("Mine".to_string(), None);
+        let smelter = engine.create_factory("Smelter".to_string(), None);
+
+        // An overloaded belt is planned anyway, with a warning
+        let mut bus = Bus::new(1, "Main");
+        bus.add_conveyor(Conveyor::new(1, ConveyorSpeed::Mk1, Item::IronOre, 120.0));
+        let transport = TransportType::Bus(bus);
+        let report =
+            engine.check_logistics_line(mine, smelter, &transport, &TransportDetails::default());
+        assert!(report.is_valid());
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].contains("over its Mk1 limit of 60"));
+        assert!(engine
+            .create_logistics_line(mine, smelter, transport.clone(), String::new())
+            .is_ok());
+
+        let missing = FactoryId::new_v4();
+        let report =
+            engine.check_logistics_line(mine, missing, &transport, &TransportDetails::default());
+        assert_eq!(
+            report.errors,
+            vec![format!("Factory with id {} does not exist", missing)]
+        );
+        let error = engine
+            .create_logistics_line(mine, missing, transport, String::new())
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!("Factory with id {} does not exist", missing)
+        );
+
+        // Neither coal nor water is made where the generator stands
+        let mut generator =
+            PowerGenerator::new(uuid_from_u64(1), GeneratorType::Coal, Item::Coal).unwrap();
+        let report = engine.check_power_generator(smelter, &generator);
+        assert_eq!(report.errors.len(), 1);
+        generator
+            .add_group(GeneratorGroup::new(2, 100.0).unwrap())
+            .unwrap();
+        let report = engine.check_power_generator(smelter, &generator);
+        assert!(report.is_valid());
+        assert_eq!(
+            report.warnings,
+            vec![
+                "Coal is not produced in Smelter; its generators rely on imports",
+                "Water is not produced in Smelter; its generators rely on imports",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_save_file_ref_matches_save_file() {
+        let mut engine = SatisflowEngine::new();
+        engine.create_factory("Test Factory".to_string(), None);
+
+        let borrowed = SaveFileRef::new(&engine);
+        let mut owned = SaveFile::new(engine.clone());
+        owned.created_at = borrowed.created_at;
+        owned.last_modified = borrowed.last_modified;
+        assert_eq!(
+            serde_json::to_string(&borrowed).unwrap(),
+            serde_json::to_string(&owned).unwrap()
+        );
+
+        // Saving straight from the engine writes the same file as going
+        // through the version-stripping path
+        let without_times = |json: &str| {
+            let mut value: serde_json::Value = serde_json::from_str(json).unwrap();
+            value["created_at"] = serde_json::Value::Null;
+            value["last_modified"] = serde_json::Value::Null;
+            value
+        };
+        let direct = engine.save_to_json().unwrap();
+        let stripped = engine
+            .save_as_version(&SaveVersion::current())
+            .unwrap()
+            .save_data;
+        assert_eq!(without_times(&direct), without_times(&stripped));
     }
 
     #[test]
@@ -1028,6 +6718,127 @@ mod tests {
         assert!(err.to_string().contains("incompatible"));
     }
 
+    #[test]
+    fn test_lenient_load_keeps_fields_from_newer_minor_version() {
+        let mut engine = SatisflowEngine::new();
+        let factory_id = engine.create_factory("Test".to_string(), None);
+        let current_version = SaveVersion::current();
+        let mut value: serde_json::Value =
+            serde_json::from_str(&engine.save_to_json().unwrap()).unwrap();
+        value["version"] =
+            format!("{}.{}.0", current_version.major, current_version.minor + 1).into();
+        value["engine"]["hub_layout"] = serde_json::json!({ "floors": 2 });
+        value["engine"]["factories"][factory_id.to_string()]["floor"] = 3.into();
+        let json = value.to_string();
+
+        let err = SatisflowEngine::load_from_json(&json).unwrap_err();
+        assert!(err.to_string().contains("too new"));
+
+        let loaded = SatisflowEngine::load_from_json_with_options(&json, &LoadOptions::default())
+            .unwrap()
+            .engine;
+        let factory = loaded.get_factory(factory_id).unwrap();
+        assert_eq!(factory.unknown_fields.get("floor"), Some(&3.into()));
+
+        let saved: serde_json::Value =
+            serde_json::from_str(&loaded.save_to_json().unwrap()).unwrap();
+        assert_eq!(saved["engine"]["hub_layout"]["floors"], 2);
+        assert_eq!(
+            saved["engine"]["factories"][factory_id.to_string()]["floor"],
+            3
+        );
+    }
+
+    #[test]
+    fn test_strict_load_rejects_unknown_names_and_fields_with_paths() {
+        let mut engine = SatisflowEngine::new();
+        let factory_id = engine.create_factory("Test".to_string(), None);
+        let json = engine.save_to_json().unwrap();
+        let strict = LoadOptions { strict: true };
+        assert!(SatisflowEngine::load_from_json_with_options(&json, &strict)
+            .unwrap()
+            .warnings
+            .is_empty());
+
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value["engine"]["factories"][factory_id.to_string()]["floor"] = 3.into();
+        let err =
+            SatisflowEngine::load_from_json_with_options(&value.to_string(), &strict).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("engine.factories.{}.floor: Unknown field", factory_id)
+        );
+
+        value["engine"]["factories"][factory_id.to_string()]["items"] =
+            serde_json::json!({ "Unobtainium": 5.0 });
+        let err =
+            SatisflowEngine::load_from_json_with_options(&value.to_string(), &strict).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "engine.factories.{}.items.Unobtainium: Unknown item `Unobtainium`",
+                factory_id
+            )
+        );
+    }
+
+    #[test]
+    fn test_lenient_load_works_around_unknown_names() {
+        let mut engine = SatisflowEngine::new();
+        let factory_id = engine.create_factory("Test".to_string(), None);
+        let goal_id = engine
+            .create_goal(Item::IronPlate, 30.0, None, None)
+            .unwrap();
+        let mut value: serde_json::Value =
+            serde_json::from_str(&engine.save_to_json().unwrap()).unwrap();
+        value["engine"]["goals"][goal_id.to_string()]["item"] = "Unobtainium".into();
+        value["engine"]["factories"][factory_id.to_string()]["floor"] = 3.into();
+
+        let loaded = SatisflowEngine::load_from_json_with_options(
+            &value.to_string(),
+            &LoadOptions::default(),
+        )
+        .unwrap();
+        assert!(loaded.engine.get_goal(goal_id).is_none());
+        assert_eq!(loaded.summary.factory_count, 1);
+        let paths: Vec<&str> = loaded.warnings.iter().map(|w| w.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                format!("engine.goals.{}.item", goal_id),
+                format!("engine.factories.{}.floor", factory_id),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_repair_removes_dangling_logistics_and_defaults_version() {
+        let mut engine = SatisflowEngine::new();
+        let factory1_id = engine.create_factory("Factory 1".to_string(), None);
+        let factory2_id = engine.create_factory("Factory 2".to_string(), None);
+        let transport = TransportType::Truck(TruckTransport::new(1, Item::IronOre, 60.0));
+        let line_id = engine
+            .create_logistics_line(factory1_id, factory2_id, transport, "Truck".to_string())
+            .unwrap();
+        let mut value: serde_json::Value =
+            serde_json::from_str(&engine.save_to_json().unwrap()).unwrap();
+        value.as_object_mut().unwrap().remove("version");
+        value["engine"]["factories"]
+            .as_object_mut()
+            .unwrap()
+            .remove(&factory2_id.to_string());
+        assert!(SatisflowEngine::load_from_json(&value.to_string()).is_err());
+
+        let (repaired, report) = SatisflowEngine::repair(&value.to_string()).unwrap();
+        assert_eq!(repaired.get_all_factories().len(), 1);
+        assert!(repaired.get_logistics_line(line_id).is_none());
+        assert_eq!(report.removed_logistics, vec![line_id]);
+        assert_eq!(report.defaulted_sections, vec!["version".to_string()]);
+
+        let (_, report) = SatisflowEngine::repair(&repaired.save_to_json().unwrap()).unwrap();
+        assert!(report.is_clean());
+    }
+
     #[test]
     fn test_load_missing_version() {
         let json = r#"{
@@ -1110,6 +6921,246 @@ mod tests {
         assert_eq!(engine.get_all_logistics().len(), 0);
     }
 
+    #[test]
+    fn test_revision_follows_mutations() {
+        let mut engine = SatisflowEngine::new();
+        assert_eq!(engine.revision(), 0);
+
+        let factory_id = engine.create_factory("Factory 1".to_string(), None);
+        let after_create = engine.revision();
+        assert!(after_create > 0);
+
+        // Reading and recomputing derived data leave it alone
+        engine.get_factory(factory_id).unwrap();
+        engine.update();
+        assert_eq!(engine.revision(), after_create);
+
+        engine.get_factory_mut(factory_id).unwrap().name = "Renamed".to_string();
+        assert!(engine.revision() > after_create);
+
+        // Replacing the state with a loaded save doesn't go back
+        let before_load = engine.revision();
+        let loaded = SatisflowEngine::load_from_json(&engine.save_to_json().unwrap()).unwrap();
+        assert_eq!(loaded.revision(), 0);
+        engine.replace(loaded);
+        assert!(engine.revision() > before_load);
+        assert_eq!(engine.get_factory(factory_id).unwrap().name, "Renamed");
+    }
+
+    #[test]
+    fn test_update_sums_every_factory() {
+        use crate::models::{ExtractorType, Purity, RawInput};
+
+        let mut engine = SatisflowEngine::new();
+        let mut factory_ids = Vec::new();
+        for index in 0..200u64 {
+            let factory_id = engine.create_factory(format!("Factory {}", index), None);
+            engine
+                .get_factory_mut(factory_id)
+                .unwrap()
+                .add_raw_input(
+                    RawInput::new(
+                        uuid_from_u64(index),
+                        ExtractorType::MinerMk1,
+                        Item::IronOre,
+                        Some(Purity::Normal),
+                        60.0,
+                        1,
+                    )
+                    .unwrap(),
+                )
+                .unwrap();
+            factory_ids.push(factory_id);
+        }
+        // Chain every factory to the next so each balance depends on logistics
+        for pair in factory_ids.windows(2) {
+            engine
+                .create_logistics_line(
+                    pair[0],
+                    pair[1],
+                    TransportType::Truck(TruckTransport::new(1, Item::IronOre, 30.0)),
+                    String::new(),
+                )
+                .unwrap();
+        }
+
+        let global = engine.update();
+        let ore =
+            |factory_id: FactoryId| engine.get_factory(factory_id).unwrap().items[&Item::IronOre];
+        // A factory in the middle of the chain passes on what it receives
+        let mined = ore(factory_ids[100]);
+        assert!(mined > 0.0);
+        assert_eq!(global[&Item::IronOre], 200.0 * mined);
+        assert_eq!(ore(factory_ids[0]), mined - 30.0);
+        assert_eq!(ore(factory_ids[199]), mined + 30.0);
+    }
+
+    #[test]
+    fn test_engine_queries() {
+        let truck = |number, item| TransportType::Truck(TruckTransport::new(number, item, 30.0));
+        let mut engine = SatisflowEngine::new();
+        let smelter = engine
+            .add_factory(
+                Factory::builder("Smelter")
+                    .production_line(
+                        ProductionLineBuilder::recipe("Ingots", Recipe::IronIngot)
+                            .machines(2, 100.0, 0),
+                    )
+                    .production_line(
+                        ProductionLineBuilder::recipe("Idle plates", Recipe::IronPlate)
+                            .machines(1, 100.0, 0)
+                            .enabled(false),
+                    )
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+        let assembly = engine
+            .add_factory(
+                Factory::builder("Assembly")
+                    .production_line(
+                        ProductionLineBuilder::blueprint("Plates and rods")
+                            .line(
+                                ProductionLineBuilder::recipe("Plates", Recipe::IronPlate)
+                                    .machines(2, 100.0, 0),
+                            )
+                            .line(
+                                ProductionLineBuilder::recipe("Rods", Recipe::IronRod)
+                                    .machines(1, 100.0, 0),
+                            ),
+                    )
+                    .power_generator(
+                        PowerGenerator::builder(GeneratorType::Coal)
+                            .fuel(Item::Coal)
+                            .group(1, 100.0),
+                    )
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+        let ingots = engine
+            .create_logistics_line(smelter, assembly, truck(1, Item::IronIngot), String::new())
+            .unwrap();
+        engine
+            .create_logistics_line(assembly, smelter, truck(2, Item::IronPlate), String::new())
+            .unwrap();
+
+        let ids = |factories: Vec<&Factory>| {
+            let mut ids: Vec<FactoryId> = factories.iter().map(|factory| factory.id).collect();
+            ids.sort();
+            ids
+        };
+        assert_eq!(
+            ids(engine.factories_producing(Item::IronIngot).collect()),
+            vec![smelter]
+        );
+        // The smelter's plate line is disabled
+        assert_eq!(
+            ids(engine.factories_producing(Item::IronPlate).collect()),
+            vec![assembly]
+        );
+        assert_eq!(
+            ids(engine.factories_consuming(Item::IronIngot).collect()),
+            vec![assembly]
+        );
+        assert_eq!(
+            ids(engine.factories_consuming(Item::Coal).collect()),
+            vec![assembly]
+        );
+        assert_eq!(
+            ids(engine.factories_consuming(Item::Water).collect()),
+            vec![assembly]
+        );
+        assert_eq!(engine.factories_producing(Item::Coal).count(), 0);
+
+        let between: Vec<_> = engine.logistics_between(smelter, assembly).collect();
+        assert_eq!(between.len(), 1);
+        assert_eq!(between[0].id, ingots);
+        assert_eq!(engine.logistics_between(assembly, smelter).count(), 1);
+        assert_eq!(engine.logistics_between(smelter, smelter).count(), 0);
+
+        let mut using_plates: Vec<(&str, &str)> = engine
+            .production_lines_using_recipe(Recipe::IronPlate)
+            .map(|(factory, line)| (factory.name.as_str(), line.name()))
+            .collect();
+        using_plates.sort();
+        assert_eq!(
+            using_plates,
+            vec![("Assembly", "Plates and rods"), ("Smelter", "Idle plates")]
+        );
+        assert_eq!(
+            engine.production_lines_using_recipe(Recipe::Screw).count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_logistics_index_follows_mutations() {
+        // The index must agree with a full scan of the lines
+        fn assert_indexed(engine: &SatisflowEngine) {
+            for factory_id in engine.get_all_factories().keys().copied() {
+                let sorted = |lines: Vec<&LogisticsFlux>| {
+                    let mut ids: Vec<LogisticsId> = lines.iter().map(|line| line.id).collect();
+                    ids.sort();
+                    ids
+                };
+                let lines = engine.get_all_logistics();
+                assert_eq!(
+                    sorted(engine.incoming_logistics(factory_id).collect()),
+                    sorted(lines.inbound(factory_id).collect())
+                );
+                assert_eq!(
+                    sorted(engine.outgoing_logistics(factory_id).collect()),
+                    sorted(lines.outbound(factory_id).collect())
+                );
+            }
+        }
+        let truck = |number, item| TransportType::Truck(TruckTransport::new(number, item, 30.0));
+
+        let mut engine = SatisflowEngine::new();
+        let mine = engine.create_factory("Mine".into(), None);
+        let smelter = engine.create_factory("Smelter".into(), None);
+        let assembly = engine.create_factory("Assembly".into(), None);
+        let ore = engine
+            .create_logistics_line(mine, smelter, truck(1, Item::IronOre), String::new())
+            .unwrap();
+        let ingots = engine
+            .create_logistics_line(smelter, assembly, truck(2, Item::IronIngot), String::new())
+            .unwrap();
+        assert_indexed(&engine);
+        assert_eq!(engine.incoming_logistics(smelter).count(), 1);
+
+        engine.reverse_logistics_line(ingots).unwrap();
+        assert_indexed(&engine);
+        assert_eq!(
+            engine.outgoing_logistics(assembly).next().unwrap().id,
+            ingots
+        );
+
+        engine
+            .update_logistics_line(ore, mine, assembly, truck(1, Item::IronOre), String::new())
+            .unwrap();
+        assert_indexed(&engine);
+        assert_eq!(engine.incoming_logistics(smelter).count(), 1);
+        assert_eq!(engine.incoming_logistics(assembly).count(), 1);
+
+        let merged = engine
+            .merge_factories(&[mine, smelter], "Iron Works")
+            .unwrap();
+        assert_indexed(&engine);
+        assert_eq!(engine.incoming_logistics(merged).count(), 1);
+        assert_eq!(engine.outgoing_logistics(merged).count(), 1);
+
+        let reloaded = SatisflowEngine::load_from_json(&engine.save_to_json().unwrap()).unwrap();
+        assert_indexed(&reloaded);
+        assert_eq!(reloaded.outgoing_logistics(merged).count(), 1);
+
+        engine.delete_factory(assembly).unwrap();
+        assert_indexed(&engine);
+        assert!(engine.get_all_logistics().is_empty());
+        assert_eq!(engine.outgoing_logistics(merged).count(), 0);
+    }
+
     // =========================================================================
     // Blueprint Template Tests
     // =========================================================================
@@ -1120,14 +7171,14 @@ mod tests {
 
         // Create a blueprint template
         let mut blueprint = ProductionLineBlueprint::new(
-            Uuid::new_v4(),
+            Uuid::new_v4().into(),
             "Test Blueprint".to_string(),
             Some("A test blueprint".to_string()),
         );
 
         // Add a production line to the blueprint
         let line = ProductionLineRecipe::new(
-            Uuid::new_v4(),
+            Uuid::new_v4().into(),
             "Iron Ingot Line".to_string(),
             Some("Smelts ore".to_string()),
             Recipe::IronIngot,
@@ -1157,10 +7208,150 @@ mod tests {
         assert_eq!(engine.get_all_blueprint_templates().len(), 0);
     }
 
+    #[test]
+    fn test_blueprint_template_versions() {
+        let mut engine = SatisflowEngine::new();
+        let template = |id: u64, name: &str| {
+            ProductionLineBlueprint::new(uuid_from_u64(id), name.to_string(), None)
+        };
+
+        let v1 = engine.add_blueprint_template(template(1, "Motors"));
+        let v2 = engine
+            .add_blueprint_template_version(v1, template(2, "Motors"), None, None)
+            .unwrap();
+        let v3 = engine
+            .add_blueprint_template_version(
+                v2,
+                template(3, "Motors"),
+                Some(TemplateVersion::new(2, 0, 0)),
+                Some("Switch to alternate rotors".to_string()),
+            )
+            .unwrap();
+        engine.add_blueprint_template(template(4, "Unrelated"));
+
+        let revision = engine.get_blueprint_template(v3).unwrap().revision.clone();
+        assert_eq!(
+            revision,
+            Some(TemplateRevision {
+                version: TemplateVersion::new(2, 0, 0),
+                parent_id: Some(v2),
+                changelog: Some("Switch to alternate rotors".to_string()),
+            })
+        );
+
+        // Any version gives the whole history, oldest first
+        for id in [v1, v2, v3] {
+            let history: Vec<_> = engine
+                .blueprint_template_history(id)
+                .unwrap()
+                .iter()
+                .map(|template| template.id)
+                .collect();
+            assert_eq!(history, vec![v1, v2, v3]);
+        }
+        let versions: Vec<_> = engine
+            .blueprint_template_history(v1)
+            .unwrap()
+            .iter()
+            .map(|template| template.revision.clone().unwrap().version.to_string())
+            .collect();
+        assert_eq!(versions, vec!["1.0.0", "1.1.0", "2.0.0"]);
+
+        // Versions only move forward
+        assert!(engine
+            .add_blueprint_template_version(
+                v3,
+                template(5, "Motors"),
+                Some(TemplateVersion::new(1, 5, 0)),
+                None
+            )
+            .is_err());
+        assert!(engine
+            .add_blueprint_template_version(uuid_from_u64(99), template(6, "Motors"), None, None)
+            .is_err());
+        assert!(engine
+            .blueprint_template_history(uuid_from_u64(99))
+            .is_err());
+    }
+
+    #[test]
+    fn test_propagate_blueprint_template() {
+        use crate::models::production_line::MachineGroup;
+
+        let mut engine = SatisflowEngine::new();
+        let template = |id: u64, machines: u32| {
+            let mut line = ProductionLineRecipe::new(
+                Uuid::new_v4().into(),
+                "Ingots".to_string(),
+                None,
+                Recipe::IronIngot,
+            );
+            line.add_machine_group(MachineGroup::new(machines, 100.0, 0))
+                .unwrap();
+            let mut blueprint =
+                ProductionLineBlueprint::new(uuid_from_u64(id), "Smelting".to_string(), None);
+            blueprint.add_production_line(line);
+            blueprint
+        };
+
+        let v1 = engine.add_blueprint_template(template(1, 2));
+        let north = engine.create_factory("North".to_string(), None);
+        let south = engine.create_factory("South".to_string(), None);
+        let (north_instance, _) = engine
+            .instantiate_blueprint_into_factory(north, v1, Some("North smelting".to_string()))
+            .unwrap();
+        engine
+            .instantiate_blueprint_into_factory(south, v1, None)
+            .unwrap();
+
+        let v2 = engine
+            .add_blueprint_template_version(v1, template(2, 4), None, None)
+            .unwrap();
+        let instances = engine.blueprint_template_instances(v2).unwrap();
+        assert_eq!(instances.len(), 2);
+        assert_eq!(instances[0].factory_name, "North");
+        assert!(instances.iter().all(|instance| !instance.up_to_date));
+
+        // A dry run reports the changes without applying them
+        let preview = engine.propagate_blueprint_template(v1, true).unwrap();
+        assert_eq!(preview.template_id, v2);
+        assert_eq!(preview.factories.len(), 2);
+        let update = &preview.factories[0].updates[0];
+        assert_eq!(update.instance_id, north_instance);
+        assert_eq!(update.to_version, TemplateVersion::new(1, 1, 0));
+        assert_eq!((update.machines_before, update.machines_after), (2, 4));
+        let ingots = update
+            .rate_changes
+            .iter()
+            .find(|change| change.item == Item::IronIngot)
+            .unwrap();
+        assert_eq!((ingots.before, ingots.after), (60.0, 120.0));
+        assert!(engine
+            .blueprint_template_instances(v1)
+            .unwrap()
+            .iter()
+            .all(|instance| !instance.up_to_date));
+
+        let applied = engine.propagate_blueprint_template(v1, false).unwrap();
+        assert_eq!(applied.factories, preview.factories);
+        let line = &engine.get_factory(north).unwrap().production_lines[&north_instance];
+        assert_eq!(line.name(), "North smelting");
+        assert_eq!(line.total_machines(), 4);
+        assert!(engine
+            .blueprint_template_instances(v1)
+            .unwrap()
+            .iter()
+            .all(|instance| instance.up_to_date && instance.template_id == v2));
+
+        // Nothing left to update
+        let again = engine.propagate_blueprint_template(v2, false).unwrap();
+        assert!(again.factories.is_empty());
+    }
+
     #[test]
     fn test_blueprint_template_remove_not_found() {
         let mut engine = SatisflowEngine::new();
-        let missing_id = Uuid::new_v4();
+        let missing_id = Uuid::new_v4().into();
 
         let result = engine.remove_blueprint_template(missing_id);
         assert!(result.is_err());
@@ -1176,19 +7367,19 @@ mod tests {
 
         // Create a blueprint template with 2 production lines
         let mut blueprint = ProductionLineBlueprint::new(
-            Uuid::new_v4(),
+            Uuid::new_v4().into(),
             "Iron Ingot + Copper Ingot".to_string(),
             None,
         );
 
         let line1 = ProductionLineRecipe::new(
-            Uuid::new_v4(),
+            Uuid::new_v4().into(),
             "Iron Line".to_string(),
             None,
             Recipe::IronIngot,
         );
         let line2 = ProductionLineRecipe::new(
-            Uuid::new_v4(),
+            Uuid::new_v4().into(),
             "Copper Line".to_string(),
             None,
             Recipe::CopperIngot,
@@ -1226,11 +7417,14 @@ mod tests {
         let factory_id = engine.create_factory("Test Factory".to_string(), None);
 
         // Create a blueprint template
-        let mut blueprint =
-            ProductionLineBlueprint::new(Uuid::new_v4(), "Shared Blueprint".to_string(), None);
+        let mut blueprint = ProductionLineBlueprint::new(
+            Uuid::new_v4().into(),
+            "Shared Blueprint".to_string(),
+            None,
+        );
 
         let line = ProductionLineRecipe::new(
-            Uuid::new_v4(),
+            Uuid::new_v4().into(),
             "Iron Line".to_string(),
             None,
             Recipe::IronIngot,
@@ -1282,11 +7476,11 @@ mod tests {
         let factory_id = engine.create_factory("Test Factory".to_string(), None);
 
         let mut blueprint =
-            ProductionLineBlueprint::new(Uuid::new_v4(), "Original Name".to_string(), None);
+            ProductionLineBlueprint::new(Uuid::new_v4().into(), "Original Name".to_string(), None);
 
         // Add a production line (required for valid blueprint)
         let line = ProductionLineRecipe::new(
-            Uuid::new_v4(),
+            Uuid::new_v4().into(),
             "Iron Line".to_string(),
             None,
             Recipe::IronIngot,
@@ -1310,7 +7504,7 @@ mod tests {
     fn test_instantiate_blueprint_not_found() {
         let mut engine = SatisflowEngine::new();
         let factory_id = engine.create_factory("Test Factory".to_string(), None);
-        let missing_blueprint_id = Uuid::new_v4();
+        let missing_blueprint_id = Uuid::new_v4().into();
 
         let result =
             engine.instantiate_blueprint_into_factory(factory_id, missing_blueprint_id, None);
@@ -1323,11 +7517,11 @@ mod tests {
         let mut engine = SatisflowEngine::new();
 
         let mut blueprint =
-            ProductionLineBlueprint::new(Uuid::new_v4(), "Test Blueprint".to_string(), None);
+            ProductionLineBlueprint::new(Uuid::new_v4().into(), "Test Blueprint".to_string(), None);
 
         // Add a production line (required for valid blueprint)
         let line = ProductionLineRecipe::new(
-            Uuid::new_v4(),
+            Uuid::new_v4().into(),
             "Iron Line".to_string(),
             None,
             Recipe::IronIngot,
@@ -1336,7 +7530,7 @@ mod tests {
 
         let blueprint_id = engine.add_blueprint_template(blueprint);
 
-        let missing_factory_id = Uuid::new_v4();
+        let missing_factory_id = Uuid::new_v4().into();
         let result =
             engine.instantiate_blueprint_into_factory(missing_factory_id, blueprint_id, None);
         assert!(result.is_err());
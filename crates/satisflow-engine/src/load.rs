@@ -0,0 +1,339 @@
+//! Strict and lenient checks applied when loading a save file
+//!
+//! Both modes look at the same things: item and recipe names this build
+//! doesn't know, and fields captured in an entity's
+//! [`UnknownFields`](crate::models::UnknownFields). A strict load stops at
+//! the first one; a lenient load works around each of them and reports it.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::models::{
+    factory::Factory,
+    production_line::{ProductionLine, ProductionLineBlueprint},
+    Item, Recipe, UnknownFields,
+};
+use crate::SatisflowEngine;
+
+/// Something in a save this engine doesn't understand
+///
+/// Returned as the error of a strict load, and as a warning by a lenient one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, thiserror::Error)]
+#[error("{path}: {message}")]
+pub struct LoadIssue {
+    /// JSON path of the value, e.g. `engine.factories.<id>.raw_inputs.<id>.item`
+    pub path: String,
+    pub message: String,
+}
+
+impl LoadIssue {
+    fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Check the item and recipe names of a serialized engine
+///
+/// A lenient check removes the entry holding an unknown name: the entity of
+/// an ID-keyed map or the element of a list it is found in, or the key of an
+/// item map.
+///
+/// # Returns
+///
+/// What was removed, or the first unknown name when `strict`
+pub fn check_names(engine: &mut Value, strict: bool) -> Result<Vec<LoadIssue>, LoadIssue> {
+    let mut warnings = Vec::new();
+    match check_value(engine, "engine", strict, &mut warnings)? {
+        // Nothing encloses the name to remove it from
+        Some(issue) => Err(issue),
+        None => Ok(warnings),
+    }
+}
+
+/// Check one value, removing the entries of its children that hold an
+/// unknown name
+///
+/// # Returns
+///
+/// The unknown name when it must be removed with the value itself
+fn check_value(
+    value: &mut Value,
+    path: &str,
+    strict: bool,
+    warnings: &mut Vec<LoadIssue>,
+) -> Result<Option<LoadIssue>, LoadIssue> {
+    match value {
+        Value::Object(map) => {
+            let keyed_by_id = map.keys().all(|key| Uuid::parse_str(key).is_ok());
+            let mut removed = Vec::new();
+            for (key, child) in map.iter_mut() {
+                let child_path = format!("{}.{}", path, key);
+                let unknown = match (key.as_str(), child) {
+                    ("item" | "fuel_type", Value::String(name)) => unknown_item(name, &child_path),
+                    ("recipe", Value::String(name)) => unknown_recipe(name, &child_path),
+                    ("items" | "nodes", Value::Object(items)) => {
+                        let names: Vec<String> = items
+                            .keys()
+                            .filter(|name| !is_item(name))
+                            .cloned()
+                            .collect();
+                        for name in names {
+                            let name_path = format!("{}.{}", child_path, name);
+                            let issue =
+                                LoadIssue::new(&name_path, format!("Unknown item `{}`", name));
+                            if strict {
+                                return Err(issue);
+                            }
+                            items.remove(&name);
+                            warnings.push(removed_issue(issue, &name_path));
+                        }
+                        None
+                    }
+                    // Custom recipe quantities, as `[item, quantity]` pairs
+                    ("inputs" | "outputs", Value::Array(pairs)) => {
+                        pairs.iter().enumerate().find_map(|(index, pair)| {
+                            let name = pair.get(0)?.as_str()?;
+                            unknown_item(name, &format!("{}[{}][0]", child_path, index))
+                        })
+                    }
+                    (_, child) => check_value(child, &child_path, strict, warnings)?,
+                };
+                if let Some(issue) = unknown {
+                    if strict {
+                        return Err(issue);
+                    }
+                    if !keyed_by_id {
+                        return Ok(Some(issue));
+                    }
+                    removed.push((key.clone(), issue));
+                }
+            }
+            for (key, issue) in removed {
+                map.remove(&key);
+                warnings.push(removed_issue(issue, &format!("{}.{}", path, key)));
+            }
+            Ok(None)
+        }
+        Value::Array(values) => {
+            let mut removed = Vec::new();
+            for (index, child) in values.iter_mut().enumerate() {
+                let child_path = format!("{}[{}]", path, index);
+                if let Some(issue) = check_value(child, &child_path, strict, warnings)? {
+                    removed.push(index);
+                    warnings.push(removed_issue(issue, &child_path));
+                }
+            }
+            for index in removed.into_iter().rev() {
+                values.remove(index);
+            }
+            Ok(None)
+        }
+        _ => Ok(None),
+    }
+}
+
+fn is_item(name: &str) -> bool {
+    serde_json::from_value::<Item>(Value::String(name.to_string())).is_ok()
+}
+
+fn unknown_item(name: &str, path: &str) -> Option<LoadIssue> {
+    (!is_item(name)).then(|| LoadIssue::new(path, format!("Unknown item `{}`", name)))
+}
+
+fn unknown_recipe(name: &str, path: &str) -> Option<LoadIssue> {
+    serde_json::from_value::<Recipe>(Value::String(name.to_string()))
+        .is_err()
+        .then(|| LoadIssue::new(path, format!("Unknown recipe `{}`", name)))
+}
+
+fn removed_issue(issue: LoadIssue, removed_path: &str) -> LoadIssue {
+    LoadIssue {
+        message: format!("{}, removed {}", issue.message, removed_path),
+        ..issue
+    }
+}
+
+/// Check the loaded engine for fields its entities captured without knowing them
+///
+/// A lenient check keeps them, so they are written back on save.
+///
+/// # Returns
+///
+/// The kept fields, or the first one when `strict`
+pub fn check_unknown_fields(
+    engine: &SatisflowEngine,
+    strict: bool,
+) -> Result<Vec<LoadIssue>, LoadIssue> {
+    let mut paths = Vec::new();
+    push_fields(&mut paths, "engine", &engine.unknown_fields);
+    for (id, factory) in &engine.factories {
+        push_factory_fields(&mut paths, &format!("engine.factories.{}", id), factory);
+    }
+    for (id, template) in &engine.factory_templates {
+        let path = format!("engine.factory_templates.{}.factory", id);
+        push_factory_fields(&mut paths, &path, &template.factory);
+    }
+    for (id, blueprint) in &engine.blueprint_templates {
+        let path = format!("engine.blueprint_templates.{}", id);
+        push_blueprint_fields(&mut paths, &path, blueprint);
+    }
+    for (id, line) in engine.logistics_lines.iter() {
+        let path = format!("engine.logistics_lines.{}", id);
+        push_fields(&mut paths, &path, &line.unknown_fields);
+    }
+    for (id, grid) in &engine.power_grids {
+        push_fields(
+            &mut paths,
+            &format!("engine.power_grids.{}", id),
+            &grid.unknown_fields,
+        );
+    }
+    for (id, goal) in &engine.goals {
+        push_fields(
+            &mut paths,
+            &format!("engine.goals.{}", id),
+            &goal.unknown_fields,
+        );
+    }
+    paths.sort();
+
+    let mut issues = paths
+        .into_iter()
+        .map(|path| LoadIssue::new(path, "Unknown field, kept to be written back on save"));
+    if strict {
+        return match issues.next() {
+            Some(issue) => Err(LoadIssue {
+                message: "Unknown field".to_string(),
+                ..issue
+            }),
+            None => Ok(Vec::new()),
+        };
+    }
+    Ok(issues.collect())
+}
+
+fn push_fields(paths: &mut Vec<String>, path: &str, fields: &UnknownFields) {
+    paths.extend(fields.keys().map(|key| format!("{}.{}", path, key)));
+}
+
+fn push_factory_fields(paths: &mut Vec<String>, path: &str, factory: &Factory) {
+    push_fields(paths, path, &factory.unknown_fields);
+    for (id, line) in &factory.production_lines {
+        match line {
+            ProductionLine::ProductionLineRecipe(recipe) => {
+                let line_path = format!("{}.production_lines.{}.ProductionLineRecipe", path, id);
+                push_fields(paths, &line_path, &recipe.unknown_fields);
+            }
+            ProductionLine::ProductionLineBlueprint(blueprint) => {
+                let line_path = format!("{}.production_lines.{}.ProductionLineBlueprint", path, id);
+                push_blueprint_fields(paths, &line_path, blueprint);
+            }
+        }
+    }
+    for (id, raw_input) in &factory.raw_inputs {
+        let input_path = format!("{}.raw_inputs.{}", path, id);
+        push_fields(paths, &input_path, &raw_input.unknown_fields);
+    }
+    for (id, generator) in &factory.power_generators {
+        let generator_path = format!("{}.power_generators.{}", path, id);
+        push_fields(paths, &generator_path, &generator.unknown_fields);
+    }
+}
+
+fn push_blueprint_fields(paths: &mut Vec<String>, path: &str, blueprint: &ProductionLineBlueprint) {
+    push_fields(paths, path, &blueprint.unknown_fields);
+    for (index, line) in blueprint.production_lines.iter().enumerate() {
+        let line_path = format!("{}.production_lines[{}]", path, index);
+        push_fields(paths, &line_path, &line.unknown_fields);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const FACTORY: &str = "9b1e4c57-8f0a-4d8e-9a57-3f8e2c1d0a01";
+    const RAW_INPUT: &str = "2f6a7d10-41c5-4c1b-b7a0-5d2e9f3c8b02";
+
+    fn engine_value(item: &str) -> Value {
+        json!({
+            "factories": {
+                FACTORY: {
+                    "raw_inputs": {
+                        RAW_INPUT: { "item": item, "quantity_per_min": 60.0 }
+                    },
+                    "items": { item: 60.0, "IronOre": 30.0 }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn known_names_pass_both_modes() {
+        let mut value = engine_value("SamOre");
+        assert_eq!(check_names(&mut value, true), Ok(Vec::new()));
+        assert_eq!(check_names(&mut value, false), Ok(Vec::new()));
+        assert_eq!(value, engine_value("SamOre"));
+    }
+
+    #[test]
+    fn strict_check_reports_path_of_unknown_item() {
+        let mut value = engine_value("Unobtainium");
+        let issue = check_names(&mut value, true).unwrap_err();
+        assert!(issue.message.contains("Unobtainium"));
+        assert!(
+            issue
+                .path
+                .starts_with(&format!("engine.factories.{}.", FACTORY)),
+            "{}",
+            issue.path
+        );
+        assert_eq!(value, engine_value("Unobtainium"));
+    }
+
+    #[test]
+    fn lenient_check_removes_entries_with_unknown_names() {
+        let mut value = engine_value("Unobtainium");
+        let mut warnings = check_names(&mut value, false).unwrap();
+        warnings.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let factory = &value["factories"][FACTORY];
+        assert_eq!(factory["raw_inputs"], json!({}));
+        assert_eq!(factory["items"], json!({ "IronOre": 30.0 }));
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(
+            warnings[1].path,
+            format!("engine.factories.{}.raw_inputs.{}.item", FACTORY, RAW_INPUT)
+        );
+        assert!(warnings[1].message.ends_with(&format!(
+            "removed engine.factories.{}.raw_inputs.{}",
+            FACTORY, RAW_INPUT
+        )));
+    }
+
+    #[test]
+    fn unknown_recipe_removes_blueprint_line_only() {
+        let mut value = json!({
+            "blueprint_templates": {
+                FACTORY: {
+                    "production_lines": [
+                        { "recipe": "IronPlate" },
+                        { "recipe": "Teleporter9000" },
+                        { "recipe": { "inputs": [["IronOre", 1.0]], "outputs": [["Nope", 1.0]] } }
+                    ]
+                }
+            }
+        });
+        let warnings = check_names(&mut value, false).unwrap();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(
+            value["blueprint_templates"][FACTORY]["production_lines"],
+            json!([{ "recipe": "IronPlate" }])
+        );
+    }
+}
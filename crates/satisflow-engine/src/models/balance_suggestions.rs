@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{factory::Factory, FactoryLogistics, Item, ProductionLineId};
+
+/// Iterations allowed for shortages to propagate down production chains
+const MAX_ITERATIONS: usize = 50;
+/// Scale changes smaller than this end the propagation
+const CONVERGENCE: f64 = 1e-6;
+
+/// How far a production line can run on the inputs available to its factory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BalanceSuggestion {
+    pub production_line_id: ProductionLineId,
+    pub production_line_name: String,
+    pub current_machines: u32,
+    /// Machines the inputs can feed at their current clock speeds, assuming the
+    /// other lines keep their rate
+    pub max_machines: u32,
+    /// Factor to apply to every machine's clock speed so the current machines
+    /// do not starve; 1.0 when the inputs suffice
+    pub clock_multiplier: f64,
+    /// Input with the least supply relative to what the line needs
+    pub limiting_item: Option<Item>,
+}
+
+impl BalanceSuggestion {
+    /// Check if the line cannot run at its configured rate
+    pub fn is_starved(&self) -> bool {
+        self.clock_multiplier < 1.0
+    }
+
+    /// Suggest a machine count and underclock for every production line
+    ///
+    /// Inputs come from raw inputs, incoming logistics and the other production
+    /// lines of the factory, minus exports and generator fuel. A starved line
+    /// produces less, so shortages are propagated down production chains until
+    /// the rates settle. Lines without inputs are skipped; results are sorted
+    /// by line name.
+    pub fn for_factory(factory: &Factory, logistics_lines: &impl FactoryLogistics) -> Vec<Self> {
+        let lines: Vec<Line> = factory
+            .active_production_lines()
+            .map(|line| Line {
+                id: line.id(),
+                name: line.name().to_string(),
+                machines: line.total_machines(),
+                inputs: line.input_rate(),
+                outputs: line.output_rate(),
+            })
+            .filter(|line| line.machines > 0 && line.inputs.iter().any(|(_, rate)| *rate > 0.0))
+            .collect();
+
+        // Everything the factory has besides production lines
+        let mut balance = factory.clone();
+        balance.calculate_item(logistics_lines);
+        let mut base = balance.items;
+        for line in factory.active_production_lines() {
+            for (item, rate) in line.output_rate() {
+                *base.entry(item).or_insert(0.0) -= rate;
+            }
+            for (item, rate) in line.input_rate() {
+                *base.entry(item).or_insert(0.0) += rate;
+            }
+        }
+
+        let mut scales = vec![1.0_f64; lines.len()];
+        for _ in 0..MAX_ITERATIONS {
+            let (supply, demand) = flows(&base, &lines, &scales, factory);
+            let mut changed = false;
+            for (line, scale) in lines.iter().zip(scales.iter_mut()) {
+                let ratio = line
+                    .inputs
+                    .iter()
+                    .map(|(item, _)| supply_ratio(&supply, &demand, *item).min(1.0))
+                    .fold(1.0_f64, f64::min);
+                if ratio < 1.0 - CONVERGENCE {
+                    *scale *= ratio;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        let (supply, demand) = flows(&base, &lines, &scales, factory);
+        let mut suggestions: Vec<Self> = lines
+            .iter()
+            .zip(&scales)
+            .map(|(line, scale)| {
+                // Share of the supply this line could use: its own draw plus the spare
+                let mut headroom = f64::INFINITY;
+                let mut limiting_item = None;
+                for (item, rate) in &line.inputs {
+                    if *rate <= 0.0 {
+                        continue;
+                    }
+                    let spare = (supply.get(item).copied().unwrap_or(0.0)
+                        - demand.get(item).copied().unwrap_or(0.0))
+                    .max(0.0);
+                    let factor = (rate * scale + spare) / rate;
+                    if factor < headroom {
+                        headroom = factor;
+                        limiting_item = Some(*item);
+                    }
+                }
+
+                Self {
+                    production_line_id: line.id,
+                    production_line_name: line.name.clone(),
+                    current_machines: line.machines,
+                    max_machines: (line.machines as f64 * headroom + 1e-4).floor() as u32,
+                    clock_multiplier: *scale,
+                    limiting_item,
+                }
+            })
+            .collect();
+        suggestions.sort_by(|a, b| a.production_line_name.cmp(&b.production_line_name));
+        suggestions
+    }
+}
+
+struct Line {
+    id: ProductionLineId,
+    name: String,
+    machines: u32,
+    inputs: Vec<(Item, f64)>,
+    outputs: Vec<(Item, f64)>,
+}
+
+/// Supply and demand of every item with the lines running at the given scales
+fn flows(
+    base: &HashMap<Item, f64>,
+    lines: &[Line],
+    scales: &[f64],
+    factory: &Factory,
+) -> (HashMap<Item, f64>, HashMap<Item, f64>) {
+    let mut supply = base.clone();
+    let mut demand: HashMap<Item, f64> = HashMap::new();
+    for (line, scale) in lines.iter().zip(scales) {
+        for (item, rate) in &line.outputs {
+            *supply.entry(*item).or_insert(0.0) += rate * scale;
+        }
+        for (item, rate) in &line.inputs {
+            *demand.entry(*item).or_insert(0.0) += rate * scale;
+        }
+    }
+    // Lines without inputs are not scaled but still produce
+    for line in factory.active_production_lines() {
+        if !lines.iter().any(|scaled| scaled.id == line.id()) {
+            for (item, rate) in line.output_rate() {
+                *supply.entry(item).or_insert(0.0) += rate;
+            }
+        }
+    }
+    (supply, demand)
+}
+
+fn supply_ratio(supply: &HashMap<Item, f64>, demand: &HashMap<Item, f64>, item: Item) -> f64 {
+    let demand = demand.get(&item).copied().unwrap_or(0.0);
+    if demand <= 0.0 {
+        return 1.0;
+    }
+    (supply.get(&item).copied().unwrap_or(0.0) / demand).max(0.0)
+}
@@ -0,0 +1,219 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{
+    game_data::MachineType, recipe_by_name, recipe_info, recipe_name, CustomRecipeId, Item, Recipe,
+};
+
+/// A modded or house-rule recipe defined by the player
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomRecipe {
+    pub id: CustomRecipeId,
+    pub name: String,
+    pub machine: MachineType,
+    /// Items consumed per cycle
+    pub inputs: Vec<(Item, f64)>,
+    /// Items produced per cycle
+    pub outputs: Vec<(Item, f64)>,
+    /// Seconds per cycle at 100% clock speed
+    pub cycle_time: f64,
+}
+
+impl CustomRecipe {
+    pub fn new(
+        id: CustomRecipeId,
+        name: String,
+        machine: MachineType,
+        inputs: Vec<(Item, f64)>,
+        outputs: Vec<(Item, f64)>,
+        cycle_time: f64,
+    ) -> Self {
+        Self {
+            id,
+            name,
+            machine,
+            inputs,
+            outputs,
+            cycle_time,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Custom recipe name cannot be empty".to_string());
+        }
+        if recipe_by_name(&self.name).is_some() {
+            return Err(format!(
+                "'{}' is already the name of a built-in recipe",
+                self.name
+            ));
+        }
+        if self.machine == MachineType::Manual {
+            return Err("Custom recipes must run in a production building".to_string());
+        }
+        if !self.cycle_time.is_finite() || self.cycle_time <= 0.0 {
+            return Err(format!(
+                "Cycle time must be positive, got {}",
+                self.cycle_time
+            ));
+        }
+        if self.outputs.is_empty() {
+            return Err("Custom recipe must produce at least one item".to_string());
+        }
+        if let Some((item, quantity)) = self
+            .inputs
+            .iter()
+            .chain(self.outputs.iter())
+            .find(|(_, quantity)| !quantity.is_finite() || *quantity <= 0.0)
+        {
+            return Err(format!(
+                "Quantity of {:?} must be positive, got {}",
+                item, quantity
+            ));
+        }
+        Ok(())
+    }
+
+    fn per_minute(&self, quantities: &[(Item, f64)]) -> Vec<(Item, f64)> {
+        quantities
+            .iter()
+            .map(|(item, quantity)| (*item, quantity * 60.0 / self.cycle_time))
+            .collect()
+    }
+}
+
+/// Recipe run by a production line: a built-in one, or a copy of a custom recipe
+///
+/// Built-in recipes serialize as their variant name, exactly as before custom
+/// recipes existed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum LineRecipe {
+    Standard(Recipe),
+    Custom(CustomRecipe),
+}
+
+impl LineRecipe {
+    pub fn name(&self) -> &str {
+        match self {
+            LineRecipe::Standard(recipe) => recipe_name(*recipe),
+            LineRecipe::Custom(custom) => &custom.name,
+        }
+    }
+
+    pub fn machine(&self) -> MachineType {
+        match self {
+            LineRecipe::Standard(recipe) => recipe_info(*recipe).machine,
+            LineRecipe::Custom(custom) => custom.machine,
+        }
+    }
+
+    /// Lowest and highest draw of one machine at 100%, in MW, when it varies
+    pub fn power_range_mw(&self) -> Option<(f64, f64)> {
+        match self {
+            LineRecipe::Standard(recipe) => recipe_info(*recipe).power_range_mw(),
+            LineRecipe::Custom(custom) => custom.machine.power_range_mw(),
+        }
+    }
+
+    /// Items consumed per minute by one machine at 100%
+    pub fn inputs(&self) -> Vec<(Item, f64)> {
+        match self {
+            LineRecipe::Standard(recipe) => recipe_info(*recipe).inputs.to_vec(),
+            LineRecipe::Custom(custom) => custom.per_minute(&custom.inputs),
+        }
+    }
+
+    /// Items produced per minute by one machine at 100%
+    pub fn outputs(&self) -> Vec<(Item, f64)> {
+        match self {
+            LineRecipe::Standard(recipe) => recipe_info(*recipe).outputs.to_vec(),
+            LineRecipe::Custom(custom) => custom.per_minute(&custom.outputs),
+        }
+    }
+
+    /// Main product, the first listed output; any other output is a byproduct
+    pub fn primary_output(&self) -> Option<Item> {
+        self.outputs().first().map(|(item, _)| *item)
+    }
+
+    /// The built-in recipe, if this is one
+    pub fn standard(&self) -> Option<Recipe> {
+        match self {
+            LineRecipe::Standard(recipe) => Some(*recipe),
+            LineRecipe::Custom(_) => None,
+        }
+    }
+
+    /// Id of the custom recipe this was copied from, if any
+    pub fn custom_id(&self) -> Option<CustomRecipeId> {
+        match self {
+            LineRecipe::Standard(_) => None,
+            LineRecipe::Custom(custom) => Some(custom.id),
+        }
+    }
+}
+
+impl From<Recipe> for LineRecipe {
+    fn from(recipe: Recipe) -> Self {
+        LineRecipe::Standard(recipe)
+    }
+}
+
+impl From<CustomRecipe> for LineRecipe {
+    fn from(recipe: CustomRecipe) -> Self {
+        LineRecipe::Custom(recipe)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn sample() -> CustomRecipe {
+        CustomRecipe::new(
+            Uuid::from_u128(1).into(),
+            "Compressed Iron".to_string(),
+            MachineType::Constructor,
+            vec![(Item::IronIngot, 5.0)],
+            vec![(Item::IronPlate, 4.0)],
+            3.0,
+        )
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(sample().validate().is_ok());
+
+        let mut recipe = sample();
+        recipe.name = "Iron Plate".to_string();
+        assert!(recipe.validate().is_err());
+
+        let mut recipe = sample();
+        recipe.cycle_time = 0.0;
+        assert!(recipe.validate().is_err());
+
+        let mut recipe = sample();
+        recipe.outputs[0].1 = -1.0;
+        assert!(recipe.validate().is_err());
+    }
+
+    #[test]
+    fn test_rates_per_minute() {
+        let recipe = LineRecipe::from(sample());
+
+        assert_eq!(recipe.inputs(), vec![(Item::IronIngot, 100.0)]);
+        assert_eq!(recipe.outputs(), vec![(Item::IronPlate, 80.0)]);
+        assert_eq!(recipe.machine(), MachineType::Constructor);
+    }
+
+    #[test]
+    fn test_standard_recipes_serialize_unchanged() {
+        let json = serde_json::to_string(&LineRecipe::from(Recipe::IronPlate)).unwrap();
+        assert_eq!(json, "\"IronPlate\"");
+
+        let custom = LineRecipe::from(sample());
+        let json = serde_json::to_string(&custom).unwrap();
+        assert_eq!(serde_json::from_str::<LineRecipe>(&json).unwrap(), custom);
+    }
+}
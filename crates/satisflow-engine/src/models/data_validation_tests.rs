@@ -12,7 +12,7 @@ mod tests {
         game_data::MachineType,
         items::{all_items, Item},
         raw_input::ExtractorType,
-        recipes::{all_recipes, recipe_info, Recipe, RecipeInfo},
+        recipes::all_recipes,
     };
     use std::collections::HashSet;
 
@@ -110,6 +110,8 @@ mod tests {
         machines.insert(MachineType::Blender);
         machines.insert(MachineType::Packager);
         machines.insert(MachineType::ParticleAccelerator);
+        machines.insert(MachineType::QuantumEncoder);
+        machines.insert(MachineType::Converter);
         machines.insert(MachineType::Manual);
         machines
     }
@@ -393,7 +395,7 @@ mod tests {
             "Recipe count ({}) is less than expected baseline of 473",
             recipe_count
         );
-        assert_eq!(machine_count, 10, "Machine type count changed");
+        assert_eq!(machine_count, 12, "Machine type count changed");
         assert_eq!(extractor_count, 6, "Extractor type count changed");
     }
 }
@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{item_name, logistics::ItemFlow, FactoryId, LogisticsId};
+
+/// A factory linked to another one through logistics lines, with the items exchanged
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FactoryDependency {
+    pub factory_id: FactoryId,
+    pub factory_name: String,
+    /// Items exchanged with the linked factory, summed across all lines
+    pub items: Vec<ItemFlow>,
+    /// Logistics lines carrying the exchange
+    pub logistics_ids: Vec<LogisticsId>,
+}
+
+impl FactoryDependency {
+    pub fn new(factory_id: FactoryId, factory_name: String) -> Self {
+        Self {
+            factory_id,
+            factory_name,
+            items: Vec::new(),
+            logistics_ids: Vec::new(),
+        }
+    }
+
+    /// Record a logistics line and merge its item flows into the totals
+    pub fn add_line(&mut self, logistics_id: LogisticsId, flows: Vec<ItemFlow>) {
+        self.logistics_ids.push(logistics_id);
+        for flow in flows {
+            match self
+                .items
+                .iter_mut()
+                .find(|existing| existing.item == flow.item)
+            {
+                Some(existing) => existing.quantity_per_min += flow.quantity_per_min,
+                None => self.items.push(flow),
+            }
+        }
+        self.items
+            .sort_by(|a, b| item_name(a.item).cmp(item_name(b.item)));
+        self.logistics_ids.sort();
+    }
+
+    /// Total quantity per minute across all exchanged items
+    pub fn total_quantity_per_min(&self) -> f64 {
+        self.items.iter().map(|flow| flow.quantity_per_min).sum()
+    }
+}
+
+/// Upstream and downstream factories for a single factory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FactoryDependencies {
+    pub factory_id: FactoryId,
+    /// Factories shipping items into this factory
+    pub dependencies: Vec<FactoryDependency>,
+    /// Factories receiving items from this factory
+    pub dependents: Vec<FactoryDependency>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Item;
+    use uuid::Uuid;
+
+    fn uuid_from_u64<T: From<Uuid>>(value: u64) -> T {
+        Uuid::from_u128(value as u128).into()
+    }
+
+    #[test]
+    fn test_add_line_merges_items() {
+        let mut dependency = FactoryDependency::new(uuid_from_u64(1), "Smelter".into());
+
+        dependency.add_line(
+            uuid_from_u64(10),
+            vec![ItemFlow {
+                item: Item::IronPlate,
+                quantity_per_min: 60.0,
+            }],
+        );
+        dependency.add_line(
+            uuid_from_u64(11),
+            vec![
+                ItemFlow {
+                    item: Item::IronPlate,
+                    quantity_per_min: 30.0,
+                },
+                ItemFlow {
+                    item: Item::CopperSheet,
+                    quantity_per_min: 15.0,
+                },
+            ],
+        );
+
+        assert_eq!(dependency.logistics_ids.len(), 2);
+        assert_eq!(dependency.items.len(), 2);
+        assert_eq!(dependency.items[0].item, Item::CopperSheet);
+        assert_eq!(dependency.items[1].quantity_per_min, 90.0);
+        assert_eq!(dependency.total_quantity_per_min(), 105.0);
+    }
+}
@@ -0,0 +1,567 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::format::NumberFormat;
+use crate::models::{
+    factory::Factory,
+    item_name,
+    logistics::{ItemPerPin, LogisticsFlux, TransportType},
+    FactoryId, GeneratorType, GridPowerStats, Item, LogisticsId, PowerGridId,
+};
+use crate::rate::{balance_sign, compare_rates};
+
+/// How urgent a diagnostic is; sorts from least to most severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum DiagnosticKind {
+    /// A logistics line starts or ends at a factory that no longer exists
+    DanglingLogistics,
+    /// A conveyor, lift or pipeline carries more than its tier allows
+    CapacityExceeded,
+    /// A generator burns a fuel it cannot use
+    IncompatibleFuel,
+    /// A power grid consumes more than it generates
+    PowerDeficit,
+    /// A factory consumes more of an item than it produces and imports
+    ItemDeficit,
+    /// A storage buffer drains and will run out
+    BufferEmptying,
+    /// A storage buffer fills and will back up the lines feeding it
+    BufferFilling,
+    /// A pipeline climbs higher than its pipe and pumps can lift the fluid
+    InsufficientHeadLift,
+}
+
+/// A problem found in the save, with the entities it concerns
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub kind: DiagnosticKind,
+    pub message: String,
+    pub factory_id: Option<FactoryId>,
+    pub logistics_id: Option<LogisticsId>,
+    pub power_grid_id: Option<PowerGridId>,
+}
+
+impl Diagnostic {
+    fn new(severity: Severity, kind: DiagnosticKind, message: String) -> Self {
+        Self {
+            severity,
+            kind,
+            message,
+            factory_id: None,
+            logistics_id: None,
+            power_grid_id: None,
+        }
+    }
+
+    fn for_factory(mut self, id: FactoryId) -> Self {
+        self.factory_id = Some(id);
+        self
+    }
+
+    fn for_logistics(mut self, id: LogisticsId) -> Self {
+        self.logistics_id = Some(id);
+        self
+    }
+
+    fn for_grid(mut self, id: Option<PowerGridId>) -> Self {
+        self.power_grid_id = id;
+        self
+    }
+
+    /// Check the whole save
+    ///
+    /// # Arguments
+    ///
+    /// * `factories` - Every factory of the save
+    /// * `logistics_lines` - Every logistics line of the save
+    /// * `grids` - Power balance of each grid
+    /// * `hidden_items` - Items whose deficits and buffers aren't reported
+    ///
+    /// # Returns
+    ///
+    /// Every problem found, most severe first
+    pub fn collect(
+        factories: &HashMap<FactoryId, Factory>,
+        logistics_lines: &HashMap<LogisticsId, LogisticsFlux>,
+        grids: &[GridPowerStats],
+        hidden_items: &[Item],
+    ) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for logistics in logistics_lines.values() {
+            diagnostics.extend(check_logistics(logistics, factories));
+        }
+        for factory in factories.values() {
+            diagnostics.extend(check_fuels(factory));
+            diagnostics.extend(check_item_deficits(factory, logistics_lines, hidden_items));
+            diagnostics.extend(check_buffers(factory, logistics_lines, hidden_items));
+        }
+        for grid in grids {
+            let deficit = grid.consumption - grid.generation;
+            if balance_sign(deficit) == Ordering::Greater {
+                diagnostics.push(
+                    Diagnostic::new(
+                        Severity::Error,
+                        DiagnosticKind::PowerDeficit,
+                        format!(
+                            "{} is short of {:.1} MW ({:.1} MW generated, {:.1} MW consumed)",
+                            grid.grid_name, deficit, grid.generation, grid.consumption
+                        ),
+                    )
+                    .for_grid(grid.grid_id),
+                );
+            }
+        }
+
+        diagnostics.sort_by(|a, b| {
+            b.severity
+                .cmp(&a.severity)
+                .then_with(|| a.kind.cmp(&b.kind))
+                .then_with(|| a.message.cmp(&b.message))
+        });
+        diagnostics
+    }
+}
+
+/// Problems of one logistics line: missing factories and overloaded transports
+pub(crate) fn check_logistics(
+    logistics: &LogisticsFlux,
+    factories: &HashMap<FactoryId, Factory>,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for (end, factory_id) in [
+        ("source", logistics.from_factory),
+        ("destination", logistics.to_factory),
+    ] {
+        if !factories.contains_key(&factory_id) {
+            diagnostics.push(
+                Diagnostic::new(
+                    Severity::Error,
+                    DiagnosticKind::DanglingLogistics,
+                    format!(
+                        "{} {} has a missing {} factory ({})",
+                        logistics.transport_type, logistics.transport_details, end, factory_id
+                    ),
+                )
+                .for_logistics(logistics.id),
+            );
+        }
+    }
+
+    if let TransportType::Bus(bus) = &logistics.transport_type {
+        for conveyor in &bus.lines {
+            let capacity = conveyor.speed.item_per_min();
+            if compare_rates(conveyor.quantity_per_min, capacity) == Ordering::Greater {
+                diagnostics.push(
+                    Diagnostic::new(
+                        Severity::Error,
+                        DiagnosticKind::CapacityExceeded,
+                        format!(
+                            "{} conveyor {} carries {:.1} {}/min, over its {:?} limit of {}",
+                            bus.bus_name,
+                            conveyor.line_id,
+                            conveyor.quantity_per_min,
+                            item_name(conveyor.item),
+                            conveyor.speed,
+                            capacity
+                        ),
+                    )
+                    .for_logistics(logistics.id),
+                );
+            }
+        }
+        for lift in &bus.lifts {
+            let capacity = lift.speed.item_per_min();
+            if compare_rates(lift.quantity_per_min, capacity) == Ordering::Greater {
+                diagnostics.push(
+                    Diagnostic::new(
+                        Severity::Error,
+                        DiagnosticKind::CapacityExceeded,
+                        format!(
+                            "{} lift {} carries {:.1} {}/min, over its {:?} limit of {}",
+                            bus.bus_name,
+                            lift.lift_id,
+                            lift.quantity_per_min,
+                            item_name(lift.item),
+                            lift.speed,
+                            capacity
+                        ),
+                    )
+                    .for_logistics(logistics.id),
+                );
+            }
+        }
+        for pipeline in &bus.pipelines {
+            let capacity = pipeline.capacity.m3_per_min();
+            if compare_rates(pipeline.quantity_per_min, capacity) == Ordering::Greater {
+                diagnostics.push(
+                    Diagnostic::new(
+                        Severity::Error,
+                        DiagnosticKind::CapacityExceeded,
+                        format!(
+                            "{} pipeline {} carries {:.1} m³/min of {}, over its {:?} limit of {}",
+                            bus.bus_name,
+                            pipeline.pipeline_id,
+                            pipeline.quantity_per_min,
+                            item_name(pipeline.item),
+                            pipeline.capacity,
+                            capacity
+                        ),
+                    )
+                    .for_logistics(logistics.id),
+                );
+            }
+            let head_lift = pipeline.head_lift();
+            if let Some(rise) = pipeline.vertical_rise {
+                if compare_rates(rise, head_lift) == Ordering::Greater {
+                    diagnostics.push(
+                        Diagnostic::new(
+                            Severity::Warning,
+                            DiagnosticKind::InsufficientHeadLift,
+                            format!(
+                                "{} pipeline {} rises {:.1} m, above the {:.1} m its pipe and pumps lift {}",
+                                bus.bus_name,
+                                pipeline.pipeline_id,
+                                rise,
+                                head_lift,
+                                item_name(pipeline.item)
+                            ),
+                        )
+                        .for_logistics(logistics.id),
+                    );
+                }
+            }
+        }
+    }
+
+    diagnostics
+}
+
+fn check_fuels(factory: &Factory) -> Vec<Diagnostic> {
+    factory
+        .power_generators
+        .values()
+        .filter(|generator| {
+            generator.generator_type != GeneratorType::Geothermal
+                && !generator
+                    .generator_type
+                    .is_compatible_with(&generator.fuel_type)
+        })
+        .map(|generator| {
+            Diagnostic::new(
+                Severity::Error,
+                DiagnosticKind::IncompatibleFuel,
+                format!(
+                    "{:?} generator in {} cannot burn {}",
+                    generator.generator_type,
+                    factory.name,
+                    item_name(generator.fuel_type)
+                ),
+            )
+            .for_factory(factory.id)
+        })
+        .collect()
+}
+
+fn check_item_deficits(
+    factory: &Factory,
+    logistics_lines: &HashMap<LogisticsId, LogisticsFlux>,
+    hidden_items: &[Item],
+) -> Vec<Diagnostic> {
+    let mut balance = factory.clone();
+    balance.calculate_item(logistics_lines);
+
+    balance
+        .items
+        .iter()
+        .filter(|(item, quantity)| {
+            balance_sign(**quantity) == Ordering::Less && !hidden_items.contains(item)
+        })
+        .map(|(item, quantity)| {
+            Diagnostic::new(
+                Severity::Warning,
+                DiagnosticKind::ItemDeficit,
+                format!(
+                    "{} is short of {:.1} {}/min",
+                    factory.name,
+                    -quantity,
+                    item_name(*item)
+                ),
+            )
+            .for_factory(factory.id)
+        })
+        .collect()
+}
+
+fn check_buffers(
+    factory: &Factory,
+    logistics_lines: &HashMap<LogisticsId, LogisticsFlux>,
+    hidden_items: &[Item],
+) -> Vec<Diagnostic> {
+    let format = NumberFormat::default();
+    let describe = |minutes: f64, fills: &str, filled: &str| {
+        if minutes > 0.0 {
+            format!("{} in {}", fills, format.duration(minutes))
+        } else {
+            format!("is {}", filled)
+        }
+    };
+
+    factory
+        .storage_buffer_statuses(logistics_lines)
+        .into_iter()
+        .filter(|status| !hidden_items.contains(&status.item))
+        .filter_map(|status| {
+            let (severity, kind, state) =
+                match (status.minutes_until_empty, status.minutes_until_full) {
+                    (Some(minutes), _) => (
+                        Severity::Warning,
+                        DiagnosticKind::BufferEmptying,
+                        describe(minutes, "empties", "empty"),
+                    ),
+                    (None, Some(minutes)) => (
+                        Severity::Info,
+                        DiagnosticKind::BufferFilling,
+                        describe(minutes, "fills up", "full"),
+                    ),
+                    (None, None) => return None,
+                };
+            Some(
+                Diagnostic::new(
+                    severity,
+                    kind,
+                    format!(
+                        "{}'s {} buffer {}",
+                        factory.name,
+                        item_name(status.item),
+                        state
+                    ),
+                )
+                .for_factory(factory.id),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        logistics::{Bus, Conveyor, ConveyorLift, ConveyorSpeed, Pipeline, PipelineCapacity},
+        production_line::{MachineGroup, ProductionLine, ProductionLineRecipe},
+        GeneratorGroup, Item, PowerGenerator, Recipe, StorageBuffer, UnknownFields,
+    };
+    use uuid::Uuid;
+
+    fn uuid_from_u64<T: From<Uuid>>(value: u64) -> T {
+        Uuid::from_u128(value as u128).into()
+    }
+
+    #[test]
+    fn test_collect() {
+        let mut factory = Factory::new(uuid_from_u64(1), "Smelter".into(), None);
+        let mut line =
+            ProductionLineRecipe::new(uuid_from_u64(2), "Ingots".into(), None, Recipe::IronIngot);
+        line.add_machine_group(MachineGroup::new(2, 100.0, 0))
+            .unwrap();
+        factory.add_production_line(ProductionLine::ProductionLineRecipe(line));
+        let mut generator =
+            PowerGenerator::new(uuid_from_u64(3), GeneratorType::Coal, Item::Coal).unwrap();
+        generator
+            .add_group(GeneratorGroup::new(1, 100.0).unwrap())
+            .unwrap();
+        factory.add_power_generator(generator).unwrap();
+        // Only possible through a hand-edited save
+        factory
+            .power_generators
+            .get_mut(&uuid_from_u64(3))
+            .unwrap()
+            .fuel_type = Item::Uranium;
+        let factories = HashMap::from([(factory.id, factory)]);
+
+        let bus = Bus::new(1, "Main").with_conveyor(Conveyor::new(
+            1,
+            ConveyorSpeed::Mk1,
+            Item::IronIngot,
+            90.0,
+        ));
+        let logistics = LogisticsFlux {
+            id: uuid_from_u64(4),
+            from_factory: uuid_from_u64(1),
+            to_factory: uuid_from_u64(99),
+            transport_type: TransportType::Bus(bus),
+            transport_details: "Main".into(),
+            timestamps: Default::default(),
+            unknown_fields: UnknownFields::new(),
+        };
+        let logistics_lines = HashMap::from([(logistics.id, logistics)]);
+
+        let mut grid = GridPowerStats::new(None, "Unassigned".into());
+        grid.add_factory(uuid_from_u64(1), 0.0, 8.0);
+
+        let diagnostics = Diagnostic::collect(&factories, &logistics_lines, &[grid], &[]);
+        let errors: Vec<DiagnosticKind> = diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Error)
+            .map(|diagnostic| diagnostic.kind)
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                DiagnosticKind::DanglingLogistics,
+                DiagnosticKind::CapacityExceeded,
+                DiagnosticKind::IncompatibleFuel,
+                DiagnosticKind::PowerDeficit,
+            ]
+        );
+        assert_eq!(diagnostics[0].logistics_id, Some(uuid_from_u64(4)));
+
+        // 60 ingots made, 90 shipped
+        let ingot_deficit = diagnostics
+            .iter()
+            .find(|diagnostic| {
+                diagnostic.kind == DiagnosticKind::ItemDeficit
+                    && diagnostic.message.contains("Iron Ingot")
+            })
+            .unwrap();
+        assert_eq!(ingot_deficit.severity, Severity::Warning);
+        assert_eq!(
+            ingot_deficit.message,
+            "Smelter is short of 30.0 Iron Ingot/min"
+        );
+        assert_eq!(diagnostics.last().unwrap().severity, Severity::Warning);
+    }
+
+    #[test]
+    fn test_buffer_diagnostics() {
+        let mut factory = Factory::new(uuid_from_u64(1), "Smelter".into(), None);
+        let mut line =
+            ProductionLineRecipe::new(uuid_from_u64(2), "Ingots".into(), None, Recipe::IronIngot);
+        line.add_machine_group(MachineGroup::new(2, 100.0, 0))
+            .unwrap();
+        factory.add_production_line(ProductionLine::ProductionLineRecipe(line));
+        // 60 ore/min drawn from 2,040 ore, 60 ingots/min into an empty buffer
+        let ore = StorageBuffer::new(uuid_from_u64(3), Item::IronOre, 24)
+            .unwrap()
+            .with_stored(2040.0);
+        factory.add_storage_buffer(ore).unwrap();
+        let ingots = StorageBuffer::new(uuid_from_u64(4), Item::IronIngot, 48).unwrap();
+        factory.add_storage_buffer(ingots).unwrap();
+        let factories = HashMap::from([(factory.id, factory)]);
+
+        let diagnostics = Diagnostic::collect(&factories, &HashMap::new(), &[], &[]);
+        let buffers: Vec<(Severity, &str)> = diagnostics
+            .iter()
+            .filter(|diagnostic| {
+                matches!(
+                    diagnostic.kind,
+                    DiagnosticKind::BufferEmptying | DiagnosticKind::BufferFilling
+                )
+            })
+            .map(|diagnostic| (diagnostic.severity, diagnostic.message.as_str()))
+            .collect();
+        assert_eq!(
+            buffers,
+            vec![
+                (
+                    Severity::Warning,
+                    "Smelter's Iron Ore buffer empties in 34 min"
+                ),
+                (
+                    Severity::Info,
+                    "Smelter's Iron Ingot buffer fills up in 1 h 20 min"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lift_capacity() {
+        let bus = Bus::new(1, "Riser")
+            .with_lift(ConveyorLift::new(1, ConveyorSpeed::Mk6, Item::Coal, 1200.0))
+            .with_lift(ConveyorLift::new(2, ConveyorSpeed::Mk5, Item::Coal, 900.0));
+        let logistics = LogisticsFlux {
+            id: uuid_from_u64(1),
+            from_factory: uuid_from_u64(2),
+            to_factory: uuid_from_u64(3),
+            transport_type: TransportType::Bus(bus),
+            transport_details: "Riser".into(),
+            timestamps: Default::default(),
+            unknown_fields: UnknownFields::new(),
+        };
+        let factories = HashMap::from([
+            (
+                uuid_from_u64(2),
+                Factory::new(uuid_from_u64(2), "Mine".into(), None),
+            ),
+            (
+                uuid_from_u64(3),
+                Factory::new(uuid_from_u64(3), "Plant".into(), None),
+            ),
+        ]);
+
+        let over: Vec<String> = check_logistics(&logistics, &factories)
+            .into_iter()
+            .filter(|diagnostic| diagnostic.kind == DiagnosticKind::CapacityExceeded)
+            .map(|diagnostic| diagnostic.message)
+            .collect();
+        assert_eq!(
+            over,
+            vec!["Riser lift 2 carries 900.0 Coal/min, over its Mk5 limit of 780"]
+        );
+    }
+
+    #[test]
+    fn test_pipeline_head_lift() {
+        let bus = Bus::new(1, "Tower")
+            .with_pipeline(
+                Pipeline::new(1, PipelineCapacity::Mk2, Item::Water, 600.0)
+                    .with_pumps(0, 1)
+                    .with_vertical_rise(70.0),
+            )
+            .with_pipeline(
+                Pipeline::new(2, PipelineCapacity::Mk1, Item::Water, 300.0)
+                    .with_pumps(1, 0)
+                    .with_vertical_rise(45.0),
+            )
+            .with_pipeline(Pipeline::new(3, PipelineCapacity::Mk1, Item::Water, 300.0));
+        let logistics = LogisticsFlux {
+            id: uuid_from_u64(1),
+            from_factory: uuid_from_u64(2),
+            to_factory: uuid_from_u64(2),
+            transport_type: TransportType::Bus(bus),
+            transport_details: "Tower".into(),
+            timestamps: Default::default(),
+            unknown_fields: UnknownFields::new(),
+        };
+        let factories = HashMap::from([(
+            uuid_from_u64(2),
+            Factory::new(uuid_from_u64(2), "Plant".into(), None),
+        )]);
+
+        let warnings: Vec<(Severity, String)> = check_logistics(&logistics, &factories)
+            .into_iter()
+            .filter(|diagnostic| diagnostic.kind == DiagnosticKind::InsufficientHeadLift)
+            .map(|diagnostic| (diagnostic.severity, diagnostic.message))
+            .collect();
+        assert_eq!(
+            warnings,
+            vec![(
+                Severity::Warning,
+                "Tower pipeline 2 rises 45.0 m, above the 30.0 m its pipe and pumps lift Water"
+                    .to_string()
+            )]
+        );
+    }
+}
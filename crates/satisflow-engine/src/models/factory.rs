@@ -3,10 +3,21 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 
 use crate::models::{
-    logistics::LogisticsFlux, power_generator::PowerGenerator, production_line::ProductionLine,
-    raw_input::RawInput, FactoryId, Item, LogisticsId, PowerGeneratorId, ProductionLineId,
-    RawInputId,
+    factory_task::{FactoryTask, FactoryTaskError},
+    game_balance::GameBalance,
+    internal_transfer::InternalTransfer,
+    item_name,
+    map::Position,
+    power_generator::{FuelShortfall, GenerationRange, PowerGenerator, PowerGeneratorBuilder},
+    power_storage::{PowerStorage, StorageStatus},
+    production_line::{ProductionLine, ProductionLineBuilder},
+    raw_input::RawInput,
+    storage_buffer::{BufferStatus, StorageBuffer},
+    timestamps::Timestamps,
+    FactoryId, FactoryLogistics, FactoryTaskId, InternalTransferId, Item, PowerGeneratorId,
+    PowerGridId, ProductionLineId, RawInputId, StorageBufferId, UnknownFields,
 };
+use crate::rate::round_rate;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Factory {
@@ -17,10 +28,41 @@ pub struct Factory {
     pub production_lines: HashMap<ProductionLineId, ProductionLine>,
     pub raw_inputs: HashMap<RawInputId, RawInput>, // Raw resource extraction sources
     pub power_generators: HashMap<PowerGeneratorId, PowerGenerator>, // Power generation systems
-    pub items: HashMap<Item, f32>,                 // Inventory of items in the factory
+    pub items: HashMap<Item, f64>,                 // Inventory of items in the factory
+    #[serde(default)]
+    pub internal_transfers: HashMap<InternalTransferId, InternalTransfer>, // Documentation only, no balance impact
+    #[serde(default)]
+    pub power_grid: Option<PowerGridId>, // None when the factory is not assigned to a grid
+    #[serde(default)]
+    pub power_storage: Option<PowerStorage>, // Batteries, charged from surplus power
+    #[serde(default)]
+    pub storage_buffers: HashMap<StorageBufferId, StorageBuffer>, // Containers taking up the net item rates
+    #[serde(default)]
+    pub timestamps: Timestamps,
+    #[serde(default)]
+    pub production_line_timestamps: HashMap<ProductionLineId, Timestamps>,
+    #[serde(default)]
+    pub tags: Vec<String>, // Free-form groups such as a region or project phase
+    #[serde(default)]
+    pub archived: bool, // Kept for reference; still counted in every calculation
+    #[serde(default)]
+    pub position: Option<Position>, // Where the factory stands on the world map
+    #[serde(default)]
+    pub tasks: Vec<FactoryTask>, // To-do checklist, in the order the player keeps it
+    /// Game constants the totals are calculated with; set by the engine
+    #[serde(skip)]
+    balance: GameBalance,
+    /// Fields from a newer save this engine doesn't know, written back as loaded
+    #[serde(flatten)]
+    pub unknown_fields: UnknownFields,
 }
 
 impl Factory {
+    /// Start building a factory, see [`FactoryBuilder`]
+    pub fn builder(name: impl Into<String>) -> FactoryBuilder {
+        FactoryBuilder::new(name)
+    }
+
     pub fn new(id: FactoryId, name: String, description: Option<String>) -> Self {
         Self {
             id,
@@ -31,11 +73,188 @@ impl Factory {
             items: HashMap::new(),
             raw_inputs: HashMap::new(),
             power_generators: HashMap::new(),
+            internal_transfers: HashMap::new(),
+            power_grid: None,
+            power_storage: None,
+            storage_buffers: HashMap::new(),
+            timestamps: Timestamps::now(),
+            production_line_timestamps: HashMap::new(),
+            tags: Vec::new(),
+            archived: false,
+            position: None,
+            tasks: Vec::new(),
+            balance: GameBalance::default(),
+            unknown_fields: UnknownFields::new(),
         }
     }
 
+    /// Game constants the factory's totals are calculated with
+    pub fn balance(&self) -> &GameBalance {
+        &self.balance
+    }
+
+    /// Calculate the factory's totals with other game constants
+    pub fn set_balance(&mut self, balance: GameBalance) {
+        self.balance = balance;
+    }
+
+    /// Mark the factory as modified right now
+    pub fn touch(&mut self) {
+        self.timestamps.touch();
+    }
+
+    /// Deep copy the factory under fresh IDs
+    ///
+    /// Production lines (including the lines inside blueprints), raw inputs,
+    /// power generators, internal transfers, storage buffers and tasks all get new IDs.
+    /// Settings, notes, the power grid, batteries and unknown fields are kept
+    /// as they are.
+    pub fn duplicate(&self, id: FactoryId, name: String) -> Factory {
+        let mut copy = Factory::new(id, name, self.description.clone());
+        copy.notes = self.notes.clone();
+        copy.power_grid = self.power_grid;
+        copy.power_storage = self.power_storage.clone();
+        copy.tags = self.tags.clone();
+        copy.balance = self.balance.clone();
+        copy.unknown_fields = self.unknown_fields.clone();
+
+        let mut line_ids = HashMap::new();
+        for original in self.production_lines.values() {
+            let line = original.with_new_ids();
+            line_ids.insert(original.id(), line.id());
+            copy.add_production_line(line);
+        }
+        for raw_input in self.raw_inputs.values() {
+            let raw_input = RawInput {
+                id: RawInputId::new_v4(),
+                ..raw_input.clone()
+            };
+            copy.raw_inputs.insert(raw_input.id, raw_input);
+        }
+        for generator in self.power_generators.values() {
+            let generator = PowerGenerator {
+                id: PowerGeneratorId::new_v4(),
+                ..generator.clone()
+            };
+            copy.power_generators.insert(generator.id, generator);
+        }
+        for transfer in self.internal_transfers.values() {
+            let transfer = InternalTransfer {
+                id: InternalTransferId::new_v4(),
+                ..transfer.clone()
+            };
+            copy.internal_transfers.insert(transfer.id, transfer);
+        }
+        for buffer in self.storage_buffers.values() {
+            let buffer = StorageBuffer {
+                id: StorageBufferId::new_v4(),
+                ..buffer.clone()
+            };
+            copy.storage_buffers.insert(buffer.id, buffer);
+        }
+        copy.tasks = self
+            .tasks
+            .iter()
+            .map(|task| FactoryTask {
+                id: FactoryTaskId::new_v4(),
+                production_line: task
+                    .production_line
+                    .and_then(|id| line_ids.get(&id).copied()),
+                ..task.clone()
+            })
+            .collect();
+
+        copy
+    }
+
+    /// Replace the tags, trimming them and dropping blank and duplicate ones
+    ///
+    /// Tags compare case-insensitively; the first spelling is kept.
+    pub fn set_tags(&mut self, tags: Vec<String>) {
+        let mut normalized: Vec<String> = Vec::new();
+        for tag in tags {
+            let tag = tag.trim();
+            if !tag.is_empty() && !normalized.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                normalized.push(tag.to_string());
+            }
+        }
+        self.tags = normalized;
+        self.touch();
+    }
+
+    /// Check if the factory carries a tag, ignoring case
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag.trim()))
+    }
+
+    /// Add a production line, or replace the line with the same ID
     pub fn add_production_line(&mut self, line: ProductionLine) {
-        self.production_lines.insert(line.id(), line);
+        let id = line.id();
+        self.production_lines.insert(id, line);
+        self.production_line_timestamps
+            .entry(id)
+            .and_modify(Timestamps::touch)
+            .or_insert_with(Timestamps::now);
+        self.touch();
+    }
+
+    /// Replace an existing production line, keeping its creation time
+    ///
+    /// # Returns
+    ///
+    /// The previous line, or `None` (and nothing changed) if no line has this ID
+    pub fn replace_production_line(&mut self, line: ProductionLine) -> Option<ProductionLine> {
+        let previous = self.production_lines.get_mut(&line.id())?;
+        let previous = std::mem::replace(previous, line);
+        self.production_line_timestamps
+            .entry(previous.id())
+            .or_default()
+            .touch();
+        self.touch();
+        Some(previous)
+    }
+
+    /// Remove a production line from this factory
+    pub fn remove_production_line(&mut self, id: ProductionLineId) -> Option<ProductionLine> {
+        let line = self.production_lines.remove(&id)?;
+        self.production_line_timestamps.remove(&id);
+        for task in &mut self.tasks {
+            if task.production_line == Some(id) {
+                task.production_line = None;
+            }
+        }
+        self.touch();
+        Some(line)
+    }
+
+    /// Get the timestamps of a production line
+    ///
+    /// Lines from saves that predate timestamps report the Unix epoch.
+    pub fn production_line_timestamps(&self, id: ProductionLineId) -> Option<Timestamps> {
+        self.production_lines.get(&id).map(|_| {
+            self.production_line_timestamps
+                .get(&id)
+                .copied()
+                .unwrap_or_default()
+        })
+    }
+
+    /// Enable or disable a production line without removing it
+    ///
+    /// # Returns
+    ///
+    /// `false` (and nothing changed) if no line has this ID
+    pub fn set_production_line_enabled(&mut self, id: ProductionLineId, enabled: bool) -> bool {
+        let Some(line) = self.production_lines.get_mut(&id) else {
+            return false;
+        };
+        line.set_enabled(enabled);
+        self.production_line_timestamps
+            .entry(id)
+            .or_default()
+            .touch();
+        self.touch();
+        true
     }
 
     /// Add a raw input to this factory
@@ -88,93 +307,544 @@ impl Factory {
     }
 
     /// Calculate total power generation from all power generators
-    pub fn total_power_generation(&self) -> f32 {
+    /// Add an internal transfer to this factory
+    pub fn add_internal_transfer(&mut self, transfer: InternalTransfer) -> Result<(), String> {
+        transfer.validate().map_err(|e| e.to_string())?;
+
+        self.internal_transfers.insert(transfer.id, transfer);
+        Ok(())
+    }
+
+    /// Remove an internal transfer from this factory
+    pub fn remove_internal_transfer(&mut self, id: InternalTransferId) -> Option<InternalTransfer> {
+        self.internal_transfers.remove(&id)
+    }
+
+    /// Get a reference to an internal transfer by ID
+    pub fn get_internal_transfer(&self, id: InternalTransferId) -> Option<&InternalTransfer> {
+        self.internal_transfers.get(&id)
+    }
+
+    /// Distinct area names referenced by internal transfers, sorted
+    pub fn internal_areas(&self) -> Vec<String> {
+        let mut areas: Vec<String> = self
+            .internal_transfers
+            .values()
+            .flat_map(|transfer| [transfer.from_area.clone(), transfer.to_area.clone()])
+            .collect();
+        areas.sort();
+        areas.dedup();
+        areas
+    }
+
+    /// Add a task to the end of the checklist, or replace the task with the same ID
+    /// in place
+    ///
+    /// A linked production line must belong to this factory.
+    pub fn add_task(&mut self, task: FactoryTask) -> Result<(), FactoryTaskError> {
+        task.validate()?;
+        if let Some(line_id) = task.production_line {
+            if !self.production_lines.contains_key(&line_id) {
+                return Err(FactoryTaskError::UnknownProductionLine { id: line_id });
+            }
+        }
+
+        match self
+            .tasks
+            .iter_mut()
+            .find(|existing| existing.id == task.id)
+        {
+            Some(existing) => *existing = task,
+            None => self.tasks.push(task),
+        }
+        Ok(())
+    }
+
+    /// Remove a task from the checklist
+    pub fn remove_task(&mut self, id: FactoryTaskId) -> Option<FactoryTask> {
+        let index = self.tasks.iter().position(|task| task.id == id)?;
+        Some(self.tasks.remove(index))
+    }
+
+    /// Get a reference to a task by ID
+    pub fn get_task(&self, id: FactoryTaskId) -> Option<&FactoryTask> {
+        self.tasks.iter().find(|task| task.id == id)
+    }
+
+    /// Number of tasks not yet done
+    pub fn open_task_count(&self) -> usize {
+        self.tasks.iter().filter(|task| !task.done).count()
+    }
+
+    /// Add a storage buffer, or replace the buffer with the same ID
+    pub fn add_storage_buffer(&mut self, buffer: StorageBuffer) -> Result<(), String> {
+        buffer.validate().map_err(|e| e.to_string())?;
+
+        self.storage_buffers.insert(buffer.id, buffer);
+        Ok(())
+    }
+
+    /// Remove a storage buffer from this factory
+    pub fn remove_storage_buffer(&mut self, id: StorageBufferId) -> Option<StorageBuffer> {
+        self.storage_buffers.remove(&id)
+    }
+
+    /// Get a reference to a storage buffer by ID
+    pub fn get_storage_buffer(&self, id: StorageBufferId) -> Option<&StorageBuffer> {
+        self.storage_buffers.get(&id)
+    }
+
+    /// Fill state of every storage buffer under the factory's net item rates
+    ///
+    /// Buffers holding the same item share its net rate in proportion to their
+    /// capacity. Returns one entry per buffer, sorted by item name.
+    pub fn storage_buffer_statuses(
+        &self,
+        logistics_lines: &impl FactoryLogistics,
+    ) -> Vec<BufferStatus> {
+        if self.storage_buffers.is_empty() {
+            return Vec::new();
+        }
+        let mut balance = self.clone();
+        balance.calculate_item(logistics_lines);
+
+        let mut capacities: HashMap<Item, f64> = HashMap::new();
+        for buffer in self.storage_buffers.values() {
+            *capacities.entry(buffer.item).or_insert(0.0) += buffer.capacity();
+        }
+        let mut statuses: Vec<BufferStatus> = self
+            .storage_buffers
+            .values()
+            .map(|buffer| {
+                let net = balance.items.get(&buffer.item).copied().unwrap_or(0.0);
+                buffer.status(net * buffer.capacity() / capacities[&buffer.item])
+            })
+            .collect();
+        statuses.sort_by(|a, b| {
+            item_name(a.item)
+                .cmp(item_name(b.item))
+                .then(a.buffer_id.cmp(&b.buffer_id))
+        });
+        statuses
+    }
+
+    /// Production lines that count towards the factory's totals
+    pub fn active_production_lines(&self) -> impl Iterator<Item = &ProductionLine> {
+        self.production_lines
+            .values()
+            .filter(|line| line.is_enabled())
+    }
+
+    /// Power generators that count towards the factory's totals
+    pub fn active_power_generators(&self) -> impl Iterator<Item = &PowerGenerator> {
         self.power_generators
             .values()
-            .map(|generator| generator.total_power_generation())
+            .filter(|generator| generator.enabled)
+    }
+
+    /// Raw inputs that count towards the factory's totals
+    pub fn active_raw_inputs(&self) -> impl Iterator<Item = &RawInput> {
+        self.raw_inputs
+            .values()
+            .filter(|raw_input| raw_input.enabled)
+    }
+
+    /// Gross rate the factory makes an item at: outputs of its production lines
+    /// plus what its raw inputs extract, before anything is consumed
+    pub fn production_rate(&self, item: Item) -> f64 {
+        let produced: f64 = self
+            .active_production_lines()
+            .flat_map(|line| line.output_rate())
+            .filter(|(output, _)| *output == item)
+            .map(|(_, quantity)| quantity)
+            .sum();
+        let extracted: f64 = self
+            .active_raw_inputs()
+            .filter(|raw_input| raw_input.item == item)
+            .map(|raw_input| raw_input.quantity_per_min_with(&self.balance))
+            .sum();
+        produced + extracted
+    }
+
+    /// Gross rate the factory uses an item up at: inputs of its production
+    /// lines plus the fuel and water of its generators, before anything is made
+    pub fn consumption_rate(&self, item: Item) -> f64 {
+        let consumed: f64 = self
+            .active_production_lines()
+            .flat_map(|line| line.input_rate())
+            .filter(|(input, _)| *input == item)
+            .map(|(_, quantity)| quantity)
+            .sum();
+        let burned: f64 = self
+            .active_power_generators()
+            .map(|generator| {
+                let mut quantity = 0.0;
+                if generator.fuel_type == item {
+                    quantity += generator.total_fuel_consumption();
+                }
+                if item == Item::Water {
+                    quantity += generator.total_water_consumption();
+                }
+                quantity
+            })
+            .sum();
+        consumed + burned
+    }
+
+    pub fn total_power_generation(&self) -> f64 {
+        self.active_power_generators()
+            .map(|generator| generator.total_power_generation_with(&self.balance))
             .sum()
     }
 
+    /// Range of power generation while geysers fluctuate
+    pub fn power_generation_range(&self) -> GenerationRange {
+        let mut range = GenerationRange::default();
+        for generator in self.active_power_generators() {
+            range.add(generator.power_generation_range_with(&self.balance));
+        }
+        range
+    }
+
     /// Calculate power balance (generation - consumption)
-    pub fn power_balance(&self) -> f32 {
+    pub fn power_balance(&self) -> f64 {
         self.total_power_generation() - self.total_power_consumption()
     }
 
-    pub fn total_power_consumption(&self) -> f32 {
+    /// Set the factory's batteries after validating them
+    pub fn set_power_storage(&mut self, storage: PowerStorage) -> Result<(), String> {
+        storage.validate().map_err(|e| e.to_string())?;
+        self.power_storage = Some(storage);
+        Ok(())
+    }
+
+    /// Remove the factory's batteries
+    pub fn clear_power_storage(&mut self) -> Option<PowerStorage> {
+        self.power_storage.take()
+    }
+
+    /// Power drawn to charge batteries from the surplus left by machines
+    pub fn storage_charging_draw(&self) -> f64 {
+        self.power_storage.as_ref().map_or(0.0, |storage| {
+            storage.charging_draw(self.total_power_generation() - self.machine_power_consumption())
+        })
+    }
+
+    /// Charge state of the factory's batteries, if it has any
+    pub fn storage_status(&self) -> Option<StorageStatus> {
+        self.power_storage.as_ref().map(|storage| {
+            StorageStatus::new(
+                storage.battery_count,
+                storage.stored_mwh,
+                storage.total_capacity(),
+                self.storage_charging_draw(),
+                self.power_balance(),
+            )
+        })
+    }
+
+    /// Total power consumption, including battery charging
+    pub fn total_power_consumption(&self) -> f64 {
+        self.machine_power_consumption() + self.storage_charging_draw()
+    }
+
+    /// Power consumed by production lines and extractors
+    pub fn machine_power_consumption(&self) -> f64 {
         let production_power = self
-            .production_lines
-            .iter()
-            .map(|line| line.1.total_power_consumption())
-            .sum::<f32>();
+            .active_production_lines()
+            .map(|line| line.total_power_consumption_with(&self.balance))
+            .sum::<f64>();
 
         let raw_input_power = self
-            .raw_inputs
-            .values()
-            .map(|raw_input| raw_input.power_consumption())
-            .sum::<f32>();
+            .active_raw_inputs()
+            .map(|raw_input| raw_input.power_consumption_with(&self.balance))
+            .sum::<f64>();
 
         production_power + raw_input_power
     }
 
-    pub fn calculate_item(&mut self, logistics_lines: &HashMap<LogisticsId, LogisticsFlux>) {
-        self.items.clear();
+    /// Check that the factory supplies the fuel its generators burn
+    ///
+    /// Fuel available to generators is what the factory extracts, produces and
+    /// imports, minus what its production lines consume and what it exports.
+    /// Returns one entry per fuel in short supply, sorted by item name.
+    pub fn fuel_shortfalls(&self, logistics_lines: &impl FactoryLogistics) -> Vec<FuelShortfall> {
+        let mut required: HashMap<Item, f64> = HashMap::new();
+        for generator in self.active_power_generators() {
+            let fuel_consumption = generator.total_fuel_consumption();
+            if fuel_consumption > 0.0 {
+                *required.entry(generator.fuel_type).or_insert(0.0) += fuel_consumption;
+            }
+        }
+        if required.is_empty() {
+            return Vec::new();
+        }
+
+        // The item balance already subtracts generator fuel; add it back
+        let mut balance = self.clone();
+        balance.calculate_item(logistics_lines);
+
+        let mut shortfalls: Vec<FuelShortfall> = required
+            .into_iter()
+            .filter_map(|(item, required)| {
+                let net = balance.items.get(&item).copied().unwrap_or(0.0);
+                FuelShortfall::check(item, required, net + required)
+            })
+            .collect();
+        shortfalls.sort_by(|a, b| item_name(a.item).cmp(item_name(b.item)));
+        shortfalls
+    }
+
+    pub fn calculate_item(&mut self, logistics_lines: &impl FactoryLogistics) {
+        let mut items = HashMap::new();
         // Add all inputs from logistics input lines
-        for line in logistics_lines
-            .iter()
-            .filter(|(_k, v)| v.to_factory == self.id)
-        {
-            for itemflow in &line.1.get_items() {
-                *self.items.entry(itemflow.item).or_insert(0.0) += itemflow.quantity_per_min;
+        for line in logistics_lines.inbound(self.id) {
+            for itemflow in &line.get_items() {
+                *items.entry(itemflow.item).or_insert(0.0) += itemflow.quantity_per_min;
             }
         }
         // Subtract all outputs to logistics output lines
-        for line in logistics_lines
-            .iter()
-            .filter(|(_k, v)| v.from_factory == self.id)
-        {
-            for itemflow in &line.1.get_items() {
-                *self.items.entry(itemflow.item).or_insert(0.0) -= itemflow.quantity_per_min;
+        for line in logistics_lines.outbound(self.id) {
+            for itemflow in &line.get_items() {
+                *items.entry(itemflow.item).or_insert(0.0) -= itemflow.quantity_per_min;
             }
         }
         // Add all raw inputs from extraction sources
-        for raw_input in self.raw_inputs.values() {
-            *self.items.entry(raw_input.item).or_insert(0.0) += raw_input.quantity_per_min;
+        for raw_input in self.active_raw_inputs() {
+            *items.entry(raw_input.item).or_insert(0.0) +=
+                raw_input.quantity_per_min_with(&self.balance);
         }
         // Add all production line outputs and subtract inputs
-        for line in self.production_lines.values() {
+        for line in self.active_production_lines() {
             for (item, qty) in line.output_rate() {
-                *self.items.entry(item).or_insert(0.0) += qty;
+                *items.entry(item).or_insert(0.0) += qty;
             }
             for (item, qty) in line.input_rate() {
-                *self.items.entry(item).or_insert(0.0) -= qty;
+                *items.entry(item).or_insert(0.0) -= qty;
             }
         }
-        // Subtract fuel consumption from power generators and add waste production
-        for generator in self.power_generators.values() {
+        // Subtract fuel and water consumption from power generators and add waste production
+        for generator in self.active_power_generators() {
             // Subtract fuel consumption
             let fuel_consumption = generator.total_fuel_consumption();
             if fuel_consumption > 0.0 {
-                *self.items.entry(generator.fuel_type).or_insert(0.0) -= fuel_consumption;
+                *items.entry(generator.fuel_type).or_insert(0.0) -= fuel_consumption;
+            }
+            // Subtract water consumption (coal and nuclear plants)
+            let water_consumption = generator.total_water_consumption();
+            if water_consumption > 0.0 {
+                *items.entry(Item::Water).or_insert(0.0) -= water_consumption;
             }
             // Add waste production (if any)
             if let Some(waste_product) = generator.waste_product() {
                 let waste_rate = generator.waste_production_rate();
                 if waste_rate > 0.0 {
-                    *self.items.entry(waste_product).or_insert(0.0) += waste_rate;
+                    *items.entry(waste_product).or_insert(0.0) += waste_rate;
                 }
             }
         }
+        self.items = items
+            .into_iter()
+            .map(|(item, quantity)| (item, round_rate(quantity)))
+            .collect();
+    }
+}
+
+/// Fluent construction of a fully configured [`Factory`], validated on
+/// [`build`](Self::build)
+///
+/// # Example
+///
+/// ```
+/// use satisflow_engine::models::{
+///     factory::Factory, production_line::ProductionLineBuilder, GeneratorType, Item,
+///     PowerGenerator, Recipe,
+/// };
+///
+/// let factory = Factory::builder("Smelting Hub")
+///     .tags(["north"])
+///     .production_line(ProductionLineBuilder::recipe("Iron", Recipe::IronIngot).machines(4, 100.0, 0))
+///     .power_generator(PowerGenerator::builder(GeneratorType::Coal).fuel(Item::Coal).group(2, 100.0))
+///     .build()
+///     .unwrap();
+/// assert_eq!(factory.production_lines.len(), 1);
+/// assert_eq!(factory.power_balance(), 134.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct FactoryBuilder {
+    id: Option<FactoryId>,
+    name: String,
+    description: Option<String>,
+    notes: Option<String>,
+    tags: Vec<String>,
+    archived: bool,
+    position: Option<Position>,
+    power_grid: Option<PowerGridId>,
+    power_storage: Option<PowerStorage>,
+    production_lines: Vec<ProductionLineBuilder>,
+    raw_inputs: Vec<RawInput>,
+    power_generators: Vec<PowerGeneratorBuilder>,
+    internal_transfers: Vec<InternalTransfer>,
+    storage_buffers: Vec<StorageBuffer>,
+}
+
+impl FactoryBuilder {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            id: None,
+            name: name.into(),
+            description: None,
+            notes: None,
+            tags: Vec::new(),
+            archived: false,
+            position: None,
+            power_grid: None,
+            power_storage: None,
+            production_lines: Vec::new(),
+            raw_inputs: Vec::new(),
+            power_generators: Vec::new(),
+            internal_transfers: Vec::new(),
+            storage_buffers: Vec::new(),
+        }
+    }
+
+    /// Use a fixed ID instead of a random one
+    pub fn id(mut self, id: FactoryId) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn notes(mut self, notes: impl Into<String>) -> Self {
+        self.notes = Some(notes.into());
+        self
+    }
+
+    /// Tags, normalized like [`Factory::set_tags`]
+    pub fn tags<T: Into<String>>(mut self, tags: impl IntoIterator<Item = T>) -> Self {
+        self.tags.extend(tags.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn archived(mut self, archived: bool) -> Self {
+        self.archived = archived;
+        self
+    }
+
+    pub fn position(mut self, position: Position) -> Self {
+        self.position = Some(position);
+        self
+    }
+
+    pub fn power_grid(mut self, power_grid: PowerGridId) -> Self {
+        self.power_grid = Some(power_grid);
+        self
+    }
+
+    pub fn power_storage(mut self, storage: PowerStorage) -> Self {
+        self.power_storage = Some(storage);
+        self
+    }
+
+    pub fn production_line(mut self, line: ProductionLineBuilder) -> Self {
+        self.production_lines.push(line);
+        self
+    }
+
+    pub fn raw_input(mut self, raw_input: RawInput) -> Self {
+        self.raw_inputs.push(raw_input);
+        self
+    }
+
+    pub fn power_generator(mut self, generator: PowerGeneratorBuilder) -> Self {
+        self.power_generators.push(generator);
+        self
+    }
+
+    pub fn internal_transfer(mut self, transfer: InternalTransfer) -> Self {
+        self.internal_transfers.push(transfer);
+        self
+    }
+
+    pub fn storage_buffer(mut self, buffer: StorageBuffer) -> Self {
+        self.storage_buffers.push(buffer);
+        self
+    }
+
+    /// Create the factory
+    ///
+    /// The item balance is left empty until the engine recalculates it.
+    ///
+    /// # Returns
+    ///
+    /// The factory, or the first problem found: a blank name, an invalid
+    /// line, raw input, generator, transfer, buffer or battery, or two parts
+    /// sharing an ID
+    pub fn build(self) -> Result<Factory, Box<dyn std::error::Error>> {
+        if self.name.trim().is_empty() {
+            return Err("Factory name cannot be empty".into());
+        }
+        let id = self.id.unwrap_or_else(FactoryId::new_v4);
+        let mut factory = Factory::new(id, self.name.trim().to_string(), self.description);
+        factory.notes = self.notes;
+        factory.set_tags(self.tags);
+        factory.archived = self.archived;
+        factory.position = self.position;
+        factory.power_grid = self.power_grid;
+
+        for line in self.production_lines {
+            let line = line.build()?;
+            if factory.production_lines.contains_key(&line.id()) {
+                return Err(format!("Duplicate production line id {}", line.id()).into());
+            }
+            factory.add_production_line(line);
+        }
+        for raw_input in self.raw_inputs {
+            if factory.raw_inputs.contains_key(&raw_input.id) {
+                return Err(format!("Duplicate raw input id {}", raw_input.id).into());
+            }
+            factory.add_raw_input(raw_input)?;
+        }
+        for generator in self.power_generators {
+            let generator = generator.build()?;
+            if factory.power_generators.contains_key(&generator.id) {
+                return Err(format!("Duplicate power generator id {}", generator.id).into());
+            }
+            factory.add_power_generator(generator)?;
+        }
+        for transfer in self.internal_transfers {
+            if factory.internal_transfers.contains_key(&transfer.id) {
+                return Err(format!("Duplicate internal transfer id {}", transfer.id).into());
+            }
+            factory.add_internal_transfer(transfer)?;
+        }
+        for buffer in self.storage_buffers {
+            if factory.storage_buffers.contains_key(&buffer.id) {
+                return Err(format!("Duplicate storage buffer id {}", buffer.id).into());
+            }
+            factory.add_storage_buffer(buffer)?;
+        }
+        if let Some(storage) = self.power_storage {
+            factory.set_power_storage(storage)?;
+        }
+        Ok(factory)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::{ExtractorType, GeneratorGroup, GeneratorType, Purity};
+    use crate::models::{
+        logistics::LogisticsFlux, ExtractorType, GeneratorGroup, GeneratorType, Purity,
+    };
     use uuid::Uuid;
 
-    fn uuid_from_u64(value: u64) -> Uuid {
-        Uuid::from_u128(value as u128)
+    fn uuid_from_u64<T: From<Uuid>>(value: u64) -> T {
+        Uuid::from_u128(value as u128).into()
     }
 
     #[test]
@@ -591,6 +1261,7 @@ mod tests {
         // Check fuel consumption and waste production
         assert_eq!(factory.items.get(&Item::UraniumFuelRod), Some(&-0.025)); // 1 * 0.025 rods/min
         assert_eq!(factory.items.get(&Item::UraniumWaste), Some(&0.025)); // 1 * 0.025 waste/min
+        assert_eq!(factory.items.get(&Item::Water), Some(&-240.0)); // 1 * 240 m³/min
     }
 
     #[test]
@@ -764,6 +1435,124 @@ mod tests {
         assert_eq!(factory.power_balance(), -45.0); // 0 - 45 (deficit)
     }
 
+    #[test]
+    fn test_fuel_shortfalls() {
+        use crate::models::logistics::{TransportType, TruckTransport};
+
+        let mut factory = Factory::new(uuid_from_u64(1), "Coal Plant".into(), None);
+        let mut generator =
+            PowerGenerator::new(uuid_from_u64(2), GeneratorType::Coal, Item::Coal).unwrap();
+        generator
+            .add_group(GeneratorGroup::new(2, 100.0).unwrap())
+            .unwrap();
+        factory.add_power_generator(generator).unwrap();
+
+        let shortfalls = factory.fuel_shortfalls(&HashMap::new());
+        assert_eq!(shortfalls.len(), 1);
+        assert_eq!(shortfalls[0].item, Item::Coal);
+        assert_eq!(shortfalls[0].required, 30.0);
+        assert_eq!(shortfalls[0].available, 0.0);
+
+        let coal_import = |id: u64, quantity: f64| LogisticsFlux {
+            id: uuid_from_u64(id),
+            from_factory: uuid_from_u64(99),
+            to_factory: uuid_from_u64(1),
+            transport_type: TransportType::Truck(TruckTransport::new(id, Item::Coal, quantity)),
+            transport_details: Default::default(),
+            timestamps: Timestamps::default(),
+            unknown_fields: UnknownFields::new(),
+        };
+        let mut logistics_lines = HashMap::from([(uuid_from_u64(10), coal_import(10, 20.0))]);
+        let shortfalls = factory.fuel_shortfalls(&logistics_lines);
+        assert_eq!(shortfalls[0].available, 20.0);
+        assert_eq!(shortfalls[0].shortfall, 10.0);
+
+        logistics_lines.insert(uuid_from_u64(11), coal_import(11, 10.0));
+        assert!(factory.fuel_shortfalls(&logistics_lines).is_empty());
+    }
+
+    #[test]
+    fn test_disabled_entries_are_skipped() {
+        use crate::models::{
+            production_line::{MachineGroup, ProductionLineRecipe},
+            Recipe,
+        };
+
+        let mut factory = Factory::new(uuid_from_u64(1), "Toggled Factory".into(), None);
+        let mut line =
+            ProductionLineRecipe::new(uuid_from_u64(2), "Ingots".into(), None, Recipe::IronIngot);
+        line.add_machine_group(MachineGroup::new(1, 100.0, 0))
+            .unwrap();
+        factory.add_production_line(ProductionLine::ProductionLineRecipe(line));
+        factory
+            .add_raw_input(
+                RawInput::new(
+                    uuid_from_u64(3),
+                    ExtractorType::MinerMk1,
+                    Item::IronOre,
+                    Some(Purity::Normal),
+                    100.0,
+                    1,
+                )
+                .unwrap(),
+            )
+            .unwrap();
+        let mut generator =
+            PowerGenerator::new(uuid_from_u64(4), GeneratorType::Coal, Item::Coal).unwrap();
+        generator
+            .add_group(GeneratorGroup::new(1, 100.0).unwrap())
+            .unwrap();
+        factory.add_power_generator(generator).unwrap();
+
+        factory.calculate_item(&HashMap::new());
+        assert_eq!(factory.items[&Item::IronIngot], 30.0);
+        assert_eq!(factory.total_power_generation(), 75.0);
+        assert_eq!(factory.total_power_consumption(), 9.0); // Smelter + Miner Mk1
+
+        assert!(factory.set_production_line_enabled(uuid_from_u64(2), false));
+        factory.get_raw_input_mut(uuid_from_u64(3)).unwrap().enabled = false;
+        factory
+            .get_power_generator_mut(uuid_from_u64(4))
+            .unwrap()
+            .enabled = false;
+
+        factory.calculate_item(&HashMap::new());
+        assert!(factory.items.is_empty());
+        assert_eq!(factory.total_power_generation(), 0.0);
+        assert_eq!(factory.total_power_consumption(), 0.0);
+        assert!(factory.fuel_shortfalls(&HashMap::new()).is_empty());
+        assert_eq!(factory.production_lines.len(), 1);
+        assert!(!factory.set_production_line_enabled(uuid_from_u64(9), false));
+    }
+
+    #[test]
+    fn test_refinery_byproducts_feed_the_item_map() {
+        use crate::models::{
+            production_line::{MachineGroup, ProductionLineRecipe},
+            Recipe,
+        };
+
+        let mut factory = Factory::new(uuid_from_u64(1), "Oil Refinery".into(), None);
+        for (id, recipe, clock) in [
+            (2, Recipe::Plastic, 100.0),
+            (3, Recipe::Rubber, 100.0),
+            (4, Recipe::ResidualFuel, 50.0),
+        ] {
+            let mut line = ProductionLineRecipe::new(uuid_from_u64(id), "Oil".into(), None, recipe);
+            line.add_machine_group(MachineGroup::new(1, clock, 0))
+                .unwrap();
+            factory.add_production_line(ProductionLine::ProductionLineRecipe(line));
+        }
+
+        factory.calculate_item(&HashMap::new());
+        assert_eq!(factory.items[&Item::CrudeOil], -60.0);
+        assert_eq!(factory.items[&Item::Plastic], 20.0);
+        assert_eq!(factory.items[&Item::Rubber], 20.0);
+        // 10 + 20 residue from plastic and rubber, all burnt into fuel
+        assert_eq!(factory.items[&Item::HeavyOilResidue], 0.0);
+        assert_eq!(factory.items[&Item::Fuel], 20.0);
+    }
+
     #[test]
     fn test_nuclear_waste_accumulation() {
         let mut factory = Factory::new(uuid_from_u64(1), "Nuclear Factory".into(), None);
@@ -875,8 +1664,8 @@ mod tests {
         // Check waste production
         assert_eq!(factory.items.get(&Item::UraniumWaste), Some(&0.025));
 
-        // Geothermal shouldn't consume any fuel
-        assert_eq!(factory.items.get(&Item::Water), None);
+        // Only coal and nuclear need water; geothermal's placeholder fuel isn't consumed
+        assert_eq!(factory.items.get(&Item::Water), Some(&-285.0));
     }
 
     #[test]
@@ -929,4 +1718,161 @@ mod tests {
         assert_eq!(factory.items.get(&Item::IronOre), Some(&60.0));
         assert_eq!(factory.items.get(&Item::Coal), Some(&-15.0));
     }
+
+    #[test]
+    fn test_internal_transfers_do_not_affect_balance() {
+        let mut factory = Factory::new(uuid_from_u64(1), "Test Factory".into(), None);
+        let transfer = InternalTransfer::new(
+            uuid_from_u64(2),
+            Item::IronPlate,
+            120.0,
+            "Smelting",
+            "Assembly",
+        )
+        .unwrap();
+
+        factory.add_internal_transfer(transfer).unwrap();
+        factory.calculate_item(&HashMap::new());
+
+        assert!(factory.items.is_empty());
+        assert_eq!(factory.internal_areas(), vec!["Assembly", "Smelting"]);
+        assert!(factory.remove_internal_transfer(uuid_from_u64(2)).is_some());
+        assert!(factory.get_internal_transfer(uuid_from_u64(2)).is_none());
+    }
+
+    #[test]
+    fn test_factory_tasks() {
+        use crate::models::{production_line::ProductionLineRecipe, Recipe};
+
+        let mut factory = Factory::new(uuid_from_u64(1), "Test Factory".into(), None);
+        factory.add_production_line(ProductionLine::ProductionLineRecipe(
+            ProductionLineRecipe::new(uuid_from_u64(2), "Ingots".into(), None, Recipe::IronIngot),
+        ));
+
+        let build = FactoryTask::new(uuid_from_u64(3), "Build smelters", Some(uuid_from_u64(2)));
+        factory.add_task(build.unwrap()).unwrap();
+        let belts = FactoryTask::new(uuid_from_u64(4), "Run belts", None).unwrap();
+        factory.add_task(belts.clone()).unwrap();
+        factory.add_task(belts.with_done(true)).unwrap();
+        assert_eq!(factory.tasks.len(), 2);
+        assert_eq!(factory.tasks[1].text, "Run belts");
+        assert_eq!(factory.open_task_count(), 1);
+
+        let orphan = FactoryTask::new(uuid_from_u64(5), "Orphan", Some(uuid_from_u64(9)));
+        assert_eq!(
+            factory.add_task(orphan.unwrap()),
+            Err(FactoryTaskError::UnknownProductionLine {
+                id: uuid_from_u64(9)
+            })
+        );
+
+        // Copies link to the copied line
+        let copy = factory.duplicate(uuid_from_u64(6), "Copy".into());
+        let copied_line = *copy.production_lines.keys().next().unwrap();
+        assert_eq!(copy.tasks[0].production_line, Some(copied_line));
+        assert_ne!(copy.tasks[0].id, uuid_from_u64(3));
+
+        // Removing the line keeps the task, unlinked
+        factory.remove_production_line(uuid_from_u64(2));
+        assert_eq!(
+            factory.get_task(uuid_from_u64(3)).unwrap().production_line,
+            None
+        );
+        assert!(factory.remove_task(uuid_from_u64(3)).is_some());
+        assert!(factory.get_task(uuid_from_u64(3)).is_none());
+    }
+
+    #[test]
+    fn test_production_line_timestamps() {
+        use crate::models::{production_line::ProductionLineRecipe, Recipe};
+
+        let mut factory = Factory::new(uuid_from_u64(1), "Test Factory".into(), None);
+        let line = |name: &str| {
+            ProductionLine::ProductionLineRecipe(ProductionLineRecipe::new(
+                uuid_from_u64(2),
+                name.into(),
+                None,
+                Recipe::IronIngot,
+            ))
+        };
+
+        factory.add_production_line(line("Ingots"));
+        let created = factory
+            .production_line_timestamps(uuid_from_u64(2))
+            .unwrap();
+        assert_eq!(created.created_at, created.updated_at);
+
+        let previous = factory.replace_production_line(line("Renamed")).unwrap();
+        assert_eq!(previous.name(), "Ingots");
+        let updated = factory
+            .production_line_timestamps(uuid_from_u64(2))
+            .unwrap();
+        assert_eq!(updated.created_at, created.created_at);
+        assert!(updated.updated_at >= created.updated_at);
+        assert!(factory.timestamps.updated_at >= updated.updated_at);
+
+        assert!(factory.remove_production_line(uuid_from_u64(2)).is_some());
+        assert!(factory
+            .production_line_timestamps(uuid_from_u64(2))
+            .is_none());
+        assert!(factory.production_line_timestamps.is_empty());
+        assert!(factory.replace_production_line(line("Missing")).is_none());
+        assert!(factory.production_lines.is_empty());
+    }
+
+    #[test]
+    fn test_builder_validates_on_build() {
+        let factory = Factory::builder(" Smelting Hub ")
+            .id(uuid_from_u64(1))
+            .description("Iron and copper")
+            .notes("Expand north")
+            .tags(["North", "north", "phase 1"])
+            .position(Position::new(10.0, 20.0, 0.0))
+            .production_line(
+                ProductionLineBuilder::recipe("Ingots", crate::models::Recipe::IronIngot)
+                    .machines(4, 100.0, 0),
+            )
+            .raw_input(
+                RawInput::new(
+                    uuid_from_u64(2),
+                    ExtractorType::MinerMk2,
+                    Item::IronOre,
+                    Some(Purity::Normal),
+                    100.0,
+                    1,
+                )
+                .unwrap(),
+            )
+            .power_generator(
+                PowerGenerator::builder(GeneratorType::Coal)
+                    .fuel(Item::Coal)
+                    .group(1, 100.0),
+            )
+            .build()
+            .expect("Should build valid factory");
+
+        assert_eq!(factory.id, uuid_from_u64(1));
+        assert_eq!(factory.name, "Smelting Hub");
+        assert_eq!(factory.notes.as_deref(), Some("Expand north"));
+        assert_eq!(factory.tags, vec!["North", "phase 1"]);
+        assert_eq!(factory.production_lines.len(), 1);
+        assert_eq!(factory.raw_inputs.len(), 1);
+        // Coal generator minus four smelters and the miner
+        assert_eq!(factory.power_balance(), 75.0 - 16.0 - 15.0);
+
+        assert!(Factory::builder("").build().is_err());
+        let line = || {
+            ProductionLineBuilder::recipe("Ingots", crate::models::Recipe::IronIngot)
+                .id(uuid_from_u64(3))
+        };
+        assert!(Factory::builder("Duplicates")
+            .production_line(line())
+            .production_line(line())
+            .build()
+            .is_err());
+        assert!(Factory::builder("No groups")
+            .power_generator(PowerGenerator::builder(GeneratorType::Coal).fuel(Item::Coal))
+            .build()
+            .is_err());
+    }
 }
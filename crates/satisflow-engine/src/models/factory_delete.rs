@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{logistics::ItemFlow, FactoryId, GoalId, Item, LogisticsId};
+
+/// What deleting a factory would remove and leave short, computed before anything is changed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FactoryDeletePreview {
+    pub factory_id: FactoryId,
+    pub name: String,
+    /// Logistics lines to or from the factory; they are deleted with it
+    pub logistics: Vec<RemovedLogisticsLine>,
+    /// Goals scoped to the factory; they are deleted with it
+    pub goals: Vec<GoalId>,
+    /// Items other factories would run short of, or shorter of, once the
+    /// factory's shipments stop
+    pub deficits: Vec<DownstreamDeficit>,
+}
+
+/// A logistics line deleted along with its factory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemovedLogisticsLine {
+    pub id: LogisticsId,
+    pub from_factory: FactoryId,
+    pub to_factory: FactoryId,
+    pub transport_name: Option<String>,
+    pub items: Vec<ItemFlow>,
+}
+
+/// An item a remaining factory would be short of after the delete
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DownstreamDeficit {
+    pub factory_id: FactoryId,
+    pub factory_name: String,
+    pub item: Item,
+    /// Net rate now
+    pub balance_before: f64,
+    /// Net rate after the delete, always negative
+    pub balance_after: f64,
+}
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{FactoryId, Item, LogisticsId};
+
+/// Outcome of merging several factories into one, computed before anything is changed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FactoryMergePreview {
+    pub name: String,
+    pub source_factories: Vec<FactoryId>,
+    pub production_line_count: usize,
+    pub raw_input_count: usize,
+    pub power_generator_count: usize,
+    /// Logistics lines between merged factories; they become internal transfers
+    pub internalized_logistics: Vec<LogisticsId>,
+    /// Logistics lines to or from outside factories; they are rewired to the merged factory
+    pub external_logistics: Vec<LogisticsId>,
+    /// Net item balance of the merged factory
+    pub items: Vec<MergedItemBalance>,
+    pub total_power_consumption: f64,
+    pub total_power_generation: f64,
+}
+
+/// Net rate of one item in the merged factory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MergedItemBalance {
+    pub item: Item,
+    pub quantity_per_min: f64,
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{FactoryId, LogisticsId, PowerGeneratorId, ProductionLineId, RawInputId};
+
+/// Parts of a factory to carve out into a new one
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FactorySplitSelection {
+    #[serde(default)]
+    pub production_line_ids: Vec<ProductionLineId>,
+    #[serde(default)]
+    pub raw_input_ids: Vec<RawInputId>,
+    #[serde(default)]
+    pub power_generator_ids: Vec<PowerGeneratorId>,
+}
+
+impl FactorySplitSelection {
+    pub fn is_empty(&self) -> bool {
+        self.production_line_ids.is_empty()
+            && self.raw_input_ids.is_empty()
+            && self.power_generator_ids.is_empty()
+    }
+}
+
+/// Outcome of carving parts of a factory out into a new one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FactorySplitResult {
+    pub source_factory: FactoryId,
+    pub new_factory: FactoryId,
+    /// Bus lines created for the flows now crossing the boundary
+    pub logistics_ids: Vec<LogisticsId>,
+}
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{FactoryTaskId, ProductionLineId};
+
+/// An entry of a factory's to-do checklist, such as construction still to do
+/// in game for a planned production line
+///
+/// Tasks are informational only: they never change the factory's item balance.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FactoryTask {
+    pub id: FactoryTaskId,
+    pub text: String,
+    #[serde(default)]
+    pub done: bool,
+    /// Production line the task is about, if any
+    #[serde(default)]
+    pub production_line: Option<ProductionLineId>,
+}
+
+impl FactoryTask {
+    /// Create a new open task, trimming its text
+    pub fn new(
+        id: FactoryTaskId,
+        text: impl Into<String>,
+        production_line: Option<ProductionLineId>,
+    ) -> Result<Self, FactoryTaskError> {
+        let task = Self {
+            id,
+            text: text.into().trim().to_string(),
+            done: false,
+            production_line,
+        };
+        task.validate()?;
+        Ok(task)
+    }
+
+    /// Set whether the task is done
+    pub fn with_done(mut self, done: bool) -> Self {
+        self.done = done;
+        self
+    }
+
+    /// Validate the task
+    pub fn validate(&self) -> Result<(), FactoryTaskError> {
+        if self.text.trim().is_empty() {
+            return Err(FactoryTaskError::EmptyText);
+        }
+        Ok(())
+    }
+}
+
+/// Errors that can occur when working with factory tasks
+#[derive(Debug, Clone, PartialEq)]
+pub enum FactoryTaskError {
+    EmptyText,
+    UnknownProductionLine { id: ProductionLineId },
+}
+
+impl std::fmt::Display for FactoryTaskError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FactoryTaskError::EmptyText => write!(f, "Task text cannot be empty"),
+            FactoryTaskError::UnknownProductionLine { id } => {
+                write!(f, "Production line with id {} not found in factory", id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FactoryTaskError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_factory_task_validation() {
+        let task =
+            FactoryTask::new(FactoryTaskId::new_v4(), "  Build the smelters ", None).unwrap();
+        assert_eq!(task.text, "Build the smelters");
+        assert!(!task.done);
+        assert!(task.with_done(true).done);
+
+        assert_eq!(
+            FactoryTask::new(FactoryTaskId::new_v4(), "   ", None),
+            Err(FactoryTaskError::EmptyText)
+        );
+    }
+}
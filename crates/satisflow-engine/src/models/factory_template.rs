@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{factory::Factory, FactoryId, FactoryTemplateId};
+
+/// A whole factory saved for reuse: its production lines, raw inputs,
+/// generators and everything else it plans
+///
+/// Unlike a blueprint template, which is a group of production lines to add to
+/// a factory, a factory template becomes a factory of its own, e.g. to repeat a
+/// standard smelting outpost at every ore field.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FactoryTemplate {
+    pub id: FactoryTemplateId,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// The factory as it was saved, without what ties it to one place in the
+    /// world: its power grid and map position
+    pub factory: Factory,
+}
+
+impl FactoryTemplate {
+    /// Capture `factory` as a template
+    pub fn from_factory(
+        id: FactoryTemplateId,
+        name: String,
+        description: Option<String>,
+        factory: &Factory,
+    ) -> Self {
+        let mut factory = factory.clone();
+        factory.power_grid = None;
+        factory.position = None;
+        Self {
+            id,
+            name,
+            description,
+            created_at: Utc::now(),
+            factory,
+        }
+    }
+
+    /// A new factory built from the template, with its own IDs
+    pub fn instantiate(&self, id: FactoryId, name: String) -> Factory {
+        self.factory.duplicate(id, name)
+    }
+}
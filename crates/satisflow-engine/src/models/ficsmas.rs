@@ -0,0 +1,81 @@
+//! FICSMAS, the game's seasonal winter event.
+//!
+//! Its items and recipes are only planned in saves that turn the event on;
+//! elsewhere they are left out of the item balance and diagnostics. Builds
+//! without the `ficsmas` feature can't turn it on at all.
+
+use crate::models::{recipes::RecipeInfo, Item, Recipe};
+
+/// Items only obtainable during FICSMAS
+pub const FICSMAS_ITEMS: [Item; 16] = [
+    Item::FICSMASGift,
+    Item::FICSMASTreeBranch,
+    Item::FICSMASBow,
+    Item::CandyCane,
+    Item::ActualSnow,
+    Item::Snowball,
+    Item::RedFICSMASOrnament,
+    Item::BlueFICSMASOrnament,
+    Item::CopperFICSMASOrnament,
+    Item::IronFICSMASOrnament,
+    Item::FICSMASOrnamentBundle,
+    Item::FICSMASDecoration,
+    Item::FICSMASWonderStar,
+    Item::FancyFireworks,
+    Item::SparklyFireworks,
+    Item::SweetFireworks,
+];
+
+/// Seasonal buildings, which neither make nor use FICSMAS items
+const FICSMAS_BUILDINGS: [Recipe; 6] = [
+    Recipe::FICSMASGiftTree,
+    Recipe::FICSMASPowerLight,
+    Recipe::FICSMASSnowDispenser,
+    Recipe::FICSMASWreath,
+    Recipe::GiantFICSMASTree,
+    Recipe::Snowman,
+];
+
+/// Whether the engine was built with the `ficsmas` feature
+pub const fn is_available() -> bool {
+    cfg!(feature = "ficsmas")
+}
+
+impl Item {
+    /// Whether the item only exists during FICSMAS
+    pub fn is_ficsmas(&self) -> bool {
+        FICSMAS_ITEMS.contains(self)
+    }
+}
+
+impl RecipeInfo {
+    /// Whether the recipe makes or uses FICSMAS items, or builds a seasonal building
+    pub fn is_ficsmas(&self) -> bool {
+        FICSMAS_BUILDINGS.contains(&self.recipe)
+            || self
+                .inputs
+                .iter()
+                .chain(self.outputs)
+                .any(|(item, _)| item.is_ficsmas())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::recipes::{all_recipes, recipe_info};
+
+    #[test]
+    fn test_ficsmas_recipes() {
+        assert!(recipe_info(Recipe::FICSMASWonderStar).is_ficsmas());
+        assert!(recipe_info(Recipe::Snowman).is_ficsmas());
+        assert!(!recipe_info(Recipe::IronIngot).is_ficsmas());
+        // Every seasonal item is made by a seasonal recipe, except the gifts
+        for item in FICSMAS_ITEMS {
+            let made = all_recipes()
+                .iter()
+                .any(|info| info.is_ficsmas() && info.produces(item));
+            assert_eq!(made, item != Item::FICSMASGift, "{:?}", item);
+        }
+    }
+}
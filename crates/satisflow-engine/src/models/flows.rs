@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{
+    factory::Factory, item_name, logistics::LogisticsFlux, naming::TransportKind, FactoryId, Item,
+    LogisticsId,
+};
+use crate::rate::round_rate;
+
+/// A factory in the flow network
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlowNode {
+    pub id: FactoryId,
+    pub name: String,
+}
+
+/// Items of one kind moving between two factories by one kind of transport
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlowLink {
+    /// Factory the items leave
+    pub source: FactoryId,
+    /// Factory the items arrive at
+    pub target: FactoryId,
+    pub item: Item,
+    pub transport: TransportKind,
+    /// Items (or m³) per minute, summed across the lines below; the link's width
+    pub value: f64,
+    pub logistics_ids: Vec<LogisticsId>,
+}
+
+/// The whole logistics network as Sankey nodes and weighted links
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct FlowNetwork {
+    /// Every factory, by name
+    pub nodes: Vec<FlowNode>,
+    /// Links ordered by source, target, item and transport
+    pub links: Vec<FlowLink>,
+}
+
+impl FlowNetwork {
+    /// Build the network from factories and the logistics lines between them
+    ///
+    /// Lines with the same ends, item and transport kind merge into one link.
+    /// Lines from or to an unknown factory are left out, as are empty flows.
+    pub fn new<'a>(
+        factories: impl IntoIterator<Item = &'a Factory>,
+        logistics_lines: impl IntoIterator<Item = &'a LogisticsFlux>,
+    ) -> Self {
+        let mut nodes: Vec<FlowNode> = factories
+            .into_iter()
+            .map(|factory| FlowNode {
+                id: factory.id,
+                name: factory.name.clone(),
+            })
+            .collect();
+        nodes.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+        let names: HashMap<FactoryId, &str> = nodes
+            .iter()
+            .map(|node| (node.id, node.name.as_str()))
+            .collect();
+
+        let mut links: HashMap<(FactoryId, FactoryId, Item, TransportKind), FlowLink> =
+            HashMap::new();
+        for line in logistics_lines {
+            if !names.contains_key(&line.from_factory) || !names.contains_key(&line.to_factory) {
+                continue;
+            }
+            let transport = line.transport_type.kind();
+            for flow in line.get_items() {
+                let link = links
+                    .entry((line.from_factory, line.to_factory, flow.item, transport))
+                    .or_insert_with(|| FlowLink {
+                        source: line.from_factory,
+                        target: line.to_factory,
+                        item: flow.item,
+                        transport,
+                        value: 0.0,
+                        logistics_ids: Vec::new(),
+                    });
+                link.value += flow.quantity_per_min;
+                if !link.logistics_ids.contains(&line.id) {
+                    link.logistics_ids.push(line.id);
+                }
+            }
+        }
+
+        let mut links: Vec<FlowLink> = links
+            .into_values()
+            .filter_map(|mut link| {
+                link.value = round_rate(link.value);
+                link.logistics_ids.sort();
+                (link.value > 0.0).then_some(link)
+            })
+            .collect();
+        links.sort_by(|a, b| {
+            names[&a.source]
+                .cmp(names[&b.source])
+                .then(a.source.cmp(&b.source))
+                .then_with(|| names[&a.target].cmp(names[&b.target]))
+                .then(a.target.cmp(&b.target))
+                .then_with(|| item_name(a.item).cmp(item_name(b.item)))
+                .then_with(|| a.transport.prefix().cmp(b.transport.prefix()))
+        });
+
+        Self { nodes, links }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        logistics::{Bus, Conveyor, ConveyorSpeed, TransportType, TruckTransport},
+        UnknownFields,
+    };
+    use uuid::Uuid;
+
+    fn uuid_from_u64<T: From<Uuid>>(value: u64) -> T {
+        Uuid::from_u128(value as u128).into()
+    }
+
+    fn line(id: u64, from: &Factory, to: &Factory, transport_type: TransportType) -> LogisticsFlux {
+        LogisticsFlux {
+            id: uuid_from_u64(id),
+            from_factory: from.id,
+            to_factory: to.id,
+            transport_type,
+            transport_details: Default::default(),
+            timestamps: Default::default(),
+            unknown_fields: UnknownFields::new(),
+        }
+    }
+
+    #[test]
+    fn test_lines_merge_into_weighted_links() {
+        let smelter = Factory::new(uuid_from_u64(1), "Smelter".into(), None);
+        let assembly = Factory::new(uuid_from_u64(2), "Assembly".into(), None);
+        let bus = Bus::new(1, "Main")
+            .with_conveyor(Conveyor::new(1, ConveyorSpeed::Mk3, Item::IronPlate, 120.0))
+            .with_conveyor(Conveyor::new(2, ConveyorSpeed::Mk3, Item::IronPlate, 60.0))
+            .with_conveyor(Conveyor::new(3, ConveyorSpeed::Mk2, Item::IronRod, 30.0));
+        let lines = [
+            line(10, &smelter, &assembly, TransportType::Bus(bus)),
+            line(
+                11,
+                &smelter,
+                &assembly,
+                TransportType::Truck(TruckTransport::new(1, Item::IronPlate, 40.0)),
+            ),
+            line(
+                12,
+                &smelter,
+                &assembly,
+                TransportType::Truck(TruckTransport::new(2, Item::IronPlate, 20.0)),
+            ),
+            line(
+                13,
+                &smelter,
+                &assembly,
+                TransportType::Truck(TruckTransport::new(3, Item::Screw, 0.0)),
+            ),
+        ];
+
+        let network = FlowNetwork::new([&smelter, &assembly], &lines);
+        let names: Vec<&str> = network
+            .nodes
+            .iter()
+            .map(|node| node.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Assembly", "Smelter"]);
+
+        let links: Vec<(Item, TransportKind, f64, usize)> = network
+            .links
+            .iter()
+            .map(|link| {
+                (
+                    link.item,
+                    link.transport,
+                    link.value,
+                    link.logistics_ids.len(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            links,
+            vec![
+                (Item::IronPlate, TransportKind::Bus, 180.0, 1),
+                (Item::IronPlate, TransportKind::Truck, 60.0, 2),
+                (Item::IronRod, TransportKind::Bus, 30.0, 1),
+            ]
+        );
+        assert!(network
+            .links
+            .iter()
+            .all(|link| link.source == smelter.id && link.target == assembly.id));
+
+        // Lines to a factory outside the network are left out
+        assert!(FlowNetwork::new([&smelter], &lines).links.is_empty());
+    }
+}
@@ -0,0 +1,148 @@
+//! Scaling constants of the game.
+//!
+//! The engine plans with the values of the current release by default. A save
+//! can override them to model an experimental patch or a mod that changes how
+//! overclocking, node purity or generators scale, without a new build.
+
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{game_data::OVERCLOCK_EXPONENT, GeneratorType, Purity};
+
+static VANILLA: Lazy<GameBalance> = Lazy::new(GameBalance::default);
+
+/// Overclock, purity and generator constants the engine calculates with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameBalance {
+    /// Power draw scales with (clock speed / 100)^overclock_exponent
+    pub overclock_exponent: f64,
+    /// Yield multiplier of impure nodes
+    pub impure_multiplier: f64,
+    /// Yield multiplier of normal nodes
+    pub normal_multiplier: f64,
+    /// Yield multiplier of pure nodes
+    pub pure_multiplier: f64,
+    /// Output in MW at 100% clock speed of the generators whose rate differs
+    /// from the game's
+    pub generator_output_mw: HashMap<GeneratorType, f64>,
+}
+
+impl Default for GameBalance {
+    fn default() -> Self {
+        Self {
+            overclock_exponent: OVERCLOCK_EXPONENT,
+            impure_multiplier: Purity::Impure.multiplier(),
+            normal_multiplier: Purity::Normal.multiplier(),
+            pure_multiplier: Purity::Pure.multiplier(),
+            generator_output_mw: HashMap::new(),
+        }
+    }
+}
+
+impl GameBalance {
+    /// The constants of the current game release
+    pub fn vanilla() -> &'static GameBalance {
+        &VANILLA
+    }
+
+    /// Whether every constant matches the current game release
+    pub fn is_vanilla(&self) -> bool {
+        self == Self::vanilla()
+    }
+
+    /// Check that every constant can be calculated with
+    ///
+    /// # Returns
+    ///
+    /// An error naming the first constant that is negative or not a number,
+    /// or an overclock exponent that is not positive
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.overclock_exponent.is_finite() || self.overclock_exponent <= 0.0 {
+            return Err(format!(
+                "Overclock exponent must be a positive number, got {}",
+                self.overclock_exponent
+            ));
+        }
+        for (name, multiplier) in [
+            ("Impure", self.impure_multiplier),
+            ("Normal", self.normal_multiplier),
+            ("Pure", self.pure_multiplier),
+        ] {
+            if !multiplier.is_finite() || multiplier < 0.0 {
+                return Err(format!(
+                    "{} purity multiplier must not be negative, got {}",
+                    name, multiplier
+                ));
+            }
+        }
+        for (generator, output) in &self.generator_output_mw {
+            if !output.is_finite() || *output < 0.0 {
+                return Err(format!(
+                    "{:?} generator output must not be negative, got {}",
+                    generator, output
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Power multiplier of a machine running at `clock_speed` percent
+    pub fn clock_power_factor(&self, clock_speed: f64) -> f64 {
+        (clock_speed / 100.0).powf(self.overclock_exponent)
+    }
+
+    /// Yield multiplier of a node of the given purity
+    pub fn purity_multiplier(&self, purity: Purity) -> f64 {
+        match purity {
+            Purity::Impure => self.impure_multiplier,
+            Purity::Normal => self.normal_multiplier,
+            Purity::Pure => self.pure_multiplier,
+        }
+    }
+
+    /// Output of a generator in MW at 100% clock speed
+    pub fn generator_output(&self, generator: GeneratorType) -> f64 {
+        self.generator_output_mw
+            .get(&generator)
+            .copied()
+            .unwrap_or_else(|| generator.base_power_output())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_game_balance() {
+        let vanilla = GameBalance::vanilla();
+        assert!(vanilla.is_vanilla());
+        assert_eq!(vanilla.clock_power_factor(100.0), 1.0);
+        assert_eq!(vanilla.purity_multiplier(Purity::Pure), 2.0);
+        assert_eq!(vanilla.generator_output(GeneratorType::Coal), 75.0);
+
+        // Missing fields keep their game value
+        let modded: GameBalance = serde_json::from_str(
+            r#"{"overclock_exponent": 2.0, "generator_output_mw": {"Coal": 100.0}}"#,
+        )
+        .unwrap();
+        assert!(!modded.is_vanilla());
+        assert_eq!(modded.clock_power_factor(200.0), 4.0);
+        assert_eq!(modded.purity_multiplier(Purity::Impure), 0.5);
+        assert_eq!(modded.generator_output(GeneratorType::Coal), 100.0);
+        assert_eq!(modded.generator_output(GeneratorType::Fuel), 150.0);
+        assert!(modded.validate().is_ok());
+
+        let broken = GameBalance {
+            pure_multiplier: -1.0,
+            ..GameBalance::default()
+        };
+        assert_eq!(
+            broken.validate().unwrap_err(),
+            "Pure purity multiplier must not be negative, got -1"
+        );
+    }
+}
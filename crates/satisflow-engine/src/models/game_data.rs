@@ -1,4 +1,4 @@
-use core::f32;
+use core::f64;
 
 use serde::{Deserialize, Serialize};
 
@@ -7,7 +7,7 @@ pub const GAME_VERSION: &str = "1.2";
 
 /// Overclock exponent: log₂(2.5) ≈ 1.321928
 /// Power consumption scales with (clock_speed/100)^OVERCLOCK_EXPONENT
-pub const OVERCLOCK_EXPONENT: f32 = 1.321928;
+pub const OVERCLOCK_EXPONENT: f64 = 1.321928;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum MachineType {
@@ -42,7 +42,7 @@ impl MachineType {
             MachineType::Manual => 0,
         }
     }
-    pub fn base_power_mw(&self) -> f32 {
+    pub fn base_power_mw(&self) -> f64 {
         match self {
             MachineType::Constructor => 4.0,
             MachineType::Assembler => 16.0,
@@ -58,6 +58,33 @@ impl MachineType {
             MachineType::Manual => 0.0,
         }
     }
+    /// Lowest and highest draw at 100% clock, in MW, of machines whose draw
+    /// cycles during each craft; production lines use the average of the two
+    pub fn power_range_mw(&self) -> Option<(f64, f64)> {
+        match self {
+            MachineType::ParticleAccelerator => Some((250.0, 750.0)),
+            MachineType::QuantumEncoder => Some((0.0, 2000.0)),
+            MachineType::Converter => Some((100.0, 400.0)),
+            _ => None,
+        }
+    }
+    /// HUB tier whose milestones unlock the machine
+    pub fn unlock_tier(&self) -> u8 {
+        match self {
+            MachineType::Constructor => 0,
+            MachineType::Smelter => 0,
+            MachineType::Assembler => 2,
+            MachineType::Foundry => 3,
+            MachineType::Manufacturer => 5,
+            MachineType::Refinery => 5,
+            MachineType::Packager => 5,
+            MachineType::Blender => 7,
+            MachineType::ParticleAccelerator => 8,
+            MachineType::QuantumEncoder => 9,
+            MachineType::Converter => 9,
+            MachineType::Manual => 0,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -86,84 +113,92 @@ pub enum MinerType {
 /// Purity multipliers for resource extraction
 pub mod purity {
     /// Impure node multiplier: 0.5x (50% yield)
-    pub const IMPURE_MULTIPLIER: f32 = 0.5;
+    pub const IMPURE_MULTIPLIER: f64 = 0.5;
     /// Normal node multiplier: 1.0x (100% yield)
-    pub const NORMAL_MULTIPLIER: f32 = 1.0;
+    pub const NORMAL_MULTIPLIER: f64 = 1.0;
     /// Pure node multiplier: 2.0x (200% yield)
-    pub const PURE_MULTIPLIER: f32 = 2.0;
+    pub const PURE_MULTIPLIER: f64 = 2.0;
 }
 
 /// Conveyor belt speeds (items per minute)
 pub mod conveyor {
     /// Conveyor Belt Mk.1: 60 items/min
-    pub const MK1_SPEED: f32 = 60.0;
+    pub const MK1_SPEED: f64 = 60.0;
     /// Conveyor Belt Mk.2: 120 items/min
-    pub const MK2_SPEED: f32 = 120.0;
+    pub const MK2_SPEED: f64 = 120.0;
     /// Conveyor Belt Mk.3: 270 items/min
-    pub const MK3_SPEED: f32 = 270.0;
+    pub const MK3_SPEED: f64 = 270.0;
     /// Conveyor Belt Mk.4: 480 items/min
-    pub const MK4_SPEED: f32 = 480.0;
+    pub const MK4_SPEED: f64 = 480.0;
     /// Conveyor Belt Mk.5: 780 items/min
-    pub const MK5_SPEED: f32 = 780.0;
+    pub const MK5_SPEED: f64 = 780.0;
     /// Conveyor Belt Mk.6: 1200 items/min
-    pub const MK6_SPEED: f32 = 1200.0;
+    pub const MK6_SPEED: f64 = 1200.0;
 }
 
 /// Pipeline capacities (m³ per minute)
 pub mod pipeline {
     /// Pipeline Mk.1: 300 m³/min
-    pub const MK1_CAPACITY: f32 = 300.0;
+    pub const MK1_CAPACITY: f64 = 300.0;
     /// Pipeline Mk.2: 600 m³/min
-    pub const MK2_CAPACITY: f32 = 600.0;
+    pub const MK2_CAPACITY: f64 = 600.0;
 }
 
 /// Machine base power consumption (MW at 100% clock speed)
 pub mod machine_power {
     /// Constructor: 4 MW
-    pub const CONSTRUCTOR: f32 = 4.0;
+    pub const CONSTRUCTOR: f64 = 4.0;
     /// Assembler: 16 MW
-    pub const ASSEMBLER: f32 = 16.0;
+    pub const ASSEMBLER: f64 = 16.0;
     /// Manufacturer: 32 MW
-    pub const MANUFACTURER: f32 = 32.0;
+    pub const MANUFACTURER: f64 = 32.0;
     /// Smelter: 4 MW
-    pub const SMELTER: f32 = 4.0;
+    pub const SMELTER: f64 = 4.0;
     /// Foundry: 16 MW
-    pub const FOUNDRY: f32 = 16.0;
+    pub const FOUNDRY: f64 = 16.0;
     /// Refinery: 16 MW
-    pub const REFINERY: f32 = 16.0;
+    pub const REFINERY: f64 = 16.0;
     /// Blender: 32 MW
-    pub const BLENDER: f32 = 32.0;
+    pub const BLENDER: f64 = 32.0;
     /// Packager: 4 MW
-    pub const PACKAGER: f32 = 4.0;
+    pub const PACKAGER: f64 = 4.0;
     /// Particle Accelerator: 64 MW
-    pub const PARTICLE_ACCELERATOR: f32 = 64.0;
+    pub const PARTICLE_ACCELERATOR: f64 = 64.0;
     /// Quantum Encoder: 1000 MW (average, fluctuates 0-2000 MW)
-    pub const QUANTUM_ENCODER: f32 = 1000.0;
+    pub const QUANTUM_ENCODER: f64 = 1000.0;
     /// Converter: 250 MW (average, fluctuates 100-400 MW)
-    pub const CONVERTER: f32 = 250.0;
+    pub const CONVERTER: f64 = 250.0;
 }
 
 /// Miner power consumption (MW at 100% clock speed)
 pub mod miner_power {
     /// Miner Mk.1: 5 MW
-    pub const MK1: f32 = 5.0;
+    pub const MK1: f64 = 5.0;
     /// Miner Mk.2: 15 MW
-    pub const MK2: f32 = 15.0;
+    pub const MK2: f64 = 15.0;
     /// Miner Mk.3: 45 MW
-    pub const MK3: f32 = 45.0;
+    pub const MK3: f64 = 45.0;
     /// Water Extractor: 20 MW
-    pub const WATER_EXTRACTOR: f32 = 20.0;
+    pub const WATER_EXTRACTOR: f64 = 20.0;
     /// Oil Extractor: 40 MW
-    pub const OIL_EXTRACTOR: f32 = 40.0;
+    pub const OIL_EXTRACTOR: f64 = 40.0;
 }
 
 /// Somersloop power formula multiplier
 /// Power multiplier = (1 + somersloop_count / max_somersloop)^2
-pub fn somersloop_power_multiplier(somersloop_count: u8, max_somersloop: u8) -> f32 {
-    let ratio = somersloop_count as f32 / max_somersloop as f32;
+pub fn somersloop_power_multiplier(somersloop_count: u8, max_somersloop: u8) -> f64 {
+    let ratio = somersloop_count as f64 / max_somersloop as f64;
     (1.0 + ratio).powi(2)
 }
 
+/// Power shards needed to run a machine at the given clock speed
+/// Each shard raises the clock limit by 50%, up to 3 shards for 250%
+pub fn power_shards_for_clock_speed(clock_speed: f64) -> u32 {
+    // Small tolerance so float noise such as 150.0001% does not need an extra shard
+    let extra = (clock_speed - 100.0 - 1e-3) / 50.0;
+    (extra.ceil().max(0.0) as u32).min(3)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -184,7 +219,7 @@ mod tests {
     #[test]
     fn test_overclock_exponent_is_log2_2_5() {
         // log₂(2.5) ≈ 1.321928094887362
-        const EXPECTED: f32 = 1.321928;
+        const EXPECTED: f64 = 1.321928;
         assert!(
             (OVERCLOCK_EXPONENT - EXPECTED).abs() < 0.000001,
             "Overclock exponent should be log₂(2.5) ≈ 1.321928, got {}",
@@ -196,8 +231,8 @@ mod tests {
     fn test_overclock_exponent_250_percent() {
         // At 250% clock speed, power multiplier = 2.5^1.321928 ≈ 3.357
         // This is because 1.321928 ≈ log₂(2.5), so 2.5^log₂(2.5) ≈ 3.357
-        let multiplier = 2.5_f32.powf(OVERCLOCK_EXPONENT);
-        let expected = 3.357_f32;
+        let multiplier = 2.5_f64.powf(OVERCLOCK_EXPONENT);
+        let expected = 3.357_f64;
         assert!(
             (multiplier - expected).abs() < 0.01,
             "250% OC power multiplier should be approximately {}, got {}",
@@ -298,11 +333,11 @@ mod tests {
     #[test]
     fn test_conveyor_speed_progression() {
         // Each tier should be faster than the previous
-        assert!(conveyor::MK2_SPEED > conveyor::MK1_SPEED);
-        assert!(conveyor::MK3_SPEED > conveyor::MK2_SPEED);
-        assert!(conveyor::MK4_SPEED > conveyor::MK3_SPEED);
-        assert!(conveyor::MK5_SPEED > conveyor::MK4_SPEED);
-        assert!(conveyor::MK6_SPEED > conveyor::MK5_SPEED);
+        const { assert!(conveyor::MK2_SPEED > conveyor::MK1_SPEED) };
+        const { assert!(conveyor::MK3_SPEED > conveyor::MK2_SPEED) };
+        const { assert!(conveyor::MK4_SPEED > conveyor::MK3_SPEED) };
+        const { assert!(conveyor::MK5_SPEED > conveyor::MK4_SPEED) };
+        const { assert!(conveyor::MK6_SPEED > conveyor::MK5_SPEED) };
     }
 
     // =========================================================================
@@ -652,6 +687,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_power_shards_for_clock_speed() {
+        assert_eq!(power_shards_for_clock_speed(50.0), 0);
+        assert_eq!(power_shards_for_clock_speed(100.0), 0);
+        assert_eq!(power_shards_for_clock_speed(100.5), 1);
+        assert_eq!(power_shards_for_clock_speed(150.0001), 1);
+        assert_eq!(power_shards_for_clock_speed(175.0), 2);
+        assert_eq!(power_shards_for_clock_speed(250.0), 3);
+    }
+
     // =========================================================================
     // Cross-Reference Verification Tests
     // =========================================================================
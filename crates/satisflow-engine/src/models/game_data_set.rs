@@ -0,0 +1,92 @@
+//! Versions of the game data a plan can be made against.
+//!
+//! Game patches change recipe rates, so a save records the version it was
+//! planned with and keeps it when loaded. Each set owns its recipe table; the
+//! engine currently ships the data of [`GAME_VERSION`] only.
+
+use serde::Serialize;
+
+use crate::models::{
+    game_data::GAME_VERSION,
+    recipes::{all_recipes, RecipeInfo},
+    Recipe,
+};
+
+/// Recipe data of one game release
+#[derive(Debug, Serialize)]
+pub struct GameDataSet {
+    /// Game version the data comes from, e.g. "1.2"
+    pub version: &'static str,
+    pub description: &'static str,
+    #[serde(skip)]
+    recipes: fn() -> &'static [RecipeInfo],
+}
+
+/// Every data set the engine can plan with, oldest first
+static GAME_DATA_SETS: &[GameDataSet] = &[GameDataSet {
+    version: GAME_VERSION,
+    description: "Satisfactory 1.2",
+    recipes: all_recipes,
+}];
+
+impl GameDataSet {
+    /// Every available data set, oldest first
+    pub fn all() -> &'static [GameDataSet] {
+        GAME_DATA_SETS
+    }
+
+    /// Look up a data set by game version
+    pub fn get(version: &str) -> Option<&'static GameDataSet> {
+        GAME_DATA_SETS
+            .iter()
+            .find(|set| set.version == version.trim())
+    }
+
+    /// The newest data set, used by new plans
+    pub fn latest() -> &'static GameDataSet {
+        GAME_DATA_SETS
+            .last()
+            .expect("at least one game data set is built in")
+    }
+
+    /// Look up a data set, failing with the list of available versions
+    pub fn find(version: &str) -> Result<&'static GameDataSet, String> {
+        Self::get(version).ok_or_else(|| {
+            let available: Vec<&str> = GAME_DATA_SETS.iter().map(|set| set.version).collect();
+            format!(
+                "Unknown game data version '{}'. Available: {}",
+                version.trim(),
+                available.join(", ")
+            )
+        })
+    }
+
+    pub fn recipes(&self) -> &'static [RecipeInfo] {
+        (self.recipes)()
+    }
+
+    /// Data of one recipe in this set, if the release has it
+    pub fn recipe_info(&self, recipe: Recipe) -> Option<&'static RecipeInfo> {
+        self.recipes().iter().find(|info| info.recipe == recipe)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_game_data_sets() {
+        let latest = GameDataSet::latest();
+        assert_eq!(latest.version, GAME_VERSION);
+        assert!(std::ptr::eq(GameDataSet::get(" 1.2 ").unwrap(), latest));
+        assert_eq!(
+            latest.recipe_info(Recipe::IronPlate).unwrap().name,
+            "Iron Plate"
+        );
+        assert_eq!(
+            GameDataSet::find("0.8").unwrap_err(),
+            "Unknown game data version '0.8'. Available: 1.2"
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{factory::Factory, FactoryId, GoalId, Item, UnknownFields};
+use crate::rate::compare_rates;
+
+/// A target production rate for an item, across all factories or for one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Goal {
+    pub id: GoalId,
+    pub item: Item,
+    /// Wanted production, in items/min
+    pub target_rate: f64,
+    /// Factory the target applies to; `None` for a global target
+    pub factory_id: Option<FactoryId>,
+    pub description: Option<String>,
+    /// Fields from a newer save this engine doesn't know, written back as loaded
+    #[serde(flatten)]
+    pub unknown_fields: UnknownFields,
+}
+
+impl Goal {
+    pub fn new(
+        id: GoalId,
+        item: Item,
+        target_rate: f64,
+        factory_id: Option<FactoryId>,
+        description: Option<String>,
+    ) -> Self {
+        Self {
+            id,
+            item,
+            target_rate,
+            factory_id,
+            description,
+            unknown_fields: UnknownFields::new(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.target_rate.is_finite() || self.target_rate <= 0.0 {
+            return Err(format!(
+                "Goal target rate must be positive, got {}",
+                self.target_rate
+            ));
+        }
+        Ok(())
+    }
+
+    /// Current production of the goal's item, in its factory or in all of them
+    pub fn actual_rate<'a>(&self, factories: impl IntoIterator<Item = &'a Factory>) -> f64 {
+        factories
+            .into_iter()
+            .filter(|factory| self.factory_id.is_none_or(|id| factory.id == id))
+            .map(|factory| factory.production_rate(self.item))
+            .sum()
+    }
+}
+
+/// How far current production is from a goal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GoalProgress {
+    pub goal_id: GoalId,
+    pub item: Item,
+    pub factory_id: Option<FactoryId>,
+    pub target_rate: f64,
+    pub actual_rate: f64,
+    /// Actual rate relative to the target; above 100 once the target is exceeded
+    pub completion_percent: f64,
+    pub is_met: bool,
+}
+
+impl GoalProgress {
+    pub fn new(goal: &Goal, actual_rate: f64) -> Self {
+        Self {
+            goal_id: goal.id,
+            item: goal.item,
+            factory_id: goal.factory_id,
+            target_rate: goal.target_rate,
+            actual_rate,
+            completion_percent: actual_rate / goal.target_rate * 100.0,
+            is_met: compare_rates(actual_rate, goal.target_rate) != Ordering::Less,
+        }
+    }
+}
@@ -1,7 +1,94 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-pub type FactoryId = Uuid;
-pub type LogisticsId = Uuid;
-pub type ProductionLineId = Uuid;
-pub type RawInputId = Uuid;
-pub type PowerGeneratorId = Uuid;
+/// Declare a UUID newtype for one kind of entity
+///
+/// Each kind gets its own type so passing, say, a logistics line ID where a
+/// factory ID is expected doesn't compile. They serialize as the bare UUID.
+macro_rules! id_type {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(
+            Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+        )]
+        #[serde(transparent)]
+        pub struct $name(Uuid);
+
+        impl $name {
+            /// A new random ID
+            pub fn new_v4() -> Self {
+                Self(Uuid::new_v4())
+            }
+
+            pub const fn from_uuid(uuid: Uuid) -> Self {
+                Self(uuid)
+            }
+
+            pub const fn as_uuid(&self) -> Uuid {
+                self.0
+            }
+        }
+
+        impl From<Uuid> for $name {
+            fn from(uuid: Uuid) -> Self {
+                Self(uuid)
+            }
+        }
+
+        impl From<$name> for Uuid {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = uuid::Error;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Uuid::parse_str(s).map(Self)
+            }
+        }
+    };
+}
+
+id_type!(FactoryId);
+id_type!(LogisticsId);
+id_type!(ProductionLineId);
+id_type!(RawInputId);
+id_type!(PowerGeneratorId);
+id_type!(PowerGridId);
+id_type!(InternalTransferId);
+id_type!(GoalId);
+id_type!(CustomRecipeId);
+id_type!(StorageBufferId);
+id_type!(FactoryTaskId);
+id_type!(FactoryTemplateId);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ids_serialize_as_bare_uuids() {
+        let uuid = Uuid::from_u128(42);
+        let id = FactoryId::from(uuid);
+
+        assert_eq!(
+            serde_json::to_string(&id).unwrap(),
+            serde_json::to_string(&uuid).unwrap()
+        );
+        let parsed: FactoryId = serde_json::from_str(&format!("\"{}\"", uuid)).unwrap();
+        assert_eq!(parsed, id);
+        assert_eq!(id.to_string().parse::<FactoryId>().unwrap(), id);
+        assert_eq!(Uuid::from(id), uuid);
+    }
+}
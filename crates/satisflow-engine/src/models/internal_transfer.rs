@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{InternalTransferId, Item};
+
+/// A documented item flow between two areas of the same factory, such as an
+/// internal main bus
+///
+/// Internal transfers are informational only: they never change the factory's
+/// item balance.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InternalTransfer {
+    pub id: InternalTransferId,
+    pub item: Item,
+    pub quantity_per_min: f64,
+    pub from_area: String,
+    pub to_area: String,
+}
+
+impl InternalTransfer {
+    /// Create a new internal transfer, trimming the area names
+    pub fn new(
+        id: InternalTransferId,
+        item: Item,
+        quantity_per_min: f64,
+        from_area: impl Into<String>,
+        to_area: impl Into<String>,
+    ) -> Result<Self, InternalTransferError> {
+        let transfer = Self {
+            id,
+            item,
+            quantity_per_min,
+            from_area: from_area.into().trim().to_string(),
+            to_area: to_area.into().trim().to_string(),
+        };
+        transfer.validate()?;
+        Ok(transfer)
+    }
+
+    /// Validate the transfer configuration
+    pub fn validate(&self) -> Result<(), InternalTransferError> {
+        if !self.quantity_per_min.is_finite() || self.quantity_per_min <= 0.0 {
+            return Err(InternalTransferError::InvalidQuantity {
+                quantity: self.quantity_per_min,
+            });
+        }
+        if self.from_area.trim().is_empty() || self.to_area.trim().is_empty() {
+            return Err(InternalTransferError::EmptyArea);
+        }
+        if self.from_area == self.to_area {
+            return Err(InternalTransferError::SameArea {
+                area: self.from_area.clone(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Errors that can occur when working with internal transfers
+#[derive(Debug, Clone, PartialEq)]
+pub enum InternalTransferError {
+    InvalidQuantity { quantity: f64 },
+    EmptyArea,
+    SameArea { area: String },
+}
+
+impl std::fmt::Display for InternalTransferError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InternalTransferError::InvalidQuantity { quantity } => {
+                write!(
+                    f,
+                    "Quantity {} is invalid. Must be greater than 0",
+                    quantity
+                )
+            }
+            InternalTransferError::EmptyArea => {
+                write!(f, "Internal transfer areas cannot be empty")
+            }
+            InternalTransferError::SameArea { area } => {
+                write!(
+                    f,
+                    "Internal transfer must connect two different areas (got '{}' twice)",
+                    area
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for InternalTransferError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_trims_and_validates() {
+        let transfer = InternalTransfer::new(
+            InternalTransferId::new_v4(),
+            Item::IronPlate,
+            120.0,
+            "  Smelting ",
+            "Assembly",
+        )
+        .unwrap();
+        assert_eq!(transfer.from_area, "Smelting");
+
+        assert!(matches!(
+            InternalTransfer::new(InternalTransferId::new_v4(), Item::IronPlate, 0.0, "A", "B"),
+            Err(InternalTransferError::InvalidQuantity { .. })
+        ));
+        assert_eq!(
+            InternalTransfer::new(
+                InternalTransferId::new_v4(),
+                Item::IronPlate,
+                10.0,
+                " ",
+                "B"
+            ),
+            Err(InternalTransferError::EmptyArea)
+        );
+        assert!(matches!(
+            InternalTransfer::new(
+                InternalTransferId::new_v4(),
+                Item::IronPlate,
+                10.0,
+                "A",
+                " A "
+            ),
+            Err(InternalTransferError::SameArea { .. })
+        ));
+    }
+}
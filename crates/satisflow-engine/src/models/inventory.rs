@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{factory::Factory, FactoryId};
+
+/// Somersloops and power shards installed in a single factory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FactoryInventory {
+    pub factory_id: FactoryId,
+    pub factory_name: String,
+    pub somersloops: u32,
+    /// Shards in production machines
+    pub production_power_shards: u32,
+    /// Shards in power generators
+    pub generator_power_shards: u32,
+    /// Shards in extractors and Resource Well pressurizers
+    pub extractor_power_shards: u32,
+}
+
+impl FactoryInventory {
+    /// Count what is installed in a factory
+    pub fn from_factory(factory: &Factory) -> Self {
+        Self {
+            factory_id: factory.id,
+            factory_name: factory.name.clone(),
+            somersloops: factory
+                .production_lines
+                .values()
+                .map(|line| line.total_somersloop())
+                .sum(),
+            production_power_shards: factory
+                .production_lines
+                .values()
+                .map(|line| line.total_power_shards())
+                .sum(),
+            generator_power_shards: factory
+                .power_generators
+                .values()
+                .map(|generator| generator.total_power_shards())
+                .sum(),
+            extractor_power_shards: factory
+                .raw_inputs
+                .values()
+                .map(|raw_input| raw_input.power_shards())
+                .sum(),
+        }
+    }
+
+    /// Power shards installed across every kind of building
+    pub fn power_shards(&self) -> u32 {
+        self.production_power_shards + self.generator_power_shards + self.extractor_power_shards
+    }
+
+    fn is_empty(&self) -> bool {
+        self.somersloops == 0 && self.power_shards() == 0
+    }
+}
+
+/// Somersloops and power shards installed across all factories
+///
+/// Both are limited by what the world provides, so players can compare these
+/// totals against what they collected.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResourceInventory {
+    pub total_somersloops: u32,
+    pub total_power_shards: u32,
+    /// Factories with at least one somersloop or power shard, sorted by name
+    pub factories: Vec<FactoryInventory>,
+}
+
+impl ResourceInventory {
+    /// Build the inventory from every factory
+    pub fn build<'a>(factories: impl IntoIterator<Item = &'a Factory>) -> Self {
+        let mut inventory = Self::default();
+
+        for factory in factories {
+            let factory_inventory = FactoryInventory::from_factory(factory);
+            if factory_inventory.is_empty() {
+                continue;
+            }
+            inventory.total_somersloops += factory_inventory.somersloops;
+            inventory.total_power_shards += factory_inventory.power_shards();
+            inventory.factories.push(factory_inventory);
+        }
+
+        inventory
+            .factories
+            .sort_by(|a, b| a.factory_name.cmp(&b.factory_name));
+        inventory
+    }
+}
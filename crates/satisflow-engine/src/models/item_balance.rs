@@ -0,0 +1,173 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{
+    factory::Factory, logistics::LogisticsFlux, FactoryId, FactoryLogistics, Item, LogisticsId,
+    PowerGeneratorId, ProductionLineId, RawInputId,
+};
+
+/// What inside (or around) a factory produces or consumes an item
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ItemFlowSource {
+    ProductionLine {
+        production_line_id: ProductionLineId,
+        name: String,
+    },
+    RawInput {
+        raw_input_id: RawInputId,
+    },
+    PowerGenerator {
+        power_generator_id: PowerGeneratorId,
+    },
+    LogisticsImport {
+        logistics_id: LogisticsId,
+        from_factory: FactoryId,
+    },
+    LogisticsExport {
+        logistics_id: LogisticsId,
+        to_factory: FactoryId,
+    },
+}
+
+/// A single producing or consuming contribution to an item's balance
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemFlowEntry {
+    pub factory_id: FactoryId,
+    pub factory_name: String,
+    pub source: ItemFlowSource,
+    pub quantity_per_min: f64,
+}
+
+/// Global balance for one item, broken down by producer and consumer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemBalanceReport {
+    pub item: Item,
+    pub total_produced: f64,
+    pub total_consumed: f64,
+    /// Produced minus consumed; matches the item's entry in `SatisflowEngine::update()`
+    pub net: f64,
+    pub producers: Vec<ItemFlowEntry>,
+    pub consumers: Vec<ItemFlowEntry>,
+}
+
+impl ItemBalanceReport {
+    /// Build the report for `item` across all factories
+    pub fn build<'a>(
+        item: Item,
+        factories: impl IntoIterator<Item = &'a Factory>,
+        logistics_lines: &impl FactoryLogistics,
+    ) -> Self {
+        let mut producers = Vec::new();
+        let mut consumers = Vec::new();
+
+        for factory in factories {
+            let entry = |source: ItemFlowSource, quantity_per_min: f64| ItemFlowEntry {
+                factory_id: factory.id,
+                factory_name: factory.name.clone(),
+                source,
+                quantity_per_min,
+            };
+
+            let carried = |line: &LogisticsFlux| -> f64 {
+                line.get_items()
+                    .iter()
+                    .filter(|flow| flow.item == item)
+                    .map(|flow| flow.quantity_per_min)
+                    .sum()
+            };
+            for line in logistics_lines.inbound(factory.id) {
+                let quantity = carried(line);
+                if quantity > 0.0 {
+                    producers.push(entry(
+                        ItemFlowSource::LogisticsImport {
+                            logistics_id: line.id,
+                            from_factory: line.from_factory,
+                        },
+                        quantity,
+                    ));
+                }
+            }
+            for line in logistics_lines.outbound(factory.id) {
+                let quantity = carried(line);
+                if quantity > 0.0 {
+                    consumers.push(entry(
+                        ItemFlowSource::LogisticsExport {
+                            logistics_id: line.id,
+                            to_factory: line.to_factory,
+                        },
+                        quantity,
+                    ));
+                }
+            }
+
+            for raw_input in factory.active_raw_inputs() {
+                let quantity = raw_input.quantity_per_min_with(factory.balance());
+                if raw_input.item == item && quantity > 0.0 {
+                    producers.push(entry(
+                        ItemFlowSource::RawInput {
+                            raw_input_id: raw_input.id,
+                        },
+                        quantity,
+                    ));
+                }
+            }
+
+            for line in factory.active_production_lines() {
+                let source = || ItemFlowSource::ProductionLine {
+                    production_line_id: line.id(),
+                    name: line.name().to_string(),
+                };
+                let produced = sum_for_item(line.output_rate(), item);
+                if produced > 0.0 {
+                    producers.push(entry(source(), produced));
+                }
+                let consumed = sum_for_item(line.input_rate(), item);
+                if consumed > 0.0 {
+                    consumers.push(entry(source(), consumed));
+                }
+            }
+
+            for generator in factory.active_power_generators() {
+                let source = || ItemFlowSource::PowerGenerator {
+                    power_generator_id: generator.id,
+                };
+                let fuel = generator.total_fuel_consumption();
+                if generator.fuel_type == item && fuel > 0.0 {
+                    consumers.push(entry(source(), fuel));
+                }
+                let waste = generator.waste_production_rate();
+                if generator.waste_product() == Some(item) && waste > 0.0 {
+                    producers.push(entry(source(), waste));
+                }
+            }
+        }
+
+        let by_rate = |a: &ItemFlowEntry, b: &ItemFlowEntry| {
+            b.quantity_per_min
+                .total_cmp(&a.quantity_per_min)
+                .then_with(|| a.factory_name.cmp(&b.factory_name))
+        };
+        producers.sort_by(by_rate);
+        consumers.sort_by(by_rate);
+
+        let total_produced: f64 = producers.iter().map(|e| e.quantity_per_min).sum();
+        let total_consumed: f64 = consumers.iter().map(|e| e.quantity_per_min).sum();
+
+        Self {
+            item,
+            total_produced,
+            total_consumed,
+            net: total_produced - total_consumed,
+            producers,
+            consumers,
+        }
+    }
+}
+
+fn sum_for_item(rates: Vec<(Item, f64)>, item: Item) -> f64 {
+    rates
+        .into_iter()
+        .filter(|(rate_item, _)| *rate_item == item)
+        .map(|(_, quantity)| quantity)
+        .sum()
+}
@@ -1,8 +1,11 @@
-use serde::{Deserialize, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize};
 use std::{fmt, str::FromStr};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// Deserializes from its variant name, or from a name listed in
+/// [`ITEM_ALIASES`] so saves written before a game rename still load
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
 pub enum Item {
+    ActualSnow,
     AILimiter,
     AdaptiveControlUnit,
     AIExpansionServer,
@@ -26,9 +29,11 @@ pub enum Item {
     Biomass,
     BlackPowder,
     BladeRunners,
+    BlueFICSMASOrnament,
     BluePowerSlug,
     BoomBox,
     Cable,
+    CandyCane,
     CateriumIngot,
     CateriumOre,
     Chainsaw,
@@ -40,6 +45,7 @@ pub enum Item {
     Computer,
     Concrete,
     CoolingSystem,
+    CopperFICSMASOrnament,
     CopperIngot,
     CopperOre,
     CopperPowder,
@@ -47,6 +53,7 @@ pub enum Item {
     CrudeOil,
     CrystalOscillator,
     DarkMatterCrystal,
+    DarkMatterResidue,
     Diamonds,
     ElectromagneticControlRod,
     EmptyCanister,
@@ -54,10 +61,18 @@ pub enum Item {
     EncasedIndustrialBeam,
     EncasedPlutoniumCell,
     EncasedUraniumCell,
+    ExcitedPhotonicMatter,
     ExplosiveRebar,
     Fabric,
+    FancyFireworks,
     FicsiteIngot,
     FicsiteTrigon,
+    FICSMASBow,
+    FICSMASDecoration,
+    FICSMASGift,
+    FICSMASOrnamentBundle,
+    FICSMASTreeBranch,
+    FICSMASWonderStar,
     Ficsonium,
     FicsoniumFuelRod,
     FactoryCart,
@@ -79,6 +94,7 @@ pub enum Item {
     HomingRifleAmmo,
     HoverPack,
     IodineInfusedFilter,
+    IronFICSMASOrnament,
     IronIngot,
     IronOre,
     IronPlate,
@@ -95,6 +111,7 @@ pub enum Item {
     ModularFrame,
     Motor,
     Mycelia,
+    NeuralQuantumProcessor,
     NitricAcid,
     NitrogenGas,
     Nobelisk,
@@ -133,21 +150,26 @@ pub enum Item {
     Quickwire,
     RadioControlUnit,
     RawQuartz,
+    ReanimatedSam,
     RebarGun,
+    RedFICSMASOrnament,
     ReinforcedIronPlate,
     Rifle,
     RifleAmmo,
     Rotor,
     Rubber,
     Sam,
+    SamFluctuator,
     Screw,
     ShatterRebar,
     Silica,
     SingularityCell,
     SmartPlating,
     SmokelessPowder,
+    Snowball,
     Somersloop,
     SolidBiofuel,
+    SparklyFireworks,
     Stator,
     SteelBeam,
     SteelIngot,
@@ -157,6 +179,8 @@ pub enum Item {
     Sulfur,
     SulfuricAcid,
     Supercomputer,
+    SuperpositionOscillator,
+    SweetFireworks,
     ThermalPropulsionRocket,
     TimeCrystal,
     TurboMotor,
@@ -176,6 +200,129 @@ pub enum Item {
     Zipline,
 }
 
+impl Item {
+    /// Whether the item is a fluid or gas moved by pipelines rather than conveyors
+    pub fn is_fluid(&self) -> bool {
+        matches!(
+            self,
+            Item::AluminaSolution
+                | Item::CrudeOil
+                | Item::DarkMatterResidue
+                | Item::ExcitedPhotonicMatter
+                | Item::Fuel
+                | Item::HeavyOilResidue
+                | Item::LiquidBiofuel
+                | Item::NitricAcid
+                | Item::NitrogenGas
+                | Item::SulfuricAcid
+                | Item::Turbofuel
+                | Item::Water
+        )
+    }
+
+    /// Number of items fitting in one inventory slot
+    ///
+    /// Anything not listed stacks to 100.
+    ///
+    /// # Returns
+    ///
+    /// `None` for fluids, which are held in tanks rather than stacked
+    pub fn stack_size(&self) -> Option<u32> {
+        if self.is_fluid() {
+            return None;
+        }
+        let size = match self {
+            Item::Bauxite
+            | Item::CateriumOre
+            | Item::Coal
+            | Item::Concrete
+            | Item::CopperOre
+            | Item::CopperPowder
+            | Item::AluminumScrap
+            | Item::FlowerPetals
+            | Item::IronOre
+            | Item::Leaves
+            | Item::Limestone
+            | Item::NonFissileUranium
+            | Item::PlutoniumWaste
+            | Item::Quickwire
+            | Item::RawQuartz
+            | Item::Sam
+            | Item::Screw
+            | Item::Sulfur
+            | Item::Uranium
+            | Item::UraniumWaste
+            | Item::Wire => 500,
+            Item::AlcladAluminumSheet
+            | Item::Battery
+            | Item::Biomass
+            | Item::Cable
+            | Item::CircuitBoard
+            | Item::CopperSheet
+            | Item::Diamonds
+            | Item::Fabric
+            | Item::IronPlate
+            | Item::IronRod
+            | Item::Mycelia
+            | Item::PetroleumCoke
+            | Item::Plastic
+            | Item::PolymerResin
+            | Item::QuartzCrystal
+            | Item::Rubber
+            | Item::Silica
+            | Item::SolidBiofuel
+            | Item::SteelBeam
+            | Item::SteelPipe
+            | Item::Wood => 200,
+            Item::AdaptiveControlUnit
+            | Item::AIExpansionServer
+            | Item::AssemblyDirectorSystem
+            | Item::AutomatedWiring
+            | Item::BallisticWarpDrive
+            | Item::BiochemicalSculptor
+            | Item::Computer
+            | Item::FicsoniumFuelRod
+            | Item::FusedModularFrame
+            | Item::HeavyModularFrame
+            | Item::MagneticFieldGenerator
+            | Item::ModularEngine
+            | Item::ModularFrame
+            | Item::Motor
+            | Item::NuclearPasta
+            | Item::PlutoniumFuelRod
+            | Item::PressureConversionCube
+            | Item::RadioControlUnit
+            | Item::SingularityCell
+            | Item::SmartPlating
+            | Item::Supercomputer
+            | Item::ThermalPropulsionRocket
+            | Item::TurboMotor
+            | Item::UraniumFuelRod
+            | Item::VersatileFramework => 50,
+            Item::BladeRunners
+            | Item::BoomBox
+            | Item::Chainsaw
+            | Item::FactoryCart
+            | Item::GasMask
+            | Item::GoldenFactoryCart
+            | Item::HazmatSuit
+            | Item::HoverPack
+            | Item::Jetpack
+            | Item::NobeliskDetonator
+            | Item::ObjectScanner
+            | Item::Parachute
+            | Item::PortableMiner
+            | Item::RebarGun
+            | Item::Rifle
+            | Item::XenoBasher
+            | Item::XenoZapper
+            | Item::Zipline => 1,
+            _ => 100,
+        };
+        Some(size)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ItemParseError {
     invalid_name: String,
@@ -238,9 +385,64 @@ macro_rules! generate_item_by_name_fn {
     };
 }
 
+macro_rules! generate_item_by_variant_name_fn {
+    ($($variant:ident => $name:expr),+ $(,)?) => {
+        const ITEM_VARIANTS: &[&str] = &[$( stringify!($variant), )+];
+
+        fn item_by_variant_name(name: &str) -> Option<Item> {
+            match name {
+                $( stringify!($variant) => Some(Item::$variant), )+
+                _ => None,
+            }
+        }
+    };
+}
+
 for_each_item_name!(generate_item_name_pairs);
 for_each_item_name!(generate_item_name_fn);
 for_each_item_name!(generate_item_by_name_fn);
+for_each_item_name!(generate_item_by_variant_name_fn);
+
+/// Names items had in earlier game versions, both as saved (variant names)
+/// and as displayed, with the item they became
+pub const ITEM_ALIASES: &[(&str, Item)] = &[
+    ("SamOre", Item::Sam),
+    ("SAM Ore", Item::Sam),
+    ("SpikedRebar", Item::IronRebar),
+    ("Spiked Rebar", Item::IronRebar),
+    ("RifleCartridge", Item::RifleAmmo),
+    ("Rifle Cartridge", Item::RifleAmmo),
+];
+
+/// Look up an item by a name it had in an earlier game version
+pub fn item_by_alias(name: &str) -> Option<Item> {
+    ITEM_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map(|(_, item)| *item)
+}
+
+impl<'de> Deserialize<'de> for Item {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ItemVisitor;
+
+        impl de::Visitor<'_> for ItemVisitor {
+            type Value = Item;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("an item name")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Item, E> {
+                item_by_variant_name(value)
+                    .or_else(|| item_by_alias(value))
+                    .ok_or_else(|| E::unknown_variant(value, ITEM_VARIANTS))
+            }
+        }
+
+        deserializer.deserialize_str(ItemVisitor)
+    }
+}
 
 impl fmt::Display for Item {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -252,7 +454,9 @@ impl TryFrom<&str> for Item {
     type Error = ItemParseError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        item_by_name(value).ok_or_else(|| ItemParseError::new(value))
+        item_by_name(value)
+            .or_else(|| item_by_alias(value))
+            .ok_or_else(|| ItemParseError::new(value))
     }
 }
 
@@ -292,6 +496,19 @@ mod tests {
         assert_eq!(item, Item::CircuitBoard);
     }
 
+    #[test]
+    fn renamed_items_resolve_through_aliases() {
+        assert_eq!(Item::try_from("SAM Ore"), Ok(Item::Sam));
+        let items: Vec<Item> = serde_json::from_str(r#"["SamOre", "Sam", "SpikedRebar"]"#).unwrap();
+        assert_eq!(items, vec![Item::Sam, Item::Sam, Item::IronRebar]);
+        let rates: std::collections::HashMap<Item, f64> =
+            serde_json::from_str(r#"{"RifleCartridge": 10.0}"#).unwrap();
+        assert_eq!(rates.get(&Item::RifleAmmo), Some(&10.0));
+        // Items are still saved under their current name
+        assert_eq!(serde_json::to_string(&Item::Sam).unwrap(), r#""Sam""#);
+        assert!(serde_json::from_str::<Item>(r#""NotAnItem""#).is_err());
+    }
+
     #[test]
     fn try_from_unknown_name_produces_error() {
         let err = Item::try_from("Not A Real Item").expect_err("invalid item should fail");
@@ -1,22 +1,25 @@
 use serde::{Deserialize, Serialize};
 
-use crate::models::{FactoryId, Item, LogisticsId};
+use crate::models::{
+    naming::TransportKind, timestamps::Timestamps, transport_details::TransportDetails, FactoryId,
+    Item, LogisticsId, UnknownFields,
+};
 
 pub trait ItemPerPin {
     /// Returns the number of items that can be transported per minute (Max throughput).
-    fn item_per_min(&self) -> f32;
+    fn item_per_min(&self) -> f64;
 }
 
 pub trait FluidPerMin {
     /// Returns the volume of fluid that can be transported per minute (Max throughput in m³/min).
-    fn m3_per_min(&self) -> f32;
+    fn m3_per_min(&self) -> f64;
 }
 
 /// Common item flow information returned by all transport types
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ItemFlow {
     pub item: Item,
-    pub quantity_per_min: f32,
+    pub quantity_per_min: f64,
 }
 
 /// Trait for all transport types that can carry items
@@ -38,11 +41,11 @@ pub trait Transport {
 pub struct TruckTransport {
     pub truck_id: u64,
     pub item: Item,
-    pub quantity_per_min: f32,
+    pub quantity_per_min: f64,
 }
 
 impl TruckTransport {
-    pub fn new(truck_id: u64, item: Item, quantity_per_min: f32) -> Self {
+    pub fn new(truck_id: u64, item: Item, quantity_per_min: f64) -> Self {
         Self {
             truck_id,
             item,
@@ -55,11 +58,11 @@ impl TruckTransport {
 pub struct DroneTransport {
     pub drone_id: u64,
     pub item: Item,
-    pub quantity_per_min: f32,
+    pub quantity_per_min: f64,
 }
 
 impl DroneTransport {
-    pub fn new(drone_id: u64, item: Item, quantity_per_min: f32) -> Self {
+    pub fn new(drone_id: u64, item: Item, quantity_per_min: f64) -> Self {
         Self {
             drone_id,
             item,
@@ -85,6 +88,68 @@ impl TransportType {
             TransportType::Drone(_) => "DRN",
         }
     }
+
+    /// Get the transport category without its payload
+    pub fn kind(&self) -> TransportKind {
+        match self {
+            TransportType::Bus(_) => TransportKind::Bus,
+            TransportType::Train(_) => TransportKind::Train,
+            TransportType::Truck(_) => TransportKind::Truck,
+            TransportType::Drone(_) => TransportKind::Drone,
+        }
+    }
+
+    /// Distinct items carried by this transport, in declaration order
+    pub fn distinct_items(&self) -> Vec<Item> {
+        let mut items = Vec::new();
+        for flow in self.get_items() {
+            if !items.contains(&flow.item) {
+                items.push(flow.item);
+            }
+        }
+        items
+    }
+
+    /// Keep only the segments (conveyors, lifts, pipelines, wagons) carrying `item`
+    ///
+    /// Trucks and drones carry a single item and are returned unchanged.
+    pub fn retain_item(&self, item: Item) -> TransportType {
+        match self {
+            TransportType::Bus(bus) => {
+                let mut bus = bus.clone();
+                bus.lines.retain(|conveyor| conveyor.item == item);
+                bus.lifts.retain(|lift| lift.item == item);
+                bus.pipelines.retain(|pipeline| pipeline.item == item);
+                TransportType::Bus(bus)
+            }
+            TransportType::Train(train) => {
+                let mut train = train.clone();
+                train.wagons.retain(|wagon| wagon.item == item);
+                TransportType::Train(train)
+            }
+            TransportType::Truck(_) | TransportType::Drone(_) => self.clone(),
+        }
+    }
+
+    /// Replace the numeric transport identifier
+    pub fn set_transport_number(&mut self, number: u64) {
+        match self {
+            TransportType::Bus(bus) => bus.bus_id = number,
+            TransportType::Train(train) => train.train_id = number,
+            TransportType::Truck(truck) => truck.truck_id = number,
+            TransportType::Drone(drone) => drone.drone_id = number,
+        }
+    }
+
+    /// Get the numeric transport identifier (bus, train, truck or drone id)
+    pub fn transport_number(&self) -> u64 {
+        match self {
+            TransportType::Bus(bus) => bus.bus_id,
+            TransportType::Train(train) => train.train_id,
+            TransportType::Truck(truck) => truck.truck_id,
+            TransportType::Drone(drone) => drone.drone_id,
+        }
+    }
 }
 
 impl std::fmt::Display for TransportType {
@@ -105,7 +170,13 @@ pub struct LogisticsFlux {
     pub from_factory: FactoryId,
     pub to_factory: FactoryId,
     pub transport_type: TransportType,
-    pub transport_details: String,
+    #[serde(deserialize_with = "crate::models::transport_details::deserialize_saved")]
+    pub transport_details: TransportDetails,
+    #[serde(default)]
+    pub timestamps: Timestamps,
+    /// Fields from a newer save this engine doesn't know, written back as loaded
+    #[serde(flatten)]
+    pub unknown_fields: UnknownFields,
 }
 
 impl LogisticsFlux {
@@ -115,7 +186,7 @@ impl LogisticsFlux {
     }
 
     /// Get total quantity across all items
-    pub fn total_quantity_per_min(&self) -> f32 {
+    pub fn total_quantity_per_min(&self) -> f64 {
         self.get_items().iter().map(|i| i.quantity_per_min).sum()
     }
 }
@@ -125,6 +196,9 @@ pub struct Bus {
     pub bus_id: u64,
     pub bus_name: String,
     pub lines: Vec<Conveyor>,
+    /// Conveyor lifts carrying items between floors
+    #[serde(default)]
+    pub lifts: Vec<ConveyorLift>,
     pub pipelines: Vec<Pipeline>,
 }
 
@@ -134,6 +208,7 @@ impl Bus {
             bus_id,
             bus_name: bus_name.into(),
             lines: Vec::new(),
+            lifts: Vec::new(),
             pipelines: Vec::new(),
         }
     }
@@ -143,6 +218,11 @@ impl Bus {
         self
     }
 
+    pub fn with_lift(mut self, lift: ConveyorLift) -> Self {
+        self.lifts.push(lift);
+        self
+    }
+
     pub fn with_pipeline(mut self, pipeline: Pipeline) -> Self {
         self.pipelines.push(pipeline);
         self
@@ -152,6 +232,10 @@ impl Bus {
         self.lines.push(conveyor);
     }
 
+    pub fn add_lift(&mut self, lift: ConveyorLift) {
+        self.lifts.push(lift);
+    }
+
     pub fn add_pipeline(&mut self, pipeline: Pipeline) {
         self.pipelines.push(pipeline);
     }
@@ -161,11 +245,11 @@ pub struct Conveyor {
     pub line_id: u64,
     pub speed: ConveyorSpeed,
     pub item: Item,
-    pub quantity_per_min: f32,
+    pub quantity_per_min: f64,
 }
 
 impl Conveyor {
-    pub fn new(line_id: u64, speed: ConveyorSpeed, item: Item, quantity_per_min: f32) -> Self {
+    pub fn new(line_id: u64, speed: ConveyorSpeed, item: Item, quantity_per_min: f64) -> Self {
         Self {
             line_id,
             speed,
@@ -175,28 +259,86 @@ impl Conveyor {
     }
 }
 
+/// Conveyor lift segment of a bus; lifts move as many items as belts of the same tier
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConveyorLift {
+    pub lift_id: u64,
+    pub speed: ConveyorSpeed,
+    pub item: Item,
+    pub quantity_per_min: f64,
+}
+
+impl ConveyorLift {
+    pub fn new(lift_id: u64, speed: ConveyorSpeed, item: Item, quantity_per_min: f64) -> Self {
+        Self {
+            lift_id,
+            speed,
+            item,
+            quantity_per_min,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Pipeline {
     pub pipeline_id: u64,
     pub capacity: PipelineCapacity,
     pub item: Item,
-    pub quantity_per_min: f32,
+    pub quantity_per_min: f64,
+    /// Pipeline Pumps Mk.1 along the pipeline
+    #[serde(default)]
+    pub pumps_mk1: u32,
+    /// Pipeline Pumps Mk.2 along the pipeline
+    #[serde(default)]
+    pub pumps_mk2: u32,
+    /// Height the fluid climbs from start to end, in meters; unknown when unset
+    #[serde(default)]
+    pub vertical_rise: Option<f64>,
 }
 
 impl Pipeline {
+    /// Head lift of a Pipeline Pump Mk.1, in meters
+    pub const PUMP_MK1_HEAD_LIFT: f64 = 20.0;
+    /// Head lift of a Pipeline Pump Mk.2, in meters
+    pub const PUMP_MK2_HEAD_LIFT: f64 = 50.0;
+
     pub fn new(
         pipeline_id: u64,
         capacity: PipelineCapacity,
         item: Item,
-        quantity_per_min: f32,
+        quantity_per_min: f64,
     ) -> Self {
         Self {
             pipeline_id,
             capacity,
             item,
             quantity_per_min,
+            pumps_mk1: 0,
+            pumps_mk2: 0,
+            vertical_rise: None,
         }
     }
+
+    pub fn with_pumps(mut self, pumps_mk1: u32, pumps_mk2: u32) -> Self {
+        self.pumps_mk1 = pumps_mk1;
+        self.pumps_mk2 = pumps_mk2;
+        self
+    }
+
+    pub fn with_vertical_rise(mut self, vertical_rise: f64) -> Self {
+        self.vertical_rise = Some(vertical_rise);
+        self
+    }
+
+    /// Highest rise the fluid can climb, in meters
+    ///
+    /// The pipe carries fluid up its own head lift and each pump, placed where
+    /// the fluid stops climbing, lifts it its head lift further.
+    pub fn head_lift(&self) -> f64 {
+        self.capacity.head_lift()
+            + self.pumps_mk1 as f64 * Self::PUMP_MK1_HEAD_LIFT
+            + self.pumps_mk2 as f64 * Self::PUMP_MK2_HEAD_LIFT
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -206,18 +348,37 @@ pub enum PipelineCapacity {
 }
 
 impl PipelineCapacity {
-    pub const MK1_CAPACITY: f32 = 300.0;
-    pub const MK2_CAPACITY: f32 = 600.0;
+    pub const MK1_CAPACITY: f64 = 300.0;
+    pub const MK2_CAPACITY: f64 = 600.0;
+    pub const MK1_HEAD_LIFT: f64 = 10.0;
+    pub const MK2_HEAD_LIFT: f64 = 20.0;
 
-    pub fn m3_per_min(&self) -> f32 {
+    pub fn m3_per_min(&self) -> f64 {
         match self {
             PipelineCapacity::Mk1 => Self::MK1_CAPACITY,
             PipelineCapacity::Mk2 => Self::MK2_CAPACITY,
         }
     }
+
+    /// Height fluid climbs in this pipe without pumps, in meters
+    pub fn head_lift(&self) -> f64 {
+        match self {
+            PipelineCapacity::Mk1 => Self::MK1_HEAD_LIFT,
+            PipelineCapacity::Mk2 => Self::MK2_HEAD_LIFT,
+        }
+    }
 }
+impl PipelineCapacity {
+    /// Smallest pipeline able to carry `m3_per_min`, if any
+    pub fn smallest_for(m3_per_min: f64) -> Option<PipelineCapacity> {
+        [PipelineCapacity::Mk1, PipelineCapacity::Mk2]
+            .into_iter()
+            .find(|capacity| capacity.m3_per_min() >= m3_per_min)
+    }
+}
+
 impl FluidPerMin for PipelineCapacity {
-    fn m3_per_min(&self) -> f32 {
+    fn m3_per_min(&self) -> f64 {
         match self {
             PipelineCapacity::Mk1 => Self::MK1_CAPACITY,
             PipelineCapacity::Mk2 => Self::MK2_CAPACITY,
@@ -236,15 +397,31 @@ pub enum ConveyorSpeed {
 }
 
 impl ConveyorSpeed {
-    pub const MK1_SPEED: f32 = 60.0;
-    pub const MK2_SPEED: f32 = 120.0;
-    pub const MK3_SPEED: f32 = 270.0;
-    pub const MK4_SPEED: f32 = 480.0;
-    pub const MK5_SPEED: f32 = 780.0;
-    pub const MK6_SPEED: f32 = 1200.0;
+    pub const MK1_SPEED: f64 = 60.0;
+    pub const MK2_SPEED: f64 = 120.0;
+    pub const MK3_SPEED: f64 = 270.0;
+    pub const MK4_SPEED: f64 = 480.0;
+    pub const MK5_SPEED: f64 = 780.0;
+    pub const MK6_SPEED: f64 = 1200.0;
 }
+impl ConveyorSpeed {
+    /// Smallest conveyor able to carry `items_per_min`, if any
+    pub fn smallest_for(items_per_min: f64) -> Option<ConveyorSpeed> {
+        [
+            ConveyorSpeed::Mk1,
+            ConveyorSpeed::Mk2,
+            ConveyorSpeed::Mk3,
+            ConveyorSpeed::Mk4,
+            ConveyorSpeed::Mk5,
+            ConveyorSpeed::Mk6,
+        ]
+        .into_iter()
+        .find(|speed| speed.item_per_min() >= items_per_min)
+    }
+}
+
 impl ItemPerPin for ConveyorSpeed {
-    fn item_per_min(&self) -> f32 {
+    fn item_per_min(&self) -> f64 {
         match self {
             ConveyorSpeed::Mk1 => Self::MK1_SPEED,
             ConveyorSpeed::Mk2 => Self::MK2_SPEED,
@@ -287,11 +464,11 @@ pub struct Wagon {
     pub wagon_id: u64,
     pub wagon_type: WagonType,
     pub item: Item,
-    pub quantity_per_min: f32,
+    pub quantity_per_min: f64,
 }
 
 impl Wagon {
-    pub fn new(wagon_id: u64, wagon_type: WagonType, item: Item, quantity_per_min: f32) -> Self {
+    pub fn new(wagon_id: u64, wagon_type: WagonType, item: Item, quantity_per_min: f64) -> Self {
         Self {
             wagon_id,
             wagon_type,
@@ -343,6 +520,12 @@ impl Transport for Bus {
             quantity_per_min: c.quantity_per_min,
         }));
 
+        // Add lift items
+        items.extend(self.lifts.iter().map(|l| ItemFlow {
+            item: l.item,
+            quantity_per_min: l.quantity_per_min,
+        }));
+
         // Add pipeline items
         items.extend(self.pipelines.iter().map(|p| ItemFlow {
             item: p.item,
@@ -452,8 +635,8 @@ mod tests {
     use super::*;
     use uuid::Uuid;
 
-    fn uuid_from_u64(value: u64) -> Uuid {
-        Uuid::from_u128(value as u128)
+    fn uuid_from_u64<T: From<Uuid>>(value: u64) -> T {
+        Uuid::from_u128(value as u128).into()
     }
     use crate::models::Item;
     #[test]
@@ -520,6 +703,22 @@ mod tests {
         assert!(ConveyorSpeed::Mk6.item_per_min() > ConveyorSpeed::Mk5.item_per_min());
     }
 
+    #[test]
+    fn test_smallest_fitting_tier() {
+        assert_eq!(ConveyorSpeed::smallest_for(60.0), Some(ConveyorSpeed::Mk1));
+        assert_eq!(ConveyorSpeed::smallest_for(61.0), Some(ConveyorSpeed::Mk2));
+        assert_eq!(
+            ConveyorSpeed::smallest_for(1200.0),
+            Some(ConveyorSpeed::Mk6)
+        );
+        assert_eq!(ConveyorSpeed::smallest_for(1201.0), None);
+        assert_eq!(
+            PipelineCapacity::smallest_for(450.0),
+            Some(PipelineCapacity::Mk2)
+        );
+        assert_eq!(PipelineCapacity::smallest_for(601.0), None);
+    }
+
     #[test]
     fn test_pipeline_capacity_progression() {
         // Test that Mk2 has higher capacity than Mk1
@@ -586,6 +785,7 @@ mod tests {
                 Conveyor::new(1, ConveyorSpeed::Mk3, Item::CopperOre, 90.0),
                 Conveyor::new(2, ConveyorSpeed::Mk4, Item::IronOre, 180.0),
             ],
+            lifts: vec![],
             pipelines: vec![],
         };
 
@@ -601,6 +801,7 @@ mod tests {
             bus_id: 1,
             bus_name: "Mixed Bus".into(),
             lines: vec![Conveyor::new(1, ConveyorSpeed::Mk3, Item::CopperOre, 90.0)],
+            lifts: vec![],
             pipelines: vec![Pipeline::new(1, PipelineCapacity::Mk2, Item::Water, 450.0)],
         };
 
@@ -610,6 +811,37 @@ mod tests {
         assert_eq!(items[1].item, Item::Water);
     }
 
+    #[test]
+    fn test_bus_get_items_lifts() {
+        let bus = Bus::new(1, "Riser")
+            .with_conveyor(Conveyor::new(1, ConveyorSpeed::Mk6, Item::Coal, 1000.0))
+            .with_lift(ConveyorLift::new(1, ConveyorSpeed::Mk6, Item::Coal, 1000.0))
+            .with_pipeline(Pipeline::new(1, PipelineCapacity::Mk2, Item::Water, 450.0));
+
+        let items: Vec<Item> = bus.get_items().iter().map(|flow| flow.item).collect();
+        assert_eq!(items, vec![Item::Coal, Item::Coal, Item::Water]);
+
+        let TransportType::Bus(coal) = TransportType::Bus(bus).retain_item(Item::Coal) else {
+            panic!("expected a bus");
+        };
+        assert_eq!(coal.lifts.len(), 1);
+        assert!(coal.pipelines.is_empty());
+    }
+
+    #[test]
+    fn test_pipeline_head_lift() {
+        let pipeline = Pipeline::new(1, PipelineCapacity::Mk2, Item::Water, 450.0);
+        assert_eq!(pipeline.head_lift(), 20.0);
+        assert_eq!(pipeline.vertical_rise, None);
+
+        let pumped = pipeline.with_pumps(1, 2).with_vertical_rise(130.0);
+        assert_eq!(pumped.head_lift(), 140.0);
+        assert_eq!(
+            Pipeline::new(2, PipelineCapacity::Mk1, Item::Water, 300.0).head_lift(),
+            10.0
+        );
+    }
+
     #[test]
     fn test_truck_transport_get_items() {
         let truck = TruckTransport {
@@ -664,6 +896,7 @@ mod tests {
             bus_id: 5,
             bus_name: "Main Line".into(),
             lines: vec![Conveyor::new(1, ConveyorSpeed::Mk3, Item::CopperOre, 90.0)],
+            lifts: vec![],
             pipelines: vec![],
         };
 
@@ -724,6 +957,8 @@ mod tests {
             to_factory: uuid_from_u64(2),
             transport_type: TransportType::Train(train),
             transport_details: "Main line".into(),
+            timestamps: Timestamps::default(),
+            unknown_fields: UnknownFields::new(),
         };
 
         let items = flux.get_items();
@@ -751,10 +986,45 @@ mod tests {
             to_factory: uuid_from_u64(2),
             transport_type: TransportType::Train(train),
             transport_details: "".into(),
+            timestamps: Timestamps::default(),
+            unknown_fields: UnknownFields::new(),
         };
         assert_eq!(flux.total_quantity_per_min(), 180.0);
     }
 
+    #[test]
+    fn test_transport_retain_item() {
+        let bus = Bus::new(1, "Main Bus")
+            .with_conveyor(Conveyor::new(1, ConveyorSpeed::Mk3, Item::IronPlate, 90.0))
+            .with_conveyor(Conveyor::new(
+                2,
+                ConveyorSpeed::Mk3,
+                Item::CopperSheet,
+                60.0,
+            ))
+            .with_conveyor(Conveyor::new(3, ConveyorSpeed::Mk3, Item::IronPlate, 30.0))
+            .with_pipeline(Pipeline::new(1, PipelineCapacity::Mk1, Item::Water, 120.0));
+        let transport = TransportType::Bus(bus);
+
+        assert_eq!(
+            transport.distinct_items(),
+            vec![Item::IronPlate, Item::CopperSheet, Item::Water]
+        );
+
+        let iron_only = transport.retain_item(Item::IronPlate);
+        assert_eq!(iron_only.get_items().len(), 2);
+        assert_eq!(iron_only.distinct_items(), vec![Item::IronPlate]);
+
+        let water_only = transport.retain_item(Item::Water);
+        match water_only {
+            TransportType::Bus(bus) => {
+                assert!(bus.lines.is_empty());
+                assert_eq!(bus.pipelines.len(), 1);
+            }
+            _ => panic!("Expected bus transport"),
+        }
+    }
+
     #[test]
     fn test_item_flow_creation() {
         let item_flow = ItemFlow {
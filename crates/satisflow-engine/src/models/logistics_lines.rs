@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+use std::ops::Deref;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::models::{logistics::LogisticsFlux, FactoryId, LogisticsId};
+
+/// Logistics lines looked up by the factory on either end
+pub trait FactoryLogistics {
+    /// Lines delivering into a factory
+    fn inbound(&self, factory: FactoryId) -> impl Iterator<Item = &LogisticsFlux>;
+
+    /// Lines shipping out of a factory
+    fn outbound(&self, factory: FactoryId) -> impl Iterator<Item = &LogisticsFlux>;
+}
+
+impl FactoryLogistics for HashMap<LogisticsId, LogisticsFlux> {
+    fn inbound(&self, factory: FactoryId) -> impl Iterator<Item = &LogisticsFlux> {
+        self.values().filter(move |line| line.to_factory == factory)
+    }
+
+    fn outbound(&self, factory: FactoryId) -> impl Iterator<Item = &LogisticsFlux> {
+        self.values()
+            .filter(move |line| line.from_factory == factory)
+    }
+}
+
+/// All logistics lines of a save, indexed by source and destination factory
+///
+/// Reads go through the underlying map; writes go through the methods below so
+/// the indices never drift from the lines. Saves hold the plain map and the
+/// indices are rebuilt on load.
+#[derive(Debug, Clone, Default)]
+pub struct LogisticsLines {
+    lines: HashMap<LogisticsId, LogisticsFlux>,
+    inbound: HashMap<FactoryId, Vec<LogisticsId>>,
+    outbound: HashMap<FactoryId, Vec<LogisticsId>>,
+}
+
+impl LogisticsLines {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a line, replacing any line with the same ID
+    pub fn insert(&mut self, id: LogisticsId, line: LogisticsFlux) -> Option<LogisticsFlux> {
+        let previous = self.remove(&id);
+        self.inbound.entry(line.to_factory).or_default().push(id);
+        self.outbound.entry(line.from_factory).or_default().push(id);
+        self.lines.insert(id, line);
+        previous
+    }
+
+    /// Remove a line
+    pub fn remove(&mut self, id: &LogisticsId) -> Option<LogisticsFlux> {
+        let line = self.lines.remove(id)?;
+        unlink(&mut self.inbound, line.to_factory, *id);
+        unlink(&mut self.outbound, line.from_factory, *id);
+        Some(line)
+    }
+
+    /// Edit a line in place, re-indexing it if its endpoints changed
+    ///
+    /// # Returns
+    ///
+    /// The closure's result, or `None` if the line doesn't exist
+    pub fn update<R>(
+        &mut self,
+        id: &LogisticsId,
+        edit: impl FnOnce(&mut LogisticsFlux) -> R,
+    ) -> Option<R> {
+        let line = self.lines.get_mut(id)?;
+        let (from, to) = (line.from_factory, line.to_factory);
+        let result = edit(line);
+        let (new_from, new_to) = (line.from_factory, line.to_factory);
+        if new_to != to {
+            unlink(&mut self.inbound, to, *id);
+            self.inbound.entry(new_to).or_default().push(*id);
+        }
+        if new_from != from {
+            unlink(&mut self.outbound, from, *id);
+            self.outbound.entry(new_from).or_default().push(*id);
+        }
+        Some(result)
+    }
+
+    /// Remove every line starting or ending at a factory
+    ///
+    /// # Returns
+    ///
+    /// The removed lines
+    pub fn remove_factory(&mut self, factory: FactoryId) -> Vec<LogisticsFlux> {
+        let mut ids = self.inbound.remove(&factory).unwrap_or_default();
+        ids.extend(self.outbound.remove(&factory).unwrap_or_default());
+        ids.into_iter().filter_map(|id| self.remove(&id)).collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.inbound.clear();
+        self.outbound.clear();
+    }
+
+    /// IDs of the lines delivering into a factory
+    pub fn inbound_ids(&self, factory: FactoryId) -> &[LogisticsId] {
+        self.inbound.get(&factory).map_or(&[], Vec::as_slice)
+    }
+
+    /// IDs of the lines shipping out of a factory
+    pub fn outbound_ids(&self, factory: FactoryId) -> &[LogisticsId] {
+        self.outbound.get(&factory).map_or(&[], Vec::as_slice)
+    }
+
+    fn resolve<'a>(
+        &'a self,
+        ids: &'a [LogisticsId],
+    ) -> impl Iterator<Item = &'a LogisticsFlux> + 'a {
+        ids.iter().filter_map(|id| self.lines.get(id))
+    }
+}
+
+/// Drop a line from the index entry of a factory
+fn unlink(index: &mut HashMap<FactoryId, Vec<LogisticsId>>, factory: FactoryId, id: LogisticsId) {
+    if let Some(ids) = index.get_mut(&factory) {
+        ids.retain(|linked| *linked != id);
+        if ids.is_empty() {
+            index.remove(&factory);
+        }
+    }
+}
+
+impl FactoryLogistics for LogisticsLines {
+    fn inbound(&self, factory: FactoryId) -> impl Iterator<Item = &LogisticsFlux> {
+        self.resolve(self.inbound_ids(factory))
+    }
+
+    fn outbound(&self, factory: FactoryId) -> impl Iterator<Item = &LogisticsFlux> {
+        self.resolve(self.outbound_ids(factory))
+    }
+}
+
+impl Deref for LogisticsLines {
+    type Target = HashMap<LogisticsId, LogisticsFlux>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.lines
+    }
+}
+
+impl<'a> IntoIterator for &'a LogisticsLines {
+    type Item = (&'a LogisticsId, &'a LogisticsFlux);
+    type IntoIter = std::collections::hash_map::Iter<'a, LogisticsId, LogisticsFlux>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.lines.iter()
+    }
+}
+
+impl From<HashMap<LogisticsId, LogisticsFlux>> for LogisticsLines {
+    fn from(lines: HashMap<LogisticsId, LogisticsFlux>) -> Self {
+        let mut indexed = Self::new();
+        for (id, line) in lines {
+            indexed.insert(id, line);
+        }
+        indexed
+    }
+}
+
+impl Serialize for LogisticsLines {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.lines.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for LogisticsLines {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        HashMap::deserialize(deserializer).map(Self::from)
+    }
+}
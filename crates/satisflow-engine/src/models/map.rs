@@ -0,0 +1,162 @@
+//! World positions of factories and travel estimates for vehicle logistics.
+//!
+//! Distances are straight lines between factory positions, in meters. Drones
+//! fly that line; roads and tracks are usually longer, so truck and train
+//! estimates are a lower bound.
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{
+    logistics::{LogisticsFlux, Transport},
+    naming::TransportKind,
+    Item, LogisticsId,
+};
+
+/// A point in the game world, in meters
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Position {
+    pub x: f64,
+    pub y: f64,
+    #[serde(default)]
+    pub z: f64,
+}
+
+impl Position {
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if [self.x, self.y, self.z]
+            .iter()
+            .all(|value| value.is_finite())
+        {
+            Ok(())
+        } else {
+            Err("Position coordinates must be finite numbers".to_string())
+        }
+    }
+
+    /// Straight-line distance to another position, in meters
+    pub fn distance_to(&self, other: &Position) -> f64 {
+        let (dx, dy, dz) = (other.x - self.x, other.y - self.y, other.z - self.z);
+        (dx * dx + dy * dy + dz * dz).sqrt()
+    }
+}
+
+/// Average travel speed of a vehicle, in meters per second
+///
+/// Buses are conveyors and pipelines, so they have no travel time.
+pub fn travel_speed(kind: TransportKind) -> Option<f64> {
+    match kind {
+        TransportKind::Bus => None,
+        TransportKind::Train => Some(25.0), // ~90 km/h including acceleration
+        TransportKind::Truck => Some(14.0), // ~50 km/h on a typical road
+        TransportKind::Drone => Some(69.0), // ~250 km/h
+    }
+}
+
+/// Time a vehicle spends at one station loading or unloading, in seconds
+pub fn docking_time(kind: TransportKind) -> f64 {
+    match kind {
+        TransportKind::Bus => 0.0,
+        TransportKind::Train => 27.0,
+        TransportKind::Truck => 12.0,
+        TransportKind::Drone => 25.0,
+    }
+}
+
+/// Quantity of an item loaded on one trip
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TripCargo {
+    pub item: Item,
+    pub quantity: f64,
+}
+
+/// Travel estimate of a vehicle logistics line between two placed factories
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RouteEstimate {
+    pub logistics_id: LogisticsId,
+    /// Straight-line distance between the factories, in meters
+    pub distance: f64,
+    /// Time for one vehicle to go there and back, docking included, in seconds
+    pub round_trip_secs: f64,
+    /// What each round trip must carry to keep up the planned rates
+    pub cargo_per_trip: Vec<TripCargo>,
+}
+
+impl RouteEstimate {
+    /// Estimate a route, if the line uses vehicles
+    pub fn new(logistics: &LogisticsFlux, from: &Position, to: &Position) -> Option<Self> {
+        let kind = logistics.transport_type.kind();
+        let speed = travel_speed(kind)?;
+
+        let distance = from.distance_to(to);
+        let round_trip_secs = 2.0 * distance / speed + 2.0 * docking_time(kind);
+        let cargo_per_trip = logistics
+            .transport_type
+            .get_items()
+            .into_iter()
+            .map(|flow| TripCargo {
+                item: flow.item,
+                quantity: flow.quantity_per_min * round_trip_secs / 60.0,
+            })
+            .collect();
+
+        Some(Self {
+            logistics_id: logistics.id,
+            distance,
+            round_trip_secs,
+            cargo_per_trip,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        logistics::{Bus, DroneTransport, TransportType},
+        UnknownFields,
+    };
+    use uuid::Uuid;
+
+    fn logistics(transport_type: TransportType) -> LogisticsFlux {
+        LogisticsFlux {
+            id: Uuid::from_u128(1).into(),
+            from_factory: Uuid::from_u128(2).into(),
+            to_factory: Uuid::from_u128(3).into(),
+            transport_type,
+            transport_details: Default::default(),
+            timestamps: Default::default(),
+            unknown_fields: UnknownFields::new(),
+        }
+    }
+
+    #[test]
+    fn test_distance() {
+        let origin = Position::new(0.0, 0.0, 0.0);
+        assert_eq!(origin.distance_to(&Position::new(300.0, 400.0, 0.0)), 500.0);
+        assert!(Position::new(f64::NAN, 0.0, 0.0).validate().is_err());
+    }
+
+    #[test]
+    fn test_route_estimate() {
+        let from = Position::new(0.0, 0.0, 0.0);
+        let to = Position::new(3450.0, 0.0, 0.0);
+
+        let drone = logistics(TransportType::Drone(DroneTransport::new(
+            1,
+            Item::Computer,
+            6.0,
+        )));
+        let estimate = RouteEstimate::new(&drone, &from, &to).unwrap();
+        assert_eq!(estimate.distance, 3450.0);
+        // 2 × 50 s of flight plus 2 × 25 s docking
+        assert_eq!(estimate.round_trip_secs, 150.0);
+        assert_eq!(estimate.cargo_per_trip[0].quantity, 15.0);
+
+        let bus = logistics(TransportType::Bus(Bus::new(1, "Main")));
+        assert!(RouteEstimate::new(&bus, &from, &to).is_none());
+    }
+}
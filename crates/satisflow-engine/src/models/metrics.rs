@@ -0,0 +1,18 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::Item;
+
+/// Dashboard metrics of the plan at one engine revision
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricsPoint {
+    pub revision: u64,
+    pub recorded_at: DateTime<Utc>,
+    pub power_generation: f64,
+    pub power_consumption: f64,
+    pub power_balance: f64,
+    /// Net rate per minute of every item with a non-zero balance
+    pub items: HashMap<Item, f64>,
+}
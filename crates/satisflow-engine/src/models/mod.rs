@@ -1,21 +1,118 @@
+pub mod balance_suggestions;
+pub mod custom_recipe;
+pub mod dependency;
+pub mod diagnostics;
 pub mod factory;
+pub mod factory_delete;
+pub mod factory_merge;
+pub mod factory_split;
+pub mod factory_task;
+pub mod factory_template;
+pub mod ficsmas;
+pub mod flows;
+pub mod game_balance;
 pub mod game_data;
+pub mod game_data_set;
+pub mod goals;
 pub mod ids;
+pub mod internal_transfer;
+pub mod inventory;
+pub mod item_balance;
 pub mod items;
 pub mod logistics;
+pub mod logistics_lines;
+pub mod map;
+pub mod metrics;
+pub mod naming;
+pub mod nuclear_waste;
+pub mod pins;
 pub mod power_generator;
+pub mod power_grid;
+pub mod power_storage;
 pub mod production_line;
 pub mod raw_input;
+pub mod recipe_chain;
 pub mod recipes;
+pub mod report_card;
+pub mod resource_nodes;
+pub mod search;
+pub mod storage_buffer;
+pub mod tag_groups;
+pub mod template_propagation;
+pub mod template_revision;
+pub mod tier_progress;
+pub mod timestamps;
+pub mod train_schedule;
+pub mod transport_details;
+pub mod validation;
 
 #[cfg(test)]
 pub mod data_validation_tests;
 
-pub use ids::{FactoryId, LogisticsId, PowerGeneratorId, ProductionLineId, RawInputId};
-pub use items::{all_items, item_by_name, item_name, Item, ItemParseError, ITEM_NAME_PAIRS};
+pub use balance_suggestions::BalanceSuggestion;
+pub use custom_recipe::{CustomRecipe, LineRecipe};
+pub use dependency::{FactoryDependencies, FactoryDependency};
+pub use diagnostics::{Diagnostic, DiagnosticKind, Severity};
+pub use factory_delete::{DownstreamDeficit, FactoryDeletePreview, RemovedLogisticsLine};
+pub use factory_merge::{FactoryMergePreview, MergedItemBalance};
+pub use factory_split::{FactorySplitResult, FactorySplitSelection};
+pub use factory_task::{FactoryTask, FactoryTaskError};
+pub use factory_template::FactoryTemplate;
+pub use ficsmas::FICSMAS_ITEMS;
+pub use flows::{FlowLink, FlowNetwork, FlowNode};
+pub use game_balance::GameBalance;
+pub use game_data_set::GameDataSet;
+pub use goals::{Goal, GoalProgress};
+pub use ids::{
+    CustomRecipeId, FactoryId, FactoryTaskId, FactoryTemplateId, GoalId, InternalTransferId,
+    LogisticsId, PowerGeneratorId, PowerGridId, ProductionLineId, RawInputId, StorageBufferId,
+};
+pub use internal_transfer::{InternalTransfer, InternalTransferError};
+pub use inventory::{FactoryInventory, ResourceInventory};
+pub use item_balance::{ItemBalanceReport, ItemFlowEntry, ItemFlowSource};
+pub use items::{
+    all_items, item_by_alias, item_by_name, item_name, Item, ItemParseError, ITEM_ALIASES,
+    ITEM_NAME_PAIRS,
+};
+pub use logistics_lines::{FactoryLogistics, LogisticsLines};
+pub use map::{Position, RouteEstimate, TripCargo};
+pub use metrics::MetricsPoint;
+pub use naming::{NamingError, TransportKind, TransportNaming};
+pub use nuclear_waste::{FactoryWaste, NuclearWasteReport, WasteFlow, NUCLEAR_WASTE_ITEMS};
+pub use pins::PinnedEntities;
 pub use power_generator::{
-    FactoryPowerStats, GeneratorGroup, GeneratorType, PowerGenerator, PowerGeneratorError,
-    PowerStats,
+    FactoryPowerStats, FuelShortfall, GenerationRange, GeneratorGroup, GeneratorType,
+    PowerGenerator, PowerGeneratorBuilder, PowerGeneratorError, PowerStats,
 };
+pub use power_grid::{GridPowerStats, PowerGrid};
+pub use power_storage::{PowerStorage, PowerStorageError, StorageStatus};
 pub use raw_input::{ExtractorType, Purity, RawInput, RawInputError};
-pub use recipes::{all_recipes, recipe_by_name, recipe_info, recipe_name, Recipe, RecipeInfo};
+pub use recipe_chain::{ChainInput, ChainSource, RecipeChain, MAX_RECIPE_CHAIN_DEPTH};
+pub use recipes::{
+    all_recipes, recipe_by_name, recipe_info, recipe_name, standard_recipe_for, Recipe, RecipeInfo,
+};
+pub use report_card::{ItemRate, ReportCard};
+pub use resource_nodes::{
+    NodeClaim, NodeCounts, NodeUsage, ResourceNodeRegistry, ResourceNodeReport,
+};
+pub use search::{SearchField, SearchHit, SearchTarget};
+pub use storage_buffer::{BufferStatus, StorageBuffer, StorageBufferError};
+pub use tag_groups::TagGroupStats;
+pub use template_propagation::{
+    FactoryPropagation, InstanceUpdate, ItemRateChange, TemplateInstance, TemplatePropagation,
+};
+pub use template_revision::{TemplateRevision, TemplateVersion};
+pub use tier_progress::{MamResearch, TierProgress};
+pub use timestamps::{ChangedEntity, RecentChange, Timestamps};
+pub use train_schedule::{TrainSchedule, TrainScheduleError, TrainThroughput, WagonThroughput};
+pub use transport_details::TransportDetails;
+pub use validation::ValidationReport;
+
+/// Serde default for `enabled` flags, so entries from older saves stay active
+pub(crate) fn enabled_by_default() -> bool {
+    true
+}
+
+/// Fields of a saved entity that this engine doesn't know, e.g. written by a
+/// newer minor version; captured on load so saving writes them back
+pub type UnknownFields = std::collections::HashMap<String, serde_json::Value>;
@@ -0,0 +1,271 @@
+//! Default naming and auto-numbering for logistics transports.
+//!
+//! When a client omits a transport identifier or name, the engine generates one
+//! from a per-transport-type pattern. Patterns are plain strings with
+//! placeholders:
+//!
+//! - `{prefix}` - the transport prefix (`BUS`, `TRN`, `TRK`, `DRN`)
+//! - `{n}` - the transport number
+//! - `{n:03}` - the transport number zero-padded to the given width
+//!
+//! For example `"{prefix}-Alpha-{n:02}"` renders as `TRN-Alpha-03` for the third
+//! train.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Transport categories, without their payload
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TransportKind {
+    Bus,
+    Train,
+    Truck,
+    Drone,
+}
+
+impl TransportKind {
+    /// Short prefix used in transport identifiers
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            TransportKind::Bus => "BUS",
+            TransportKind::Train => "TRN",
+            TransportKind::Truck => "TRK",
+            TransportKind::Drone => "DRN",
+        }
+    }
+
+    /// All transport kinds
+    pub fn all() -> [TransportKind; 4] {
+        [
+            TransportKind::Bus,
+            TransportKind::Train,
+            TransportKind::Truck,
+            TransportKind::Drone,
+        ]
+    }
+}
+
+impl fmt::Display for TransportKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            TransportKind::Bus => "Bus",
+            TransportKind::Train => "Train",
+            TransportKind::Truck => "Truck",
+            TransportKind::Drone => "Drone",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Naming patterns used when generating default transport labels
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransportNaming {
+    pub bus: String,
+    pub train: String,
+    pub truck: String,
+    pub drone: String,
+}
+
+impl Default for TransportNaming {
+    fn default() -> Self {
+        Self {
+            bus: "Bus {n}".to_string(),
+            train: "Train {n}".to_string(),
+            truck: "{prefix}-{n:03}".to_string(),
+            drone: "{prefix}-{n:03}".to_string(),
+        }
+    }
+}
+
+impl TransportNaming {
+    /// Get the pattern configured for a transport kind
+    pub fn pattern(&self, kind: TransportKind) -> &str {
+        match kind {
+            TransportKind::Bus => &self.bus,
+            TransportKind::Train => &self.train,
+            TransportKind::Truck => &self.truck,
+            TransportKind::Drone => &self.drone,
+        }
+    }
+
+    /// Replace the pattern for a transport kind after validating it
+    pub fn set_pattern(
+        &mut self,
+        kind: TransportKind,
+        pattern: impl Into<String>,
+    ) -> Result<(), NamingError> {
+        let pattern = pattern.into();
+        validate_pattern(&pattern)?;
+        match kind {
+            TransportKind::Bus => self.bus = pattern,
+            TransportKind::Train => self.train = pattern,
+            TransportKind::Truck => self.truck = pattern,
+            TransportKind::Drone => self.drone = pattern,
+        }
+        Ok(())
+    }
+
+    /// Render the default label for the given transport number
+    pub fn format(&self, kind: TransportKind, number: u64) -> String {
+        render_pattern(self.pattern(kind), kind.prefix(), number)
+    }
+}
+
+/// Errors raised by invalid naming patterns
+#[derive(Debug, Clone, PartialEq)]
+pub enum NamingError {
+    EmptyPattern,
+    MissingNumber { pattern: String },
+    UnknownPlaceholder { placeholder: String },
+    UnclosedPlaceholder { pattern: String },
+}
+
+impl fmt::Display for NamingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NamingError::EmptyPattern => write!(f, "Naming pattern cannot be empty"),
+            NamingError::MissingNumber { pattern } => write!(
+                f,
+                "Naming pattern '{}' must contain a {{n}} placeholder to stay unique",
+                pattern
+            ),
+            NamingError::UnknownPlaceholder { placeholder } => {
+                write!(f, "Unknown naming placeholder '{{{}}}'", placeholder)
+            }
+            NamingError::UnclosedPlaceholder { pattern } => {
+                write!(
+                    f,
+                    "Naming pattern '{}' has an unclosed placeholder",
+                    pattern
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for NamingError {}
+
+enum Placeholder {
+    Prefix,
+    Number { width: usize },
+}
+
+fn parse_placeholder(token: &str) -> Result<Placeholder, NamingError> {
+    match token {
+        "prefix" => Ok(Placeholder::Prefix),
+        "n" => Ok(Placeholder::Number { width: 0 }),
+        _ => token
+            .strip_prefix("n:")
+            .and_then(|width| width.parse::<usize>().ok())
+            .map(|width| Placeholder::Number { width })
+            .ok_or_else(|| NamingError::UnknownPlaceholder {
+                placeholder: token.to_string(),
+            }),
+    }
+}
+
+/// Check that a pattern only uses known placeholders and contains a number
+pub fn validate_pattern(pattern: &str) -> Result<(), NamingError> {
+    if pattern.trim().is_empty() {
+        return Err(NamingError::EmptyPattern);
+    }
+
+    let mut has_number = false;
+    let mut rest = pattern;
+    while let Some(start) = rest.find('{') {
+        let after = &rest[start + 1..];
+        let end = after
+            .find('}')
+            .ok_or_else(|| NamingError::UnclosedPlaceholder {
+                pattern: pattern.to_string(),
+            })?;
+        if let Placeholder::Number { .. } = parse_placeholder(&after[..end])? {
+            has_number = true;
+        }
+        rest = &after[end + 1..];
+    }
+
+    if !has_number {
+        return Err(NamingError::MissingNumber {
+            pattern: pattern.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Render a pattern, leaving unknown placeholders untouched
+pub fn render_pattern(pattern: &str, prefix: &str, number: u64) -> String {
+    let mut output = String::with_capacity(pattern.len() + 8);
+    let mut rest = pattern;
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('}') else {
+            output.push_str(&rest[start..]);
+            return output;
+        };
+        match parse_placeholder(&after[..end]) {
+            Ok(Placeholder::Prefix) => output.push_str(prefix),
+            Ok(Placeholder::Number { width }) => {
+                output.push_str(&format!("{:0width$}", number, width = width))
+            }
+            Err(_) => output.push_str(&rest[start..start + end + 2]),
+        }
+        rest = &after[end + 1..];
+    }
+    output.push_str(rest);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_patterns_match_legacy_labels() {
+        let naming = TransportNaming::default();
+        assert_eq!(naming.format(TransportKind::Truck, 7), "TRK-007");
+        assert_eq!(naming.format(TransportKind::Drone, 12), "DRN-012");
+        assert_eq!(naming.format(TransportKind::Bus, 1), "Bus 1");
+        assert_eq!(naming.format(TransportKind::Train, 3), "Train 3");
+    }
+
+    #[test]
+    fn test_custom_patterns() {
+        let mut naming = TransportNaming::default();
+        naming
+            .set_pattern(TransportKind::Train, "{prefix}-Alpha-{n:02}")
+            .unwrap();
+        naming
+            .set_pattern(TransportKind::Bus, "{prefix}-North-{n}")
+            .unwrap();
+
+        assert_eq!(naming.format(TransportKind::Train, 3), "TRN-Alpha-03");
+        assert_eq!(naming.format(TransportKind::Bus, 2), "BUS-North-2");
+    }
+
+    #[test]
+    fn test_invalid_patterns_are_rejected() {
+        let mut naming = TransportNaming::default();
+
+        assert_eq!(
+            naming.set_pattern(TransportKind::Truck, "   "),
+            Err(NamingError::EmptyPattern)
+        );
+        assert!(matches!(
+            naming.set_pattern(TransportKind::Truck, "{prefix}-fixed"),
+            Err(NamingError::MissingNumber { .. })
+        ));
+        assert!(matches!(
+            naming.set_pattern(TransportKind::Truck, "{prefix}-{id}-{n}"),
+            Err(NamingError::UnknownPlaceholder { .. })
+        ));
+        assert!(matches!(
+            naming.set_pattern(TransportKind::Truck, "{prefix}-{n"),
+            Err(NamingError::UnclosedPlaceholder { .. })
+        ));
+
+        // Failed updates leave the previous pattern in place
+        assert_eq!(naming.pattern(TransportKind::Truck), "{prefix}-{n:03}");
+    }
+}
@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{
+    factory::Factory, logistics::LogisticsFlux, FactoryId, FactoryLogistics, Item,
+};
+
+/// Radioactive waste items; they cannot be sunk, only reprocessed or stored
+pub const NUCLEAR_WASTE_ITEMS: [Item; 2] = [Item::UraniumWaste, Item::PlutoniumWaste];
+
+/// Flows of one waste item through a factory, in items per minute
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WasteFlow {
+    pub item: Item,
+    /// Waste left by nuclear generators
+    pub produced: f64,
+    /// Waste reprocessed by production lines
+    pub reprocessed: f64,
+    pub imported: f64,
+    pub exported: f64,
+    /// Waste piling up in the factory; negative when reprocessing lacks input
+    pub accumulation: f64,
+}
+
+impl WasteFlow {
+    fn new(item: Item) -> Self {
+        Self {
+            item,
+            produced: 0.0,
+            reprocessed: 0.0,
+            imported: 0.0,
+            exported: 0.0,
+            accumulation: 0.0,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.produced == 0.0
+            && self.reprocessed == 0.0
+            && self.imported == 0.0
+            && self.exported == 0.0
+    }
+
+    fn add(&mut self, other: &WasteFlow) {
+        self.produced += other.produced;
+        self.reprocessed += other.reprocessed;
+        self.imported += other.imported;
+        self.exported += other.exported;
+        self.accumulation += other.accumulation;
+    }
+}
+
+/// Waste handled by a single factory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FactoryWaste {
+    pub factory_id: FactoryId,
+    pub factory_name: String,
+    pub waste: Vec<WasteFlow>,
+}
+
+impl FactoryWaste {
+    /// Check if any waste piles up in this factory
+    pub fn is_accumulating(&self) -> bool {
+        self.waste.iter().any(|flow| flow.accumulation > 0.0)
+    }
+}
+
+/// Un-sunk uranium and plutonium waste across all factories
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NuclearWasteReport {
+    /// Factories producing, reprocessing or shipping waste, sorted by name
+    pub factories: Vec<FactoryWaste>,
+    /// Totals per waste item; imports and exports cancel out globally
+    pub totals: Vec<WasteFlow>,
+}
+
+impl NuclearWasteReport {
+    /// Build the report from every factory and the logistics lines between them
+    pub fn build<'a>(
+        factories: impl IntoIterator<Item = &'a Factory>,
+        logistics_lines: &impl FactoryLogistics,
+    ) -> Self {
+        let mut report_factories = Vec::new();
+        let mut totals: Vec<WasteFlow> = NUCLEAR_WASTE_ITEMS.map(WasteFlow::new).to_vec();
+
+        for factory in factories {
+            let mut waste: Vec<WasteFlow> = NUCLEAR_WASTE_ITEMS.map(WasteFlow::new).to_vec();
+
+            for flow in &mut waste {
+                for generator in factory.active_power_generators() {
+                    if generator.waste_product() == Some(flow.item) {
+                        flow.produced += generator.waste_production_rate();
+                    }
+                }
+                for line in factory.active_production_lines() {
+                    flow.produced += rate_for_item(line.output_rate(), flow.item);
+                    flow.reprocessed += rate_for_item(line.input_rate(), flow.item);
+                }
+                let waste_item = flow.item;
+                let carried = |line: &LogisticsFlux| -> f64 {
+                    line.get_items()
+                        .iter()
+                        .filter(|item_flow| item_flow.item == waste_item)
+                        .map(|item_flow| item_flow.quantity_per_min)
+                        .sum()
+                };
+                flow.imported += logistics_lines
+                    .inbound(factory.id)
+                    .map(carried)
+                    .sum::<f64>();
+                flow.exported += logistics_lines
+                    .outbound(factory.id)
+                    .map(carried)
+                    .sum::<f64>();
+                flow.accumulation =
+                    flow.produced + flow.imported - flow.reprocessed - flow.exported;
+            }
+
+            waste.retain(|flow| !flow.is_empty());
+            if waste.is_empty() {
+                continue;
+            }
+            for flow in &waste {
+                if let Some(total) = totals.iter_mut().find(|total| total.item == flow.item) {
+                    total.add(flow);
+                }
+            }
+            report_factories.push(FactoryWaste {
+                factory_id: factory.id,
+                factory_name: factory.name.clone(),
+                waste,
+            });
+        }
+
+        report_factories.sort_by(|a, b| a.factory_name.cmp(&b.factory_name));
+        totals.retain(|flow| !flow.is_empty());
+
+        Self {
+            factories: report_factories,
+            totals,
+        }
+    }
+}
+
+fn rate_for_item(rates: Vec<(Item, f64)>, item: Item) -> f64 {
+    rates
+        .into_iter()
+        .filter(|(rate_item, _)| *rate_item == item)
+        .map(|(_, quantity)| quantity)
+        .sum()
+}
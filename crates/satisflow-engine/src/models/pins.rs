@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{FactoryId, Item};
+
+/// Factories and items marked as favorites, in the order they were pinned
+///
+/// Pins are global until the server has user accounts.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PinnedEntities {
+    pub factories: Vec<FactoryId>,
+    pub items: Vec<Item>,
+}
+
+impl PinnedEntities {
+    /// Pin a factory; returns `false` if it was already pinned
+    pub fn pin_factory(&mut self, id: FactoryId) -> bool {
+        if self.factories.contains(&id) {
+            return false;
+        }
+        self.factories.push(id);
+        true
+    }
+
+    /// Unpin a factory; returns `false` if it was not pinned
+    pub fn unpin_factory(&mut self, id: FactoryId) -> bool {
+        let count = self.factories.len();
+        self.factories.retain(|pinned| *pinned != id);
+        self.factories.len() != count
+    }
+
+    /// Pin an item; returns `false` if it was already pinned
+    pub fn pin_item(&mut self, item: Item) -> bool {
+        if self.items.contains(&item) {
+            return false;
+        }
+        self.items.push(item);
+        true
+    }
+
+    /// Unpin an item; returns `false` if it was not pinned
+    pub fn unpin_item(&mut self, item: Item) -> bool {
+        let count = self.items.len();
+        self.items.retain(|pinned| *pinned != item);
+        self.items.len() != count
+    }
+
+    /// Replace a pinned factory with another one, keeping its position;
+    /// returns `false` if the old factory was not pinned
+    pub fn replace_factory(&mut self, old: FactoryId, new: FactoryId) -> bool {
+        let Some(pinned) = self.factories.iter_mut().find(|pinned| **pinned == old) else {
+            return false;
+        };
+        *pinned = new;
+        let mut seen = Vec::with_capacity(self.factories.len());
+        self.factories.retain(|id| {
+            let first = !seen.contains(id);
+            seen.push(*id);
+            first
+        });
+        true
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.factories.is_empty() && self.items.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn uuid_from_u64<T: From<Uuid>>(value: u64) -> T {
+        Uuid::from_u128(value as u128).into()
+    }
+
+    #[test]
+    fn test_pin_and_unpin() {
+        let mut pins = PinnedEntities::default();
+
+        assert!(pins.pin_factory(uuid_from_u64(2)));
+        assert!(pins.pin_factory(uuid_from_u64(1)));
+        assert!(!pins.pin_factory(uuid_from_u64(2)));
+        assert!(pins.pin_item(Item::IronPlate));
+        assert!(!pins.pin_item(Item::IronPlate));
+        assert_eq!(pins.factories, vec![uuid_from_u64(2), uuid_from_u64(1)]);
+
+        assert!(pins.unpin_item(Item::IronPlate));
+        assert!(!pins.unpin_item(Item::IronPlate));
+        assert!(pins.unpin_factory(uuid_from_u64(2)));
+        assert!(pins.unpin_factory(uuid_from_u64(1)));
+        assert!(pins.is_empty());
+    }
+
+    #[test]
+    fn test_replace_factory_keeps_order_without_duplicates() {
+        let mut pins = PinnedEntities::default();
+        for id in [1, 2, 3] {
+            pins.pin_factory(uuid_from_u64(id));
+        }
+
+        assert!(pins.replace_factory(uuid_from_u64(1), uuid_from_u64(9)));
+        assert!(pins.replace_factory(uuid_from_u64(3), uuid_from_u64(9)));
+        assert!(!pins.replace_factory(uuid_from_u64(4), uuid_from_u64(9)));
+
+        assert_eq!(pins.factories, vec![uuid_from_u64(9), uuid_from_u64(2)]);
+    }
+}
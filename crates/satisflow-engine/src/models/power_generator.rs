@@ -1,7 +1,14 @@
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::fmt;
 
-use crate::models::{FactoryId, Item, PowerGeneratorId};
+use crate::format::NumberFormat;
+use crate::models::{
+    game_balance::GameBalance, game_data::power_shards_for_clock_speed, item_name,
+    power_grid::GridPowerStats, power_storage::StorageStatus, raw_input::Purity, FactoryId, Item,
+    PowerGeneratorId, PowerGridId, UnknownFields,
+};
+use crate::rate::{balance_sign, is_zero};
 
 /// Types of power generators available in Satisfactory
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Eq, Hash)]
@@ -15,7 +22,7 @@ pub enum GeneratorType {
 
 impl GeneratorType {
     /// Get the base power output for this generator type in MW at 100% clock speed
-    pub fn base_power_output(&self) -> f32 {
+    pub fn base_power_output(&self) -> f64 {
         match self {
             GeneratorType::Biomass => 30.0,     // Biomass Burner
             GeneratorType::Coal => 75.0,        // Coal Generator
@@ -27,7 +34,7 @@ impl GeneratorType {
 
     /// Get the base fuel consumption for this generator type at 100% clock speed
     /// Returns items/min for solid fuels or m³/min for liquid fuels
-    pub fn base_fuel_consumption(&self) -> f32 {
+    pub fn base_fuel_consumption(&self) -> f64 {
         match self {
             GeneratorType::Biomass => 4.5,    // Biomass items/min
             GeneratorType::Coal => 15.0,      // Coal items/min
@@ -37,6 +44,16 @@ impl GeneratorType {
         }
     }
 
+    /// Get the base water consumption for this generator type at 100% clock speed
+    /// Returns m³/min; only steam-driven generators need water
+    pub fn base_water_consumption(&self) -> f64 {
+        match self {
+            GeneratorType::Coal => 45.0,     // Coal Generator
+            GeneratorType::Nuclear => 240.0, // Nuclear Power Plant
+            _ => 0.0,
+        }
+    }
+
     /// Check if this generator type is compatible with the given fuel type
     pub fn is_compatible_with(&self, fuel: &Item) -> bool {
         match self {
@@ -58,14 +75,16 @@ impl GeneratorType {
             GeneratorType::Fuel => {
                 matches!(fuel, Item::Fuel | Item::Turbofuel | Item::LiquidBiofuel)
             }
-            GeneratorType::Nuclear => matches!(fuel, Item::UraniumFuelRod),
+            GeneratorType::Nuclear => {
+                matches!(fuel, Item::UraniumFuelRod | Item::PlutoniumFuelRod)
+            }
             GeneratorType::Geothermal => false, // Geothermal doesn't use fuel
         }
     }
 
     /// Get the fuel consumption multiplier for this generator type
     /// This allows different fuels to have different efficiency rates
-    pub fn fuel_consumption_multiplier(&self, fuel: &Item) -> f32 {
+    pub fn fuel_consumption_multiplier(&self, fuel: &Item) -> f64 {
         match self {
             GeneratorType::Biomass => 1.0, // All biomass types have same efficiency
             GeneratorType::Coal => match fuel {
@@ -80,7 +99,10 @@ impl GeneratorType {
                 Item::LiquidBiofuel => 1.33, // Less efficient
                 _ => 1.0,
             },
-            GeneratorType::Nuclear => 1.0, // Nuclear fuel rods have fixed efficiency
+            GeneratorType::Nuclear => match fuel {
+                Item::PlutoniumFuelRod => 0.5, // Burns twice as long as uranium
+                _ => 1.0,
+            },
             GeneratorType::Geothermal => 0.0, // No fuel consumption
         }
     }
@@ -98,8 +120,34 @@ impl GeneratorType {
         }
     }
 
+    /// Relative swing of the output around its average, as a fraction
+    ///
+    /// Geysers cycle between 50% and 150% of their average output.
+    pub fn output_fluctuation(&self) -> f64 {
+        match self {
+            GeneratorType::Geothermal => 0.5,
+            _ => 0.0,
+        }
+    }
+
+    /// Get the waste product left by burning the given fuel (if any)
+    pub fn waste_product_for(&self, fuel: &Item) -> Option<Item> {
+        match (self, fuel) {
+            (GeneratorType::Nuclear, Item::PlutoniumFuelRod) => Some(Item::PlutoniumWaste),
+            _ => self.waste_product(),
+        }
+    }
+
+    /// Get the waste production multiplier for the given fuel, relative to uranium
+    pub fn waste_multiplier(&self, fuel: &Item) -> f64 {
+        match (self, fuel) {
+            (GeneratorType::Nuclear, Item::PlutoniumFuelRod) => 0.1, // 1 waste/min vs 10 for uranium
+            _ => 1.0,
+        }
+    }
+
     /// Get the waste production rate at 100% clock speed (items/min)
-    pub fn base_waste_production(&self) -> f32 {
+    pub fn base_waste_production(&self) -> f64 {
         match self {
             GeneratorType::Nuclear => 0.025, // 1 waste rod every 40 minutes
             _ => 0.0,
@@ -111,12 +159,12 @@ impl GeneratorType {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GeneratorGroup {
     pub number_of_generators: u32,
-    pub clock_speed: f32, // 0.000 to 250.000
+    pub clock_speed: f64, // 0.000 to 250.000
 }
 
 impl GeneratorGroup {
     /// Create a new generator group with validation
-    pub fn new(number_of_generators: u32, clock_speed: f32) -> Result<Self, PowerGeneratorError> {
+    pub fn new(number_of_generators: u32, clock_speed: f64) -> Result<Self, PowerGeneratorError> {
         if number_of_generators == 0 {
             return Err(PowerGeneratorError::InvalidGeneratorCount {
                 count: number_of_generators,
@@ -135,27 +183,38 @@ impl GeneratorGroup {
 
     /// Calculate power generation for this group
     /// Power generation scales linearly with clock speed
-    pub fn power_generation(&self, base_power: f32) -> f32 {
-        base_power * (self.clock_speed / 100.0) * self.number_of_generators as f32
+    pub fn power_generation(&self, base_power: f64) -> f64 {
+        base_power * (self.clock_speed / 100.0) * self.number_of_generators as f64
     }
 
     /// Calculate fuel consumption for this group
     /// Fuel consumption scales linearly with clock speed (constant efficiency)
-    pub fn fuel_consumption(&self, base_consumption: f32, fuel_multiplier: f32) -> f32 {
+    pub fn fuel_consumption(&self, base_consumption: f64, fuel_multiplier: f64) -> f64 {
         base_consumption
             * fuel_multiplier
             * (self.clock_speed / 100.0)
-            * self.number_of_generators as f32
+            * self.number_of_generators as f64
+    }
+
+    /// Calculate water consumption for this group
+    /// Water consumption scales linearly with clock speed
+    pub fn water_consumption(&self, base_water: f64) -> f64 {
+        base_water * (self.clock_speed / 100.0) * self.number_of_generators as f64
     }
 
     /// Calculate waste production for this group (if applicable)
     /// Waste production scales linearly with clock speed
-    pub fn waste_production(&self, base_waste: f32) -> f32 {
-        base_waste * (self.clock_speed / 100.0) * self.number_of_generators as f32
+    pub fn waste_production(&self, base_waste: f64) -> f64 {
+        base_waste * (self.clock_speed / 100.0) * self.number_of_generators as f64
+    }
+
+    /// Power shards slotted across the group to reach its clock speed
+    pub fn power_shards(&self) -> u32 {
+        power_shards_for_clock_speed(self.clock_speed) * self.number_of_generators
     }
 
     /// Set the clock speed of this generator group
-    pub fn set_clock_speed(&mut self, clock_speed: f32) -> Result<(), PowerGeneratorError> {
+    pub fn set_clock_speed(&mut self, clock_speed: f64) -> Result<(), PowerGeneratorError> {
         if !(0.0..=250.0).contains(&clock_speed) {
             return Err(PowerGeneratorError::InvalidClockSpeed { clock_speed });
         }
@@ -173,6 +232,32 @@ impl GeneratorGroup {
     }
 }
 
+/// Minimum, average and maximum power output, in MW
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct GenerationRange {
+    pub min: f64,
+    pub average: f64,
+    pub max: f64,
+}
+
+impl GenerationRange {
+    /// A steady output that does not fluctuate
+    pub fn steady(output: f64) -> Self {
+        Self {
+            min: output,
+            average: output,
+            max: output,
+        }
+    }
+
+    /// Add another range to this one
+    pub fn add(&mut self, other: GenerationRange) {
+        self.min += other.min;
+        self.average += other.average;
+        self.max += other.max;
+    }
+}
+
 /// Represents a power generator system in a factory
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PowerGenerator {
@@ -180,6 +265,15 @@ pub struct PowerGenerator {
     pub generator_type: GeneratorType,
     pub fuel_type: Item,
     pub groups: Vec<GeneratorGroup>,
+    /// Geyser purity, geothermal generators only; `None` means Normal
+    #[serde(default)]
+    pub purity: Option<Purity>,
+    /// Disabled entries stay in the save but are left out of every calculation
+    #[serde(default = "crate::models::enabled_by_default")]
+    pub enabled: bool,
+    /// Fields from a newer save this engine doesn't know, written back as loaded
+    #[serde(flatten)]
+    pub unknown_fields: UnknownFields,
 }
 
 impl PowerGenerator {
@@ -207,9 +301,17 @@ impl PowerGenerator {
             generator_type,
             fuel_type,
             groups: Vec::new(),
+            purity: None,
+            enabled: true,
+            unknown_fields: UnknownFields::new(),
         })
     }
 
+    /// Start building a generator, see [`PowerGeneratorBuilder`]
+    pub fn builder(generator_type: GeneratorType) -> PowerGeneratorBuilder {
+        PowerGeneratorBuilder::new(generator_type)
+    }
+
     /// Create a new geothermal generator (special case that doesn't use fuel)
     pub fn new_geothermal(id: PowerGeneratorId) -> Self {
         Self {
@@ -217,6 +319,30 @@ impl PowerGenerator {
             generator_type: GeneratorType::Geothermal,
             fuel_type: Item::Water, // Placeholder, not actually used
             groups: Vec::new(),
+            purity: None,
+            enabled: true,
+            unknown_fields: UnknownFields::new(),
+        }
+    }
+
+    /// Set the purity of the geyser under a geothermal generator
+    pub fn set_purity(&mut self, purity: Purity) -> Result<(), PowerGeneratorError> {
+        if self.generator_type != GeneratorType::Geothermal {
+            return Err(PowerGeneratorError::PurityRequiresGeothermal {
+                generator: self.generator_type,
+            });
+        }
+        self.purity = Some(purity);
+        Ok(())
+    }
+
+    /// Output multiplier from the geyser purity (1.0 for fuel-burning generators)
+    fn purity_multiplier(&self, balance: &GameBalance) -> f64 {
+        match self.generator_type {
+            GeneratorType::Geothermal => {
+                balance.purity_multiplier(self.purity.unwrap_or(Purity::Normal))
+            }
+            _ => 1.0,
         }
     }
 
@@ -245,16 +371,40 @@ impl PowerGenerator {
     }
 
     /// Calculate total power generation from all groups
-    pub fn total_power_generation(&self) -> f32 {
-        let base_power = self.generator_type.base_power_output();
+    ///
+    /// For geothermal generators this is the average output of the geyser.
+    pub fn total_power_generation(&self) -> f64 {
+        self.total_power_generation_with(GameBalance::vanilla())
+    }
+
+    /// Total power generation with the given game constants
+    pub fn total_power_generation_with(&self, balance: &GameBalance) -> f64 {
+        let base_power =
+            balance.generator_output(self.generator_type) * self.purity_multiplier(balance);
         self.groups
             .iter()
             .map(|group| group.power_generation(base_power))
             .sum()
     }
 
+    /// Calculate the range of power generation, accounting for output fluctuation
+    pub fn power_generation_range(&self) -> GenerationRange {
+        self.power_generation_range_with(GameBalance::vanilla())
+    }
+
+    /// Range of power generation with the given game constants
+    pub fn power_generation_range_with(&self, balance: &GameBalance) -> GenerationRange {
+        let average = self.total_power_generation_with(balance);
+        let swing = average * self.generator_type.output_fluctuation();
+        GenerationRange {
+            min: average - swing,
+            average,
+            max: average + swing,
+        }
+    }
+
     /// Calculate total fuel consumption from all groups
-    pub fn total_fuel_consumption(&self) -> f32 {
+    pub fn total_fuel_consumption(&self) -> f64 {
         if self.generator_type == GeneratorType::Geothermal {
             return 0.0;
         }
@@ -269,13 +419,28 @@ impl PowerGenerator {
             .sum()
     }
 
+    /// Calculate total water consumption from all groups, in m³/min
+    pub fn total_water_consumption(&self) -> f64 {
+        let base_water = self.generator_type.base_water_consumption();
+        self.groups
+            .iter()
+            .map(|group| group.water_consumption(base_water))
+            .sum()
+    }
+
+    /// Power shards slotted across all groups
+    pub fn total_power_shards(&self) -> u32 {
+        self.groups.iter().map(GeneratorGroup::power_shards).sum()
+    }
+
     /// Calculate total waste production from all groups (if applicable)
-    pub fn waste_production_rate(&self) -> f32 {
+    pub fn waste_production_rate(&self) -> f64 {
         if !self.generator_type.produces_waste() {
             return 0.0;
         }
 
-        let base_waste = self.generator_type.base_waste_production();
+        let base_waste = self.generator_type.base_waste_production()
+            * self.generator_type.waste_multiplier(&self.fuel_type);
         self.groups
             .iter()
             .map(|group| group.waste_production(base_waste))
@@ -284,7 +449,7 @@ impl PowerGenerator {
 
     /// Get the waste product type (if any)
     pub fn waste_product(&self) -> Option<Item> {
-        self.generator_type.waste_product()
+        self.generator_type.waste_product_for(&self.fuel_type)
     }
 
     /// Validate that this power generator configuration is correct
@@ -299,6 +464,12 @@ impl PowerGenerator {
             });
         }
 
+        if self.purity.is_some() && self.generator_type != GeneratorType::Geothermal {
+            return Err(PowerGeneratorError::PurityRequiresGeothermal {
+                generator: self.generator_type,
+            });
+        }
+
         // Check that there's at least one group
         if self.groups.is_empty() {
             return Err(PowerGeneratorError::NoGroups);
@@ -322,6 +493,99 @@ impl PowerGenerator {
     }
 }
 
+/// Fluent construction of a [`PowerGenerator`], validated on [`build`](Self::build)
+///
+/// # Example
+///
+/// ```
+/// use satisflow_engine::models::{GeneratorType, Item, PowerGenerator};
+///
+/// let generator = PowerGenerator::builder(GeneratorType::Coal)
+///     .fuel(Item::Coal)
+///     .group(4, 100.0)
+///     .build()
+///     .unwrap();
+/// assert_eq!(generator.total_power_generation(), 300.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PowerGeneratorBuilder {
+    id: Option<PowerGeneratorId>,
+    generator_type: GeneratorType,
+    fuel_type: Option<Item>,
+    groups: Vec<(u32, f64)>,
+    purity: Option<Purity>,
+    enabled: bool,
+}
+
+impl PowerGeneratorBuilder {
+    pub fn new(generator_type: GeneratorType) -> Self {
+        Self {
+            id: None,
+            generator_type,
+            fuel_type: None,
+            groups: Vec::new(),
+            purity: None,
+            enabled: true,
+        }
+    }
+
+    /// Use a fixed ID instead of a random one
+    pub fn id(mut self, id: PowerGeneratorId) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    /// Fuel burned by the generators; required for everything but geothermal
+    pub fn fuel(mut self, fuel_type: Item) -> Self {
+        self.fuel_type = Some(fuel_type);
+        self
+    }
+
+    /// Add a group of generators running at the same clock speed
+    pub fn group(mut self, number_of_generators: u32, clock_speed: f64) -> Self {
+        self.groups.push((number_of_generators, clock_speed));
+        self
+    }
+
+    /// Geyser purity, geothermal generators only
+    pub fn purity(mut self, purity: Purity) -> Self {
+        self.purity = Some(purity);
+        self
+    }
+
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Create the generator
+    ///
+    /// # Returns
+    ///
+    /// The generator, or the first problem found: a missing or incompatible
+    /// fuel, an invalid group, a purity on a fuel-burning generator, or no
+    /// group at all
+    pub fn build(self) -> Result<PowerGenerator, PowerGeneratorError> {
+        let id = self.id.unwrap_or_else(PowerGeneratorId::new_v4);
+        let mut generator = match (self.generator_type, self.fuel_type) {
+            (GeneratorType::Geothermal, None) => PowerGenerator::new_geothermal(id),
+            (generator_type, Some(fuel_type)) => {
+                PowerGenerator::new(id, generator_type, fuel_type)?
+            }
+            (generator, None) => return Err(PowerGeneratorError::MissingFuel { generator }),
+        };
+        if let Some(purity) = self.purity {
+            generator.set_purity(purity)?;
+        }
+        for (number_of_generators, clock_speed) in self.groups {
+            generator.add_group(GeneratorGroup::new(number_of_generators, clock_speed)?)?;
+        }
+        generator.enabled = self.enabled;
+        generator.validate()?;
+        Ok(generator)
+    }
+}
+
 /// Errors that can occur when working with power generators
 #[derive(Debug, Clone, PartialEq)]
 pub enum PowerGeneratorError {
@@ -330,7 +594,7 @@ pub enum PowerGeneratorError {
         fuel: Item,
     },
     InvalidClockSpeed {
-        clock_speed: f32,
+        clock_speed: f64,
     },
     InvalidGeneratorCount {
         count: u32,
@@ -340,6 +604,12 @@ pub enum PowerGeneratorError {
         index: usize,
     },
     GeothermalUsesFuel,
+    PurityRequiresGeothermal {
+        generator: GeneratorType,
+    },
+    MissingFuel {
+        generator: GeneratorType,
+    },
 }
 
 impl fmt::Display for PowerGeneratorError {
@@ -371,6 +641,16 @@ impl fmt::Display for PowerGeneratorError {
             PowerGeneratorError::GeothermalUsesFuel => {
                 write!(f, "Geothermal generators do not use fuel")
             }
+            PowerGeneratorError::PurityRequiresGeothermal { generator } => {
+                write!(
+                    f,
+                    "Generator {:?} has no purity; only geothermal generators do",
+                    generator
+                )
+            }
+            PowerGeneratorError::MissingFuel { generator } => {
+                write!(f, "Generator {:?} needs a fuel type", generator)
+            }
         }
     }
 }
@@ -380,10 +660,57 @@ impl std::error::Error for PowerGeneratorError {}
 /// Global power statistics for the entire Satisflow system
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PowerStats {
-    pub total_generation: f32,
-    pub total_consumption: f32,
-    pub power_balance: f32,
+    pub total_generation: f64,
+    pub total_consumption: f64,
+    pub power_balance: f64,
+    /// Range of total generation while geysers fluctuate
+    #[serde(default)]
+    pub generation_range: GenerationRange,
     pub factory_stats: Vec<FactoryPowerStats>,
+    /// Per-grid balances; the totals above are the cross-grid sums
+    #[serde(default)]
+    pub grid_stats: Vec<GridPowerStats>,
+    /// Combined charge state of every factory's batteries; `None` without batteries
+    #[serde(default)]
+    pub storage: Option<StorageStatus>,
+}
+
+/// Fuel burned by a factory's generators that the factory cannot supply
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FuelShortfall {
+    pub item: Item,
+    /// Fuel burned by the generators, in items (or m³) per minute
+    pub required: f64,
+    /// Fuel left for the generators after production lines and exports
+    pub available: f64,
+    pub shortfall: f64,
+}
+
+impl FuelShortfall {
+    /// Compare required and available fuel; returns `None` when supply is sufficient
+    pub fn check(item: Item, required: f64, available: f64) -> Option<Self> {
+        let available = available.max(0.0);
+        let shortfall = required - available;
+        (balance_sign(shortfall) == Ordering::Greater).then_some(Self {
+            item,
+            required,
+            available,
+            shortfall,
+        })
+    }
+}
+
+impl fmt::Display for FuelShortfall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let format = NumberFormat::default().with_precision(2);
+        write!(
+            f,
+            "Generators burn {} of {} but only {} is available",
+            format.rate(self.required),
+            item_name(self.item),
+            format.rate(self.available)
+        )
+    }
 }
 
 /// Power statistics for a single factory
@@ -391,18 +718,27 @@ pub struct PowerStats {
 pub struct FactoryPowerStats {
     pub factory_id: FactoryId,
     pub factory_name: String,
-    pub generation: f32,
-    pub consumption: f32,
-    pub balance: f32,
+    pub generation: f64,
+    #[serde(default)]
+    pub generation_range: GenerationRange,
+    pub consumption: f64,
+    pub balance: f64,
     pub generator_count: u32,
     pub generator_types: Vec<GeneratorType>,
+    #[serde(default)]
+    pub power_grid: Option<PowerGridId>,
+    #[serde(default)]
+    pub storage: Option<StorageStatus>,
+    /// Generator fuels the factory does not supply itself
+    #[serde(default)]
+    pub fuel_shortfalls: Vec<FuelShortfall>,
 }
 
 impl PowerStats {
     /// Create new power statistics
     pub fn new(
-        total_generation: f32,
-        total_consumption: f32,
+        total_generation: f64,
+        total_consumption: f64,
         factory_stats: Vec<FactoryPowerStats>,
     ) -> Self {
         let power_balance = total_generation - total_consumption;
@@ -410,23 +746,45 @@ impl PowerStats {
             total_generation,
             total_consumption,
             power_balance,
+            generation_range: GenerationRange::steady(total_generation),
             factory_stats,
+            grid_stats: Vec::new(),
+            storage: None,
         }
     }
 
     /// Check if the system has a power surplus
     pub fn has_surplus(&self) -> bool {
-        self.power_balance > 0.0
+        balance_sign(self.power_balance) == Ordering::Greater
     }
 
     /// Check if the system has a power deficit
     pub fn has_deficit(&self) -> bool {
-        self.power_balance < 0.0
+        balance_sign(self.power_balance) == Ordering::Less
+    }
+
+    /// Balance at the lowest point of the generation range
+    pub fn worst_case_balance(&self) -> f64 {
+        self.generation_range.min - self.total_consumption
+    }
+
+    /// One warning per generator fuel a factory does not supply, sorted by factory name
+    pub fn fuel_warnings(&self) -> Vec<String> {
+        let mut factory_stats: Vec<&FactoryPowerStats> = self.factory_stats.iter().collect();
+        factory_stats.sort_by(|a, b| a.factory_name.cmp(&b.factory_name));
+        factory_stats
+            .into_iter()
+            .flat_map(|stat| {
+                stat.fuel_shortfalls
+                    .iter()
+                    .map(move |shortfall| format!("{}: {}", stat.factory_name, shortfall))
+            })
+            .collect()
     }
 
     /// Check if the system is power balanced
     pub fn is_balanced(&self) -> bool {
-        (self.power_balance - 0.0).abs() < f32::EPSILON
+        is_zero(self.power_balance)
     }
 }
 
@@ -435,8 +793,8 @@ impl FactoryPowerStats {
     pub fn new(
         factory_id: FactoryId,
         factory_name: String,
-        generation: f32,
-        consumption: f32,
+        generation: f64,
+        consumption: f64,
         generator_count: u32,
         generator_types: Vec<GeneratorType>,
     ) -> Self {
@@ -445,26 +803,30 @@ impl FactoryPowerStats {
             factory_id,
             factory_name,
             generation,
+            generation_range: GenerationRange::steady(generation),
             consumption,
             balance,
             generator_count,
             generator_types,
+            power_grid: None,
+            storage: None,
+            fuel_shortfalls: Vec::new(),
         }
     }
 
     /// Check if this factory has a power surplus
     pub fn has_surplus(&self) -> bool {
-        self.balance > 0.0
+        balance_sign(self.balance) == Ordering::Greater
     }
 
     /// Check if this factory has a power deficit
     pub fn has_deficit(&self) -> bool {
-        self.balance < 0.0
+        balance_sign(self.balance) == Ordering::Less
     }
 
     /// Check if this factory is power balanced
     pub fn is_balanced(&self) -> bool {
-        (self.balance - 0.0).abs() < f32::EPSILON
+        is_zero(self.balance)
     }
 }
 
@@ -473,8 +835,8 @@ mod tests {
     use super::*;
     use uuid::Uuid;
 
-    fn uuid_from_u64(value: u64) -> Uuid {
-        Uuid::from_u128(value as u128)
+    fn uuid_from_u64<T: From<Uuid>>(value: u64) -> T {
+        Uuid::from_u128(value as u128).into()
     }
 
     // ===== GeneratorType Tests =====
@@ -602,6 +964,51 @@ mod tests {
 
     // ===== GeneratorGroup Tests =====
 
+    #[test]
+    fn test_water_consumption() {
+        assert_eq!(GeneratorType::Coal.base_water_consumption(), 45.0);
+        assert_eq!(GeneratorType::Nuclear.base_water_consumption(), 240.0);
+        assert_eq!(GeneratorType::Fuel.base_water_consumption(), 0.0);
+
+        let mut coal =
+            PowerGenerator::new(uuid_from_u64(1), GeneratorType::Coal, Item::Coal).unwrap();
+        coal.add_group(GeneratorGroup::new(2, 100.0).unwrap())
+            .unwrap();
+        coal.add_group(GeneratorGroup::new(1, 50.0).unwrap())
+            .unwrap();
+        assert_eq!(coal.total_water_consumption(), 112.5);
+
+        let mut nuclear = PowerGenerator::new(
+            uuid_from_u64(2),
+            GeneratorType::Nuclear,
+            Item::UraniumFuelRod,
+        )
+        .unwrap();
+        nuclear
+            .add_group(GeneratorGroup::new(1, 250.0).unwrap())
+            .unwrap();
+        assert_eq!(nuclear.total_water_consumption(), 600.0);
+
+        assert_eq!(
+            PowerGenerator::new_geothermal(uuid_from_u64(3)).total_water_consumption(),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_fuel_shortfall_check() {
+        assert_eq!(FuelShortfall::check(Item::Coal, 30.0, 30.0), None);
+        assert_eq!(FuelShortfall::check(Item::Coal, 30.0, 45.0), None);
+
+        let shortfall = FuelShortfall::check(Item::Coal, 30.0, -10.0).unwrap();
+        assert_eq!(shortfall.available, 0.0);
+        assert_eq!(shortfall.shortfall, 30.0);
+        assert_eq!(
+            shortfall.to_string(),
+            "Generators burn 30.00/min of Coal but only 0.00/min is available"
+        );
+    }
+
     #[test]
     fn test_generator_group_creation() {
         let group = GeneratorGroup::new(5, 150.0).expect("Should create valid group");
@@ -914,6 +1321,56 @@ mod tests {
         assert_eq!(generator.total_fuel_consumption(), 36.0);
     }
 
+    #[test]
+    fn test_geothermal_purity_and_fluctuation() {
+        let mut generator = PowerGenerator::new_geothermal(uuid_from_u64(1));
+        generator
+            .add_group(GeneratorGroup::new(2, 100.0).unwrap())
+            .unwrap();
+
+        // Legacy generators without a purity behave as Normal geysers
+        assert_eq!(
+            generator.power_generation_range(),
+            GenerationRange {
+                min: 200.0,
+                average: 400.0,
+                max: 600.0,
+            }
+        );
+
+        generator.set_purity(Purity::Pure).unwrap();
+        assert_eq!(generator.total_power_generation(), 800.0);
+        assert_eq!(generator.power_generation_range().min, 400.0);
+        assert_eq!(generator.power_generation_range().max, 1200.0);
+
+        generator.set_purity(Purity::Impure).unwrap();
+        assert_eq!(generator.power_generation_range().min, 100.0);
+        assert_eq!(generator.power_generation_range().max, 300.0);
+    }
+
+    #[test]
+    fn test_purity_is_geothermal_only() {
+        let mut generator =
+            PowerGenerator::new(uuid_from_u64(1), GeneratorType::Coal, Item::Coal).unwrap();
+        generator
+            .add_group(GeneratorGroup::new(1, 100.0).unwrap())
+            .unwrap();
+
+        assert_eq!(
+            generator.set_purity(Purity::Pure),
+            Err(PowerGeneratorError::PurityRequiresGeothermal {
+                generator: GeneratorType::Coal
+            })
+        );
+        assert_eq!(
+            generator.power_generation_range(),
+            GenerationRange::steady(75.0)
+        );
+
+        generator.purity = Some(Purity::Pure);
+        assert!(generator.validate().is_err());
+    }
+
     #[test]
     fn test_geothermal_no_fuel_consumption() {
         let mut generator = PowerGenerator::new_geothermal(uuid_from_u64(1));
@@ -941,6 +1398,24 @@ mod tests {
         assert_eq!(generator.waste_product(), Some(Item::UraniumWaste));
     }
 
+    #[test]
+    fn test_plutonium_fuel_rods() {
+        let mut generator = PowerGenerator::new(
+            uuid_from_u64(1),
+            GeneratorType::Nuclear,
+            Item::PlutoniumFuelRod,
+        )
+        .expect("Nuclear plants should burn plutonium rods");
+
+        let group = GeneratorGroup::new(2, 100.0).expect("Should create valid group");
+        generator.add_group(group).expect("Should add group");
+
+        assert_eq!(generator.total_power_generation(), 5000.0);
+        assert_eq!(generator.total_fuel_consumption(), 0.025);
+        assert_eq!(generator.waste_product(), Some(Item::PlutoniumWaste));
+        assert!((generator.waste_production_rate() - 0.005).abs() < 1e-6);
+    }
+
     #[test]
     fn test_nuclear_waste_production_with_overclock() {
         let mut generator = PowerGenerator::new(
@@ -1130,4 +1605,52 @@ mod tests {
         assert_eq!(generator.total_power_generation(), base_power * 0.5);
         assert_eq!(generator.total_fuel_consumption(), base_fuel * 0.5);
     }
+
+    #[test]
+    fn test_builder_validates_on_build() {
+        let generator = PowerGenerator::builder(GeneratorType::Fuel)
+            .id(uuid_from_u64(7))
+            .fuel(Item::Fuel)
+            .group(2, 100.0)
+            .group(1, 50.0)
+            .enabled(false)
+            .build()
+            .expect("Should build valid generator");
+        assert_eq!(generator.id, uuid_from_u64(7));
+        assert_eq!(generator.groups.len(), 2);
+        assert!(!generator.enabled);
+        assert_eq!(generator.total_power_generation(), 375.0);
+
+        let geothermal = PowerGenerator::builder(GeneratorType::Geothermal)
+            .purity(Purity::Pure)
+            .group(1, 100.0)
+            .build()
+            .expect("Should build valid geothermal generator");
+        assert_eq!(geothermal.purity, Some(Purity::Pure));
+
+        let missing_fuel = PowerGenerator::builder(GeneratorType::Coal).group(1, 100.0);
+        assert_eq!(
+            missing_fuel.build(),
+            Err(PowerGeneratorError::MissingFuel {
+                generator: GeneratorType::Coal
+            })
+        );
+        let no_groups = PowerGenerator::builder(GeneratorType::Coal).fuel(Item::Coal);
+        assert_eq!(no_groups.build(), Err(PowerGeneratorError::NoGroups));
+        let bad_clock = PowerGenerator::builder(GeneratorType::Coal)
+            .fuel(Item::Coal)
+            .group(1, 300.0);
+        assert!(matches!(
+            bad_clock.build(),
+            Err(PowerGeneratorError::InvalidClockSpeed { .. })
+        ));
+        let purity_on_coal = PowerGenerator::builder(GeneratorType::Coal)
+            .fuel(Item::Coal)
+            .purity(Purity::Pure)
+            .group(1, 100.0);
+        assert!(matches!(
+            purity_on_coal.build(),
+            Err(PowerGeneratorError::PurityRequiresGeothermal { .. })
+        ));
+    }
 }
@@ -0,0 +1,84 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{FactoryId, PowerGridId, UnknownFields};
+use crate::rate::balance_sign;
+
+/// An independent electrical network; factories assigned to it share its power
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PowerGrid {
+    pub id: PowerGridId,
+    pub name: String,
+    pub description: Option<String>,
+    /// Fields from a newer save this engine doesn't know, written back as loaded
+    #[serde(flatten)]
+    pub unknown_fields: UnknownFields,
+}
+
+impl PowerGrid {
+    pub fn new(id: PowerGridId, name: String, description: Option<String>) -> Self {
+        Self {
+            id,
+            name,
+            description,
+            unknown_fields: UnknownFields::new(),
+        }
+    }
+}
+
+/// Power balance of one grid
+///
+/// Factories without a grid are reported together with `grid_id: None`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GridPowerStats {
+    pub grid_id: Option<PowerGridId>,
+    pub grid_name: String,
+    pub generation: f64,
+    pub consumption: f64,
+    pub balance: f64,
+    pub factory_ids: Vec<FactoryId>,
+}
+
+impl GridPowerStats {
+    pub fn new(grid_id: Option<PowerGridId>, grid_name: String) -> Self {
+        Self {
+            grid_id,
+            grid_name,
+            generation: 0.0,
+            consumption: 0.0,
+            balance: 0.0,
+            factory_ids: Vec::new(),
+        }
+    }
+
+    /// Add a factory's generation and consumption to the grid totals
+    pub fn add_factory(&mut self, factory_id: FactoryId, generation: f64, consumption: f64) {
+        self.factory_ids.push(factory_id);
+        self.generation += generation;
+        self.consumption += consumption;
+        self.balance = self.generation - self.consumption;
+    }
+
+    /// Check if the grid has a power deficit
+    pub fn has_deficit(&self) -> bool {
+        balance_sign(self.balance) == Ordering::Less
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_rounding_noise_is_not_a_deficit() {
+        let mut stats = GridPowerStats::new(None, "Main".into());
+        stats.add_factory(Uuid::from_u128(1).into(), 0.1 + 0.2, 0.3);
+        assert_ne!(stats.balance, 0.0);
+        assert!(!stats.has_deficit());
+
+        stats.add_factory(Uuid::from_u128(2).into(), 0.0, 1.0);
+        assert!(stats.has_deficit());
+    }
+}
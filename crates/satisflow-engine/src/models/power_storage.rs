@@ -0,0 +1,264 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::rate::balance_sign;
+
+/// Energy held by a single Power Storage building
+pub const POWER_STORAGE_CAPACITY_MWH: f64 = 100.0;
+/// Maximum rate at which a single Power Storage building charges
+pub const POWER_STORAGE_CHARGE_RATE_MW: f64 = 100.0;
+
+/// A bank of identical batteries (Power Storage buildings) in a factory
+///
+/// Batteries only charge from surplus power, up to their combined charge rate,
+/// and discharge to cover deficits.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PowerStorage {
+    pub battery_count: u32,
+    /// Maximum charge rate of one battery, in MW
+    pub charge_rate: f64,
+    /// Capacity of one battery, in MWh
+    pub capacity: f64,
+    /// Energy currently stored across all batteries, in MWh
+    pub stored_mwh: f64,
+}
+
+impl PowerStorage {
+    /// Create an empty bank of standard Power Storage buildings
+    pub fn new(battery_count: u32) -> Self {
+        Self {
+            battery_count,
+            charge_rate: POWER_STORAGE_CHARGE_RATE_MW,
+            capacity: POWER_STORAGE_CAPACITY_MWH,
+            stored_mwh: 0.0,
+        }
+    }
+
+    /// Set the energy currently stored
+    pub fn with_stored(mut self, stored_mwh: f64) -> Self {
+        self.stored_mwh = stored_mwh;
+        self
+    }
+
+    /// One bank holding the batteries and stored energy of both
+    ///
+    /// Batteries of different sizes are averaged so the combined capacity and
+    /// charge rate stay the sums of both banks.
+    pub fn combine(&self, other: &PowerStorage) -> Self {
+        let battery_count = self.battery_count + other.battery_count;
+        let per_battery = |total: f64| match battery_count {
+            0 => 0.0,
+            count => total / count as f64,
+        };
+        Self {
+            battery_count,
+            charge_rate: per_battery(self.max_charge_rate() + other.max_charge_rate()),
+            capacity: per_battery(self.total_capacity() + other.total_capacity()),
+            stored_mwh: self.stored_mwh + other.stored_mwh,
+        }
+    }
+
+    /// Combined capacity of all batteries, in MWh
+    pub fn total_capacity(&self) -> f64 {
+        self.capacity * self.battery_count as f64
+    }
+
+    /// Combined maximum charge rate of all batteries, in MW
+    pub fn max_charge_rate(&self) -> f64 {
+        self.charge_rate * self.battery_count as f64
+    }
+
+    /// Check if the batteries cannot store more energy
+    pub fn is_full(&self) -> bool {
+        self.stored_mwh >= self.total_capacity()
+    }
+
+    /// Power drawn to charge the batteries from the given surplus, in MW
+    pub fn charging_draw(&self, surplus: f64) -> f64 {
+        if self.is_full() {
+            return 0.0;
+        }
+        surplus.clamp(0.0, self.max_charge_rate())
+    }
+
+    /// Validate the storage configuration
+    pub fn validate(&self) -> Result<(), PowerStorageError> {
+        if self.battery_count == 0 {
+            return Err(PowerStorageError::InvalidBatteryCount);
+        }
+        if !self.charge_rate.is_finite() || self.charge_rate <= 0.0 {
+            return Err(PowerStorageError::InvalidChargeRate {
+                charge_rate: self.charge_rate,
+            });
+        }
+        if !self.capacity.is_finite() || self.capacity <= 0.0 {
+            return Err(PowerStorageError::InvalidCapacity {
+                capacity: self.capacity,
+            });
+        }
+        if !self.stored_mwh.is_finite()
+            || self.stored_mwh < 0.0
+            || self.stored_mwh > self.total_capacity()
+        {
+            return Err(PowerStorageError::InvalidStoredEnergy {
+                stored_mwh: self.stored_mwh,
+                capacity: self.total_capacity(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Errors raised by invalid power storage configurations
+#[derive(Debug, Clone, PartialEq)]
+pub enum PowerStorageError {
+    InvalidBatteryCount,
+    InvalidChargeRate { charge_rate: f64 },
+    InvalidCapacity { capacity: f64 },
+    InvalidStoredEnergy { stored_mwh: f64, capacity: f64 },
+}
+
+impl fmt::Display for PowerStorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PowerStorageError::InvalidBatteryCount => {
+                write!(f, "Power storage must have at least one battery")
+            }
+            PowerStorageError::InvalidChargeRate { charge_rate } => {
+                write!(
+                    f,
+                    "Charge rate {} MW is invalid. Must be greater than 0",
+                    charge_rate
+                )
+            }
+            PowerStorageError::InvalidCapacity { capacity } => {
+                write!(
+                    f,
+                    "Capacity {} MWh is invalid. Must be greater than 0",
+                    capacity
+                )
+            }
+            PowerStorageError::InvalidStoredEnergy {
+                stored_mwh,
+                capacity,
+            } => {
+                write!(
+                    f,
+                    "Stored energy {} MWh is invalid. Must be between 0 and {} MWh",
+                    stored_mwh, capacity
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for PowerStorageError {}
+
+/// Charge state of one or more battery banks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StorageStatus {
+    pub battery_count: u32,
+    pub stored_mwh: f64,
+    pub capacity_mwh: f64,
+    /// Power currently drawn to charge the batteries, in MW
+    pub charging_draw: f64,
+    /// Hours until the batteries are empty; only set during a deficit
+    pub hours_until_depleted: Option<f64>,
+    /// Hours until the batteries are full; only set while charging
+    pub hours_until_full: Option<f64>,
+}
+
+impl StorageStatus {
+    /// Compute the status of batteries given the net balance after charging
+    ///
+    /// # Arguments
+    ///
+    /// * `stored_mwh` / `capacity_mwh` - Energy stored and total capacity
+    /// * `charging_draw` - Power drawn to charge the batteries, in MW
+    /// * `balance` - Generation minus consumption, charging draw included
+    pub fn new(
+        battery_count: u32,
+        stored_mwh: f64,
+        capacity_mwh: f64,
+        charging_draw: f64,
+        balance: f64,
+    ) -> Self {
+        let hours_until_depleted =
+            (balance_sign(balance) == Ordering::Less).then(|| stored_mwh / -balance);
+        let hours_until_full = (balance_sign(charging_draw) == Ordering::Greater)
+            .then(|| (capacity_mwh - stored_mwh).max(0.0) / charging_draw);
+        Self {
+            battery_count,
+            stored_mwh,
+            capacity_mwh,
+            charging_draw,
+            hours_until_depleted,
+            hours_until_full,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_charging_draw_is_capped() {
+        let storage = PowerStorage::new(2).with_stored(50.0);
+
+        assert_eq!(storage.total_capacity(), 200.0);
+        assert_eq!(storage.charging_draw(150.0), 150.0);
+        assert_eq!(storage.charging_draw(500.0), 200.0);
+        assert_eq!(storage.charging_draw(-30.0), 0.0);
+        assert_eq!(storage.with_stored(200.0).charging_draw(150.0), 0.0);
+    }
+
+    #[test]
+    fn test_combine_keeps_totals() {
+        let mut large = PowerStorage::new(1).with_stored(100.0);
+        large.capacity = 300.0;
+        let combined = PowerStorage::new(2).with_stored(50.0).combine(&large);
+
+        assert_eq!(combined.battery_count, 3);
+        assert_eq!(combined.total_capacity(), 500.0);
+        assert_eq!(combined.max_charge_rate(), 300.0);
+        assert_eq!(combined.stored_mwh, 150.0);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(PowerStorage::new(1).validate().is_ok());
+        assert_eq!(
+            PowerStorage::new(0).validate(),
+            Err(PowerStorageError::InvalidBatteryCount)
+        );
+        assert!(matches!(
+            PowerStorage::new(1).with_stored(150.0).validate(),
+            Err(PowerStorageError::InvalidStoredEnergy { .. })
+        ));
+
+        let mut storage = PowerStorage::new(1);
+        storage.charge_rate = 0.0;
+        assert!(matches!(
+            storage.validate(),
+            Err(PowerStorageError::InvalidChargeRate { .. })
+        ));
+    }
+
+    #[test]
+    fn test_storage_status_times() {
+        let charging = StorageStatus::new(2, 50.0, 200.0, 75.0, 0.0);
+        assert_eq!(charging.hours_until_full, Some(2.0));
+        assert_eq!(charging.hours_until_depleted, None);
+
+        let draining = StorageStatus::new(2, 50.0, 200.0, 0.0, -100.0);
+        assert_eq!(draining.hours_until_depleted, Some(0.5));
+        assert_eq!(draining.hours_until_full, None);
+
+        // Rounding noise neither drains nor charges the batteries
+        let balanced = StorageStatus::new(2, 50.0, 200.0, 1e-12, -1e-12);
+        assert_eq!(balanced.hours_until_depleted, None);
+        assert_eq!(balanced.hours_until_full, None);
+    }
+}
@@ -2,7 +2,12 @@ use std::fmt::Debug;
 
 use serde::{Deserialize, Serialize};
 
-use crate::models::{recipe_info, Item, ProductionLineId, Recipe};
+use crate::models::{
+    custom_recipe::LineRecipe,
+    game_balance::GameBalance,
+    game_data::{power_shards_for_clock_speed, MachineType},
+    Item, ProductionLineId, Recipe, TemplateRevision, UnknownFields,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ProductionLine {
@@ -24,6 +29,36 @@ impl ProductionLine {
         }
     }
 
+    /// Check if the line counts towards factory totals
+    pub fn is_enabled(&self) -> bool {
+        match self {
+            ProductionLine::ProductionLineRecipe(line) => line.enabled,
+            ProductionLine::ProductionLineBlueprint(blueprint) => blueprint.enabled,
+        }
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        match self {
+            ProductionLine::ProductionLineRecipe(line) => line.enabled = enabled,
+            ProductionLine::ProductionLineBlueprint(blueprint) => blueprint.enabled = enabled,
+        }
+    }
+
+    /// A copy of the line with new IDs, including those of a blueprint's lines
+    pub fn with_new_ids(&self) -> ProductionLine {
+        let mut line = self.clone();
+        match &mut line {
+            ProductionLine::ProductionLineRecipe(recipe) => recipe.id = ProductionLineId::new_v4(),
+            ProductionLine::ProductionLineBlueprint(blueprint) => {
+                blueprint.id = ProductionLineId::new_v4();
+                for inner in &mut blueprint.production_lines {
+                    inner.id = ProductionLineId::new_v4();
+                }
+            }
+        }
+        line
+    }
+
     pub fn total_machines(&self) -> u32 {
         match self {
             ProductionLine::ProductionLineRecipe(line) => line.total_machines(),
@@ -38,21 +73,56 @@ impl ProductionLine {
         }
     }
 
-    pub fn output_rate(&self) -> Vec<(Item, f32)> {
+    /// Power shards slotted to reach the overclock of every machine
+    pub fn total_power_shards(&self) -> u32 {
+        match self {
+            ProductionLine::ProductionLineRecipe(line) => line.total_power_shards(),
+            ProductionLine::ProductionLineBlueprint(blueprint) => blueprint.total_power_shards(),
+        }
+    }
+
+    pub fn output_rate(&self) -> Vec<(Item, f64)> {
         match self {
             ProductionLine::ProductionLineRecipe(line) => line.output_rate(),
             ProductionLine::ProductionLineBlueprint(blueprint) => blueprint.output_rate(),
         }
     }
 
-    pub fn input_rate(&self) -> Vec<(Item, f32)> {
+    /// Part of `output_rate` made as a side effect of each recipe
+    pub fn byproduct_rate(&self) -> Vec<(Item, f64)> {
+        match self {
+            ProductionLine::ProductionLineRecipe(line) => line.byproduct_rate(),
+            ProductionLine::ProductionLineBlueprint(blueprint) => blueprint.byproduct_rate(),
+        }
+    }
+
+    /// How much somersloops multiply the line's output, 1.0 when it has none
+    pub fn output_amplification(&self) -> f64 {
+        match self {
+            ProductionLine::ProductionLineRecipe(line) => line.output_amplification(),
+            ProductionLine::ProductionLineBlueprint(blueprint) => blueprint.output_amplification(),
+        }
+    }
+
+    pub fn input_rate(&self) -> Vec<(Item, f64)> {
         match self {
             ProductionLine::ProductionLineRecipe(line) => line.input_rate(),
             ProductionLine::ProductionLineBlueprint(blueprint) => blueprint.input_rate(),
         }
     }
 
-    pub fn total_power_consumption(&self) -> f32 {
+    /// Whether the line, or one of a blueprint's inner lines, runs a recipe
+    pub fn uses_recipe(&self, recipe: Recipe) -> bool {
+        let runs = |line: &ProductionLineRecipe| line.recipe == LineRecipe::Standard(recipe);
+        match self {
+            ProductionLine::ProductionLineRecipe(line) => runs(line),
+            ProductionLine::ProductionLineBlueprint(blueprint) => {
+                blueprint.production_lines.iter().any(runs)
+            }
+        }
+    }
+
+    pub fn total_power_consumption(&self) -> f64 {
         match self {
             ProductionLine::ProductionLineRecipe(line) => line.total_power_consumption(),
             ProductionLine::ProductionLineBlueprint(blueprint) => {
@@ -60,6 +130,55 @@ impl ProductionLine {
             }
         }
     }
+
+    /// Average power draw with the given game constants
+    pub fn total_power_consumption_with(&self, balance: &GameBalance) -> f64 {
+        self.consumption_range_with(balance).average
+    }
+
+    /// Lowest, average and highest power draw of the line's machines
+    pub fn consumption_range(&self) -> ConsumptionRange {
+        self.consumption_range_with(GameBalance::vanilla())
+    }
+
+    /// Lowest, average and highest power draw with the given game constants
+    pub fn consumption_range_with(&self, balance: &GameBalance) -> ConsumptionRange {
+        match self {
+            ProductionLine::ProductionLineRecipe(line) => line.consumption_range_with(balance),
+            ProductionLine::ProductionLineBlueprint(blueprint) => {
+                blueprint.consumption_range_with(balance)
+            }
+        }
+    }
+}
+
+/// Minimum, average and maximum power draw, in MW
+///
+/// Particle Accelerators, Quantum Encoders and Converters cycle their draw
+/// during each craft; totals use the average.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConsumptionRange {
+    pub min: f64,
+    pub average: f64,
+    pub max: f64,
+}
+
+impl ConsumptionRange {
+    /// A steady draw that does not fluctuate
+    pub fn steady(draw: f64) -> Self {
+        Self {
+            min: draw,
+            average: draw,
+            max: draw,
+        }
+    }
+
+    /// Add another range to this one
+    pub fn add(&mut self, other: ConsumptionRange) {
+        self.min += other.min;
+        self.average += other.average;
+        self.max += other.max;
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,8 +186,14 @@ pub struct ProductionLineRecipe {
     pub id: ProductionLineId,
     pub name: String,
     pub description: Option<String>,
-    pub recipe: Recipe,
+    pub recipe: LineRecipe,
     pub machine_groups: Vec<MachineGroup>,
+    /// Disabled entries stay in the save but are left out of every calculation
+    #[serde(default = "crate::models::enabled_by_default")]
+    pub enabled: bool,
+    /// Fields from a newer save this engine doesn't know, written back as loaded
+    #[serde(flatten)]
+    pub unknown_fields: UnknownFields,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -77,12 +202,50 @@ pub struct ProductionLineBlueprint {
     pub name: String,
     pub description: Option<String>,
     pub production_lines: Vec<ProductionLineRecipe>,
+    /// Disabled entries stay in the save but are left out of every calculation
+    #[serde(default = "crate::models::enabled_by_default")]
+    pub enabled: bool,
+    /// Version history of library templates; set on every template in the library
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revision: Option<TemplateRevision>,
+    /// Library template an instance was created from or last updated to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template_id: Option<ProductionLineId>,
+    /// Fields from a newer save this engine doesn't know, written back as loaded
+    #[serde(flatten)]
+    pub unknown_fields: UnknownFields,
+}
+
+/// Smallest clock speed step the game accepts, in percent
+const CLOCK_SPEED_STEP: f64 = 0.0001;
+
+/// Clock speed needed for a machine count to hit a target output rate
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClockOptimization {
+    /// Primary output of the recipe, which the target rate applies to
+    pub item: Item,
+    pub target_rate: f64,
+    pub number_of_machine: u32,
+    /// Per-machine clock speed, rounded up to the game's 0.0001% step
+    pub clock_speed: f64,
+    pub power_shards_per_machine: u32,
+    pub total_power_shards: u32,
+    /// Output rate reached at the rounded clock speed
+    pub actual_rate: f64,
+    pub power_consumption: f64,
+}
+
+impl ClockOptimization {
+    /// Machine group running the suggested configuration, without somersloops
+    pub fn machine_group(&self) -> MachineGroup {
+        MachineGroup::new(self.number_of_machine, self.clock_speed, 0)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MachineGroup {
     pub number_of_machine: u32, // number of machine in the groupe
-    pub oc_value: f32,          // overclock value
+    pub oc_value: f64,          // overclock value
     pub somersloop: u8,         // number of somersloop per machine
 }
 
@@ -92,14 +255,16 @@ impl ProductionLineRecipe {
         id: ProductionLineId,
         name: String,
         description: Option<String>,
-        recipe: Recipe,
+        recipe: impl Into<LineRecipe>,
     ) -> Self {
         Self {
             id,
             name,
             description,
-            recipe,
+            recipe: recipe.into(),
             machine_groups: Vec::new(),
+            enabled: true,
+            unknown_fields: UnknownFields::new(),
         }
     }
 
@@ -109,8 +274,8 @@ impl ProductionLineRecipe {
         &mut self,
         group: MachineGroup,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        if group.somersloop > recipe_info(self.recipe).machine.max_somersloop() {
-            return Err(format!("Cannot add machine group with more somersloop than the machine type allows {} > {}", group.somersloop, recipe_info(self.recipe).machine.max_somersloop()).into());
+        if group.somersloop > self.recipe.machine().max_somersloop() {
+            return Err(format!("Cannot add machine group with more somersloop than the machine type allows {} > {}", group.somersloop, self.recipe.machine().max_somersloop()).into());
         }
         if group.oc_value < 0.0 || group.oc_value > 250.0 {
             return Err("Overclock value must be between 0.000 and 250.000".into());
@@ -119,6 +284,64 @@ impl ProductionLineRecipe {
         Ok(())
     }
 
+    /// Compute the clock speed hitting a target rate with a given machine count
+    ///
+    /// # Arguments
+    ///
+    /// * `target_rate` - Wanted output of the recipe's primary product, in items/min
+    /// * `machine_count` - Number of machines sharing the load, without somersloops
+    ///
+    /// # Returns
+    ///
+    /// Result containing the suggested configuration, or an error if the target
+    /// cannot be reached within the 250% clock limit
+    pub fn optimize_clock_for(
+        &self,
+        target_rate: f64,
+        machine_count: u32,
+    ) -> Result<ClockOptimization, Box<dyn std::error::Error>> {
+        if target_rate <= 0.0 {
+            return Err("Target rate must be greater than 0".into());
+        }
+        if machine_count == 0 {
+            return Err("Machine count must be greater than 0".into());
+        }
+        let (item, base_rate) = *self
+            .recipe
+            .outputs()
+            .first()
+            .ok_or("Recipe has no output")?;
+
+        let exact_clock = target_rate / (base_rate * machine_count as f64) * 100.0;
+        // Round up so the line never falls short; the epsilon absorbs float noise
+        let steps = (exact_clock / CLOCK_SPEED_STEP - 1e-3).ceil();
+        let clock_speed = steps * CLOCK_SPEED_STEP;
+        if clock_speed > 250.0 {
+            return Err(format!(
+                "{} machines cannot reach {}/min, even at 250% (needs {:.4}%)",
+                machine_count, target_rate, clock_speed
+            )
+            .into());
+        }
+
+        let mut line =
+            ProductionLineRecipe::new(self.id, self.name.clone(), None, self.recipe.clone());
+        line.machine_groups
+            .push(MachineGroup::new(machine_count, clock_speed, 0));
+        let power_shards_per_machine = power_shards_for_clock_speed(clock_speed);
+
+        Ok(ClockOptimization {
+            item,
+            target_rate,
+            number_of_machine: machine_count,
+            clock_speed,
+            power_shards_per_machine,
+            total_power_shards: power_shards_per_machine * machine_count,
+            actual_rate: base_rate * clock_speed / 100.0 * machine_count as f64,
+            power_consumption: line.total_power_consumption(),
+        })
+    }
+
     fn id(&self) -> ProductionLineId {
         self.id
     }
@@ -141,55 +364,115 @@ impl ProductionLineRecipe {
             .sum()
     }
 
-    fn output_rate(&self) -> Vec<(Item, f32)> {
-        let recipe_info = recipe_info(self.recipe);
+    fn total_power_shards(&self) -> u32 {
+        self.machine_groups
+            .iter()
+            .map(|group| group.number_of_machine * group.power_shards())
+            .sum()
+    }
+
+    /// One entry per recipe output, byproducts included, summed over the machine groups
+    fn output_rate(&self) -> Vec<(Item, f64)> {
+        if self.machine_groups.is_empty() {
+            return Vec::new();
+        }
+        let machine = self.recipe.machine();
         let mut result = vec![];
-        for (item, rate) in recipe_info.outputs.iter() {
-            for group in &self.machine_groups {
-                let machine_output =
-                    rate * (group.oc_value / 100.0) * group.number_of_machine as f32;
-                if group.somersloop > 0 {
-                    // Somersloop multiply the production rate depending on the number of somersloop and the machine type
-                    let somersloop_multiplier = 1.0
-                        + (group.somersloop as f32 / recipe_info.machine.max_somersloop() as f32);
-                    result.push((*item, machine_output * somersloop_multiplier));
-                } else {
-                    result.push((*item, machine_output));
-                }
-            }
+        for (item, rate) in self.recipe.outputs().iter() {
+            // Byproducts are amplified the same way as the main product
+            let total = self
+                .machine_groups
+                .iter()
+                .map(|group| {
+                    rate * (group.oc_value / 100.0)
+                        * group.number_of_machine as f64
+                        * group.amplification(machine)
+                })
+                .sum();
+            result.push((*item, total));
         }
         result
     }
 
-    fn input_rate(&self) -> Vec<(Item, f32)> {
-        let recipe_info = recipe_info(self.recipe);
+    /// Output compared to the same machines without somersloops, 1.0 to 2.0
+    fn output_amplification(&self) -> f64 {
+        let machine = self.recipe.machine();
+        let (base, amplified) =
+            self.machine_groups
+                .iter()
+                .fold((0.0, 0.0), |(base, amplified), group| {
+                    let cycles = group.oc_value / 100.0 * group.number_of_machine as f64;
+                    (
+                        base + cycles,
+                        amplified + cycles * group.amplification(machine),
+                    )
+                });
+        if base > 0.0 {
+            amplified / base
+        } else {
+            1.0
+        }
+    }
+
+    /// Outputs other than the recipe's main product, e.g. Heavy Oil Residue from Plastic
+    fn byproduct_rate(&self) -> Vec<(Item, f64)> {
+        let primary = self.recipe.primary_output();
+        self.output_rate()
+            .into_iter()
+            .filter(|(item, _)| Some(*item) != primary)
+            .collect()
+    }
+
+    fn input_rate(&self) -> Vec<(Item, f64)> {
+        if self.machine_groups.is_empty() {
+            return Vec::new();
+        }
         let mut result = vec![];
-        for (item, rate) in recipe_info.inputs.iter() {
-            for group in &self.machine_groups {
-                let machine_input =
-                    rate * (group.oc_value / 100.0) * group.number_of_machine as f32;
-                result.push((*item, machine_input));
-            }
+        for (item, rate) in self.recipe.inputs().iter() {
+            let total = self
+                .machine_groups
+                .iter()
+                .map(|group| rate * (group.oc_value / 100.0) * group.number_of_machine as f64)
+                .sum();
+            result.push((*item, total));
         }
         result
     }
 
+    fn total_power_consumption(&self) -> f64 {
+        self.consumption_range().average
+    }
+
+    fn consumption_range(&self) -> ConsumptionRange {
+        self.consumption_range_with(GameBalance::vanilla())
+    }
+
     /// Power multiplier = (1 + somersloop / max_somersloop)²
-    /// Power usage = Base power usage × Power multiplier × (Clock speed100)^1.321928
-    fn total_power_consumption(&self) -> f32 {
-        let recipe_info = recipe_info(self.recipe);
-        let base_power = recipe_info.machine.base_power_mw();
-        let mut total_power = 0.0;
+    /// Power usage = Base power usage × Power multiplier × (Clock speed/100)^overclock_exponent
+    ///
+    /// Machines with a varying draw use the average of their recipe's range as
+    /// base power.
+    fn consumption_range_with(&self, balance: &GameBalance) -> ConsumptionRange {
+        let machine = self.recipe.machine();
+        let base_power = match self.recipe.power_range_mw() {
+            Some((min, max)) => ConsumptionRange {
+                min,
+                average: (min + max) / 2.0,
+                max,
+            },
+            None => ConsumptionRange::steady(machine.base_power_mw()),
+        };
+        let mut total_power = ConsumptionRange::default();
         for group in &self.machine_groups {
-            let somersloop_multiplier = if group.somersloop > 0 {
-                1.0 + (group.somersloop as f32 / recipe_info.machine.max_somersloop() as f32)
-            } else {
-                1.0
-            };
-            let power_multiplier = somersloop_multiplier * somersloop_multiplier;
-            let machine_power =
-                base_power * power_multiplier * (group.oc_value / 100.0).powf(1.321928);
-            total_power += machine_power * group.number_of_machine as f32;
+            let power_multiplier = group.amplification(machine).powi(2);
+            let scale = power_multiplier
+                * balance.clock_power_factor(group.oc_value)
+                * group.number_of_machine as f64;
+            total_power.add(ConsumptionRange {
+                min: base_power.min * scale,
+                average: base_power.average * scale,
+                max: base_power.max * scale,
+            });
         }
         total_power
     }
@@ -202,6 +485,10 @@ impl ProductionLineBlueprint {
             name,
             description,
             production_lines: Vec::new(),
+            enabled: true,
+            revision: None,
+            template_id: None,
+            unknown_fields: UnknownFields::new(),
         }
     }
 
@@ -209,6 +496,32 @@ impl ProductionLineBlueprint {
         self.production_lines.push(line);
     }
 
+    /// Multiply the size of every inner line
+    ///
+    /// Whole machines keep their clock speed; the fractional remainder of each
+    /// group becomes one extra machine underclocked to match.
+    ///
+    /// # Arguments
+    ///
+    /// * `factor` - Scale to apply, e.g. `2.5` for two and a half copies
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success, or an error if the factor is not positive
+    pub fn scale(&mut self, factor: f64) -> Result<(), Box<dyn std::error::Error>> {
+        if !factor.is_finite() || factor <= 0.0 {
+            return Err(format!("Scale must be a positive number, got {}", factor).into());
+        }
+        for line in &mut self.production_lines {
+            line.machine_groups = line
+                .machine_groups
+                .iter()
+                .flat_map(|group| group.scaled(factor))
+                .collect();
+        }
+        Ok(())
+    }
+
     fn id(&self) -> ProductionLineId {
         self.id
     }
@@ -231,7 +544,14 @@ impl ProductionLineBlueprint {
             .sum()
     }
 
-    fn output_rate(&self) -> Vec<(Item, f32)> {
+    fn total_power_shards(&self) -> u32 {
+        self.production_lines
+            .iter()
+            .map(|line| line.total_power_shards())
+            .sum()
+    }
+
+    fn output_rate(&self) -> Vec<(Item, f64)> {
         let mut result = vec![];
         for line in &self.production_lines {
             for (item, rate) in line.output_rate() {
@@ -245,7 +565,21 @@ impl ProductionLineBlueprint {
         result
     }
 
-    fn input_rate(&self) -> Vec<(Item, f32)> {
+    fn byproduct_rate(&self) -> Vec<(Item, f64)> {
+        let mut result = vec![];
+        for line in &self.production_lines {
+            for (item, rate) in line.byproduct_rate() {
+                if let Some(existing) = result.iter_mut().find(|(i, _)| *i == item) {
+                    existing.1 += rate;
+                } else {
+                    result.push((item, rate));
+                }
+            }
+        }
+        result
+    }
+
+    fn input_rate(&self) -> Vec<(Item, f64)> {
         let mut result = vec![];
         for line in &self.production_lines {
             for (item, rate) in line.input_rate() {
@@ -259,22 +593,243 @@ impl ProductionLineBlueprint {
         result
     }
 
-    fn total_power_consumption(&self) -> f32 {
+    /// Weighted over every inner line by its unamplified output
+    fn output_amplification(&self) -> f64 {
+        let (base, amplified) =
+            self.production_lines
+                .iter()
+                .fold((0.0, 0.0), |(base, amplified), line| {
+                    let cycles: f64 = line
+                        .machine_groups
+                        .iter()
+                        .map(|group| group.oc_value / 100.0 * group.number_of_machine as f64)
+                        .sum();
+                    (
+                        base + cycles,
+                        amplified + cycles * line.output_amplification(),
+                    )
+                });
+        if base > 0.0 {
+            amplified / base
+        } else {
+            1.0
+        }
+    }
+
+    fn total_power_consumption(&self) -> f64 {
         self.production_lines
             .iter()
             .map(|line| line.total_power_consumption())
             .sum()
     }
+
+    fn consumption_range_with(&self, balance: &GameBalance) -> ConsumptionRange {
+        let mut total = ConsumptionRange::default();
+        for line in &self.production_lines {
+            total.add(line.consumption_range_with(balance));
+        }
+        total
+    }
 }
 
 impl MachineGroup {
-    pub fn new(number_of_machines: u32, overclock: f32, somersloop_per_machine: u8) -> Self {
+    pub fn new(number_of_machines: u32, overclock: f64, somersloop_per_machine: u8) -> Self {
         Self {
             number_of_machine: number_of_machines,
             oc_value: overclock,
             somersloop: somersloop_per_machine,
         }
     }
+
+    /// Output multiplier from the group's somersloops on `machine`
+    ///
+    /// Each somersloop adds an equal share of the machine's base output, so
+    /// filling every amplifier slot doubles it: `1 + somersloop / max_somersloop`.
+    pub fn amplification(&self, machine: MachineType) -> f64 {
+        let max_somersloop = machine.max_somersloop();
+        if self.somersloop == 0 || max_somersloop == 0 {
+            return 1.0;
+        }
+        1.0 + self.somersloop as f64 / max_somersloop as f64
+    }
+
+    /// Power shards per machine needed for the group's overclock
+    pub fn power_shards(&self) -> u32 {
+        power_shards_for_clock_speed(self.oc_value)
+    }
+
+    /// Split the group into whole machines and an underclocked remainder
+    /// producing `factor` times its output
+    pub(crate) fn scaled(&self, factor: f64) -> Vec<MachineGroup> {
+        let machines = self.number_of_machine as f64 * factor;
+        // Absorb float noise so 3 × 1.0 doesn't leave a 0.00001% machine
+        let whole = (machines + 1e-4).floor();
+        let remainder = machines - whole;
+
+        let mut groups = Vec::new();
+        if whole >= 1.0 {
+            groups.push(MachineGroup::new(
+                whole as u32,
+                self.oc_value,
+                self.somersloop,
+            ));
+        }
+        if remainder > 1e-4 {
+            groups.push(MachineGroup::new(
+                1,
+                self.oc_value * remainder,
+                self.somersloop,
+            ));
+        }
+        groups
+    }
+}
+
+/// Fluent construction of a [`ProductionLine`], validated on [`build`](Self::build)
+///
+/// Start from [`recipe`](Self::recipe) for a single recipe with its machine
+/// groups, or from [`blueprint`](Self::blueprint) for a blueprint made of
+/// recipe lines.
+///
+/// # Example
+///
+/// ```
+/// use satisflow_engine::models::{production_line::ProductionLineBuilder, Recipe};
+///
+/// let blueprint = ProductionLineBuilder::blueprint("Reinforced Plates")
+///     .line(ProductionLineBuilder::recipe("Plates", Recipe::IronPlate).machines(6, 100.0, 0))
+///     .line(ProductionLineBuilder::recipe("Screws", Recipe::Screw).machines(4, 100.0, 0))
+///     .build()
+///     .unwrap();
+/// assert_eq!(blueprint.total_machines(), 10);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ProductionLineBuilder {
+    id: Option<ProductionLineId>,
+    name: String,
+    description: Option<String>,
+    /// `None` for a blueprint
+    recipe: Option<LineRecipe>,
+    machine_groups: Vec<MachineGroup>,
+    lines: Vec<ProductionLineBuilder>,
+    enabled: bool,
+}
+
+impl ProductionLineBuilder {
+    /// Start a line running a single recipe
+    pub fn recipe(name: impl Into<String>, recipe: impl Into<LineRecipe>) -> Self {
+        Self {
+            recipe: Some(recipe.into()),
+            ..Self::blueprint(name)
+        }
+    }
+
+    /// Start a blueprint; add its recipe lines with [`line`](Self::line)
+    pub fn blueprint(name: impl Into<String>) -> Self {
+        Self {
+            id: None,
+            name: name.into(),
+            description: None,
+            recipe: None,
+            machine_groups: Vec::new(),
+            lines: Vec::new(),
+            enabled: true,
+        }
+    }
+
+    /// Use a fixed ID instead of a random one
+    pub fn id(mut self, id: ProductionLineId) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Add a machine group to a recipe line
+    pub fn machines(self, number_of_machines: u32, overclock: f64, somersloop: u8) -> Self {
+        self.machine_group(MachineGroup::new(number_of_machines, overclock, somersloop))
+    }
+
+    /// Add a machine group to a recipe line
+    pub fn machine_group(mut self, group: MachineGroup) -> Self {
+        self.machine_groups.push(group);
+        self
+    }
+
+    /// Add a recipe line to a blueprint
+    pub fn line(mut self, line: ProductionLineBuilder) -> Self {
+        self.lines.push(line);
+        self
+    }
+
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Create the production line
+    ///
+    /// # Returns
+    ///
+    /// The line, or an error if the name is blank, a machine group is invalid,
+    /// a blueprint has no recipe line or a recipe line is given sub-lines
+    pub fn build(self) -> Result<ProductionLine, Box<dyn std::error::Error>> {
+        if self.recipe.is_some() {
+            self.build_recipe()
+                .map(ProductionLine::ProductionLineRecipe)
+        } else {
+            self.build_blueprint()
+                .map(ProductionLine::ProductionLineBlueprint)
+        }
+    }
+
+    /// Create a recipe line, e.g. to add it to an existing blueprint
+    pub fn build_recipe(self) -> Result<ProductionLineRecipe, Box<dyn std::error::Error>> {
+        let name = validated_name(&self.name)?;
+        let Some(recipe) = self.recipe else {
+            return Err(format!("{} is a blueprint, not a recipe line", name).into());
+        };
+        if !self.lines.is_empty() {
+            return Err(format!("Recipe line {} cannot contain other lines", name).into());
+        }
+
+        let id = self.id.unwrap_or_else(ProductionLineId::new_v4);
+        let mut line = ProductionLineRecipe::new(id, name, self.description, recipe);
+        for group in self.machine_groups {
+            line.add_machine_group(group)?;
+        }
+        line.enabled = self.enabled;
+        Ok(line)
+    }
+
+    fn build_blueprint(self) -> Result<ProductionLineBlueprint, Box<dyn std::error::Error>> {
+        let name = validated_name(&self.name)?;
+        if !self.machine_groups.is_empty() {
+            return Err(format!("Blueprint {} has no machines of its own", name).into());
+        }
+        if self.lines.is_empty() {
+            return Err(format!("Blueprint {} requires at least one recipe line", name).into());
+        }
+
+        let id = self.id.unwrap_or_else(ProductionLineId::new_v4);
+        let mut blueprint = ProductionLineBlueprint::new(id, name, self.description);
+        for line in self.lines {
+            blueprint.add_production_line(line.build_recipe()?);
+        }
+        blueprint.enabled = self.enabled;
+        Ok(blueprint)
+    }
+}
+
+fn validated_name(name: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Production line name cannot be empty".into());
+    }
+    Ok(name.to_string())
 }
 
 #[cfg(test)]
@@ -283,8 +838,8 @@ mod tests {
     use crate::models::Recipe;
     use uuid::Uuid;
 
-    fn uuid_from_u64(value: u64) -> Uuid {
-        Uuid::from_u128(value as u128)
+    fn uuid_from_u64<T: From<Uuid>>(value: u64) -> T {
+        Uuid::from_u128(value as u128).into()
     }
 
     #[test]
@@ -339,6 +894,47 @@ mod tests {
         assert_eq!(production_line.total_power_consumption(), 16.0); // 4 machines * 4 MW each at 100% clock speed
     }
 
+    #[test]
+    fn test_byproducts() {
+        let mut plastic = ProductionLineRecipe::new(
+            uuid_from_u64(1),
+            "Plastic".to_string(),
+            None,
+            Recipe::Plastic,
+        );
+        plastic
+            .add_machine_group(MachineGroup::new(2, 100.0, 0))
+            .expect("Invalid group");
+        plastic
+            .add_machine_group(MachineGroup::new(1, 50.0, 2))
+            .expect("Invalid group");
+
+        // Groups are summed per item; somersloops double the byproduct too
+        assert_eq!(
+            plastic.output_rate(),
+            vec![(Item::Plastic, 60.0), (Item::HeavyOilResidue, 30.0)]
+        );
+        assert_eq!(plastic.input_rate(), vec![(Item::CrudeOil, 75.0)]);
+        assert_eq!(
+            plastic.byproduct_rate(),
+            vec![(Item::HeavyOilResidue, 30.0)]
+        );
+
+        let mut fuel =
+            ProductionLineRecipe::new(uuid_from_u64(2), "Fuel".to_string(), None, Recipe::Fuel);
+        fuel.add_machine_group(MachineGroup::new(1, 100.0, 0))
+            .expect("Invalid group");
+        let mut blueprint = ProductionLineBlueprint::new(uuid_from_u64(3), "Oil".to_string(), None);
+        blueprint.add_production_line(plastic);
+        blueprint.add_production_line(fuel);
+
+        let line = ProductionLine::ProductionLineBlueprint(blueprint);
+        assert_eq!(
+            line.byproduct_rate(),
+            vec![(Item::HeavyOilResidue, 30.0), (Item::PolymerResin, 30.0)]
+        );
+    }
+
     #[test]
     #[should_panic(
         expected = "Cannot add machine group with more somersloop than the machine type allows"
@@ -511,4 +1107,206 @@ mod tests {
         // 4.0 * (250/100)^1.321928 = 4.0 * 2.5^1.321928 ≈ 4.0 * 3.36 = 13.44
         assert!((total_power - 13.44).abs() < 0.1);
     }
+
+    #[test]
+    fn test_power_variable_draw() {
+        // Converters cycle between 100 and 400 MW, 250 MW on average
+        let mut converters = ProductionLineRecipe::new(
+            uuid_from_u64(1),
+            "Reanimated SAM".to_string(),
+            None,
+            Recipe::ReanimatedSam,
+        );
+        converters
+            .add_machine_group(MachineGroup::new(2, 100.0, 0))
+            .expect("Invalid group");
+        assert_eq!(
+            converters.consumption_range(),
+            ConsumptionRange {
+                min: 200.0,
+                average: 500.0,
+                max: 800.0,
+            }
+        );
+        assert_eq!(converters.total_power_consumption(), 500.0);
+
+        // Dark matter draws 500 to 1,500 MW per accelerator
+        let mut accelerators = ProductionLineRecipe::new(
+            uuid_from_u64(2),
+            "Dark Matter".to_string(),
+            None,
+            Recipe::DarkMatterCrystal,
+        );
+        accelerators
+            .add_machine_group(MachineGroup::new(1, 100.0, 0))
+            .expect("Invalid group");
+        let mut blueprint = ProductionLineBlueprint::new(uuid_from_u64(3), "Endgame".into(), None);
+        blueprint.add_production_line(converters);
+        blueprint.add_production_line(accelerators);
+        let range = ProductionLine::ProductionLineBlueprint(blueprint).consumption_range();
+        assert_eq!(
+            (range.min, range.average, range.max),
+            (700.0, 1500.0, 2300.0)
+        );
+    }
+
+    #[test]
+    fn test_somersloop_amplification_slot_limits() {
+        let line_with = |recipe, somersloop| {
+            let mut line =
+                ProductionLineRecipe::new(uuid_from_u64(1), "Amplified".to_string(), None, recipe);
+            line.add_machine_group(MachineGroup::new(1, 100.0, somersloop))
+                .map(|_| line)
+        };
+
+        // Smelter: 1 slot, filling it doubles the output
+        let smelter = line_with(Recipe::IronIngot, 1).expect("Invalid group");
+        assert_eq!(smelter.output_rate(), vec![(Item::IronIngot, 60.0)]);
+        assert_eq!(smelter.output_amplification(), 2.0);
+        assert!(line_with(Recipe::IronIngot, 2).is_err());
+
+        // Assembler: 2 slots, each adding half of the base output
+        let assembler = line_with(Recipe::ReinforcedIronPlate, 1).expect("Invalid group");
+        assert_eq!(assembler.output_amplification(), 1.5);
+        assert_eq!(
+            assembler.input_rate(),
+            vec![(Item::IronPlate, 30.0), (Item::Screw, 60.0)]
+        );
+        let assembler = line_with(Recipe::ReinforcedIronPlate, 2).expect("Invalid group");
+        assert_eq!(
+            assembler.output_rate(),
+            vec![(Item::ReinforcedIronPlate, 10.0)]
+        );
+        assert!(line_with(Recipe::ReinforcedIronPlate, 3).is_err());
+
+        // Manufacturer: 4 slots
+        let manufacturer = line_with(Recipe::Computer, 2).expect("Invalid group");
+        assert_eq!(manufacturer.output_amplification(), 1.5);
+        let manufacturer = line_with(Recipe::Computer, 4).expect("Invalid group");
+        assert_eq!(manufacturer.output_rate(), vec![(Item::Computer, 5.0)]);
+        assert_eq!(manufacturer.output_amplification(), 2.0);
+        assert!(line_with(Recipe::Computer, 5).is_err());
+
+        // Blueprints weigh each line by its unamplified output
+        let mut blueprint =
+            ProductionLineBlueprint::new(uuid_from_u64(2), "Mixed".to_string(), None);
+        blueprint.add_production_line(line_with(Recipe::IronIngot, 0).expect("Invalid group"));
+        blueprint.add_production_line(line_with(Recipe::IronIngot, 1).expect("Invalid group"));
+        let line = ProductionLine::ProductionLineBlueprint(blueprint);
+        assert_eq!(line.output_amplification(), 1.5);
+        assert_eq!(line.output_rate(), vec![(Item::IronIngot, 90.0)]);
+    }
+
+    #[test]
+    fn test_blueprint_scale() {
+        let mut line = ProductionLineRecipe::new(
+            uuid_from_u64(2),
+            "Ingots".to_string(),
+            None,
+            Recipe::IronIngot,
+        );
+        line.add_machine_group(MachineGroup::new(3, 100.0, 0))
+            .unwrap();
+        let mut blueprint = ProductionLineBlueprint::new(uuid_from_u64(1), "Ingots".into(), None);
+        blueprint.add_production_line(line);
+        let mut shrunk = blueprint.clone();
+
+        blueprint.scale(2.5).unwrap();
+        let groups = &blueprint.production_lines[0].machine_groups;
+        // 7.5 smelters: 7 at 100% and one at 50%
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].number_of_machine, 7);
+        assert_eq!(groups[1].number_of_machine, 1);
+        assert_eq!(groups[1].oc_value, 50.0);
+        assert_eq!(blueprint.output_rate(), vec![(Item::IronIngot, 225.0)]);
+
+        // Less than one machine leaves only the underclocked one
+        shrunk.scale(0.25).unwrap();
+        let groups = &shrunk.production_lines[0].machine_groups;
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].number_of_machine, 1);
+        assert_eq!(groups[0].oc_value, 75.0);
+
+        assert!(blueprint.scale(0.0).is_err());
+        assert!(blueprint.scale(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_optimize_clock_for() {
+        let line = ProductionLineRecipe::new(
+            uuid_from_u64(1),
+            "Ingots".to_string(),
+            None,
+            Recipe::IronIngot,
+        );
+
+        // Iron Ingot: 30/min per smelter at 100%
+        let optimization = line.optimize_clock_for(45.0, 2).unwrap();
+        assert_eq!(optimization.item, Item::IronIngot);
+        assert_eq!(optimization.clock_speed, 75.0);
+        assert_eq!(optimization.total_power_shards, 0);
+        assert_eq!(optimization.actual_rate, 45.0);
+
+        let optimization = line.optimize_clock_for(100.0, 3).unwrap();
+        assert!((optimization.clock_speed - 111.1112).abs() < 1e-3);
+        assert!(optimization.actual_rate >= 100.0);
+        assert_eq!(optimization.power_shards_per_machine, 1);
+        assert_eq!(optimization.total_power_shards, 3);
+        assert_eq!(optimization.machine_group().number_of_machine, 3);
+
+        assert!(line.optimize_clock_for(200.0, 2).is_err());
+        assert!(line.optimize_clock_for(30.0, 0).is_err());
+        assert!(line.optimize_clock_for(0.0, 1).is_err());
+    }
+
+    #[test]
+    fn test_builder_validates_on_build() {
+        let line = ProductionLineBuilder::recipe("Ingots", Recipe::IronIngot)
+            .id(uuid_from_u64(3))
+            .description("Main smelters")
+            .machines(4, 100.0, 0)
+            .machines(1, 50.0, 0)
+            .build()
+            .expect("Should build valid line");
+        assert_eq!(line.id(), uuid_from_u64(3));
+        assert_eq!(line.total_machines(), 5);
+        let ProductionLine::ProductionLineRecipe(recipe) = &line else {
+            panic!("Expected a recipe line");
+        };
+        assert_eq!(recipe.description.as_deref(), Some("Main smelters"));
+
+        let blueprint = ProductionLineBuilder::blueprint("Plates")
+            .line(ProductionLineBuilder::recipe("Ingots", Recipe::IronIngot).machines(2, 100.0, 0))
+            .line(ProductionLineBuilder::recipe("Plates", Recipe::IronPlate).machines(3, 100.0, 0))
+            .enabled(false)
+            .build()
+            .expect("Should build valid blueprint");
+        assert!(matches!(
+            blueprint,
+            ProductionLine::ProductionLineBlueprint(_)
+        ));
+        assert_eq!(blueprint.total_machines(), 5);
+        assert!(!blueprint.is_enabled());
+
+        assert!(ProductionLineBuilder::recipe("  ", Recipe::IronIngot)
+            .build()
+            .is_err());
+        assert!(ProductionLineBuilder::recipe("Ingots", Recipe::IronIngot)
+            .machines(1, 300.0, 0)
+            .build()
+            .is_err());
+        assert!(ProductionLineBuilder::blueprint("Empty").build().is_err());
+        assert!(ProductionLineBuilder::blueprint("Machines")
+            .machines(1, 100.0, 0)
+            .line(ProductionLineBuilder::recipe("Ingots", Recipe::IronIngot))
+            .build()
+            .is_err());
+        assert!(ProductionLineBuilder::blueprint("Nested")
+            .line(
+                ProductionLineBuilder::blueprint("Inner")
+                    .line(ProductionLineBuilder::recipe("Ingots", Recipe::IronIngot))
+            )
+            .build()
+            .is_err());
+    }
 }
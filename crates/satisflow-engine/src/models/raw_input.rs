@@ -1,9 +1,12 @@
 use serde::{Deserialize, Serialize};
 
-use crate::models::{Item, RawInputId};
+use crate::models::{
+    game_balance::GameBalance, game_data::power_shards_for_clock_speed, Item, RawInputId,
+    UnknownFields,
+};
 
 /// Purity levels for resource nodes in Satisfactory
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Purity {
     Impure, // 50% yield (0.5x multiplier)
     Normal, // 100% yield (1.0x multiplier)
@@ -12,7 +15,7 @@ pub enum Purity {
 
 impl Purity {
     /// Get the yield multiplier for this purity level
-    pub fn multiplier(&self) -> f32 {
+    pub fn multiplier(&self) -> f64 {
         match self {
             Purity::Impure => 0.5,
             Purity::Normal => 1.0,
@@ -34,7 +37,7 @@ pub enum ExtractorType {
 
 impl ExtractorType {
     /// Get the base extraction rate for this extractor type (items/min or m³/min)
-    pub fn base_rate(&self) -> f32 {
+    pub fn base_rate(&self) -> f64 {
         match self {
             ExtractorType::MinerMk1 => 60.0,
             ExtractorType::MinerMk2 => 120.0,
@@ -46,7 +49,7 @@ impl ExtractorType {
     }
 
     /// Get the base power consumption for this extractor type in MW at 100% clock speed
-    pub fn base_power_consumption(&self) -> f32 {
+    pub fn base_power_consumption(&self) -> f64 {
         match self {
             ExtractorType::MinerMk1 => 5.0,
             ExtractorType::MinerMk2 => 15.0,
@@ -87,6 +90,17 @@ impl ExtractorType {
         }
     }
 
+    /// Check if this extractor sits on a finite resource node
+    pub fn occupies_node(&self) -> bool {
+        matches!(
+            self,
+            ExtractorType::MinerMk1
+                | ExtractorType::MinerMk2
+                | ExtractorType::MinerMk3
+                | ExtractorType::OilExtractor
+        )
+    }
+
     /// Get all items compatible with this extractor type
     pub fn compatible_items(&self) -> Vec<Item> {
         match self {
@@ -112,16 +126,28 @@ impl ExtractorType {
     }
 }
 
+/// Whether any extractor can pull `item` out of the ground
+pub fn is_extractable(item: Item) -> bool {
+    [
+        ExtractorType::MinerMk1,
+        ExtractorType::WaterExtractor,
+        ExtractorType::OilExtractor,
+        ExtractorType::ResourceWellExtractor,
+    ]
+    .iter()
+    .any(|extractor| extractor.is_compatible_with(&item))
+}
+
 /// Resource Well Pressurizer - Main building that powers Resource Well Extractors
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ResourceWellPressurizer {
     pub id: u64,
-    pub clock_speed: f32, // 0.000 to 250.000
+    pub clock_speed: f64, // 0.000 to 250.000
 }
 
 impl ResourceWellPressurizer {
     /// Create a new Resource Well Pressurizer
-    pub fn new(id: u64, clock_speed: f32) -> Result<Self, RawInputError> {
+    pub fn new(id: u64, clock_speed: f64) -> Result<Self, RawInputError> {
         if !(0.0..=250.0).contains(&clock_speed) {
             return Err(RawInputError::InvalidClockSpeed { clock_speed });
         }
@@ -131,13 +157,18 @@ impl ResourceWellPressurizer {
 
     /// Calculate power consumption using the same formula as ProductionLine
     /// Power usage = Base power usage × (Clock speed/100)^1.321928
-    pub fn power_consumption(&self) -> f32 {
-        const BASE_POWER: f32 = 150.0; // 150MW base power consumption
-        BASE_POWER * (self.clock_speed / 100.0).powf(1.321928)
+    pub fn power_consumption(&self) -> f64 {
+        self.power_consumption_with(GameBalance::vanilla())
+    }
+
+    /// Power consumption with the given game constants
+    pub fn power_consumption_with(&self, balance: &GameBalance) -> f64 {
+        const BASE_POWER: f64 = 150.0; // 150MW base power consumption
+        BASE_POWER * balance.clock_power_factor(self.clock_speed)
     }
 
     /// Set the clock speed of the pressurizer
-    pub fn set_clock_speed(&mut self, clock_speed: f32) -> Result<(), RawInputError> {
+    pub fn set_clock_speed(&mut self, clock_speed: f64) -> Result<(), RawInputError> {
         if !(0.0..=250.0).contains(&clock_speed) {
             return Err(RawInputError::InvalidClockSpeed { clock_speed });
         }
@@ -161,7 +192,7 @@ impl ResourceWellExtractor {
 
     /// Calculate extraction rate based on purity and pressurizer clock speed
     /// Base rates: Impure 30, Normal 60, Pure 120 m³/min at 100% clock
-    pub fn extraction_rate(&self, pressurizer_clock_speed: f32) -> f32 {
+    pub fn extraction_rate(&self, pressurizer_clock_speed: f64) -> f64 {
         let base_rate = match self.purity {
             Purity::Impure => 30.0,
             Purity::Normal => 60.0,
@@ -178,12 +209,21 @@ pub struct RawInput {
     pub extractor_type: ExtractorType,
     pub item: Item,
     pub purity: Option<Purity>, // Some for ores/oil/gas, None for water
-    pub quantity_per_min: f32,
-    pub overclock_percent: f32, // 0.0 to 250.0
+    pub quantity_per_min: f64,
+    pub overclock_percent: f64, // 0.0 to 250.0
     pub count: u32,             // Number of extractors in group (>= 1)
     // Resource Well system fields
     pub pressurizer: Option<ResourceWellPressurizer>,
     pub extractors: Vec<ResourceWellExtractor>,
+    /// Whether the extractors occupy real map nodes checked against the node registry
+    #[serde(default)]
+    pub claims_node: bool,
+    /// Disabled entries stay in the save but are left out of every calculation
+    #[serde(default = "crate::models::enabled_by_default")]
+    pub enabled: bool,
+    /// Fields from a newer save this engine doesn't know, written back as loaded
+    #[serde(flatten)]
+    pub unknown_fields: UnknownFields,
 }
 
 impl RawInput {
@@ -193,7 +233,7 @@ impl RawInput {
         extractor_type: ExtractorType,
         item: Item,
         purity: Option<Purity>,
-        overclock_percent: f32,
+        overclock_percent: f64,
         count: u32,
     ) -> Result<Self, RawInputError> {
         // Validate resource compatibility
@@ -242,6 +282,9 @@ impl RawInput {
             count,
             pressurizer: None,
             extractors: Vec::new(),
+            claims_node: false,
+            enabled: true,
+            unknown_fields: UnknownFields::new(),
         })
     }
 
@@ -270,7 +313,7 @@ impl RawInput {
         let sum_of_nodes = extractors
             .iter()
             .map(|e| e.extraction_rate(100.0))
-            .sum::<f32>();
+            .sum::<f64>();
 
         // Apply pressurizer OC to the sum: sum × (pressurizer_oc/100)
         let quantity_per_min = sum_of_nodes * (pressurizer.clock_speed / 100.0);
@@ -285,6 +328,9 @@ impl RawInput {
             count: 1, // Resource Well systems don't use count (each node is separate)
             pressurizer: Some(pressurizer),
             extractors,
+            claims_node: false,
+            enabled: true,
+            unknown_fields: UnknownFields::new(),
         })
     }
 
@@ -293,9 +339,9 @@ impl RawInput {
     pub fn calculate_extraction_rate(
         extractor_type: ExtractorType,
         purity: Option<Purity>,
-        overclock_percent: f32,
+        overclock_percent: f64,
         count: u32,
-    ) -> f32 {
+    ) -> f64 {
         let base_rate = extractor_type.base_rate();
         let purity_multiplier = purity.map(|p| p.multiplier()).unwrap_or(1.0);
 
@@ -304,7 +350,26 @@ impl RawInput {
             return 0.0;
         }
 
-        base_rate * purity_multiplier * (overclock_percent / 100.0) * count as f32
+        base_rate * purity_multiplier * (overclock_percent / 100.0) * count as f64
+    }
+
+    /// Extraction rate with the given game constants
+    ///
+    /// The recorded rate is scaled by how far the purity multipliers differ
+    /// from the game's, so a manually entered rate keeps its proportion.
+    pub fn quantity_per_min_with(&self, balance: &GameBalance) -> f64 {
+        let vanilla = GameBalance::vanilla();
+        let purities: Vec<Purity> = if self.pressurizer.is_some() {
+            self.extractors.iter().map(|e| e.purity).collect()
+        } else {
+            self.purity.into_iter().collect()
+        };
+        let base: f64 = purities.iter().map(|p| vanilla.purity_multiplier(*p)).sum();
+        if purities.is_empty() || base == 0.0 {
+            return self.quantity_per_min;
+        }
+        let scaled: f64 = purities.iter().map(|p| balance.purity_multiplier(*p)).sum();
+        self.quantity_per_min * scaled / base
     }
 
     /// Update extraction rates for Resource Well systems when clock speed changes
@@ -315,7 +380,7 @@ impl RawInput {
                 .extractors
                 .iter()
                 .map(|e| e.extraction_rate(100.0))
-                .sum::<f32>();
+                .sum::<f64>();
 
             // Apply pressurizer OC to the sum: sum × (pressurizer_oc/100)
             self.quantity_per_min = sum_of_nodes * (pressurizer.clock_speed / 100.0);
@@ -326,21 +391,31 @@ impl RawInput {
     /// Get the power consumption of this raw input
     /// Resource Well systems consume power only from the pressurizer
     /// Regular extractors: base_power × (oc/100)^1.321928 × count
-    pub fn power_consumption(&self) -> f32 {
+    pub fn power_consumption(&self) -> f64 {
+        self.power_consumption_with(GameBalance::vanilla())
+    }
+
+    /// Power consumption with the given game constants
+    pub fn power_consumption_with(&self, balance: &GameBalance) -> f64 {
         if let Some(pressurizer) = &self.pressurizer {
-            pressurizer.power_consumption()
+            pressurizer.power_consumption_with(balance)
         } else {
-            // Regular extractors: base_power × (oc/100)^1.321928 × count
+            // Regular extractors: base_power × (oc/100)^overclock_exponent × count
             let base_power = self.extractor_type.base_power_consumption();
             let oc_multiplier = if self.overclock_percent == 0.0 {
                 0.0
             } else {
-                (self.overclock_percent / 100.0).powf(1.321928)
+                balance.clock_power_factor(self.overclock_percent)
             };
-            base_power * oc_multiplier * self.count as f32
+            base_power * oc_multiplier * self.count as f64
         }
     }
 
+    /// Power shards slotted in the extractors, or in the pressurizer of a Resource Well
+    pub fn power_shards(&self) -> u32 {
+        power_shards_for_clock_speed(self.overclock_percent) * self.count
+    }
+
     /// Add an extractor to a Resource Well system
     pub fn add_extractor(&mut self, extractor: ResourceWellExtractor) -> Result<(), RawInputError> {
         if self.pressurizer.is_none() {
@@ -388,6 +463,25 @@ impl RawInput {
     }
 
     /// Validate that this raw input configuration is correct
+    /// Mark whether this raw input claims map nodes from the resource node registry
+    pub fn set_claims_node(&mut self, claims_node: bool) -> Result<(), RawInputError> {
+        if claims_node && !self.extractor_type.occupies_node() {
+            return Err(RawInputError::NodeClaimUnsupported {
+                extractor: self.extractor_type,
+            });
+        }
+        self.claims_node = claims_node;
+        Ok(())
+    }
+
+    /// Number of map nodes claimed, with their purity
+    pub fn claimed_nodes(&self) -> Option<(Purity, u32)> {
+        if !self.claims_node || !self.extractor_type.occupies_node() {
+            return None;
+        }
+        self.purity.map(|purity| (purity, self.count))
+    }
+
     pub fn validate(&self) -> Result<(), RawInputError> {
         // Check resource compatibility
         if !self.extractor_type.is_compatible_with(&self.item) {
@@ -413,6 +507,12 @@ impl RawInput {
             });
         }
 
+        if self.claims_node && !self.extractor_type.occupies_node() {
+            return Err(RawInputError::NodeClaimUnsupported {
+                extractor: self.extractor_type,
+            });
+        }
+
         // Validate Resource Well system
         if self.extractor_type == ExtractorType::ResourceWellExtractor {
             if self.pressurizer.is_none() {
@@ -442,7 +542,7 @@ pub enum RawInputError {
         extractor: ExtractorType,
     },
     InvalidClockSpeed {
-        clock_speed: f32,
+        clock_speed: f64,
     },
     InvalidCount {
         count: u32,
@@ -453,6 +553,9 @@ pub enum RawInputError {
     ExtractorNotFound {
         id: u64,
     },
+    NodeClaimUnsupported {
+        extractor: ExtractorType,
+    },
 }
 
 impl std::fmt::Display for RawInputError {
@@ -496,6 +599,13 @@ impl std::fmt::Display for RawInputError {
             RawInputError::ExtractorNotFound { id } => {
                 write!(f, "Extractor with ID {} not found", id)
             }
+            RawInputError::NodeClaimUnsupported { extractor } => {
+                write!(
+                    f,
+                    "Extractor {:?} does not occupy a resource node",
+                    extractor
+                )
+            }
         }
     }
 }
@@ -507,8 +617,8 @@ mod tests {
     use super::*;
     use uuid::Uuid;
 
-    fn uuid_from_u64(value: u64) -> Uuid {
-        Uuid::from_u128(value as u128)
+    fn uuid_from_u64<T: From<Uuid>>(value: u64) -> T {
+        Uuid::from_u128(value as u128).into()
     }
 
     // ===== Purity Tests =====
@@ -1327,7 +1437,7 @@ mod tests {
 
         // Power should come only from pressurizer, not extractors
         let power = raw_input.power_consumption();
-        let expected = 150.0_f32 * (1.5_f32).powf(1.321928_f32);
+        let expected = 150.0_f64 * (1.5_f64).powf(1.321928_f64);
         assert!(
             (power - expected).abs() < 1.0,
             "Power should be approximately {}, got {}",
@@ -1515,7 +1625,7 @@ mod tests {
 
         assert_eq!(input.quantity_per_min, 300.0); // 120 * 2.5
         let power = input.power_consumption();
-        let expected = 15.0 * (2.5_f32).powf(1.321928);
+        let expected = 15.0 * (2.5_f64).powf(1.321928);
         assert!(
             (power - expected).abs() < 0.1,
             "Power should be approximately {}, got {}",
@@ -1554,7 +1664,7 @@ mod tests {
 
         assert_eq!(input.quantity_per_min, 540.0); // 120 * 1.5 * 3
         let power = input.power_consumption();
-        let expected = 15.0 * (1.5_f32).powf(1.321928) * 3.0;
+        let expected = 15.0 * (1.5_f64).powf(1.321928) * 3.0;
         assert!(
             (power - expected).abs() < 0.1,
             "Power should be approximately {}, got {}",
@@ -0,0 +1,166 @@
+//! Recipe dependency trees: what a recipe needs, all the way down to raw
+//! resources.
+//!
+//! Each input is made with the standard recipe for it, so alternates never
+//! appear below the root. Rates are scaled so the root runs one machine at
+//! 100% clock.
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{
+    game_data::MachineType,
+    raw_input::is_extractable,
+    recipes::{recipe_info, standard_recipe_for},
+    Item, Recipe,
+};
+
+/// Deepest chain the tree expands
+pub const MAX_RECIPE_CHAIN_DEPTH: u32 = 16;
+
+/// One recipe step of a chain and the inputs feeding it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecipeChain {
+    pub recipe: Recipe,
+    pub name: String,
+    pub machine: MachineType,
+    /// Machines at 100% clock running this step
+    pub machines: f64,
+    /// Main product and its rate at this step
+    pub item: Item,
+    pub quantity_per_min: f64,
+    pub inputs: Vec<ChainInput>,
+}
+
+/// An input of a chain step, with the rate the step consumes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChainInput {
+    pub item: Item,
+    pub quantity_per_min: f64,
+    pub source: ChainSource,
+}
+
+/// Where a chain input comes from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ChainSource {
+    /// Mined or extracted
+    Raw,
+    /// Made by the nested step
+    Recipe(Box<RecipeChain>),
+    /// Left unexpanded below the requested depth
+    Truncated,
+    /// No standard recipe makes the item, or making it loops back up the chain
+    Unresolved,
+}
+
+impl RecipeChain {
+    /// Expand a recipe's inputs recursively for one machine
+    ///
+    /// # Arguments
+    ///
+    /// * `recipe` - Recipe at the root of the tree
+    /// * `depth` - Recipe levels to expand below the root, capped at
+    ///   [`MAX_RECIPE_CHAIN_DEPTH`]; 0 lists the root's inputs only
+    pub fn new(recipe: Recipe, depth: u32) -> Self {
+        Self::expand(
+            recipe,
+            1.0,
+            depth.min(MAX_RECIPE_CHAIN_DEPTH),
+            &mut Vec::new(),
+        )
+    }
+
+    fn expand(recipe: Recipe, machines: f64, depth: u32, chain: &mut Vec<Item>) -> Self {
+        let info = recipe_info(recipe);
+        let (item, per_machine) = info.outputs[0];
+        chain.push(item);
+
+        let inputs = info
+            .inputs
+            .iter()
+            .map(|&(input, quantity)| {
+                let quantity_per_min = quantity * machines;
+                let source = if is_extractable(input) {
+                    ChainSource::Raw
+                } else if depth == 0 {
+                    ChainSource::Truncated
+                } else {
+                    match standard_recipe_for(input).filter(|_| !chain.contains(&input)) {
+                        Some(step) => {
+                            let step_rate = recipe_info(step).outputs[0].1;
+                            ChainSource::Recipe(Box::new(Self::expand(
+                                step,
+                                quantity_per_min / step_rate,
+                                depth - 1,
+                                chain,
+                            )))
+                        }
+                        None => ChainSource::Unresolved,
+                    }
+                };
+                ChainInput {
+                    item: input,
+                    quantity_per_min,
+                    source,
+                }
+            })
+            .collect();
+
+        chain.pop();
+        Self {
+            recipe,
+            name: info.name.to_string(),
+            machine: info.machine,
+            machines,
+            item,
+            quantity_per_min: per_machine * machines,
+            inputs,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(input: &ChainInput) -> &RecipeChain {
+        match &input.source {
+            ChainSource::Recipe(step) => step,
+            other => panic!("{:?} is not made by a recipe: {:?}", input.item, other),
+        }
+    }
+
+    #[test]
+    fn test_recipe_chain() {
+        let chain = RecipeChain::new(Recipe::ReinforcedIronPlate, 10);
+        assert_eq!(chain.machines, 1.0);
+        assert_eq!(chain.quantity_per_min, 5.0);
+
+        // 30 plates and 60 screws a minute
+        let plates = step(&chain.inputs[0]);
+        assert_eq!(plates.recipe, Recipe::IronPlate);
+        assert_eq!(plates.machines, 1.5);
+        let screws = step(&chain.inputs[1]);
+        assert_eq!(screws.machines, 1.5);
+
+        // Screws need 15 rods, from 15 ingots, from 15 ore
+        let rods = step(&screws.inputs[0]);
+        assert_eq!(rods.quantity_per_min, 15.0);
+        let ingots = step(&rods.inputs[0]);
+        assert_eq!(ingots.machines, 0.5);
+        assert_eq!(ingots.inputs[0].item, Item::IronOre);
+        assert_eq!(ingots.inputs[0].quantity_per_min, 15.0);
+        assert_eq!(ingots.inputs[0].source, ChainSource::Raw);
+    }
+
+    #[test]
+    fn test_recipe_chain_depth() {
+        let chain = RecipeChain::new(Recipe::ReinforcedIronPlate, 1);
+        let plates = step(&chain.inputs[0]);
+        assert_eq!(plates.inputs[0].item, Item::IronIngot);
+        assert_eq!(plates.inputs[0].quantity_per_min, 45.0);
+        assert_eq!(plates.inputs[0].source, ChainSource::Truncated);
+
+        let shallow = RecipeChain::new(Recipe::IronIngot, 0);
+        assert_eq!(shallow.inputs[0].source, ChainSource::Raw);
+    }
+}
@@ -6,7 +6,10 @@ use super::items::Item;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Recipe {
+    ActualSnow,
+    AIExpansionServer,
     AILimiter,
+    AlienPowerMatrix,
     AWESOMEShop,
     AWESOMESink,
     AdaptiveControlUnit,
@@ -127,6 +130,7 @@ pub enum Recipe {
     BladeRunners,
     Blender,
     BlockSignal,
+    BlueFICSMASOrnament,
     BlueprintDesigner,
     Cable,
     CandyCane,
@@ -167,12 +171,16 @@ pub enum Recipe {
     ConveyorWallX2,
     ConveyorWallX3,
     CoolingSystem,
+    CopperFICSMASOrnament,
     CopperIngot,
     CopperPowder,
     CopperSheet,
     CraftBench,
     CrystalOscillator,
     CyberWagon,
+    DarkMatterCrystal,
+    DarkMatterResidue,
+    Diamonds,
     DisplaySign,
     DoubleRamp2m,
     DoubleRamp4m,
@@ -195,11 +203,22 @@ pub enum Recipe {
     EncasedPlutoniumCell,
     EncasedUraniumCell,
     EquipmentWorkshop,
+    ExcitedPhotonicMatter,
     Explorer,
     ExplosiveRebar,
+    FancyFireworks,
+    FicsiteIngotAluminum,
+    FicsiteIngotCaterium,
+    FicsiteIngotIron,
+    FicsiteTrigon,
+    FICSMASBow,
+    FICSMASDecoration,
     FICSMASGiftTree,
+    FICSMASOrnamentBundle,
     FICSMASPowerLight,
     FICSMASSnowDispenser,
+    FICSMASTreeBranch,
+    FICSMASWonderStar,
     FICSMASWreath,
     Fabric,
     FactoryCart,
@@ -275,6 +294,7 @@ pub enum Recipe {
     InvertedOuterCornerQuarterPipe,
     InvertedQuarterPipe,
     IodineInfusedFilter,
+    IronFICSMASOrnament,
     IronIngot,
     IronPlate,
     IronRebar,
@@ -301,6 +321,7 @@ pub enum Recipe {
     ModularEngine,
     ModularFrame,
     Motor,
+    NeuralQuantumProcessor,
     NitricAcid,
     Nobelisk,
     NobeliskDetonator,
@@ -381,7 +402,9 @@ pub enum Recipe {
     RampWall2m,
     RampWall4m,
     RampWall8m,
+    ReanimatedSam,
     RebarGun,
+    RedFICSMASOrnament,
     Refinery,
     ReinforcedIronPlate,
     ReinforcedWindow,
@@ -399,6 +422,7 @@ pub enum Recipe {
     RoofFlat,
     Rotor,
     Rubber,
+    SamFluctuator,
     Screw,
     ShatterRebar,
     SideDoorWall,
@@ -413,9 +437,11 @@ pub enum Recipe {
     SmartSplitter,
     Smelter,
     SmokelessPowder,
+    Snowball,
     Snowman,
     SolidBiofuel,
     SpaceElevator,
+    SparklyFireworks,
     SpitterProtein,
     SquareSign05m,
     SquareSign1m,
@@ -435,6 +461,8 @@ pub enum Recipe {
     StunRebar,
     SulfuricAcid,
     Supercomputer,
+    SuperpositionOscillator,
+    SweetFireworks,
     TheHUB,
     TherapeuticInhaler,
     ThermalPropulsionRocket,
@@ -444,6 +472,7 @@ pub enum Recipe {
     TiltedCornerWall8m,
     TiltedWall4m,
     TiltedWall8m,
+    TimeCrystal,
     Tractor,
     TrainStation,
     Truck,
@@ -486,8 +515,8 @@ pub struct RecipeInfo {
     pub recipe: Recipe,
     pub name: &'static str,
     pub machine: crate::models::game_data::MachineType,
-    pub inputs: &'static [(Item, f32)],
-    pub outputs: &'static [(Item, f32)],
+    pub inputs: &'static [(Item, f64)],
+    pub outputs: &'static [(Item, f64)],
 }
 
 macro_rules! define_recipes {
@@ -519,6 +548,33 @@ macro_rules! define_recipes {
     };
 }
 
+impl RecipeInfo {
+    /// Alternate recipes are unlocked by analyzing hard drives in the MAM
+    pub fn is_alternate(&self) -> bool {
+        self.name.starts_with("Alternate:")
+    }
+
+    /// Whether `item` is among the recipe's outputs
+    pub fn produces(&self, item: Item) -> bool {
+        self.outputs.iter().any(|(output, _)| *output == item)
+    }
+
+    /// Whether `item` is among the recipe's inputs
+    pub fn consumes(&self, item: Item) -> bool {
+        self.inputs.iter().any(|(input, _)| *input == item)
+    }
+
+    /// Lowest and highest draw of one machine at 100% clock, in MW, when the
+    /// machine's draw cycles during each craft
+    pub fn power_range_mw(&self) -> Option<(f64, f64)> {
+        match self.recipe {
+            // The heaviest Particle Accelerator recipes draw twice as much
+            Recipe::NuclearPasta | Recipe::DarkMatterCrystal => Some((500.0, 1500.0)),
+            _ => self.machine.power_range_mw(),
+        }
+    }
+}
+
 include!("recipes_data.inc");
 recipe_data!(define_recipes);
 
@@ -556,6 +612,19 @@ pub fn all_recipes() -> &'static [RecipeInfo] {
     RECIPE_INFOS
 }
 
+/// Standard (non-alternate) recipe whose main product is `item`, made in a
+/// building rather than by hand
+pub fn standard_recipe_for(item: Item) -> Option<Recipe> {
+    RECIPE_INFOS
+        .iter()
+        .find(|info| {
+            !info.is_alternate()
+                && info.machine != crate::models::game_data::MachineType::Manual
+                && info.outputs.first().map(|(output, _)| *output) == Some(item)
+        })
+        .map(|info| info.recipe)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -568,4 +637,13 @@ mod tests {
         let info = recipe_info(recipe);
         assert_eq!(info.outputs[0].0, Item::AILimiter);
     }
+
+    #[test]
+    fn recipe_inputs_and_outputs() {
+        let info = recipe_info(Recipe::AlternateAdheredIronPlate);
+        assert!(info.produces(Item::ReinforcedIronPlate));
+        assert!(info.consumes(Item::IronPlate));
+        assert!(!info.produces(Item::IronPlate));
+        assert!(!info.consumes(Item::ReinforcedIronPlate));
+    }
 }
@@ -0,0 +1,269 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::format::NumberFormat;
+use crate::models::{factory::Factory, item_name, Item};
+use crate::rate::balance_sign;
+
+/// Number of items listed in the production and deficit highlights
+const HIGHLIGHT_COUNT: usize = 3;
+
+/// A rate attached to an item, in items per minute
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemRate {
+    pub item: Item,
+    pub quantity_per_min: f64,
+}
+
+/// Condensed status of the whole save, with a human-readable summary
+///
+/// Meant for places that only have room for a paragraph: notifications,
+/// command-line output, report cover pages.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportCard {
+    pub factory_count: usize,
+    pub production_line_count: usize,
+    pub logistics_count: usize,
+    /// Items with the highest output from production lines, highest first
+    pub top_production: Vec<ItemRate>,
+    /// Items with the most negative global balance, worst first
+    pub biggest_deficits: Vec<ItemRate>,
+    pub power_generation: f64,
+    pub power_consumption: f64,
+    /// Generation minus consumption, in MW
+    pub power_margin: f64,
+}
+
+impl ReportCard {
+    /// Build the report card from every factory
+    ///
+    /// Logistics lines only move items between factories, so global balances
+    /// are computed from the factories alone.
+    pub fn build<'a>(
+        factories: impl IntoIterator<Item = &'a Factory>,
+        logistics_count: usize,
+    ) -> Self {
+        let mut factory_count = 0;
+        let mut production_line_count = 0;
+        let mut produced: HashMap<Item, f64> = HashMap::new();
+        let mut balances: HashMap<Item, f64> = HashMap::new();
+        let mut power_generation = 0.0;
+        let mut power_consumption = 0.0;
+
+        for factory in factories {
+            factory_count += 1;
+            production_line_count += factory.production_lines.len();
+            power_generation += factory.total_power_generation();
+            power_consumption += factory.total_power_consumption();
+
+            for line in factory.active_production_lines() {
+                for (item, quantity) in line.output_rate() {
+                    *produced.entry(item).or_insert(0.0) += quantity;
+                }
+            }
+
+            let mut standalone = factory.clone();
+            standalone.calculate_item(&HashMap::new());
+            for (item, quantity) in standalone.items {
+                *balances.entry(item).or_insert(0.0) += quantity;
+            }
+        }
+
+        let mut top_production = rates(produced, |quantity| {
+            balance_sign(quantity) == Ordering::Greater
+        });
+        top_production.sort_by(|a, b| {
+            b.quantity_per_min
+                .total_cmp(&a.quantity_per_min)
+                .then_with(|| item_name(a.item).cmp(item_name(b.item)))
+        });
+        top_production.truncate(HIGHLIGHT_COUNT);
+
+        let mut biggest_deficits = rates(balances, |quantity| {
+            balance_sign(quantity) == Ordering::Less
+        });
+        biggest_deficits.sort_by(|a, b| {
+            a.quantity_per_min
+                .total_cmp(&b.quantity_per_min)
+                .then_with(|| item_name(a.item).cmp(item_name(b.item)))
+        });
+        biggest_deficits.truncate(HIGHLIGHT_COUNT);
+
+        Self {
+            factory_count,
+            production_line_count,
+            logistics_count,
+            top_production,
+            biggest_deficits,
+            power_generation,
+            power_consumption,
+            power_margin: power_generation - power_consumption,
+        }
+    }
+
+    /// Power margin as a percentage of generation, if anything generates power
+    pub fn power_margin_percent(&self) -> Option<f64> {
+        (balance_sign(self.power_generation) == Ordering::Greater)
+            .then(|| self.power_margin / self.power_generation * 100.0)
+    }
+
+    /// Short status paragraph
+    pub fn summary(&self) -> String {
+        self.to_string()
+    }
+
+    /// Short status paragraph, with numbers formatted using the given options
+    pub fn summary_with(&self, format: &NumberFormat) -> String {
+        if self.factory_count == 0 {
+            return "No factories yet.".to_string();
+        }
+
+        let mut summary = format!(
+            "{} across {}, linked by {}.",
+            plural(self.production_line_count, "production line"),
+            plural(self.factory_count, "factory"),
+            plural(self.logistics_count, "logistics line"),
+        );
+
+        if !self.top_production.is_empty() {
+            summary += &format!(
+                " Top production: {}.",
+                format_rates(&self.top_production, format)
+            );
+        }
+
+        if self.biggest_deficits.is_empty() {
+            summary += " No item deficits.";
+        } else {
+            summary += &format!(
+                " Biggest deficits: {}.",
+                format_rates(&self.biggest_deficits, format)
+            );
+        }
+
+        summary += &format!(
+            " Power: {} generated, {} consumed",
+            format.power(self.power_generation),
+            format.power(self.power_consumption)
+        );
+        if balance_sign(self.power_margin) == Ordering::Less {
+            summary += &format!(", {} short.", format.power(-self.power_margin));
+        } else {
+            match self.power_margin_percent() {
+                Some(percent) => {
+                    summary += &format!(
+                        ", {} margin ({:.0}%).",
+                        format.power(self.power_margin),
+                        percent
+                    );
+                }
+                None => summary += ".",
+            }
+        }
+        summary
+    }
+}
+
+impl fmt::Display for ReportCard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.summary_with(&NumberFormat::default()))
+    }
+}
+
+fn rates(quantities: HashMap<Item, f64>, keep: impl Fn(f64) -> bool) -> Vec<ItemRate> {
+    quantities
+        .into_iter()
+        .filter(|(_, quantity)| keep(*quantity))
+        .map(|(item, quantity_per_min)| ItemRate {
+            item,
+            quantity_per_min,
+        })
+        .collect()
+}
+
+fn format_rates(rates: &[ItemRate], format: &NumberFormat) -> String {
+    rates
+        .iter()
+        .map(|rate| {
+            format!(
+                "{} {}",
+                item_name(rate.item),
+                format.rate(rate.quantity_per_min)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    match (count, noun.strip_suffix('y')) {
+        (1, _) => format!("1 {}", noun),
+        (_, Some(stem)) => format!("{} {}ies", count, stem),
+        (_, None) => format!("{} {}s", count, noun),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::RateUnit;
+    use crate::models::{
+        production_line::{MachineGroup, ProductionLine, ProductionLineRecipe},
+        Recipe,
+    };
+    use uuid::Uuid;
+
+    fn uuid_from_u64<T: From<Uuid>>(value: u64) -> T {
+        Uuid::from_u128(value as u128).into()
+    }
+
+    #[test]
+    fn test_empty_report_card() {
+        let report = ReportCard::build(std::iter::empty(), 0);
+        assert_eq!(report.summary(), "No factories yet.");
+    }
+
+    #[test]
+    fn test_report_card_summary() {
+        let mut factory = Factory::new(uuid_from_u64(1), "Smelter".into(), None);
+        let mut ingots =
+            ProductionLineRecipe::new(uuid_from_u64(2), "Ingots".into(), None, Recipe::IronIngot);
+        ingots
+            .add_machine_group(MachineGroup::new(2, 100.0, 0))
+            .unwrap();
+        factory.add_production_line(ProductionLine::ProductionLineRecipe(ingots));
+
+        let report = ReportCard::build([&factory], 0);
+
+        assert_eq!(report.top_production[0].item, Item::IronIngot);
+        assert_eq!(report.top_production[0].quantity_per_min, 60.0);
+        assert_eq!(report.biggest_deficits[0].item, Item::IronOre);
+        assert_eq!(report.power_margin, -8.0);
+        assert_eq!(
+            report.summary(),
+            "1 production line across 1 factory, linked by 0 logistics lines. \
+             Top production: Iron Ingot 60.0/min. Biggest deficits: Iron Ore -60.0/min. \
+             Power: 0.0 MW generated, 8.0 MW consumed, 8.0 MW short."
+        );
+        assert!(report
+            .summary_with(&NumberFormat::default().with_rate_unit(RateUnit::PerSecond))
+            .contains("Top production: Iron Ingot 1.0/s"));
+    }
+
+    #[test]
+    fn test_rounding_noise_is_not_a_deficit() {
+        let mut report = ReportCard::build(std::iter::empty(), 0);
+        report.factory_count = 1;
+        report.power_generation = 0.1 + 0.2;
+        report.power_consumption = 0.3;
+        report.power_margin = report.power_generation - report.power_consumption;
+        assert_ne!(report.power_margin, 0.0);
+        assert!(!report.summary().contains("short"));
+
+        report.power_generation = 1e-12;
+        assert_eq!(report.power_margin_percent(), None);
+    }
+}
@@ -0,0 +1,306 @@
+//! Registry of the map's finite resource nodes.
+//!
+//! Raw inputs that claim nodes are checked against these counts so plans cannot
+//! use more nodes of a purity than the world has. The default registry holds the
+//! Satisfactory 1.0 map counts; users can adjust them for modded maps.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{factory::Factory, item_name, raw_input::Purity, FactoryId, Item, RawInputId};
+
+/// Number of nodes available per purity for one resource
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeCounts {
+    pub impure: u32,
+    pub normal: u32,
+    pub pure: u32,
+}
+
+impl NodeCounts {
+    pub fn new(impure: u32, normal: u32, pure: u32) -> Self {
+        Self {
+            impure,
+            normal,
+            pure,
+        }
+    }
+
+    /// Nodes available for a purity
+    pub fn get(&self, purity: Purity) -> u32 {
+        match purity {
+            Purity::Impure => self.impure,
+            Purity::Normal => self.normal,
+            Purity::Pure => self.pure,
+        }
+    }
+
+    /// Total nodes across all purities
+    pub fn total(&self) -> u32 {
+        self.impure + self.normal + self.pure
+    }
+}
+
+/// Node counts per resource for the whole map
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourceNodeRegistry {
+    nodes: HashMap<Item, NodeCounts>,
+}
+
+impl Default for ResourceNodeRegistry {
+    fn default() -> Self {
+        let nodes = [
+            (Item::IronOre, NodeCounts::new(39, 42, 46)),
+            (Item::CopperOre, NodeCounts::new(13, 29, 13)),
+            (Item::Limestone, NodeCounts::new(15, 50, 29)),
+            (Item::Coal, NodeCounts::new(15, 31, 16)),
+            (Item::CateriumOre, NodeCounts::new(0, 9, 8)),
+            (Item::RawQuartz, NodeCounts::new(3, 7, 7)),
+            (Item::Sulfur, NodeCounts::new(6, 5, 5)),
+            (Item::Bauxite, NodeCounts::new(5, 6, 6)),
+            (Item::Uranium, NodeCounts::new(3, 2, 0)),
+            (Item::CrudeOil, NodeCounts::new(10, 12, 8)),
+        ];
+        Self {
+            nodes: nodes.into_iter().collect(),
+        }
+    }
+}
+
+impl ResourceNodeRegistry {
+    /// Node counts for a resource; resources missing from the registry have none
+    pub fn counts(&self, item: Item) -> NodeCounts {
+        self.nodes.get(&item).copied().unwrap_or_default()
+    }
+
+    /// Replace the node counts for a resource
+    pub fn set_counts(&mut self, item: Item, counts: NodeCounts) {
+        self.nodes.insert(item, counts);
+    }
+
+    /// Registered resources with their counts, sorted by item name
+    pub fn entries(&self) -> Vec<(Item, NodeCounts)> {
+        let mut entries: Vec<(Item, NodeCounts)> = self
+            .nodes
+            .iter()
+            .map(|(item, counts)| (*item, *counts))
+            .collect();
+        entries.sort_by(|a, b| item_name(a.0).cmp(item_name(b.0)));
+        entries
+    }
+
+    /// Compare the nodes claimed by raw inputs against the registry
+    pub fn validate_claims<'a>(
+        &self,
+        factories: impl IntoIterator<Item = &'a Factory>,
+    ) -> ResourceNodeReport {
+        let mut usage: Vec<NodeUsage> = Vec::new();
+        for (item, counts) in self.entries() {
+            for purity in [Purity::Impure, Purity::Normal, Purity::Pure] {
+                if counts.get(purity) > 0 {
+                    usage.push(NodeUsage::new(item, purity, counts.get(purity)));
+                }
+            }
+        }
+
+        for factory in factories {
+            for raw_input in factory.raw_inputs.values() {
+                let Some((purity, count)) = raw_input.claimed_nodes() else {
+                    continue;
+                };
+                let index = match usage
+                    .iter()
+                    .position(|entry| entry.item == raw_input.item && entry.purity == purity)
+                {
+                    Some(index) => index,
+                    None => {
+                        usage.push(NodeUsage::new(raw_input.item, purity, 0));
+                        usage.len() - 1
+                    }
+                };
+                let entry = &mut usage[index];
+                entry.claimed += count;
+                entry.claims.push(NodeClaim {
+                    factory_id: factory.id,
+                    factory_name: factory.name.clone(),
+                    raw_input_id: raw_input.id,
+                    count,
+                });
+            }
+        }
+
+        for entry in &mut usage {
+            entry.over_claimed = entry.claimed > entry.available;
+            entry
+                .claims
+                .sort_by(|a, b| a.factory_name.cmp(&b.factory_name));
+        }
+        usage.sort_by(|a, b| {
+            item_name(a.item)
+                .cmp(item_name(b.item))
+                .then_with(|| purity_rank(a.purity).cmp(&purity_rank(b.purity)))
+        });
+
+        ResourceNodeReport {
+            is_valid: usage.iter().all(|entry| !entry.over_claimed),
+            usage,
+        }
+    }
+}
+
+fn purity_rank(purity: Purity) -> u8 {
+    match purity {
+        Purity::Impure => 0,
+        Purity::Normal => 1,
+        Purity::Pure => 2,
+    }
+}
+
+/// A raw input claiming nodes of a given resource and purity
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeClaim {
+    pub factory_id: FactoryId,
+    pub factory_name: String,
+    pub raw_input_id: RawInputId,
+    pub count: u32,
+}
+
+/// Claimed versus available nodes for one resource and purity
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeUsage {
+    pub item: Item,
+    pub purity: Purity,
+    pub available: u32,
+    pub claimed: u32,
+    /// More nodes are claimed than the world has
+    pub over_claimed: bool,
+    pub claims: Vec<NodeClaim>,
+}
+
+impl NodeUsage {
+    fn new(item: Item, purity: Purity, available: u32) -> Self {
+        Self {
+            item,
+            purity,
+            available,
+            claimed: 0,
+            over_claimed: false,
+            claims: Vec::new(),
+        }
+    }
+}
+
+/// Node usage across all factories, flagging over-claimed resources
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourceNodeReport {
+    pub is_valid: bool,
+    pub usage: Vec<NodeUsage>,
+}
+
+impl ResourceNodeReport {
+    /// Entries claiming more nodes than available
+    pub fn violations(&self) -> impl Iterator<Item = &NodeUsage> {
+        self.usage.iter().filter(|entry| entry.over_claimed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ExtractorType, RawInput};
+    use uuid::Uuid;
+
+    fn uuid_from_u64<T: From<Uuid>>(value: u64) -> T {
+        Uuid::from_u128(value as u128).into()
+    }
+
+    fn claiming_miner(id: u64, purity: Purity, count: u32) -> RawInput {
+        let mut raw_input = RawInput::new(
+            uuid_from_u64(id),
+            ExtractorType::MinerMk2,
+            Item::IronOre,
+            Some(purity),
+            100.0,
+            count,
+        )
+        .unwrap();
+        raw_input.set_claims_node(true).unwrap();
+        raw_input
+    }
+
+    #[test]
+    fn test_claims_within_availability() {
+        let registry = ResourceNodeRegistry::default();
+        let mut factory = Factory::new(uuid_from_u64(1), "Iron".into(), None);
+        factory
+            .add_raw_input(claiming_miner(2, Purity::Pure, 3))
+            .unwrap();
+
+        let report = registry.validate_claims([&factory]);
+
+        assert!(report.is_valid);
+        let pure_iron = report
+            .usage
+            .iter()
+            .find(|entry| entry.item == Item::IronOre && entry.purity == Purity::Pure)
+            .unwrap();
+        assert_eq!(pure_iron.available, 46);
+        assert_eq!(pure_iron.claimed, 3);
+        assert_eq!(pure_iron.claims[0].factory_name, "Iron");
+    }
+
+    #[test]
+    fn test_over_claimed_nodes_are_flagged() {
+        let mut registry = ResourceNodeRegistry::default();
+        registry.set_counts(Item::IronOre, NodeCounts::new(1, 1, 2));
+
+        let mut factory_a = Factory::new(uuid_from_u64(1), "A".into(), None);
+        factory_a
+            .add_raw_input(claiming_miner(2, Purity::Pure, 2))
+            .unwrap();
+        let mut factory_b = Factory::new(uuid_from_u64(3), "B".into(), None);
+        factory_b
+            .add_raw_input(claiming_miner(4, Purity::Pure, 1))
+            .unwrap();
+        // Unclaimed raw inputs are not counted
+        factory_b
+            .add_raw_input(
+                RawInput::new(
+                    uuid_from_u64(5),
+                    ExtractorType::MinerMk1,
+                    Item::IronOre,
+                    Some(Purity::Pure),
+                    100.0,
+                    10,
+                )
+                .unwrap(),
+            )
+            .unwrap();
+
+        let report = registry.validate_claims([&factory_a, &factory_b]);
+
+        assert!(!report.is_valid);
+        let violations: Vec<&NodeUsage> = report.violations().collect();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].claimed, 3);
+        assert_eq!(violations[0].available, 2);
+        assert_eq!(violations[0].claims.len(), 2);
+    }
+
+    #[test]
+    fn test_claims_require_node_extractors() {
+        let mut water = RawInput::new(
+            uuid_from_u64(1),
+            ExtractorType::WaterExtractor,
+            Item::Water,
+            None,
+            100.0,
+            1,
+        )
+        .unwrap();
+
+        assert!(water.set_claims_node(true).is_err());
+        assert!(!water.claims_node);
+    }
+}
@@ -0,0 +1,328 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{
+    all_items, all_recipes,
+    custom_recipe::CustomRecipe,
+    factory::Factory,
+    logistics::{LogisticsFlux, Transport},
+    production_line::ProductionLine,
+    CustomRecipeId, FactoryId, Item, LogisticsId, ProductionLineId, Recipe,
+};
+
+/// What a search hit points to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum SearchTarget {
+    Factory {
+        id: FactoryId,
+    },
+    ProductionLine {
+        id: ProductionLineId,
+        factory_id: FactoryId,
+    },
+    Logistics {
+        id: LogisticsId,
+        from_factory: FactoryId,
+        to_factory: FactoryId,
+    },
+    Item {
+        item: Item,
+    },
+    Recipe {
+        recipe: Recipe,
+    },
+    CustomRecipe {
+        id: CustomRecipeId,
+    },
+}
+
+/// Text of a hit the query was found in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SearchField {
+    Name,
+    Description,
+    Notes,
+    Tag,
+    /// Transport details of a logistics line
+    Details,
+}
+
+/// One entity matching a search query
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchHit {
+    #[serde(flatten)]
+    pub target: SearchTarget,
+    pub name: String,
+    /// Best matching field; names rank before any other field
+    pub field: SearchField,
+    pub matched_text: String,
+}
+
+/// How well a hit matches, best first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Rank {
+    ExactName,
+    NamePrefix,
+    Name,
+    OtherField,
+}
+
+/// Case-insensitive substring search over a save and the game data
+struct Search {
+    needle: String,
+    hits: Vec<(Rank, SearchHit)>,
+}
+
+impl Search {
+    /// Record a hit for the first field containing the query, if any
+    ///
+    /// `fields` lists the entity's searchable texts, names first; an entity
+    /// can have several, such as a transport's name and its ID.
+    fn check(&mut self, target: SearchTarget, name: &str, fields: &[(SearchField, &str)]) {
+        let found = fields
+            .iter()
+            .find(|(_, text)| text.to_lowercase().contains(&self.needle));
+        let Some(&(field, text)) = found else {
+            return;
+        };
+
+        let rank = match field {
+            SearchField::Name => {
+                let name = text.to_lowercase();
+                if name == self.needle {
+                    Rank::ExactName
+                } else if name.starts_with(&self.needle) {
+                    Rank::NamePrefix
+                } else {
+                    Rank::Name
+                }
+            }
+            _ => Rank::OtherField,
+        };
+        self.hits.push((
+            rank,
+            SearchHit {
+                target,
+                name: name.to_string(),
+                field,
+                matched_text: text.to_string(),
+            },
+        ));
+    }
+}
+
+/// Search factories, their production lines, logistics lines, items and recipes
+///
+/// Matching is a case-insensitive substring test on names, descriptions,
+/// notes, tags and transport details. Each entity appears at most once.
+///
+/// # Arguments
+///
+/// * `query` - Text to look for; surrounding whitespace is ignored
+///
+/// # Returns
+///
+/// Hits ordered by exact name match, then name prefix, then any other name
+/// match, then matches in other fields, alphabetically within each group;
+/// nothing for a blank query
+pub fn search<'a>(
+    query: &str,
+    factories: impl IntoIterator<Item = &'a Factory>,
+    logistics_lines: impl IntoIterator<Item = &'a LogisticsFlux>,
+    custom_recipes: impl IntoIterator<Item = &'a CustomRecipe>,
+) -> Vec<SearchHit> {
+    let needle = query.trim().to_lowercase();
+    if needle.is_empty() {
+        return Vec::new();
+    }
+    let mut search = Search {
+        needle,
+        hits: Vec::new(),
+    };
+
+    for factory in factories {
+        let mut fields = vec![(SearchField::Name, factory.name.as_str())];
+        fields.extend(
+            factory
+                .description
+                .as_deref()
+                .map(|text| (SearchField::Description, text)),
+        );
+        fields.extend(
+            factory
+                .notes
+                .as_deref()
+                .map(|text| (SearchField::Notes, text)),
+        );
+        fields.extend(
+            factory
+                .tags
+                .iter()
+                .map(|tag| (SearchField::Tag, tag.as_str())),
+        );
+        search.check(
+            SearchTarget::Factory { id: factory.id },
+            &factory.name,
+            &fields,
+        );
+
+        for line in factory.production_lines.values() {
+            let description = match line {
+                ProductionLine::ProductionLineRecipe(line) => line.description.as_deref(),
+                ProductionLine::ProductionLineBlueprint(blueprint) => {
+                    blueprint.description.as_deref()
+                }
+            };
+            let mut fields = vec![(SearchField::Name, line.name())];
+            fields.extend(description.map(|text| (SearchField::Description, text)));
+            search.check(
+                SearchTarget::ProductionLine {
+                    id: line.id(),
+                    factory_id: factory.id,
+                },
+                line.name(),
+                &fields,
+            );
+        }
+    }
+
+    for line in logistics_lines {
+        let transport_id = line.transport_type.get_transport_id();
+        let name = line
+            .transport_type
+            .get_transport_name()
+            .unwrap_or_else(|| transport_id.clone());
+        let details = line.transport_details.to_string();
+        search.check(
+            SearchTarget::Logistics {
+                id: line.id,
+                from_factory: line.from_factory,
+                to_factory: line.to_factory,
+            },
+            &name,
+            &[
+                (SearchField::Name, &name),
+                (SearchField::Name, &transport_id),
+                (SearchField::Details, &details),
+            ],
+        );
+    }
+
+    for &(item, name) in all_items() {
+        search.check(
+            SearchTarget::Item { item },
+            name,
+            &[(SearchField::Name, name)],
+        );
+    }
+    for info in all_recipes() {
+        search.check(
+            SearchTarget::Recipe {
+                recipe: info.recipe,
+            },
+            info.name,
+            &[(SearchField::Name, info.name)],
+        );
+    }
+    for recipe in custom_recipes {
+        search.check(
+            SearchTarget::CustomRecipe { id: recipe.id },
+            &recipe.name,
+            &[(SearchField::Name, &recipe.name)],
+        );
+    }
+
+    let mut hits = search.hits;
+    hits.sort_by(|(a_rank, a), (b_rank, b)| {
+        a_rank
+            .cmp(b_rank)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    hits.into_iter().map(|(_, hit)| hit).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        logistics::{TransportType, TruckTransport},
+        production_line::ProductionLineBuilder,
+        UnknownFields,
+    };
+    use uuid::Uuid;
+
+    fn uuid_from_u64<T: From<Uuid>>(value: u64) -> T {
+        Uuid::from_u128(value as u128).into()
+    }
+
+    #[test]
+    fn test_search_ranks_names_first() {
+        let works = Factory::builder("Motor Works")
+            .id(uuid_from_u64(1))
+            .production_line(
+                ProductionLineBuilder::recipe("Rotors", Recipe::Rotor)
+                    .id(uuid_from_u64(2))
+                    .description("Feeds the motor line"),
+            )
+            .build()
+            .unwrap();
+        let hub = Factory::builder("Hub")
+            .id(uuid_from_u64(3))
+            .notes("Send MOTORS north")
+            .tags(["north-wing"])
+            .build()
+            .unwrap();
+        let line = LogisticsFlux {
+            id: uuid_from_u64(4),
+            from_factory: works.id,
+            to_factory: hub.id,
+            transport_type: TransportType::Truck(TruckTransport::new(7, Item::Motor, 10.0)),
+            transport_details: "Motor parts".into(),
+            timestamps: Default::default(),
+            unknown_fields: UnknownFields::new(),
+        };
+
+        let hits = search("  motor ", [&works, &hub], [&line], []);
+        assert_eq!(hits[0].target, SearchTarget::Item { item: Item::Motor });
+        assert_eq!(
+            hits[1].target,
+            SearchTarget::Recipe {
+                recipe: Recipe::Motor
+            }
+        );
+        // Game data also has e.g. Turbo Motor; keep the save's own hits
+        let from_save: Vec<(&str, SearchField)> = hits
+            .iter()
+            .filter(|hit| {
+                !matches!(
+                    hit.target,
+                    SearchTarget::Item { .. } | SearchTarget::Recipe { .. }
+                )
+            })
+            .map(|hit| (hit.name.as_str(), hit.field))
+            .collect();
+        assert_eq!(
+            from_save,
+            vec![
+                ("Motor Works", SearchField::Name),
+                ("Hub", SearchField::Notes),
+                ("Rotors", SearchField::Description),
+                ("TRK-7", SearchField::Details),
+            ]
+        );
+        let notes = hits.iter().find(|hit| hit.name == "Hub").unwrap();
+        assert_eq!(notes.matched_text, "Send MOTORS north");
+
+        // The transport ID is searchable too
+        let hits = search("trk-7", [], [&line], []);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].field, SearchField::Name);
+
+        assert_eq!(
+            search("north-wing", [&hub], [], [])[0].field,
+            SearchField::Tag
+        );
+        assert!(search("   ", [&works], [], []).is_empty());
+    }
+}
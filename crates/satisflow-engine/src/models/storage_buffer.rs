@@ -0,0 +1,181 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::models::{item_name, Item, StorageBufferId};
+use crate::rate::is_zero;
+
+/// Storage containers a factory fills or drains, e.g. to run a line off stock
+///
+/// The buffer takes up the factory's net rate of its item: a surplus fills it
+/// and a deficit is drawn from it until it runs out.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StorageBuffer {
+    pub id: StorageBufferId,
+    pub item: Item,
+    /// Slots of all containers together, e.g. 48 for two Storage Containers
+    pub capacity_stacks: u32,
+    /// Items currently stored
+    pub stored: f64,
+}
+
+impl StorageBuffer {
+    /// Create an empty buffer
+    pub fn new(
+        id: StorageBufferId,
+        item: Item,
+        capacity_stacks: u32,
+    ) -> Result<Self, StorageBufferError> {
+        let buffer = Self {
+            id,
+            item,
+            capacity_stacks,
+            stored: 0.0,
+        };
+        buffer.validate()?;
+        Ok(buffer)
+    }
+
+    /// Set the items currently stored
+    pub fn with_stored(mut self, stored: f64) -> Self {
+        self.stored = stored;
+        self
+    }
+
+    /// Number of items the buffer holds when full; 0 for fluids
+    pub fn capacity(&self) -> f64 {
+        let stack_size = self.item.stack_size().unwrap_or(0);
+        (self.capacity_stacks * stack_size) as f64
+    }
+
+    /// Validate the buffer configuration
+    pub fn validate(&self) -> Result<(), StorageBufferError> {
+        if self.item.stack_size().is_none() {
+            return Err(StorageBufferError::FluidItem { item: self.item });
+        }
+        if self.capacity_stacks == 0 {
+            return Err(StorageBufferError::InvalidCapacity);
+        }
+        if !self.stored.is_finite() || self.stored < 0.0 || self.stored > self.capacity() {
+            return Err(StorageBufferError::InvalidStored {
+                stored: self.stored,
+                capacity: self.capacity(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Fill state of the buffer while the factory's net rate of its item is `net_rate`
+    pub fn status(&self, net_rate: f64) -> BufferStatus {
+        BufferStatus::new(self, net_rate)
+    }
+}
+
+/// Errors raised by invalid storage buffer configurations
+#[derive(Debug, Clone, PartialEq)]
+pub enum StorageBufferError {
+    FluidItem { item: Item },
+    InvalidCapacity,
+    InvalidStored { stored: f64, capacity: f64 },
+}
+
+impl fmt::Display for StorageBufferError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageBufferError::FluidItem { item } => {
+                write!(
+                    f,
+                    "{} is a fluid and cannot be stored in containers",
+                    item_name(*item)
+                )
+            }
+            StorageBufferError::InvalidCapacity => {
+                write!(f, "Storage buffer must have at least one stack")
+            }
+            StorageBufferError::InvalidStored { stored, capacity } => {
+                write!(
+                    f,
+                    "Stored quantity {} is invalid. Must be between 0 and {}",
+                    stored, capacity
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for StorageBufferError {}
+
+/// Fill state of a storage buffer under its factory's net rate
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BufferStatus {
+    pub buffer_id: StorageBufferId,
+    pub item: Item,
+    pub stored: f64,
+    pub capacity: f64,
+    /// Net rate of the item in the factory, per minute; positive fills the buffer
+    pub net_rate: f64,
+    /// Minutes until the buffer is empty; only set while draining
+    pub minutes_until_empty: Option<f64>,
+    /// Minutes until the buffer is full; only set while filling
+    pub minutes_until_full: Option<f64>,
+}
+
+impl BufferStatus {
+    fn new(buffer: &StorageBuffer, net_rate: f64) -> Self {
+        let capacity = buffer.capacity();
+        let draining = !is_zero(net_rate) && net_rate < 0.0;
+        let filling = !is_zero(net_rate) && net_rate > 0.0;
+        Self {
+            buffer_id: buffer.id,
+            item: buffer.item,
+            stored: buffer.stored,
+            capacity,
+            net_rate,
+            minutes_until_empty: draining.then(|| buffer.stored / -net_rate),
+            minutes_until_full: filling.then(|| (capacity - buffer.stored).max(0.0) / net_rate),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capacity_and_validate() {
+        let buffer = StorageBuffer::new(StorageBufferId::new_v4(), Item::IronOre, 24).unwrap();
+        assert_eq!(buffer.capacity(), 12_000.0);
+        assert!(buffer.clone().with_stored(12_000.0).validate().is_ok());
+        assert!(matches!(
+            buffer.with_stored(12_001.0).validate(),
+            Err(StorageBufferError::InvalidStored { .. })
+        ));
+
+        assert_eq!(
+            StorageBuffer::new(StorageBufferId::new_v4(), Item::IronOre, 0),
+            Err(StorageBufferError::InvalidCapacity)
+        );
+        assert_eq!(
+            StorageBuffer::new(StorageBufferId::new_v4(), Item::Water, 1),
+            Err(StorageBufferError::FluidItem { item: Item::Water })
+        );
+    }
+
+    #[test]
+    fn test_fill_and_drain_times() {
+        let buffer = StorageBuffer::new(StorageBufferId::new_v4(), Item::Motor, 24)
+            .unwrap()
+            .with_stored(600.0);
+
+        let draining = buffer.status(-20.0);
+        assert_eq!(draining.minutes_until_empty, Some(30.0));
+        assert_eq!(draining.minutes_until_full, None);
+
+        let filling = buffer.status(5.0);
+        assert_eq!(filling.minutes_until_full, Some(120.0));
+        assert_eq!(filling.minutes_until_empty, None);
+
+        let balanced = buffer.status(1e-9);
+        assert_eq!(balanced.minutes_until_empty, None);
+        assert_eq!(balanced.minutes_until_full, None);
+    }
+}
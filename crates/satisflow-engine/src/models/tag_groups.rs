@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{factory::Factory, FactoryId};
+
+/// Dashboard totals of the factories sharing a tag
+///
+/// A factory with several tags counts in each of their groups. Factories
+/// without tags are reported together with `tag: None`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagGroupStats {
+    pub tag: Option<String>,
+    pub factory_ids: Vec<FactoryId>,
+    pub archived_factories: usize,
+    pub total_production_lines: usize,
+    pub power_generation: f64,
+    pub power_consumption: f64,
+    pub power_balance: f64,
+}
+
+impl TagGroupStats {
+    pub fn new(tag: Option<String>) -> Self {
+        Self {
+            tag,
+            factory_ids: Vec::new(),
+            archived_factories: 0,
+            total_production_lines: 0,
+            power_generation: 0.0,
+            power_consumption: 0.0,
+            power_balance: 0.0,
+        }
+    }
+
+    /// Add a factory to the group totals
+    pub fn add_factory(&mut self, factory: &Factory) {
+        self.factory_ids.push(factory.id);
+        if factory.archived {
+            self.archived_factories += 1;
+        }
+        self.total_production_lines += factory.production_lines.len();
+        self.power_generation += factory.total_power_generation();
+        self.power_consumption += factory.total_power_consumption();
+        self.power_balance = self.power_generation - self.power_consumption;
+    }
+}
@@ -0,0 +1,141 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{
+    production_line::{ProductionLine, ProductionLineBlueprint},
+    FactoryId, Item, ProductionLineId, TemplateVersion,
+};
+use crate::rate;
+
+/// A blueprint in a factory created from a library template
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateInstance {
+    pub factory_id: FactoryId,
+    pub factory_name: String,
+    pub instance_id: ProductionLineId,
+    pub instance_name: String,
+    /// Template version the instance currently follows
+    pub template_id: ProductionLineId,
+    pub version: TemplateVersion,
+    /// Whether the instance already matches the latest version
+    pub up_to_date: bool,
+}
+
+/// Net rate of one item before and after an update, negative when consumed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemRateChange {
+    pub item: Item,
+    pub before: f64,
+    pub after: f64,
+}
+
+/// What updating one instance to the latest template version changes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstanceUpdate {
+    pub instance_id: ProductionLineId,
+    pub instance_name: String,
+    pub from_version: TemplateVersion,
+    pub to_version: TemplateVersion,
+    pub machines_before: u32,
+    pub machines_after: u32,
+    pub power_before: f64,
+    pub power_after: f64,
+    /// Only items whose rate changes
+    pub rate_changes: Vec<ItemRateChange>,
+}
+
+impl InstanceUpdate {
+    /// Compare an instance with the template version it would be updated to
+    pub fn new(instance: &ProductionLineBlueprint, latest: &ProductionLineBlueprint) -> Self {
+        let before = ProductionLine::ProductionLineBlueprint(instance.clone());
+        let after = ProductionLine::ProductionLineBlueprint(latest.clone());
+        let version = |blueprint: &ProductionLineBlueprint| {
+            blueprint.revision.clone().unwrap_or_default().version
+        };
+
+        let mut rate_changes: Vec<ItemRateChange> = Vec::new();
+        for (item, rate) in net_rates(&before) {
+            rate_changes.push(ItemRateChange {
+                item,
+                before: rate,
+                after: 0.0,
+            });
+        }
+        for (item, rate) in net_rates(&after) {
+            match rate_changes.iter_mut().find(|change| change.item == item) {
+                Some(change) => change.after = rate,
+                None => rate_changes.push(ItemRateChange {
+                    item,
+                    before: 0.0,
+                    after: rate,
+                }),
+            }
+        }
+        rate_changes.retain(|change| !rate::is_zero(change.after - change.before));
+
+        Self {
+            instance_id: instance.id,
+            instance_name: instance.name.clone(),
+            from_version: version(instance),
+            to_version: version(latest),
+            machines_before: before.total_machines(),
+            machines_after: after.total_machines(),
+            power_before: before.total_power_consumption(),
+            power_after: after.total_power_consumption(),
+            rate_changes,
+        }
+    }
+}
+
+/// Instance updates within one factory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FactoryPropagation {
+    pub factory_id: FactoryId,
+    pub factory_name: String,
+    pub updates: Vec<InstanceUpdate>,
+}
+
+/// Result of updating every instance of a template to its latest version
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplatePropagation {
+    /// Latest version the instances are updated to
+    pub template_id: ProductionLineId,
+    pub version: TemplateVersion,
+    /// When set, nothing was changed and `factories` is a preview
+    pub dry_run: bool,
+    pub factories: Vec<FactoryPropagation>,
+}
+
+/// Rebuild an instance from a newer template version
+///
+/// The instance keeps its ID, name and enabled flag; inner lines get fresh IDs.
+pub fn updated_instance(
+    instance: &ProductionLineBlueprint,
+    latest: &ProductionLineBlueprint,
+) -> ProductionLineBlueprint {
+    let mut updated = latest.clone();
+    updated.id = instance.id;
+    updated.name = instance.name.clone();
+    updated.enabled = instance.enabled;
+    updated.template_id = Some(latest.id);
+    for line in &mut updated.production_lines {
+        line.id = ProductionLineId::new_v4();
+    }
+    updated
+}
+
+/// Outputs minus inputs per item, in order of first appearance
+fn net_rates(line: &ProductionLine) -> Vec<(Item, f64)> {
+    let mut rates: Vec<(Item, f64)> = Vec::new();
+    let flows = line.output_rate().into_iter().chain(
+        line.input_rate()
+            .into_iter()
+            .map(|(item, rate)| (item, -rate)),
+    );
+    for (item, rate) in flows {
+        match rates.iter_mut().find(|(existing, _)| *existing == item) {
+            Some((_, total)) => *total += rate,
+            None => rates.push((item, rate)),
+        }
+    }
+    rates
+}
@@ -0,0 +1,109 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::ProductionLineId;
+
+/// Semantic version of a blueprint template, serialized as `"major.minor.patch"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TemplateVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl TemplateVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Parse a version string like "1.2.0"
+    pub fn parse(version: &str) -> Result<Self, String> {
+        let parts: Vec<u32> = version
+            .trim()
+            .split('.')
+            .map(|part| part.parse())
+            .collect::<Result<_, _>>()
+            .map_err(|_| format!("Invalid template version '{}'", version))?;
+
+        match parts[..] {
+            [major, minor, patch] => Ok(Self::new(major, minor, patch)),
+            _ => Err(format!(
+                "Invalid template version '{}', expected major.minor.patch",
+                version
+            )),
+        }
+    }
+
+    /// Version given to an update that doesn't pick one
+    pub fn next_minor(&self) -> Self {
+        Self::new(self.major, self.minor + 1, 0)
+    }
+}
+
+impl Default for TemplateVersion {
+    fn default() -> Self {
+        Self::new(1, 0, 0)
+    }
+}
+
+impl fmt::Display for TemplateVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl TryFrom<String> for TemplateVersion {
+    type Error = String;
+
+    fn try_from(version: String) -> Result<Self, Self::Error> {
+        Self::parse(&version)
+    }
+}
+
+impl From<TemplateVersion> for String {
+    fn from(version: TemplateVersion) -> Self {
+        version.to_string()
+    }
+}
+
+/// Where a blueprint template sits in its version history
+///
+/// Instances keep the revision of the template they were created from.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TemplateRevision {
+    pub version: TemplateVersion,
+    /// Template this version was made from, `None` for the first one
+    pub parent_id: Option<ProductionLineId>,
+    /// What changed since the parent version
+    pub changelog: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_order() {
+        let version = TemplateVersion::parse("1.2.3").unwrap();
+        assert_eq!(version, TemplateVersion::new(1, 2, 3));
+        assert_eq!(version.to_string(), "1.2.3");
+        assert_eq!(version.next_minor(), TemplateVersion::new(1, 3, 0));
+        assert!(TemplateVersion::new(1, 10, 0) > TemplateVersion::new(1, 9, 4));
+
+        assert!(TemplateVersion::parse("1.2").is_err());
+        assert!(TemplateVersion::parse("1.x.0").is_err());
+    }
+
+    #[test]
+    fn test_serializes_as_string() {
+        let json = serde_json::to_string(&TemplateVersion::new(2, 0, 1)).unwrap();
+        assert_eq!(json, "\"2.0.1\"");
+        assert!(serde_json::from_str::<TemplateVersion>("\"2\"").is_err());
+    }
+}
@@ -0,0 +1,230 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{game_data::MachineType, recipe_info, recipe_name, Item, Recipe};
+
+/// Last HUB tier of the game
+pub const MAX_HUB_TIER: u8 = 9;
+
+/// MAM research trees that gate recipes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MamResearch {
+    AlienOrganisms,
+    AlienTechnology,
+    Caterium,
+    Mycelia,
+    PowerSlugs,
+    Quartz,
+    Sulfur,
+}
+
+impl MamResearch {
+    /// Items that can only be made once the research is completed
+    pub fn items(&self) -> &'static [Item] {
+        match self {
+            MamResearch::AlienOrganisms => &[Item::AlienProtein, Item::AlienDNACapsule],
+            MamResearch::AlienTechnology => &[
+                Item::FicsiteIngot,
+                Item::FicsiteTrigon,
+                Item::AlienPowerMatrix,
+            ],
+            MamResearch::Caterium => &[
+                Item::CateriumIngot,
+                Item::Quickwire,
+                Item::AILimiter,
+                Item::HighSpeedConnector,
+            ],
+            MamResearch::Mycelia => &[Item::Fabric],
+            MamResearch::PowerSlugs => &[Item::PowerShard],
+            MamResearch::Quartz => &[Item::QuartzCrystal, Item::Silica, Item::CrystalOscillator],
+            MamResearch::Sulfur => &[Item::BlackPowder, Item::CompactedCoal, Item::Turbofuel],
+        }
+    }
+
+    fn unlocks(&self, item: Item) -> bool {
+        self.items().contains(&item)
+    }
+}
+
+const ALL_RESEARCH: [MamResearch; 7] = [
+    MamResearch::AlienOrganisms,
+    MamResearch::AlienTechnology,
+    MamResearch::Caterium,
+    MamResearch::Mycelia,
+    MamResearch::PowerSlugs,
+    MamResearch::Quartz,
+    MamResearch::Sulfur,
+];
+
+/// HUB tier whose milestones unlock making an item; 0 for items available from the start
+pub fn item_unlock_tier(item: Item) -> u8 {
+    match item {
+        Item::SteelIngot => 3,
+        Item::Plastic
+        | Item::Rubber
+        | Item::Fuel
+        | Item::HeavyOilResidue
+        | Item::PolymerResin
+        | Item::PetroleumCoke => 5,
+        Item::AluminaSolution | Item::AluminumScrap | Item::AluminumIngot => 7,
+        Item::NitrogenGas
+        | Item::NitricAcid
+        | Item::EncasedUraniumCell
+        | Item::UraniumFuelRod
+        | Item::NonFissileUranium
+        | Item::PlutoniumPellet => 8,
+        Item::Ficsonium | Item::SingularityCell => 9,
+        _ => 0,
+    }
+}
+
+fn recipe_items(recipe: Recipe) -> impl Iterator<Item = Item> {
+    let info = recipe_info(recipe);
+    info.inputs
+        .iter()
+        .chain(info.outputs.iter())
+        .map(|(item, _)| *item)
+}
+
+/// HUB tier needed before a recipe can be used
+///
+/// A recipe needs its machine and every item it uses or makes.
+pub fn recipe_unlock_tier(recipe: Recipe) -> u8 {
+    recipe_items(recipe)
+        .map(item_unlock_tier)
+        .chain(std::iter::once(recipe_info(recipe).machine.unlock_tier()))
+        .max()
+        .unwrap_or(0)
+}
+
+/// MAM research needed before a recipe can be used
+pub fn recipe_research(recipe: Recipe) -> Vec<MamResearch> {
+    ALL_RESEARCH
+        .iter()
+        .filter(|research| recipe_items(recipe).any(|item| research.unlocks(item)))
+        .copied()
+        .collect()
+}
+
+/// How far a save has progressed through the HUB milestones and the MAM
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TierProgress {
+    /// Highest HUB tier whose milestones are completed
+    pub hub_tier: u8,
+    #[serde(default)]
+    pub mam_research: Vec<MamResearch>,
+    /// Alternate recipes unlocked from hard drives
+    #[serde(default)]
+    pub alternate_recipes: Vec<Recipe>,
+}
+
+impl TierProgress {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.hub_tier > MAX_HUB_TIER {
+            return Err(format!(
+                "HUB tier must be at most {}, got {}",
+                MAX_HUB_TIER, self.hub_tier
+            ));
+        }
+        if let Some(recipe) = self
+            .alternate_recipes
+            .iter()
+            .find(|recipe| !recipe_info(**recipe).is_alternate())
+        {
+            return Err(format!(
+                "'{}' is not an alternate recipe",
+                recipe_name(*recipe)
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn is_machine_unlocked(&self, machine: MachineType) -> bool {
+        machine.unlock_tier() <= self.hub_tier
+    }
+
+    pub fn is_recipe_unlocked(&self, recipe: Recipe) -> bool {
+        self.missing_unlocks(recipe).is_empty()
+    }
+
+    /// Describe what is still needed before a recipe can be used
+    ///
+    /// # Returns
+    ///
+    /// One human-readable message per missing unlock, empty if the recipe is available
+    pub fn missing_unlocks(&self, recipe: Recipe) -> Vec<String> {
+        let name = recipe_name(recipe);
+        let mut missing = Vec::new();
+
+        let tier = recipe_unlock_tier(recipe);
+        if tier > self.hub_tier {
+            missing.push(format!(
+                "{} needs HUB tier {} (completed: {})",
+                name, tier, self.hub_tier
+            ));
+        }
+        for research in recipe_research(recipe) {
+            if !self.mam_research.contains(&research) {
+                missing.push(format!("{} needs the {:?} MAM research", name, research));
+            }
+        }
+        if recipe_info(recipe).is_alternate() && !self.alternate_recipes.contains(&recipe) {
+            missing.push(format!("{} has not been unlocked from a hard drive", name));
+        }
+
+        missing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recipe_requirements() {
+        assert_eq!(recipe_unlock_tier(Recipe::IronPlate), 0);
+        assert_eq!(recipe_unlock_tier(Recipe::SteelBeam), 3);
+        assert_eq!(recipe_unlock_tier(Recipe::Computer), 5);
+        assert_eq!(
+            recipe_research(Recipe::AILimiter),
+            vec![MamResearch::Caterium]
+        );
+        assert!(recipe_research(Recipe::IronPlate).is_empty());
+    }
+
+    #[test]
+    fn test_missing_unlocks() {
+        let mut progress = TierProgress {
+            hub_tier: 2,
+            ..TierProgress::default()
+        };
+
+        assert!(progress.is_recipe_unlocked(Recipe::ReinforcedIronPlate));
+        assert!(progress.is_machine_unlocked(MachineType::Assembler));
+        assert!(!progress.is_machine_unlocked(MachineType::Foundry));
+        assert_eq!(progress.missing_unlocks(Recipe::AILimiter).len(), 1);
+        assert_eq!(
+            progress.missing_unlocks(Recipe::AlternateBoltedIronPlate),
+            vec!["Alternate: Bolted Iron Plate has not been unlocked from a hard drive"]
+        );
+
+        progress.mam_research.push(MamResearch::Caterium);
+        progress
+            .alternate_recipes
+            .push(Recipe::AlternateBoltedIronPlate);
+        assert!(progress.is_recipe_unlocked(Recipe::AILimiter));
+        assert!(progress.is_recipe_unlocked(Recipe::AlternateBoltedIronPlate));
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut progress = TierProgress {
+            hub_tier: 10,
+            ..TierProgress::default()
+        };
+        assert!(progress.validate().is_err());
+
+        progress.hub_tier = 4;
+        progress.alternate_recipes.push(Recipe::IronPlate);
+        assert!(progress.validate().is_err());
+    }
+}
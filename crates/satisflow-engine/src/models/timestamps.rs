@@ -0,0 +1,106 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::FactoryId;
+
+/// Creation and last modification times of an entity
+///
+/// Entities loaded from saves that predate timestamps default to the Unix epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Timestamps {
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Timestamps {
+    /// Timestamps for an entity created right now
+    pub fn now() -> Self {
+        let now = Utc::now();
+        Self {
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Mark the entity as modified right now
+    pub fn touch(&mut self) {
+        self.updated_at = Utc::now();
+    }
+}
+
+impl Default for Timestamps {
+    fn default() -> Self {
+        Self {
+            created_at: DateTime::UNIX_EPOCH,
+            updated_at: DateTime::UNIX_EPOCH,
+        }
+    }
+}
+
+/// Kind of entity listed in the recent changes feed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChangedEntity {
+    Factory,
+    ProductionLine,
+    Logistics,
+}
+
+/// An entry of the recent changes feed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentChange {
+    pub entity: ChangedEntity,
+    pub id: Uuid,
+    pub name: String,
+    /// Owning factory for production lines, source factory for logistics
+    pub factory_id: FactoryId,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl RecentChange {
+    pub fn new(
+        entity: ChangedEntity,
+        id: impl Into<Uuid>,
+        name: String,
+        factory_id: FactoryId,
+        timestamps: Timestamps,
+    ) -> Self {
+        Self {
+            entity,
+            id: id.into(),
+            name,
+            factory_id,
+            created_at: timestamps.created_at,
+            updated_at: timestamps.updated_at,
+        }
+    }
+
+    /// Whether the entity was modified after being created
+    pub fn is_update(&self) -> bool {
+        self.updated_at > self.created_at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_touch_keeps_creation_time() {
+        let mut timestamps = Timestamps::now();
+        let created_at = timestamps.created_at;
+
+        timestamps.touch();
+
+        assert_eq!(timestamps.created_at, created_at);
+        assert!(timestamps.updated_at >= created_at);
+    }
+
+    #[test]
+    fn test_legacy_entities_default_to_epoch() {
+        let timestamps = Timestamps::default();
+        assert_eq!(timestamps.created_at, DateTime::UNIX_EPOCH);
+        assert_eq!(timestamps.updated_at, DateTime::UNIX_EPOCH);
+    }
+}
@@ -0,0 +1,262 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::models::{
+    item_name,
+    logistics::{Train, Wagon, WagonType},
+    map::{docking_time, travel_speed},
+    naming::TransportKind,
+    Item,
+};
+use crate::rate::compare_rates;
+
+/// Inventory slots of a freight wagon
+pub const CARGO_WAGON_SLOTS: u32 = 32;
+/// Tank volume of a fluid freight wagon, in m³
+pub const FLUID_WAGON_CAPACITY: f64 = 1600.0;
+
+/// Route a train shuttles along, there and back
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrainSchedule {
+    /// Track length between the two stations, in meters
+    pub distance: f64,
+    /// Average speed, acceleration included, in m/s
+    pub speed: f64,
+    /// Time spent at each station loading or unloading, in seconds
+    pub station_time_secs: f64,
+}
+
+impl TrainSchedule {
+    /// A route of `distance` meters at the usual train speed and docking time
+    pub fn new(distance: f64) -> Self {
+        Self {
+            distance,
+            speed: travel_speed(TransportKind::Train).unwrap_or_default(),
+            station_time_secs: docking_time(TransportKind::Train),
+        }
+    }
+
+    pub fn with_speed(mut self, speed: f64) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub fn with_station_time(mut self, station_time_secs: f64) -> Self {
+        self.station_time_secs = station_time_secs;
+        self
+    }
+
+    /// Validate the schedule
+    pub fn validate(&self) -> Result<(), TrainScheduleError> {
+        if !self.distance.is_finite() || self.distance < 0.0 {
+            return Err(TrainScheduleError::InvalidDistance(self.distance));
+        }
+        if !self.speed.is_finite() || self.speed <= 0.0 {
+            return Err(TrainScheduleError::InvalidSpeed(self.speed));
+        }
+        if !self.station_time_secs.is_finite() || self.station_time_secs < 0.0 {
+            return Err(TrainScheduleError::InvalidStationTime(
+                self.station_time_secs,
+            ));
+        }
+        if self.round_trip_secs() <= 0.0 {
+            return Err(TrainScheduleError::InstantRoundTrip);
+        }
+        Ok(())
+    }
+
+    /// Time for the train to go there and back, both station stops included
+    pub fn round_trip_secs(&self) -> f64 {
+        2.0 * self.distance / self.speed + 2.0 * self.station_time_secs
+    }
+}
+
+/// Errors raised by invalid train schedules
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrainScheduleError {
+    InvalidDistance(f64),
+    InvalidSpeed(f64),
+    InvalidStationTime(f64),
+    InstantRoundTrip,
+}
+
+impl fmt::Display for TrainScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrainScheduleError::InvalidDistance(distance) => {
+                write!(f, "Distance {} is invalid. Must be 0 or more", distance)
+            }
+            TrainScheduleError::InvalidSpeed(speed) => {
+                write!(f, "Speed {} is invalid. Must be above 0", speed)
+            }
+            TrainScheduleError::InvalidStationTime(secs) => {
+                write!(f, "Station time {} is invalid. Must be 0 or more", secs)
+            }
+            TrainScheduleError::InstantRoundTrip => {
+                write!(f, "A round trip must take some time")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TrainScheduleError {}
+
+impl Wagon {
+    /// Quantity the wagon holds when full; `None` when the item doesn't fit the
+    /// wagon, e.g. a fluid in a freight wagon
+    pub fn capacity_per_trip(&self) -> Option<f64> {
+        match self.wagon_type {
+            WagonType::Cargo => self
+                .item
+                .stack_size()
+                .map(|stack_size| (CARGO_WAGON_SLOTS * stack_size) as f64),
+            WagonType::Fluid => self.item.is_fluid().then_some(FLUID_WAGON_CAPACITY),
+        }
+    }
+}
+
+/// What one wagon can move on a schedule against what it is assigned
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WagonThroughput {
+    pub wagon_id: u64,
+    pub item: Item,
+    pub capacity_per_trip: f64,
+    /// Most the wagon sustains per minute on the schedule
+    pub max_per_min: f64,
+    pub quantity_per_min: f64,
+    /// The assigned rate is more than the wagon can move
+    pub over_capacity: bool,
+}
+
+/// Sustainable rates of every wagon of a train on a schedule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrainThroughput {
+    pub schedule: TrainSchedule,
+    pub round_trip_secs: f64,
+    pub wagons: Vec<WagonThroughput>,
+    /// One line per wagon over capacity or carrying an item it can't hold
+    pub warnings: Vec<String>,
+}
+
+impl TrainThroughput {
+    /// Whether any wagon can't keep up with its assigned rate
+    pub fn is_over_capacity(&self) -> bool {
+        self.wagons.iter().any(|wagon| wagon.over_capacity)
+    }
+}
+
+impl Train {
+    /// Work out what each wagon can move per minute on `schedule`
+    ///
+    /// # Arguments
+    ///
+    /// * `schedule` - Route the train runs, there and back
+    ///
+    /// # Returns
+    ///
+    /// Wagons in train order, each flagged when its assigned rate exceeds
+    /// what a full wagon per round trip can move. A wagon whose item doesn't
+    /// fit its type moves nothing.
+    pub fn throughput(
+        &self,
+        schedule: &TrainSchedule,
+    ) -> Result<TrainThroughput, TrainScheduleError> {
+        schedule.validate()?;
+        let round_trip_secs = schedule.round_trip_secs();
+
+        let mut warnings = Vec::new();
+        let wagons = self
+            .wagons
+            .iter()
+            .map(|wagon| {
+                let capacity_per_trip = wagon.capacity_per_trip().unwrap_or_else(|| {
+                    warnings.push(format!(
+                        "Wagon {} is a {} wagon and cannot carry {}",
+                        wagon.wagon_id,
+                        match wagon.wagon_type {
+                            WagonType::Cargo => "freight",
+                            WagonType::Fluid => "fluid",
+                        },
+                        item_name(wagon.item)
+                    ));
+                    0.0
+                });
+                let max_per_min = capacity_per_trip * 60.0 / round_trip_secs;
+                let over_capacity =
+                    compare_rates(wagon.quantity_per_min, max_per_min) == Ordering::Greater;
+                if over_capacity && capacity_per_trip > 0.0 {
+                    warnings.push(format!(
+                        "Wagon {} is assigned {:.1} {}/min but can move at most {:.1}/min",
+                        wagon.wagon_id,
+                        wagon.quantity_per_min,
+                        item_name(wagon.item),
+                        max_per_min
+                    ));
+                }
+                WagonThroughput {
+                    wagon_id: wagon.wagon_id,
+                    item: wagon.item,
+                    capacity_per_trip,
+                    max_per_min,
+                    quantity_per_min: wagon.quantity_per_min,
+                    over_capacity,
+                }
+            })
+            .collect();
+
+        Ok(TrainThroughput {
+            schedule: schedule.clone(),
+            round_trip_secs,
+            wagons,
+            warnings,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_throughput() {
+        let train = Train::new(1, "Ore Express")
+            .with_wagon(Wagon::new(1, WagonType::Cargo, Item::IronOre, 1000.0))
+            .with_wagon(Wagon::new(2, WagonType::Cargo, Item::IronOre, 4000.0))
+            .with_wagon(Wagon::new(3, WagonType::Fluid, Item::Water, 300.0))
+            .with_wagon(Wagon::new(4, WagonType::Cargo, Item::Water, 10.0));
+
+        // 2 × 3,000 m at 25 m/s plus 2 × 30 s at the stations: 5 min a trip
+        let schedule = TrainSchedule::new(3000.0).with_station_time(30.0);
+        let throughput = train.throughput(&schedule).unwrap();
+        assert_eq!(throughput.round_trip_secs, 300.0);
+
+        let rates: Vec<(f64, bool)> = throughput
+            .wagons
+            .iter()
+            .map(|wagon| (wagon.max_per_min, wagon.over_capacity))
+            .collect();
+        // 32 stacks of 500 ore, and 1,600 m³ of water, per 5 min
+        assert_eq!(
+            rates,
+            vec![(3200.0, false), (3200.0, true), (320.0, false), (0.0, true)]
+        );
+        assert!(throughput.is_over_capacity());
+        assert_eq!(
+            throughput.warnings,
+            vec![
+                "Wagon 2 is assigned 4000.0 Iron Ore/min but can move at most 3200.0/min",
+                "Wagon 4 is a freight wagon and cannot carry Water",
+            ]
+        );
+
+        assert_eq!(
+            train.throughput(&TrainSchedule::new(1000.0).with_speed(0.0)),
+            Err(TrainScheduleError::InvalidSpeed(0.0))
+        );
+        assert_eq!(
+            train.throughput(&TrainSchedule::new(0.0).with_station_time(0.0)),
+            Err(TrainScheduleError::InstantRoundTrip)
+        );
+    }
+}
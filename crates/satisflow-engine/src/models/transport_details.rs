@@ -0,0 +1,344 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+
+use crate::models::{
+    logistics::{Transport, TransportType},
+    naming::TransportKind,
+    Item,
+};
+
+/// Labels and notes saved with a logistics line
+///
+/// The transport model numbers vehicles and segments; typed details keep the
+/// labels they were given, such as "TRK-777" or "CV-101". Segment labels are
+/// listed in the order of the transport's conveyors, lifts, pipelines or wagons.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TransportDetails {
+    /// Free-form description, for lines created without typed details
+    Note(String),
+    Truck {
+        truck_id: String,
+    },
+    Drone {
+        drone_id: String,
+    },
+    Bus {
+        conveyor_ids: Vec<String>,
+        #[serde(default)]
+        lift_ids: Vec<String>,
+        pipeline_ids: Vec<String>,
+    },
+    Train {
+        wagon_ids: Vec<String>,
+    },
+}
+
+impl Default for TransportDetails {
+    fn default() -> Self {
+        TransportDetails::Note(String::new())
+    }
+}
+
+impl From<String> for TransportDetails {
+    fn from(note: String) -> Self {
+        TransportDetails::Note(note)
+    }
+}
+
+impl From<&str> for TransportDetails {
+    fn from(note: &str) -> Self {
+        TransportDetails::Note(note.to_string())
+    }
+}
+
+impl TransportDetails {
+    /// Read the details of a save written before they were typed
+    ///
+    /// The server used to store its request payload as a JSON string; the
+    /// labels in it are kept. Any other text becomes a note.
+    pub fn from_legacy(text: &str) -> Self {
+        let Ok(serde_json::Value::Object(object)) = serde_json::from_str(text) else {
+            return TransportDetails::Note(text.to_string());
+        };
+        let labels = |key: &str, id_key: &str| -> Vec<String> {
+            object
+                .get(key)
+                .and_then(|entries| entries.as_array())
+                .map(|entries| {
+                    entries
+                        .iter()
+                        .filter_map(|entry| entry[id_key].as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        if let Some(truck_id) = object.get("truck_id").and_then(|id| id.as_str()) {
+            TransportDetails::Truck {
+                truck_id: truck_id.to_string(),
+            }
+        } else if let Some(drone_id) = object.get("drone_id").and_then(|id| id.as_str()) {
+            TransportDetails::Drone {
+                drone_id: drone_id.to_string(),
+            }
+        } else if object.contains_key("conveyors") || object.contains_key("pipelines") {
+            TransportDetails::Bus {
+                conveyor_ids: labels("conveyors", "line_id"),
+                lift_ids: labels("lifts", "lift_id"),
+                pipeline_ids: labels("pipelines", "pipeline_id"),
+            }
+        } else if object.contains_key("wagons") {
+            TransportDetails::Train {
+                wagon_ids: labels("wagons", "wagon_id"),
+            }
+        } else {
+            TransportDetails::Note(text.to_string())
+        }
+    }
+
+    /// Transport kind the details describe; `None` for notes, which fit any line
+    pub fn kind(&self) -> Option<TransportKind> {
+        match self {
+            TransportDetails::Note(_) => None,
+            TransportDetails::Truck { .. } => Some(TransportKind::Truck),
+            TransportDetails::Drone { .. } => Some(TransportKind::Drone),
+            TransportDetails::Bus { .. } => Some(TransportKind::Bus),
+            TransportDetails::Train { .. } => Some(TransportKind::Train),
+        }
+    }
+
+    /// Check that the details fit `transport`
+    ///
+    /// # Returns
+    ///
+    /// An error if the details are for another transport kind, a label is
+    /// blank, or there isn't exactly one label per segment
+    pub fn validate(&self, transport: &TransportType) -> Result<(), String> {
+        if let Some(kind) = self.kind() {
+            if kind != transport.kind() {
+                return Err(format!(
+                    "{:?} details do not fit a {} line",
+                    kind,
+                    transport.get_transport_type_name()
+                ));
+            }
+        }
+
+        let check = |what: &str, labels: &[String], segments: usize| {
+            if labels.iter().any(|label| label.trim().is_empty()) {
+                return Err(format!("{} labels must not be blank", what));
+            }
+            if labels.len() != segments {
+                return Err(format!(
+                    "{} labels: expected {}, got {}",
+                    what,
+                    segments,
+                    labels.len()
+                ));
+            }
+            Ok(())
+        };
+        match (self, transport) {
+            (TransportDetails::Truck { truck_id: id }, _)
+            | (TransportDetails::Drone { drone_id: id }, _) => {
+                check("Vehicle", std::slice::from_ref(id), 1)
+            }
+            (
+                TransportDetails::Bus {
+                    conveyor_ids,
+                    lift_ids,
+                    pipeline_ids,
+                },
+                TransportType::Bus(bus),
+            ) => {
+                check("Conveyor", conveyor_ids, bus.lines.len())?;
+                check("Lift", lift_ids, bus.lifts.len())?;
+                check("Pipeline", pipeline_ids, bus.pipelines.len())
+            }
+            (TransportDetails::Train { wagon_ids }, TransportType::Train(train)) => {
+                check("Wagon", wagon_ids, train.wagons.len())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Keep only the labels of the segments of `transport` carrying `item`
+    ///
+    /// Mirrors [`TransportType::retain_item`]; notes and vehicle labels are
+    /// returned unchanged.
+    pub fn retain_item(&self, transport: &TransportType, item: Item) -> TransportDetails {
+        fn retain(labels: &[String], items: impl Iterator<Item = Item>, item: Item) -> Vec<String> {
+            labels
+                .iter()
+                .zip(items)
+                .filter(|(_, carried)| *carried == item)
+                .map(|(label, _)| label.clone())
+                .collect()
+        }
+
+        match (self, transport) {
+            (
+                TransportDetails::Bus {
+                    conveyor_ids,
+                    lift_ids,
+                    pipeline_ids,
+                },
+                TransportType::Bus(bus),
+            ) => TransportDetails::Bus {
+                conveyor_ids: retain(conveyor_ids, bus.lines.iter().map(|c| c.item), item),
+                lift_ids: retain(lift_ids, bus.lifts.iter().map(|l| l.item), item),
+                pipeline_ids: retain(pipeline_ids, bus.pipelines.iter().map(|p| p.item), item),
+            },
+            (TransportDetails::Train { wagon_ids }, TransportType::Train(train)) => {
+                TransportDetails::Train {
+                    wagon_ids: retain(wagon_ids, train.wagons.iter().map(|w| w.item), item),
+                }
+            }
+            _ => self.clone(),
+        }
+    }
+}
+
+impl fmt::Display for TransportDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransportDetails::Note(note) => write!(f, "{}", note),
+            TransportDetails::Truck { truck_id: id } | TransportDetails::Drone { drone_id: id } => {
+                write!(f, "{}", id)
+            }
+            TransportDetails::Bus {
+                conveyor_ids,
+                lift_ids,
+                pipeline_ids,
+            } => {
+                let labels: Vec<&str> = conveyor_ids
+                    .iter()
+                    .chain(lift_ids)
+                    .chain(pipeline_ids)
+                    .map(String::as_str)
+                    .collect();
+                write!(f, "{}", labels.join(", "))
+            }
+            TransportDetails::Train { wagon_ids } => write!(f, "{}", wagon_ids.join(", ")),
+        }
+    }
+}
+
+/// Deserialize details saved either typed or as the string of older saves
+pub(crate) fn deserialize_saved<'de, D>(deserializer: D) -> Result<TransportDetails, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Saved {
+        Legacy(String),
+        Typed(TransportDetails),
+    }
+
+    Ok(match Saved::deserialize(deserializer)? {
+        Saved::Legacy(text) => TransportDetails::from_legacy(&text),
+        Saved::Typed(details) => details,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::logistics::{
+        Bus, Conveyor, ConveyorSpeed, Pipeline, PipelineCapacity, TruckTransport,
+    };
+
+    #[test]
+    fn test_from_legacy() {
+        let bus = TransportDetails::from_legacy(
+            r#"{"bus_id":1,"bus_name":"Main","conveyors":[{"line_id":"CV-101","item":"IronPlate"}],"pipelines":[{"pipeline_id":"PL-501","item":"Water"}]}"#,
+        );
+        assert_eq!(
+            bus,
+            TransportDetails::Bus {
+                conveyor_ids: vec!["CV-101".into()],
+                lift_ids: vec![],
+                pipeline_ids: vec!["PL-501".into()],
+            }
+        );
+        assert_eq!(
+            TransportDetails::from_legacy(r#"{"truck_id":"TRK-777","item":"Coal"}"#),
+            TransportDetails::Truck {
+                truck_id: "TRK-777".into()
+            }
+        );
+        assert_eq!(
+            TransportDetails::from_legacy("Ore run"),
+            TransportDetails::Note("Ore run".into())
+        );
+    }
+
+    #[test]
+    fn test_validate_and_retain_item() {
+        let transport = TransportType::Bus(
+            Bus::new(1, "Main")
+                .with_conveyor(Conveyor::new(
+                    101,
+                    ConveyorSpeed::Mk4,
+                    Item::IronPlate,
+                    180.0,
+                ))
+                .with_conveyor(Conveyor::new(102, ConveyorSpeed::Mk4, Item::Coal, 60.0))
+                .with_pipeline(Pipeline::new(
+                    501,
+                    PipelineCapacity::Mk2,
+                    Item::Water,
+                    480.0,
+                )),
+        );
+        let details = TransportDetails::Bus {
+            conveyor_ids: vec!["CV-101".into(), "CV-102".into()],
+            lift_ids: vec![],
+            pipeline_ids: vec!["PL-501".into()],
+        };
+        assert!(details.validate(&transport).is_ok());
+        assert_eq!(details.to_string(), "CV-101, CV-102, PL-501");
+        assert!(TransportDetails::from("anything")
+            .validate(&transport)
+            .is_ok());
+
+        let truck = TransportType::Truck(TruckTransport::new(1, Item::Coal, 60.0));
+        assert!(details.validate(&truck).is_err());
+        let missing = TransportDetails::Bus {
+            conveyor_ids: vec!["CV-101".into()],
+            lift_ids: vec![],
+            pipeline_ids: vec!["PL-501".into()],
+        };
+        assert!(missing.validate(&transport).is_err());
+
+        assert_eq!(
+            details.retain_item(&transport, Item::Coal),
+            TransportDetails::Bus {
+                conveyor_ids: vec!["CV-102".into()],
+                lift_ids: vec![],
+                pipeline_ids: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn test_saved_forms() {
+        #[derive(Deserialize)]
+        struct Line {
+            #[serde(deserialize_with = "deserialize_saved")]
+            details: TransportDetails,
+        }
+
+        let typed = TransportDetails::Drone {
+            drone_id: "DRN-4".into(),
+        };
+        let json = format!(
+            r#"{{"details":{}}}"#,
+            serde_json::to_string(&typed).unwrap()
+        );
+        assert_eq!(serde_json::from_str::<Line>(&json).unwrap().details, typed);
+        let legacy: Line = serde_json::from_str(r#"{"details":"Test truck"}"#).unwrap();
+        assert_eq!(legacy.details, TransportDetails::from("Test truck"));
+    }
+}
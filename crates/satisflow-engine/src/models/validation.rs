@@ -0,0 +1,78 @@
+//! Checks run on an entity before it is added to the save.
+//!
+//! Errors make the entity impossible to plan and reject it; warnings point at
+//! something the player probably wants to fix but may have a reason for, so
+//! the entity is kept and the warnings are shown alongside it.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Errors and warnings found while checking an entity
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ValidationReport {
+    /// Problems that reject the entity
+    pub errors: Vec<String>,
+    /// Problems the entity is kept with
+    pub warnings: Vec<String>,
+}
+
+impl ValidationReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a problem that rejects the entity
+    pub fn error(&mut self, message: impl Into<String>) {
+        self.errors.push(message.into());
+    }
+
+    /// Record a problem the entity is kept with
+    pub fn warn(&mut self, message: impl Into<String>) {
+        self.warnings.push(message.into());
+    }
+
+    /// Whether nothing rejects the entity
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// The warnings if the entity is valid, otherwise the report itself
+    pub fn into_result(self) -> Result<Vec<String>, ValidationReport> {
+        if self.is_valid() {
+            Ok(self.warnings)
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.errors.join("; "))
+    }
+}
+
+impl std::error::Error for ValidationReport {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validation_report() {
+        let mut report = ValidationReport::new();
+        report.warn("Conveyor over capacity");
+        assert!(report.is_valid());
+        assert_eq!(
+            report.clone().into_result(),
+            Ok(vec!["Conveyor over capacity".to_string()])
+        );
+
+        report.error("Factory missing");
+        report.error("Unknown item");
+        assert!(!report.is_valid());
+        assert_eq!(report.to_string(), "Factory missing; Unknown item");
+        assert_eq!(report.clone().into_result(), Err(report));
+    }
+}
@@ -0,0 +1,74 @@
+//! Rounding policy for rates and power
+//!
+//! Rates are `f64` items (or m³) per minute and power is `f64` MW. Summing
+//! dozens of machine groups still leaves rounding noise, so a chain that
+//! balances exactly can add up to `-0.0000000001`. Balance checks go through
+//! the helpers below instead of comparing with zero, so such a chain shows as
+//! balanced rather than as a deficit.
+
+use std::cmp::Ordering;
+
+/// Resolution of rates and power; smaller differences are rounding noise
+///
+/// A millionth of an item per minute is far below anything a machine can be
+/// set to, yet far above the noise of summing thousands of `f64` rates.
+pub const RATE_EPSILON: f64 = 1e-6;
+/// `1 / RATE_EPSILON`, exact so rounded rates are the closest `f64` to a decimal
+const RATE_SCALE: f64 = 1e6;
+
+/// Round a rate to the nearest multiple of [`RATE_EPSILON`]
+///
+/// Noise around zero becomes `0.0`, never `-0.0`, so it doesn't print with a
+/// sign either.
+pub fn round_rate(rate: f64) -> f64 {
+    // Adding 0 turns -0 into 0
+    (rate * RATE_SCALE).round() / RATE_SCALE + 0.0
+}
+
+/// Whether a rate or balance is zero once rounded
+pub fn is_zero(rate: f64) -> bool {
+    round_rate(rate) == 0.0
+}
+
+/// Compare two rates, treating them as equal when their difference rounds to zero
+pub fn compare_rates(a: f64, b: f64) -> Ordering {
+    if is_zero(a - b) {
+        Ordering::Equal
+    } else if a < b {
+        Ordering::Less
+    } else {
+        Ordering::Greater
+    }
+}
+
+/// Whether a balance is a surplus, a deficit or balanced
+pub fn balance_sign(balance: f64) -> Ordering {
+    compare_rates(balance, 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_noise_rounds_to_zero() {
+        assert_eq!(round_rate(-0.000_000_1), 0.0);
+        assert!(round_rate(-0.000_000_1).is_sign_positive());
+        assert!(is_zero(1e-9));
+        assert_eq!(balance_sign(-1e-9), Ordering::Equal);
+        assert_eq!(balance_sign(-0.01), Ordering::Less);
+        assert_eq!(balance_sign(0.01), Ordering::Greater);
+        assert_eq!(round_rate(37.5000001), 37.5);
+        assert_eq!(round_rate(0.1 + 0.2), 0.3);
+    }
+
+    #[test]
+    fn test_summed_rates_balance() {
+        // Ten lines of 0.1/min consumed by one line of 1/min
+        let produced: f64 = (0..10).map(|_| 0.1).sum();
+        assert_ne!(produced - 1.0, 0.0);
+        assert_eq!(balance_sign(produced - 1.0), Ordering::Equal);
+        assert_eq!(compare_rates(produced, 1.0), Ordering::Equal);
+        assert_eq!(compare_rates(0.5, 1.0), Ordering::Less);
+    }
+}
@@ -0,0 +1,381 @@
+//! Repair of save files damaged by hand edits, crashes or older bugs
+//!
+//! Every fix works on the JSON tree of the save before it is deserialized,
+//! since most corruption keeps serde from reading the save at all.
+
+use std::collections::HashSet;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::models::LogisticsId;
+use crate::{LoadIssue, SaveVersion};
+
+/// Rate fields zeroed when they aren't a number; NaN is saved as `null`
+const RATE_FIELDS: &[&str] = &["quantity_per_min", "target_rate"];
+
+/// Maps of a factory that must be present
+const FACTORY_SECTIONS: &[&str] = &[
+    "production_lines",
+    "raw_inputs",
+    "power_generators",
+    "items",
+];
+
+/// An entity given a new ID
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegeneratedId {
+    /// JSON path of the entity in the damaged save
+    pub path: String,
+    /// The ID it had, if any
+    pub old_id: Option<String>,
+    pub new_id: Uuid,
+}
+
+/// What [`SatisflowEngine::repair`](crate::SatisflowEngine::repair) fixed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RepairReport {
+    /// Logistics lines removed because a factory they connect doesn't exist
+    pub removed_logistics: Vec<LogisticsId>,
+    /// Entities whose ID was missing, didn't match their key, or was already
+    /// used by an earlier entity of the same kind
+    pub regenerated_ids: Vec<RegeneratedId>,
+    /// JSON paths of rates that weren't numbers, now 0
+    pub zeroed_rates: Vec<String>,
+    /// JSON paths of required sections that were missing, now at their default
+    pub defaulted_sections: Vec<String>,
+    /// What loading the repaired save then worked around, see [`LoadOptions`](crate::LoadOptions)
+    pub load_warnings: Vec<LoadIssue>,
+}
+
+impl RepairReport {
+    /// Check whether the save needed no repair
+    pub fn is_clean(&self) -> bool {
+        self.removed_logistics.is_empty()
+            && self.regenerated_ids.is_empty()
+            && self.zeroed_rates.is_empty()
+            && self.defaulted_sections.is_empty()
+            && self.load_warnings.is_empty()
+    }
+}
+
+/// Fix the recoverable corruption of a save file tree in place
+///
+/// # Returns
+///
+/// What was fixed, or an error if the save isn't a JSON object
+pub fn repair_save(save: &mut Value) -> Result<RepairReport, Box<dyn std::error::Error>> {
+    let mut report = RepairReport::default();
+    let save = save
+        .as_object_mut()
+        .ok_or("Save file must be a JSON object")?;
+
+    let now = serde_json::to_value(Utc::now())?;
+    default_section(
+        save,
+        "",
+        "version",
+        || SaveVersion::current().to_string().into(),
+        &mut report,
+    );
+    default_section(save, "", "created_at", || now.clone(), &mut report);
+    default_section(save, "", "last_modified", || now.clone(), &mut report);
+    default_section(save, "", "engine", empty_object, &mut report);
+    let engine = save["engine"]
+        .as_object_mut()
+        .ok_or("The engine section of the save must be a JSON object")?;
+    default_section(engine, "engine", "factories", empty_object, &mut report);
+    default_section(
+        engine,
+        "engine",
+        "logistics_lines",
+        empty_object,
+        &mut report,
+    );
+
+    let mut production_line_ids = HashSet::new();
+    let mut raw_input_ids = HashSet::new();
+    let mut generator_ids = HashSet::new();
+    let factory_ids = rekey_entities(
+        &mut engine["factories"],
+        "engine.factories",
+        &mut HashSet::new(),
+        &mut report,
+    );
+    if let Some(factories) = engine["factories"].as_object_mut() {
+        for (factory_id, factory) in factories.iter_mut() {
+            let path = format!("engine.factories.{}", factory_id);
+            let Some(factory) = factory.as_object_mut() else {
+                continue;
+            };
+            for section in FACTORY_SECTIONS {
+                default_section(factory, &path, section, empty_object, &mut report);
+            }
+            rekey_entities(
+                &mut factory["production_lines"],
+                &format!("{}.production_lines", path),
+                &mut production_line_ids,
+                &mut report,
+            );
+            rekey_entities(
+                &mut factory["raw_inputs"],
+                &format!("{}.raw_inputs", path),
+                &mut raw_input_ids,
+                &mut report,
+            );
+            rekey_entities(
+                &mut factory["power_generators"],
+                &format!("{}.power_generators", path),
+                &mut generator_ids,
+                &mut report,
+            );
+        }
+    }
+
+    rekey_entities(
+        &mut engine["logistics_lines"],
+        "engine.logistics_lines",
+        &mut HashSet::new(),
+        &mut report,
+    );
+    if let Some(lines) = engine["logistics_lines"].as_object_mut() {
+        lines.retain(|id, line| {
+            let connects = |end: &str| {
+                line[end]
+                    .as_str()
+                    .is_some_and(|factory_id| factory_ids.contains(factory_id))
+            };
+            let keep = connects("from_factory") && connects("to_factory");
+            if !keep {
+                if let Ok(id) = id.parse() {
+                    report.removed_logistics.push(id);
+                }
+            }
+            keep
+        });
+    }
+
+    for section in ["power_grids", "goals"] {
+        if let Some(entities) = engine.get_mut(section) {
+            let path = format!("engine.{}", section);
+            rekey_entities(entities, &path, &mut HashSet::new(), &mut report);
+        }
+    }
+
+    for (key, value) in engine.iter_mut() {
+        zero_rates(value, &format!("engine.{}", key), &mut report.zeroed_rates);
+    }
+    Ok(report)
+}
+
+fn empty_object() -> Value {
+    Value::Object(Map::new())
+}
+
+/// Insert a missing or `null` section with its default
+fn default_section(
+    object: &mut Map<String, Value>,
+    path: &str,
+    key: &str,
+    default: impl FnOnce() -> Value,
+    report: &mut RepairReport,
+) {
+    if object.get(key).is_none_or(Value::is_null) {
+        object.insert(key.to_string(), default());
+        report.defaulted_sections.push(match path {
+            "" => key.to_string(),
+            _ => format!("{}.{}", path, key),
+        });
+    }
+}
+
+/// Make every entity of an ID-keyed map carry its key as ID, with keys
+/// unique among `seen`
+///
+/// # Returns
+///
+/// The keys of the map after the fix
+fn rekey_entities(
+    entities: &mut Value,
+    path: &str,
+    seen: &mut HashSet<String>,
+    report: &mut RepairReport,
+) -> HashSet<String> {
+    let Some(map) = entities.as_object_mut() else {
+        return HashSet::new();
+    };
+    let mut rekeyed = Map::new();
+    for (key, mut entity) in std::mem::take(map) {
+        let entity_path = format!("{}.{}", path, key);
+        let unique = Uuid::parse_str(&key).is_ok() && seen.insert(key.clone());
+        let key = if unique {
+            key
+        } else {
+            let new_id = Uuid::new_v4();
+            report.regenerated_ids.push(RegeneratedId {
+                path: entity_path,
+                old_id: Some(key),
+                new_id,
+            });
+            seen.insert(new_id.to_string());
+            new_id.to_string()
+        };
+
+        if let Some(fields) = entity_fields(&mut entity) {
+            if fields.get("id").and_then(Value::as_str) != Some(key.as_str()) {
+                if unique {
+                    report.regenerated_ids.push(RegeneratedId {
+                        path: format!("{}.{}", path, key),
+                        old_id: fields.get("id").and_then(Value::as_str).map(String::from),
+                        new_id: Uuid::parse_str(&key).expect("checked above"),
+                    });
+                }
+                fields.insert("id".to_string(), key.clone().into());
+            }
+        }
+        rekeyed.insert(key, entity);
+    }
+    *map = rekeyed;
+    map.keys().cloned().collect()
+}
+
+/// Fields of an entity, looking inside the variant of production lines
+fn entity_fields(entity: &mut Value) -> Option<&mut Map<String, Value>> {
+    let object = entity.as_object_mut()?;
+    let is_variant = object.len() == 1
+        && object
+            .keys()
+            .all(|key| key == "ProductionLineRecipe" || key == "ProductionLineBlueprint");
+    if is_variant {
+        object.values_mut().next()?.as_object_mut()
+    } else {
+        Some(object)
+    }
+}
+
+/// Set the rates that aren't numbers to 0
+fn zero_rates(value: &mut Value, path: &str, zeroed: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                let child_path = format!("{}.{}", path, key);
+                if RATE_FIELDS.contains(&key.as_str()) && !child.is_number() {
+                    *child = 0.0.into();
+                    zeroed.push(child_path);
+                } else if key == "items" && child.is_object() {
+                    for (item, rate) in child.as_object_mut().into_iter().flatten() {
+                        if !rate.is_number() {
+                            *rate = 0.0.into();
+                            zeroed.push(format!("{}.{}", child_path, item));
+                        }
+                    }
+                } else {
+                    zero_rates(child, &child_path, zeroed);
+                }
+            }
+        }
+        Value::Array(values) => {
+            for (index, child) in values.iter_mut().enumerate() {
+                zero_rates(child, &format!("{}[{}]", path, index), zeroed);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const FACTORY: &str = "9b1e4c57-8f0a-4d8e-9a57-3f8e2c1d0a01";
+    const OTHER_FACTORY: &str = "9b1e4c57-8f0a-4d8e-9a57-3f8e2c1d0a02";
+    const LINE: &str = "2f6a7d10-41c5-4c1b-b7a0-5d2e9f3c8b02";
+
+    #[test]
+    fn clean_save_is_left_alone() {
+        let mut save = json!({
+            "version": "0.2.0",
+            "created_at": "2025-10-25T12:00:00Z",
+            "last_modified": "2025-10-25T12:00:00Z",
+            "engine": { "factories": {}, "logistics_lines": {} }
+        });
+        let before = save.clone();
+        assert!(repair_save(&mut save).unwrap().is_clean());
+        assert_eq!(save, before);
+    }
+
+    #[test]
+    fn missing_sections_are_defaulted() {
+        let mut save = json!({ "engine": { "factories": { FACTORY: { "id": FACTORY } } } });
+        let report = repair_save(&mut save).unwrap();
+        assert_eq!(
+            report.defaulted_sections[..5],
+            [
+                "version",
+                "created_at",
+                "last_modified",
+                "engine.logistics_lines"
+            ]
+            .iter()
+            .map(|path| path.to_string())
+            .chain([format!("engine.factories.{}.production_lines", FACTORY)])
+            .collect::<Vec<_>>()[..]
+        );
+        assert_eq!(save["engine"]["factories"][FACTORY]["items"], json!({}));
+    }
+
+    #[test]
+    fn duplicate_production_line_ids_are_regenerated() {
+        let line = json!({ "ProductionLineRecipe": { "id": LINE } });
+        let mut save = json!({
+            "engine": {
+                "factories": {
+                    FACTORY: { "id": FACTORY, "production_lines": { LINE: line } },
+                    OTHER_FACTORY: { "id": FACTORY, "production_lines": { LINE: line } }
+                }
+            }
+        });
+        let report = repair_save(&mut save).unwrap();
+
+        // The second factory's ID didn't match its key, its line repeats the first one's
+        assert_eq!(report.regenerated_ids.len(), 2);
+        let regenerated = &report.regenerated_ids[1];
+        assert_eq!(regenerated.old_id.as_deref(), Some(LINE));
+        let lines = &save["engine"]["factories"][OTHER_FACTORY]["production_lines"];
+        let new_id = regenerated.new_id.to_string();
+        assert_eq!(lines[&new_id]["ProductionLineRecipe"]["id"], json!(new_id));
+        assert_eq!(
+            save["engine"]["factories"][OTHER_FACTORY]["id"],
+            OTHER_FACTORY
+        );
+    }
+
+    #[test]
+    fn dangling_logistics_and_null_rates_are_fixed() {
+        let mut save = json!({
+            "engine": {
+                "factories": { FACTORY: { "id": FACTORY, "items": { "IronOre": null } } },
+                "logistics_lines": {
+                    LINE: { "id": LINE, "from_factory": FACTORY, "to_factory": OTHER_FACTORY }
+                },
+                "goals": {
+                    LINE: { "id": LINE, "item": "IronPlate", "target_rate": null }
+                }
+            }
+        });
+        let report = repair_save(&mut save).unwrap();
+        assert_eq!(report.removed_logistics, vec![LINE.parse().unwrap()]);
+        assert_eq!(save["engine"]["logistics_lines"], json!({}));
+        assert_eq!(
+            report.zeroed_rates,
+            vec![
+                format!("engine.factories.{}.items.IronOre", FACTORY),
+                format!("engine.goals.{}.target_rate", LINE),
+            ]
+        );
+        assert_eq!(save["engine"]["goals"][LINE]["target_rate"], 0.0);
+    }
+}
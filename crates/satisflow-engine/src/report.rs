@@ -0,0 +1,289 @@
+//! Markdown reports to paste into wikis or chat
+//!
+//! A factory report lists the factory's production lines, extractors, item
+//! balance, power and the logistics lines connecting it to other factories.
+//! The global report starts with an overview table and then has one section
+//! per factory.
+
+use std::fmt::Write;
+
+use crate::format::NumberFormat;
+use crate::models::{
+    factory::Factory, item_name, production_line::ProductionLine, FactoryId, Item,
+};
+use crate::{rate, SatisflowEngine};
+
+/// Render the report of one factory
+///
+/// # Returns
+///
+/// Result containing the Markdown document, or an error if the factory doesn't exist
+pub fn factory_markdown(
+    engine: &SatisflowEngine,
+    factory_id: FactoryId,
+    format: &NumberFormat,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let factory = engine
+        .get_factory(factory_id)
+        .ok_or_else(|| format!("Factory {} not found", factory_id))?;
+
+    let mut out = String::new();
+    write_factory(&mut out, engine, factory, 1, format);
+    Ok(out)
+}
+
+/// Render the report of every factory, sorted by name
+pub fn global_markdown(engine: &SatisflowEngine, format: &NumberFormat) -> String {
+    let mut factories: Vec<&Factory> = engine.get_all_factories().values().collect();
+    factories.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+    let power = engine.global_power_stats();
+
+    let mut out = String::from("# Factory report\n\n");
+    let _ = writeln!(
+        out,
+        "{} factories, {} logistics lines. Power: {} generated, {} consumed, {} balance.\n",
+        factories.len(),
+        engine.get_all_logistics().len(),
+        format.power(power.total_generation),
+        format.power(power.total_consumption),
+        format.power(power.power_balance),
+    );
+
+    if !factories.is_empty() {
+        out.push_str("| Factory | Production lines | Power balance |\n|---|---:|---:|\n");
+        for factory in &factories {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} |",
+                cell(&factory.name),
+                factory.production_lines.len(),
+                format.power(factory.power_balance()),
+            );
+        }
+        out.push('\n');
+    }
+
+    for factory in factories {
+        write_factory(&mut out, engine, factory, 2, format);
+    }
+    out
+}
+
+fn write_factory(
+    out: &mut String,
+    engine: &SatisflowEngine,
+    factory: &Factory,
+    level: usize,
+    format: &NumberFormat,
+) {
+    let heading = "#".repeat(level);
+    let subheading = "#".repeat(level + 1);
+
+    let _ = writeln!(out, "{} {}\n", heading, factory.name);
+    if let Some(description) = &factory.description {
+        let _ = writeln!(out, "{}\n", description);
+    }
+    let _ = writeln!(
+        out,
+        "**Power:** {} generated, {} consumed, {} balance\n",
+        format.power(factory.total_power_generation()),
+        format.power(factory.total_power_consumption()),
+        format.power(factory.power_balance()),
+    );
+
+    let mut lines: Vec<&ProductionLine> = factory.production_lines.values().collect();
+    lines.sort_by(|a, b| a.name().cmp(b.name()).then(a.id().cmp(&b.id())));
+    if !lines.is_empty() {
+        let _ = writeln!(out, "{} Production lines\n", subheading);
+        out.push_str("| Line | Recipe | Machines | Outputs | Power |\n|---|---|---:|---|---:|\n");
+        for line in lines {
+            let recipe = match line {
+                ProductionLine::ProductionLineRecipe(recipe_line) => {
+                    recipe_line.recipe.name().to_string()
+                }
+                ProductionLine::ProductionLineBlueprint(blueprint) => {
+                    let recipes: Vec<&str> = blueprint
+                        .production_lines
+                        .iter()
+                        .map(|inner| inner.recipe.name())
+                        .collect();
+                    format!("Blueprint: {}", recipes.join(", "))
+                }
+            };
+            let name = if line.is_enabled() {
+                cell(line.name())
+            } else {
+                format!("~~{}~~ (disabled)", cell(line.name()))
+            };
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} | {} |",
+                name,
+                cell(&recipe),
+                line.total_machines(),
+                rates(&line.output_rate(), format),
+                format.power(line.total_power_consumption_with(factory.balance())),
+            );
+        }
+        out.push('\n');
+    }
+
+    let mut extractors: Vec<_> = factory.active_raw_inputs().collect();
+    extractors.sort_by_key(|raw_input| item_name(raw_input.item));
+    if !extractors.is_empty() {
+        let _ = writeln!(out, "{} Extraction\n", subheading);
+        for raw_input in extractors {
+            let _ = writeln!(
+                out,
+                "- {} × {:?}: {}",
+                raw_input.count,
+                raw_input.extractor_type,
+                rates(
+                    &[(
+                        raw_input.item,
+                        raw_input.quantity_per_min_with(factory.balance())
+                    )],
+                    format,
+                ),
+            );
+        }
+        out.push('\n');
+    }
+
+    let mut balance = factory.clone();
+    balance.calculate_item(engine.get_all_logistics());
+    let mut items: Vec<(Item, f64)> = balance
+        .items
+        .into_iter()
+        .filter(|(_, quantity)| !rate::is_zero(*quantity))
+        .collect();
+    items.sort_by_key(|(item, _)| item_name(*item));
+    if !items.is_empty() {
+        let _ = writeln!(out, "{} Item balance\n", subheading);
+        out.push_str("| Item | Net rate |\n|---|---:|\n");
+        for (item, quantity) in items {
+            let _ = writeln!(
+                out,
+                "| {} | {}{} |",
+                item_name(item),
+                if quantity > 0.0 { "+" } else { "" },
+                format.rate(quantity),
+            );
+        }
+        out.push('\n');
+    }
+
+    let mut logistics: Vec<_> = engine
+        .outgoing_logistics(factory.id)
+        .chain(
+            engine
+                .incoming_logistics(factory.id)
+                .filter(|line| line.from_factory != factory.id),
+        )
+        .collect();
+    logistics.sort_by_cached_key(|line| (line.transport_details.to_string(), line.id));
+    if !logistics.is_empty() {
+        let _ = writeln!(out, "{} Logistics\n", subheading);
+        out.push_str("| Direction | Factory | Transport | Items |\n|---|---|---|---|\n");
+        for line in logistics {
+            let (direction, other) = if line.from_factory == factory.id {
+                ("To", line.to_factory)
+            } else {
+                ("From", line.from_factory)
+            };
+            let other = engine
+                .get_factory(other)
+                .map(|other| other.name.clone())
+                .unwrap_or_else(|| other.to_string());
+            let items: Vec<(Item, f64)> = line
+                .get_items()
+                .into_iter()
+                .map(|flow| (flow.item, flow.quantity_per_min))
+                .collect();
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} {} | {} |",
+                direction,
+                cell(&other),
+                line.transport_type,
+                cell(&line.transport_details.to_string()),
+                rates(&items, format),
+            );
+        }
+        out.push('\n');
+    }
+}
+
+/// List rates, e.g. `Iron Ingot 60.0/min, Slag 5.0/min`
+fn rates(rates: &[(Item, f64)], format: &NumberFormat) -> String {
+    rates
+        .iter()
+        .map(|(item, rate)| format!("{} {}", item_name(*item), format.rate(*rate)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Keep user text from breaking a table row
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        logistics::{TransportType, TruckTransport},
+        production_line::{MachineGroup, ProductionLineRecipe},
+        Recipe,
+    };
+    use uuid::Uuid;
+
+    #[test]
+    fn test_factory_markdown() {
+        let mut engine = SatisflowEngine::new();
+        let mine = engine.create_factory("Mine".to_string(), None);
+        let smelter = engine.create_factory(
+            "Smelter | East".to_string(),
+            Some("Iron for the hub".to_string()),
+        );
+        let mut line = ProductionLineRecipe::new(
+            Uuid::from_u128(1).into(),
+            "Ingots".to_string(),
+            None,
+            Recipe::IronIngot,
+        );
+        line.add_machine_group(MachineGroup::new(2, 100.0, 0))
+            .unwrap();
+        engine
+            .get_factory_mut(smelter)
+            .unwrap()
+            .add_production_line(ProductionLine::ProductionLineRecipe(line));
+        engine
+            .create_logistics_line(
+                mine,
+                smelter,
+                TransportType::Truck(TruckTransport::new(1, Item::IronOre, 60.0)),
+                "Ore run".to_string(),
+            )
+            .unwrap();
+
+        let report = factory_markdown(&engine, smelter, &NumberFormat::default()).unwrap();
+        assert!(report.starts_with("# Smelter | East\n\nIron for the hub\n"));
+        assert!(report.contains("**Power:** 0.0 MW generated, 8.0 MW consumed, -8.0 MW balance"));
+        assert!(report.contains("| Ingots | Iron Ingot | 2 | Iron Ingot 60.0/min | 8.0 MW |"));
+        assert!(report.contains("| Iron Ingot | +60.0/min |"));
+        assert!(!report.contains("| Iron Ore |"));
+        assert!(report.contains("| From | Mine | Truck Ore run | Iron Ore 60.0/min |"));
+
+        let global = global_markdown(&engine, &NumberFormat::default());
+        assert!(global.contains("| Smelter \\| East | 1 | -8.0 MW |"));
+        assert!(global.find("## Mine").unwrap() < global.find("## Smelter | East").unwrap());
+
+        assert!(factory_markdown(
+            &engine,
+            Uuid::from_u128(99).into(),
+            &NumberFormat::default()
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,271 @@
+//! Trend statistics across several saves of the same world
+//!
+//! A "season" is a series of snapshots (e.g. weekly saves) of one playthrough.
+//! [`SeasonOverview`] compares them to show how the world grew: factories over
+//! time, power growth and the products that appeared along the way.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::export::csv_field;
+use crate::models::{item_name, report_card::ItemRate, Item};
+use crate::SatisflowEngine;
+
+/// Number of products listed in the season's top new products
+const TOP_NEW_PRODUCTS: usize = 5;
+
+/// Headline numbers of a single save
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeasonSnapshot {
+    /// Name shown for this snapshot, e.g. the save file name
+    pub label: String,
+    /// When the save was last modified, if known
+    pub saved_at: Option<DateTime<Utc>>,
+    pub factory_count: usize,
+    pub production_line_count: usize,
+    pub logistics_count: usize,
+    pub power_generation: f64,
+    pub power_consumption: f64,
+    /// Output of every production line, highest first
+    pub production: Vec<ItemRate>,
+    /// Items produced here but not in the previous snapshot, sorted by name
+    #[serde(default)]
+    pub new_products: Vec<Item>,
+}
+
+impl SeasonSnapshot {
+    /// Take a snapshot of an engine
+    pub fn from_engine(
+        label: impl Into<String>,
+        saved_at: Option<DateTime<Utc>>,
+        engine: &SatisflowEngine,
+    ) -> Self {
+        let mut production_line_count = 0;
+        let mut power_generation = 0.0;
+        let mut power_consumption = 0.0;
+        let mut produced: HashMap<Item, f64> = HashMap::new();
+
+        for factory in engine.get_all_factories().values() {
+            production_line_count += factory.production_lines.len();
+            power_generation += factory.total_power_generation();
+            power_consumption += factory.total_power_consumption();
+            for line in factory.active_production_lines() {
+                for (item, quantity) in line.output_rate() {
+                    *produced.entry(item).or_insert(0.0) += quantity;
+                }
+            }
+        }
+
+        let mut production: Vec<ItemRate> = produced
+            .into_iter()
+            .filter(|(_, quantity)| *quantity > 0.0)
+            .map(|(item, quantity_per_min)| ItemRate {
+                item,
+                quantity_per_min,
+            })
+            .collect();
+        sort_rates(&mut production);
+
+        Self {
+            label: label.into(),
+            saved_at,
+            factory_count: engine.get_all_factories().len(),
+            production_line_count,
+            logistics_count: engine.get_all_logistics().len(),
+            power_generation,
+            power_consumption,
+            production,
+            new_products: Vec::new(),
+        }
+    }
+
+    /// Load a save file and take a snapshot of it
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - Name shown for this snapshot
+    /// * `json` - JSON string containing the save file
+    ///
+    /// # Returns
+    ///
+    /// Result containing the snapshot or the error raised while loading the save
+    pub fn from_save_json(
+        label: impl Into<String>,
+        json: &str,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let engine = SatisflowEngine::load_from_json(json)?;
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        let saved_at = value["last_modified"]
+            .as_str()
+            .and_then(|timestamp| DateTime::parse_from_rfc3339(timestamp).ok())
+            .map(|timestamp| timestamp.with_timezone(&Utc));
+        Ok(Self::from_engine(label, saved_at, &engine))
+    }
+
+    fn produces(&self, item: Item) -> bool {
+        self.production.iter().any(|rate| rate.item == item)
+    }
+}
+
+/// Growth of a world across several snapshots, oldest first
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeasonOverview {
+    pub snapshots: Vec<SeasonSnapshot>,
+    /// Factories gained between the first and last snapshot
+    pub factory_growth: i64,
+    /// Power generation gained between the first and last snapshot, in MW
+    pub power_growth: f64,
+    /// Products of the last snapshot that the first did not make, highest first
+    pub top_new_products: Vec<ItemRate>,
+}
+
+impl SeasonOverview {
+    /// Compare snapshots given oldest first
+    ///
+    /// Snapshots are kept in the given order rather than sorted by `saved_at`,
+    /// since copied or re-exported saves do not keep their original timestamps.
+    pub fn build(mut snapshots: Vec<SeasonSnapshot>) -> Self {
+        for index in 0..snapshots.len() {
+            let mut new_products: Vec<Item> = match index.checked_sub(1) {
+                Some(previous) => snapshots[index]
+                    .production
+                    .iter()
+                    .map(|rate| rate.item)
+                    .filter(|item| !snapshots[previous].produces(*item))
+                    .collect(),
+                None => Vec::new(),
+            };
+            new_products.sort_by_key(|item| item_name(*item));
+            snapshots[index].new_products = new_products;
+        }
+
+        let (factory_growth, power_growth, mut top_new_products) =
+            match (snapshots.first(), snapshots.last()) {
+                (Some(first), Some(last)) => (
+                    last.factory_count as i64 - first.factory_count as i64,
+                    last.power_generation - first.power_generation,
+                    last.production
+                        .iter()
+                        .filter(|rate| !first.produces(rate.item))
+                        .cloned()
+                        .collect(),
+                ),
+                _ => (0, 0.0, Vec::new()),
+            };
+        sort_rates(&mut top_new_products);
+        top_new_products.truncate(TOP_NEW_PRODUCTS);
+
+        Self {
+            snapshots,
+            factory_growth,
+            power_growth,
+            top_new_products,
+        }
+    }
+
+    /// One CSV row per snapshot, with a header row
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "label,saved_at,factories,production_lines,logistics_lines,\
+             power_generation_mw,power_consumption_mw,new_products\n",
+        );
+        for snapshot in &self.snapshots {
+            let new_products: Vec<&str> = snapshot
+                .new_products
+                .iter()
+                .map(|item| item_name(*item))
+                .collect();
+            csv += &format!(
+                "{},{},{},{},{},{},{},{}\n",
+                csv_field(&snapshot.label),
+                snapshot
+                    .saved_at
+                    .map(|saved_at| saved_at.to_rfc3339())
+                    .unwrap_or_default(),
+                snapshot.factory_count,
+                snapshot.production_line_count,
+                snapshot.logistics_count,
+                snapshot.power_generation,
+                snapshot.power_consumption,
+                csv_field(&new_products.join(";")),
+            );
+        }
+        csv
+    }
+}
+
+fn sort_rates(rates: &mut [ItemRate]) {
+    rates.sort_by(|a, b| {
+        b.quantity_per_min
+            .total_cmp(&a.quantity_per_min)
+            .then_with(|| item_name(a.item).cmp(item_name(b.item)))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        production_line::{MachineGroup, ProductionLine, ProductionLineRecipe},
+        Recipe,
+    };
+    use uuid::Uuid;
+
+    fn uuid_from_u64<T: From<Uuid>>(value: u64) -> T {
+        Uuid::from_u128(value as u128).into()
+    }
+
+    fn add_line(engine: &mut SatisflowEngine, factory_name: &str, id: u64, recipe: Recipe) {
+        let factory_id = engine.create_factory(factory_name.into(), None);
+        let mut line =
+            ProductionLineRecipe::new(uuid_from_u64(id), factory_name.into(), None, recipe);
+        line.add_machine_group(MachineGroup::new(1, 100.0, 0))
+            .unwrap();
+        engine
+            .get_factory_mut(factory_id)
+            .unwrap()
+            .add_production_line(ProductionLine::ProductionLineRecipe(line));
+    }
+
+    #[test]
+    fn test_season_overview() {
+        let mut engine = SatisflowEngine::new();
+        add_line(&mut engine, "Smelter", 1, Recipe::IronIngot);
+        let week_1 = SeasonSnapshot::from_engine("Week 1", None, &engine);
+
+        add_line(&mut engine, "Plates", 2, Recipe::IronPlate);
+        add_line(&mut engine, "Rods", 3, Recipe::IronRod);
+        let week_2 =
+            SeasonSnapshot::from_save_json("Week, 2", &engine.save_to_json().unwrap()).unwrap();
+
+        let overview = SeasonOverview::build(vec![week_1, week_2]);
+
+        assert_eq!(overview.factory_growth, 2);
+        assert_eq!(overview.power_growth, 0.0);
+        assert!(overview.snapshots[0].new_products.is_empty());
+        assert_eq!(
+            overview.snapshots[1].new_products,
+            vec![Item::IronPlate, Item::IronRod]
+        );
+        assert_eq!(overview.top_new_products[0].item, Item::IronPlate);
+        assert_eq!(overview.top_new_products[0].quantity_per_min, 20.0);
+        assert!(overview.snapshots[1].saved_at.is_some());
+
+        let csv = overview.to_csv();
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[1], "Week 1,,1,1,0,0,4,");
+        assert!(rows[2].starts_with("\"Week, 2\","));
+        assert!(rows[2].ends_with(",3,3,0,0,12,Iron Plate;Iron Rod"));
+    }
+
+    #[test]
+    fn test_empty_season() {
+        let overview = SeasonOverview::build(Vec::new());
+        assert_eq!(overview.factory_growth, 0);
+        assert!(overview.top_new_products.is_empty());
+        assert_eq!(overview.to_csv().lines().count(), 1);
+    }
+}
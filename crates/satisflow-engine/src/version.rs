@@ -3,6 +3,8 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+use crate::models::TransportDetails;
+
 /// Semantic version for save files
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct SaveVersion {
@@ -13,7 +15,7 @@ pub struct SaveVersion {
 
 impl SaveVersion {
     /// Create a new version
-    pub fn new(major: u32, minor: u32, patch: u32) -> Self {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
         Self {
             major,
             minor,
@@ -75,6 +77,381 @@ impl fmt::Display for SaveVersion {
     }
 }
 
+/// An optional save field that is only written when the save targets the
+/// version that introduced it (or later)
+///
+/// Gates let the format evolve while "save as" an older version still produces
+/// files that older clients accept. A feature gated above the current engine
+/// version stays dark until the crate version is bumped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SaveFeature {
+    pub name: &'static str,
+    pub introduced_in: SaveVersion,
+    /// Path from the `engine` object to the field; `*` matches every entry of a map
+    pub path: &'static [&'static str],
+    /// Value older clients assume when the field is missing, for flags that
+    /// default to `true`; `None` treats any empty value as assumed
+    pub assumed: Option<bool>,
+}
+
+impl SaveFeature {
+    /// Check whether the feature is written for a target version
+    pub fn is_enabled_for(&self, target: &SaveVersion) -> bool {
+        *target >= self.introduced_in
+    }
+}
+
+/// Optional save fields and the version that introduced them
+pub const SAVE_FEATURES: &[SaveFeature] = &[
+    SaveFeature {
+        name: "transport_naming",
+        introduced_in: SaveVersion::new(0, 2, 0),
+        path: &["transport_naming"],
+        assumed: None,
+    },
+    SaveFeature {
+        name: "resource_nodes",
+        introduced_in: SaveVersion::new(0, 2, 0),
+        path: &["resource_nodes"],
+        assumed: None,
+    },
+    SaveFeature {
+        name: "internal_transfers",
+        introduced_in: SaveVersion::new(0, 2, 0),
+        path: &["factories", "*", "internal_transfers"],
+        assumed: None,
+    },
+    SaveFeature {
+        name: "factory_tasks",
+        introduced_in: SaveVersion::new(0, 2, 0),
+        path: &["factories", "*", "tasks"],
+        assumed: None,
+    },
+    SaveFeature {
+        name: "factory_templates",
+        introduced_in: SaveVersion::new(0, 2, 0),
+        path: &["factory_templates"],
+        assumed: None,
+    },
+    SaveFeature {
+        name: "raw_input_node_claims",
+        introduced_in: SaveVersion::new(0, 2, 0),
+        path: &["factories", "*", "raw_inputs", "*", "claims_node"],
+        assumed: None,
+    },
+    SaveFeature {
+        name: "power_grids",
+        introduced_in: SaveVersion::new(0, 2, 0),
+        path: &["power_grids"],
+        assumed: None,
+    },
+    SaveFeature {
+        name: "factory_power_grid",
+        introduced_in: SaveVersion::new(0, 2, 0),
+        path: &["factories", "*", "power_grid"],
+        assumed: None,
+    },
+    SaveFeature {
+        name: "factory_power_storage",
+        introduced_in: SaveVersion::new(0, 2, 0),
+        path: &["factories", "*", "power_storage"],
+        assumed: None,
+    },
+    SaveFeature {
+        name: "factory_storage_buffers",
+        introduced_in: SaveVersion::new(0, 2, 0),
+        path: &["factories", "*", "storage_buffers"],
+        assumed: None,
+    },
+    SaveFeature {
+        name: "bus_lifts",
+        introduced_in: SaveVersion::new(0, 2, 0),
+        path: &["logistics_lines", "*", "transport_type", "Bus", "lifts"],
+        assumed: None,
+    },
+    SaveFeature {
+        name: "geothermal_purity",
+        introduced_in: SaveVersion::new(0, 2, 0),
+        path: &["factories", "*", "power_generators", "*", "purity"],
+        assumed: None,
+    },
+    SaveFeature {
+        name: "pinned",
+        introduced_in: SaveVersion::new(0, 2, 0),
+        path: &["pinned"],
+        assumed: None,
+    },
+    SaveFeature {
+        name: "factory_timestamps",
+        introduced_in: SaveVersion::new(0, 2, 0),
+        path: &["factories", "*", "timestamps"],
+        assumed: None,
+    },
+    SaveFeature {
+        name: "production_line_timestamps",
+        introduced_in: SaveVersion::new(0, 2, 0),
+        path: &["factories", "*", "production_line_timestamps"],
+        assumed: None,
+    },
+    SaveFeature {
+        name: "logistics_timestamps",
+        introduced_in: SaveVersion::new(0, 2, 0),
+        path: &["logistics_lines", "*", "timestamps"],
+        assumed: None,
+    },
+    SaveFeature {
+        name: "goals",
+        introduced_in: SaveVersion::new(0, 2, 0),
+        path: &["goals"],
+        assumed: None,
+    },
+    SaveFeature {
+        name: "production_line_enabled",
+        introduced_in: SaveVersion::new(0, 2, 0),
+        path: &["factories", "*", "production_lines", "*", "*", "enabled"],
+        assumed: Some(true),
+    },
+    SaveFeature {
+        name: "power_generator_enabled",
+        introduced_in: SaveVersion::new(0, 2, 0),
+        path: &["factories", "*", "power_generators", "*", "enabled"],
+        assumed: Some(true),
+    },
+    SaveFeature {
+        name: "raw_input_enabled",
+        introduced_in: SaveVersion::new(0, 2, 0),
+        path: &["factories", "*", "raw_inputs", "*", "enabled"],
+        assumed: Some(true),
+    },
+    SaveFeature {
+        name: "tier_progress",
+        introduced_in: SaveVersion::new(0, 2, 0),
+        path: &["tier_progress"],
+        assumed: None,
+    },
+    SaveFeature {
+        name: "custom_recipes",
+        introduced_in: SaveVersion::new(0, 2, 0),
+        path: &["custom_recipes"],
+        assumed: None,
+    },
+    SaveFeature {
+        name: "factory_tags",
+        introduced_in: SaveVersion::new(0, 2, 0),
+        path: &["factories", "*", "tags"],
+        assumed: None,
+    },
+    SaveFeature {
+        name: "factory_archived",
+        introduced_in: SaveVersion::new(0, 2, 0),
+        path: &["factories", "*", "archived"],
+        assumed: None,
+    },
+    SaveFeature {
+        name: "factory_position",
+        introduced_in: SaveVersion::new(0, 2, 0),
+        path: &["factories", "*", "position"],
+        assumed: None,
+    },
+    SaveFeature {
+        name: "blueprint_template_revision",
+        introduced_in: SaveVersion::new(0, 2, 0),
+        path: &["factories", "*", "production_lines", "*", "*", "revision"],
+        assumed: None,
+    },
+    SaveFeature {
+        name: "blueprint_template_link",
+        introduced_in: SaveVersion::new(0, 2, 0),
+        path: &[
+            "factories",
+            "*",
+            "production_lines",
+            "*",
+            "*",
+            "template_id",
+        ],
+        assumed: None,
+    },
+    SaveFeature {
+        name: "ficsmas",
+        introduced_in: SaveVersion::new(0, 2, 0),
+        path: &["ficsmas"],
+        assumed: Some(false),
+    },
+    SaveFeature {
+        name: "game_balance",
+        introduced_in: SaveVersion::new(0, 2, 0),
+        path: &["balance"],
+        assumed: None,
+    },
+];
+
+/// First version whose saves store logistics transport details typed rather
+/// than as text
+pub const TYPED_TRANSPORT_DETAILS: SaveVersion = SaveVersion::new(0, 2, 0);
+
+/// Write logistics transport details as text for targets older than
+/// [`TYPED_TRANSPORT_DETAILS`]
+///
+/// Older clients read details as a plain string, so typed details are written
+/// as their labels.
+pub fn flatten_transport_details(engine: &mut serde_json::Value, target: &SaveVersion) {
+    if *target >= TYPED_TRANSPORT_DETAILS {
+        return;
+    }
+    let Some(lines) = engine["logistics_lines"].as_object_mut() else {
+        return;
+    };
+    for line in lines.values_mut() {
+        let details = &mut line["transport_details"];
+        if let Ok(typed) = serde_json::from_value::<TransportDetails>(details.clone()) {
+            *details = serde_json::Value::String(typed.to_string());
+        }
+    }
+}
+
+/// Values removed from a save for one gated feature
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrippedField {
+    pub feature: &'static SaveFeature,
+    pub values: Vec<serde_json::Value>,
+}
+
+/// Remove every field gated above `target` from a serialized engine
+///
+/// # Returns
+///
+/// The removed values, grouped by feature; features with nothing to remove are omitted
+pub fn strip_gated_fields(
+    engine: &mut serde_json::Value,
+    features: &'static [SaveFeature],
+    target: &SaveVersion,
+) -> Vec<StrippedField> {
+    let mut stripped = Vec::new();
+    for feature in features {
+        if feature.is_enabled_for(target) {
+            continue;
+        }
+        let mut values = Vec::new();
+        remove_path(engine, feature.path, &mut values);
+        if !values.is_empty() {
+            stripped.push(StrippedField { feature, values });
+        }
+    }
+    stripped
+}
+
+fn remove_path(value: &mut serde_json::Value, path: &[&str], removed: &mut Vec<serde_json::Value>) {
+    let Some((head, rest)) = path.split_first() else {
+        return;
+    };
+    let Some(object) = value.as_object_mut() else {
+        return;
+    };
+    match (*head, rest.is_empty()) {
+        ("*", _) => {
+            for child in object.values_mut() {
+                remove_path(child, rest, removed);
+            }
+        }
+        (key, true) => {
+            if let Some(value) = object.remove(key) {
+                removed.push(value);
+            }
+        }
+        (key, false) => {
+            if let Some(child) = object.get_mut(key) {
+                remove_path(child, rest, removed);
+            }
+        }
+    }
+}
+
+/// A feature whose data could not be kept when saving as an older version
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LostFeature {
+    pub feature: String,
+    pub introduced_in: String,
+    /// Number of dropped entries; a changed setting counts as one
+    pub count: usize,
+}
+
+/// What was dropped when exporting a save for an older version
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DowngradeReport {
+    pub target_version: String,
+    pub lost: Vec<LostFeature>,
+}
+
+impl DowngradeReport {
+    /// Build the report from stripped fields
+    ///
+    /// Empty values (`null`, `false`, `{}`, `[]`), flags at their assumed value
+    /// and top-level settings still at their default are not counted as lost,
+    /// since older clients fall back to the same defaults.
+    pub fn from_stripped(
+        target: &SaveVersion,
+        stripped: &[StrippedField],
+        defaults: &serde_json::Value,
+    ) -> Self {
+        let lost = stripped
+            .iter()
+            .filter_map(|field| {
+                let default = match field.feature.path {
+                    [key] => defaults.get(*key),
+                    _ => None,
+                };
+                let count = field
+                    .values
+                    .iter()
+                    .filter(|value| !is_assumed(field.feature, value) && Some(*value) != default)
+                    .map(entry_count)
+                    .sum::<usize>();
+                (count > 0).then(|| LostFeature {
+                    feature: field.feature.name.to_string(),
+                    introduced_in: field.feature.introduced_in.to_string(),
+                    count,
+                })
+            })
+            .collect();
+
+        Self {
+            target_version: target.to_string(),
+            lost,
+        }
+    }
+
+    /// Check whether nothing was lost
+    pub fn is_lossless(&self) -> bool {
+        self.lost.is_empty()
+    }
+}
+
+/// Collections (arrays, maps of objects) count their entries; anything else is one setting
+fn entry_count(value: &serde_json::Value) -> usize {
+    match value {
+        serde_json::Value::Array(items) => items.len(),
+        serde_json::Value::Object(map) if map.values().all(|v| v.is_object()) => map.len(),
+        _ => 1,
+    }
+}
+
+fn is_assumed(feature: &SaveFeature, value: &serde_json::Value) -> bool {
+    match feature.assumed {
+        Some(assumed) => value.as_bool() == Some(assumed),
+        None => is_empty_value(value),
+    }
+}
+
+fn is_empty_value(value: &serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Null | serde_json::Value::Bool(false) => true,
+        serde_json::Value::Object(map) => map.is_empty(),
+        serde_json::Value::Array(items) => items.is_empty(),
+        _ => false,
+    }
+}
+
 /// Errors related to version handling
 #[derive(Debug, thiserror::Error)]
 pub enum VersionError {
@@ -97,6 +474,12 @@ pub enum VersionError {
         engine_version: String,
     },
 
+    #[error("Cannot save as version {target_version}: engine version {engine_version} can only target compatible versions up to its own")]
+    UnsupportedTarget {
+        target_version: String,
+        engine_version: String,
+    },
+
     #[error("Save file is too old: file version {save_version}, engine version {engine_version}. Migration not yet implemented.")]
     SaveTooOld {
         save_version: String,
@@ -170,6 +553,132 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_strip_gated_fields() {
+        static FEATURES: &[SaveFeature] = &[
+            SaveFeature {
+                name: "top_level",
+                introduced_in: SaveVersion::new(1, 3, 0),
+                path: &["extra"],
+                assumed: None,
+            },
+            SaveFeature {
+                name: "nested",
+                introduced_in: SaveVersion::new(1, 4, 0),
+                path: &["factories", "*", "tags"],
+                assumed: None,
+            },
+        ];
+        let original = serde_json::json!({
+            "extra": 1,
+            "factories": {
+                "a": { "name": "A", "tags": ["x"] },
+                "b": { "name": "B" }
+            }
+        });
+
+        let mut value = original.clone();
+        let stripped = strip_gated_fields(&mut value, FEATURES, &SaveVersion::new(1, 4, 0));
+        assert_eq!(value, original);
+        assert!(stripped.is_empty());
+
+        let stripped = strip_gated_fields(&mut value, FEATURES, &SaveVersion::new(1, 3, 2));
+        assert_eq!(value["extra"], 1);
+        assert!(value["factories"]["a"].get("tags").is_none());
+        assert_eq!(stripped.len(), 1);
+        assert_eq!(stripped[0].feature.name, "nested");
+
+        strip_gated_fields(&mut value, FEATURES, &SaveVersion::new(1, 2, 0));
+        assert!(value.get("extra").is_none());
+        assert_eq!(value["factories"]["a"]["name"], "A");
+    }
+
+    #[test]
+    fn test_flatten_transport_details() {
+        let typed = serde_json::to_value(TransportDetails::Truck {
+            truck_id: "TRK-777".into(),
+        })
+        .unwrap();
+        let original = serde_json::json!({
+            "logistics_lines": {
+                "a": { "transport_details": typed },
+                "b": { "transport_details": { "Note": "Ore run" } }
+            }
+        });
+
+        let mut value = original.clone();
+        flatten_transport_details(&mut value, &TYPED_TRANSPORT_DETAILS);
+        assert_eq!(value, original);
+
+        flatten_transport_details(&mut value, &SaveVersion::new(0, 1, 0));
+        assert_eq!(
+            value["logistics_lines"]["a"]["transport_details"],
+            "TRK-777"
+        );
+        assert_eq!(
+            value["logistics_lines"]["b"]["transport_details"],
+            "Ore run"
+        );
+    }
+
+    #[test]
+    fn test_downgrade_report_ignores_empty_and_default_values() {
+        static FEATURES: &[SaveFeature] = &[
+            SaveFeature {
+                name: "settings",
+                introduced_in: SaveVersion::new(1, 3, 0),
+                path: &["settings"],
+                assumed: None,
+            },
+            SaveFeature {
+                name: "tags",
+                introduced_in: SaveVersion::new(1, 3, 0),
+                path: &["factories", "*", "tags"],
+                assumed: None,
+            },
+        ];
+        let defaults = serde_json::json!({ "settings": { "mode": "auto" } });
+        let target = SaveVersion::new(1, 2, 0);
+
+        let mut value = serde_json::json!({
+            "settings": { "mode": "auto" },
+            "factories": { "a": { "tags": [] }, "b": { "tags": ["x", "y"] } }
+        });
+        let stripped = strip_gated_fields(&mut value, FEATURES, &target);
+        let report = DowngradeReport::from_stripped(&target, &stripped, &defaults);
+
+        assert_eq!(report.target_version, "1.2.0");
+        assert_eq!(report.lost.len(), 1);
+        assert_eq!(report.lost[0].feature, "tags");
+        assert_eq!(report.lost[0].count, 2);
+
+        let mut value = serde_json::json!({ "settings": { "mode": "manual" } });
+        let stripped = strip_gated_fields(&mut value, FEATURES, &target);
+        let report = DowngradeReport::from_stripped(&target, &stripped, &defaults);
+        assert_eq!(report.lost[0].feature, "settings");
+        assert_eq!(report.lost[0].count, 1);
+        assert!(!report.is_lossless());
+    }
+
+    #[test]
+    fn test_downgrade_report_counts_flags_off_their_assumed_value() {
+        static FEATURES: &[SaveFeature] = &[SaveFeature {
+            name: "active",
+            introduced_in: SaveVersion::new(1, 3, 0),
+            path: &["factories", "*", "active"],
+            assumed: Some(true),
+        }];
+        let target = SaveVersion::new(1, 2, 0);
+
+        let mut value = serde_json::json!({
+            "factories": { "a": { "active": true }, "b": { "active": false } }
+        });
+        let stripped = strip_gated_fields(&mut value, FEATURES, &target);
+        let report = DowngradeReport::from_stripped(&target, &stripped, &serde_json::json!({}));
+        assert_eq!(report.lost.len(), 1);
+        assert_eq!(report.lost[0].count, 1);
+    }
+
     #[test]
     fn test_version_equality() {
         let v1 = SaveVersion::new(1, 2, 3);
@@ -0,0 +1,14 @@
+// crates/satisflow-server/build.rs
+//! Generates the gRPC service from `proto/satisflow.proto` when the `grpc`
+//! feature is on
+
+fn main() {
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/satisflow.proto");
+        // Use the bundled protoc so building doesn't need one installed
+        let protoc = protoc_bin_vendored::protoc_bin_path().expect("no bundled protoc");
+        std::env::set_var("PROTOC", protoc);
+        tonic_build::compile_protos("proto/satisflow.proto").expect("failed to compile protos");
+    }
+}
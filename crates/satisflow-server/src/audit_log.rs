@@ -0,0 +1,208 @@
+// crates/satisflow-server/src/audit_log.rs
+//! Who changed what, and when
+//!
+//! Every write that changes the engine is recorded with the request that made
+//! it, the ID of the entity it targeted and, once API keys are configured, the
+//! key that sent it. The log is append-only and kept in memory; with
+//! `AUDIT_LOG_DIR` set each entry is also appended to `audit.jsonl` in that
+//! directory, which is read back when the server starts.
+
+use std::env;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+const FILE_NAME: &str = "audit.jsonl";
+
+/// Who sent a request, set by the API key middleware
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Actor(pub String);
+
+/// A recorded change to the engine
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Engine revision after the change
+    pub revision: u64,
+    pub timestamp: DateTime<Utc>,
+    /// HTTP method of the request, or `gRPC` for calls to the gRPC service
+    pub method: String,
+    /// Request path, or the name of the gRPC call
+    pub path: String,
+    /// Entity the request targeted or created, when it names one
+    pub entity_id: Option<Uuid>,
+    /// API key that sent the request, when authentication is on
+    pub actor: Option<String>,
+}
+
+impl AuditEntry {
+    /// Entry for a change made right now
+    pub fn new(
+        revision: u64,
+        method: impl Into<String>,
+        path: impl Into<String>,
+        entity_id: Option<Uuid>,
+        actor: Option<&Actor>,
+    ) -> Self {
+        Self {
+            revision,
+            timestamp: Utc::now(),
+            method: method.into(),
+            path: path.into(),
+            entity_id,
+            actor: actor.map(|actor| actor.0.clone()),
+        }
+    }
+}
+
+/// The recorded changes of one engine, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct AuditLog {
+    entries: Arc<RwLock<Vec<AuditEntry>>>,
+    /// JSON Lines file every entry is appended to
+    file: Option<PathBuf>,
+}
+
+impl AuditLog {
+    /// Persist the log to `AUDIT_LOG_DIR` when it is set, loading the entries
+    /// already there; keep it in memory otherwise
+    pub fn from_env() -> anyhow::Result<Self> {
+        match env::var("AUDIT_LOG_DIR") {
+            Ok(dir) if !dir.trim().is_empty() => Self::in_dir(PathBuf::from(dir.trim())),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// Log persisted to `audit.jsonl` in `dir`, starting with the entries
+    /// already in that file
+    ///
+    /// # Returns
+    ///
+    /// An error if the file exists but can't be read
+    pub fn in_dir(dir: PathBuf) -> anyhow::Result<Self> {
+        let file = dir.join(FILE_NAME);
+        let mut entries = Vec::new();
+        if file.exists() {
+            let reader = BufReader::new(std::fs::File::open(&file)?);
+            for line in reader.lines() {
+                let line = line?;
+                if !line.trim().is_empty() {
+                    entries.push(serde_json::from_str(&line)?);
+                }
+            }
+        }
+        Ok(Self {
+            entries: Arc::new(RwLock::new(entries)),
+            file: Some(file),
+        })
+    }
+
+    /// Log for a workspace other than the default one
+    pub fn for_workspace(&self, id: &str) -> Self {
+        Self {
+            entries: Arc::default(),
+            file: self.file.as_ref().and_then(|file| {
+                let dir = file.parent()?;
+                Some(dir.join("workspaces").join(id).join(FILE_NAME))
+            }),
+        }
+    }
+
+    /// Append an entry
+    ///
+    /// # Returns
+    ///
+    /// An error if the entry couldn't be written to the file; it is kept in
+    /// memory either way
+    pub async fn record(&self, entry: AuditEntry) -> anyhow::Result<()> {
+        let line = serde_json::to_string(&entry)?;
+        self.entries.write().await.push(entry);
+
+        if let Some(file) = &self.file {
+            if let Some(dir) = file.parent() {
+                tokio::fs::create_dir_all(dir).await?;
+            }
+            let mut file = tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(file)
+                .await?;
+            file.write_all(format!("{}\n", line).as_bytes()).await?;
+            file.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// Entries recorded after `since`, or all of them, oldest first
+    pub async fn entries(&self, since: Option<DateTime<Utc>>) -> Vec<AuditEntry> {
+        self.entries
+            .read()
+            .await
+            .iter()
+            .filter(|entry| since.is_none_or(|since| entry.timestamp > since))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Last UUID among the segments of a request path
+pub fn path_entity_id(path: &str) -> Option<Uuid> {
+    path.split('/')
+        .rev()
+        .find_map(|segment| Uuid::parse_str(segment).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_audit_log_persists() {
+        let dir = env::temp_dir().join(format!("satisflow-audit-{}", Uuid::new_v4()));
+        let log = AuditLog::in_dir(dir.clone()).unwrap();
+        let id = Uuid::new_v4();
+        let path = format!("/api/logistics/{}", id);
+        let actor = Actor("key 1234abcd".to_string());
+        log.record(AuditEntry::new(
+            4,
+            "DELETE",
+            &path,
+            path_entity_id(&path),
+            Some(&actor),
+        ))
+        .await
+        .unwrap();
+
+        let reloaded = AuditLog::in_dir(dir.clone()).unwrap();
+        let entries = reloaded.entries(None).await;
+        assert_eq!(entries, log.entries(None).await);
+        assert_eq!(entries[0].entity_id, Some(id));
+        assert_eq!(entries[0].actor.as_deref(), Some("key 1234abcd"));
+        assert!(reloaded
+            .entries(Some(entries[0].timestamp))
+            .await
+            .is_empty());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_path_entity_id() {
+        let factory = Uuid::new_v4();
+        let task = Uuid::new_v4();
+        assert_eq!(
+            path_entity_id(&format!("/api/factories/{}/tasks/{}", factory, task)),
+            Some(task)
+        );
+        assert_eq!(
+            path_entity_id(&format!("/api/factories/{}/tasks", factory)),
+            Some(factory)
+        );
+        assert_eq!(path_entity_id("/api/load"), None);
+    }
+}
@@ -0,0 +1,198 @@
+// crates/satisflow-server/src/auth.rs
+//! Optional API key authentication
+//!
+//! Without keys configured the API is open, which is fine on localhost. Once
+//! `API_KEYS` or `READ_ONLY_API_KEYS` is set every `/api` request needs one of
+//! the keys, sent as `Authorization: Bearer <key>` or `X-API-Key: <key>`.
+//! Read-only keys may only send `GET` and `HEAD` requests.
+
+use std::collections::HashMap;
+use std::env;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, Method},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::audit_log::Actor;
+use crate::error::AppError;
+
+/// Header carrying the key for clients that can't set `Authorization`
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// What a key is allowed to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    ReadOnly,
+    ReadWrite,
+}
+
+impl Role {
+    /// Whether the role may send a request with `method`
+    pub fn allows(self, method: &Method) -> bool {
+        match self {
+            Role::ReadWrite => true,
+            Role::ReadOnly => matches!(*method, Method::GET | Method::HEAD),
+        }
+    }
+}
+
+/// The accepted API keys and their roles
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthConfig {
+    keys: Arc<HashMap<String, Role>>,
+}
+
+impl AuthConfig {
+    /// Build a configuration from lists of keys
+    ///
+    /// # Returns
+    ///
+    /// An error if a key is listed with both roles
+    pub fn new(
+        read_write: impl IntoIterator<Item = String>,
+        read_only: impl IntoIterator<Item = String>,
+    ) -> anyhow::Result<Self> {
+        let mut keys: HashMap<String, Role> = read_write
+            .into_iter()
+            .map(|key| (key, Role::ReadWrite))
+            .collect();
+        for key in read_only {
+            if keys.get(&key) == Some(&Role::ReadWrite) {
+                return Err(anyhow!("An API key can't be both read-write and read-only"));
+            }
+            keys.insert(key, Role::ReadOnly);
+        }
+        Ok(Self {
+            keys: Arc::new(keys),
+        })
+    }
+
+    /// Read the keys from `API_KEYS` (read-write) and `READ_ONLY_API_KEYS`,
+    /// both comma separated
+    ///
+    /// # Returns
+    ///
+    /// `None` when neither lists a key, i.e. authentication is off
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        let read_write = keys_from_env("API_KEYS");
+        let read_only = keys_from_env("READ_ONLY_API_KEYS");
+        if read_write.is_empty() && read_only.is_empty() {
+            return Ok(None);
+        }
+        Self::new(read_write, read_only).map(Some)
+    }
+
+    /// Role of `key`, if it is one of the configured keys
+    pub fn role(&self, key: &str) -> Option<Role> {
+        // Compare every key in full so timing doesn't hint at a valid prefix
+        self.keys.iter().fold(None, |found, (candidate, role)| {
+            if constant_time_eq(candidate.as_bytes(), key.as_bytes()) {
+                Some(*role)
+            } else {
+                found
+            }
+        })
+    }
+
+    /// Label of `key` in the audit log
+    ///
+    /// A fingerprint tells the keys apart without writing them to the log.
+    pub fn actor(&self, key: &str) -> Actor {
+        Actor(format!("key {:08x}", fingerprint(key)))
+    }
+
+    /// Number of configured keys with `role`
+    pub fn count(&self, role: Role) -> usize {
+        self.keys.values().filter(|r| **r == role).count()
+    }
+}
+
+fn keys_from_env(name: &str) -> Vec<String> {
+    env::var(name)
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// 32-bit FNV-1a hash, stable across builds unlike `DefaultHasher`
+fn fingerprint(key: &str) -> u32 {
+    key.bytes().fold(0x811c_9dc5, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Key sent with a request, from `Authorization: Bearer` or `X-API-Key`
+fn request_key(headers: &HeaderMap) -> Option<&str> {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    bearer
+        .or_else(|| {
+            headers
+                .get(API_KEY_HEADER)
+                .and_then(|value| value.to_str().ok())
+        })
+        .map(str::trim)
+}
+
+/// Middleware answering `401 Unauthorized` to requests without a valid key,
+/// and `403 Forbidden` to writes with a read-only key
+///
+/// Accepted requests carry the [`Actor`] of their key for the audit log.
+pub async fn require_api_key(
+    State(config): State<AuthConfig>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let Some(key) = request_key(request.headers()) else {
+        return AppError::Unauthorized("An API key is required".to_string()).into_response();
+    };
+    let Some(role) = config.role(key) else {
+        return AppError::Unauthorized("Invalid API key".to_string()).into_response();
+    };
+    if !role.allows(request.method()) {
+        return AppError::Forbidden("This API key is read-only".to_string()).into_response();
+    }
+    let actor = config.actor(key);
+    request.extensions_mut().insert(actor);
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roles() {
+        let config =
+            AuthConfig::new(vec!["writer".to_string()], vec!["reader".to_string()]).unwrap();
+
+        assert_eq!(config.role("writer"), Some(Role::ReadWrite));
+        assert_eq!(config.role("reader"), Some(Role::ReadOnly));
+        assert_eq!(config.role("write"), None);
+        assert_eq!(config.role(""), None);
+
+        assert!(Role::ReadOnly.allows(&Method::GET));
+        assert!(!Role::ReadOnly.allows(&Method::DELETE));
+        assert!(Role::ReadWrite.allows(&Method::POST));
+
+        assert!(AuthConfig::new(vec!["key".to_string()], vec!["key".to_string()]).is_err());
+
+        assert_eq!(config.actor("writer"), config.actor("writer"));
+        assert_ne!(config.actor("writer"), config.actor("reader"));
+        assert!(!config.actor("writer").0.contains("writer"));
+    }
+}
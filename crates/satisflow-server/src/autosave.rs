@@ -0,0 +1,286 @@
+// crates/satisflow-server/src/autosave.rs
+//! Background autosave
+//!
+//! When `AUTOSAVE_DIR` is set the server writes the engine state to that
+//! directory on a timer, skipping ticks where nothing changed since the last
+//! autosave, and only keeps the most recent files.
+
+use std::collections::hash_map::DefaultHasher;
+use std::env;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use satisflow_engine::{SatisflowEngine, SaveFormat, SaveOptions};
+use serde::Serialize;
+use serde_json::json;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::state::AppState;
+use crate::webhooks::WebhookEvent;
+
+pub const DEFAULT_INTERVAL_SECS: u64 = 300;
+pub const DEFAULT_KEEP: usize = 10;
+
+const FILE_PREFIX: &str = "autosave-";
+const FILE_EXTENSION: &str = ".json";
+
+/// Settings of the autosave task
+#[derive(Debug, Clone, PartialEq)]
+pub struct AutosaveConfig {
+    /// Directory the autosaves are written to, created if missing
+    pub dir: PathBuf,
+    pub interval: Duration,
+    /// Number of autosaves kept; older ones are deleted
+    pub keep: usize,
+}
+
+impl AutosaveConfig {
+    /// Read the autosave settings from `AUTOSAVE_DIR`, `AUTOSAVE_INTERVAL_SECS`
+    /// and `AUTOSAVE_KEEP`
+    ///
+    /// # Returns
+    ///
+    /// `None` unless `AUTOSAVE_DIR` is set
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        let dir = match env::var("AUTOSAVE_DIR") {
+            Ok(dir) if !dir.trim().is_empty() => PathBuf::from(dir.trim()),
+            _ => return Ok(None),
+        };
+
+        let interval_secs: u64 = env::var("AUTOSAVE_INTERVAL_SECS")
+            .unwrap_or_else(|_| DEFAULT_INTERVAL_SECS.to_string())
+            .parse()
+            .ok()
+            .filter(|secs| *secs > 0)
+            .ok_or_else(|| anyhow!("Invalid AUTOSAVE_INTERVAL_SECS format"))?;
+
+        let keep: usize = env::var("AUTOSAVE_KEEP")
+            .unwrap_or_else(|_| DEFAULT_KEEP.to_string())
+            .parse()
+            .ok()
+            .filter(|keep| *keep > 0)
+            .ok_or_else(|| anyhow!("Invalid AUTOSAVE_KEEP format"))?;
+
+        Ok(Some(Self {
+            dir,
+            interval: Duration::from_secs(interval_secs),
+            keep,
+        }))
+    }
+}
+
+/// An autosave file on disk
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AutosaveEntry {
+    pub file_name: String,
+    pub size_bytes: u64,
+    pub saved_at: DateTime<Utc>,
+}
+
+/// Fingerprint of the engine state, to tell whether it changed
+fn state_hash(engine: &SatisflowEngine) -> anyhow::Result<u64> {
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_vec(engine)?.hash(&mut hasher);
+    Ok(hasher.finish())
+}
+
+/// Write an autosave if the state changed since `last_hash`
+///
+/// # Returns
+///
+/// The path of the new autosave, or `None` when the state didn't change
+pub async fn autosave_if_changed(
+    state: &AppState,
+    config: &AutosaveConfig,
+    last_hash: &mut Option<u64>,
+) -> anyhow::Result<Option<PathBuf>> {
+    let bytes = {
+        let engine = state.engine.snapshot();
+        let hash = state_hash(&engine)?;
+        if *last_hash == Some(hash) {
+            return Ok(None);
+        }
+        *last_hash = Some(hash);
+        let options = SaveOptions {
+            format: SaveFormat::JsonCompact,
+            ..Default::default()
+        };
+        engine
+            .save_to_bytes_with_options(&options)
+            .map_err(|e| anyhow!("Failed to serialize save: {}", e))?
+    };
+
+    let config = config.clone();
+    let path = tokio::task::spawn_blocking(move || {
+        write_autosave(&config.dir, &bytes, Utc::now(), config.keep)
+    })
+    .await??;
+    Ok(Some(path))
+}
+
+/// Write `bytes` as a new autosave and delete the ones beyond `keep`
+///
+/// # Returns
+///
+/// The path of the new autosave
+pub fn write_autosave(
+    dir: &Path,
+    bytes: &[u8],
+    now: DateTime<Utc>,
+    keep: usize,
+) -> anyhow::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let file_name = format!(
+        "{}{}{}",
+        FILE_PREFIX,
+        now.format("%Y%m%d-%H%M%S%.3f"),
+        FILE_EXTENSION
+    );
+    let path = dir.join(&file_name);
+
+    // Write then rename, so a crash never leaves a truncated autosave behind
+    let partial = dir.join(format!(".{}.partial", file_name));
+    std::fs::write(&partial, bytes)?;
+    std::fs::rename(&partial, &path)?;
+
+    for old in autosave_names(dir)?.into_iter().skip(keep) {
+        if let Err(e) = std::fs::remove_file(dir.join(&old)) {
+            warn!("Failed to delete old autosave '{}': {}", old, e);
+        }
+    }
+    Ok(path)
+}
+
+/// Names of the autosaves in `dir`, newest first
+///
+/// File names embed the save time, so sorting them sorts by age.
+fn autosave_names(dir: &Path) -> std::io::Result<Vec<String>> {
+    let mut names = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let name = entry?.file_name().to_string_lossy().into_owned();
+        if name.starts_with(FILE_PREFIX) && name.ends_with(FILE_EXTENSION) {
+            names.push(name);
+        }
+    }
+    names.sort_unstable_by(|a, b| b.cmp(a));
+    Ok(names)
+}
+
+/// List the autosaves in `dir`, newest first
+///
+/// A directory that doesn't exist yet has no autosaves.
+pub fn list_autosaves(dir: &Path) -> std::io::Result<Vec<AutosaveEntry>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    autosave_names(dir)?
+        .into_iter()
+        .map(|file_name| {
+            let metadata = std::fs::metadata(dir.join(&file_name))?;
+            Ok(AutosaveEntry {
+                size_bytes: metadata.len(),
+                saved_at: metadata.modified()?.into(),
+                file_name,
+            })
+        })
+        .collect()
+}
+
+/// Autosave the engine state every `interval` when it changed
+pub fn spawn_autosave_task(state: AppState, config: AutosaveConfig) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        // The state the server started with doesn't need saving
+        let mut last_hash = state_hash(&state.engine.snapshot()).ok();
+        let mut interval = tokio::time::interval(config.interval);
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            match autosave_if_changed(&state, &config, &mut last_hash).await {
+                Ok(Some(path)) => {
+                    info!("Autosaved to {}", path.display());
+                    let file_name = path.file_name().map(|name| name.to_string_lossy());
+                    state.webhooks.notify(
+                        WebhookEvent::SaveCreated,
+                        json!({ "source": "autosave", "file_name": file_name }),
+                    );
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    // Try again on the next tick even if nothing changes
+                    last_hash = None;
+                    warn!("Autosave failed: {}", e);
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn temp_dir() -> PathBuf {
+        env::temp_dir().join(format!("satisflow-autosave-{}", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn test_write_autosave_rotates_old_files() {
+        let dir = temp_dir();
+        for minute in 0..4 {
+            let now = Utc.with_ymd_and_hms(2024, 5, 1, 12, minute, 0).unwrap();
+            write_autosave(&dir, b"{}", now, 2).unwrap();
+        }
+
+        let entries = list_autosaves(&dir).unwrap();
+        let names: Vec<&str> = entries.iter().map(|e| e.file_name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "autosave-20240501-120300.000.json",
+                "autosave-20240501-120200.000.json"
+            ]
+        );
+        assert_eq!(entries[0].size_bytes, 2);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(list_autosaves(&dir).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_autosave_skips_unchanged_state() {
+        let dir = temp_dir();
+        let config = AutosaveConfig {
+            dir: dir.clone(),
+            interval: Duration::from_secs(60),
+            keep: 5,
+        };
+        let state = AppState::new();
+        let mut last_hash = None;
+
+        let path = autosave_if_changed(&state, &config, &mut last_hash)
+            .await
+            .unwrap()
+            .unwrap();
+        let saved = SatisflowEngine::load_from_file(&path).unwrap();
+        assert!(saved.get_all_factories().is_empty());
+        assert!(autosave_if_changed(&state, &config, &mut last_hash)
+            .await
+            .unwrap()
+            .is_none());
+
+        let mut engine = state.engine.write().await;
+        engine.create_factory("Smelter".to_string(), None);
+        engine.commit();
+        assert!(autosave_if_changed(&state, &config, &mut last_hash)
+            .await
+            .unwrap()
+            .is_some());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,194 @@
+// crates/satisflow-server/src/demo.rs
+//! Public demo mode
+//!
+//! A demo server starts from a bundled example, refuses to save or load, puts
+//! its state back to the example on a timer and limits how many requests each
+//! client can send, so it can be exposed as a public playground.
+
+use std::collections::HashMap;
+use std::env;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::anyhow;
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use satisflow_engine::examples::{load_example, EXAMPLE_NAMES};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::{error::AppError, state::AppState};
+
+pub const DEFAULT_EXAMPLE: &str = "five-factory";
+pub const DEFAULT_RESET_MINUTES: u64 = 30;
+pub const DEFAULT_REQUESTS_PER_MINUTE: u32 = 120;
+
+/// Length of a rate limiting window
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Settings of a demo server
+#[derive(Debug, Clone, PartialEq)]
+pub struct DemoConfig {
+    /// Bundled example the state is reset to
+    pub example: String,
+    pub reset_interval: Duration,
+    /// Requests allowed per client and per minute
+    pub requests_per_minute: u32,
+}
+
+impl DemoConfig {
+    /// Read the demo settings from `DEMO_MODE`, `DEMO_EXAMPLE`,
+    /// `DEMO_RESET_MINUTES` and `DEMO_RATE_LIMIT`
+    ///
+    /// # Returns
+    ///
+    /// `None` unless `DEMO_MODE` is `true` or `1`
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        let enabled = env::var("DEMO_MODE")
+            .map(|value| matches!(value.trim(), "true" | "1"))
+            .unwrap_or(false);
+        if !enabled {
+            return Ok(None);
+        }
+
+        let example = env::var("DEMO_EXAMPLE").unwrap_or_else(|_| DEFAULT_EXAMPLE.to_string());
+        if !EXAMPLE_NAMES.contains(&example.as_str()) {
+            return Err(anyhow!(
+                "Invalid DEMO_EXAMPLE '{}' (available: {})",
+                example,
+                EXAMPLE_NAMES.join(", ")
+            ));
+        }
+
+        let reset_minutes: u64 = env::var("DEMO_RESET_MINUTES")
+            .unwrap_or_else(|_| DEFAULT_RESET_MINUTES.to_string())
+            .parse()
+            .ok()
+            .filter(|minutes| *minutes > 0)
+            .ok_or_else(|| anyhow!("Invalid DEMO_RESET_MINUTES format"))?;
+
+        let requests_per_minute: u32 = env::var("DEMO_RATE_LIMIT")
+            .unwrap_or_else(|_| DEFAULT_REQUESTS_PER_MINUTE.to_string())
+            .parse()
+            .ok()
+            .filter(|limit| *limit > 0)
+            .ok_or_else(|| anyhow!("Invalid DEMO_RATE_LIMIT format"))?;
+
+        Ok(Some(Self {
+            example,
+            reset_interval: Duration::from_secs(reset_minutes * 60),
+            requests_per_minute,
+        }))
+    }
+}
+
+/// Replace the engine state with the demo example
+pub async fn reset_demo_state(state: &AppState, config: &DemoConfig) -> anyhow::Result<()> {
+    let mut example = load_example(&config.example)
+        .ok_or_else(|| anyhow!("Example '{}' not found", config.example))?;
+    example.update();
+
+    let mut engine = state.engine.write().await;
+    engine.replace(example);
+
+    engine.commit();
+
+    Ok(())
+}
+
+/// Reset the engine state to the demo example every `reset_interval`
+pub fn spawn_reset_task(state: AppState, config: DemoConfig) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(config.reset_interval);
+        // The first tick completes immediately; the state is already fresh
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            match reset_demo_state(&state, &config).await {
+                Ok(()) => info!("Demo state reset to example '{}'", config.example),
+                Err(e) => warn!("Failed to reset demo state: {}", e),
+            }
+        }
+    })
+}
+
+/// Fixed-window request counter per client IP
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    limit: u32,
+    window: Arc<Mutex<Window>>,
+}
+
+#[derive(Debug)]
+struct Window {
+    started: Instant,
+    counts: HashMap<IpAddr, u32>,
+}
+
+impl RateLimiter {
+    pub fn new(requests_per_minute: u32) -> Self {
+        Self {
+            limit: requests_per_minute,
+            window: Arc::new(Mutex::new(Window {
+                started: Instant::now(),
+                counts: HashMap::new(),
+            })),
+        }
+    }
+
+    /// Count a request from `client`
+    ///
+    /// # Returns
+    ///
+    /// `false` once the client went over its limit for the current minute
+    pub fn check(&self, client: IpAddr) -> bool {
+        let mut window = self.window.lock().unwrap_or_else(|e| e.into_inner());
+        if window.started.elapsed() >= RATE_WINDOW {
+            window.started = Instant::now();
+            window.counts.clear();
+        }
+        let count = window.counts.entry(client).or_insert(0);
+        *count += 1;
+        *count <= self.limit
+    }
+}
+
+/// Middleware answering `429 Too Many Requests` to clients over the limit
+///
+/// Clients are told apart by the address of the connection, so the router
+/// must be served with `into_make_service_with_connect_info::<SocketAddr>()`.
+pub async fn rate_limit(
+    State(limiter): State<RateLimiter>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !limiter.check(addr.ip()) {
+        return AppError::TooManyRequests(
+            "Demo rate limit exceeded, please try again in a minute".to_string(),
+        )
+        .into_response();
+    }
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter_counts_each_client() {
+        let limiter = RateLimiter::new(2);
+        let first = IpAddr::from([10, 0, 0, 1]);
+        let second = IpAddr::from([10, 0, 0, 2]);
+
+        assert!(limiter.check(first));
+        assert!(limiter.check(first));
+        assert!(!limiter.check(first));
+        assert!(limiter.check(second));
+    }
+}
@@ -0,0 +1,504 @@
+// crates/satisflow-server/src/graphql.rs
+//! GraphQL schema over the engine state
+//!
+//! Lets a client fetch nested data, e.g. factories with their production
+//! lines and the recipes they run, in one request instead of one REST call per
+//! factory. Every request reads one snapshot of the engine taken when it
+//! starts, so nested fields never see a half-applied change. The schema is
+//! read-only: changes still go through the REST API.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_graphql::{
+    Context, EmptyMutation, EmptySubscription, Object, Result, Schema, SimpleObject,
+};
+use satisflow_engine::{
+    models::{
+        factory::Factory, item_name, logistics::LogisticsFlux, production_line::ProductionLine,
+        FactoryId, Item, LineRecipe, LogisticsId,
+    },
+    SatisflowEngine,
+};
+use uuid::Uuid;
+
+use crate::{error::AppError, handlers::dashboard::balance_state};
+
+pub type SatisflowSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Deepest nesting a query may use; factories link to logistics lines which
+/// link back to factories, so queries could otherwise nest without end
+pub const MAX_DEPTH: usize = 12;
+
+pub fn build_schema() -> SatisflowSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .limit_depth(MAX_DEPTH)
+        .finish()
+}
+
+/// The engine version a request reads, as published with its calculations
+/// up to date
+pub struct EngineSnapshot {
+    pub engine: Arc<SatisflowEngine>,
+    /// Net rate of every item across all factories
+    pub items: HashMap<Item, f64>,
+}
+
+impl EngineSnapshot {
+    pub fn new(engine: Arc<SatisflowEngine>) -> Self {
+        let items = engine.global_items();
+        Self { engine, items }
+    }
+}
+
+fn snapshot(ctx: &Context<'_>) -> Result<Arc<EngineSnapshot>> {
+    ctx.data::<Arc<EngineSnapshot>>().cloned()
+}
+
+/// An item with a rate in items/min
+#[derive(SimpleObject)]
+pub struct ItemRate {
+    /// Item id, as in the REST API
+    pub item: String,
+    pub item_name: String,
+    pub rate: f64,
+}
+
+impl ItemRate {
+    fn new(item: Item, rate: f64) -> Self {
+        Self {
+            item: format!("{:?}", item),
+            item_name: item_name(item).to_string(),
+            rate,
+        }
+    }
+
+    /// Rates sorted by item name
+    fn list(rates: impl IntoIterator<Item = (Item, f64)>) -> Vec<Self> {
+        let mut rates: Vec<Self> = rates
+            .into_iter()
+            .map(|(item, rate)| Self::new(item, rate))
+            .collect();
+        rates.sort_by(|a, b| a.item_name.cmp(&b.item_name));
+        rates
+    }
+}
+
+/// A recipe, with rates of one machine at 100% clock speed
+#[derive(SimpleObject)]
+pub struct RecipeInfo {
+    pub name: String,
+    pub machine: String,
+    pub custom: bool,
+    pub inputs: Vec<ItemRate>,
+    pub outputs: Vec<ItemRate>,
+}
+
+impl From<&LineRecipe> for RecipeInfo {
+    fn from(recipe: &LineRecipe) -> Self {
+        Self {
+            name: recipe.name().to_string(),
+            machine: format!("{:?}", recipe.machine()),
+            custom: matches!(recipe, LineRecipe::Custom(_)),
+            inputs: ItemRate::list(recipe.inputs()),
+            outputs: ItemRate::list(recipe.outputs()),
+        }
+    }
+}
+
+/// Net rate of an item across all factories
+#[derive(SimpleObject)]
+pub struct ItemBalance {
+    pub item: String,
+    pub item_name: String,
+    pub balance: f64,
+    /// `overflow`, `underflow` or `balanced`
+    pub state: String,
+}
+
+pub struct ProductionLineObject(ProductionLine);
+
+#[Object(name = "ProductionLine")]
+impl ProductionLineObject {
+    async fn id(&self) -> Uuid {
+        self.0.id().into()
+    }
+
+    async fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    async fn description(&self) -> Option<&str> {
+        match &self.0 {
+            ProductionLine::ProductionLineRecipe(line) => line.description.as_deref(),
+            ProductionLine::ProductionLineBlueprint(blueprint) => blueprint.description.as_deref(),
+        }
+    }
+
+    /// `recipe` or `blueprint`
+    async fn kind(&self) -> &str {
+        match &self.0 {
+            ProductionLine::ProductionLineRecipe(_) => "recipe",
+            ProductionLine::ProductionLineBlueprint(_) => "blueprint",
+        }
+    }
+
+    async fn enabled(&self) -> bool {
+        self.0.is_enabled()
+    }
+
+    async fn total_machines(&self) -> u32 {
+        self.0.total_machines()
+    }
+
+    async fn total_somersloop(&self) -> u32 {
+        self.0.total_somersloop()
+    }
+
+    /// Power draw in MW
+    async fn power_consumption(&self) -> f64 {
+        self.0.total_power_consumption()
+    }
+
+    async fn inputs(&self) -> Vec<ItemRate> {
+        ItemRate::list(self.0.input_rate())
+    }
+
+    async fn outputs(&self) -> Vec<ItemRate> {
+        ItemRate::list(self.0.output_rate())
+    }
+
+    /// Recipe of a recipe line; `null` for blueprints
+    async fn recipe(&self) -> Option<RecipeInfo> {
+        match &self.0 {
+            ProductionLine::ProductionLineRecipe(line) => Some(RecipeInfo::from(&line.recipe)),
+            ProductionLine::ProductionLineBlueprint(_) => None,
+        }
+    }
+
+    /// Lines inside a blueprint; empty for recipe lines
+    async fn lines(&self) -> Vec<ProductionLineObject> {
+        match &self.0 {
+            ProductionLine::ProductionLineRecipe(_) => Vec::new(),
+            ProductionLine::ProductionLineBlueprint(blueprint) => blueprint
+                .production_lines
+                .iter()
+                .map(|line| {
+                    ProductionLineObject(ProductionLine::ProductionLineRecipe(line.clone()))
+                })
+                .collect(),
+        }
+    }
+}
+
+pub struct FactoryObject {
+    snapshot: Arc<EngineSnapshot>,
+    id: FactoryId,
+}
+
+impl FactoryObject {
+    fn factory(&self) -> Result<&Factory> {
+        let factory =
+            self.snapshot.engine.get_factory(self.id).ok_or_else(|| {
+                AppError::NotFound(format!("Factory with id {} not found", self.id))
+            })?;
+        Ok(factory)
+    }
+}
+
+#[Object(name = "Factory")]
+impl FactoryObject {
+    async fn id(&self) -> Uuid {
+        self.id.into()
+    }
+
+    async fn name(&self) -> Result<&str> {
+        Ok(&self.factory()?.name)
+    }
+
+    async fn description(&self) -> Result<Option<&str>> {
+        Ok(self.factory()?.description.as_deref())
+    }
+
+    async fn notes(&self) -> Result<Option<&str>> {
+        Ok(self.factory()?.notes.as_deref())
+    }
+
+    async fn tags(&self) -> Result<&[String]> {
+        Ok(&self.factory()?.tags)
+    }
+
+    async fn archived(&self) -> Result<bool> {
+        Ok(self.factory()?.archived)
+    }
+
+    /// Production lines sorted by name
+    async fn production_lines(&self) -> Result<Vec<ProductionLineObject>> {
+        let mut lines: Vec<&ProductionLine> = self.factory()?.production_lines.values().collect();
+        lines.sort_by(|a, b| a.name().cmp(b.name()).then(a.id().cmp(&b.id())));
+        Ok(lines
+            .into_iter()
+            .map(|line| ProductionLineObject(line.clone()))
+            .collect())
+    }
+
+    /// Net rate of every item in the factory, logistics included
+    async fn items(&self) -> Result<Vec<ItemRate>> {
+        Ok(ItemRate::list(
+            self.factory()?
+                .items
+                .iter()
+                .map(|(item, rate)| (*item, *rate)),
+        ))
+    }
+
+    async fn total_power_consumption(&self) -> Result<f64> {
+        Ok(self.factory()?.total_power_consumption())
+    }
+
+    async fn total_power_generation(&self) -> Result<f64> {
+        Ok(self.factory()?.total_power_generation())
+    }
+
+    async fn power_balance(&self) -> Result<f64> {
+        Ok(self.factory()?.power_balance())
+    }
+
+    /// Logistics lines delivering to this factory
+    async fn incoming_logistics(&self) -> Vec<LogisticsObject> {
+        logistics_objects(
+            &self.snapshot,
+            self.snapshot.engine.incoming_logistics(self.id),
+        )
+    }
+
+    /// Logistics lines leaving this factory
+    async fn outgoing_logistics(&self) -> Vec<LogisticsObject> {
+        logistics_objects(
+            &self.snapshot,
+            self.snapshot.engine.outgoing_logistics(self.id),
+        )
+    }
+}
+
+pub struct LogisticsObject {
+    snapshot: Arc<EngineSnapshot>,
+    id: LogisticsId,
+}
+
+impl LogisticsObject {
+    fn line(&self) -> Result<&LogisticsFlux> {
+        let line = self
+            .snapshot
+            .engine
+            .get_logistics_line(self.id)
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Logistics line with id {} not found", self.id))
+            })?;
+        Ok(line)
+    }
+
+    fn factory(&self, id: FactoryId) -> FactoryObject {
+        FactoryObject {
+            snapshot: self.snapshot.clone(),
+            id,
+        }
+    }
+}
+
+#[Object(name = "LogisticsLine")]
+impl LogisticsObject {
+    async fn id(&self) -> Uuid {
+        self.id.into()
+    }
+
+    #[graphql(name = "fromFactory")]
+    async fn source_factory(&self) -> Result<FactoryObject> {
+        Ok(self.factory(self.line()?.from_factory))
+    }
+
+    async fn to_factory(&self) -> Result<FactoryObject> {
+        Ok(self.factory(self.line()?.to_factory))
+    }
+
+    /// `Bus`, `Train`, `Truck` or `Drone`
+    async fn transport_type(&self) -> Result<String> {
+        Ok(format!("{:?}", self.line()?.transport_type.kind()))
+    }
+
+    /// Labels or note saved with the line, as text
+    async fn transport_details(&self) -> Result<String> {
+        Ok(self.line()?.transport_details.to_string())
+    }
+
+    async fn items(&self) -> Result<Vec<ItemRate>> {
+        Ok(self
+            .line()?
+            .get_items()
+            .into_iter()
+            .map(|flow| ItemRate::new(flow.item, flow.quantity_per_min))
+            .collect())
+    }
+
+    async fn total_quantity_per_min(&self) -> Result<f64> {
+        Ok(self.line()?.total_quantity_per_min())
+    }
+}
+
+/// Totals shown on the dashboard
+pub struct Dashboard {
+    snapshot: Arc<EngineSnapshot>,
+}
+
+#[Object]
+impl Dashboard {
+    async fn total_factories(&self) -> usize {
+        self.snapshot.engine.get_all_factories().len()
+    }
+
+    async fn total_production_lines(&self) -> usize {
+        self.snapshot
+            .engine
+            .get_all_factories()
+            .values()
+            .map(|factory| factory.production_lines.len())
+            .sum()
+    }
+
+    async fn total_logistics_lines(&self) -> usize {
+        self.snapshot.engine.get_all_logistics().len()
+    }
+
+    async fn total_power_consumption(&self) -> f64 {
+        self.snapshot
+            .engine
+            .get_all_factories()
+            .values()
+            .map(Factory::total_power_consumption)
+            .sum()
+    }
+
+    async fn total_power_generation(&self) -> f64 {
+        self.snapshot
+            .engine
+            .get_all_factories()
+            .values()
+            .map(Factory::total_power_generation)
+            .sum()
+    }
+
+    async fn net_power(&self) -> f64 {
+        self.snapshot
+            .engine
+            .get_all_factories()
+            .values()
+            .map(|factory| factory.total_power_generation() - factory.total_power_consumption())
+            .sum()
+    }
+
+    /// Net rate of every item across all factories, sorted by item name
+    async fn item_balances(&self) -> Vec<ItemBalance> {
+        ItemRate::list(
+            self.snapshot
+                .items
+                .iter()
+                .map(|(item, rate)| (*item, *rate)),
+        )
+        .into_iter()
+        .map(|rate| ItemBalance {
+            state: balance_state(rate.rate),
+            item: rate.item,
+            item_name: rate.item_name,
+            balance: rate.rate,
+        })
+        .collect()
+    }
+}
+
+/// Logistics lines matching `matches`, sorted by transport details
+fn logistics_objects<'a>(
+    snapshot: &Arc<EngineSnapshot>,
+    lines: impl Iterator<Item = &'a LogisticsFlux>,
+) -> Vec<LogisticsObject> {
+    let mut lines: Vec<&LogisticsFlux> = lines.collect();
+    lines.sort_by_cached_key(|line| (line.transport_details.to_string(), line.id));
+    lines
+        .into_iter()
+        .map(|line| LogisticsObject {
+            snapshot: snapshot.clone(),
+            id: line.id,
+        })
+        .collect()
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Factories sorted by name
+    ///
+    /// * `name_contains` - Case-insensitive part of the name
+    /// * `tag` - Only factories with this tag
+    async fn factories(
+        &self,
+        ctx: &Context<'_>,
+        name_contains: Option<String>,
+        tag: Option<String>,
+    ) -> Result<Vec<FactoryObject>> {
+        let snapshot = snapshot(ctx)?;
+        let needle = name_contains.map(|needle| needle.to_lowercase());
+        let mut factories: Vec<&Factory> = snapshot
+            .engine
+            .get_all_factories()
+            .values()
+            .filter(|factory| {
+                needle
+                    .as_ref()
+                    .is_none_or(|needle| factory.name.to_lowercase().contains(needle))
+                    && tag.as_ref().is_none_or(|tag| factory.has_tag(tag))
+            })
+            .collect();
+        factories.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+
+        Ok(factories
+            .into_iter()
+            .map(|factory| FactoryObject {
+                snapshot: snapshot.clone(),
+                id: factory.id,
+            })
+            .collect())
+    }
+
+    async fn factory(&self, ctx: &Context<'_>, id: Uuid) -> Result<Option<FactoryObject>> {
+        let snapshot = snapshot(ctx)?;
+        let id = FactoryId::from(id);
+        if snapshot.engine.get_factory(id).is_none() {
+            return Ok(None);
+        }
+        Ok(Some(FactoryObject { snapshot, id }))
+    }
+
+    /// Logistics lines sorted by transport details
+    async fn logistics(&self, ctx: &Context<'_>) -> Result<Vec<LogisticsObject>> {
+        let snapshot = snapshot(ctx)?;
+        Ok(logistics_objects(
+            &snapshot,
+            snapshot.engine.get_all_logistics().values(),
+        ))
+    }
+
+    async fn logistics_line(&self, ctx: &Context<'_>, id: Uuid) -> Result<Option<LogisticsObject>> {
+        let snapshot = snapshot(ctx)?;
+        let id = LogisticsId::from(id);
+        if snapshot.engine.get_logistics_line(id).is_none() {
+            return Ok(None);
+        }
+        Ok(Some(LogisticsObject { snapshot, id }))
+    }
+
+    async fn dashboard(&self, ctx: &Context<'_>) -> Result<Dashboard> {
+        Ok(Dashboard {
+            snapshot: snapshot(ctx)?,
+        })
+    }
+}
@@ -0,0 +1,513 @@
+// crates/satisflow-server/src/grpc.rs
+//! gRPC service mirroring the factory, logistics and save endpoints
+//!
+//! Built with the `grpc` feature from `proto/satisflow.proto`, for desktop
+//! companions and other tools that would rather not speak JSON over HTTP.
+//! Every call goes through the handler of its REST endpoint, so both APIs
+//! validate and answer alike, and writes take the same write gate so they
+//! can't slip between an `If-Match` check and the REST write it guards.
+//! Writes record a dashboard metrics point and send power deficit webhooks
+//! like REST writes, and are recorded in the audit log under the name of
+//! their call.
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use satisflow_engine::{
+    models::{FactoryId, Item, LogisticsId},
+    SaveFileSummary,
+};
+use serde_json::Value;
+use std::str::FromStr;
+use tonic::{Request, Response, Status};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    audit_log::{Actor, AuditEntry},
+    auth::{AuthConfig, Role, API_KEY_HEADER},
+    error::{AppError, Result},
+    handlers::{
+        factory::{
+            self, CreateFactoryRequest, FactoryListQuery, FactoryResponse, UpdateFactoryRequest,
+        },
+        logistics::{
+            self, BusConveyorRequest, BusLiftRequest, BusPipelineRequest, CreateLogisticsRequest,
+            CreateLogisticsTransport, LogisticsListQuery, LogisticsResponse, TrainWagonRequest,
+        },
+        pagination::Listing,
+        save_load::{self, LoadQuery, LoadRequest, SaveQuery},
+    },
+    state::AppState,
+};
+
+/// Types and client generated from the protobuf definitions
+pub mod proto {
+    tonic::include_proto!("satisflow.v1");
+}
+
+use proto::{
+    create_logistics_line_request::Transport,
+    satisflow_server::{Satisflow, SatisflowServer},
+};
+
+type RpcResult<T> = std::result::Result<Response<T>, Status>;
+
+/// Port of the gRPC server when `GRPC_PORT` isn't set
+pub const DEFAULT_GRPC_PORT: u16 = 50051;
+
+impl From<AppError> for Status {
+    fn from(err: AppError) -> Self {
+        match err {
+            AppError::NotFound(msg) => Status::not_found(msg),
+            AppError::BadRequest(msg) | AppError::ValidationError(msg) => {
+                Status::invalid_argument(msg)
+            }
+            AppError::Unauthorized(msg) => Status::unauthenticated(msg),
+            AppError::Forbidden(msg) => Status::permission_denied(msg),
+            AppError::TooManyRequests(msg) => Status::resource_exhausted(msg),
+            AppError::Conflict(msg) => Status::aborted(msg),
+            AppError::EngineError(msg) => Status::internal(msg),
+            AppError::InternalError(_) | AppError::SerializationError(_) => {
+                Status::internal("An internal error occurred")
+            }
+        }
+    }
+}
+
+fn parse_id<T: FromStr>(id: &str) -> Result<T> {
+    id.parse()
+        .map_err(|_| AppError::BadRequest(format!("Invalid id: {}", id)))
+}
+
+fn parse_item(item: &str) -> Result<Item> {
+    serde_json::from_value(Value::String(item.to_string()))
+        .map_err(|_| AppError::BadRequest(format!("Unknown item: {}", item)))
+}
+
+fn item_rate(item: Item, rate: f64) -> proto::ItemRate {
+    proto::ItemRate {
+        item: format!("{:?}", item),
+        rate,
+    }
+}
+
+fn entries<T>(listing: Listing<T>) -> Vec<T> {
+    match listing {
+        Listing::All(entries) => entries,
+        Listing::Paged(page) => page.items,
+    }
+}
+
+impl From<FactoryResponse> for proto::Factory {
+    fn from(factory: FactoryResponse) -> Self {
+        Self {
+            id: factory.id.to_string(),
+            name: factory.name,
+            description: factory.description,
+            notes: factory.notes,
+            tags: factory.tags,
+            archived: factory.archived,
+            production_lines: factory
+                .production_lines
+                .into_iter()
+                .map(|line| proto::ProductionLine {
+                    id: line.production_line.id().to_string(),
+                    name: line.production_line.name().to_string(),
+                    enabled: line.production_line.is_enabled(),
+                    total_machines: line.total_machines,
+                    total_somersloop: line.total_somersloop,
+                    power_consumption: line.total_power_consumption,
+                    inputs: line
+                        .input_rate
+                        .into_iter()
+                        .map(|rate| item_rate(rate.item, rate.quantity))
+                        .collect(),
+                    outputs: line
+                        .output_rate
+                        .into_iter()
+                        .map(|rate| item_rate(rate.item, rate.quantity))
+                        .collect(),
+                })
+                .collect(),
+            items: factory
+                .items
+                .into_iter()
+                .map(|balance| item_rate(balance.item, balance.quantity))
+                .collect(),
+            total_power_consumption: factory.total_power_consumption,
+            total_power_generation: factory.total_power_generation,
+            power_balance: factory.power_balance,
+        }
+    }
+}
+
+impl From<LogisticsResponse> for proto::LogisticsLine {
+    fn from(line: LogisticsResponse) -> Self {
+        Self {
+            id: line.id.to_string(),
+            from_factory: line.from_factory.to_string(),
+            to_factory: line.to_factory.to_string(),
+            transport_type: line.transport_type,
+            transport_id: line.transport_id,
+            transport_name: line.transport_name,
+            transport_details: line.transport_details.to_string(),
+            items: line
+                .items
+                .into_iter()
+                .map(|flow| item_rate(flow.item, flow.quantity_per_min))
+                .collect(),
+            total_quantity_per_min: line.total_quantity_per_min,
+        }
+    }
+}
+
+impl From<SaveFileSummary> for proto::SaveSummary {
+    fn from(summary: SaveFileSummary) -> Self {
+        Self {
+            version: summary.version,
+            factory_count: summary.factory_count as u64,
+            logistics_count: summary.logistics_count as u64,
+            blueprint_template_count: summary.blueprint_template_count as u64,
+        }
+    }
+}
+
+fn logistics_transport(transport: Transport) -> Result<CreateLogisticsTransport> {
+    Ok(match transport {
+        Transport::Truck(truck) => CreateLogisticsTransport::Truck {
+            item: parse_item(&truck.item)?,
+            quantity_per_min: truck.quantity_per_min,
+            truck_id: truck.vehicle_id,
+        },
+        Transport::Drone(drone) => CreateLogisticsTransport::Drone {
+            item: parse_item(&drone.item)?,
+            quantity_per_min: drone.quantity_per_min,
+            drone_id: drone.vehicle_id,
+        },
+        Transport::Bus(bus) => CreateLogisticsTransport::Bus {
+            bus_name: bus.bus_name,
+            conveyors: bus
+                .conveyors
+                .into_iter()
+                .map(|conveyor| {
+                    Ok(BusConveyorRequest {
+                        line_id: conveyor.line_id,
+                        conveyor_type: conveyor.conveyor_type,
+                        item: parse_item(&conveyor.item)?,
+                        quantity_per_min: conveyor.quantity_per_min,
+                    })
+                })
+                .collect::<Result<_>>()?,
+            lifts: bus
+                .lifts
+                .into_iter()
+                .map(|lift| {
+                    Ok(BusLiftRequest {
+                        lift_id: lift.lift_id,
+                        lift_type: lift.lift_type,
+                        item: parse_item(&lift.item)?,
+                        quantity_per_min: lift.quantity_per_min,
+                    })
+                })
+                .collect::<Result<_>>()?,
+            pipelines: bus
+                .pipelines
+                .into_iter()
+                .map(|pipeline| {
+                    Ok(BusPipelineRequest {
+                        pipeline_id: pipeline.pipeline_id,
+                        pipeline_type: pipeline.pipeline_type,
+                        item: parse_item(&pipeline.item)?,
+                        quantity_per_min: pipeline.quantity_per_min,
+                        pumps_mk1: pipeline.pumps_mk1,
+                        pumps_mk2: pipeline.pumps_mk2,
+                        vertical_rise: pipeline.vertical_rise,
+                    })
+                })
+                .collect::<Result<_>>()?,
+        },
+        Transport::Train(train) => CreateLogisticsTransport::Train {
+            train_name: train.train_name,
+            wagons: train
+                .wagons
+                .into_iter()
+                .map(|wagon| {
+                    Ok(TrainWagonRequest {
+                        wagon_id: wagon.wagon_id,
+                        wagon_type: wagon.wagon_type,
+                        item: parse_item(&wagon.item)?,
+                        quantity_per_min: wagon.quantity_per_min,
+                    })
+                })
+                .collect::<Result<_>>()?,
+        },
+    })
+}
+
+/// The gRPC service, working on the same state as the REST API
+pub struct SatisflowService {
+    state: AppState,
+    auth: Option<AuthConfig>,
+}
+
+impl SatisflowService {
+    /// # Arguments
+    ///
+    /// * `state` - State shared with the REST API
+    /// * `auth` - API keys calls must carry, `None` to leave the service open
+    pub fn new(state: AppState, auth: Option<AuthConfig>) -> Self {
+        Self { state, auth }
+    }
+
+    pub fn into_server(self) -> SatisflowServer<Self> {
+        SatisflowServer::new(self)
+    }
+
+    /// Check the API key of a call, sent as `authorization: Bearer <key>` or
+    /// `x-api-key: <key>` metadata like the REST headers
+    ///
+    /// # Returns
+    ///
+    /// The actor of the key, `None` when the service is open
+    fn authorize<T>(&self, request: &Request<T>, write: bool) -> Result<Option<Actor>> {
+        let Some(config) = &self.auth else {
+            return Ok(None);
+        };
+        let metadata = request.metadata();
+        let key = metadata
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .or_else(|| {
+                metadata
+                    .get(API_KEY_HEADER)
+                    .and_then(|value| value.to_str().ok())
+            })
+            .map(str::trim)
+            .ok_or_else(|| AppError::Unauthorized("An API key is required".to_string()))?;
+        match config.role(key) {
+            None => Err(AppError::Unauthorized("Invalid API key".to_string())),
+            Some(Role::ReadOnly) if write => {
+                Err(AppError::Forbidden("This API key is read-only".to_string()))
+            }
+            Some(_) => Ok(Some(config.actor(key))),
+        }
+    }
+
+    /// Follow up a write call made since `revision` like a REST write, then
+    /// record it in the audit log
+    async fn finish_write(
+        &self,
+        revision: u64,
+        call: &str,
+        entity_id: Option<Uuid>,
+        actor: Option<Actor>,
+    ) {
+        let engine = self.state.record_write(revision).await;
+        let entry = AuditEntry::new(engine.revision(), "gRPC", call, entity_id, actor.as_ref());
+        if let Err(e) = self.state.audit_log.record(entry).await {
+            warn!("Failed to write the audit log: {}", e);
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl Satisflow for SatisflowService {
+    async fn list_factories(
+        &self,
+        request: Request<proto::ListFactoriesRequest>,
+    ) -> RpcResult<proto::ListFactoriesResponse> {
+        self.authorize(&request, false)?;
+        let request = request.into_inner();
+        let query = FactoryListQuery {
+            tag: request.tag,
+            name_contains: request.name_contains,
+            ..Default::default()
+        };
+        let Json(listing) = factory::get_factories(State(self.state.clone()), Query(query)).await?;
+        Ok(Response::new(proto::ListFactoriesResponse {
+            factories: entries(listing).into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn get_factory(
+        &self,
+        request: Request<proto::GetFactoryRequest>,
+    ) -> RpcResult<proto::Factory> {
+        self.authorize(&request, false)?;
+        let id = parse_id(&request.get_ref().id)?;
+        let Json(factory) = factory::get_factory(State(self.state.clone()), Path(id)).await?;
+        Ok(Response::new(factory.into()))
+    }
+
+    async fn create_factory(
+        &self,
+        request: Request<proto::CreateFactoryRequest>,
+    ) -> RpcResult<proto::Factory> {
+        let actor = self.authorize(&request, true)?;
+        let request = request.into_inner();
+        let _gate = self.state.write_gate.lock().await;
+        let revision = self.state.engine.snapshot().revision();
+        let (_, Json(factory)) = factory::create_factory(
+            State(self.state.clone()),
+            Json(CreateFactoryRequest {
+                name: request.name,
+                description: request.description,
+                notes: request.notes,
+                tags: request.tags,
+                archived: request.archived,
+                position: None,
+            }),
+        )
+        .await?;
+        self.finish_write(revision, "CreateFactory", Some(factory.id.as_uuid()), actor)
+            .await;
+        Ok(Response::new(factory.into()))
+    }
+
+    async fn update_factory(
+        &self,
+        request: Request<proto::UpdateFactoryRequest>,
+    ) -> RpcResult<proto::Factory> {
+        let actor = self.authorize(&request, true)?;
+        let request = request.into_inner();
+        let id: FactoryId = parse_id(&request.id)?;
+        let _gate = self.state.write_gate.lock().await;
+        let revision = self.state.engine.snapshot().revision();
+        let Json(factory) = factory::update_factory(
+            State(self.state.clone()),
+            Path(id),
+            Json(UpdateFactoryRequest {
+                name: request.name,
+                description: request.description,
+                notes: request.notes,
+                tags: request.tags.map(|tags| tags.tags),
+                archived: request.archived,
+                position: None,
+                clear_position: false,
+            }),
+        )
+        .await?;
+        self.finish_write(revision, "UpdateFactory", Some(id.as_uuid()), actor)
+            .await;
+        Ok(Response::new(factory.into()))
+    }
+
+    async fn delete_factory(
+        &self,
+        request: Request<proto::DeleteFactoryRequest>,
+    ) -> RpcResult<proto::DeleteResponse> {
+        let actor = self.authorize(&request, true)?;
+        let id: FactoryId = parse_id(&request.get_ref().id)?;
+        let _gate = self.state.write_gate.lock().await;
+        let revision = self.state.engine.snapshot().revision();
+        factory::delete_factory(State(self.state.clone()), Path(id)).await?;
+        self.finish_write(revision, "DeleteFactory", Some(id.as_uuid()), actor)
+            .await;
+        Ok(Response::new(proto::DeleteResponse {}))
+    }
+
+    async fn list_logistics(
+        &self,
+        request: Request<proto::ListLogisticsRequest>,
+    ) -> RpcResult<proto::ListLogisticsResponse> {
+        self.authorize(&request, false)?;
+        let request = request.into_inner();
+        let query = LogisticsListQuery {
+            transport_type: request.transport_type,
+            item: request.item.as_deref().map(parse_item).transpose()?,
+            ..Default::default()
+        };
+        let Json(listing) =
+            logistics::get_logistics(State(self.state.clone()), Query(query)).await?;
+        Ok(Response::new(proto::ListLogisticsResponse {
+            lines: entries(listing).into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn get_logistics_line(
+        &self,
+        request: Request<proto::GetLogisticsLineRequest>,
+    ) -> RpcResult<proto::LogisticsLine> {
+        self.authorize(&request, false)?;
+        let id = parse_id(&request.get_ref().id)?;
+        let Json(line) = logistics::get_logistics_line(State(self.state.clone()), Path(id)).await?;
+        Ok(Response::new(line.into()))
+    }
+
+    async fn create_logistics_line(
+        &self,
+        request: Request<proto::CreateLogisticsLineRequest>,
+    ) -> RpcResult<proto::LogisticsLine> {
+        let actor = self.authorize(&request, true)?;
+        let request = request.into_inner();
+        let transport = request
+            .transport
+            .ok_or_else(|| AppError::BadRequest("A transport is required".to_string()))?;
+        let request = CreateLogisticsRequest {
+            from_factory: parse_id(&request.from_factory)?,
+            to_factory: parse_id(&request.to_factory)?,
+            transport: logistics_transport(transport)?,
+        };
+        let _gate = self.state.write_gate.lock().await;
+        let revision = self.state.engine.snapshot().revision();
+        let (_, Json(line)) =
+            logistics::create_logistics(State(self.state.clone()), Json(request)).await?;
+        self.finish_write(
+            revision,
+            "CreateLogisticsLine",
+            Some(line.id.as_uuid()),
+            actor,
+        )
+        .await;
+        Ok(Response::new(line.into()))
+    }
+
+    async fn delete_logistics_line(
+        &self,
+        request: Request<proto::DeleteLogisticsLineRequest>,
+    ) -> RpcResult<proto::DeleteResponse> {
+        let actor = self.authorize(&request, true)?;
+        let id: LogisticsId = parse_id(&request.get_ref().id)?;
+        let _gate = self.state.write_gate.lock().await;
+        let revision = self.state.engine.snapshot().revision();
+        logistics::delete_logistics(State(self.state.clone()), Path(id)).await?;
+        self.finish_write(revision, "DeleteLogisticsLine", Some(id.as_uuid()), actor)
+            .await;
+        Ok(Response::new(proto::DeleteResponse {}))
+    }
+
+    async fn save(&self, request: Request<proto::SaveRequest>) -> RpcResult<proto::SaveResponse> {
+        self.authorize(&request, false)?;
+        let query = SaveQuery {
+            target_version: request.into_inner().target_version,
+            compressed: false,
+        };
+        let Json(save) = save_load::save_engine(State(self.state.clone()), Query(query)).await?;
+        Ok(Response::new(proto::SaveResponse {
+            save_data: save.save_data,
+            summary: Some(save.summary.into()),
+        }))
+    }
+
+    async fn load(&self, request: Request<proto::LoadRequest>) -> RpcResult<proto::LoadResponse> {
+        let actor = self.authorize(&request, true)?;
+        let request = LoadRequest {
+            save_data: request.into_inner().save_data,
+        };
+        let _gate = self.state.write_gate.lock().await;
+        let revision = self.state.engine.snapshot().revision();
+        let Json(loaded) = save_load::load_engine(
+            State(self.state.clone()),
+            Query(LoadQuery::default()),
+            Json(request),
+        )
+        .await?;
+        self.finish_write(revision, "Load", None, actor).await;
+        Ok(Response::new(proto::LoadResponse {
+            summary: Some(loaded.summary.into()),
+        }))
+    }
+}
@@ -0,0 +1,30 @@
+// crates/satisflow-server/src/handlers/analysis.rs
+use axum::{extract::State, routing::get, Json, Router};
+use satisflow_engine::models::{Diagnostic, FlowNetwork};
+
+use crate::{error::Result, state::AppState};
+
+/// GET /api/analysis/diagnostics
+///
+/// Lists every problem found in the save, most severe first
+pub async fn get_diagnostics(State(state): State<AppState>) -> Result<Json<Vec<Diagnostic>>> {
+    let engine = state.engine.snapshot();
+
+    Ok(Json(engine.validate_all()))
+}
+
+/// GET /api/analysis/flows
+///
+/// The logistics network as factory nodes and item links weighted by rate,
+/// ready for a Sankey diagram
+pub async fn get_flows(State(state): State<AppState>) -> Result<Json<FlowNetwork>> {
+    let engine = state.engine.snapshot();
+
+    Ok(Json(engine.flow_network()))
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/diagnostics", get(get_diagnostics))
+        .route("/flows", get(get_flows))
+}
@@ -0,0 +1,32 @@
+// crates/satisflow-server/src/handlers/audit.rs
+use axum::{
+    extract::{Query, State},
+    response::Json,
+    routing::get,
+    Router,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::{audit_log::AuditEntry, error::Result, state::AppState};
+
+/// Query parameters for the audit log
+#[derive(Debug, Default, Deserialize)]
+pub struct AuditQuery {
+    /// Only list the changes made after this time
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// GET /api/audit
+///
+/// Changes made to the engine, oldest first
+pub async fn get_audit_log(
+    State(state): State<AppState>,
+    Query(query): Query<AuditQuery>,
+) -> Result<Json<Vec<AuditEntry>>> {
+    Ok(Json(state.audit_log.entries(query.since).await))
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/", get(get_audit_log))
+}
@@ -9,12 +9,11 @@ use axum::{
 };
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
 
 use crate::{error::AppError, state::AppState};
 use satisflow_engine::models::{
     production_line::{ProductionLine, ProductionLineBlueprint},
-    Item, ProductionLineId,
+    FactoryId, Item, ProductionLineId,
 };
 
 /// Metadata about a blueprint export
@@ -23,9 +22,9 @@ pub struct BlueprintMetadata {
     pub name: String,
     pub description: Option<String>,
     pub total_machines: u32,
-    pub total_power: f32,
-    pub input_items: Vec<(Item, f32)>,
-    pub output_items: Vec<(Item, f32)>,
+    pub total_power: f64,
+    pub input_items: Vec<(Item, f64)>,
+    pub output_items: Vec<(Item, f64)>,
     pub exported_at: String,
 }
 
@@ -52,7 +51,7 @@ pub struct BlueprintImportRequest {
 pub struct BlueprintImportResponse {
     pub message: String,
     pub blueprint_id: ProductionLineId,
-    pub factory_id: Uuid,
+    pub factory_id: FactoryId,
 }
 
 /// GET /api/factories/:factory_id/production-lines/:line_id/export
@@ -67,9 +66,9 @@ pub struct BlueprintImportResponse {
 /// - `500 Internal Server Error` if serialization fails
 pub async fn export_blueprint(
     State(state): State<AppState>,
-    Path((factory_id, line_id)): Path<(Uuid, Uuid)>,
+    Path((factory_id, line_id)): Path<(FactoryId, ProductionLineId)>,
 ) -> Result<Json<BlueprintExportResponse>, AppError> {
-    let engine = state.engine.read().await;
+    let engine = state.engine.snapshot();
 
     // Find the factory
     let factory = engine
@@ -134,7 +133,7 @@ pub async fn export_blueprint(
 /// - `500 Internal Server Error` if import fails
 pub async fn import_blueprint(
     State(state): State<AppState>,
-    Path(factory_id): Path<Uuid>,
+    Path(factory_id): Path<FactoryId>,
     Json(request): Json<BlueprintImportRequest>,
 ) -> Result<Json<BlueprintImportResponse>, AppError> {
     // Deserialize the blueprint JSON
@@ -145,9 +144,9 @@ pub async fn import_blueprint(
     validate_blueprint(&blueprint)?;
 
     // Generate new UUIDs to avoid conflicts
-    blueprint.id = Uuid::new_v4();
+    blueprint.id = ProductionLineId::new_v4();
     for line in &mut blueprint.production_lines {
-        line.id = Uuid::new_v4();
+        line.id = ProductionLineId::new_v4();
     }
 
     // Override name if provided
@@ -162,10 +161,8 @@ pub async fn import_blueprint(
         .ok_or_else(|| AppError::NotFound(format!("Factory {} not found", factory_id)))?;
 
     let blueprint_id = blueprint.id;
-    factory.production_lines.insert(
-        blueprint_id,
-        ProductionLine::ProductionLineBlueprint(blueprint),
-    );
+    factory.add_production_line(ProductionLine::ProductionLineBlueprint(blueprint));
+    engine.commit();
 
     Ok(Json(BlueprintImportResponse {
         message: format!(
@@ -269,35 +266,40 @@ pub fn routes() -> Router<AppState> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::state::AppState;
-    use satisflow_engine::{
-        models::{
-            production_line::{
-                MachineGroup, ProductionLine, ProductionLineBlueprint, ProductionLineRecipe,
-            },
-            Recipe,
+    use crate::{shared_engine::SharedEngine, state::AppState};
+    use satisflow_engine::models::{
+        production_line::{
+            MachineGroup, ProductionLine, ProductionLineBlueprint, ProductionLineRecipe,
         },
-        SatisflowEngine,
+        Recipe,
     };
-    use std::sync::Arc;
-    use tokio::sync::RwLock;
 
     fn create_test_state() -> AppState {
         AppState {
-            engine: Arc::new(RwLock::new(SatisflowEngine::new())),
+            engine: SharedEngine::default(),
+            demo_mode: false,
+            autosave: None,
+            snapshots: Default::default(),
+            write_gate: Default::default(),
+            workspaces: Default::default(),
+            dashboard_summary: Default::default(),
+            metrics_history: Default::default(),
+            scenarios: Default::default(),
+            audit_log: Default::default(),
+            webhooks: Default::default(),
         }
     }
 
     fn create_test_blueprint() -> ProductionLineBlueprint {
         let mut blueprint = ProductionLineBlueprint::new(
-            Uuid::new_v4(),
+            ProductionLineId::new_v4(),
             "Test Blueprint".to_string(),
             Some("A test blueprint for reinforced plates".to_string()),
         );
 
         // Add a production line for iron plates
         let mut iron_plate_line = ProductionLineRecipe::new(
-            Uuid::new_v4(),
+            ProductionLineId::new_v4(),
             "Iron Plate Line".to_string(),
             None,
             Recipe::IronPlate,
@@ -309,7 +311,7 @@ mod tests {
 
         // Add a production line for screws
         let mut screw_line = ProductionLineRecipe::new(
-            Uuid::new_v4(),
+            ProductionLineId::new_v4(),
             "Screw Line".to_string(),
             None,
             Recipe::Screw,
@@ -333,10 +335,8 @@ mod tests {
             let mut engine = state.engine.write().await;
             let factory_id = engine.create_factory("Test Factory".to_string(), None);
             let factory = engine.get_factory_mut(factory_id).unwrap();
-            factory.production_lines.insert(
-                blueprint_id,
-                ProductionLine::ProductionLineBlueprint(blueprint),
-            );
+            factory.add_production_line(ProductionLine::ProductionLineBlueprint(blueprint));
+            engine.commit();
             factory_id
         };
 
@@ -359,8 +359,8 @@ mod tests {
     #[tokio::test]
     async fn test_export_blueprint_not_found_factory() {
         let state = create_test_state();
-        let fake_factory_id = Uuid::new_v4();
-        let fake_line_id = Uuid::new_v4();
+        let fake_factory_id = FactoryId::new_v4();
+        let fake_line_id = ProductionLineId::new_v4();
 
         let result = export_blueprint(State(state), Path((fake_factory_id, fake_line_id))).await;
 
@@ -378,10 +378,12 @@ mod tests {
         // Create factory without blueprint
         let factory_id = {
             let mut engine = state.engine.write().await;
-            engine.create_factory("Test Factory".to_string(), None)
+            let factory_id = engine.create_factory("Test Factory".to_string(), None);
+            engine.commit();
+            factory_id
         };
 
-        let fake_line_id = Uuid::new_v4();
+        let fake_line_id = ProductionLineId::new_v4();
 
         let result = export_blueprint(State(state), Path((factory_id, fake_line_id))).await;
 
@@ -403,7 +405,7 @@ mod tests {
             let factory = engine.get_factory_mut(factory_id).unwrap();
 
             let recipe_line = ProductionLineRecipe::new(
-                Uuid::new_v4(),
+                ProductionLineId::new_v4(),
                 "Regular Recipe".to_string(),
                 None,
                 Recipe::IronPlate,
@@ -412,6 +414,7 @@ mod tests {
             factory
                 .production_lines
                 .insert(line_id, ProductionLine::ProductionLineRecipe(recipe_line));
+            engine.commit();
             (factory_id, line_id)
         };
 
@@ -431,7 +434,9 @@ mod tests {
         // Create factory
         let factory_id = {
             let mut engine = state.engine.write().await;
-            engine.create_factory("Test Factory".to_string(), None)
+            let factory_id = engine.create_factory("Test Factory".to_string(), None);
+            engine.commit();
+            factory_id
         };
 
         // Serialize blueprint
@@ -452,7 +457,7 @@ mod tests {
 
         // Verify blueprint was added to factory
         {
-            let engine = state.engine.read().await;
+            let engine = state.engine.snapshot();
             let factory = engine.get_factory(factory_id).unwrap();
             assert_eq!(factory.production_lines.len(), 1);
 
@@ -473,7 +478,9 @@ mod tests {
 
         let factory_id = {
             let mut engine = state.engine.write().await;
-            engine.create_factory("Test Factory".to_string(), None)
+            let factory_id = engine.create_factory("Test Factory".to_string(), None);
+            engine.commit();
+            factory_id
         };
 
         let blueprint = create_test_blueprint();
@@ -490,7 +497,7 @@ mod tests {
 
         // Verify name was overridden
         {
-            let engine = state.engine.read().await;
+            let engine = state.engine.snapshot();
             let factory = engine.get_factory(factory_id).unwrap();
 
             let (_, production_line) = factory.production_lines.iter().next().unwrap();
@@ -509,7 +516,9 @@ mod tests {
 
         let factory_id = {
             let mut engine = state.engine.write().await;
-            engine.create_factory("Test Factory".to_string(), None)
+            let factory_id = engine.create_factory("Test Factory".to_string(), None);
+            engine.commit();
+            factory_id
         };
 
         let blueprint = create_test_blueprint();
@@ -529,7 +538,7 @@ mod tests {
 
         // Verify new UUIDs were generated
         {
-            let engine = state.engine.read().await;
+            let engine = state.engine.snapshot();
             let factory = engine.get_factory(factory_id).unwrap();
 
             let (_, production_line) = factory.production_lines.iter().next().unwrap();
@@ -554,7 +563,9 @@ mod tests {
 
         let factory_id = {
             let mut engine = state.engine.write().await;
-            engine.create_factory("Test Factory".to_string(), None)
+            let factory_id = engine.create_factory("Test Factory".to_string(), None);
+            engine.commit();
+            factory_id
         };
 
         let request = BlueprintImportRequest {
@@ -574,7 +585,7 @@ mod tests {
     #[tokio::test]
     async fn test_import_blueprint_factory_not_found() {
         let state = create_test_state();
-        let fake_factory_id = Uuid::new_v4();
+        let fake_factory_id = FactoryId::new_v4();
 
         let blueprint = create_test_blueprint();
         let blueprint_json = serde_json::to_string(&blueprint).unwrap();
@@ -595,10 +606,15 @@ mod tests {
 
     #[tokio::test]
     async fn test_validate_blueprint_invalid_overclock() {
-        let mut blueprint = ProductionLineBlueprint::new(Uuid::new_v4(), "Test".to_string(), None);
+        let mut blueprint =
+            ProductionLineBlueprint::new(ProductionLineId::new_v4(), "Test".to_string(), None);
 
-        let mut line =
-            ProductionLineRecipe::new(Uuid::new_v4(), "Line".to_string(), None, Recipe::IronPlate);
+        let mut line = ProductionLineRecipe::new(
+            ProductionLineId::new_v4(),
+            "Line".to_string(),
+            None,
+            Recipe::IronPlate,
+        );
 
         // Add invalid machine group (overclock > 250%)
         line.machine_groups.push(MachineGroup::new(1, 300.0, 0));
@@ -614,10 +630,15 @@ mod tests {
 
     #[tokio::test]
     async fn test_validate_blueprint_zero_machines() {
-        let mut blueprint = ProductionLineBlueprint::new(Uuid::new_v4(), "Test".to_string(), None);
+        let mut blueprint =
+            ProductionLineBlueprint::new(ProductionLineId::new_v4(), "Test".to_string(), None);
 
-        let mut line =
-            ProductionLineRecipe::new(Uuid::new_v4(), "Line".to_string(), None, Recipe::IronPlate);
+        let mut line = ProductionLineRecipe::new(
+            ProductionLineId::new_v4(),
+            "Line".to_string(),
+            None,
+            Recipe::IronPlate,
+        );
 
         // Add invalid machine group (0 machines)
         line.machine_groups.push(MachineGroup::new(0, 100.0, 0));
@@ -643,10 +664,8 @@ mod tests {
 
             let blueprint = create_test_blueprint();
             let blueprint_id = blueprint.id;
-            factory.production_lines.insert(
-                blueprint_id,
-                ProductionLine::ProductionLineBlueprint(blueprint),
-            );
+            factory.add_production_line(ProductionLine::ProductionLineBlueprint(blueprint));
+            engine.commit();
             (factory_id, blueprint_id)
         };
 
@@ -660,7 +679,9 @@ mod tests {
         // Create second factory
         let factory2_id = {
             let mut engine = state.engine.write().await;
-            engine.create_factory("Factory 2".to_string(), None)
+            let factory_id = engine.create_factory("Factory 2".to_string(), None);
+            engine.commit();
+            factory_id
         };
 
         // Import into second factory
@@ -680,7 +701,7 @@ mod tests {
 
         // Verify both factories have blueprints with same content (but different IDs)
         {
-            let engine = state.engine.read().await;
+            let engine = state.engine.snapshot();
             let factory1 = engine.get_factory(factory_id).unwrap();
             let factory2 = engine.get_factory(factory2_id).unwrap();
 
@@ -11,7 +11,6 @@ use axum::{
 };
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
 
 use crate::{error::AppError, state::AppState};
 use satisflow_engine::models::{
@@ -19,7 +18,8 @@ use satisflow_engine::models::{
         MachineGroup, ProductionLine, ProductionLineBlueprint, ProductionLineRecipe,
     },
     recipes::recipe_by_name,
-    Item, ProductionLineId,
+    FactoryId, Item, ProductionLineId, TemplateInstance, TemplatePropagation, TemplateRevision,
+    TemplateVersion,
 };
 
 /// Response for a single blueprint template
@@ -28,11 +28,14 @@ pub struct BlueprintTemplateResponse {
     pub id: ProductionLineId,
     pub name: String,
     pub description: Option<String>,
+    pub version: TemplateVersion,
+    pub parent_id: Option<ProductionLineId>,
+    pub changelog: Option<String>,
     pub production_lines: Vec<ProductionLineRecipeInfo>,
     pub total_machines: u32,
-    pub total_power: f32,
-    pub input_items: Vec<(Item, f32)>,
-    pub output_items: Vec<(Item, f32)>,
+    pub total_power: f64,
+    pub input_items: Vec<(Item, f64)>,
+    pub output_items: Vec<(Item, f64)>,
 }
 
 /// Information about a production line within a blueprint
@@ -49,7 +52,7 @@ pub struct ProductionLineRecipeInfo {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MachineGroupInfo {
     pub number_of_machine: u32,
-    pub oc_value: f32,
+    pub oc_value: f64,
     pub somersloop: u8,
 }
 
@@ -59,6 +62,9 @@ pub struct CreateBlueprintTemplateRequest {
     pub name: String,
     pub description: Option<String>,
     pub production_lines: Vec<CreateProductionLineRequest>,
+    /// Defaults to 1.0.0 on create and to the next minor version on update
+    pub version: Option<TemplateVersion>,
+    pub changelog: Option<String>,
 }
 
 /// Request for creating a production line within a blueprint
@@ -90,9 +96,9 @@ pub struct TemplateMetadata {
     pub name: String,
     pub description: Option<String>,
     pub total_machines: u32,
-    pub total_power: f32,
-    pub input_items: Vec<(Item, f32)>,
-    pub output_items: Vec<(Item, f32)>,
+    pub total_power: f64,
+    pub input_items: Vec<(Item, f64)>,
+    pub output_items: Vec<(Item, f64)>,
     pub exported_at: String,
 }
 
@@ -100,6 +106,16 @@ pub struct TemplateMetadata {
 #[derive(Debug, Deserialize)]
 pub struct CreateFromTemplateRequest {
     pub name: Option<String>,
+    /// Multiplies machine counts, e.g. `2.5` for two and a half copies
+    pub scale: Option<f64>,
+}
+
+/// Request for updating the instances of a template
+#[derive(Debug, Deserialize)]
+pub struct PropagateTemplateRequest {
+    /// Only report what would change
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
 /// Response for creating instance from template
@@ -107,17 +123,25 @@ pub struct CreateFromTemplateRequest {
 pub struct CreateFromTemplateResponse {
     pub message: String,
     pub blueprint_id: ProductionLineId,
-    pub factory_id: Uuid,
+    pub factory_id: FactoryId,
+    pub total_machines: u32,
+    pub total_power: f64,
+    pub input_items: Vec<(Item, f64)>,
+    pub output_items: Vec<(Item, f64)>,
 }
 
 impl From<&ProductionLineBlueprint> for BlueprintTemplateResponse {
     fn from(blueprint: &ProductionLineBlueprint) -> Self {
         let production_line = ProductionLine::ProductionLineBlueprint(blueprint.clone());
+        let revision = blueprint.revision.clone().unwrap_or_default();
 
         Self {
             id: blueprint.id,
             name: blueprint.name.clone(),
             description: blueprint.description.clone(),
+            version: revision.version,
+            parent_id: revision.parent_id,
+            changelog: revision.changelog,
             production_lines: blueprint
                 .production_lines
                 .iter()
@@ -125,7 +149,10 @@ impl From<&ProductionLineBlueprint> for BlueprintTemplateResponse {
                     id: line.id,
                     name: line.name.clone(),
                     description: line.description.clone(),
-                    recipe: format!("{:?}", line.recipe),
+                    recipe: match line.recipe.standard() {
+                        Some(recipe) => format!("{:?}", recipe),
+                        None => line.recipe.name().to_string(),
+                    },
                     machine_groups: line
                         .machine_groups
                         .iter()
@@ -155,7 +182,7 @@ impl From<&ProductionLineBlueprint> for BlueprintTemplateResponse {
 pub async fn get_all_templates(
     State(state): State<AppState>,
 ) -> Result<Json<Vec<BlueprintTemplateResponse>>, AppError> {
-    let engine = state.engine.read().await;
+    let engine = state.engine.snapshot();
 
     let templates: Vec<BlueprintTemplateResponse> = engine
         .get_all_blueprint_templates()
@@ -176,9 +203,9 @@ pub async fn get_all_templates(
 /// - `404 Not Found` if template doesn't exist
 pub async fn get_template(
     State(state): State<AppState>,
-    Path(id): Path<Uuid>,
+    Path(id): Path<ProductionLineId>,
 ) -> Result<Json<BlueprintTemplateResponse>, AppError> {
-    let engine = state.engine.read().await;
+    let engine = state.engine.snapshot();
 
     let template = engine
         .get_blueprint_template(id)
@@ -206,8 +233,16 @@ pub async fn create_template(
         ));
     }
 
-    let mut blueprint =
-        ProductionLineBlueprint::new(Uuid::new_v4(), request.name, request.description);
+    let mut blueprint = ProductionLineBlueprint::new(
+        ProductionLineId::new_v4(),
+        request.name,
+        request.description,
+    );
+    blueprint.revision = Some(TemplateRevision {
+        version: request.version.unwrap_or_default(),
+        parent_id: None,
+        changelog: request.changelog,
+    });
 
     // Convert request production lines to actual ProductionLineRecipe instances
     for line_request in request.production_lines {
@@ -217,7 +252,7 @@ pub async fn create_template(
         })?;
 
         let mut line = ProductionLineRecipe::new(
-            Uuid::new_v4(),
+            ProductionLineId::new_v4(),
             line_request.name,
             line_request.description,
             recipe,
@@ -241,36 +276,46 @@ pub async fn create_template(
 
     // Add to engine
     let mut engine = state.engine.write().await;
-    engine.add_blueprint_template(blueprint.clone());
+    let id = engine.add_blueprint_template(blueprint);
+    let engine = engine.commit();
+    let blueprint = engine
+        .get_blueprint_template(id)
+        .ok_or_else(|| AppError::NotFound(format!("Blueprint template {} not found", id)))?;
 
-    Ok((StatusCode::CREATED, Json((&blueprint).into())))
+    Ok((StatusCode::CREATED, Json(blueprint.into())))
 }
 
 /// PUT /api/blueprints/templates/:id
 ///
 /// Update a template (creates new version with new ID)
 ///
+/// The new version links back to the updated one and carries the request's
+/// changelog.
+///
 /// # Returns
 ///
 /// - `200 OK` with new template
 /// - `404 Not Found` if original template doesn't exist
-/// - `400 Bad Request` if validation fails
+/// - `400 Bad Request` if validation fails or the version is not newer
 pub async fn update_template(
     State(state): State<AppState>,
-    Path(id): Path<Uuid>,
+    Path(id): Path<ProductionLineId>,
     Json(request): Json<CreateBlueprintTemplateRequest>,
 ) -> Result<Json<BlueprintTemplateResponse>, AppError> {
     // Verify original exists
     {
-        let engine = state.engine.read().await;
+        let engine = state.engine.snapshot();
         engine
             .get_blueprint_template(id)
             .ok_or_else(|| AppError::NotFound(format!("Blueprint template {} not found", id)))?;
     }
 
     // Create new template with new ID (versioning behavior)
-    let mut new_blueprint =
-        ProductionLineBlueprint::new(Uuid::new_v4(), request.name, request.description);
+    let mut new_blueprint = ProductionLineBlueprint::new(
+        ProductionLineId::new_v4(),
+        request.name,
+        request.description,
+    );
 
     // Convert request production lines
     for line_request in request.production_lines {
@@ -279,7 +324,7 @@ pub async fn update_template(
         })?;
 
         let mut line = ProductionLineRecipe::new(
-            Uuid::new_v4(),
+            ProductionLineId::new_v4(),
             line_request.name,
             line_request.description,
             recipe,
@@ -301,9 +346,36 @@ pub async fn update_template(
 
     // Add new version to library
     let mut engine = state.engine.write().await;
-    engine.add_blueprint_template(new_blueprint.clone());
+    let new_id = engine
+        .add_blueprint_template_version(id, new_blueprint, request.version, request.changelog)
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+    let engine = engine.commit();
+    let new_blueprint = engine
+        .get_blueprint_template(new_id)
+        .ok_or_else(|| AppError::NotFound(format!("Blueprint template {} not found", new_id)))?;
+
+    Ok(Json(new_blueprint.into()))
+}
+
+/// GET /api/blueprints/templates/:id/versions
+///
+/// Get the full version history of a template
+///
+/// # Returns
+///
+/// - `200 OK` with every version, oldest first
+/// - `404 Not Found` if template doesn't exist
+pub async fn get_template_versions(
+    State(state): State<AppState>,
+    Path(id): Path<ProductionLineId>,
+) -> Result<Json<Vec<BlueprintTemplateResponse>>, AppError> {
+    let engine = state.engine.snapshot();
 
-    Ok(Json((&new_blueprint).into()))
+    let history = engine
+        .blueprint_template_history(id)
+        .map_err(|e| AppError::NotFound(e.to_string()))?;
+
+    Ok(Json(history.into_iter().map(Into::into).collect()))
 }
 
 /// DELETE /api/blueprints/templates/:id
@@ -316,12 +388,15 @@ pub async fn update_template(
 /// - `404 Not Found` if template doesn't exist
 pub async fn delete_template(
     State(state): State<AppState>,
-    Path(id): Path<Uuid>,
+    Path(id): Path<ProductionLineId>,
 ) -> Result<StatusCode, AppError> {
     let mut engine = state.engine.write().await;
     engine
         .remove_blueprint_template(id)
         .map_err(|e| AppError::NotFound(e.to_string()))?;
+
+    engine.commit();
+
     Ok(StatusCode::NO_CONTENT)
 }
 
@@ -342,9 +417,9 @@ pub async fn import_template(
         .map_err(|e| AppError::BadRequest(format!("Invalid blueprint JSON: {}", e)))?;
 
     // Generate new UUIDs
-    blueprint.id = Uuid::new_v4();
+    blueprint.id = ProductionLineId::new_v4();
     for line in &mut blueprint.production_lines {
-        line.id = Uuid::new_v4();
+        line.id = ProductionLineId::new_v4();
     }
 
     // Override name if provided
@@ -358,6 +433,8 @@ pub async fn import_template(
     let mut engine = state.engine.write().await;
     engine.add_blueprint_template(blueprint.clone());
 
+    engine.commit();
+
     Ok((StatusCode::CREATED, Json((&blueprint).into())))
 }
 
@@ -371,9 +448,9 @@ pub async fn import_template(
 /// - `404 Not Found` if template doesn't exist
 pub async fn export_template(
     State(state): State<AppState>,
-    Path(id): Path<Uuid>,
+    Path(id): Path<ProductionLineId>,
 ) -> Result<Json<ExportTemplateResponse>, AppError> {
-    let engine = state.engine.read().await;
+    let engine = state.engine.snapshot();
 
     let template = engine
         .get_blueprint_template(id)
@@ -404,13 +481,17 @@ pub async fn export_template(
 ///
 /// Create a production line instance from a template in a factory
 ///
+/// An optional `scale` multiplies every machine group; fractional machines
+/// become one underclocked machine.
+///
 /// # Returns
 ///
-/// - `201 Created` with created instance
+/// - `201 Created` with created instance and its totals
+/// - `400 Bad Request` if the scale is not positive
 /// - `404 Not Found` if factory or template doesn't exist
 pub async fn create_from_template(
     State(state): State<AppState>,
-    Path((factory_id, template_id)): Path<(Uuid, Uuid)>,
+    Path((factory_id, template_id)): Path<(FactoryId, ProductionLineId)>,
     Json(request): Json<CreateFromTemplateRequest>,
 ) -> Result<(StatusCode, Json<CreateFromTemplateResponse>), AppError> {
     let mut engine = state.engine.write().await;
@@ -423,9 +504,10 @@ pub async fn create_from_template(
 
     // Deep clone and regenerate UUIDs
     let mut blueprint = template.clone();
-    blueprint.id = Uuid::new_v4();
+    blueprint.id = ProductionLineId::new_v4();
+    blueprint.template_id = Some(template_id);
     for line in &mut blueprint.production_lines {
-        line.id = Uuid::new_v4();
+        line.id = ProductionLineId::new_v4();
     }
 
     // Override name if provided
@@ -433,25 +515,78 @@ pub async fn create_from_template(
         blueprint.name = name;
     }
 
+    if let Some(scale) = request.scale {
+        blueprint
+            .scale(scale)
+            .map_err(|e| AppError::BadRequest(e.to_string()))?;
+    }
+
     // Get factory and add blueprint
     let factory = engine
         .get_factory_mut(factory_id)
         .ok_or_else(|| AppError::NotFound(format!("Factory {} not found", factory_id)))?;
 
     let blueprint_id = blueprint.id;
-    factory.production_lines.insert(
+    let production_line = ProductionLine::ProductionLineBlueprint(blueprint);
+    let response = CreateFromTemplateResponse {
+        message: format!("Blueprint instance created in factory {}", factory_id),
         blueprint_id,
-        ProductionLine::ProductionLineBlueprint(blueprint),
-    );
+        factory_id,
+        total_machines: production_line.total_machines(),
+        total_power: production_line.total_power_consumption(),
+        input_items: production_line.input_rate(),
+        output_items: production_line.output_rate(),
+    };
+    factory.add_production_line(production_line);
+
+    engine.commit();
 
-    Ok((
-        StatusCode::CREATED,
-        Json(CreateFromTemplateResponse {
-            message: format!("Blueprint instance created in factory {}", factory_id),
-            blueprint_id,
-            factory_id,
-        }),
-    ))
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// GET /api/blueprints/templates/:id/instances
+///
+/// Get the blueprints in factories created from any version of a template
+///
+/// # Returns
+///
+/// - `200 OK` with the instances and whether they follow the latest version
+/// - `404 Not Found` if template doesn't exist
+pub async fn get_template_instances(
+    State(state): State<AppState>,
+    Path(id): Path<ProductionLineId>,
+) -> Result<Json<Vec<TemplateInstance>>, AppError> {
+    let engine = state.engine.snapshot();
+
+    let instances = engine
+        .blueprint_template_instances(id)
+        .map_err(|e| AppError::NotFound(e.to_string()))?;
+
+    Ok(Json(instances))
+}
+
+/// POST /api/blueprints/templates/:id/propagate
+///
+/// Update every instance of a template to its latest version
+///
+/// # Returns
+///
+/// - `200 OK` with the changes per factory, applied unless `dry_run` is set
+/// - `404 Not Found` if template doesn't exist
+pub async fn propagate_template(
+    State(state): State<AppState>,
+    Path(id): Path<ProductionLineId>,
+    Json(request): Json<PropagateTemplateRequest>,
+) -> Result<Json<TemplatePropagation>, AppError> {
+    let mut engine = state.engine.write().await;
+
+    let propagation = engine
+        .propagate_blueprint_template(id, request.dry_run)
+        .map_err(|e| AppError::NotFound(e.to_string()))?;
+
+    engine.commit();
+
+    Ok(Json(propagation))
 }
 
 /// Validates a blueprint template
@@ -500,6 +635,18 @@ pub fn routes() -> Router<AppState> {
         )
         .route("/blueprints/templates/import", post(import_template))
         .route("/blueprints/templates/:id/export", get(export_template))
+        .route(
+            "/blueprints/templates/:id/versions",
+            get(get_template_versions),
+        )
+        .route(
+            "/blueprints/templates/:id/instances",
+            get(get_template_instances),
+        )
+        .route(
+            "/blueprints/templates/:id/propagate",
+            post(propagate_template),
+        )
         .route(
             "/factories/:factory_id/production-lines/from-template/:template_id",
             post(create_from_template),
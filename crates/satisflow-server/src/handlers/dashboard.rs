@@ -1,55 +1,132 @@
 // crates/satisflow-server/src/handlers/dashboard.rs
-use axum::{extract::State, routing::get, Json, Router};
-use satisflow_engine::models::{power_generator::GeneratorType, Item};
-use serde::Serialize;
-use uuid::Uuid;
+use std::cmp::Ordering;
 
-use crate::{error::Result, state::AppState};
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, put},
+    Json, Router,
+};
+use satisflow_engine::format::{NumberFormat, RateUnit};
+use satisflow_engine::models::{
+    item_name,
+    power_generator::{FuelShortfall, GenerationRange, GeneratorType},
+    FactoryId, GoalProgress, GridPowerStats, Item, ItemBalanceReport, MetricsPoint,
+    NuclearWasteReport, PinnedEntities, PowerGridId, ReportCard, ResourceInventory, StorageStatus,
+    TagGroupStats,
+};
+use satisflow_engine::rate;
+use serde::{Deserialize, Serialize};
 
-#[derive(Serialize)]
+use crate::{
+    error::{AppError, Result},
+    state::AppState,
+};
+
+#[derive(Clone, Serialize)]
 pub struct DashboardSummary {
     pub total_factories: usize,
     pub total_production_lines: usize,
     pub total_logistics_lines: usize,
-    pub total_power_consumption: f32,
-    pub total_power_generation: f32,
-    pub net_power: f32,
+    pub total_power_consumption: f64,
+    pub total_power_generation: f64,
+    pub net_power: f64,
+    /// Checklist entries across every factory, and how many are not done yet
+    pub total_tasks: usize,
+    pub open_tasks: usize,
+    /// Progress towards every production goal, least complete first
+    pub goal_progress: Vec<GoalProgress>,
 }
 
 #[derive(Serialize)]
 pub struct ItemBalance {
     pub item: Item,
-    pub balance: f32,
+    pub balance: f64,
+    pub state: String,
+}
+
+#[derive(Serialize)]
+pub struct ItemBreakdownResponse {
+    #[serde(flatten)]
+    pub report: ItemBalanceReport,
     pub state: String,
 }
 
 #[derive(Serialize)]
 pub struct FactoryPowerStatsResponse {
-    pub factory_id: Uuid,
+    pub factory_id: FactoryId,
     pub factory_name: String,
-    pub generation: f32,
-    pub consumption: f32,
-    pub balance: f32,
+    pub generation: f64,
+    pub generation_range: GenerationRange,
+    pub consumption: f64,
+    pub balance: f64,
     pub generator_count: u32,
     pub generator_types: Vec<GeneratorType>,
+    pub power_grid: Option<PowerGridId>,
+    pub storage: Option<StorageStatus>,
+    pub fuel_shortfalls: Vec<FuelShortfall>,
 }
 
 #[derive(Serialize)]
 pub struct PowerStatisticsResponse {
-    pub total_generation: f32,
-    pub total_consumption: f32,
-    pub power_balance: f32,
+    pub total_generation: f64,
+    pub total_consumption: f64,
+    pub power_balance: f64,
+    pub generation_range: GenerationRange,
+    /// Balance when every geyser is at its lowest output
+    pub worst_case_balance: f64,
     pub has_surplus: bool,
     pub has_deficit: bool,
     pub is_balanced: bool,
     pub factory_stats: Vec<FactoryPowerStatsResponse>,
+    pub grid_stats: Vec<GridPowerStats>,
+    pub storage: Option<StorageStatus>,
+    /// Generator fuels that factories do not supply themselves
+    pub warnings: Vec<String>,
 }
 
-pub async fn get_summary(State(state): State<AppState>) -> Result<Json<DashboardSummary>> {
-    let mut engine = state.engine.write().await;
+#[derive(Serialize)]
+pub struct ReportCardResponse {
+    #[serde(flatten)]
+    pub report: ReportCard,
+    pub power_margin_percent: Option<f64>,
+    pub summary: String,
+}
 
-    // Update all factories to get current calculations
-    let _global_items = engine.update();
+#[derive(Serialize)]
+pub struct PinnedFactoryResponse {
+    pub factory_id: FactoryId,
+    pub factory_name: String,
+    pub items: Vec<ItemBalance>,
+    pub power_generation: f64,
+    pub power_consumption: f64,
+    pub power_balance: f64,
+    pub storage: Option<StorageStatus>,
+}
+
+#[derive(Serialize)]
+pub struct PinnedItemResponse {
+    pub item: Item,
+    pub total_produced: f64,
+    pub total_consumed: f64,
+    pub balance: f64,
+    pub state: String,
+}
+
+#[derive(Serialize)]
+pub struct PinnedDashboardResponse {
+    pub factories: Vec<PinnedFactoryResponse>,
+    pub items: Vec<PinnedItemResponse>,
+}
+
+/// GET /api/dashboard/summary
+///
+/// The summary is cached until the engine revision changes, so polling
+/// dashboards don't recalculate every factory.
+pub async fn get_summary(State(state): State<AppState>) -> Result<Json<DashboardSummary>> {
+    let engine = state.engine.snapshot();
+    if let Some(summary) = state.dashboard_summary.get(engine.revision()).await {
+        return Ok(Json(summary));
+    }
 
     let factories = engine.get_all_factories();
     let logistics_lines = engine.get_all_logistics();
@@ -61,46 +138,58 @@ pub async fn get_summary(State(state): State<AppState>) -> Result<Json<Dashboard
     let mut total_production_lines = 0;
     let mut total_power_consumption = 0.0;
     let mut total_power_generation = 0.0;
+    let mut total_tasks = 0;
+    let mut open_tasks = 0;
 
     for factory in factories.values() {
         total_production_lines += factory.production_lines.len();
+        total_tasks += factory.tasks.len();
+        open_tasks += factory.open_task_count();
         total_power_consumption += factory.total_power_consumption();
         total_power_generation += factory.total_power_generation();
     }
 
     let net_power = total_power_generation - total_power_consumption;
 
-    Ok(Json(DashboardSummary {
+    let summary = DashboardSummary {
         total_factories,
         total_production_lines,
         total_logistics_lines,
         total_power_consumption,
         total_power_generation,
         net_power,
-    }))
+        total_tasks,
+        open_tasks,
+        goal_progress: engine.all_goal_progress(),
+    };
+    state
+        .dashboard_summary
+        .store(engine.revision(), summary.clone())
+        .await;
+
+    Ok(Json(summary))
 }
 
-pub async fn get_item_balances(State(state): State<AppState>) -> Result<Json<Vec<ItemBalance>>> {
-    let mut engine = state.engine.write().await;
+/// GET /api/dashboard/tags
+///
+/// Dashboard totals for each factory tag, with untagged factories last
+pub async fn get_tag_groups(State(state): State<AppState>) -> Result<Json<Vec<TagGroupStats>>> {
+    let engine = state.engine.snapshot();
 
-    // Update all factories to get current calculations
-    let global_items = engine.update();
+    Ok(Json(engine.tag_group_stats()))
+}
+
+pub async fn get_item_balances(State(state): State<AppState>) -> Result<Json<Vec<ItemBalance>>> {
+    let engine = state.engine.snapshot();
+    let global_items = engine.global_items();
 
     let mut item_balances = Vec::new();
 
     for (item, balance) in global_items {
-        let state = if balance > 0.0 {
-            "overflow".to_string()
-        } else if balance < 0.0 {
-            "underflow".to_string()
-        } else {
-            "balanced".to_string()
-        };
-
         item_balances.push(ItemBalance {
             item,
             balance,
-            state,
+            state: balance_state(balance),
         });
     }
 
@@ -110,10 +199,186 @@ pub async fn get_item_balances(State(state): State<AppState>) -> Result<Json<Vec
     Ok(Json(item_balances))
 }
 
+pub async fn get_item_breakdown(
+    State(state): State<AppState>,
+    Path(item): Path<Item>,
+) -> Result<Json<ItemBreakdownResponse>> {
+    let engine = state.engine.snapshot();
+
+    let report = engine.item_balance_report(item);
+    let state = balance_state(report.net);
+
+    Ok(Json(ItemBreakdownResponse { report, state }))
+}
+
+/// Query parameters for the report card
+#[derive(Debug, Default, Deserialize)]
+pub struct ReportCardQuery {
+    /// Time base for rates in the summary; defaults to per minute
+    #[serde(default)]
+    pub rate_unit: RateUnit,
+}
+
+pub async fn get_report_card(
+    State(state): State<AppState>,
+    Query(query): Query<ReportCardQuery>,
+) -> Result<Json<ReportCardResponse>> {
+    let engine = state.engine.snapshot();
+
+    let report = engine.report_card();
+    let format = NumberFormat::default().with_rate_unit(query.rate_unit);
+
+    Ok(Json(ReportCardResponse {
+        power_margin_percent: report.power_margin_percent(),
+        summary: report.summary_with(&format),
+        report,
+    }))
+}
+
+pub async fn get_nuclear_waste(State(state): State<AppState>) -> Result<Json<NuclearWasteReport>> {
+    let engine = state.engine.snapshot();
+
+    Ok(Json(engine.nuclear_waste_report()))
+}
+
+pub async fn get_resource_inventory(
+    State(state): State<AppState>,
+) -> Result<Json<ResourceInventory>> {
+    let engine = state.engine.snapshot();
+
+    Ok(Json(engine.resource_inventory()))
+}
+
+pub async fn get_pinned_dashboard(
+    State(state): State<AppState>,
+) -> Result<Json<PinnedDashboardResponse>> {
+    let engine = state.engine.snapshot();
+
+    let pinned = engine.pinned();
+
+    let factories = pinned
+        .factories
+        .iter()
+        .filter_map(|id| engine.get_factory(*id))
+        .map(|factory| {
+            let mut items: Vec<ItemBalance> = factory
+                .items
+                .iter()
+                .map(|(item, balance)| ItemBalance {
+                    item: *item,
+                    balance: *balance,
+                    state: balance_state(*balance),
+                })
+                .collect();
+            items.sort_by(|a, b| item_name(a.item).cmp(item_name(b.item)));
+
+            PinnedFactoryResponse {
+                factory_id: factory.id,
+                factory_name: factory.name.clone(),
+                items,
+                power_generation: factory.total_power_generation(),
+                power_consumption: factory.total_power_consumption(),
+                power_balance: factory.power_balance(),
+                storage: factory.storage_status(),
+            }
+        })
+        .collect();
+
+    let items = pinned
+        .items
+        .iter()
+        .map(|item| {
+            let report = engine.item_balance_report(*item);
+            PinnedItemResponse {
+                item: *item,
+                total_produced: report.total_produced,
+                total_consumed: report.total_consumed,
+                balance: report.net,
+                state: balance_state(report.net),
+            }
+        })
+        .collect();
+
+    Ok(Json(PinnedDashboardResponse { factories, items }))
+}
+
+pub async fn pin_factory(
+    State(state): State<AppState>,
+    Path(id): Path<FactoryId>,
+) -> Result<Json<PinnedEntities>> {
+    let mut engine = state.engine.write().await;
+
+    engine
+        .pin_factory(id)
+        .map_err(|_| AppError::NotFound(format!("Factory with id {} not found", id)))?;
+
+    let engine = engine.commit();
+
+    Ok(Json(engine.pinned().clone()))
+}
+
+pub async fn unpin_factory(
+    State(state): State<AppState>,
+    Path(id): Path<FactoryId>,
+) -> Result<Json<PinnedEntities>> {
+    let mut engine = state.engine.write().await;
+
+    if !engine.unpin_factory(id) {
+        return Err(AppError::NotFound(format!(
+            "Factory with id {} is not pinned",
+            id
+        )));
+    }
+
+    let engine = engine.commit();
+
+    Ok(Json(engine.pinned().clone()))
+}
+
+pub async fn pin_item(
+    State(state): State<AppState>,
+    Path(item): Path<Item>,
+) -> Result<Json<PinnedEntities>> {
+    let mut engine = state.engine.write().await;
+
+    engine.pin_item(item);
+
+    let engine = engine.commit();
+
+    Ok(Json(engine.pinned().clone()))
+}
+
+pub async fn unpin_item(
+    State(state): State<AppState>,
+    Path(item): Path<Item>,
+) -> Result<Json<PinnedEntities>> {
+    let mut engine = state.engine.write().await;
+
+    if !engine.unpin_item(item) {
+        return Err(AppError::NotFound(format!(
+            "Item {} is not pinned",
+            item_name(item)
+        )));
+    }
+
+    let engine = engine.commit();
+
+    Ok(Json(engine.pinned().clone()))
+}
+
+/// `overflow`, `underflow` or `balanced`
+pub fn balance_state(balance: f64) -> String {
+    match rate::balance_sign(balance) {
+        Ordering::Greater => "overflow".to_string(),
+        Ordering::Less => "underflow".to_string(),
+        Ordering::Equal => "balanced".to_string(),
+    }
+}
+
 pub async fn get_power_statistics(
     State(state): State<AppState>,
 ) -> Result<Json<PowerStatisticsResponse>> {
-    let engine = state.engine.read().await;
+    let engine = state.engine.snapshot();
 
     // Get power statistics from the engine
     let power_stats = engine.global_power_stats();
@@ -126,10 +391,14 @@ pub async fn get_power_statistics(
             factory_id: stat.factory_id,
             factory_name: stat.factory_name.clone(),
             generation: stat.generation,
+            generation_range: stat.generation_range,
             consumption: stat.consumption,
             balance: stat.balance,
             generator_count: stat.generator_count,
             generator_types: stat.generator_types.clone(),
+            power_grid: stat.power_grid,
+            storage: stat.storage.clone(),
+            fuel_shortfalls: stat.fuel_shortfalls.clone(),
         })
         .collect();
 
@@ -137,18 +406,60 @@ pub async fn get_power_statistics(
         total_generation: power_stats.total_generation,
         total_consumption: power_stats.total_consumption,
         power_balance: power_stats.power_balance,
+        generation_range: power_stats.generation_range,
+        worst_case_balance: power_stats.worst_case_balance(),
         has_surplus: power_stats.has_surplus(),
         has_deficit: power_stats.has_deficit(),
         is_balanced: power_stats.is_balanced(),
+        warnings: power_stats.fuel_warnings(),
         factory_stats,
+        grid_stats: power_stats.grid_stats,
+        storage: power_stats.storage,
     };
 
     Ok(Json(response))
 }
 
+/// Query parameters for the metrics history
+#[derive(Debug, Default, Deserialize)]
+pub struct HistoryQuery {
+    /// Only report the rate of this item, as 0 where it had no balance
+    pub item: Option<Item>,
+}
+
+/// GET /api/dashboard/history
+///
+/// Power balance and item rates recorded after each change, oldest first
+pub async fn get_history(
+    State(state): State<AppState>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Json<Vec<MetricsPoint>>> {
+    let mut points = state.metrics_history.points().await;
+    if let Some(item) = query.item {
+        for point in &mut points {
+            let balance = point.items.get(&item).copied().unwrap_or(0.0);
+            point.items = [(item, balance)].into();
+        }
+    }
+
+    Ok(Json(points))
+}
+
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/summary", get(get_summary))
+        .route("/history", get(get_history))
         .route("/items", get(get_item_balances))
+        .route("/items/:item/breakdown", get(get_item_breakdown))
         .route("/power", get(get_power_statistics))
+        .route("/tags", get(get_tag_groups))
+        .route("/report-card", get(get_report_card))
+        .route("/nuclear-waste", get(get_nuclear_waste))
+        .route("/resources", get(get_resource_inventory))
+        .route("/pinned", get(get_pinned_dashboard))
+        .route(
+            "/pinned/factories/:id",
+            put(pin_factory).delete(unpin_factory),
+        )
+        .route("/pinned/items/:item", put(pin_item).delete(unpin_item))
 }
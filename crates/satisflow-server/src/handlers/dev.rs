@@ -0,0 +1,72 @@
+// crates/satisflow-server/src/handlers/dev.rs
+use axum::{
+    extract::{Query, State},
+    routing::post,
+    Json, Router,
+};
+use satisflow_engine::examples::{load_example, EXAMPLE_NAMES};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{AppError, Result},
+    state::AppState,
+};
+
+/// Query parameters for loading an example
+#[derive(Debug, Deserialize)]
+pub struct LoadExampleQuery {
+    /// Name of the bundled example, e.g. `five-factory`
+    pub name: String,
+}
+
+/// Response for a loaded example
+#[derive(Debug, Serialize)]
+pub struct LoadExampleResponse {
+    pub message: String,
+    pub name: String,
+    pub factory_count: usize,
+    pub logistics_count: usize,
+}
+
+/// POST /api/dev/load-example?name=five-factory
+///
+/// Replaces the current engine state with one of the engine's bundled examples
+///
+/// # Returns
+///
+/// - `200 OK` with the number of factories and logistics lines loaded
+/// - `404 Not Found` if no example has this name
+pub async fn load_example_handler(
+    State(state): State<AppState>,
+    Query(query): Query<LoadExampleQuery>,
+) -> Result<Json<LoadExampleResponse>> {
+    let mut example = load_example(&query.name).ok_or_else(|| {
+        AppError::NotFound(format!(
+            "Example '{}' not found (available: {})",
+            query.name,
+            EXAMPLE_NAMES.join(", ")
+        ))
+    })?;
+    example.update();
+
+    let factory_count = example.get_all_factories().len();
+    let logistics_count = example.get_all_logistics().len();
+
+    let mut engine = state.engine.write().await;
+    engine.replace(example);
+    engine.commit();
+
+    Ok(Json(LoadExampleResponse {
+        message: format!(
+            "Loaded example '{}' ({} factories, {} logistics lines)",
+            query.name, factory_count, logistics_count
+        ),
+        name: query.name,
+        factory_count,
+        logistics_count,
+    }))
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/load-example", post(load_example_handler))
+}
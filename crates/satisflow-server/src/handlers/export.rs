@@ -0,0 +1,84 @@
+// crates/satisflow-server/src/handlers/export.rs
+use axum::{
+    extract::{Query, State},
+    http::header,
+    response::IntoResponse,
+    routing::get,
+    Router,
+};
+use satisflow_engine::models::FactoryId;
+use serde::Deserialize;
+
+use crate::{
+    error::{AppError, Result},
+    state::AppState,
+};
+use satisflow_engine::{export, report, CsvSection, NumberFormat, RateUnit};
+
+/// Query parameters for the CSV export
+#[derive(Debug, Deserialize)]
+pub struct CsvExportQuery {
+    /// `factories`, `production_lines`, `items` or `logistics`
+    pub section: CsvSection,
+}
+
+/// GET /api/export/csv
+///
+/// Downloads one table of the save as a CSV file for spreadsheets
+pub async fn export_csv(
+    State(state): State<AppState>,
+    Query(query): Query<CsvExportQuery>,
+) -> Result<impl IntoResponse> {
+    let engine = state.engine.snapshot();
+
+    let disposition = format!("attachment; filename=\"{}\"", query.section.file_name());
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        export::to_csv(&engine, query.section),
+    ))
+}
+
+/// Query parameters for the Markdown report
+#[derive(Debug, Default, Deserialize)]
+pub struct ReportQuery {
+    /// Report on this factory only; every factory when omitted
+    pub factory_id: Option<FactoryId>,
+    /// Time base for rates; defaults to per minute
+    #[serde(default)]
+    pub rate_unit: RateUnit,
+}
+
+/// GET /api/export/report.md
+///
+/// Renders a Markdown report of one factory or of the whole save
+///
+/// # Returns
+///
+/// - `200 OK` with the report as `text/markdown`
+/// - `404 Not Found` if `factory_id` doesn't exist
+pub async fn export_markdown_report(
+    State(state): State<AppState>,
+    Query(query): Query<ReportQuery>,
+) -> Result<impl IntoResponse> {
+    let engine = state.engine.snapshot();
+    let format = NumberFormat::default().with_rate_unit(query.rate_unit);
+
+    let markdown = match query.factory_id {
+        Some(factory_id) => report::factory_markdown(&engine, factory_id, &format)
+            .map_err(|e| AppError::NotFound(e.to_string()))?,
+        None => report::global_markdown(&engine, &format),
+    };
+    Ok((
+        [(header::CONTENT_TYPE, "text/markdown; charset=utf-8")],
+        markdown,
+    ))
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/csv", get(export_csv))
+        .route("/report.md", get(export_markdown_report))
+}
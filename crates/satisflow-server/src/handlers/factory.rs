@@ -1,30 +1,51 @@
 // crates/satisflow-server/src/handlers/factory.rs
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
-    routing::{get, post, put},
+    routing::{get, patch, post, put},
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use std::collections::HashMap;
-use uuid::Uuid;
 
 use crate::{
     error::{AppError, Result},
+    handlers::{
+        factory_templates,
+        pagination::{contains_ignore_case, paginate, Listing, SortOrder},
+        patch::patched_payload,
+    },
     state::AppState,
 };
-use satisflow_engine::models::recipes::recipe_by_name;
 use satisflow_engine::models::{
+    balance_suggestions::BalanceSuggestion,
+    dependency::FactoryDependency,
     factory::Factory,
+    factory_delete::FactoryDeletePreview,
+    factory_merge::FactoryMergePreview,
+    factory_split::FactorySplitSelection,
+    factory_task::FactoryTask,
+    game_balance::GameBalance,
+    internal_transfer::InternalTransfer,
     logistics::LogisticsFlux,
-    power_generator::{GeneratorGroup as EngineGeneratorGroup, GeneratorType, PowerGenerator},
+    map::Position,
+    power_generator::{
+        FuelShortfall, GenerationRange, GeneratorGroup as EngineGeneratorGroup, GeneratorType,
+        PowerGenerator,
+    },
+    power_storage::PowerStorage,
     production_line::{
-        MachineGroup as EngineMachineGroup, ProductionLine, ProductionLineBlueprint,
-        ProductionLineRecipe,
+        ClockOptimization, ConsumptionRange, MachineGroup as EngineMachineGroup, ProductionLine,
+        ProductionLineBlueprint, ProductionLineRecipe,
     },
     raw_input::{ExtractorType, Purity, RawInput, ResourceWellExtractor, ResourceWellPressurizer},
-    Item,
+    storage_buffer::{BufferStatus, StorageBuffer},
+    timestamps::Timestamps,
+    FactoryId, FactoryTaskId, InternalTransferId, Item, LogisticsId, PowerGeneratorId, PowerGridId,
+    ProductionLineId, RawInputId, StorageBufferId,
 };
+use satisflow_engine::SatisflowEngine;
 
 // DTOs for API requests/responses
 #[derive(Serialize, Deserialize)]
@@ -32,6 +53,11 @@ pub struct CreateFactoryRequest {
     pub name: String,
     pub description: Option<String>,
     pub notes: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub archived: bool,
+    pub position: Option<Position>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -39,6 +65,89 @@ pub struct UpdateFactoryRequest {
     pub name: Option<String>,
     pub description: Option<String>,
     pub notes: Option<String>,
+    /// Replaces every tag; an empty list removes them
+    pub tags: Option<Vec<String>>,
+    pub archived: Option<bool>,
+    pub position: Option<Position>,
+    /// Take the factory off the map
+    #[serde(default)]
+    pub clear_position: bool,
+}
+
+/// Filters, sorting and paging for the factory list
+#[derive(Debug, Default, Deserialize)]
+pub struct FactoryListQuery {
+    /// Only factories carrying this tag, ignoring case
+    pub tag: Option<String>,
+    /// Only archived (`true`) or active (`false`) factories
+    pub archived: Option<bool>,
+    /// Only factories whose name contains this text, ignoring case
+    pub name_contains: Option<String>,
+    /// Only factories with this item in their balance
+    pub item: Option<Item>,
+    #[serde(default)]
+    pub sort_by: FactorySort,
+    #[serde(default)]
+    pub order: SortOrder,
+    /// Page number, starting at 1; the list is paged when this or `per_page` is set
+    pub page: Option<usize>,
+    pub per_page: Option<usize>,
+}
+
+/// Sort keys of the factory list
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FactorySort {
+    #[default]
+    Name,
+    CreatedAt,
+    UpdatedAt,
+    PowerBalance,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct MergeFactoriesRequest {
+    pub factory_ids: Vec<FactoryId>,
+    pub name: String,
+}
+
+#[derive(Deserialize)]
+pub struct AssignPowerGridRequest {
+    pub power_grid_id: Option<PowerGridId>,
+}
+
+#[derive(Deserialize)]
+pub struct PowerStoragePayload {
+    pub battery_count: u32,
+    /// Charge rate of one battery in MW; defaults to a standard Power Storage
+    pub charge_rate: Option<f64>,
+    /// Capacity of one battery in MWh; defaults to a standard Power Storage
+    pub capacity: Option<f64>,
+    #[serde(default)]
+    pub stored_mwh: f64,
+}
+
+#[derive(Deserialize)]
+pub struct CloneFactoryRequest {
+    pub name: String,
+    /// Add a task to the copy for each outbound logistics line, to connect later
+    #[serde(default)]
+    pub clone_logistics: bool,
+}
+
+#[derive(Deserialize)]
+pub struct SplitFactoryRequest {
+    /// The production lines, raw inputs and power generators to move
+    #[serde(flatten)]
+    pub selection: FactorySplitSelection,
+    pub name: String,
+    /// Create buses for the item flows crossing the new boundary
+    #[serde(default = "create_buses_by_default")]
+    pub create_buses: bool,
+}
+
+fn create_buses_by_default() -> bool {
+    true
 }
 
 #[derive(Deserialize, Clone, Copy)]
@@ -51,7 +160,7 @@ pub enum ProductionLineType {
 #[derive(Deserialize, Clone)]
 pub struct MachineGroupPayload {
     pub number_of_machine: u32,
-    pub oc_value: f32,
+    pub oc_value: f64,
     pub somersloop: u8,
 }
 
@@ -81,7 +190,7 @@ pub struct ProductionLinePayload {
 pub struct RawInputPressurizerPayload {
     #[serde(default)]
     pub id: Option<u64>,
-    pub clock_speed: f32,
+    pub clock_speed: f64,
 }
 
 #[derive(Deserialize, Clone)]
@@ -92,7 +201,33 @@ pub struct RawInputExtractorPayload {
     #[serde(default)]
     pub _item: Option<Item>,
     #[serde(default)]
-    pub _quantity_per_min: Option<f32>,
+    pub _quantity_per_min: Option<f64>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct InternalTransferPayload {
+    pub item: Item,
+    pub quantity_per_min: f64,
+    pub from_area: String,
+    pub to_area: String,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct FactoryTaskPayload {
+    pub text: String,
+    #[serde(default)]
+    pub done: bool,
+    #[serde(default)]
+    pub production_line: Option<ProductionLineId>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct StorageBufferPayload {
+    pub item: Item,
+    pub capacity_stacks: u32,
+    /// Items currently stored; the buffer starts empty when omitted
+    #[serde(default)]
+    pub stored: f64,
 }
 
 #[derive(Deserialize, Clone)]
@@ -101,27 +236,32 @@ pub struct RawInputPayload {
     pub item: Item,
     pub purity: Option<Purity>,
     #[serde(default)]
-    pub overclock_percent: Option<f32>, // Default to 100.0 if None
+    pub overclock_percent: Option<f64>, // Default to 100.0 if None
     #[serde(default)]
     pub count: Option<u32>, // Default to 1 if None
     #[serde(default)]
-    pub quantity_per_min: f32,
+    pub quantity_per_min: f64,
     #[serde(default)]
     pub pressurizer: Option<RawInputPressurizerPayload>,
     #[serde(default)]
     pub extractors: Vec<RawInputExtractorPayload>,
+    #[serde(default)]
+    pub claims_node: bool,
 }
 
 #[derive(Deserialize, Clone)]
 pub struct GeneratorGroupPayload {
     pub number_of_generators: u32,
-    pub clock_speed: f32,
+    pub clock_speed: f64,
 }
 
 #[derive(Deserialize, Clone)]
 pub struct PowerGeneratorPayload {
     pub generator_type: GeneratorType,
     pub fuel_type: Option<Item>,
+    /// Geyser purity, geothermal generators only
+    #[serde(default)]
+    pub purity: Option<Purity>,
     #[serde(default)]
     pub groups: Vec<GeneratorGroupPayload>,
 }
@@ -129,56 +269,118 @@ pub struct PowerGeneratorPayload {
 #[derive(Serialize)]
 pub struct ItemBalanceResponse {
     pub item: Item,
-    pub quantity: f32,
+    pub quantity: f64,
 }
 
 #[derive(Serialize)]
 pub struct ItemQuantity {
     pub item: Item,
-    pub quantity: f32,
+    pub quantity: f64,
 }
 
 #[derive(Serialize)]
 pub struct ProductionLineResponse {
     #[serde(flatten)]
     pub production_line: ProductionLine,
-    pub total_power_consumption: f32,
+    #[serde(flatten)]
+    pub timestamps: Timestamps,
+    pub total_power_consumption: f64,
     pub total_machines: u32,
     pub total_somersloop: u32,
     pub input_rate: Vec<ItemQuantity>,
+    /// Every output, byproducts included
     pub output_rate: Vec<ItemQuantity>,
+    /// Part of `output_rate` that is not the main product of its recipe
+    pub byproduct_rate: Vec<ItemQuantity>,
 }
 
 #[derive(Serialize)]
 pub struct RawInputResponse {
     #[serde(flatten)]
     pub raw_input: RawInput,
-    pub power_consumption: f32,
+    pub power_consumption: f64,
 }
 
 #[derive(Serialize)]
 pub struct PowerGeneratorResponse {
     #[serde(flatten)]
     pub power_generator: PowerGenerator,
-    pub total_power_generation: f32,
-    pub total_fuel_consumption: f32,
-    pub waste_production_rate: f32,
+    pub total_power_generation: f64,
+    pub generation_range: GenerationRange,
+    pub total_fuel_consumption: f64,
+    pub total_water_consumption: f64,
+    pub waste_production_rate: f64,
     pub waste_product: Option<Item>,
 }
 
 #[derive(Serialize)]
 pub struct FactoryResponse {
-    pub id: Uuid,
+    pub id: FactoryId,
     pub name: String,
     pub description: Option<String>,
     pub notes: Option<String>,
+    pub tags: Vec<String>,
+    pub archived: bool,
+    /// World coordinates in meters, for the map view
+    pub position: Option<Position>,
+    pub power_grid: Option<PowerGridId>,
+    pub power_storage: Option<PowerStorage>,
     pub production_lines: Vec<ProductionLineResponse>,
     pub raw_inputs: Vec<RawInputResponse>,
     pub power_generators: Vec<PowerGeneratorResponse>,
+    pub internal_transfers: Vec<InternalTransfer>,
+    /// Buffers with their fill or drain time under the factory's net rates
+    pub storage_buffers: Vec<BufferStatus>,
+    /// To-do checklist, in the order the player keeps it
+    pub tasks: Vec<FactoryTask>,
     pub items: Vec<ItemBalanceResponse>,
-    pub total_power_consumption: f32,
-    pub total_power_generation: f32,
-    pub power_balance: f32,
+    pub total_power_consumption: f64,
+    pub total_power_generation: f64,
+    pub power_balance: f64,
+    pub fuel_shortfalls: Vec<FuelShortfall>,
+    /// Human-readable version of `fuel_shortfalls`; creating a production line
+    /// also lists the recipes it uses that are beyond the recorded tier progress,
+    /// and creating a power generator the fuel and water the factory doesn't make
+    pub warnings: Vec<String>,
+    #[serde(flatten)]
+    pub timestamps: Timestamps,
+}
+
+#[derive(Serialize)]
+pub struct FactorySplitResponse {
+    pub source_factory: FactoryResponse,
+    pub new_factory: FactoryResponse,
+    pub logistics_ids: Vec<LogisticsId>,
+}
+
+#[derive(Serialize)]
+pub struct FactoryDependencyResponse {
+    pub factory_id: FactoryId,
+    pub factory_name: String,
+    pub items: Vec<ItemQuantity>,
+    pub logistics_ids: Vec<LogisticsId>,
+    pub total_quantity_per_min: f64,
+}
+
+#[derive(Serialize)]
+pub struct FactoryDependenciesResponse {
+    pub factory_id: FactoryId,
+    pub dependencies: Vec<FactoryDependencyResponse>,
+    pub dependents: Vec<FactoryDependencyResponse>,
+}
+
+/// Target of the clock speed optimizer
+#[derive(Deserialize, Clone)]
+pub struct OptimizeClockRequest {
+    /// Wanted output of the recipe's primary product, in items/min
+    pub target_rate: f64,
+    pub machine_count: u32,
+}
+
+/// Body of the endpoints enabling or disabling an entry of a factory
+#[derive(Deserialize, Clone)]
+pub struct SetEnabledRequest {
+    pub enabled: bool,
 }
 
 // Preview request/response types
@@ -200,6 +402,8 @@ pub struct PowerGeneratorPreviewRequest {
     pub generator_type: GeneratorType,
     pub fuel_type: Option<Item>,
     #[serde(default)]
+    pub purity: Option<Purity>,
+    #[serde(default)]
     pub groups: Vec<GeneratorGroupPayload>,
 }
 
@@ -209,11 +413,11 @@ pub struct RawInputPreviewRequest {
     pub item: Item,
     pub purity: Option<Purity>,
     #[serde(default)]
-    pub overclock_percent: Option<f32>,
+    pub overclock_percent: Option<f64>,
     #[serde(default)]
     pub count: Option<u32>,
     #[serde(default)]
-    pub quantity_per_min: f32,
+    pub quantity_per_min: f64,
     #[serde(default)]
     pub pressurizer: Option<RawInputPressurizerPayload>,
     #[serde(default)]
@@ -222,29 +426,38 @@ pub struct RawInputPreviewRequest {
 
 #[derive(Serialize)]
 pub struct ProductionLinePreviewResponse {
-    pub total_power_consumption: f32,
+    /// Average draw; see `consumption_range` for machines whose draw varies
+    pub total_power_consumption: f64,
+    pub consumption_range: ConsumptionRange,
     pub total_machines: u32,
     pub total_somersloop: u32,
+    /// Output multiplier from somersloops, 2.0 when every slot is filled
+    pub output_amplification: f64,
     pub input_rate: Vec<ItemQuantity>,
+    /// Every output, byproducts included
     pub output_rate: Vec<ItemQuantity>,
+    /// Part of `output_rate` that is not the main product of its recipe
+    pub byproduct_rate: Vec<ItemQuantity>,
 }
 
 #[derive(Serialize)]
 pub struct PowerGeneratorPreviewResponse {
-    pub total_power_generation: f32,
-    pub total_fuel_consumption: f32,
-    pub waste_production_rate: f32,
+    pub total_power_generation: f64,
+    pub generation_range: GenerationRange,
+    pub total_fuel_consumption: f64,
+    pub total_water_consumption: f64,
+    pub waste_production_rate: f64,
     pub waste_product: Option<Item>,
 }
 
 #[derive(Serialize)]
 pub struct RawInputPreviewResponse {
-    pub power_consumption: f32,
-    pub quantity_per_min: f32,
+    pub power_consumption: f64,
+    pub quantity_per_min: f64,
 }
 
-// Helper function to convert HashMap<Item, f32> to Vec<ItemBalanceResponse>
-fn convert_items_to_response(items: &HashMap<Item, f32>) -> Vec<ItemBalanceResponse> {
+// Helper function to convert HashMap<Item, f64> to Vec<ItemBalanceResponse>
+fn convert_items_to_response(items: &HashMap<Item, f64>) -> Vec<ItemBalanceResponse> {
     items
         .iter()
         .map(|(item, quantity)| ItemBalanceResponse {
@@ -255,10 +468,9 @@ fn convert_items_to_response(items: &HashMap<Item, f32>) -> Vec<ItemBalanceRespo
 }
 
 // Helper function to convert HashMap to Vec for nested data
-fn convert_production_lines_to_response(
-    production_lines: &HashMap<Uuid, ProductionLine>,
-) -> Vec<ProductionLineResponse> {
-    production_lines
+fn convert_production_lines_to_response(factory: &Factory) -> Vec<ProductionLineResponse> {
+    factory
+        .production_lines
         .values()
         .map(|pl| {
             let input_rate = pl
@@ -271,75 +483,182 @@ fn convert_production_lines_to_response(
                 .into_iter()
                 .map(|(item, quantity)| ItemQuantity { item, quantity })
                 .collect();
+            let byproduct_rate = pl
+                .byproduct_rate()
+                .into_iter()
+                .map(|(item, quantity)| ItemQuantity { item, quantity })
+                .collect();
 
             ProductionLineResponse {
-                total_power_consumption: pl.total_power_consumption(),
+                total_power_consumption: pl.total_power_consumption_with(factory.balance()),
                 total_machines: pl.total_machines(),
                 total_somersloop: pl.total_somersloop(),
                 input_rate,
                 output_rate,
+                byproduct_rate,
+                timestamps: factory
+                    .production_line_timestamps(pl.id())
+                    .unwrap_or_default(),
                 production_line: pl.clone(),
             }
         })
         .collect()
 }
 
-fn convert_raw_inputs_to_response(raw_inputs: &HashMap<Uuid, RawInput>) -> Vec<RawInputResponse> {
+fn convert_raw_inputs_to_response(
+    raw_inputs: &HashMap<RawInputId, RawInput>,
+    balance: &GameBalance,
+) -> Vec<RawInputResponse> {
     raw_inputs
         .values()
         .map(|ri| RawInputResponse {
             raw_input: ri.clone(),
-            power_consumption: ri.power_consumption(),
+            power_consumption: ri.power_consumption_with(balance),
         })
         .collect()
 }
 
 fn convert_power_generators_to_response(
-    power_generators: &HashMap<Uuid, PowerGenerator>,
+    power_generators: &HashMap<PowerGeneratorId, PowerGenerator>,
+    balance: &GameBalance,
 ) -> Vec<PowerGeneratorResponse> {
     power_generators
         .values()
         .map(|pg| PowerGeneratorResponse {
             power_generator: pg.clone(),
-            total_power_generation: pg.total_power_generation(),
+            total_power_generation: pg.total_power_generation_with(balance),
+            generation_range: pg.power_generation_range_with(balance),
             total_fuel_consumption: pg.total_fuel_consumption(),
+            total_water_consumption: pg.total_water_consumption(),
             waste_production_rate: pg.waste_production_rate(),
             waste_product: pg.waste_product(),
         })
         .collect()
 }
 
-fn build_factory_response(
+fn sorted_internal_transfers(
+    transfers: &HashMap<InternalTransferId, InternalTransfer>,
+) -> Vec<InternalTransfer> {
+    let mut transfers: Vec<InternalTransfer> = transfers.values().cloned().collect();
+    transfers
+        .sort_by(|a, b| (&a.from_area, &a.to_area, a.id).cmp(&(&b.from_area, &b.to_area, b.id)));
+    transfers
+}
+
+fn build_internal_transfer_from_payload(
+    payload: &InternalTransferPayload,
+    transfer_id: Option<InternalTransferId>,
+) -> Result<InternalTransfer> {
+    InternalTransfer::new(
+        transfer_id.unwrap_or_else(InternalTransferId::new_v4),
+        payload.item,
+        payload.quantity_per_min,
+        payload.from_area.clone(),
+        payload.to_area.clone(),
+    )
+    .map_err(|e| AppError::ValidationError(e.to_string()))
+}
+
+fn build_task_from_payload(
+    payload: &FactoryTaskPayload,
+    task_id: Option<FactoryTaskId>,
+) -> Result<FactoryTask> {
+    FactoryTask::new(
+        task_id.unwrap_or_else(FactoryTaskId::new_v4),
+        payload.text.clone(),
+        payload.production_line,
+    )
+    .map(|task| task.with_done(payload.done))
+    .map_err(|e| AppError::ValidationError(e.to_string()))
+}
+
+fn build_storage_buffer_from_payload(
+    payload: &StorageBufferPayload,
+    buffer_id: Option<StorageBufferId>,
+) -> Result<StorageBuffer> {
+    let buffer = StorageBuffer::new(
+        buffer_id.unwrap_or_else(StorageBufferId::new_v4),
+        payload.item,
+        payload.capacity_stacks,
+    )
+    .map_err(|e| AppError::ValidationError(e.to_string()))?
+    .with_stored(payload.stored);
+    buffer
+        .validate()
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+    Ok(buffer)
+}
+
+pub fn build_factory_response(
     factory: &Factory,
-    logistics: &HashMap<Uuid, LogisticsFlux>,
+    logistics: &HashMap<LogisticsId, LogisticsFlux>,
 ) -> FactoryResponse {
     let mut temp_factory = factory.clone();
     temp_factory.calculate_item(logistics);
+    let fuel_shortfalls = factory.fuel_shortfalls(logistics);
 
     FactoryResponse {
         id: factory.id,
         name: factory.name.clone(),
         description: factory.description.clone(),
         notes: factory.notes.clone(),
-        production_lines: convert_production_lines_to_response(&factory.production_lines),
-        raw_inputs: convert_raw_inputs_to_response(&factory.raw_inputs),
-        power_generators: convert_power_generators_to_response(&factory.power_generators),
+        tags: factory.tags.clone(),
+        archived: factory.archived,
+        position: factory.position,
+        power_grid: factory.power_grid,
+        power_storage: factory.power_storage.clone(),
+        production_lines: convert_production_lines_to_response(factory),
+        raw_inputs: convert_raw_inputs_to_response(&factory.raw_inputs, factory.balance()),
+        power_generators: convert_power_generators_to_response(
+            &factory.power_generators,
+            factory.balance(),
+        ),
+        internal_transfers: sorted_internal_transfers(&factory.internal_transfers),
+        storage_buffers: factory.storage_buffer_statuses(logistics),
+        tasks: factory.tasks.clone(),
         items: convert_items_to_response(&temp_factory.items),
         total_power_consumption: temp_factory.total_power_consumption(),
         total_power_generation: temp_factory.total_power_generation(),
         power_balance: temp_factory.power_balance(),
+        warnings: fuel_shortfalls.iter().map(ToString::to_string).collect(),
+        fuel_shortfalls,
+        timestamps: factory.timestamps,
     }
 }
 
+fn convert_dependencies_to_response(
+    dependencies: &[FactoryDependency],
+) -> Vec<FactoryDependencyResponse> {
+    dependencies
+        .iter()
+        .map(|dependency| FactoryDependencyResponse {
+            factory_id: dependency.factory_id,
+            factory_name: dependency.factory_name.clone(),
+            items: dependency
+                .items
+                .iter()
+                .map(|flow| ItemQuantity {
+                    item: flow.item,
+                    quantity: flow.quantity_per_min,
+                })
+                .collect(),
+            logistics_ids: dependency.logistics_ids.clone(),
+            total_quantity_per_min: dependency.total_quantity_per_min(),
+        })
+        .collect()
+}
+
 fn build_recipe_line_from_payload(
+    engine: &SatisflowEngine,
     payload: &ProductionLinePayload,
-    line_id: Uuid,
+    line_id: ProductionLineId,
 ) -> Result<ProductionLineRecipe> {
     let recipe_name = payload.recipe.as_ref().ok_or_else(|| {
         AppError::BadRequest("Recipe name is required for recipe lines".to_string())
     })?;
 
-    let recipe = recipe_by_name(recipe_name)
+    let recipe = engine
+        .resolve_recipe(recipe_name)
         .ok_or_else(|| AppError::BadRequest(format!("Unknown recipe: {}", recipe_name)))?;
 
     if payload.machine_groups.is_empty() {
@@ -366,8 +685,9 @@ fn build_recipe_line_from_payload(
 }
 
 fn build_blueprint_line_from_payload(
+    engine: &SatisflowEngine,
     payload: &ProductionLinePayload,
-    line_id: Uuid,
+    line_id: ProductionLineId,
 ) -> Result<ProductionLine> {
     if payload.production_lines.is_empty() {
         return Err(AppError::BadRequest(
@@ -379,7 +699,8 @@ fn build_blueprint_line_from_payload(
         ProductionLineBlueprint::new(line_id, payload.name.clone(), payload.description.clone());
 
     for sub_line in &payload.production_lines {
-        let recipe = recipe_by_name(&sub_line.recipe)
+        let recipe = engine
+            .resolve_recipe(&sub_line.recipe)
             .ok_or_else(|| AppError::BadRequest(format!("Unknown recipe: {}", sub_line.recipe)))?;
 
         if sub_line.machine_groups.is_empty() {
@@ -390,7 +711,7 @@ fn build_blueprint_line_from_payload(
         }
 
         let mut blueprint_line = ProductionLineRecipe::new(
-            Uuid::new_v4(),
+            ProductionLineId::new_v4(),
             sub_line.name.clone(),
             sub_line.description.clone(),
             recipe,
@@ -411,22 +732,28 @@ fn build_blueprint_line_from_payload(
 }
 
 fn build_production_line_from_payload(
+    engine: &SatisflowEngine,
     payload: &ProductionLinePayload,
-    id: Option<Uuid>,
+    id: Option<ProductionLineId>,
 ) -> Result<ProductionLine> {
-    let line_id = id.unwrap_or_else(Uuid::new_v4);
+    let line_id = id.unwrap_or_else(ProductionLineId::new_v4);
 
     match payload.line_type {
         ProductionLineType::Recipe => {
-            let recipe_line = build_recipe_line_from_payload(payload, line_id)?;
+            let recipe_line = build_recipe_line_from_payload(engine, payload, line_id)?;
             Ok(ProductionLine::ProductionLineRecipe(recipe_line))
         }
-        ProductionLineType::Blueprint => build_blueprint_line_from_payload(payload, line_id),
+        ProductionLineType::Blueprint => {
+            build_blueprint_line_from_payload(engine, payload, line_id)
+        }
     }
 }
 
-fn build_raw_input_from_payload(payload: &RawInputPayload, id: Option<Uuid>) -> Result<RawInput> {
-    let raw_input_id = id.unwrap_or_else(Uuid::new_v4);
+fn build_raw_input_from_payload(
+    payload: &RawInputPayload,
+    id: Option<RawInputId>,
+) -> Result<RawInput> {
+    let raw_input_id = id.unwrap_or_else(RawInputId::new_v4);
 
     // Extract OC and count with defaults
     let overclock_percent = payload.overclock_percent.unwrap_or(100.0);
@@ -475,6 +802,10 @@ fn build_raw_input_from_payload(payload: &RawInputPayload, id: Option<Uuid>) ->
         .map_err(|e| AppError::ValidationError(e.to_string()))?
     };
 
+    raw_input
+        .set_claims_node(payload.claims_node)
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
     // Only override quantity_per_min if explicitly provided and > 0
     // Otherwise, use the calculated value from the engine
     if payload.quantity_per_min > 0.0 {
@@ -486,9 +817,9 @@ fn build_raw_input_from_payload(payload: &RawInputPayload, id: Option<Uuid>) ->
 
 fn build_power_generator_from_payload(
     payload: &PowerGeneratorPayload,
-    id: Option<Uuid>,
+    id: Option<PowerGeneratorId>,
 ) -> Result<PowerGenerator> {
-    let generator_id = id.unwrap_or_else(Uuid::new_v4);
+    let generator_id = id.unwrap_or_else(PowerGeneratorId::new_v4);
 
     let mut generator = match payload.generator_type {
         GeneratorType::Geothermal => PowerGenerator::new_geothermal(generator_id),
@@ -501,6 +832,12 @@ fn build_power_generator_from_payload(
         }
     };
 
+    if let Some(purity) = payload.purity {
+        generator
+            .set_purity(purity)
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+    }
+
     if payload.groups.is_empty() {
         return Err(AppError::BadRequest(
             "At least one generator group is required".to_string(),
@@ -520,24 +857,59 @@ fn build_power_generator_from_payload(
 }
 
 // API handlers
-pub async fn get_factories(State(state): State<AppState>) -> Result<Json<Vec<FactoryResponse>>> {
-    let engine = state.engine.read().await;
+pub async fn get_factories(
+    State(state): State<AppState>,
+    Query(query): Query<FactoryListQuery>,
+) -> Result<Json<Listing<FactoryResponse>>> {
+    let engine = state.engine.snapshot();
     let factories = engine.get_all_factories();
     let logistics_lines = engine.get_all_logistics();
 
-    let responses = factories
+    let mut selected: Vec<&Factory> = factories
         .values()
-        .map(|factory| build_factory_response(factory, logistics_lines))
+        .filter(|factory| query.tag.as_deref().is_none_or(|tag| factory.has_tag(tag)))
+        .filter(|factory| {
+            query
+                .archived
+                .is_none_or(|archived| factory.archived == archived)
+        })
+        .filter(|factory| {
+            query
+                .name_contains
+                .as_deref()
+                .is_none_or(|text| contains_ignore_case(&factory.name, text))
+        })
+        .filter(|factory| {
+            query.item.is_none_or(|item| {
+                let mut balance = (*factory).clone();
+                balance.calculate_item(logistics_lines);
+                balance.items.contains_key(&item)
+            })
+        })
         .collect();
 
-    Ok(Json(responses))
+    selected.sort_by(|a, b| {
+        let ordering = match query.sort_by {
+            FactorySort::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            FactorySort::CreatedAt => a.timestamps.created_at.cmp(&b.timestamps.created_at),
+            FactorySort::UpdatedAt => a.timestamps.updated_at.cmp(&b.timestamps.updated_at),
+            FactorySort::PowerBalance => a.power_balance().total_cmp(&b.power_balance()),
+        };
+        query.order.apply(ordering.then(a.id.cmp(&b.id)))
+    });
+
+    // Only the factories of the requested page are turned into responses
+    let listing = paginate(selected, query.page, query.per_page)?
+        .map(|factory| build_factory_response(factory, logistics_lines));
+
+    Ok(Json(listing))
 }
 
 pub async fn get_factory(
     State(state): State<AppState>,
-    Path(id): Path<Uuid>,
+    Path(id): Path<FactoryId>,
 ) -> Result<Json<FactoryResponse>> {
-    let engine = state.engine.read().await;
+    let engine = state.engine.snapshot();
 
     let factory = engine
         .get_factory(id)
@@ -561,6 +933,10 @@ pub async fn create_factory(
         ));
     }
 
+    if let Some(position) = &request.position {
+        position.validate().map_err(AppError::ValidationError)?;
+    }
+
     let factory_id = engine.create_factory(request.name.clone(), request.description.clone());
 
     if let Some(factory) = engine.get_factory_mut(factory_id) {
@@ -568,20 +944,28 @@ pub async fn create_factory(
             Some(notes) if notes.trim().is_empty() => None,
             other => other,
         };
+        factory.set_tags(request.tags.clone());
+        factory.archived = request.archived;
+        factory.position = request.position;
     }
 
-    let factory = engine
-        .get_factory(factory_id)
-        .ok_or_else(|| AppError::InternalError(anyhow::anyhow!("Failed to retrieve created factory")))?;
+    let factory = engine.get_factory(factory_id).ok_or_else(|| {
+        AppError::InternalError(anyhow::anyhow!("Failed to retrieve created factory"))
+    })?;
 
     let response = build_factory_response(factory, engine.get_all_logistics());
 
+    engine.commit();
+
     Ok((StatusCode::CREATED, Json(response)))
 }
 
+/// PUT or PATCH /api/factories/:id
+///
+/// Only the fields present in the body are changed, whichever the verb
 pub async fn update_factory(
     State(state): State<AppState>,
-    Path(id): Path<Uuid>,
+    Path(id): Path<FactoryId>,
     Json(request): Json<UpdateFactoryRequest>,
 ) -> Result<Json<FactoryResponse>> {
     let mut engine = state.engine.write().await;
@@ -611,6 +995,24 @@ pub async fn update_factory(
                 factory.notes = Some(notes);
             }
         }
+
+        if let Some(tags) = request.tags {
+            factory.set_tags(tags);
+        }
+
+        if let Some(archived) = request.archived {
+            factory.archived = archived;
+        }
+    }
+
+    if request.clear_position {
+        engine
+            .set_factory_position(id, None)
+            .map_err(|e| AppError::EngineError(e.to_string()))?;
+    } else if request.position.is_some() {
+        engine
+            .set_factory_position(id, request.position)
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
     }
 
     let updated_factory = engine
@@ -619,204 +1021,1039 @@ pub async fn update_factory(
 
     let response = build_factory_response(updated_factory, engine.get_all_logistics());
 
+    engine.commit();
+
     Ok(Json(response))
 }
 
-pub async fn delete_factory(
+pub async fn assign_factory_power_grid(
     State(state): State<AppState>,
-    Path(id): Path<Uuid>,
-) -> Result<StatusCode> {
+    Path(id): Path<FactoryId>,
+    Json(request): Json<AssignPowerGridRequest>,
+) -> Result<Json<FactoryResponse>> {
     let mut engine = state.engine.write().await;
 
+    if engine.get_factory(id).is_none() {
+        return Err(AppError::NotFound(format!(
+            "Factory with id {} not found",
+            id
+        )));
+    }
+
     engine
-        .delete_factory(id)
-        .map_err(|_| AppError::NotFound(format!("Factory with id {} not found", id)))?;
+        .assign_factory_to_power_grid(id, request.power_grid_id)
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
 
-    Ok(StatusCode::NO_CONTENT)
+    let factory = engine
+        .get_factory(id)
+        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", id)))?;
+
+    let response = build_factory_response(factory, engine.get_all_logistics());
+
+    engine.commit();
+
+    Ok(Json(response))
 }
 
-pub async fn create_production_line(
+pub async fn set_factory_power_storage(
     State(state): State<AppState>,
-    Path(factory_id): Path<Uuid>,
-    Json(payload): Json<ProductionLinePayload>,
-) -> Result<(StatusCode, Json<FactoryResponse>)> {
+    Path(id): Path<FactoryId>,
+    Json(payload): Json<PowerStoragePayload>,
+) -> Result<Json<FactoryResponse>> {
     let mut engine = state.engine.write().await;
 
-    let production_line = build_production_line_from_payload(&payload, None)?;
+    let mut storage = PowerStorage::new(payload.battery_count).with_stored(payload.stored_mwh);
+    if let Some(charge_rate) = payload.charge_rate {
+        storage.charge_rate = charge_rate;
+    }
+    if let Some(capacity) = payload.capacity {
+        storage.capacity = capacity;
+    }
 
     {
-        let factory = engine.get_factory_mut(factory_id).ok_or_else(|| {
-            AppError::NotFound(format!("Factory with id {} not found", factory_id))
-        })?;
-        factory.add_production_line(production_line);
+        let factory = engine
+            .get_factory_mut(id)
+            .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", id)))?;
+        factory
+            .set_power_storage(storage)
+            .map_err(AppError::ValidationError)?;
     }
 
     let factory = engine
-        .get_factory(factory_id)
-        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", factory_id)))?;
+        .get_factory(id)
+        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", id)))?;
 
     let response = build_factory_response(factory, engine.get_all_logistics());
 
-    Ok((StatusCode::CREATED, Json(response)))
+    engine.commit();
+
+    Ok(Json(response))
 }
 
-pub async fn update_production_line(
+pub async fn delete_factory_power_storage(
     State(state): State<AppState>,
-    Path((factory_id, line_id)): Path<(Uuid, Uuid)>,
-    Json(payload): Json<ProductionLinePayload>,
+    Path(id): Path<FactoryId>,
 ) -> Result<Json<FactoryResponse>> {
     let mut engine = state.engine.write().await;
 
-    let production_line = build_production_line_from_payload(&payload, Some(line_id))?;
-
     {
-        let factory = engine.get_factory_mut(factory_id).ok_or_else(|| {
-            AppError::NotFound(format!("Factory with id {} not found", factory_id))
-        })?;
-
-        if factory
-            .production_lines
-            .insert(line_id, production_line)
-            .is_none()
-        {
+        let factory = engine
+            .get_factory_mut(id)
+            .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", id)))?;
+        if factory.clear_power_storage().is_none() {
             return Err(AppError::NotFound(format!(
-                "Production line with id {} not found",
-                line_id
+                "Factory with id {} has no power storage",
+                id
             )));
         }
     }
 
     let factory = engine
-        .get_factory(factory_id)
-        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", factory_id)))?;
+        .get_factory(id)
+        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", id)))?;
 
     let response = build_factory_response(factory, engine.get_all_logistics());
 
+    engine.commit();
+
     Ok(Json(response))
 }
 
-pub async fn delete_production_line(
+/// GET /api/factories/:id/delete-preview
+///
+/// Lists what deleting the factory would also remove, and the deficits its
+/// missing shipments would leave in other factories
+///
+/// # Returns
+///
+/// - `200 OK` with the preview
+/// - `404 Not Found` if the factory doesn't exist
+pub async fn preview_delete_factory(
     State(state): State<AppState>,
-    Path((factory_id, line_id)): Path<(Uuid, Uuid)>,
-) -> Result<Json<FactoryResponse>> {
+    Path(id): Path<FactoryId>,
+) -> Result<Json<FactoryDeletePreview>> {
+    let engine = state.engine.snapshot();
+
+    let preview = engine
+        .preview_delete_factory(id)
+        .map_err(|_| AppError::NotFound(format!("Factory with id {} not found", id)))?;
+
+    Ok(Json(preview))
+}
+
+pub async fn delete_factory(
+    State(state): State<AppState>,
+    Path(id): Path<FactoryId>,
+) -> Result<StatusCode> {
     let mut engine = state.engine.write().await;
 
-    {
-        let factory = engine.get_factory_mut(factory_id).ok_or_else(|| {
-            AppError::NotFound(format!("Factory with id {} not found", factory_id))
-        })?;
+    engine
+        .delete_factory(id)
+        .map_err(|_| AppError::NotFound(format!("Factory with id {} not found", id)))?;
 
-        if factory.production_lines.remove(&line_id).is_none() {
-            return Err(AppError::NotFound(format!(
-                "Production line with id {} not found",
-                line_id
-            )));
-        }
-    }
+    engine.commit();
 
-    let factory = engine
-        .get_factory(factory_id)
-        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", factory_id)))?;
+    Ok(StatusCode::NO_CONTENT)
+}
 
-    let response = build_factory_response(factory, engine.get_all_logistics());
+pub async fn preview_merge_factories(
+    State(state): State<AppState>,
+    Json(request): Json<MergeFactoriesRequest>,
+) -> Result<Json<FactoryMergePreview>> {
+    let engine = state.engine.snapshot();
 
-    Ok(Json(response))
+    let preview = engine
+        .preview_merge_factories(&request.factory_ids, &request.name)
+        .map_err(|e| AppError::BadRequest(format!("Cannot merge factories: {}", e)))?;
+
+    Ok(Json(preview))
 }
 
-pub async fn create_raw_input(
+pub async fn merge_factories(
     State(state): State<AppState>,
-    Path(factory_id): Path<Uuid>,
-    Json(payload): Json<RawInputPayload>,
+    Json(request): Json<MergeFactoriesRequest>,
 ) -> Result<(StatusCode, Json<FactoryResponse>)> {
     let mut engine = state.engine.write().await;
 
-    let raw_input = build_raw_input_from_payload(&payload, None)?;
+    let merged_id = engine
+        .merge_factories(&request.factory_ids, &request.name)
+        .map_err(|e| AppError::BadRequest(format!("Cannot merge factories: {}", e)))?;
 
-    {
-        let factory = engine.get_factory_mut(factory_id).ok_or_else(|| {
-            AppError::NotFound(format!("Factory with id {} not found", factory_id))
-        })?;
-        factory
-            .add_raw_input(raw_input)
-            .map_err(AppError::ValidationError)?;
-    }
+    let merged = engine.get_factory(merged_id).ok_or_else(|| {
+        AppError::InternalError(anyhow::anyhow!("Failed to retrieve merged factory"))
+    })?;
 
-    let factory = engine
-        .get_factory(factory_id)
-        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", factory_id)))?;
+    let response = build_factory_response(merged, engine.get_all_logistics());
 
-    let response = build_factory_response(factory, engine.get_all_logistics());
+    engine.commit();
 
     Ok((StatusCode::CREATED, Json(response)))
 }
 
-pub async fn update_raw_input(
+pub async fn clone_factory(
     State(state): State<AppState>,
-    Path((factory_id, raw_input_id)): Path<(Uuid, Uuid)>,
-    Json(payload): Json<RawInputPayload>,
-) -> Result<Json<FactoryResponse>> {
+    Path(id): Path<FactoryId>,
+    Json(request): Json<CloneFactoryRequest>,
+) -> Result<(StatusCode, Json<FactoryResponse>)> {
     let mut engine = state.engine.write().await;
 
-    let raw_input = build_raw_input_from_payload(&payload, Some(raw_input_id))?;
+    if engine.get_factory(id).is_none() {
+        return Err(AppError::NotFound(format!(
+            "Factory with id {} not found",
+            id
+        )));
+    }
 
-    {
-        let factory = engine.get_factory_mut(factory_id).ok_or_else(|| {
-            AppError::NotFound(format!("Factory with id {} not found", factory_id))
-        })?;
+    let clone_id = engine
+        .clone_factory(id, &request.name, request.clone_logistics)
+        .map_err(|e| AppError::BadRequest(format!("Cannot clone factory: {}", e)))?;
 
-        if factory.raw_inputs.insert(raw_input_id, raw_input).is_none() {
-            return Err(AppError::NotFound(format!(
-                "Raw input with id {} not found",
-                raw_input_id
-            )));
-        }
-    }
+    let clone = engine.get_factory(clone_id).ok_or_else(|| {
+        AppError::InternalError(anyhow::anyhow!("Failed to retrieve cloned factory"))
+    })?;
 
-    let factory = engine
-        .get_factory(factory_id)
-        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", factory_id)))?;
+    let response = build_factory_response(clone, engine.get_all_logistics());
 
-    let response = build_factory_response(factory, engine.get_all_logistics());
+    engine.commit();
 
-    Ok(Json(response))
+    Ok((StatusCode::CREATED, Json(response)))
 }
 
-pub async fn delete_raw_input(
+pub async fn split_factory(
     State(state): State<AppState>,
-    Path((factory_id, raw_input_id)): Path<(Uuid, Uuid)>,
-) -> Result<Json<FactoryResponse>> {
+    Path(id): Path<FactoryId>,
+    Json(request): Json<SplitFactoryRequest>,
+) -> Result<(StatusCode, Json<FactorySplitResponse>)> {
     let mut engine = state.engine.write().await;
 
-    {
-        let factory = engine.get_factory_mut(factory_id).ok_or_else(|| {
-            AppError::NotFound(format!("Factory with id {} not found", factory_id))
-        })?;
-
-        if factory.remove_raw_input(raw_input_id).is_none() {
-            return Err(AppError::NotFound(format!(
-                "Raw input with id {} not found",
-                raw_input_id
-            )));
-        }
+    if engine.get_factory(id).is_none() {
+        return Err(AppError::NotFound(format!(
+            "Factory with id {} not found",
+            id
+        )));
     }
 
-    let factory = engine
-        .get_factory(factory_id)
-        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", factory_id)))?;
+    let result = engine
+        .split_factory(id, &request.selection, &request.name, request.create_buses)
+        .map_err(|e| AppError::BadRequest(format!("Cannot split factory: {}", e)))?;
 
-    let response = build_factory_response(factory, engine.get_all_logistics());
+    let logistics = engine.get_all_logistics();
+    let (source, new_factory) = match (
+        engine.get_factory(result.source_factory),
+        engine.get_factory(result.new_factory),
+    ) {
+        (Some(source), Some(new_factory)) => (source, new_factory),
+        _ => {
+            return Err(AppError::InternalError(anyhow::anyhow!(
+                "Failed to retrieve split factories"
+            )))
+        }
+    };
+
+    let response = FactorySplitResponse {
+        source_factory: build_factory_response(source, logistics),
+        new_factory: build_factory_response(new_factory, logistics),
+        logistics_ids: result.logistics_ids,
+    };
+
+    engine.commit();
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+pub async fn get_factory_dependencies(
+    State(state): State<AppState>,
+    Path(id): Path<FactoryId>,
+) -> Result<Json<FactoryDependenciesResponse>> {
+    let engine = state.engine.snapshot();
+
+    let dependencies = engine
+        .factory_dependencies(id)
+        .map_err(|_| AppError::NotFound(format!("Factory with id {} not found", id)))?;
+
+    Ok(Json(FactoryDependenciesResponse {
+        factory_id: dependencies.factory_id,
+        dependencies: convert_dependencies_to_response(&dependencies.dependencies),
+        dependents: convert_dependencies_to_response(&dependencies.dependents),
+    }))
+}
+
+/// GET /api/factories/:id/balance-suggestions
+///
+/// Suggests, for each production line, the machine count and underclock its
+/// inputs can sustain
+pub async fn get_balance_suggestions(
+    State(state): State<AppState>,
+    Path(id): Path<FactoryId>,
+) -> Result<Json<Vec<BalanceSuggestion>>> {
+    let engine = state.engine.snapshot();
+
+    let suggestions = engine
+        .balance_suggestions(id)
+        .map_err(|_| AppError::NotFound(format!("Factory with id {} not found", id)))?;
+
+    Ok(Json(suggestions))
+}
+
+pub async fn create_production_line(
+    State(state): State<AppState>,
+    Path(factory_id): Path<FactoryId>,
+    Json(payload): Json<ProductionLinePayload>,
+) -> Result<(StatusCode, Json<FactoryResponse>)> {
+    let mut engine = state.engine.write().await;
+
+    let production_line = build_production_line_from_payload(&engine, &payload, None)?;
+    let progression_warnings = engine.progression_warnings(&production_line);
+
+    {
+        let factory = engine.get_factory_mut(factory_id).ok_or_else(|| {
+            AppError::NotFound(format!("Factory with id {} not found", factory_id))
+        })?;
+        factory.add_production_line(production_line);
+    }
+
+    let factory = engine
+        .get_factory(factory_id)
+        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", factory_id)))?;
+
+    let mut response = build_factory_response(factory, engine.get_all_logistics());
+    // The line is still created; the player may have progressed past the record
+    response.warnings.extend(progression_warnings);
+
+    engine.commit();
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+pub async fn update_production_line(
+    State(state): State<AppState>,
+    Path((factory_id, line_id)): Path<(FactoryId, ProductionLineId)>,
+    Json(payload): Json<ProductionLinePayload>,
+) -> Result<Json<FactoryResponse>> {
+    let mut engine = state.engine.write().await;
+    let response = replace_production_line(&mut engine, factory_id, line_id, &payload)?;
+    engine.commit();
+
+    Ok(Json(response))
+}
+
+/// PATCH /api/factories/:id/production-lines/:line_id
+///
+/// Merges the given fields into the line and validates it like PUT; lists
+/// such as `machine_groups` are replaced as a whole
+pub async fn patch_production_line(
+    State(state): State<AppState>,
+    Path((factory_id, line_id)): Path<(FactoryId, ProductionLineId)>,
+    Json(patch): Json<Value>,
+) -> Result<Json<FactoryResponse>> {
+    let mut engine = state.engine.write().await;
+
+    let current = engine
+        .get_factory(factory_id)
+        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", factory_id)))?
+        .production_lines
+        .get(&line_id)
+        .map(production_line_payload)
+        .ok_or_else(|| {
+            AppError::NotFound(format!("Production line with id {} not found", line_id))
+        })?;
+    let payload = patched_payload(current, patch)?;
+
+    let response = replace_production_line(&mut engine, factory_id, line_id, &payload)?;
+    engine.commit();
+
+    Ok(Json(response))
+}
+
+/// PUT payload describing an existing production line
+fn production_line_payload(line: &ProductionLine) -> Value {
+    let sub_line = |line: &ProductionLineRecipe| {
+        json!({
+            "name": line.name,
+            "description": line.description,
+            "recipe": line.recipe.name(),
+            "machine_groups": line.machine_groups,
+        })
+    };
+    match line {
+        ProductionLine::ProductionLineRecipe(recipe_line) => {
+            let mut payload = sub_line(recipe_line);
+            payload["type"] = json!("recipe");
+            payload
+        }
+        ProductionLine::ProductionLineBlueprint(blueprint) => json!({
+            "name": blueprint.name,
+            "description": blueprint.description,
+            "type": "blueprint",
+            "production_lines": blueprint.production_lines.iter().map(sub_line).collect::<Vec<_>>(),
+        }),
+    }
+}
+
+/// Replace a production line, keeping its enabled flag
+fn replace_production_line(
+    engine: &mut SatisflowEngine,
+    factory_id: FactoryId,
+    line_id: ProductionLineId,
+    payload: &ProductionLinePayload,
+) -> Result<FactoryResponse> {
+    let mut production_line = build_production_line_from_payload(engine, payload, Some(line_id))?;
+
+    {
+        let factory = engine.get_factory_mut(factory_id).ok_or_else(|| {
+            AppError::NotFound(format!("Factory with id {} not found", factory_id))
+        })?;
+
+        // The enabled flag is only changed through its own endpoint
+        if let Some(previous) = factory.production_lines.get(&line_id) {
+            production_line.set_enabled(previous.is_enabled());
+        }
+        if factory.replace_production_line(production_line).is_none() {
+            return Err(AppError::NotFound(format!(
+                "Production line with id {} not found",
+                line_id
+            )));
+        }
+    }
+
+    let factory = engine
+        .get_factory(factory_id)
+        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", factory_id)))?;
+
+    Ok(build_factory_response(factory, engine.get_all_logistics()))
+}
+
+/// POST /api/factories/:id/production-lines/:line_id/optimize
+///
+/// Suggests the per-machine clock speed and power shards needed for a recipe
+/// line to reach a target output rate with the given number of machines
+pub async fn optimize_production_line(
+    State(state): State<AppState>,
+    Path((factory_id, line_id)): Path<(FactoryId, ProductionLineId)>,
+    Json(request): Json<OptimizeClockRequest>,
+) -> Result<Json<ClockOptimization>> {
+    let engine = state.engine.snapshot();
+
+    let factory = engine
+        .get_factory(factory_id)
+        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", factory_id)))?;
+
+    let line = match factory.production_lines.get(&line_id) {
+        Some(ProductionLine::ProductionLineRecipe(line)) => line,
+        Some(ProductionLine::ProductionLineBlueprint(_)) => {
+            return Err(AppError::BadRequest(
+                "Clock optimization is only available for recipe production lines".to_string(),
+            ))
+        }
+        None => {
+            return Err(AppError::NotFound(format!(
+                "Production line with id {} not found",
+                line_id
+            )))
+        }
+    };
+
+    let optimization = line
+        .optimize_clock_for(request.target_rate, request.machine_count)
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+    Ok(Json(optimization))
+}
+
+/// PATCH /api/factories/:id/production-lines/:line_id/enabled
+///
+/// Disabled lines stay in the factory but are left out of its item balance and power usage
+pub async fn set_production_line_enabled(
+    State(state): State<AppState>,
+    Path((factory_id, line_id)): Path<(FactoryId, ProductionLineId)>,
+    Json(request): Json<SetEnabledRequest>,
+) -> Result<Json<FactoryResponse>> {
+    let mut engine = state.engine.write().await;
+
+    {
+        let factory = engine.get_factory_mut(factory_id).ok_or_else(|| {
+            AppError::NotFound(format!("Factory with id {} not found", factory_id))
+        })?;
+
+        if !factory.set_production_line_enabled(line_id, request.enabled) {
+            return Err(AppError::NotFound(format!(
+                "Production line with id {} not found",
+                line_id
+            )));
+        }
+    }
+
+    let factory = engine
+        .get_factory(factory_id)
+        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", factory_id)))?;
+
+    let response = build_factory_response(factory, engine.get_all_logistics());
+
+    engine.commit();
+
+    Ok(Json(response))
+}
+
+/// `404 Not Found` unless both factories exist and the line is in the first
+fn check_line_transfer(
+    engine: &SatisflowEngine,
+    factory_id: FactoryId,
+    line_id: ProductionLineId,
+    destination_id: FactoryId,
+) -> Result<()> {
+    for id in [factory_id, destination_id] {
+        if engine.get_factory(id).is_none() {
+            return Err(AppError::NotFound(format!(
+                "Factory with id {} not found",
+                id
+            )));
+        }
+    }
+    if !engine
+        .get_factory(factory_id)
+        .is_some_and(|factory| factory.production_lines.contains_key(&line_id))
+    {
+        return Err(AppError::NotFound(format!(
+            "Production line with id {} not found",
+            line_id
+        )));
+    }
+    Ok(())
+}
+
+/// POST /api/factories/:id/production-lines/:line_id/copy-to/:destination_id
+///
+/// Copies the line, with new IDs, into the destination factory
+///
+/// # Returns
+///
+/// - `201 Created` with the destination factory
+/// - `404 Not Found` if a factory or the line doesn't exist
+pub async fn copy_production_line(
+    State(state): State<AppState>,
+    Path((factory_id, line_id, destination_id)): Path<(FactoryId, ProductionLineId, FactoryId)>,
+) -> Result<(StatusCode, Json<FactoryResponse>)> {
+    let mut engine = state.engine.write().await;
+
+    check_line_transfer(&engine, factory_id, line_id, destination_id)?;
+    engine
+        .copy_production_line(factory_id, line_id, destination_id)
+        .map_err(|e| AppError::BadRequest(format!("Cannot copy production line: {}", e)))?;
+
+    let destination = engine.get_factory(destination_id).ok_or_else(|| {
+        AppError::NotFound(format!("Factory with id {} not found", destination_id))
+    })?;
+    let response = build_factory_response(destination, engine.get_all_logistics());
+
+    engine.commit();
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// POST /api/factories/:id/production-lines/:line_id/move-to/:destination_id
+///
+/// Moves the line, keeping its ID, to the destination factory
+///
+/// # Returns
+///
+/// - `200 OK` with the destination factory
+/// - `400 Bad Request` if the line is already in the destination
+/// - `404 Not Found` if a factory or the line doesn't exist
+pub async fn move_production_line(
+    State(state): State<AppState>,
+    Path((factory_id, line_id, destination_id)): Path<(FactoryId, ProductionLineId, FactoryId)>,
+) -> Result<Json<FactoryResponse>> {
+    let mut engine = state.engine.write().await;
+
+    check_line_transfer(&engine, factory_id, line_id, destination_id)?;
+    engine
+        .move_production_line(factory_id, line_id, destination_id)
+        .map_err(|e| AppError::BadRequest(format!("Cannot move production line: {}", e)))?;
+
+    let destination = engine.get_factory(destination_id).ok_or_else(|| {
+        AppError::NotFound(format!("Factory with id {} not found", destination_id))
+    })?;
+    let response = build_factory_response(destination, engine.get_all_logistics());
+
+    engine.commit();
+
+    Ok(Json(response))
+}
+
+pub async fn delete_production_line(
+    State(state): State<AppState>,
+    Path((factory_id, line_id)): Path<(FactoryId, ProductionLineId)>,
+) -> Result<Json<FactoryResponse>> {
+    let mut engine = state.engine.write().await;
+
+    {
+        let factory = engine.get_factory_mut(factory_id).ok_or_else(|| {
+            AppError::NotFound(format!("Factory with id {} not found", factory_id))
+        })?;
+
+        if factory.remove_production_line(line_id).is_none() {
+            return Err(AppError::NotFound(format!(
+                "Production line with id {} not found",
+                line_id
+            )));
+        }
+    }
+
+    let factory = engine
+        .get_factory(factory_id)
+        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", factory_id)))?;
+
+    let response = build_factory_response(factory, engine.get_all_logistics());
+
+    engine.commit();
+
+    Ok(Json(response))
+}
+
+pub async fn create_raw_input(
+    State(state): State<AppState>,
+    Path(factory_id): Path<FactoryId>,
+    Json(payload): Json<RawInputPayload>,
+) -> Result<(StatusCode, Json<FactoryResponse>)> {
+    let mut engine = state.engine.write().await;
+
+    let raw_input = build_raw_input_from_payload(&payload, None)?;
+
+    {
+        let factory = engine.get_factory_mut(factory_id).ok_or_else(|| {
+            AppError::NotFound(format!("Factory with id {} not found", factory_id))
+        })?;
+        factory
+            .add_raw_input(raw_input)
+            .map_err(AppError::ValidationError)?;
+    }
+
+    let factory = engine
+        .get_factory(factory_id)
+        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", factory_id)))?;
+
+    let response = build_factory_response(factory, engine.get_all_logistics());
+
+    engine.commit();
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+pub async fn update_raw_input(
+    State(state): State<AppState>,
+    Path((factory_id, raw_input_id)): Path<(FactoryId, RawInputId)>,
+    Json(payload): Json<RawInputPayload>,
+) -> Result<Json<FactoryResponse>> {
+    let mut engine = state.engine.write().await;
+
+    let mut raw_input = build_raw_input_from_payload(&payload, Some(raw_input_id))?;
+
+    {
+        let factory = engine.get_factory_mut(factory_id).ok_or_else(|| {
+            AppError::NotFound(format!("Factory with id {} not found", factory_id))
+        })?;
+
+        // The enabled flag is only changed through its own endpoint
+        if let Some(previous) = factory.get_raw_input(raw_input_id) {
+            raw_input.enabled = previous.enabled;
+        }
+        if factory.raw_inputs.insert(raw_input_id, raw_input).is_none() {
+            return Err(AppError::NotFound(format!(
+                "Raw input with id {} not found",
+                raw_input_id
+            )));
+        }
+    }
+
+    let factory = engine
+        .get_factory(factory_id)
+        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", factory_id)))?;
+
+    let response = build_factory_response(factory, engine.get_all_logistics());
+
+    engine.commit();
+
+    Ok(Json(response))
+}
+
+/// PATCH /api/factories/:id/raw-inputs/:raw_input_id/enabled
+///
+/// Disabled raw inputs stay in the factory but extract nothing and draw no power
+pub async fn set_raw_input_enabled(
+    State(state): State<AppState>,
+    Path((factory_id, raw_input_id)): Path<(FactoryId, RawInputId)>,
+    Json(request): Json<SetEnabledRequest>,
+) -> Result<Json<FactoryResponse>> {
+    let mut engine = state.engine.write().await;
+
+    {
+        let factory = engine.get_factory_mut(factory_id).ok_or_else(|| {
+            AppError::NotFound(format!("Factory with id {} not found", factory_id))
+        })?;
+
+        let raw_input = factory.get_raw_input_mut(raw_input_id).ok_or_else(|| {
+            AppError::NotFound(format!("Raw input with id {} not found", raw_input_id))
+        })?;
+        raw_input.enabled = request.enabled;
+    }
+
+    let factory = engine
+        .get_factory(factory_id)
+        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", factory_id)))?;
+
+    let response = build_factory_response(factory, engine.get_all_logistics());
+
+    engine.commit();
+
+    Ok(Json(response))
+}
+
+pub async fn delete_raw_input(
+    State(state): State<AppState>,
+    Path((factory_id, raw_input_id)): Path<(FactoryId, RawInputId)>,
+) -> Result<Json<FactoryResponse>> {
+    let mut engine = state.engine.write().await;
+
+    {
+        let factory = engine.get_factory_mut(factory_id).ok_or_else(|| {
+            AppError::NotFound(format!("Factory with id {} not found", factory_id))
+        })?;
+
+        if factory.remove_raw_input(raw_input_id).is_none() {
+            return Err(AppError::NotFound(format!(
+                "Raw input with id {} not found",
+                raw_input_id
+            )));
+        }
+    }
+
+    let factory = engine
+        .get_factory(factory_id)
+        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", factory_id)))?;
+
+    let response = build_factory_response(factory, engine.get_all_logistics());
+
+    engine.commit();
+
+    Ok(Json(response))
+}
+
+pub async fn create_internal_transfer(
+    State(state): State<AppState>,
+    Path(factory_id): Path<FactoryId>,
+    Json(payload): Json<InternalTransferPayload>,
+) -> Result<(StatusCode, Json<FactoryResponse>)> {
+    let mut engine = state.engine.write().await;
+
+    let transfer = build_internal_transfer_from_payload(&payload, None)?;
+
+    {
+        let factory = engine.get_factory_mut(factory_id).ok_or_else(|| {
+            AppError::NotFound(format!("Factory with id {} not found", factory_id))
+        })?;
+        factory
+            .add_internal_transfer(transfer)
+            .map_err(AppError::ValidationError)?;
+    }
+
+    let factory = engine
+        .get_factory(factory_id)
+        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", factory_id)))?;
+
+    let response = build_factory_response(factory, engine.get_all_logistics());
+
+    engine.commit();
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+pub async fn update_internal_transfer(
+    State(state): State<AppState>,
+    Path((factory_id, transfer_id)): Path<(FactoryId, InternalTransferId)>,
+    Json(payload): Json<InternalTransferPayload>,
+) -> Result<Json<FactoryResponse>> {
+    let mut engine = state.engine.write().await;
+
+    let transfer = build_internal_transfer_from_payload(&payload, Some(transfer_id))?;
+
+    {
+        let factory = engine.get_factory_mut(factory_id).ok_or_else(|| {
+            AppError::NotFound(format!("Factory with id {} not found", factory_id))
+        })?;
+
+        if factory.get_internal_transfer(transfer_id).is_none() {
+            return Err(AppError::NotFound(format!(
+                "Internal transfer with id {} not found",
+                transfer_id
+            )));
+        }
+        factory
+            .add_internal_transfer(transfer)
+            .map_err(AppError::ValidationError)?;
+    }
+
+    let factory = engine
+        .get_factory(factory_id)
+        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", factory_id)))?;
+
+    let response = build_factory_response(factory, engine.get_all_logistics());
+
+    engine.commit();
+
+    Ok(Json(response))
+}
+
+pub async fn delete_internal_transfer(
+    State(state): State<AppState>,
+    Path((factory_id, transfer_id)): Path<(FactoryId, InternalTransferId)>,
+) -> Result<Json<FactoryResponse>> {
+    let mut engine = state.engine.write().await;
+
+    {
+        let factory = engine.get_factory_mut(factory_id).ok_or_else(|| {
+            AppError::NotFound(format!("Factory with id {} not found", factory_id))
+        })?;
+
+        if factory.remove_internal_transfer(transfer_id).is_none() {
+            return Err(AppError::NotFound(format!(
+                "Internal transfer with id {} not found",
+                transfer_id
+            )));
+        }
+    }
+
+    let factory = engine
+        .get_factory(factory_id)
+        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", factory_id)))?;
+
+    let response = build_factory_response(factory, engine.get_all_logistics());
+
+    engine.commit();
+
+    Ok(Json(response))
+}
+
+pub async fn list_tasks(
+    State(state): State<AppState>,
+    Path(factory_id): Path<FactoryId>,
+) -> Result<Json<Vec<FactoryTask>>> {
+    let engine = state.engine.snapshot();
+
+    let factory = engine
+        .get_factory(factory_id)
+        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", factory_id)))?;
+
+    Ok(Json(factory.tasks.clone()))
+}
+
+pub async fn create_task(
+    State(state): State<AppState>,
+    Path(factory_id): Path<FactoryId>,
+    Json(payload): Json<FactoryTaskPayload>,
+) -> Result<(StatusCode, Json<FactoryResponse>)> {
+    let mut engine = state.engine.write().await;
+
+    let task = build_task_from_payload(&payload, None)?;
+
+    {
+        let factory = engine.get_factory_mut(factory_id).ok_or_else(|| {
+            AppError::NotFound(format!("Factory with id {} not found", factory_id))
+        })?;
+        factory
+            .add_task(task)
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+    }
+
+    let factory = engine
+        .get_factory(factory_id)
+        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", factory_id)))?;
+
+    let response = build_factory_response(factory, engine.get_all_logistics());
+
+    engine.commit();
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+pub async fn update_task(
+    State(state): State<AppState>,
+    Path((factory_id, task_id)): Path<(FactoryId, FactoryTaskId)>,
+    Json(payload): Json<FactoryTaskPayload>,
+) -> Result<Json<FactoryResponse>> {
+    let mut engine = state.engine.write().await;
+
+    let task = build_task_from_payload(&payload, Some(task_id))?;
+
+    {
+        let factory = engine.get_factory_mut(factory_id).ok_or_else(|| {
+            AppError::NotFound(format!("Factory with id {} not found", factory_id))
+        })?;
+
+        if factory.get_task(task_id).is_none() {
+            return Err(AppError::NotFound(format!(
+                "Task with id {} not found",
+                task_id
+            )));
+        }
+        factory
+            .add_task(task)
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+    }
+
+    let factory = engine
+        .get_factory(factory_id)
+        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", factory_id)))?;
+
+    let response = build_factory_response(factory, engine.get_all_logistics());
+
+    engine.commit();
+
+    Ok(Json(response))
+}
+
+pub async fn delete_task(
+    State(state): State<AppState>,
+    Path((factory_id, task_id)): Path<(FactoryId, FactoryTaskId)>,
+) -> Result<Json<FactoryResponse>> {
+    let mut engine = state.engine.write().await;
+
+    {
+        let factory = engine.get_factory_mut(factory_id).ok_or_else(|| {
+            AppError::NotFound(format!("Factory with id {} not found", factory_id))
+        })?;
+
+        if factory.remove_task(task_id).is_none() {
+            return Err(AppError::NotFound(format!(
+                "Task with id {} not found",
+                task_id
+            )));
+        }
+    }
+
+    let factory = engine
+        .get_factory(factory_id)
+        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", factory_id)))?;
+
+    let response = build_factory_response(factory, engine.get_all_logistics());
+
+    engine.commit();
+
+    Ok(Json(response))
+}
+
+pub async fn create_storage_buffer(
+    State(state): State<AppState>,
+    Path(factory_id): Path<FactoryId>,
+    Json(payload): Json<StorageBufferPayload>,
+) -> Result<(StatusCode, Json<FactoryResponse>)> {
+    let mut engine = state.engine.write().await;
+
+    let buffer = build_storage_buffer_from_payload(&payload, None)?;
+
+    {
+        let factory = engine.get_factory_mut(factory_id).ok_or_else(|| {
+            AppError::NotFound(format!("Factory with id {} not found", factory_id))
+        })?;
+        factory
+            .add_storage_buffer(buffer)
+            .map_err(AppError::ValidationError)?;
+    }
+
+    let factory = engine
+        .get_factory(factory_id)
+        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", factory_id)))?;
+
+    let response = build_factory_response(factory, engine.get_all_logistics());
+
+    engine.commit();
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+pub async fn update_storage_buffer(
+    State(state): State<AppState>,
+    Path((factory_id, buffer_id)): Path<(FactoryId, StorageBufferId)>,
+    Json(payload): Json<StorageBufferPayload>,
+) -> Result<Json<FactoryResponse>> {
+    let mut engine = state.engine.write().await;
+
+    let buffer = build_storage_buffer_from_payload(&payload, Some(buffer_id))?;
+
+    {
+        let factory = engine.get_factory_mut(factory_id).ok_or_else(|| {
+            AppError::NotFound(format!("Factory with id {} not found", factory_id))
+        })?;
+
+        if factory.get_storage_buffer(buffer_id).is_none() {
+            return Err(AppError::NotFound(format!(
+                "Storage buffer with id {} not found",
+                buffer_id
+            )));
+        }
+        factory
+            .add_storage_buffer(buffer)
+            .map_err(AppError::ValidationError)?;
+    }
+
+    let factory = engine
+        .get_factory(factory_id)
+        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", factory_id)))?;
+
+    let response = build_factory_response(factory, engine.get_all_logistics());
+
+    engine.commit();
+
+    Ok(Json(response))
+}
+
+pub async fn delete_storage_buffer(
+    State(state): State<AppState>,
+    Path((factory_id, buffer_id)): Path<(FactoryId, StorageBufferId)>,
+) -> Result<Json<FactoryResponse>> {
+    let mut engine = state.engine.write().await;
+
+    {
+        let factory = engine.get_factory_mut(factory_id).ok_or_else(|| {
+            AppError::NotFound(format!("Factory with id {} not found", factory_id))
+        })?;
+
+        if factory.remove_storage_buffer(buffer_id).is_none() {
+            return Err(AppError::NotFound(format!(
+                "Storage buffer with id {} not found",
+                buffer_id
+            )));
+        }
+    }
+
+    let factory = engine
+        .get_factory(factory_id)
+        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", factory_id)))?;
+
+    let response = build_factory_response(factory, engine.get_all_logistics());
+
+    engine.commit();
 
     Ok(Json(response))
 }
 
 pub async fn create_power_generator(
     State(state): State<AppState>,
-    Path(factory_id): Path<Uuid>,
+    Path(factory_id): Path<FactoryId>,
     Json(payload): Json<PowerGeneratorPayload>,
 ) -> Result<(StatusCode, Json<FactoryResponse>)> {
     let mut engine = state.engine.write().await;
 
     let generator = build_power_generator_from_payload(&payload, None)?;
+    if engine.get_factory(factory_id).is_none() {
+        return Err(AppError::NotFound(format!(
+            "Factory with id {} not found",
+            factory_id
+        )));
+    }
+    let warnings = engine
+        .check_power_generator(factory_id, &generator)
+        .into_result()
+        .map_err(|report| AppError::ValidationError(report.to_string()))?;
 
     {
         let factory = engine.get_factory_mut(factory_id).ok_or_else(|| {
@@ -831,25 +2068,79 @@ pub async fn create_power_generator(
         .get_factory(factory_id)
         .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", factory_id)))?;
 
-    let response = build_factory_response(factory, engine.get_all_logistics());
+    let mut response = build_factory_response(factory, engine.get_all_logistics());
+    response.warnings.extend(warnings);
+
+    engine.commit();
 
     Ok((StatusCode::CREATED, Json(response)))
 }
 
 pub async fn update_power_generator(
     State(state): State<AppState>,
-    Path((factory_id, generator_id)): Path<(Uuid, Uuid)>,
+    Path((factory_id, generator_id)): Path<(FactoryId, PowerGeneratorId)>,
     Json(payload): Json<PowerGeneratorPayload>,
 ) -> Result<Json<FactoryResponse>> {
     let mut engine = state.engine.write().await;
+    let response = replace_power_generator(&mut engine, factory_id, generator_id, &payload)?;
+    engine.commit();
+
+    Ok(Json(response))
+}
+
+/// PATCH /api/factories/:id/power-generators/:generator_id
+///
+/// Merges the given fields into the generator and validates it like PUT;
+/// `groups` is replaced as a whole
+pub async fn patch_power_generator(
+    State(state): State<AppState>,
+    Path((factory_id, generator_id)): Path<(FactoryId, PowerGeneratorId)>,
+    Json(patch): Json<Value>,
+) -> Result<Json<FactoryResponse>> {
+    let mut engine = state.engine.write().await;
+
+    let generator = engine
+        .get_factory(factory_id)
+        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", factory_id)))?
+        .get_power_generator(generator_id)
+        .ok_or_else(|| {
+            AppError::NotFound(format!(
+                "Power generator with id {} not found",
+                generator_id
+            ))
+        })?;
+    let current = json!({
+        "generator_type": generator.generator_type,
+        "fuel_type": generator.fuel_type,
+        "purity": generator.purity,
+        "groups": generator.groups,
+    });
+    let payload = patched_payload(current, patch)?;
+
+    let response = replace_power_generator(&mut engine, factory_id, generator_id, &payload)?;
+    engine.commit();
+
+    Ok(Json(response))
+}
 
-    let generator = build_power_generator_from_payload(&payload, Some(generator_id))?;
+/// Replace a power generator, keeping its enabled flag
+fn replace_power_generator(
+    engine: &mut SatisflowEngine,
+    factory_id: FactoryId,
+    generator_id: PowerGeneratorId,
+    payload: &PowerGeneratorPayload,
+) -> Result<FactoryResponse> {
+    let mut generator = build_power_generator_from_payload(payload, Some(generator_id))?;
 
     {
         let factory = engine.get_factory_mut(factory_id).ok_or_else(|| {
             AppError::NotFound(format!("Factory with id {} not found", factory_id))
         })?;
 
+        // The enabled flag is only changed through its own endpoint
+        if let Some(previous) = factory.get_power_generator(generator_id) {
+            generator.enabled = previous.enabled;
+        }
         if factory
             .power_generators
             .insert(generator_id, generator)
@@ -862,18 +2153,53 @@ pub async fn update_power_generator(
         }
     }
 
+    let factory = engine
+        .get_factory(factory_id)
+        .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", factory_id)))?;
+
+    Ok(build_factory_response(factory, engine.get_all_logistics()))
+}
+
+/// PATCH /api/factories/:id/power-generators/:generator_id/enabled
+///
+/// Disabled generators stay in the factory but produce no power and burn no fuel
+pub async fn set_power_generator_enabled(
+    State(state): State<AppState>,
+    Path((factory_id, generator_id)): Path<(FactoryId, PowerGeneratorId)>,
+    Json(request): Json<SetEnabledRequest>,
+) -> Result<Json<FactoryResponse>> {
+    let mut engine = state.engine.write().await;
+
+    {
+        let factory = engine.get_factory_mut(factory_id).ok_or_else(|| {
+            AppError::NotFound(format!("Factory with id {} not found", factory_id))
+        })?;
+
+        let generator = factory
+            .get_power_generator_mut(generator_id)
+            .ok_or_else(|| {
+                AppError::NotFound(format!(
+                    "Power generator with id {} not found",
+                    generator_id
+                ))
+            })?;
+        generator.enabled = request.enabled;
+    }
+
     let factory = engine
         .get_factory(factory_id)
         .ok_or_else(|| AppError::NotFound(format!("Factory with id {} not found", factory_id)))?;
 
     let response = build_factory_response(factory, engine.get_all_logistics());
 
+    engine.commit();
+
     Ok(Json(response))
 }
 
 pub async fn delete_power_generator(
     State(state): State<AppState>,
-    Path((factory_id, generator_id)): Path<(Uuid, Uuid)>,
+    Path((factory_id, generator_id)): Path<(FactoryId, PowerGeneratorId)>,
 ) -> Result<Json<FactoryResponse>> {
     let mut engine = state.engine.write().await;
 
@@ -896,16 +2222,18 @@ pub async fn delete_power_generator(
 
     let response = build_factory_response(factory, engine.get_all_logistics());
 
+    engine.commit();
+
     Ok(Json(response))
 }
 
 // Preview handlers for real-time form calculations
 pub async fn preview_production_line(
     State(state): State<AppState>,
-    Path(factory_id): Path<Uuid>,
+    Path(factory_id): Path<FactoryId>,
     Json(request): Json<ProductionLinePreviewRequest>,
 ) -> Result<Json<ProductionLinePreviewResponse>> {
-    let engine = state.engine.read().await;
+    let engine = state.engine.snapshot();
 
     // Validate factory exists
     engine
@@ -914,6 +2242,7 @@ pub async fn preview_production_line(
 
     // Build production line from request (same logic as create/update)
     let production_line = build_production_line_from_payload(
+        &engine,
         &ProductionLinePayload {
             name: request.name,
             description: request.description,
@@ -938,12 +2267,22 @@ pub async fn preview_production_line(
         .map(|(item, quantity)| ItemQuantity { item, quantity })
         .collect();
 
+    let byproduct_rate = production_line
+        .byproduct_rate()
+        .into_iter()
+        .map(|(item, quantity)| ItemQuantity { item, quantity })
+        .collect();
+
     let response = ProductionLinePreviewResponse {
-        total_power_consumption: production_line.total_power_consumption(),
+        total_power_consumption: production_line
+            .total_power_consumption_with(engine.game_balance()),
+        consumption_range: production_line.consumption_range_with(engine.game_balance()),
         total_machines: production_line.total_machines(),
         total_somersloop: production_line.total_somersloop(),
+        output_amplification: production_line.output_amplification(),
         input_rate,
         output_rate,
+        byproduct_rate,
     };
 
     Ok(Json(response))
@@ -951,10 +2290,10 @@ pub async fn preview_production_line(
 
 pub async fn preview_power_generator(
     State(state): State<AppState>,
-    Path(factory_id): Path<Uuid>,
+    Path(factory_id): Path<FactoryId>,
     Json(request): Json<PowerGeneratorPreviewRequest>,
 ) -> Result<Json<PowerGeneratorPreviewResponse>> {
-    let engine = state.engine.read().await;
+    let engine = state.engine.snapshot();
 
     // Validate factory exists
     engine
@@ -966,6 +2305,7 @@ pub async fn preview_power_generator(
         &PowerGeneratorPayload {
             generator_type: request.generator_type,
             fuel_type: request.fuel_type,
+            purity: request.purity,
             groups: request.groups,
         },
         None,
@@ -973,8 +2313,10 @@ pub async fn preview_power_generator(
 
     // Calculate preview data
     let response = PowerGeneratorPreviewResponse {
-        total_power_generation: generator.total_power_generation(),
+        total_power_generation: generator.total_power_generation_with(engine.game_balance()),
+        generation_range: generator.power_generation_range_with(engine.game_balance()),
         total_fuel_consumption: generator.total_fuel_consumption(),
+        total_water_consumption: generator.total_water_consumption(),
         waste_production_rate: generator.waste_production_rate(),
         waste_product: generator.waste_product(),
     };
@@ -984,10 +2326,10 @@ pub async fn preview_power_generator(
 
 pub async fn preview_raw_input(
     State(state): State<AppState>,
-    Path(factory_id): Path<Uuid>,
+    Path(factory_id): Path<FactoryId>,
     Json(request): Json<RawInputPreviewRequest>,
 ) -> Result<Json<RawInputPreviewResponse>> {
-    let engine = state.engine.read().await;
+    let engine = state.engine.snapshot();
 
     // Validate factory exists
     engine
@@ -1005,14 +2347,15 @@ pub async fn preview_raw_input(
             quantity_per_min: request.quantity_per_min,
             pressurizer: request.pressurizer,
             extractors: request.extractors,
+            claims_node: false,
         },
         None,
     )?;
 
     // Calculate preview data
     let response = RawInputPreviewResponse {
-        power_consumption: raw_input.power_consumption(),
-        quantity_per_min: raw_input.quantity_per_min,
+        power_consumption: raw_input.power_consumption_with(engine.game_balance()),
+        quantity_per_min: raw_input.quantity_per_min_with(engine.game_balance()),
     };
 
     Ok(Json(response))
@@ -1022,24 +2365,83 @@ pub async fn preview_raw_input(
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/", get(get_factories).post(create_factory))
+        .route("/merge", post(merge_factories))
+        .route("/merge/preview", post(preview_merge_factories))
         .route(
             "/:id",
-            get(get_factory).put(update_factory).delete(delete_factory),
+            get(get_factory)
+                .put(update_factory)
+                .patch(update_factory)
+                .delete(delete_factory),
+        )
+        .route("/:id/delete-preview", get(preview_delete_factory))
+        .route("/:id/dependencies", get(get_factory_dependencies))
+        .route("/:id/balance-suggestions", get(get_balance_suggestions))
+        .route("/:id/clone", post(clone_factory))
+        .route(
+            "/:id/save-as-template",
+            post(factory_templates::save_factory_as_template),
+        )
+        .route("/:id/split", post(split_factory))
+        .route("/:id/power-grid", put(assign_factory_power_grid))
+        .route(
+            "/:id/power-storage",
+            put(set_factory_power_storage).delete(delete_factory_power_storage),
         )
         .route("/:id/production-lines", post(create_production_line))
         .route(
             "/:id/production-lines/:line_id",
-            put(update_production_line).delete(delete_production_line),
+            put(update_production_line)
+                .patch(patch_production_line)
+                .delete(delete_production_line),
+        )
+        .route(
+            "/:id/production-lines/:line_id/optimize",
+            post(optimize_production_line),
+        )
+        .route(
+            "/:id/production-lines/:line_id/enabled",
+            patch(set_production_line_enabled),
+        )
+        .route(
+            "/:id/production-lines/:line_id/copy-to/:destination_id",
+            post(copy_production_line),
+        )
+        .route(
+            "/:id/production-lines/:line_id/move-to/:destination_id",
+            post(move_production_line),
         )
         .route("/:id/raw-inputs", post(create_raw_input))
         .route(
             "/:id/raw-inputs/:raw_input_id",
             put(update_raw_input).delete(delete_raw_input),
         )
+        .route(
+            "/:id/raw-inputs/:raw_input_id/enabled",
+            patch(set_raw_input_enabled),
+        )
+        .route("/:id/internal-transfers", post(create_internal_transfer))
+        .route(
+            "/:id/internal-transfers/:transfer_id",
+            put(update_internal_transfer).delete(delete_internal_transfer),
+        )
+        .route("/:id/tasks", get(list_tasks).post(create_task))
+        .route("/:id/tasks/:task_id", put(update_task).delete(delete_task))
+        .route("/:id/storage-buffers", post(create_storage_buffer))
+        .route(
+            "/:id/storage-buffers/:buffer_id",
+            put(update_storage_buffer).delete(delete_storage_buffer),
+        )
         .route("/:id/power-generators", post(create_power_generator))
         .route(
             "/:id/power-generators/:generator_id",
-            put(update_power_generator).delete(delete_power_generator),
+            put(update_power_generator)
+                .patch(patch_power_generator)
+                .delete(delete_power_generator),
+        )
+        .route(
+            "/:id/power-generators/:generator_id/enabled",
+            patch(set_power_generator_enabled),
         )
         // Preview endpoints for real-time form calculations
         .route(
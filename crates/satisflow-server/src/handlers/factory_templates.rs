@@ -0,0 +1,193 @@
+// crates/satisflow-server/src/handlers/factory_templates.rs
+//! Whole factories saved for reuse
+//!
+//! `POST /api/factories/{id}/save-as-template` captures a factory; the routes
+//! here list, inspect, delete and instantiate the templates.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{AppError, Result},
+    handlers::factory::{build_factory_response, FactoryResponse},
+    state::AppState,
+};
+use satisflow_engine::models::{factory_template::FactoryTemplate, FactoryId, FactoryTemplateId};
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SaveAsTemplateRequest {
+    /// Defaults to the factory's name
+    pub name: Option<String>,
+    /// Defaults to the factory's description
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InstantiateTemplateRequest {
+    /// Name of the new factory
+    pub name: String,
+}
+
+/// A factory template without the factory it holds
+#[derive(Debug, Serialize)]
+pub struct FactoryTemplateSummary {
+    pub id: FactoryTemplateId,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub production_line_count: usize,
+    pub raw_input_count: usize,
+    pub power_generator_count: usize,
+}
+
+impl From<&FactoryTemplate> for FactoryTemplateSummary {
+    fn from(template: &FactoryTemplate) -> Self {
+        Self {
+            id: template.id,
+            name: template.name.clone(),
+            description: template.description.clone(),
+            created_at: template.created_at,
+            production_line_count: template.factory.production_lines.len(),
+            raw_input_count: template.factory.raw_inputs.len(),
+            power_generator_count: template.factory.power_generators.len(),
+        }
+    }
+}
+
+fn not_found(id: FactoryTemplateId) -> AppError {
+    AppError::NotFound(format!("Factory template with id {} not found", id))
+}
+
+/// POST /api/factories/{id}/save-as-template
+///
+/// Saves a copy of the factory as a template
+///
+/// # Returns
+///
+/// - `201 Created` with the template
+/// - `400 Bad Request` if the name is empty
+/// - `404 Not Found` if the factory doesn't exist
+pub async fn save_factory_as_template(
+    State(state): State<AppState>,
+    Path(id): Path<FactoryId>,
+    Json(request): Json<SaveAsTemplateRequest>,
+) -> Result<(StatusCode, Json<FactoryTemplateSummary>)> {
+    let mut engine = state.engine.write().await;
+
+    if engine.get_factory(id).is_none() {
+        return Err(AppError::NotFound(format!(
+            "Factory with id {} not found",
+            id
+        )));
+    }
+
+    let template_id = engine
+        .save_factory_as_template(id, request.name.as_deref(), request.description)
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+    let engine = engine.commit();
+    let template = engine
+        .get_factory_template(template_id)
+        .ok_or_else(|| not_found(template_id))?;
+
+    Ok((StatusCode::CREATED, Json(template.into())))
+}
+
+/// GET /api/factory-templates
+///
+/// Lists the factory templates, sorted by name
+pub async fn list_templates(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<FactoryTemplateSummary>>> {
+    let engine = state.engine.snapshot();
+
+    let mut templates: Vec<FactoryTemplateSummary> = engine
+        .get_all_factory_templates()
+        .values()
+        .map(FactoryTemplateSummary::from)
+        .collect();
+    templates.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+
+    Ok(Json(templates))
+}
+
+/// GET /api/factory-templates/{id}
+///
+/// Gets a template with the factory it holds
+pub async fn get_template(
+    State(state): State<AppState>,
+    Path(id): Path<FactoryTemplateId>,
+) -> Result<Json<FactoryTemplate>> {
+    let engine = state.engine.snapshot();
+
+    let template = engine
+        .get_factory_template(id)
+        .ok_or_else(|| not_found(id))?;
+
+    Ok(Json(template.clone()))
+}
+
+/// DELETE /api/factory-templates/{id}
+///
+/// Deletes a template; factories made from it are kept
+pub async fn delete_template(
+    State(state): State<AppState>,
+    Path(id): Path<FactoryTemplateId>,
+) -> Result<StatusCode> {
+    let mut engine = state.engine.write().await;
+
+    engine
+        .delete_factory_template(id)
+        .map_err(|_| not_found(id))?;
+
+    engine.commit();
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/factory-templates/{id}/instantiate
+///
+/// Creates a factory from the template, with new IDs and without a power grid
+/// or map position
+///
+/// # Returns
+///
+/// - `201 Created` with the new factory
+/// - `400 Bad Request` if the name is empty
+/// - `404 Not Found` if the template doesn't exist
+pub async fn instantiate_template(
+    State(state): State<AppState>,
+    Path(id): Path<FactoryTemplateId>,
+    Json(request): Json<InstantiateTemplateRequest>,
+) -> Result<(StatusCode, Json<FactoryResponse>)> {
+    let mut engine = state.engine.write().await;
+
+    if engine.get_factory_template(id).is_none() {
+        return Err(not_found(id));
+    }
+
+    let factory_id = engine
+        .instantiate_factory_template(id, &request.name)
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+    let factory = engine.get_factory(factory_id).ok_or_else(|| {
+        AppError::InternalError(anyhow::anyhow!("Failed to retrieve the new factory"))
+    })?;
+
+    let response = build_factory_response(factory, engine.get_all_logistics());
+
+    engine.commit();
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_templates))
+        .route("/:id", get(get_template).delete(delete_template))
+        .route("/:id/instantiate", post(instantiate_template))
+}
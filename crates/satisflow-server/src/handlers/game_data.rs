@@ -1,11 +1,30 @@
 // crates/satisflow-server/src/handlers/game_data.rs
-use axum::{extract::State, routing::get, Json, Router};
-use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 
-use crate::{error::Result, state::AppState};
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Path, Query, Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, put},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{AppError, Result},
+    state::AppState,
+};
 use satisflow_engine::models::game_data::MachineType;
+use satisflow_engine::models::logistics::{ConveyorSpeed, ItemPerPin};
 use satisflow_engine::models::raw_input::ExtractorType;
-use satisflow_engine::models::{all_items, all_recipes, Item};
+use satisflow_engine::models::{
+    all_items, all_recipes, CustomRecipe, CustomRecipeId, GameBalance, GameDataSet, Item, Recipe,
+    RecipeChain, MAX_RECIPE_CHAIN_DEPTH,
+};
+use satisflow_engine::SatisflowEngine;
 
 #[derive(Serialize)]
 pub struct RecipeInfo {
@@ -15,54 +34,315 @@ pub struct RecipeInfo {
     pub outputs: Vec<ItemQuantity>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 pub struct ItemQuantity {
     pub item: Item,
-    pub quantity: f32,
+    pub quantity: f64,
 }
 
 #[derive(Serialize)]
 pub struct MachineInfo {
     pub name: MachineType,
-    pub base_power: f32,
+    pub base_power: f64,
     pub max_somersloop: u8,
 }
 
-pub async fn get_recipes(State(_state): State<AppState>) -> Result<Json<Vec<RecipeInfo>>> {
-    let recipes: Vec<RecipeInfo> = all_recipes()
-        .iter()
-        .map(|details| RecipeInfo {
-            name: details.name.to_string(),
-            machine: details.machine,
-            inputs: details
-                .inputs
-                .iter()
-                .map(|(item, qty)| ItemQuantity {
-                    item: *item,
-                    quantity: *qty,
-                })
-                .collect(),
-            outputs: details
-                .outputs
+/// Conveyor part carrying items along a bus
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ConveyorKind {
+    Belt,
+    Lift,
+}
+
+/// One tier of conveyor belt or lift
+#[derive(Serialize)]
+pub struct ConveyorInfo {
+    pub name: String,
+    pub kind: ConveyorKind,
+    pub tier: ConveyorSpeed,
+    pub items_per_min: f64,
+}
+
+/// Query parameters for recipe and machine lists
+#[derive(Debug, Default, Deserialize)]
+pub struct UnlockedQuery {
+    /// Only list what the recorded tier progress has unlocked; ignored when
+    /// no progress is recorded
+    #[serde(default)]
+    pub unlocked_only: bool,
+}
+
+/// Query parameters for recipe search; every given filter must match
+#[derive(Debug, Default, Deserialize)]
+pub struct RecipeSearchQuery {
+    /// Recipes producing this item
+    pub output: Option<Item>,
+    /// Recipes consuming this item
+    pub input: Option<Item>,
+    pub machine: Option<MachineType>,
+    #[serde(default)]
+    pub unlocked_only: bool,
+}
+
+/// Every recipe producing or consuming an item
+#[derive(Serialize)]
+pub struct ItemUses {
+    pub item: Item,
+    pub produced_by: Vec<RecipeInfo>,
+    pub consumed_by: Vec<RecipeInfo>,
+}
+
+impl From<&satisflow_engine::models::RecipeInfo> for RecipeInfo {
+    fn from(details: &satisflow_engine::models::RecipeInfo) -> Self {
+        let quantities = |pairs: &[(Item, f64)]| {
+            pairs
                 .iter()
                 .map(|(item, qty)| ItemQuantity {
                     item: *item,
                     quantity: *qty,
                 })
-                .collect(),
+                .collect()
+        };
+        Self {
+            name: details.name.to_string(),
+            machine: details.machine,
+            inputs: quantities(details.inputs),
+            outputs: quantities(details.outputs),
+        }
+    }
+}
+
+/// Query parameters for a game data set
+#[derive(Debug, Default, Deserialize)]
+pub struct GameDataQuery {
+    /// Game version of the set; the one the loaded plan uses when omitted
+    pub version: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct GameDataVersionRequest {
+    pub version: String,
+}
+
+#[derive(Deserialize)]
+pub struct FicsmasRequest {
+    pub enabled: bool,
+}
+
+/// One game data set and its recipes
+#[derive(Serialize)]
+pub struct GameDataResponse {
+    pub version: &'static str,
+    pub description: &'static str,
+    /// The loaded plan is made against this set
+    pub selected: bool,
+    /// Versions a plan can be made against
+    pub available: Vec<&'static str>,
+    /// The loaded plan includes the seasonal FICSMAS items and recipes
+    pub ficsmas: bool,
+    /// Recipes of the set; FICSMAS ones only when the plan includes them
+    pub recipes: Vec<RecipeInfo>,
+}
+
+fn game_data_response(set: &'static GameDataSet, engine: &SatisflowEngine) -> GameDataResponse {
+    GameDataResponse {
+        version: set.version,
+        description: set.description,
+        selected: std::ptr::eq(set, engine.game_data()),
+        available: GameDataSet::all().iter().map(|set| set.version).collect(),
+        ficsmas: engine.ficsmas_enabled(),
+        recipes: set
+            .recipes()
+            .iter()
+            .filter(|details| engine.ficsmas_enabled() || !details.is_ficsmas())
+            .map(RecipeInfo::from)
+            .collect(),
+    }
+}
+
+/// GET /api/game-data?version=1.2
+pub async fn get_game_data(
+    State(state): State<AppState>,
+    Query(query): Query<GameDataQuery>,
+) -> Result<Json<GameDataResponse>> {
+    let engine = state.engine.snapshot();
+    let set = match query.version.as_deref() {
+        Some(version) => GameDataSet::find(version).map_err(AppError::NotFound)?,
+        None => engine.game_data(),
+    };
+
+    Ok(Json(game_data_response(set, &engine)))
+}
+
+/// PUT /api/game-data/version
+///
+/// Plan the loaded save against another game data version
+pub async fn set_game_data_version(
+    State(state): State<AppState>,
+    Json(request): Json<GameDataVersionRequest>,
+) -> Result<Json<GameDataResponse>> {
+    let mut engine = state.engine.write().await;
+
+    engine
+        .set_game_data_version(&request.version)
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+    let engine = engine.commit();
+
+    Ok(Json(game_data_response(engine.game_data(), &engine)))
+}
+
+/// PUT /api/game-data/ficsmas
+///
+/// Include the seasonal FICSMAS items and recipes in the loaded plan, or leave
+/// them out of its balance and diagnostics
+pub async fn set_ficsmas(
+    State(state): State<AppState>,
+    Json(request): Json<FicsmasRequest>,
+) -> Result<Json<GameDataResponse>> {
+    let mut engine = state.engine.write().await;
+
+    engine
+        .set_ficsmas_enabled(request.enabled)
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+    let engine = engine.commit();
+
+    Ok(Json(game_data_response(engine.game_data(), &engine)))
+}
+
+/// GET /api/game-data/balance
+///
+/// Overclock exponent, purity multipliers and generator outputs the loaded
+/// plan is calculated with
+pub async fn get_game_balance(State(state): State<AppState>) -> Result<Json<GameBalance>> {
+    let engine = state.engine.snapshot();
+    Ok(Json(engine.game_balance().clone()))
+}
+
+/// PUT /api/game-data/balance
+///
+/// Calculate the loaded plan with other game constants, e.g. to model a mod.
+/// Omitted constants take the game's value.
+pub async fn set_game_balance(
+    State(state): State<AppState>,
+    Json(balance): Json<GameBalance>,
+) -> Result<Json<GameBalance>> {
+    let mut engine = state.engine.write().await;
+
+    engine
+        .set_game_balance(balance)
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+    let engine = engine.commit();
+
+    Ok(Json(engine.game_balance().clone()))
+}
+
+pub async fn get_recipes(
+    State(state): State<AppState>,
+    Query(query): Query<UnlockedQuery>,
+) -> Result<Json<Vec<RecipeInfo>>> {
+    let engine = state.engine.snapshot();
+    let progress = engine.tier_progress().filter(|_| query.unlocked_only);
+
+    let recipes: Vec<RecipeInfo> = all_recipes()
+        .iter()
+        .filter(|details| {
+            progress.is_none_or(|progress| progress.is_recipe_unlocked(details.recipe))
         })
+        .map(RecipeInfo::from)
         .collect();
 
     Ok(Json(recipes))
 }
 
+/// GET /api/game-data/recipes/search?output=IronPlate&input=Coal&machine=Assembler
+pub async fn search_recipes(
+    State(state): State<AppState>,
+    Query(query): Query<RecipeSearchQuery>,
+) -> Result<Json<Vec<RecipeInfo>>> {
+    let engine = state.engine.snapshot();
+    let progress = engine.tier_progress().filter(|_| query.unlocked_only);
+
+    let recipes: Vec<RecipeInfo> = all_recipes()
+        .iter()
+        .filter(|details| query.output.is_none_or(|item| details.produces(item)))
+        .filter(|details| query.input.is_none_or(|item| details.consumes(item)))
+        .filter(|details| {
+            query
+                .machine
+                .is_none_or(|machine| details.machine == machine)
+        })
+        .filter(|details| {
+            progress.is_none_or(|progress| progress.is_recipe_unlocked(details.recipe))
+        })
+        .map(RecipeInfo::from)
+        .collect();
+
+    Ok(Json(recipes))
+}
+
+/// Query parameters for a recipe chain
+#[derive(Debug, Default, Deserialize)]
+pub struct RecipeChainQuery {
+    /// Recipe levels to expand below the root; every level when omitted
+    pub depth: Option<u32>,
+}
+
+/// GET /api/game-data/recipes/:recipe/chain?depth=N
+///
+/// Dependency tree of a recipe's inputs, with the rates needed to run one
+/// machine of it
+pub async fn get_recipe_chain(
+    State(_state): State<AppState>,
+    Path(recipe): Path<Recipe>,
+    Query(query): Query<RecipeChainQuery>,
+) -> Result<Json<RecipeChain>> {
+    let depth = query.depth.unwrap_or(MAX_RECIPE_CHAIN_DEPTH);
+    if depth > MAX_RECIPE_CHAIN_DEPTH {
+        return Err(AppError::BadRequest(format!(
+            "depth must be at most {}",
+            MAX_RECIPE_CHAIN_DEPTH
+        )));
+    }
+    Ok(Json(RecipeChain::new(recipe, depth)))
+}
+
+/// GET /api/game-data/items/:item/uses
+pub async fn get_item_uses(
+    State(_state): State<AppState>,
+    Path(item): Path<Item>,
+) -> Result<Json<ItemUses>> {
+    let recipes = all_recipes();
+    Ok(Json(ItemUses {
+        item,
+        produced_by: recipes
+            .iter()
+            .filter(|details| details.produces(item))
+            .map(RecipeInfo::from)
+            .collect(),
+        consumed_by: recipes
+            .iter()
+            .filter(|details| details.consumes(item))
+            .map(RecipeInfo::from)
+            .collect(),
+    }))
+}
+
 pub async fn get_items(State(_state): State<AppState>) -> Result<Json<Vec<Item>>> {
     let items: Vec<Item> = all_items().iter().map(|(item, _)| *item).collect();
 
     Ok(Json(items))
 }
 
-pub async fn get_machines(State(_state): State<AppState>) -> Result<Json<Vec<MachineInfo>>> {
+pub async fn get_machines(
+    State(state): State<AppState>,
+    Query(query): Query<UnlockedQuery>,
+) -> Result<Json<Vec<MachineInfo>>> {
+    let engine = state.engine.snapshot();
+    let progress = engine.tier_progress().filter(|_| query.unlocked_only);
+
     let machines: Vec<MachineInfo> = [
         MachineType::Constructor,
         MachineType::Assembler,
@@ -76,6 +356,7 @@ pub async fn get_machines(State(_state): State<AppState>) -> Result<Json<Vec<Mac
         MachineType::Manual,
     ]
     .iter()
+    .filter(|machine| progress.is_none_or(|progress| progress.is_machine_unlocked(**machine)))
     .map(|machine| MachineInfo {
         name: *machine,
         base_power: machine.base_power_mw(),
@@ -86,6 +367,34 @@ pub async fn get_machines(State(_state): State<AppState>) -> Result<Json<Vec<Mac
     Ok(Json(machines))
 }
 
+/// Belt and lift tiers, slowest first; lifts move as many items as belts of their tier
+pub async fn get_conveyors(State(_state): State<AppState>) -> Result<Json<Vec<ConveyorInfo>>> {
+    let tiers = [
+        ConveyorSpeed::Mk1,
+        ConveyorSpeed::Mk2,
+        ConveyorSpeed::Mk3,
+        ConveyorSpeed::Mk4,
+        ConveyorSpeed::Mk5,
+        ConveyorSpeed::Mk6,
+    ];
+    let conveyors = [ConveyorKind::Belt, ConveyorKind::Lift]
+        .into_iter()
+        .flat_map(|kind| {
+            tiers
+                .iter()
+                .enumerate()
+                .map(move |(index, tier)| ConveyorInfo {
+                    name: format!("Conveyor {:?} Mk.{}", kind, index + 1),
+                    kind,
+                    tier: tier.clone(),
+                    items_per_min: tier.item_per_min(),
+                })
+        })
+        .collect();
+
+    Ok(Json(conveyors))
+}
+
 #[derive(Serialize)]
 pub struct ExtractorCompatibleItemsResponse {
     pub extractor_type: ExtractorType,
@@ -115,13 +424,267 @@ pub async fn get_extractor_compatible_items(
     Ok(Json(responses))
 }
 
+// ========== Custom Recipes ==========
+
+#[derive(Deserialize)]
+pub struct CustomRecipeRequest {
+    /// Must differ from every built-in and custom recipe name
+    pub name: String,
+    pub machine: MachineType,
+    /// Items consumed per cycle
+    #[serde(default)]
+    pub inputs: Vec<ItemQuantity>,
+    /// Items produced per cycle
+    pub outputs: Vec<ItemQuantity>,
+    /// Seconds per cycle at 100% clock speed
+    pub cycle_time: f64,
+}
+
+fn to_pairs(quantities: &[ItemQuantity]) -> Vec<(Item, f64)> {
+    quantities
+        .iter()
+        .map(|quantity| (quantity.item, quantity.quantity))
+        .collect()
+}
+
+#[derive(Serialize)]
+pub struct CustomRecipeResponse {
+    pub id: CustomRecipeId,
+    pub name: String,
+    pub machine: MachineType,
+    pub inputs: Vec<ItemQuantity>,
+    pub outputs: Vec<ItemQuantity>,
+    pub cycle_time: f64,
+}
+
+impl From<&CustomRecipe> for CustomRecipeResponse {
+    fn from(recipe: &CustomRecipe) -> Self {
+        let quantities = |pairs: &[(Item, f64)]| {
+            pairs
+                .iter()
+                .map(|(item, quantity)| ItemQuantity {
+                    item: *item,
+                    quantity: *quantity,
+                })
+                .collect()
+        };
+
+        Self {
+            id: recipe.id,
+            name: recipe.name.clone(),
+            machine: recipe.machine,
+            inputs: quantities(&recipe.inputs),
+            outputs: quantities(&recipe.outputs),
+            cycle_time: recipe.cycle_time,
+        }
+    }
+}
+
+/// GET /api/game-data/custom-recipes
+///
+/// Custom recipes sorted by name
+pub async fn get_custom_recipes(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<CustomRecipeResponse>>> {
+    let engine = state.engine.snapshot();
+
+    let mut recipes: Vec<&CustomRecipe> = engine.get_all_custom_recipes().values().collect();
+    recipes.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(Json(recipes.into_iter().map(Into::into).collect()))
+}
+
+pub async fn get_custom_recipe(
+    State(state): State<AppState>,
+    Path(id): Path<CustomRecipeId>,
+) -> Result<Json<CustomRecipeResponse>> {
+    let engine = state.engine.snapshot();
+
+    let recipe = engine
+        .get_custom_recipe(id)
+        .ok_or_else(|| AppError::NotFound(format!("Custom recipe with id {} not found", id)))?;
+
+    Ok(Json(recipe.into()))
+}
+
+pub async fn create_custom_recipe(
+    State(state): State<AppState>,
+    Json(request): Json<CustomRecipeRequest>,
+) -> Result<(StatusCode, Json<CustomRecipeResponse>)> {
+    let mut engine = state.engine.write().await;
+
+    let id = engine
+        .create_custom_recipe(
+            request.name,
+            request.machine,
+            to_pairs(&request.inputs),
+            to_pairs(&request.outputs),
+            request.cycle_time,
+        )
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+    let engine = engine.commit();
+
+    let recipe = engine.get_custom_recipe(id).ok_or_else(|| {
+        AppError::InternalError(anyhow::anyhow!("Custom recipe {} was not stored", id))
+    })?;
+
+    Ok((StatusCode::CREATED, Json(recipe.into())))
+}
+
+/// PUT /api/game-data/custom-recipes/:id
+///
+/// Production lines running the recipe are updated too
+pub async fn update_custom_recipe(
+    State(state): State<AppState>,
+    Path(id): Path<CustomRecipeId>,
+    Json(request): Json<CustomRecipeRequest>,
+) -> Result<Json<CustomRecipeResponse>> {
+    let mut engine = state.engine.write().await;
+
+    if engine.get_custom_recipe(id).is_none() {
+        return Err(AppError::NotFound(format!(
+            "Custom recipe with id {} not found",
+            id
+        )));
+    }
+
+    let recipe = CustomRecipe::new(
+        id,
+        request.name,
+        request.machine,
+        to_pairs(&request.inputs),
+        to_pairs(&request.outputs),
+        request.cycle_time,
+    );
+    engine
+        .replace_custom_recipe(recipe.clone())
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+    engine.commit();
+
+    Ok(Json((&recipe).into()))
+}
+
+/// DELETE /api/game-data/custom-recipes/:id
+///
+/// Refused while a production line still runs the recipe
+pub async fn delete_custom_recipe(
+    State(state): State<AppState>,
+    Path(id): Path<CustomRecipeId>,
+) -> Result<StatusCode> {
+    let mut engine = state.engine.write().await;
+
+    if engine.get_custom_recipe(id).is_none() {
+        return Err(AppError::NotFound(format!(
+            "Custom recipe with id {} not found",
+            id
+        )));
+    }
+
+    engine
+        .delete_custom_recipe(id)
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+    engine.commit();
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ========== Caching ==========
+
+/// How long browsers may reuse the built-in game data without asking again
+pub const STATIC_DATA_MAX_AGE_SECS: u64 = 3600;
+
+/// Middleware letting clients cache the built-in game data
+///
+/// These lists only change with the build, or with the recorded tier progress
+/// when `unlocked_only` is set. Responses get a strong `ETag` hashed from their
+/// body, and a matching `If-None-Match` is answered with `304 Not Modified`.
+pub async fn cache_static_data(
+    Query(query): Query<UnlockedQuery>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let if_none_match = request.headers().get(header::IF_NONE_MATCH).cloned();
+    let response = next.run(request).await;
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            return AppError::InternalError(anyhow::anyhow!("Failed to read response: {}", e))
+                .into_response()
+        }
+    };
+
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    let etag = format!("\"{:016x}\"", hasher.finish());
+    let cache_control = if query.unlocked_only {
+        "no-cache".to_string()
+    } else {
+        format!("public, max-age={}", STATIC_DATA_MAX_AGE_SECS)
+    };
+
+    let headers = &mut parts.headers;
+    headers.insert(
+        header::ETAG,
+        HeaderValue::from_str(&etag).expect("hex digits are a valid header value"),
+    );
+    headers.insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_str(&cache_control).expect("ASCII is a valid header value"),
+    );
+
+    let not_modified = if_none_match
+        .as_ref()
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| {
+            value
+                .split(',')
+                .map(|tag| tag.trim().trim_start_matches("W/"))
+                .any(|tag| tag == "*" || tag == etag)
+        });
+    if not_modified {
+        parts.status = StatusCode::NOT_MODIFIED;
+        parts.headers.remove(header::CONTENT_LENGTH);
+        return Response::from_parts(parts, Body::empty());
+    }
+    Response::from_parts(parts, Body::from(bytes))
+}
+
 pub fn routes() -> Router<AppState> {
-    Router::new()
+    let static_data = Router::new()
         .route("/recipes", get(get_recipes))
+        .route("/recipes/search", get(search_recipes))
+        .route("/recipes/:recipe/chain", get(get_recipe_chain))
         .route("/items", get(get_items))
+        .route("/items/:item/uses", get(get_item_uses))
         .route("/machines", get(get_machines))
+        .route("/conveyors", get(get_conveyors))
         .route(
             "/extractor-compatible-items",
             get(get_extractor_compatible_items),
         )
+        .route_layer(middleware::from_fn(cache_static_data));
+
+    Router::new()
+        .merge(static_data)
+        .route("/", get(get_game_data))
+        .route("/version", put(set_game_data_version))
+        .route("/ficsmas", put(set_ficsmas))
+        .route("/balance", get(get_game_balance).put(set_game_balance))
+        .route(
+            "/custom-recipes",
+            get(get_custom_recipes).post(create_custom_recipe),
+        )
+        .route(
+            "/custom-recipes/:id",
+            get(get_custom_recipe)
+                .put(update_custom_recipe)
+                .delete(delete_custom_recipe),
+        )
 }
@@ -0,0 +1,168 @@
+// crates/satisflow-server/src/handlers/goals.rs
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use satisflow_engine::models::{FactoryId, GoalId};
+use satisflow_engine::{
+    models::{Goal, Item},
+    SatisflowEngine,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{AppError, Result},
+    state::AppState,
+};
+
+#[derive(Deserialize)]
+pub struct CreateGoalRequest {
+    pub item: Item,
+    /// Wanted production, in items/min
+    pub target_rate: f64,
+    /// Factory the goal applies to; omit for a global goal
+    pub factory_id: Option<FactoryId>,
+    pub description: Option<String>,
+}
+
+/// Fields left out are not changed
+#[derive(Deserialize)]
+pub struct UpdateGoalRequest {
+    pub item: Option<Item>,
+    pub target_rate: Option<f64>,
+    pub factory_id: Option<FactoryId>,
+    /// Make a factory goal global
+    #[serde(default)]
+    pub global: bool,
+    pub description: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct GoalResponse {
+    #[serde(flatten)]
+    pub goal: Goal,
+    pub actual_rate: f64,
+    pub completion_percent: f64,
+    pub is_met: bool,
+}
+
+fn build_goal_response(engine: &SatisflowEngine, goal_id: GoalId) -> Result<GoalResponse> {
+    let goal = engine
+        .get_goal(goal_id)
+        .ok_or_else(|| AppError::NotFound(format!("Goal with id {} not found", goal_id)))?;
+    let progress = engine.goal_progress(goal_id).ok_or_else(|| {
+        AppError::InternalError(anyhow::anyhow!("Progress of goal {} not computed", goal_id))
+    })?;
+
+    Ok(GoalResponse {
+        goal: goal.clone(),
+        actual_rate: progress.actual_rate,
+        completion_percent: progress.completion_percent,
+        is_met: progress.is_met,
+    })
+}
+
+/// GET /api/goals
+///
+/// Lists every goal with its progress, least complete first
+pub async fn get_goals(State(state): State<AppState>) -> Result<Json<Vec<GoalResponse>>> {
+    let engine = state.engine.snapshot();
+
+    let goals = engine
+        .all_goal_progress()
+        .iter()
+        .map(|progress| build_goal_response(&engine, progress.goal_id))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Json(goals))
+}
+
+pub async fn get_goal(
+    State(state): State<AppState>,
+    Path(id): Path<GoalId>,
+) -> Result<Json<GoalResponse>> {
+    let engine = state.engine.snapshot();
+
+    Ok(Json(build_goal_response(&engine, id)?))
+}
+
+pub async fn create_goal(
+    State(state): State<AppState>,
+    Json(request): Json<CreateGoalRequest>,
+) -> Result<(StatusCode, Json<GoalResponse>)> {
+    let mut engine = state.engine.write().await;
+
+    let goal_id = engine
+        .create_goal(
+            request.item,
+            request.target_rate,
+            request.factory_id,
+            request.description,
+        )
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+    let engine = engine.commit();
+
+    Ok((
+        StatusCode::CREATED,
+        Json(build_goal_response(&engine, goal_id)?),
+    ))
+}
+
+pub async fn update_goal(
+    State(state): State<AppState>,
+    Path(id): Path<GoalId>,
+    Json(request): Json<UpdateGoalRequest>,
+) -> Result<Json<GoalResponse>> {
+    let mut engine = state.engine.write().await;
+
+    let mut goal = engine
+        .get_goal(id)
+        .cloned()
+        .ok_or_else(|| AppError::NotFound(format!("Goal with id {} not found", id)))?;
+
+    if let Some(item) = request.item {
+        goal.item = item;
+    }
+    if let Some(target_rate) = request.target_rate {
+        goal.target_rate = target_rate;
+    }
+    if request.global {
+        goal.factory_id = None;
+    } else if let Some(factory_id) = request.factory_id {
+        goal.factory_id = Some(factory_id);
+    }
+    if let Some(description) = request.description {
+        goal.description = Some(description);
+    }
+
+    engine
+        .replace_goal(goal)
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+    let engine = engine.commit();
+
+    Ok(Json(build_goal_response(&engine, id)?))
+}
+
+pub async fn delete_goal(
+    State(state): State<AppState>,
+    Path(id): Path<GoalId>,
+) -> Result<StatusCode> {
+    let mut engine = state.engine.write().await;
+
+    engine
+        .delete_goal(id)
+        .map_err(|_| AppError::NotFound(format!("Goal with id {} not found", id)))?;
+
+    engine.commit();
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_goals).post(create_goal))
+        .route("/:id", get(get_goal).put(update_goal).delete(delete_goal))
+}
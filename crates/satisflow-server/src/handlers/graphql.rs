@@ -0,0 +1,81 @@
+// crates/satisflow-server/src/handlers/graphql.rs
+//! GraphQL endpoint
+//!
+//! Queries are sent as JSON with `POST`, or as query parameters with `GET` so
+//! read-only API keys can use them too.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Extension, Json, Router,
+};
+use serde::Deserialize;
+
+use crate::{
+    error::{AppError, Result},
+    graphql::{build_schema, EngineSnapshot, SatisflowSchema},
+    state::AppState,
+};
+
+/// A query sent as query parameters
+#[derive(Debug, Deserialize)]
+pub struct GraphQLGetRequest {
+    pub query: String,
+    #[serde(rename = "operationName")]
+    pub operation_name: Option<String>,
+    /// Variables as a JSON object
+    pub variables: Option<String>,
+}
+
+async fn execute(
+    state: &AppState,
+    schema: &SatisflowSchema,
+    request: async_graphql::Request,
+) -> Json<async_graphql::Response> {
+    let snapshot = EngineSnapshot::new(state.engine.snapshot());
+    Json(schema.execute(request.data(Arc::new(snapshot))).await)
+}
+
+/// POST /api/graphql
+///
+/// # Returns
+///
+/// - `200 OK` with the GraphQL response, errors included
+pub async fn graphql_post(
+    State(state): State<AppState>,
+    Extension(schema): Extension<SatisflowSchema>,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    execute(&state, &schema, request).await
+}
+
+/// GET /api/graphql?query=...
+///
+/// # Returns
+///
+/// - `200 OK` with the GraphQL response, errors included
+/// - `400 Bad Request` if `variables` isn't a JSON object
+pub async fn graphql_get(
+    State(state): State<AppState>,
+    Extension(schema): Extension<SatisflowSchema>,
+    Query(params): Query<GraphQLGetRequest>,
+) -> Result<Json<async_graphql::Response>> {
+    let mut request = async_graphql::Request::new(params.query);
+    if let Some(operation_name) = params.operation_name {
+        request = request.operation_name(operation_name);
+    }
+    if let Some(variables) = params.variables {
+        let variables = serde_json::from_str(&variables)
+            .map_err(|e| AppError::BadRequest(format!("Invalid variables: {}", e)))?;
+        request = request.variables(async_graphql::Variables::from_json(variables));
+    }
+    Ok(execute(&state, &schema, request).await)
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(graphql_get).post(graphql_post))
+        .layer(Extension(build_schema()))
+}
@@ -0,0 +1,142 @@
+// crates/satisflow-server/src/handlers/import.rs
+use axum::{
+    extract::{DefaultBodyLimit, Multipart, State},
+    http::StatusCode,
+    routing::post,
+    Json, Router,
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{
+    error::{AppError, Result},
+    state::AppState,
+};
+use satisflow_engine::{
+    game_save, import, ExternalFormat, ExternalPlan, GameSaveImportReport, GameSavePlan,
+    ImportReport,
+};
+
+/// Largest game save accepted, late-game saves are tens of megabytes
+const MAX_SAVE_SIZE: usize = 256 * 1024 * 1024;
+
+/// Request body for importing a plan from another planner
+#[derive(Debug, Deserialize)]
+pub struct ExternalImportRequest {
+    /// `satisfactory_tools` or `satisfactory_calculator`
+    pub format: ExternalFormat,
+    /// The plan as exported by the planner
+    pub data: Value,
+    /// Only validate the plan and report what would be created
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// POST /api/import/external
+///
+/// Creates factories and production lines from a Satisfactory Tools or
+/// Satisfactory Calculator JSON export
+///
+/// # Returns
+///
+/// - `201 Created` with the import report, or `200 OK` on a dry run
+/// - `400 Bad Request` if `data` doesn't have the shape of `format`
+pub async fn import_external(
+    State(state): State<AppState>,
+    Json(request): Json<ExternalImportRequest>,
+) -> Result<(StatusCode, Json<ImportReport>)> {
+    let plan = ExternalPlan::parse(request.format, &request.data)
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+    let mut engine = state.engine.write().await;
+    let report = import::import_plan(&mut engine, plan, request.dry_run)
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+    let status = if request.dry_run {
+        StatusCode::OK
+    } else {
+        StatusCode::CREATED
+    };
+
+    engine.commit();
+
+    Ok((status, Json(report)))
+}
+
+/// POST /api/import/game-save
+///
+/// Creates one factory per power circuit of a Satisfactory `.sav` file, sent
+/// as the `file` field of a multipart form. A `dry_run` field set to `true`
+/// only reports what would be created.
+///
+/// # Returns
+///
+/// - `201 Created` with the import report, or `200 OK` on a dry run
+/// - `400 Bad Request` if the form has no file, or the file isn't a save from
+///   Satisfactory 1.0 or later
+pub async fn import_game_save(
+    State(state): State<AppState>,
+    mut multipart: Multipart,
+) -> Result<(StatusCode, Json<GameSaveImportReport>)> {
+    let mut save = None;
+    let mut dry_run = false;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::BadRequest(e.to_string()))?
+    {
+        match field.name() {
+            Some("file") => {
+                save = Some(
+                    field
+                        .bytes()
+                        .await
+                        .map_err(|e| AppError::BadRequest(e.to_string()))?,
+                )
+            }
+            Some("dry_run") => {
+                let value = field
+                    .text()
+                    .await
+                    .map_err(|e| AppError::BadRequest(e.to_string()))?;
+                dry_run = value.trim().parse().map_err(|_| {
+                    AppError::BadRequest(format!(
+                        "Invalid dry_run '{}', expected true or false",
+                        value
+                    ))
+                })?;
+            }
+            _ => {}
+        }
+    }
+    let save =
+        save.ok_or_else(|| AppError::BadRequest("Missing 'file' field with the .sav file".into()))?;
+
+    // Inflating and walking a late-game save takes a while
+    let plan =
+        tokio::task::spawn_blocking(move || GameSavePlan::read(&save).map_err(|e| e.to_string()))
+            .await
+            .map_err(|e| anyhow::anyhow!("Game save task failed: {}", e))?
+            .map_err(AppError::BadRequest)?;
+
+    let mut engine = state.engine.write().await;
+    let report = game_save::import_game_save(&mut engine, plan, dry_run)
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+    let status = if dry_run {
+        StatusCode::OK
+    } else {
+        StatusCode::CREATED
+    };
+
+    engine.commit();
+
+    Ok((status, Json(report)))
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/external", post(import_external))
+        .route(
+            "/game-save",
+            post(import_game_save).layer(DefaultBodyLimit::max(MAX_SAVE_SIZE)),
+        )
+}
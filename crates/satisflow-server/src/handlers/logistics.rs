@@ -1,29 +1,35 @@
 // crates/satisflow-server/src/handlers/logistics.rs
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
-    routing::get,
+    routing::{get, post},
     Json, Router,
 };
 use satisflow_engine::models::logistics::{
-    Bus, Conveyor, ConveyorSpeed, DroneTransport, LogisticsFlux, Pipeline, PipelineCapacity, Train,
-    Transport, TransportType, TruckTransport, Wagon, WagonType,
+    Bus, Conveyor, ConveyorLift, ConveyorSpeed, DroneTransport, LogisticsFlux, Pipeline,
+    PipelineCapacity, Train, Transport, TransportType, TruckTransport, Wagon, WagonType,
+};
+use satisflow_engine::models::{
+    FactoryId, Item, LogisticsId, RouteEstimate, Timestamps, TrainSchedule, TrainThroughput,
+    TransportDetails, TransportKind, TransportNaming,
 };
-use satisflow_engine::models::Item;
 use satisflow_engine::SatisflowEngine;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
-use uuid::Uuid;
+use serde_json::{json, Value};
 
 use crate::{
     error::{AppError, Result},
+    handlers::{
+        pagination::{contains_ignore_case, paginate, Listing, SortOrder},
+        patch::patched_payload,
+    },
     state::AppState,
 };
 
 #[derive(Serialize, Deserialize)]
 pub struct CreateLogisticsRequest {
-    pub from_factory: Uuid,
-    pub to_factory: Uuid,
+    pub from_factory: FactoryId,
+    pub to_factory: FactoryId,
     #[serde(flatten)]
     pub transport: CreateLogisticsTransport,
 }
@@ -34,14 +40,14 @@ pub enum CreateLogisticsTransport {
     #[serde(rename = "Truck", alias = "truck")]
     Truck {
         item: Item,
-        quantity_per_min: f32,
+        quantity_per_min: f64,
         #[serde(default)]
         truck_id: Option<String>,
     },
     #[serde(rename = "Drone", alias = "drone")]
     Drone {
         item: Item,
-        quantity_per_min: f32,
+        quantity_per_min: f64,
         #[serde(default)]
         drone_id: Option<String>,
     },
@@ -52,6 +58,8 @@ pub enum CreateLogisticsTransport {
         #[serde(default)]
         conveyors: Vec<BusConveyorRequest>,
         #[serde(default)]
+        lifts: Vec<BusLiftRequest>,
+        #[serde(default)]
         pipelines: Vec<BusPipelineRequest>,
     },
     #[serde(rename = "Train", alias = "train")]
@@ -68,7 +76,16 @@ pub struct BusConveyorRequest {
     pub line_id: Option<String>,
     pub conveyor_type: String,
     pub item: Item,
-    pub quantity_per_min: f32,
+    pub quantity_per_min: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BusLiftRequest {
+    pub lift_id: Option<String>,
+    /// Lift tier, Mk1 to Mk6
+    pub lift_type: String,
+    pub item: Item,
+    pub quantity_per_min: f64,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -76,7 +93,14 @@ pub struct BusPipelineRequest {
     pub pipeline_id: Option<String>,
     pub pipeline_type: String,
     pub item: Item,
-    pub quantity_per_min: f32,
+    pub quantity_per_min: f64,
+    #[serde(default)]
+    pub pumps_mk1: u32,
+    #[serde(default)]
+    pub pumps_mk2: u32,
+    /// Height the fluid climbs, in meters
+    #[serde(default)]
+    pub vertical_rise: Option<f64>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -84,29 +108,78 @@ pub struct TrainWagonRequest {
     pub wagon_id: Option<String>,
     pub wagon_type: String,
     pub item: Item,
-    pub quantity_per_min: f32,
+    pub quantity_per_min: f64,
+}
+
+/// Filters, sorting and paging for the logistics list
+#[derive(Debug, Default, Deserialize)]
+pub struct LogisticsListQuery {
+    /// Only lines whose transport ID, name or details contain this text, ignoring case
+    pub name_contains: Option<String>,
+    /// Only lines of this transport type (e.g. `Truck`), ignoring case
+    pub transport_type: Option<String>,
+    /// Only lines carrying this item
+    pub item: Option<Item>,
+    #[serde(default)]
+    pub sort_by: LogisticsSort,
+    #[serde(default)]
+    pub order: SortOrder,
+    /// Page number, starting at 1; the list is paged when this or `per_page` is set
+    pub page: Option<usize>,
+    pub per_page: Option<usize>,
+}
+
+/// Schedule of the train throughput calculator; unset values fall back to the
+/// distance between the factories on the map and the usual speed and docking time
+#[derive(Debug, Default, Deserialize)]
+pub struct TrainScheduleQuery {
+    /// Track length between the stations, in meters
+    pub distance: Option<f64>,
+    /// Average speed, in m/s
+    pub speed: Option<f64>,
+    /// Time spent at each station, in seconds
+    pub station_time_secs: Option<f64>,
+}
+
+/// Sort keys of the logistics list
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogisticsSort {
+    #[default]
+    TransportId,
+    TransportType,
+    Quantity,
+    CreatedAt,
+    UpdatedAt,
 }
 
 #[derive(Serialize)]
 pub struct ItemFlowResponse {
     pub item: Item,
-    pub quantity_per_min: f32,
+    pub quantity_per_min: f64,
 }
 
 #[derive(Serialize)]
 pub struct LogisticsResponse {
-    pub id: Uuid,
-    pub from_factory: Uuid,
-    pub to_factory: Uuid,
+    pub id: LogisticsId,
+    pub from_factory: FactoryId,
+    pub to_factory: FactoryId,
     pub transport_type: String,
     pub transport_id: String,
     pub transport_name: Option<String>,
-    pub transport_details: String,
+    pub transport_details: TransportDetails,
     pub items: Vec<ItemFlowResponse>,
-    pub total_quantity_per_min: f32,
+    pub total_quantity_per_min: f64,
+    /// Travel estimate for trains, trucks and drones between factories placed on the map
+    pub route: Option<RouteEstimate>,
+    #[serde(flatten)]
+    pub timestamps: Timestamps,
+    /// Problems the line was created with anyway, e.g. an overloaded conveyor
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
 }
 
-fn logistics_to_response(logistics: &LogisticsFlux) -> LogisticsResponse {
+fn logistics_to_response(engine: &SatisflowEngine, logistics: &LogisticsFlux) -> LogisticsResponse {
     let items = convert_item_flows(logistics.get_items());
     let total_quantity = logistics.total_quantity_per_min();
 
@@ -123,6 +196,9 @@ fn logistics_to_response(logistics: &LogisticsFlux) -> LogisticsResponse {
         transport_details: logistics.transport_details.clone(),
         items,
         total_quantity_per_min: total_quantity,
+        route: engine.route_estimate(logistics.id),
+        timestamps: logistics.timestamps,
+        warnings: Vec::new(),
     }
 }
 
@@ -179,29 +255,117 @@ fn convert_item_flows(
         .collect()
 }
 
-pub async fn get_logistics(State(state): State<AppState>) -> Result<Json<Vec<LogisticsResponse>>> {
-    let engine = state.engine.read().await;
-    let logistics_lines = engine.get_all_logistics();
+pub async fn get_logistics(
+    State(state): State<AppState>,
+    Query(query): Query<LogisticsListQuery>,
+) -> Result<Json<Listing<LogisticsResponse>>> {
+    let engine = state.engine.snapshot();
 
-    let responses = logistics_lines
+    let mut selected: Vec<&LogisticsFlux> = engine
+        .get_all_logistics()
         .values()
-        .map(logistics_to_response)
+        .filter(|line| {
+            query.name_contains.as_deref().is_none_or(|text| {
+                contains_ignore_case(&line.transport_type.get_transport_id(), text)
+                    || line
+                        .transport_type
+                        .get_transport_name()
+                        .is_some_and(|name| contains_ignore_case(&name, text))
+                    || contains_ignore_case(&line.transport_details.to_string(), text)
+            })
+        })
+        .filter(|line| {
+            query
+                .transport_type
+                .as_deref()
+                .is_none_or(|transport_type| {
+                    line.transport_type
+                        .get_transport_type_name()
+                        .eq_ignore_ascii_case(transport_type.trim())
+                })
+        })
+        .filter(|line| {
+            query
+                .item
+                .is_none_or(|item| line.get_items().iter().any(|flow| flow.item == item))
+        })
         .collect();
 
-    Ok(Json(responses))
+    selected.sort_by(|a, b| {
+        let ordering = match query.sort_by {
+            LogisticsSort::TransportId => a
+                .transport_type
+                .get_transport_id()
+                .cmp(&b.transport_type.get_transport_id()),
+            LogisticsSort::TransportType => a
+                .transport_type
+                .get_transport_type_name()
+                .cmp(b.transport_type.get_transport_type_name()),
+            LogisticsSort::Quantity => a
+                .total_quantity_per_min()
+                .total_cmp(&b.total_quantity_per_min()),
+            LogisticsSort::CreatedAt => a.timestamps.created_at.cmp(&b.timestamps.created_at),
+            LogisticsSort::UpdatedAt => a.timestamps.updated_at.cmp(&b.timestamps.updated_at),
+        };
+        query.order.apply(ordering.then(a.id.cmp(&b.id)))
+    });
+
+    let listing = paginate(selected, query.page, query.per_page)?
+        .map(|logistics| logistics_to_response(&engine, logistics));
+
+    Ok(Json(listing))
 }
 
 pub async fn get_logistics_line(
     State(state): State<AppState>,
-    Path(id): Path<Uuid>,
+    Path(id): Path<LogisticsId>,
 ) -> Result<Json<LogisticsResponse>> {
-    let engine = state.engine.read().await;
+    let engine = state.engine.snapshot();
 
     let logistics = engine
         .get_logistics_line(id)
         .ok_or_else(|| AppError::NotFound(format!("Logistics line with id {} not found", id)))?;
 
-    Ok(Json(logistics_to_response(logistics)))
+    Ok(Json(logistics_to_response(&engine, logistics)))
+}
+
+pub async fn get_train_throughput(
+    State(state): State<AppState>,
+    Path(id): Path<LogisticsId>,
+    Query(query): Query<TrainScheduleQuery>,
+) -> Result<Json<TrainThroughput>> {
+    let engine = state.engine.snapshot();
+
+    let logistics = engine
+        .get_logistics_line(id)
+        .ok_or_else(|| AppError::NotFound(format!("Logistics line with id {} not found", id)))?;
+    if !matches!(logistics.transport_type, TransportType::Train(_)) {
+        return Err(AppError::BadRequest(format!(
+            "Logistics line {} is not a train",
+            id
+        )));
+    }
+
+    let mut schedule = match (query.distance, engine.train_schedule(id)) {
+        (Some(distance), _) => TrainSchedule::new(distance),
+        (None, Some(schedule)) => schedule,
+        (None, None) => {
+            return Err(AppError::BadRequest(
+                "distance is required unless both factories are placed on the map".to_string(),
+            ))
+        }
+    };
+    if let Some(speed) = query.speed {
+        schedule = schedule.with_speed(speed);
+    }
+    if let Some(station_time_secs) = query.station_time_secs {
+        schedule = schedule.with_station_time(station_time_secs);
+    }
+
+    let throughput = engine
+        .train_throughput(id, &schedule)
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+    Ok(Json(throughput))
 }
 
 pub async fn create_logistics(
@@ -228,45 +392,171 @@ pub async fn create_logistics(
         )));
     }
 
-    let (transport_type, transport_details) = build_transport(&engine, request.transport, None)
-        .map_err(|err| match err {
-            AppError::SerializationError(_) => {
-                AppError::BadRequest("Failed to serialize transport details".to_string())
-            }
-            other => other,
-        })?;
+    let (transport_type, transport_details) = build_transport(&engine, request.transport, None)?;
+    let warnings = engine
+        .check_logistics_line(
+            from_factory,
+            to_factory,
+            &transport_type,
+            &transport_details,
+        )
+        .into_result()
+        .map_err(|report| AppError::ValidationError(report.to_string()))?;
 
     let logistics_id = engine
         .create_logistics_line(from_factory, to_factory, transport_type, transport_details)
         .map_err(|e| AppError::BadRequest(format!("Failed to create logistics line: {}", e)))?;
 
-    let logistics = engine
-        .get_logistics_line(logistics_id)
-        .ok_or_else(|| AppError::InternalError(anyhow::anyhow!("Failed to retrieve created logistics line")))?;
-    let response = logistics_to_response(logistics);
+    let logistics = engine.get_logistics_line(logistics_id).ok_or_else(|| {
+        AppError::InternalError(anyhow::anyhow!("Failed to retrieve created logistics line"))
+    })?;
+    let mut response = logistics_to_response(&engine, logistics);
+    response.warnings = warnings;
+
+    engine.commit();
 
     Ok((StatusCode::CREATED, Json(response)))
 }
 
 pub async fn update_logistics(
     State(state): State<AppState>,
-    Path(id): Path<Uuid>,
+    Path(id): Path<LogisticsId>,
     Json(request): Json<CreateLogisticsRequest>,
 ) -> Result<Json<LogisticsResponse>> {
     let mut engine = state.engine.write().await;
+    let response = replace_logistics(&mut engine, id, request)?;
+    engine.commit();
+
+    Ok(Json(response))
+}
+
+/// PATCH /api/logistics/:id
+///
+/// Merges the given fields into the line and validates it like PUT; lists
+/// such as `conveyors` or `wagons` are replaced as a whole
+pub async fn patch_logistics(
+    State(state): State<AppState>,
+    Path(id): Path<LogisticsId>,
+    Json(patch): Json<Value>,
+) -> Result<Json<LogisticsResponse>> {
+    let mut engine = state.engine.write().await;
+
+    let current = engine
+        .get_logistics_line(id)
+        .map(logistics_payload)
+        .ok_or_else(|| AppError::NotFound(format!("Logistics line with id {} not found", id)))?;
+    // Switching transport type starts from an empty transport rather than
+    // the fields of the old one
+    let current_type = current["transport_type"].as_str().unwrap_or_default();
+    let base = if patch["transport_type"]
+        .as_str()
+        .is_some_and(|transport_type| !transport_type.eq_ignore_ascii_case(current_type))
+    {
+        json!({
+            "from_factory": current["from_factory"],
+            "to_factory": current["to_factory"],
+        })
+    } else {
+        current
+    };
+    let request = patched_payload(base, patch)?;
+
+    let response = replace_logistics(&mut engine, id, request)?;
+    engine.commit();
+
+    Ok(Json(response))
+}
+
+/// PUT payload describing an existing logistics line
+///
+/// The transport comes from the model; the details saved with it add the
+/// labels given when the line was created, such as a truck or wagon ID.
+fn logistics_payload(logistics: &LogisticsFlux) -> Value {
+    let mut payload = match &logistics.transport_type {
+        TransportType::Truck(truck) => json!({
+            "truck_id": logistics.transport_type.get_transport_id(),
+            "item": truck.item,
+            "quantity_per_min": truck.quantity_per_min,
+        }),
+        TransportType::Drone(drone) => json!({
+            "drone_id": logistics.transport_type.get_transport_id(),
+            "item": drone.item,
+            "quantity_per_min": drone.quantity_per_min,
+        }),
+        TransportType::Bus(bus) => json!({
+            "bus_name": bus.bus_name,
+            "conveyors": bus.lines.iter().map(|conveyor| json!({
+                "line_id": format!("CV-{:03}", conveyor.line_id),
+                "conveyor_type": conveyor.speed,
+                "item": conveyor.item,
+                "quantity_per_min": conveyor.quantity_per_min,
+            })).collect::<Vec<_>>(),
+            "lifts": bus.lifts.iter().map(|lift| json!({
+                "lift_id": format!("LF-{:03}", lift.lift_id),
+                "lift_type": lift.speed,
+                "item": lift.item,
+                "quantity_per_min": lift.quantity_per_min,
+            })).collect::<Vec<_>>(),
+            "pipelines": bus.pipelines.iter().map(|pipeline| json!({
+                "pipeline_id": format!("PL-{:03}", pipeline.pipeline_id),
+                "pipeline_type": pipeline.capacity,
+                "item": pipeline.item,
+                "quantity_per_min": pipeline.quantity_per_min,
+                "pumps_mk1": pipeline.pumps_mk1,
+                "pumps_mk2": pipeline.pumps_mk2,
+                "vertical_rise": pipeline.vertical_rise,
+            })).collect::<Vec<_>>(),
+        }),
+        TransportType::Train(train) => json!({
+            "train_name": train.train_name,
+            "wagons": train.wagons.iter().map(|wagon| json!({
+                "wagon_id": format!("WG-{:03}", wagon.wagon_id),
+                "wagon_type": wagon.wagon_type,
+                "item": wagon.item,
+                "quantity_per_min": wagon.quantity_per_min,
+            })).collect::<Vec<_>>(),
+        }),
+    };
+    let mut label = |list: &str, key: &str, labels: &[String]| {
+        if let Some(entries) = payload[list].as_array_mut() {
+            for (entry, label) in entries.iter_mut().zip(labels) {
+                entry[key] = json!(label);
+            }
+        }
+    };
+    match &logistics.transport_details {
+        TransportDetails::Note(_) => {}
+        TransportDetails::Truck { truck_id } => payload["truck_id"] = json!(truck_id),
+        TransportDetails::Drone { drone_id } => payload["drone_id"] = json!(drone_id),
+        TransportDetails::Bus {
+            conveyor_ids,
+            lift_ids,
+            pipeline_ids,
+        } => {
+            label("conveyors", "line_id", conveyor_ids);
+            label("lifts", "lift_id", lift_ids);
+            label("pipelines", "pipeline_id", pipeline_ids);
+        }
+        TransportDetails::Train { wagon_ids } => label("wagons", "wagon_id", wagon_ids),
+    }
+    payload["transport_type"] = json!(logistics.transport_type.get_transport_type_name());
+    payload["from_factory"] = json!(logistics.from_factory);
+    payload["to_factory"] = json!(logistics.to_factory);
+    payload
+}
 
+fn replace_logistics(
+    engine: &mut SatisflowEngine,
+    id: LogisticsId,
+    request: CreateLogisticsRequest,
+) -> Result<LogisticsResponse> {
     let existing = engine
         .get_logistics_line(id)
         .cloned()
         .ok_or_else(|| AppError::NotFound(format!("Logistics line with id {} not found", id)))?;
 
     let (transport_type, transport_details) =
-        build_transport(&engine, request.transport, Some(&existing)).map_err(|err| match err {
-            AppError::SerializationError(_) => {
-                AppError::BadRequest("Failed to serialize transport details".to_string())
-            }
-            other => other,
-        })?;
+        build_transport(engine, request.transport, Some(&existing))?;
 
     engine
         .update_logistics_line(
@@ -282,14 +572,14 @@ pub async fn update_logistics(
         .get_logistics_line(id)
         .ok_or_else(|| AppError::NotFound(format!("Logistics line with id {} not found", id)))?;
 
-    Ok(Json(logistics_to_response(updated)))
+    Ok(logistics_to_response(engine, updated))
 }
 
 fn build_transport(
     engine: &SatisflowEngine,
     transport: CreateLogisticsTransport,
     existing: Option<&LogisticsFlux>,
-) -> std::result::Result<(TransportType, String), AppError> {
+) -> std::result::Result<(TransportType, TransportDetails), AppError> {
     match transport {
         CreateLogisticsTransport::Truck {
             item,
@@ -303,23 +593,21 @@ fn build_transport(
                     TransportType::Truck(truck) => Some(truck.truck_id),
                     _ => None,
                 })
-                .unwrap_or_else(|| next_transport_identifier(engine));
+                .unwrap_or_else(|| engine.next_transport_number(TransportKind::Truck));
             let numeric_id = parse_numeric_identifier(truck_id.as_deref(), fallback_id);
             let display_id = truck_id
                 .as_ref()
                 .map(|val| val.trim())
                 .filter(|val| !val.is_empty())
                 .map(|val| val.to_string())
-                .unwrap_or_else(|| format!("TRK-{numeric_id:03}"));
+                .unwrap_or_else(|| engine.default_transport_name(TransportKind::Truck, numeric_id));
 
             let transport =
                 TransportType::Truck(TruckTransport::new(numeric_id, item_enum, quantity));
 
-            let details = serde_json::to_string(&json!({
-                "truck_id": display_id,
-                "item": item_enum,
-                "quantity_per_min": quantity,
-            }))?;
+            let details = TransportDetails::Truck {
+                truck_id: display_id,
+            };
 
             Ok((transport, details))
         }
@@ -335,29 +623,28 @@ fn build_transport(
                     TransportType::Drone(drone) => Some(drone.drone_id),
                     _ => None,
                 })
-                .unwrap_or_else(|| next_transport_identifier(engine));
+                .unwrap_or_else(|| engine.next_transport_number(TransportKind::Drone));
             let numeric_id = parse_numeric_identifier(drone_id.as_deref(), fallback_id);
             let display_id = drone_id
                 .as_ref()
                 .map(|val| val.trim())
                 .filter(|val| !val.is_empty())
                 .map(|val| val.to_string())
-                .unwrap_or_else(|| format!("DRN-{numeric_id:03}"));
+                .unwrap_or_else(|| engine.default_transport_name(TransportKind::Drone, numeric_id));
 
             let transport =
                 TransportType::Drone(DroneTransport::new(numeric_id, item_enum, quantity));
 
-            let details = serde_json::to_string(&json!({
-                "drone_id": display_id,
-                "item": item_enum,
-                "quantity_per_min": quantity,
-            }))?;
+            let details = TransportDetails::Drone {
+                drone_id: display_id,
+            };
 
             Ok((transport, details))
         }
         CreateLogisticsTransport::Bus {
             bus_name,
             conveyors,
+            lifts,
             pipelines,
         } => {
             let existing_bus = existing.and_then(|flux| match &flux.transport_type {
@@ -366,7 +653,7 @@ fn build_transport(
             });
             let bus_id = existing_bus
                 .map(|bus| bus.bus_id)
-                .unwrap_or_else(|| next_transport_identifier(engine));
+                .unwrap_or_else(|| engine.next_transport_number(TransportKind::Bus));
             let provided_name = bus_name
                 .as_ref()
                 .map(|val| val.trim())
@@ -374,10 +661,10 @@ fn build_transport(
                 .map(|val| val.to_string());
             let name = provided_name
                 .or_else(|| existing_bus.map(|bus| bus.bus_name.clone()))
-                .unwrap_or_else(|| sanitize_name(None, "Bus", bus_id));
+                .unwrap_or_else(|| engine.default_transport_name(TransportKind::Bus, bus_id));
             let mut bus = Bus::new(bus_id, name.clone());
 
-            let mut conveyor_details = Vec::new();
+            let mut conveyor_ids = Vec::new();
             for (index, conveyor) in conveyors.into_iter().enumerate() {
                 let BusConveyorRequest {
                     line_id,
@@ -405,21 +692,49 @@ fn build_transport(
                     quantity,
                 ));
 
-                conveyor_details.push(json!({
-                    "line_id": line_label,
-                    "conveyor_type": speed,
-                    "item": item_enum,
-                    "quantity_per_min": quantity,
-                }));
+                conveyor_ids.push(line_label);
+            }
+
+            let mut lift_ids = Vec::new();
+            for (index, lift) in lifts.into_iter().enumerate() {
+                let BusLiftRequest {
+                    lift_id,
+                    lift_type,
+                    item,
+                    quantity_per_min,
+                } = lift;
+
+                let quantity = ensure_positive(quantity_per_min, "Bus lift quantity_per_min")?;
+                let speed = parse_conveyor_speed(&lift_type)?;
+                let numeric_lift_id =
+                    parse_numeric_identifier(lift_id.as_deref(), (index + 1) as u64);
+                let lift_label = lift_id
+                    .as_ref()
+                    .map(|val| val.trim())
+                    .filter(|val| !val.is_empty())
+                    .map(|val| val.to_string())
+                    .unwrap_or_else(|| format!("LF-{numeric_lift_id:03}"));
+
+                bus.add_lift(ConveyorLift::new(
+                    numeric_lift_id,
+                    speed.clone(),
+                    item,
+                    quantity,
+                ));
+
+                lift_ids.push(lift_label);
             }
 
-            let mut pipeline_details = Vec::new();
+            let mut pipeline_ids = Vec::new();
             for (index, pipeline) in pipelines.into_iter().enumerate() {
                 let BusPipelineRequest {
                     pipeline_id,
                     pipeline_type,
                     item,
                     quantity_per_min,
+                    pumps_mk1,
+                    pumps_mk2,
+                    vertical_rise,
                 } = pipeline;
 
                 let quantity = ensure_positive(quantity_per_min, "Bus pipeline quantity_per_min")?;
@@ -434,33 +749,31 @@ fn build_transport(
                     .map(|val| val.to_string())
                     .unwrap_or_else(|| format!("PL-{numeric_pipeline_id:03}"));
 
-                bus.add_pipeline(Pipeline::new(
-                    numeric_pipeline_id,
-                    capacity.clone(),
-                    item_enum,
-                    quantity,
-                ));
-
-                pipeline_details.push(json!({
-                    "pipeline_id": pipeline_label,
-                    "pipeline_type": capacity,
-                    "item": item_enum,
-                    "quantity_per_min": quantity,
-                }));
+                if vertical_rise.is_some_and(|rise| !rise.is_finite()) {
+                    return Err(AppError::BadRequest(
+                        "Bus pipeline vertical_rise must be a finite number".to_string(),
+                    ));
+                }
+                let mut segment =
+                    Pipeline::new(numeric_pipeline_id, capacity.clone(), item_enum, quantity)
+                        .with_pumps(pumps_mk1, pumps_mk2);
+                segment.vertical_rise = vertical_rise;
+                bus.add_pipeline(segment);
+
+                pipeline_ids.push(pipeline_label);
             }
 
-            if bus.lines.is_empty() && bus.pipelines.is_empty() {
+            if bus.lines.is_empty() && bus.lifts.is_empty() && bus.pipelines.is_empty() {
                 return Err(AppError::BadRequest(
                     "Bus transport requires at least one conveyor or pipeline".to_string(),
                 ));
             }
 
-            let details = serde_json::to_string(&json!({
-                "bus_id": bus_id,
-                "bus_name": name,
-                "conveyors": conveyor_details,
-                "pipelines": pipeline_details,
-            }))?;
+            let details = TransportDetails::Bus {
+                conveyor_ids,
+                lift_ids,
+                pipeline_ids,
+            };
 
             Ok((TransportType::Bus(bus), details))
         }
@@ -471,7 +784,7 @@ fn build_transport(
             });
             let train_id = existing_train
                 .map(|train| train.train_id)
-                .unwrap_or_else(|| next_transport_identifier(engine));
+                .unwrap_or_else(|| engine.next_transport_number(TransportKind::Train));
             let provided_name = train_name
                 .as_ref()
                 .map(|val| val.trim())
@@ -479,10 +792,10 @@ fn build_transport(
                 .map(|val| val.to_string());
             let name = provided_name
                 .or_else(|| existing_train.map(|train| train.train_name.clone()))
-                .unwrap_or_else(|| sanitize_name(None, "Train", train_id));
+                .unwrap_or_else(|| engine.default_transport_name(TransportKind::Train, train_id));
             let mut train = Train::new(train_id, name.clone());
 
-            let mut wagon_details = Vec::new();
+            let mut wagon_ids = Vec::new();
             for (index, wagon) in wagons.into_iter().enumerate() {
                 let TrainWagonRequest {
                     wagon_id,
@@ -510,35 +823,22 @@ fn build_transport(
                     quantity,
                 ));
 
-                wagon_details.push(json!({
-                    "wagon_id": wagon_label,
-                    "wagon_type": wagon_type_enum,
-                    "item": item_enum,
-                    "quantity_per_min": quantity,
-                }));
+                wagon_ids.push(wagon_label);
             }
 
-            if wagon_details.is_empty() {
+            if wagon_ids.is_empty() {
                 return Err(AppError::BadRequest(
                     "Train transport requires at least one wagon".to_string(),
                 ));
             }
 
-            let details = serde_json::to_string(&json!({
-                "train_id": train_id,
-                "train_name": name,
-                "wagons": wagon_details,
-            }))?;
+            let details = TransportDetails::Train { wagon_ids };
 
             Ok((TransportType::Train(train), details))
         }
     }
 }
 
-fn next_transport_identifier(engine: &SatisflowEngine) -> u64 {
-    engine.get_all_logistics().len() as u64 + 1
-}
-
 fn parse_numeric_identifier(value: Option<&str>, fallback: u64) -> u64 {
     value
         .and_then(|raw| {
@@ -552,15 +852,7 @@ fn parse_numeric_identifier(value: Option<&str>, fallback: u64) -> u64 {
         .unwrap_or(fallback)
 }
 
-fn sanitize_name(value: Option<&str>, label: &str, id: u64) -> String {
-    value
-        .map(|val| val.trim())
-        .filter(|val| !val.is_empty())
-        .map(|val| val.to_string())
-        .unwrap_or_else(|| format!("{} {}", label, id))
-}
-
-fn ensure_positive(value: f32, context: &str) -> std::result::Result<f32, AppError> {
+fn ensure_positive(value: f64, context: &str) -> std::result::Result<f64, AppError> {
     if value > 0.0 {
         Ok(value)
     } else {
@@ -570,9 +862,55 @@ fn ensure_positive(value: f32, context: &str) -> std::result::Result<f32, AppErr
     }
 }
 
+pub async fn reverse_logistics(
+    State(state): State<AppState>,
+    Path(id): Path<LogisticsId>,
+) -> Result<Json<LogisticsResponse>> {
+    let mut engine = state.engine.write().await;
+
+    engine
+        .reverse_logistics_line(id)
+        .map_err(|_| AppError::NotFound(format!("Logistics line with id {} not found", id)))?;
+    let engine = engine.commit();
+
+    let reversed = engine
+        .get_logistics_line(id)
+        .ok_or_else(|| AppError::NotFound(format!("Logistics line with id {} not found", id)))?;
+
+    Ok(Json(logistics_to_response(&engine, reversed)))
+}
+
+pub async fn split_logistics(
+    State(state): State<AppState>,
+    Path(id): Path<LogisticsId>,
+) -> Result<Json<Vec<LogisticsResponse>>> {
+    let mut engine = state.engine.write().await;
+
+    if engine.get_logistics_line(id).is_none() {
+        return Err(AppError::NotFound(format!(
+            "Logistics line with id {} not found",
+            id
+        )));
+    }
+
+    let ids = engine
+        .split_logistics_line(id)
+        .map_err(|e| AppError::BadRequest(format!("Failed to split logistics line: {}", e)))?;
+
+    let responses = ids
+        .into_iter()
+        .filter_map(|line_id| engine.get_logistics_line(line_id))
+        .map(|line| logistics_to_response(&engine, line))
+        .collect();
+
+    engine.commit();
+
+    Ok(Json(responses))
+}
+
 pub async fn delete_logistics(
     State(state): State<AppState>,
-    Path(id): Path<Uuid>,
+    Path(id): Path<LogisticsId>,
 ) -> Result<StatusCode> {
     let mut engine = state.engine.write().await;
 
@@ -580,16 +918,73 @@ pub async fn delete_logistics(
         .delete_logistics_line(id)
         .map_err(|_| AppError::NotFound(format!("Logistics line with id {} not found", id)))?;
 
+    engine.commit();
+
     Ok(StatusCode::NO_CONTENT)
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct UpdateTransportNamingRequest {
+    pub bus: Option<String>,
+    pub train: Option<String>,
+    pub truck: Option<String>,
+    pub drone: Option<String>,
+}
+
+pub async fn get_transport_naming(State(state): State<AppState>) -> Result<Json<TransportNaming>> {
+    let engine = state.engine.snapshot();
+    Ok(Json(engine.transport_naming().clone()))
+}
+
+pub async fn update_transport_naming(
+    State(state): State<AppState>,
+    Json(request): Json<UpdateTransportNamingRequest>,
+) -> Result<Json<TransportNaming>> {
+    let mut engine = state.engine.write().await;
+
+    let updates = [
+        (TransportKind::Bus, request.bus),
+        (TransportKind::Train, request.train),
+        (TransportKind::Truck, request.truck),
+        (TransportKind::Drone, request.drone),
+    ];
+
+    // Validate every pattern before applying any of them
+    let mut naming = engine.transport_naming().clone();
+    for (kind, pattern) in updates {
+        if let Some(pattern) = pattern {
+            naming.set_pattern(kind, pattern).map_err(|e| {
+                AppError::ValidationError(format!("Invalid {} naming pattern: {}", kind, e))
+            })?;
+        }
+    }
+
+    for kind in TransportKind::all() {
+        engine
+            .set_transport_naming_pattern(kind, naming.pattern(kind))
+            .map_err(|e| AppError::EngineError(e.to_string()))?;
+    }
+
+    let engine = engine.commit();
+
+    Ok(Json(engine.transport_naming().clone()))
+}
+
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/", get(get_logistics).post(create_logistics))
+        .route(
+            "/naming",
+            get(get_transport_naming).put(update_transport_naming),
+        )
         .route(
             "/:id",
             get(get_logistics_line)
                 .put(update_logistics)
+                .patch(patch_logistics)
                 .delete(delete_logistics),
         )
+        .route("/:id/reverse", post(reverse_logistics))
+        .route("/:id/split", post(split_logistics))
+        .route("/:id/train-throughput", get(get_train_throughput))
 }
@@ -1,8 +1,60 @@
 // crates/satisflow-server/src/handlers/mod.rs
+use axum::Router;
+
+use crate::state::AppState;
+
+pub mod analysis;
+pub mod audit;
 pub mod blueprint;
 pub mod blueprint_templates;
 pub mod dashboard;
+pub mod dev;
+pub mod export;
 pub mod factory;
+pub mod factory_templates;
 pub mod game_data;
+pub mod goals;
+pub mod graphql;
+pub mod import;
 pub mod logistics;
+pub mod pagination;
+pub mod patch;
+pub mod power_grids;
+pub mod recent_changes;
+pub mod resource_nodes;
 pub mod save_load;
+pub mod scenarios;
+pub mod scripts;
+pub mod search;
+pub mod snapshots;
+pub mod tier_progress;
+pub mod workspaces;
+
+/// Routes working on one workspace's engine
+///
+/// Served at `/api` for the default workspace and under
+/// `/api/workspaces/{id}` for the others.
+pub fn engine_routes() -> Router<AppState> {
+    Router::new()
+        .nest("/api/factories", factory::routes())
+        .nest("/api/factory-templates", factory_templates::routes())
+        .nest("/api/logistics", logistics::routes())
+        .nest("/api/dashboard", dashboard::routes())
+        .nest("/api/game-data", game_data::routes())
+        .nest("/api/resource-nodes", resource_nodes::routes())
+        .nest("/api/power-grids", power_grids::routes())
+        .nest("/api/goals", goals::routes())
+        .nest("/api/graphql", graphql::routes())
+        .nest("/api/recent-changes", recent_changes::routes())
+        .nest("/api/audit", audit::routes())
+        .nest("/api/scripts", scripts::routes())
+        .nest("/api/search", search::routes())
+        .nest("/api/tier-progress", tier_progress::routes())
+        .nest("/api/analysis", analysis::routes())
+        .nest("/api/export", export::routes())
+        .nest("/api/import", import::routes())
+        .nest("/api/snapshots", snapshots::routes())
+        .nest("/api", save_load::routes())
+        .nest("/api", blueprint_templates::routes())
+        .nest("/api", blueprint::routes())
+}
@@ -0,0 +1,151 @@
+// crates/satisflow-server/src/handlers/pagination.rs
+//! Paging shared by the list endpoints
+//!
+//! Lists are returned whole unless `page` or `per_page` is given, in which
+//! case they come wrapped in a [`Page`] envelope.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{AppError, Result};
+
+pub const DEFAULT_PER_PAGE: usize = 50;
+pub const MAX_PER_PAGE: usize = 500;
+
+/// Sort direction of a list
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    /// Apply the direction to an ascending comparison
+    pub fn apply(self, ordering: std::cmp::Ordering) -> std::cmp::Ordering {
+        match self {
+            SortOrder::Asc => ordering,
+            SortOrder::Desc => ordering.reverse(),
+        }
+    }
+}
+
+/// One page of a list
+#[derive(Debug, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Page number, starting at 1
+    pub page: usize,
+    pub per_page: usize,
+    /// Number of entries across all pages
+    pub total: usize,
+    pub total_pages: usize,
+}
+
+/// A whole list, or one page of it when paging was asked for
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum Listing<T> {
+    All(Vec<T>),
+    Paged(Page<T>),
+}
+
+impl<T> Listing<T> {
+    /// Convert the entries, e.g. into responses once the page is known
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Listing<U> {
+        match self {
+            Listing::All(entries) => Listing::All(entries.into_iter().map(f).collect()),
+            Listing::Paged(page) => Listing::Paged(Page {
+                items: page.items.into_iter().map(f).collect(),
+                page: page.page,
+                per_page: page.per_page,
+                total: page.total,
+                total_pages: page.total_pages,
+            }),
+        }
+    }
+}
+
+/// Keep the entries of the requested page
+///
+/// # Arguments
+///
+/// * `entries` - Every entry, already filtered and sorted
+/// * `page` - Page number, starting at 1
+/// * `per_page` - Entries per page, at most [`MAX_PER_PAGE`]
+///
+/// # Returns
+///
+/// The whole list when neither `page` nor `per_page` is given, or a `400 Bad
+/// Request` when either is out of range
+pub fn paginate<T>(
+    entries: Vec<T>,
+    page: Option<usize>,
+    per_page: Option<usize>,
+) -> Result<Listing<T>> {
+    if page.is_none() && per_page.is_none() {
+        return Ok(Listing::All(entries));
+    }
+
+    let page = page.unwrap_or(1);
+    let per_page = per_page.unwrap_or(DEFAULT_PER_PAGE);
+    if page == 0 {
+        return Err(AppError::ValidationError("page starts at 1".to_string()));
+    }
+    if !(1..=MAX_PER_PAGE).contains(&per_page) {
+        return Err(AppError::ValidationError(format!(
+            "per_page must be between 1 and {}",
+            MAX_PER_PAGE
+        )));
+    }
+
+    let total = entries.len();
+    let items = entries
+        .into_iter()
+        .skip((page - 1).saturating_mul(per_page))
+        .take(per_page)
+        .collect();
+    Ok(Listing::Paged(Page {
+        items,
+        page,
+        per_page,
+        total,
+        total_pages: total.div_ceil(per_page),
+    }))
+}
+
+/// Case-insensitive substring match
+pub fn contains_ignore_case(text: &str, needle: &str) -> bool {
+    text.to_lowercase().contains(&needle.trim().to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paginate() {
+        let entries: Vec<u32> = (1..=7).collect();
+
+        let Listing::All(all) = paginate(entries.clone(), None, None).unwrap() else {
+            panic!("expected the whole list");
+        };
+        assert_eq!(all.len(), 7);
+
+        let Listing::Paged(page) = paginate(entries.clone(), Some(3), Some(3)).unwrap() else {
+            panic!("expected a page");
+        };
+        assert_eq!(page.items, [7]);
+        assert_eq!(page.total, 7);
+        assert_eq!(page.total_pages, 3);
+
+        let Listing::Paged(page) = paginate(entries.clone(), Some(5), None).unwrap() else {
+            panic!("expected a page");
+        };
+        assert!(page.items.is_empty());
+        assert_eq!(page.per_page, DEFAULT_PER_PAGE);
+
+        assert!(paginate(entries.clone(), Some(0), None).is_err());
+        assert!(paginate(entries, None, Some(MAX_PER_PAGE + 1)).is_err());
+    }
+}
@@ -0,0 +1,85 @@
+// crates/satisflow-server/src/handlers/patch.rs
+//! JSON merge patches (RFC 7386) for the PATCH endpoints
+//!
+//! A PATCH handler turns the current entity into its PUT payload, merges the
+//! request body into it and goes through the PUT code path, so both verbs
+//! validate the same way.
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::error::{AppError, Result};
+
+/// Merge `patch` into `target`
+///
+/// Objects are merged member by member, `null` removes a member and any other
+/// value, arrays included, replaces the current one.
+pub fn merge_patch(target: &mut Value, patch: Value) {
+    match patch {
+        Value::Object(members) => {
+            if !target.is_object() {
+                *target = Value::Object(Default::default());
+            }
+            let fields = target.as_object_mut().expect("replaced by an object above");
+            for (key, value) in members {
+                if value.is_null() {
+                    fields.remove(&key);
+                } else {
+                    merge_patch(fields.entry(key).or_insert(Value::Null), value);
+                }
+            }
+        }
+        other => *target = other,
+    }
+}
+
+/// Merge `patch` into `current` and read the result as a PUT payload
+///
+/// # Returns
+///
+/// The payload, or a `400 Bad Request` if the body isn't a JSON object or the
+/// merged payload is invalid
+pub fn patched_payload<T: DeserializeOwned>(mut current: Value, patch: Value) -> Result<T> {
+    if !patch.is_object() {
+        return Err(AppError::BadRequest(
+            "PATCH body must be a JSON object".to_string(),
+        ));
+    }
+    merge_patch(&mut current, patch);
+    serde_json::from_value(current)
+        .map_err(|e| AppError::BadRequest(format!("Invalid update: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_merge_patch() {
+        let mut target = json!({
+            "name": "Smelter",
+            "description": "Iron",
+            "groups": [{ "count": 1 }, { "count": 2 }],
+            "nested": { "a": 1, "b": 2 },
+        });
+        merge_patch(
+            &mut target,
+            json!({
+                "description": null,
+                "groups": [{ "count": 3 }],
+                "nested": { "b": 5, "c": 6 },
+            }),
+        );
+        assert_eq!(
+            target,
+            json!({
+                "name": "Smelter",
+                "groups": [{ "count": 3 }],
+                "nested": { "a": 1, "b": 5, "c": 6 },
+            })
+        );
+
+        assert!(patched_payload::<Value>(json!({}), json!([1])).is_err());
+    }
+}
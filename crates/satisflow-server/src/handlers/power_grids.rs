@@ -0,0 +1,145 @@
+// crates/satisflow-server/src/handlers/power_grids.rs
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, put},
+    Json, Router,
+};
+use satisflow_engine::models::{FactoryId, GridPowerStats, PowerGrid, PowerGridId};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{AppError, Result},
+    state::AppState,
+};
+
+#[derive(Deserialize)]
+pub struct CreatePowerGridRequest {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct UpdatePowerGridRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct PowerGridResponse {
+    #[serde(flatten)]
+    pub grid: PowerGrid,
+    pub generation: f64,
+    pub consumption: f64,
+    pub balance: f64,
+    pub factory_ids: Vec<FactoryId>,
+}
+
+fn build_power_grid_response(grid: &PowerGrid, stats: &[GridPowerStats]) -> PowerGridResponse {
+    let grid_stats = stats.iter().find(|s| s.grid_id == Some(grid.id));
+    PowerGridResponse {
+        grid: grid.clone(),
+        generation: grid_stats.map_or(0.0, |s| s.generation),
+        consumption: grid_stats.map_or(0.0, |s| s.consumption),
+        balance: grid_stats.map_or(0.0, |s| s.balance),
+        factory_ids: grid_stats.map_or_else(Vec::new, |s| s.factory_ids.clone()),
+    }
+}
+
+pub async fn get_power_grids(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<PowerGridResponse>>> {
+    let engine = state.engine.snapshot();
+
+    let stats = engine.grid_power_stats();
+    let mut grids: Vec<PowerGridResponse> = engine
+        .get_all_power_grids()
+        .values()
+        .map(|grid| build_power_grid_response(grid, &stats))
+        .collect();
+    grids.sort_by(|a, b| a.grid.name.cmp(&b.grid.name));
+
+    Ok(Json(grids))
+}
+
+pub async fn create_power_grid(
+    State(state): State<AppState>,
+    Json(request): Json<CreatePowerGridRequest>,
+) -> Result<(StatusCode, Json<PowerGridResponse>)> {
+    let name = request.name.trim();
+    if name.is_empty() {
+        return Err(AppError::BadRequest(
+            "Power grid name cannot be empty".to_string(),
+        ));
+    }
+
+    let mut engine = state.engine.write().await;
+    let grid_id = engine.create_power_grid(name.to_string(), request.description);
+
+    let grid = engine.get_power_grid(grid_id).ok_or_else(|| {
+        AppError::InternalError(anyhow::anyhow!("Failed to retrieve created power grid"))
+    })?;
+    let response = build_power_grid_response(grid, &engine.grid_power_stats());
+
+    engine.commit();
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+pub async fn update_power_grid(
+    State(state): State<AppState>,
+    Path(id): Path<PowerGridId>,
+    Json(request): Json<UpdatePowerGridRequest>,
+) -> Result<Json<PowerGridResponse>> {
+    let mut engine = state.engine.write().await;
+
+    {
+        let grid = engine
+            .get_power_grid_mut(id)
+            .ok_or_else(|| AppError::NotFound(format!("Power grid with id {} not found", id)))?;
+
+        if let Some(name) = request.name {
+            let name = name.trim();
+            if name.is_empty() {
+                return Err(AppError::BadRequest(
+                    "Power grid name cannot be empty".to_string(),
+                ));
+            }
+            grid.name = name.to_string();
+        }
+
+        if let Some(description) = request.description {
+            grid.description = Some(description);
+        }
+    }
+
+    let grid = engine
+        .get_power_grid(id)
+        .ok_or_else(|| AppError::NotFound(format!("Power grid with id {} not found", id)))?;
+    let response = build_power_grid_response(grid, &engine.grid_power_stats());
+
+    engine.commit();
+
+    Ok(Json(response))
+}
+
+pub async fn delete_power_grid(
+    State(state): State<AppState>,
+    Path(id): Path<PowerGridId>,
+) -> Result<StatusCode> {
+    let mut engine = state.engine.write().await;
+
+    engine
+        .delete_power_grid(id)
+        .map_err(|_| AppError::NotFound(format!("Power grid with id {} not found", id)))?;
+
+    engine.commit();
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_power_grids).post(create_power_grid))
+        .route("/:id", put(update_power_grid).delete(delete_power_grid))
+}
@@ -0,0 +1,43 @@
+// crates/satisflow-server/src/handlers/recent_changes.rs
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Json, Router,
+};
+use satisflow_engine::models::RecentChange;
+use serde::Deserialize;
+
+use crate::{
+    error::{AppError, Result},
+    state::AppState,
+};
+
+const DEFAULT_LIMIT: usize = 50;
+const MAX_LIMIT: usize = 500;
+
+/// Query parameters for the recent changes feed
+#[derive(Debug, Default, Deserialize)]
+pub struct RecentChangesQuery {
+    /// Maximum number of entries to return; defaults to 50, capped at 500
+    pub limit: Option<usize>,
+}
+
+pub async fn get_recent_changes(
+    State(state): State<AppState>,
+    Query(query): Query<RecentChangesQuery>,
+) -> Result<Json<Vec<RecentChange>>> {
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT);
+    if limit == 0 {
+        return Err(AppError::BadRequest(
+            "limit must be greater than 0".to_string(),
+        ));
+    }
+
+    let engine = state.engine.snapshot();
+
+    Ok(Json(engine.recent_changes(limit.min(MAX_LIMIT))))
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/", get(get_recent_changes))
+}
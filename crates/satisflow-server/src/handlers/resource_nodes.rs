@@ -0,0 +1,74 @@
+// crates/satisflow-server/src/handlers/resource_nodes.rs
+use axum::{
+    extract::{Path, State},
+    routing::{get, put},
+    Json, Router,
+};
+use satisflow_engine::models::{Item, NodeCounts, ResourceNodeReport};
+use serde::Serialize;
+
+use crate::{
+    error::{AppError, Result},
+    state::AppState,
+};
+
+#[derive(Serialize)]
+pub struct ResourceNodeEntry {
+    pub item: Item,
+    #[serde(flatten)]
+    pub counts: NodeCounts,
+    pub total: u32,
+}
+
+pub async fn get_resource_nodes(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<ResourceNodeEntry>>> {
+    let engine = state.engine.snapshot();
+
+    let entries = engine
+        .resource_nodes()
+        .entries()
+        .into_iter()
+        .map(|(item, counts)| ResourceNodeEntry {
+            item,
+            counts,
+            total: counts.total(),
+        })
+        .collect();
+
+    Ok(Json(entries))
+}
+
+pub async fn update_resource_nodes(
+    State(state): State<AppState>,
+    Path(item): Path<Item>,
+    Json(counts): Json<NodeCounts>,
+) -> Result<Json<ResourceNodeEntry>> {
+    let mut engine = state.engine.write().await;
+
+    engine
+        .set_resource_node_counts(item, counts)
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+    engine.commit();
+
+    Ok(Json(ResourceNodeEntry {
+        item,
+        counts,
+        total: counts.total(),
+    }))
+}
+
+pub async fn get_resource_node_report(
+    State(state): State<AppState>,
+) -> Result<Json<ResourceNodeReport>> {
+    let engine = state.engine.snapshot();
+
+    Ok(Json(engine.resource_node_report()))
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_resource_nodes))
+        .route("/report", get(get_resource_node_report))
+        .route("/:item", put(update_resource_nodes))
+}
@@ -3,29 +3,65 @@
 //! Provides endpoints for saving and loading the entire engine state.
 
 use axum::{
-    extract::State,
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Response},
     routing::{get, post},
     Json, Router,
 };
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
 use serde::{Deserialize, Serialize};
 
-use crate::{error::AppError, state::AppState};
-use satisflow_engine::{SatisflowEngine, SaveFile, SaveFileSummary};
+use crate::{
+    autosave::{self, AutosaveEntry},
+    error::AppError,
+    state::AppState,
+};
+use satisflow_engine::{
+    compression, models::Diagnostic, DowngradeReport, LoadIssue, LoadOptions, LoadedSave,
+    RepairReport, SatisflowEngine, SaveDiff, SaveFile, SaveFileSummary, SaveVersion,
+    SeasonOverview, SeasonSnapshot,
+};
 
 /// Request body for loading a save file
 #[derive(Debug, Deserialize)]
 pub struct LoadRequest {
-    /// JSON string of the save file
+    /// JSON string of the save file, or base64 gzip with `compressed=true`
     pub save_data: String,
 }
 
+/// Query parameters for the load endpoint
+#[derive(Debug, Default, Deserialize)]
+pub struct LoadQuery {
+    /// `save_data` is a base64-encoded gzip file, as returned by `GET /api/save?compressed=true`
+    #[serde(default)]
+    pub compressed: bool,
+    /// Reject unknown items, recipes and fields instead of working around them
+    #[serde(default)]
+    pub strict: bool,
+}
+
+/// Query parameters for the save endpoint
+#[derive(Debug, Default, Deserialize)]
+pub struct SaveQuery {
+    /// Save format version to write (e.g. "0.1.0"); defaults to the engine version
+    pub target_version: Option<String>,
+    /// Return `save_data` as a base64-encoded gzip file
+    #[serde(default)]
+    pub compressed: bool,
+}
+
 /// Response for save endpoint
 #[derive(Debug, Serialize)]
 pub struct SaveResponse {
-    /// The save file as JSON string
+    /// The save file as JSON string, or base64 gzip when `compressed` is set
     pub save_data: String,
+    pub compressed: bool,
     /// Summary information about the save
     pub summary: SaveFileSummary,
+    /// What was dropped to save as an older version (only with `target_version`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub downgrade_report: Option<DowngradeReport>,
 }
 
 /// Response for successful load
@@ -35,6 +71,116 @@ pub struct LoadResponse {
     pub message: String,
     /// Summary of loaded save file
     pub summary: SaveFileSummary,
+    /// What a lenient load worked around (never set by a strict load)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<LoadIssue>,
+}
+
+/// Request body for comparing two saves
+#[derive(Debug, Deserialize)]
+pub struct SaveDiffRequest {
+    /// JSON string of the older save file
+    pub before: String,
+    /// JSON string of the newer save file
+    pub after: String,
+}
+
+/// Request body for repairing a save file
+#[derive(Debug, Deserialize)]
+pub struct RepairRequest {
+    /// JSON string of the damaged save file
+    pub save_data: String,
+}
+
+/// Response for the repair endpoint
+#[derive(Debug, Serialize)]
+pub struct RepairResponse {
+    /// The repaired save file as JSON string, ready for `POST /api/load`
+    pub save_data: String,
+    /// Summary of the repaired save
+    pub summary: SaveFileSummary,
+    /// What was fixed
+    pub report: RepairReport,
+}
+
+/// Request body for validating a save file
+#[derive(Debug, Deserialize)]
+pub struct ValidateRequest {
+    /// JSON string of the save file to check
+    pub save_data: String,
+}
+
+/// Query parameters for the validate endpoint
+#[derive(Debug, Default, Deserialize)]
+pub struct ValidateQuery {
+    /// Check as a strict load would, see `POST /api/load`
+    #[serde(default)]
+    pub strict: bool,
+}
+
+/// Response for the validate endpoint
+#[derive(Debug, Serialize)]
+pub struct ValidateResponse {
+    /// Whether `POST /api/load` would accept the save
+    pub loadable: bool,
+    /// Version the save was written with, when it has one
+    pub save_version: Option<String>,
+    /// Version of this engine
+    pub engine_version: String,
+    /// Why the save can't be loaded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Summary of the save, when it loads
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<SaveFileSummary>,
+    /// What loading works around (never set when strict)
+    pub load_warnings: Vec<LoadIssue>,
+    /// Problems found in the loaded save, most severe first
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Response for the autosave listing
+#[derive(Debug, Serialize)]
+pub struct AutosaveListResponse {
+    /// Whether the server autosaves (`AUTOSAVE_DIR` is set)
+    pub enabled: bool,
+    /// Seconds between autosaves, when enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval_secs: Option<u64>,
+    /// Autosave files, newest first
+    pub autosaves: Vec<AutosaveEntry>,
+}
+
+/// One save file of a season overview
+#[derive(Debug, Deserialize)]
+pub struct SeasonSave {
+    /// Name shown for this snapshot, e.g. "Week 3"
+    pub label: String,
+    /// JSON string of the save file
+    pub save_data: String,
+}
+
+/// Request body for the season overview
+#[derive(Debug, Deserialize)]
+pub struct SeasonOverviewRequest {
+    /// Save files of the same world, oldest first
+    pub saves: Vec<SeasonSave>,
+}
+
+/// Output format of the season overview
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SeasonFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+/// Query parameters for the season overview endpoint
+#[derive(Debug, Default, Deserialize)]
+pub struct SeasonOverviewQuery {
+    #[serde(default)]
+    pub format: SeasonFormat,
 }
 
 /// Response for reset endpoint
@@ -48,17 +194,42 @@ pub struct ResetResponse {
 ///
 /// Saves the current engine state and returns it as JSON
 ///
+/// # Query Parameters
+///
+/// - `target_version` - optional "save as" version; fields introduced after it
+///   are left out so older clients can load the file
+/// - `compressed` - return the save as base64-encoded gzip, several times smaller
+///
 /// # Returns
 ///
-/// - `200 OK` with save data and summary
+/// - `200 OK` with save data and summary, plus a downgrade report when a target
+///   version is given
+/// - `400 Bad Request` if the target version is invalid or unsupported
+/// - `403 Forbidden` on a demo server
 /// - `500 Internal Server Error` if save fails
-pub async fn save_engine(State(state): State<AppState>) -> Result<Json<SaveResponse>, AppError> {
-    let engine = state.engine.read().await;
-
-    // Save to JSON string
-    let save_json = engine
-        .save_to_json()
-        .map_err(|e| AppError::EngineError(e.to_string()))?;
+pub async fn save_engine(
+    State(state): State<AppState>,
+    Query(query): Query<SaveQuery>,
+) -> Result<Json<SaveResponse>, AppError> {
+    reject_in_demo_mode(&state)?;
+    let engine = state.engine.snapshot();
+
+    let (save_json, downgrade_report) = match query.target_version.as_deref() {
+        Some(target_version) => {
+            let target = SaveVersion::parse(target_version)
+                .map_err(|e| AppError::BadRequest(e.to_string()))?;
+            let export = engine
+                .save_as_version(&target)
+                .map_err(|e| AppError::BadRequest(e.to_string()))?;
+            (export.save_data, Some(export.report))
+        }
+        None => {
+            let save_json = engine
+                .save_to_json()
+                .map_err(|e| AppError::EngineError(e.to_string()))?;
+            (save_json, None)
+        }
+    };
 
     // Parse to get summary
     let save_file: SaveFile =
@@ -66,9 +237,17 @@ pub async fn save_engine(State(state): State<AppState>) -> Result<Json<SaveRespo
 
     let summary = save_file.summary();
 
+    let save_data = if query.compressed {
+        BASE64.encode(compression::gzip(save_json.as_bytes()))
+    } else {
+        save_json
+    };
+
     Ok(Json(SaveResponse {
-        save_data: save_json,
+        save_data,
+        compressed: query.compressed,
         summary,
+        downgrade_report,
     }))
 }
 
@@ -84,28 +263,52 @@ pub async fn save_engine(State(state): State<AppState>) -> Result<Json<SaveRespo
 /// }
 /// ```
 ///
+/// # Query Parameters
+///
+/// - `compressed` - `save_data` is base64-encoded gzip instead of JSON
+/// - `strict` - reject saves from a newer version and unknown items, recipes
+///   and fields, naming the JSON path of the first one; by default entries
+///   naming unknown items or recipes are removed, unknown fields are kept and
+///   each is listed in `warnings`
+///
 /// # Returns
 ///
-/// - `200 OK` with success message and summary
-/// - `400 Bad Request` if JSON is invalid or version incompatible
+/// - `200 OK` with success message, summary and any warnings
+/// - `400 Bad Request` if JSON is invalid, version incompatible or a strict
+///   load finds something unknown
+/// - `403 Forbidden` on a demo server
 /// - `500 Internal Server Error` if load fails
 pub async fn load_engine(
     State(state): State<AppState>,
+    Query(query): Query<LoadQuery>,
     Json(request): Json<LoadRequest>,
 ) -> Result<Json<LoadResponse>, AppError> {
-    // Attempt to load the engine from JSON
-    let new_engine = SatisflowEngine::load_from_json(&request.save_data)
-        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+    reject_in_demo_mode(&state)?;
 
-    // Get summary before replacing
-    let save_file: SaveFile = serde_json::from_str(&request.save_data)
-        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+    let save_json = if query.compressed {
+        let bytes = BASE64
+            .decode(request.save_data.trim())
+            .map_err(|e| AppError::BadRequest(format!("Invalid base64 save data: {}", e)))?;
+        compression::decode_save(&bytes).map_err(|e| AppError::BadRequest(e.to_string()))?
+    } else {
+        request.save_data
+    };
 
-    let summary = save_file.summary();
+    // Attempt to load the engine from JSON
+    let options = LoadOptions {
+        strict: query.strict,
+    };
+    let LoadedSave {
+        engine: new_engine,
+        summary,
+        warnings,
+    } = SatisflowEngine::load_from_json_with_options(&save_json, &options)
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
 
     // Replace the engine state
     let mut engine = state.engine.write().await;
-    *engine = new_engine;
+    engine.replace(new_engine);
+    engine.commit();
 
     Ok(Json(LoadResponse {
         message: format!(
@@ -113,9 +316,20 @@ pub async fn load_engine(
             summary.version, summary.factory_count, summary.logistics_count
         ),
         summary,
+        warnings,
     }))
 }
 
+/// Saving and loading are disabled on public demo servers
+fn reject_in_demo_mode(state: &AppState) -> Result<(), AppError> {
+    if state.demo_mode {
+        return Err(AppError::Forbidden(
+            "Saving and loading are disabled in demo mode".to_string(),
+        ));
+    }
+    Ok(())
+}
+
 /// POST /api/reset
 ///
 /// Resets the engine to an empty state (clears all factories and logistics lines)
@@ -130,6 +344,7 @@ pub async fn reset_engine(State(state): State<AppState>) -> Result<Json<ResetRes
     engine
         .reset()
         .map_err(|e| AppError::EngineError(e.to_string()))?;
+    engine.commit();
 
     Ok(Json(ResetResponse {
         message: "Engine reset successfully - all factories and logistics lines have been cleared"
@@ -137,24 +352,211 @@ pub async fn reset_engine(State(state): State<AppState>) -> Result<Json<ResetRes
     }))
 }
 
+/// POST /api/season-overview
+///
+/// Compares several save files of the same world and returns trend statistics.
+/// The current engine state is left untouched.
+///
+/// # Query Parameters
+///
+/// - `format` - `json` (default) or `csv` for one row per save
+///
+/// # Returns
+///
+/// - `200 OK` with the overview as JSON, or as `text/csv`
+/// - `400 Bad Request` if no save is given or a save cannot be loaded
+pub async fn season_overview(
+    Query(query): Query<SeasonOverviewQuery>,
+    Json(request): Json<SeasonOverviewRequest>,
+) -> Result<Response, AppError> {
+    if request.saves.is_empty() {
+        return Err(AppError::BadRequest(
+            "At least one save file is required".to_string(),
+        ));
+    }
+
+    let snapshots = request
+        .saves
+        .into_iter()
+        .map(|save| {
+            SeasonSnapshot::from_save_json(save.label.clone(), &save.save_data).map_err(|e| {
+                AppError::BadRequest(format!("Failed to load save '{}': {}", save.label, e))
+            })
+        })
+        .collect::<Result<Vec<_>, AppError>>()?;
+
+    let overview = SeasonOverview::build(snapshots);
+
+    Ok(match query.format {
+        SeasonFormat::Json => Json(overview).into_response(),
+        SeasonFormat::Csv => (
+            [(header::CONTENT_TYPE, "text/csv; charset=utf-8")],
+            overview.to_csv(),
+        )
+            .into_response(),
+    })
+}
+
+/// POST /api/saves/diff
+///
+/// Compares two save files of the same world. The current engine state is
+/// left untouched.
+///
+/// # Returns
+///
+/// - `200 OK` with factories and logistics lines added, removed or modified,
+///   and the change of every item's net rate
+/// - `400 Bad Request` if a save cannot be loaded
+pub async fn diff_saves(Json(request): Json<SaveDiffRequest>) -> Result<Json<SaveDiff>, AppError> {
+    let parse = |label: &str, json: &str| -> Result<SaveFile, AppError> {
+        // Loading the engine checks the save version first
+        SatisflowEngine::load_from_json(json)
+            .and_then(|_| Ok(serde_json::from_str::<SaveFile>(json)?))
+            .map_err(|e| AppError::BadRequest(format!("Failed to load '{}' save: {}", label, e)))
+    };
+    let before = parse("before", &request.before)?;
+    let after = parse("after", &request.after)?;
+
+    Ok(Json(before.diff(&after)))
+}
+
+/// POST /api/saves/repair
+///
+/// Fixes the recoverable corruption of a save file: logistics lines
+/// connecting a missing factory are removed, duplicate or mismatched IDs
+/// regenerated, rates saved as `null` (NaN) zeroed and missing required
+/// sections defaulted. The current engine state is left untouched; load the
+/// returned save to use it.
+///
+/// # Returns
+///
+/// - `200 OK` with the repaired save, its summary and what was fixed
+/// - `400 Bad Request` if the save isn't JSON, is from another major version
+///   or is still unreadable after the repair
+/// - `500 Internal Server Error` if the repaired save can't be written
+pub async fn repair_save(
+    Json(request): Json<RepairRequest>,
+) -> Result<Json<RepairResponse>, AppError> {
+    let (engine, report) = SatisflowEngine::repair(&request.save_data)
+        .map_err(|e| AppError::BadRequest(format!("Failed to repair save: {}", e)))?;
+    let save_file = SaveFile::new(engine);
+    let save_data = serde_json::to_string_pretty(&save_file).map_err(|e| {
+        AppError::InternalError(anyhow::anyhow!("Failed to write repaired save: {}", e))
+    })?;
+
+    Ok(Json(RepairResponse {
+        save_data,
+        summary: save_file.summary(),
+        report,
+    }))
+}
+
+/// POST /api/saves/validate
+///
+/// Checks a save file before importing it, e.g. one shared by another
+/// player: its version, what loading it would work around, and the problems
+/// found in it. The current engine state is left untouched.
+///
+/// # Query Parameters
+///
+/// - `strict` - check as a strict load would
+///
+/// # Returns
+///
+/// - `200 OK` with the report, also when the save can't be loaded
+pub async fn validate_save(
+    Query(query): Query<ValidateQuery>,
+    Json(request): Json<ValidateRequest>,
+) -> Json<ValidateResponse> {
+    let save_version = serde_json::from_str::<serde_json::Value>(&request.save_data)
+        .ok()
+        .and_then(|value| value["version"].as_str().map(String::from));
+    let options = LoadOptions {
+        strict: query.strict,
+    };
+    let mut response = ValidateResponse {
+        loadable: false,
+        save_version,
+        engine_version: SaveVersion::current().to_string(),
+        error: None,
+        summary: None,
+        load_warnings: Vec::new(),
+        diagnostics: Vec::new(),
+    };
+
+    match SatisflowEngine::load_from_json_with_options(&request.save_data, &options) {
+        Ok(loaded) => {
+            response.loadable = true;
+            response.diagnostics = loaded.engine.validate_all();
+            response.summary = Some(loaded.summary);
+            response.load_warnings = loaded.warnings;
+        }
+        Err(e) => response.error = Some(e.to_string()),
+    }
+    Json(response)
+}
+
+/// GET /api/saves/autosaves
+///
+/// Lists the autosaves written by the background autosave task.
+///
+/// # Returns
+///
+/// - `200 OK` with the autosaves, newest first (empty when autosave is off)
+/// - `500 Internal Server Error` if the autosave directory can't be read
+pub async fn list_autosaves(
+    State(state): State<AppState>,
+) -> Result<Json<AutosaveListResponse>, AppError> {
+    let Some(config) = &state.autosave else {
+        return Ok(Json(AutosaveListResponse {
+            enabled: false,
+            interval_secs: None,
+            autosaves: Vec::new(),
+        }));
+    };
+
+    let autosaves = autosave::list_autosaves(&config.dir).map_err(|e| {
+        AppError::InternalError(anyhow::anyhow!("Failed to read autosave directory: {}", e))
+    })?;
+
+    Ok(Json(AutosaveListResponse {
+        enabled: true,
+        interval_secs: Some(config.interval.as_secs()),
+        autosaves,
+    }))
+}
+
 // Route configuration
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/save", get(save_engine))
         .route("/load", post(load_engine))
         .route("/reset", post(reset_engine))
+        .route("/season-overview", post(season_overview))
+        .route("/saves/diff", post(diff_saves))
+        .route("/saves/repair", post(repair_save))
+        .route("/saves/validate", post(validate_save))
+        .route("/saves/autosaves", get(list_autosaves))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::state::AppState;
-    use std::sync::Arc;
-    use tokio::sync::RwLock;
+    use crate::{shared_engine::SharedEngine, state::AppState};
 
     fn create_test_state() -> AppState {
         AppState {
-            engine: Arc::new(RwLock::new(SatisflowEngine::new())),
+            engine: SharedEngine::default(),
+            demo_mode: false,
+            autosave: None,
+            snapshots: Default::default(),
+            write_gate: Default::default(),
+            workspaces: Default::default(),
+            dashboard_summary: Default::default(),
+            metrics_history: Default::default(),
+            scenarios: Default::default(),
+            audit_log: Default::default(),
+            webhooks: Default::default(),
         }
     }
 
@@ -162,7 +564,7 @@ mod tests {
     async fn test_save_empty_engine() {
         let state = create_test_state();
 
-        let result = save_engine(State(state)).await;
+        let result = save_engine(State(state), Query(SaveQuery::default())).await;
         assert!(result.is_ok());
 
         let response = result.unwrap();
@@ -171,6 +573,31 @@ mod tests {
         assert_eq!(response.0.summary.logistics_count, 0);
     }
 
+    #[tokio::test]
+    async fn test_save_as_target_version() {
+        let state = create_test_state();
+
+        let query = SaveQuery {
+            target_version: Some("0.1.0".to_string()),
+            ..Default::default()
+        };
+        let response = save_engine(State(state.clone()), Query(query))
+            .await
+            .unwrap();
+        assert_eq!(response.0.summary.version, "0.1.0");
+        assert!(!response.0.save_data.contains("transport_naming"));
+        assert!(response.0.downgrade_report.unwrap().is_lossless());
+
+        for target in ["999.0.0", "not-a-version"] {
+            let query = SaveQuery {
+                target_version: Some(target.to_string()),
+                ..Default::default()
+            };
+            let result = save_engine(State(state.clone()), Query(query)).await;
+            assert!(matches!(result, Err(AppError::BadRequest(_))));
+        }
+    }
+
     #[tokio::test]
     async fn test_save_with_factories() {
         let state = create_test_state();
@@ -180,9 +607,10 @@ mod tests {
             let mut engine = state.engine.write().await;
             engine.create_factory("Factory 1".to_string(), None);
             engine.create_factory("Factory 2".to_string(), None);
+            engine.commit();
         }
 
-        let result = save_engine(State(state)).await;
+        let result = save_engine(State(state), Query(SaveQuery::default())).await;
         assert!(result.is_ok());
 
         let response = result.unwrap();
@@ -199,10 +627,11 @@ mod tests {
         {
             let mut engine = state.engine.write().await;
             engine.create_factory("Test Factory".to_string(), Some("Description".to_string()));
+            engine.commit();
         }
 
         // Save
-        let save_result = save_engine(State(state.clone())).await;
+        let save_result = save_engine(State(state.clone()), Query(SaveQuery::default())).await;
         assert!(save_result.is_ok());
 
         let save_data = save_result.unwrap().0.save_data;
@@ -211,17 +640,23 @@ mod tests {
         {
             let mut engine = state.engine.write().await;
             *engine = SatisflowEngine::new();
+            engine.commit();
         }
 
         // Verify empty
         {
-            let engine = state.engine.read().await;
+            let engine = state.engine.snapshot();
             assert_eq!(engine.get_all_factories().len(), 0);
         }
 
         // Load
         let load_request = LoadRequest { save_data };
-        let load_result = load_engine(State(state.clone()), Json(load_request)).await;
+        let load_result = load_engine(
+            State(state.clone()),
+            Query(LoadQuery::default()),
+            Json(load_request),
+        )
+        .await;
         assert!(load_result.is_ok());
 
         let response = load_result.unwrap();
@@ -229,7 +664,7 @@ mod tests {
 
         // Verify loaded
         {
-            let engine = state.engine.read().await;
+            let engine = state.engine.snapshot();
             assert_eq!(engine.get_all_factories().len(), 1);
             let factory = engine.get_all_factories().values().next().unwrap();
             assert_eq!(factory.name, "Test Factory");
@@ -237,6 +672,58 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_compressed_roundtrip() {
+        let state = create_test_state();
+        {
+            let mut engine = state.engine.write().await;
+            engine.create_factory("Test Factory".to_string(), None);
+            engine.commit();
+        }
+
+        let query = SaveQuery {
+            compressed: true,
+            ..Default::default()
+        };
+        let response = save_engine(State(state.clone()), Query(query))
+            .await
+            .unwrap();
+        assert!(response.0.compressed);
+        assert_eq!(response.0.summary.factory_count, 1);
+        let save_data = response.0.save_data;
+        assert!(compression::is_gzip(&BASE64.decode(&save_data).unwrap()));
+
+        let mut engine = state.engine.write().await;
+        *engine = SatisflowEngine::new();
+        engine.commit();
+        let query = LoadQuery {
+            compressed: true,
+            ..LoadQuery::default()
+        };
+        let response = load_engine(
+            State(state.clone()),
+            Query(query),
+            Json(LoadRequest { save_data }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.0.summary.factory_count, 1);
+
+        let request = LoadRequest {
+            save_data: "not base64!".to_string(),
+        };
+        let result = load_engine(
+            State(state),
+            Query(LoadQuery {
+                compressed: true,
+                ..LoadQuery::default()
+            }),
+            Json(request),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
     #[tokio::test]
     async fn test_load_invalid_json() {
         let state = create_test_state();
@@ -245,7 +732,7 @@ mod tests {
             save_data: "{ invalid json }".to_string(),
         };
 
-        let result = load_engine(State(state), Json(request)).await;
+        let result = load_engine(State(state), Query(LoadQuery::default()), Json(request)).await;
         assert!(result.is_err());
 
         let err = result.unwrap_err();
@@ -255,6 +742,20 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_save_and_load_rejected_in_demo_mode() {
+        let state = AppState::demo();
+
+        let result = save_engine(State(state.clone()), Query(SaveQuery::default())).await;
+        assert!(matches!(result, Err(AppError::Forbidden(_))));
+
+        let request = LoadRequest {
+            save_data: SatisflowEngine::new().save_to_json().unwrap(),
+        };
+        let result = load_engine(State(state), Query(LoadQuery::default()), Json(request)).await;
+        assert!(matches!(result, Err(AppError::Forbidden(_))));
+    }
+
     #[tokio::test]
     async fn test_load_future_version() {
         let state = create_test_state();
@@ -273,7 +774,7 @@ mod tests {
             .to_string(),
         };
 
-        let result = load_engine(State(state), Json(request)).await;
+        let result = load_engine(State(state), Query(LoadQuery::default()), Json(request)).await;
         assert!(result.is_err());
 
         let err = result.unwrap_err();
@@ -289,6 +790,100 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_strict_and_lenient_load() {
+        let state = create_test_state();
+        let mut value: serde_json::Value =
+            serde_json::from_str(&SatisflowEngine::new().save_to_json().unwrap()).unwrap();
+        value["engine"]["hub_layout"] = serde_json::json!({ "floors": 2 });
+        let request = || LoadRequest {
+            save_data: value.to_string(),
+        };
+
+        let strict = LoadQuery {
+            strict: true,
+            ..LoadQuery::default()
+        };
+        let result = load_engine(State(state.clone()), Query(strict), Json(request())).await;
+        match result {
+            Err(AppError::BadRequest(msg)) => {
+                assert_eq!(msg, "engine.hub_layout: Unknown field")
+            }
+            _ => panic!("Expected BadRequest error"),
+        }
+
+        let response = load_engine(State(state), Query(LoadQuery::default()), Json(request()))
+            .await
+            .unwrap();
+        assert_eq!(response.warnings.len(), 1);
+        assert_eq!(response.warnings[0].path, "engine.hub_layout");
+    }
+
+    #[tokio::test]
+    async fn test_repair_save() {
+        let mut value: serde_json::Value =
+            serde_json::from_str(&SatisflowEngine::new().save_to_json().unwrap()).unwrap();
+        value["engine"].as_object_mut().unwrap().remove("factories");
+
+        let response = repair_save(Json(RepairRequest {
+            save_data: value.to_string(),
+        }))
+        .await
+        .unwrap();
+        assert_eq!(
+            response.report.defaulted_sections,
+            vec!["engine.factories".to_string()]
+        );
+        assert!(SatisflowEngine::load_from_json(&response.save_data).is_ok());
+
+        let result = repair_save(Json(RepairRequest {
+            save_data: "[]".to_string(),
+        }))
+        .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_validate_save() {
+        let mut engine = SatisflowEngine::new();
+        let factory_id = engine.create_factory("Lonely".to_string(), None);
+        let save_data = engine.save_to_json().unwrap();
+
+        let Json(report) = validate_save(
+            Query(ValidateQuery::default()),
+            Json(ValidateRequest {
+                save_data: save_data.clone(),
+            }),
+        )
+        .await;
+        assert!(report.loadable);
+        assert_eq!(report.save_version, Some(report.engine_version.clone()));
+        assert_eq!(report.summary.unwrap().factory_count, 1);
+        assert_eq!(report.diagnostics, engine.validate_all());
+
+        let mut value: serde_json::Value = serde_json::from_str(&save_data).unwrap();
+        value["engine"]["factories"][factory_id.to_string()]["floor"] = 3.into();
+        let Json(report) = validate_save(
+            Query(ValidateQuery { strict: true }),
+            Json(ValidateRequest {
+                save_data: value.to_string(),
+            }),
+        )
+        .await;
+        assert!(!report.loadable);
+        assert!(report.error.unwrap().ends_with("floor: Unknown field"));
+
+        let Json(report) = validate_save(
+            Query(ValidateQuery::default()),
+            Json(ValidateRequest {
+                save_data: "{ invalid json }".to_string(),
+            }),
+        )
+        .await;
+        assert!(!report.loadable);
+        assert_eq!(report.save_version, None);
+    }
+
     #[tokio::test]
     async fn test_load_missing_version() {
         let state = create_test_state();
@@ -306,7 +901,7 @@ mod tests {
             .to_string(),
         };
 
-        let result = load_engine(State(state), Json(request)).await;
+        let result = load_engine(State(state), Query(LoadQuery::default()), Json(request)).await;
         assert!(result.is_err());
     }
 
@@ -321,7 +916,7 @@ mod tests {
         assert!(response.0.message.contains("reset successfully"));
 
         // Verify engine is empty
-        let engine = state.engine.read().await;
+        let engine = state.engine.snapshot();
         assert_eq!(engine.get_all_factories().len(), 0);
         assert_eq!(engine.get_all_logistics().len(), 0);
     }
@@ -335,11 +930,12 @@ mod tests {
             let mut engine = state.engine.write().await;
             engine.create_factory("Factory 1".to_string(), None);
             engine.create_factory("Factory 2".to_string(), None);
+            engine.commit();
         }
 
         // Verify data exists
         {
-            let engine = state.engine.read().await;
+            let engine = state.engine.snapshot();
             assert_eq!(engine.get_all_factories().len(), 2);
         }
 
@@ -349,7 +945,7 @@ mod tests {
 
         // Verify data is cleared
         {
-            let engine = state.engine.read().await;
+            let engine = state.engine.snapshot();
             assert_eq!(engine.get_all_factories().len(), 0);
             assert_eq!(engine.get_all_logistics().len(), 0);
         }
@@ -372,11 +968,12 @@ mod tests {
             engine
                 .create_logistics_line(factory1, factory2, transport, "Test".to_string())
                 .unwrap();
+            engine.commit();
         }
 
         // Verify data exists
         {
-            let engine = state.engine.read().await;
+            let engine = state.engine.snapshot();
             assert_eq!(engine.get_all_factories().len(), 2);
             assert_eq!(engine.get_all_logistics().len(), 1);
         }
@@ -387,7 +984,7 @@ mod tests {
 
         // Verify everything is cleared
         {
-            let engine = state.engine.read().await;
+            let engine = state.engine.snapshot();
             assert_eq!(engine.get_all_factories().len(), 0);
             assert_eq!(engine.get_all_logistics().len(), 0);
         }
@@ -0,0 +1,261 @@
+// crates/satisflow-server/src/handlers/scenarios.rs
+//! What-if scenario API handlers
+//!
+//! Fork the engine into a sandbox, change it through
+//! `/api/scenarios/{id}/...` (e.g. `/api/scenarios/{id}/factories`), compare
+//! it with the real state and commit it once it looks right.
+
+use axum::{
+    extract::{Path, Request, State},
+    http::{StatusCode, Uri},
+    response::{IntoResponse, Response},
+    routing::{any, get, post},
+    Extension, Json, Router,
+};
+use chrono::Utc;
+use satisflow_engine::SaveDiff;
+use serde::{Deserialize, Serialize};
+use tower::ServiceExt;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    audit_log::{Actor, AuditEntry},
+    error::{AppError, Result},
+    handlers::workspaces::engine_router,
+    scenario_store::{Scenario, ScenarioInfo},
+    shared_engine::SharedEngine,
+    state::AppState,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct ScenarioRequest {
+    pub name: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScenarioResponse {
+    #[serde(flatten)]
+    pub info: ScenarioInfo,
+    /// Current revision of the scenario's own state
+    pub revision: u64,
+}
+
+async fn scenario_response(scenario: &Scenario) -> ScenarioResponse {
+    ScenarioResponse {
+        info: scenario.info.clone(),
+        revision: scenario.state.engine.snapshot().revision(),
+    }
+}
+
+fn not_found(id: &str) -> AppError {
+    AppError::NotFound(format!("Scenario with id {} not found", id))
+}
+
+async fn find(state: &AppState, id: &str) -> Result<Scenario> {
+    state.scenarios.get(id).await.ok_or_else(|| not_found(id))
+}
+
+/// GET /api/scenarios
+///
+/// # Returns
+///
+/// - `200 OK` with every scenario, oldest first
+pub async fn list_scenarios(State(state): State<AppState>) -> Result<Json<Vec<ScenarioResponse>>> {
+    let mut responses = Vec::new();
+    for info in state.scenarios.list().await {
+        if let Some(scenario) = state.scenarios.get(&info.id).await {
+            responses.push(scenario_response(&scenario).await);
+        }
+    }
+    Ok(Json(responses))
+}
+
+/// POST /api/scenarios
+///
+/// Forks the current engine state into a named sandbox
+///
+/// # Returns
+///
+/// - `201 Created` with the scenario
+/// - `400 Bad Request` if the name is empty
+/// - `403 Forbidden` on a demo server
+pub async fn create_scenario(
+    State(state): State<AppState>,
+    Json(request): Json<ScenarioRequest>,
+) -> Result<(StatusCode, Json<ScenarioResponse>)> {
+    if state.demo_mode {
+        return Err(AppError::Forbidden(
+            "Scenarios can't be created in demo mode".to_string(),
+        ));
+    }
+    let name = request.name.trim();
+    if name.is_empty() {
+        return Err(AppError::ValidationError(
+            "Scenario name cannot be empty".to_string(),
+        ));
+    }
+
+    let engine = (*state.engine.snapshot()).clone();
+    let info = ScenarioInfo {
+        id: Uuid::new_v4().to_string(),
+        name: name.to_string(),
+        created_at: Utc::now(),
+        base_revision: engine.revision(),
+    };
+    let scenario_state = AppState {
+        engine: SharedEngine::new(engine),
+        demo_mode: state.demo_mode,
+        autosave: None,
+        ..AppState::new()
+    };
+    let scenario = Scenario {
+        info,
+        router: engine_router(scenario_state.clone()),
+        state: scenario_state,
+    };
+    state.scenarios.insert(scenario.clone()).await;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(scenario_response(&scenario).await),
+    ))
+}
+
+/// GET /api/scenarios/{id}
+///
+/// # Returns
+///
+/// - `200 OK` with the scenario
+/// - `404 Not Found` if the scenario doesn't exist
+pub async fn get_scenario(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ScenarioResponse>> {
+    let scenario = find(&state, &id).await?;
+    Ok(Json(scenario_response(&scenario).await))
+}
+
+/// DELETE /api/scenarios/{id}
+///
+/// Discards the scenario, leaving the real state as it is
+///
+/// # Returns
+///
+/// - `204 No Content` once discarded
+/// - `404 Not Found` if the scenario doesn't exist
+pub async fn delete_scenario(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode> {
+    state
+        .scenarios
+        .remove(&id)
+        .await
+        .ok_or_else(|| not_found(&id))?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /api/scenarios/{id}/diff
+///
+/// # Returns
+///
+/// - `200 OK` with what committing the scenario would change in the real state
+/// - `404 Not Found` if the scenario doesn't exist
+pub async fn diff_scenario(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<SaveDiff>> {
+    let scenario = find(&state, &id).await?;
+    let engine = state.engine.snapshot();
+    let sandbox = scenario.state.engine.snapshot();
+
+    Ok(Json(SaveDiff::between(&engine, &sandbox)))
+}
+
+/// POST /api/scenarios/{id}/commit
+///
+/// Replaces the real state with the scenario's, then discards the scenario
+///
+/// # Returns
+///
+/// - `200 OK` with the changes applied to the real state
+/// - `404 Not Found` if the scenario doesn't exist
+/// - `409 Conflict` if the real state changed since the scenario was forked
+pub async fn commit_scenario(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    actor: Option<Extension<Actor>>,
+) -> Result<Json<SaveDiff>> {
+    let scenario = find(&state, &id).await?;
+
+    // Like any other write, wait for the ones in progress
+    let _write = state.write_gate.lock().await;
+    let mut engine = state.engine.write().await;
+    if engine.revision() != scenario.info.base_revision {
+        return Err(AppError::Conflict(format!(
+            "The state changed since scenario {} was forked (now at revision {}), \
+             fork a new scenario",
+            id,
+            engine.revision()
+        )));
+    }
+
+    let revision = engine.revision();
+    let sandbox = (*scenario.state.engine.snapshot()).clone();
+    let diff = SaveDiff::between(&engine, &sandbox);
+    engine.replace(sandbox);
+    let entry = AuditEntry::new(
+        engine.revision(),
+        "POST",
+        format!("/api/scenarios/{}/commit", id),
+        None,
+        actor.as_ref().map(|Extension(actor)| actor),
+    );
+    if let Err(e) = state.audit_log.record(entry).await {
+        warn!("Failed to write the audit log: {}", e);
+    }
+    state.scenarios.remove(&id).await;
+
+    engine.commit();
+    state.record_write(revision).await;
+
+    Ok(Json(diff))
+}
+
+/// ANY /api/scenarios/{id}/{*rest}
+///
+/// Handles the request as `/api/{rest}` in the scenario
+///
+/// # Returns
+///
+/// - The response of the engine route
+/// - `404 Not Found` if the scenario doesn't exist
+pub async fn scenario_api(
+    State(state): State<AppState>,
+    Path((id, rest)): Path<(String, String)>,
+    request: Request,
+) -> Result<Response> {
+    let router = find(&state, &id).await?.router;
+
+    let (mut parts, body) = request.into_parts();
+    let path = match parts.uri.query() {
+        Some(query) => format!("/api/{}?{}", rest, query),
+        None => format!("/api/{}", rest),
+    };
+    parts.uri = path
+        .parse::<Uri>()
+        .map_err(|e| AppError::BadRequest(format!("Invalid path: {}", e)))?;
+
+    let response = router.oneshot(Request::from_parts(parts, body)).await;
+    Ok(response.into_response())
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_scenarios).post(create_scenario))
+        .route("/:id", get(get_scenario).delete(delete_scenario))
+        .route("/:id/diff", get(diff_scenario))
+        .route("/:id/commit", post(commit_scenario))
+        .route("/:id/*rest", any(scenario_api))
+}
@@ -0,0 +1,90 @@
+// crates/satisflow-server/src/handlers/scripts.rs
+use axum::{extract::State, response::Json, routing::post, Router};
+use satisflow_engine::SaveDiff;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    error::{AppError, Result},
+    scripting,
+    state::AppState,
+};
+
+/// Request body for running a script
+#[derive(Debug, Deserialize)]
+pub struct ScriptRequest {
+    /// Rhai source, with the save bound to `save`
+    pub script: String,
+    /// Replace the state with the script's edits to `save`
+    #[serde(default)]
+    pub apply: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScriptResponse {
+    /// Value the script evaluated to
+    pub result: Value,
+    /// Lines the script printed
+    pub output: Vec<String>,
+    /// What the script's edits change, applied or not
+    pub diff: SaveDiff,
+    /// Whether the edits were applied to the state
+    pub applied: bool,
+}
+
+/// POST /api/scripts/run
+///
+/// Runs a Rhai script against a copy of the state; see the `scripting` module
+/// for what scripts can do. The state itself is only changed with `apply`.
+///
+/// # Returns
+///
+/// - `200 OK` with the script's result and edits
+/// - `400 Bad Request` if the script fails or its edits don't make a valid save
+/// - `403 Forbidden` in demo mode
+pub async fn run_script(
+    State(state): State<AppState>,
+    Json(request): Json<ScriptRequest>,
+) -> Result<Json<ScriptResponse>> {
+    if state.demo_mode {
+        return Err(AppError::Forbidden(
+            "Scripts are disabled in demo mode".to_string(),
+        ));
+    }
+
+    // Writes are serialized by the revision middleware, so the state can't
+    // change between taking the snapshot and applying the edits
+    let snapshot = state.engine.snapshot();
+    let (snapshot, outcome) = tokio::task::spawn_blocking(move || {
+        let outcome = scripting::run(&request.script, &snapshot);
+        (snapshot, outcome)
+    })
+    .await
+    .map_err(|e| anyhow::anyhow!("Script task failed: {}", e))?;
+    let outcome = outcome?;
+
+    let diff = match &outcome.edited {
+        Some(edited) => SaveDiff::between(&snapshot, edited),
+        None => SaveDiff::between(&snapshot, &snapshot),
+    };
+    let applied = match outcome.edited {
+        Some(edited) if request.apply => {
+            let mut engine = state.engine.write().await;
+            engine.replace(edited);
+            engine.commit();
+            true
+        }
+        _ => false,
+    };
+
+    Ok(Json(ScriptResponse {
+        result: outcome.result,
+        output: outcome.output,
+        diff,
+        applied,
+    }))
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/run", post(run_script))
+}
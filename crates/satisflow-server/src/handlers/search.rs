@@ -0,0 +1,49 @@
+// crates/satisflow-server/src/handlers/search.rs
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Json, Router,
+};
+use satisflow_engine::models::SearchHit;
+use serde::Deserialize;
+
+use crate::{
+    error::{AppError, Result},
+    state::AppState,
+};
+
+const DEFAULT_LIMIT: usize = 50;
+const MAX_LIMIT: usize = 500;
+
+/// Query parameters for the global search
+#[derive(Debug, Default, Deserialize)]
+pub struct SearchQuery {
+    /// Text to look for, case-insensitively
+    #[serde(default)]
+    pub q: String,
+    /// Maximum number of hits to return; defaults to 50, capped at 500
+    pub limit: Option<usize>,
+}
+
+pub async fn search(
+    State(state): State<AppState>,
+    Query(query): Query<SearchQuery>,
+) -> Result<Json<Vec<SearchHit>>> {
+    if query.q.trim().is_empty() {
+        return Err(AppError::BadRequest("q must not be empty".to_string()));
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT);
+    if limit == 0 {
+        return Err(AppError::BadRequest(
+            "limit must be greater than 0".to_string(),
+        ));
+    }
+
+    let engine = state.engine.snapshot();
+
+    Ok(Json(engine.search(&query.q, limit.min(MAX_LIMIT))))
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/", get(search))
+}
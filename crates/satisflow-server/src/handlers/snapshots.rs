@@ -0,0 +1,165 @@
+// crates/satisflow-server/src/handlers/snapshots.rs
+//! Snapshot API handlers
+//!
+//! Checkpoint the engine state under a label before a big change, and go
+//! back to it later.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{delete, get, post},
+    Json, Router,
+};
+use chrono::Utc;
+use satisflow_engine::{SatisflowEngine, SaveFile};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, Result},
+    snapshot_store::{Snapshot, SnapshotInfo},
+    state::AppState,
+    webhooks::WebhookEvent,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct CreateSnapshotRequest {
+    /// Name shown for this snapshot, e.g. "Before rebuilding the steel line"
+    pub label: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RestoreSnapshotResponse {
+    pub message: String,
+    pub snapshot: SnapshotInfo,
+}
+
+/// Snapshots hold full saves, so they follow the demo rules for saving
+fn reject_in_demo_mode(state: &AppState) -> Result<()> {
+    if state.demo_mode {
+        return Err(AppError::Forbidden(
+            "Snapshots are disabled in demo mode".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// GET /api/snapshots
+///
+/// # Returns
+///
+/// - `200 OK` with every snapshot, newest first
+pub async fn list_snapshots(State(state): State<AppState>) -> Result<Json<Vec<SnapshotInfo>>> {
+    Ok(Json(state.snapshots.list().await?))
+}
+
+/// POST /api/snapshots
+///
+/// Captures the current engine state under a label
+///
+/// # Returns
+///
+/// - `201 Created` with the snapshot
+/// - `400 Bad Request` if the label is empty
+/// - `403 Forbidden` on a demo server
+pub async fn create_snapshot(
+    State(state): State<AppState>,
+    Json(request): Json<CreateSnapshotRequest>,
+) -> Result<(StatusCode, Json<SnapshotInfo>)> {
+    reject_in_demo_mode(&state)?;
+
+    let label = request.label.trim();
+    if label.is_empty() {
+        return Err(AppError::ValidationError(
+            "Snapshot label cannot be empty".to_string(),
+        ));
+    }
+
+    let save_data = state
+        .engine
+        .snapshot()
+        .save_to_json()
+        .map_err(|e| AppError::EngineError(e.to_string()))?;
+    let save_file: SaveFile = serde_json::from_str(&save_data)?;
+
+    let snapshot = Snapshot {
+        info: SnapshotInfo {
+            id: Uuid::new_v4(),
+            label: label.to_string(),
+            created_at: Utc::now(),
+            summary: save_file.summary(),
+        },
+        save_data,
+    };
+    let info = snapshot.info.clone();
+    state.snapshots.insert(snapshot).await?;
+    state.webhooks.notify(
+        WebhookEvent::SaveCreated,
+        json!({ "source": "snapshot", "id": info.id, "label": info.label }),
+    );
+
+    Ok((StatusCode::CREATED, Json(info)))
+}
+
+/// POST /api/snapshots/{id}/restore
+///
+/// Replaces the engine state with a snapshot; the snapshot is kept
+///
+/// # Returns
+///
+/// - `200 OK` with the restored snapshot
+/// - `403 Forbidden` on a demo server
+/// - `404 Not Found` if the snapshot doesn't exist
+pub async fn restore_snapshot(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<RestoreSnapshotResponse>> {
+    reject_in_demo_mode(&state)?;
+
+    let snapshot = state
+        .snapshots
+        .get(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Snapshot with id {} not found", id)))?;
+
+    let restored = SatisflowEngine::load_from_json(&snapshot.save_data)
+        .map_err(|e| AppError::EngineError(e.to_string()))?;
+    let mut engine = state.engine.write().await;
+    engine.replace(restored);
+    engine.commit();
+
+    Ok(Json(RestoreSnapshotResponse {
+        message: format!("Restored snapshot '{}'", snapshot.info.label),
+        snapshot: snapshot.info,
+    }))
+}
+
+/// DELETE /api/snapshots/{id}
+///
+/// # Returns
+///
+/// - `204 No Content` once deleted
+/// - `403 Forbidden` on a demo server
+/// - `404 Not Found` if the snapshot doesn't exist
+pub async fn delete_snapshot(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode> {
+    reject_in_demo_mode(&state)?;
+
+    if !state.snapshots.remove(id).await? {
+        return Err(AppError::NotFound(format!(
+            "Snapshot with id {} not found",
+            id
+        )));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_snapshots).post(create_snapshot))
+        .route("/:id", delete(delete_snapshot))
+        .route("/:id/restore", post(restore_snapshot))
+}
@@ -0,0 +1,58 @@
+// crates/satisflow-server/src/handlers/tier_progress.rs
+use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
+use satisflow_engine::models::TierProgress;
+
+use crate::{
+    error::{AppError, Result},
+    state::AppState,
+};
+
+/// GET /api/tier-progress
+///
+/// `null` until the progression has been recorded
+pub async fn get_tier_progress(
+    State(state): State<AppState>,
+) -> Result<Json<Option<TierProgress>>> {
+    let engine = state.engine.snapshot();
+
+    Ok(Json(engine.tier_progress().cloned()))
+}
+
+pub async fn set_tier_progress(
+    State(state): State<AppState>,
+    Json(progress): Json<TierProgress>,
+) -> Result<Json<TierProgress>> {
+    let mut engine = state.engine.write().await;
+
+    engine
+        .set_tier_progress(Some(progress.clone()))
+        .map_err(|e| AppError::ValidationError(e.to_string()))?;
+
+    engine.commit();
+
+    Ok(Json(progress))
+}
+
+/// DELETE /api/tier-progress
+///
+/// Forget the progression so nothing is treated as locked
+pub async fn clear_tier_progress(State(state): State<AppState>) -> Result<StatusCode> {
+    let mut engine = state.engine.write().await;
+
+    engine
+        .set_tier_progress(None)
+        .map_err(|e| AppError::EngineError(e.to_string()))?;
+
+    engine.commit();
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route(
+        "/",
+        get(get_tier_progress)
+            .put(set_tier_progress)
+            .delete(clear_tier_progress),
+    )
+}
@@ -0,0 +1,293 @@
+// crates/satisflow-server/src/handlers/workspaces.rs
+//! Workspace API handlers
+//!
+//! Manage workspaces, and forward `/api/workspaces/{id}/...` requests to the
+//! engine routes of the workspace, e.g. `/api/workspaces/{id}/factories`.
+
+use axum::{
+    extract::{Path, Request, State},
+    http::{StatusCode, Uri},
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{any, get},
+    Json, Router,
+};
+use chrono::Utc;
+use satisflow_engine::SatisflowEngine;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, Result},
+    handlers::engine_routes,
+    revision,
+    shared_engine::SharedEngine,
+    state::AppState,
+    workspace_store::{Workspace, WorkspaceInfo, WorkspaceStore, DEFAULT_WORKSPACE},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct WorkspaceRequest {
+    pub name: String,
+    #[serde(default)]
+    pub members: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorkspaceResponse {
+    #[serde(flatten)]
+    pub info: WorkspaceInfo,
+    pub factory_count: usize,
+}
+
+/// Engine routes handling requests with `state`
+pub fn engine_router(state: AppState) -> Router {
+    engine_routes()
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            revision::check_revision,
+        ))
+        .with_state(state)
+}
+
+/// Trimmed name and members, without blank or repeated members
+fn validate(request: WorkspaceRequest) -> Result<(String, Vec<String>)> {
+    let name = request.name.trim();
+    if name.is_empty() {
+        return Err(AppError::ValidationError(
+            "Workspace name cannot be empty".to_string(),
+        ));
+    }
+
+    let mut members: Vec<String> = Vec::new();
+    for member in request.members {
+        let member = member.trim();
+        if member.is_empty() {
+            return Err(AppError::ValidationError(
+                "Member names cannot be empty".to_string(),
+            ));
+        }
+        if !members.iter().any(|existing| existing == member) {
+            members.push(member.to_string());
+        }
+    }
+    Ok((name.to_string(), members))
+}
+
+async fn workspace_response(state: &AppState, info: WorkspaceInfo) -> WorkspaceResponse {
+    let factory_count = if info.id == DEFAULT_WORKSPACE {
+        state.engine.snapshot().get_all_factories().len()
+    } else {
+        match state.workspaces.get(&info.id).await {
+            Some(workspace) => workspace.state.engine.snapshot().get_all_factories().len(),
+            None => 0,
+        }
+    };
+    WorkspaceResponse {
+        info,
+        factory_count,
+    }
+}
+
+fn not_found(id: &str) -> AppError {
+    AppError::NotFound(format!("Workspace with id {} not found", id))
+}
+
+/// GET /api/workspaces
+///
+/// # Returns
+///
+/// - `200 OK` with every workspace, the default one first
+pub async fn list_workspaces(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<WorkspaceResponse>>> {
+    let mut responses = Vec::new();
+    for info in state.workspaces.list().await {
+        responses.push(workspace_response(&state, info).await);
+    }
+    Ok(Json(responses))
+}
+
+/// POST /api/workspaces
+///
+/// Creates an empty workspace
+///
+/// # Returns
+///
+/// - `201 Created` with the workspace
+/// - `400 Bad Request` if the name or a member name is empty
+/// - `403 Forbidden` on a demo server
+pub async fn create_workspace(
+    State(state): State<AppState>,
+    Json(request): Json<WorkspaceRequest>,
+) -> Result<(StatusCode, Json<WorkspaceResponse>)> {
+    if state.demo_mode {
+        return Err(AppError::Forbidden(
+            "Workspaces can't be created in demo mode".to_string(),
+        ));
+    }
+    let (name, members) = validate(request)?;
+
+    let id = Uuid::new_v4().to_string();
+    let webhooks = state.webhooks.for_workspace(&id);
+    let mut engine = SatisflowEngine::new();
+    webhooks.observe(&mut engine);
+    let workspace_state = AppState {
+        engine: SharedEngine::new(engine),
+        demo_mode: state.demo_mode,
+        // Only the default workspace is autosaved
+        autosave: None,
+        snapshots: state.snapshots.for_workspace(&id),
+        write_gate: Arc::default(),
+        workspaces: WorkspaceStore::default(),
+        dashboard_summary: Default::default(),
+        metrics_history: Default::default(),
+        scenarios: Default::default(),
+        audit_log: state.audit_log.for_workspace(&id),
+        webhooks,
+    };
+    let info = WorkspaceInfo {
+        id,
+        name,
+        members,
+        created_at: Utc::now(),
+    };
+    state
+        .workspaces
+        .insert(Workspace {
+            info: info.clone(),
+            router: engine_router(workspace_state.clone()),
+            state: workspace_state,
+        })
+        .await;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(workspace_response(&state, info).await),
+    ))
+}
+
+/// GET /api/workspaces/{id}
+///
+/// # Returns
+///
+/// - `200 OK` with the workspace
+/// - `404 Not Found` if the workspace doesn't exist
+pub async fn get_workspace(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<WorkspaceResponse>> {
+    let info = state
+        .workspaces
+        .info(&id)
+        .await
+        .ok_or_else(|| not_found(&id))?;
+    Ok(Json(workspace_response(&state, info).await))
+}
+
+/// PUT /api/workspaces/{id}
+///
+/// Renames the workspace and replaces its members
+///
+/// # Returns
+///
+/// - `200 OK` with the workspace
+/// - `400 Bad Request` if the name or a member name is empty
+/// - `404 Not Found` if the workspace doesn't exist
+pub async fn update_workspace(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(request): Json<WorkspaceRequest>,
+) -> Result<Json<WorkspaceResponse>> {
+    let (name, members) = validate(request)?;
+    let info = state
+        .workspaces
+        .update(&id, name, members)
+        .await
+        .ok_or_else(|| not_found(&id))?;
+    Ok(Json(workspace_response(&state, info).await))
+}
+
+/// DELETE /api/workspaces/{id}
+///
+/// Deletes the workspace with its engine state and snapshots
+///
+/// # Returns
+///
+/// - `204 No Content` once deleted
+/// - `400 Bad Request` for the default workspace
+/// - `404 Not Found` if the workspace doesn't exist
+pub async fn delete_workspace(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode> {
+    if id == DEFAULT_WORKSPACE {
+        return Err(AppError::BadRequest(
+            "The default workspace can't be deleted".to_string(),
+        ));
+    }
+    let workspace = state
+        .workspaces
+        .remove(&id)
+        .await
+        .ok_or_else(|| not_found(&id))?;
+
+    let snapshots = workspace.state.snapshots;
+    for snapshot in snapshots.list().await? {
+        snapshots.remove(snapshot.id).await?;
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// ANY /api/workspaces/{id}/{*rest}
+///
+/// Handles the request as `/api/{rest}` in the workspace
+///
+/// # Returns
+///
+/// - The response of the engine route
+/// - `404 Not Found` if the workspace doesn't exist
+pub async fn workspace_api(
+    State(state): State<AppState>,
+    Path((id, rest)): Path<(String, String)>,
+    request: Request,
+) -> Result<Response> {
+    let router = if id == DEFAULT_WORKSPACE {
+        state
+            .workspaces
+            .default_router(|| engine_router(state.clone()))
+    } else {
+        state
+            .workspaces
+            .get(&id)
+            .await
+            .ok_or_else(|| not_found(&id))?
+            .router
+    };
+
+    let (mut parts, body) = request.into_parts();
+    let path = match parts.uri.query() {
+        Some(query) => format!("/api/{}?{}", rest, query),
+        None => format!("/api/{}", rest),
+    };
+    parts.uri = path
+        .parse::<Uri>()
+        .map_err(|e| AppError::BadRequest(format!("Invalid path: {}", e)))?;
+
+    let response = router.oneshot(Request::from_parts(parts, body)).await;
+    Ok(response.into_response())
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_workspaces).post(create_workspace))
+        .route(
+            "/:id",
+            get(get_workspace)
+                .put(update_workspace)
+                .delete(delete_workspace),
+        )
+        .route("/:id/*rest", any(workspace_api))
+}
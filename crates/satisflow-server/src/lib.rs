@@ -1,7 +1,23 @@
 // crates/satisflow-server/src/lib.rs
+pub mod audit_log;
+pub mod auth;
+pub mod autosave;
+pub mod demo;
 pub mod error;
+pub mod graphql;
+#[cfg(feature = "grpc")]
+pub mod grpc;
 pub mod handlers;
+pub mod metrics_history;
+pub mod revision;
+pub mod revision_cache;
+pub mod scenario_store;
+pub mod scripting;
+pub mod shared_engine;
+pub mod snapshot_store;
 pub mod state;
+pub mod webhooks;
+pub mod workspace_store;
 
 pub use error::{AppError, Result};
 pub use state::AppState;
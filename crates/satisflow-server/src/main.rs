@@ -1,26 +1,51 @@
 // crates/satisflow-server/src/main.rs
-use axum::{http::Method, routing::get, Router};
+use axum::{
+    http::{header, Method},
+    middleware,
+    routing::get,
+};
 use serde_json::json;
 use std::env;
 use std::net::SocketAddr;
 use tokio::signal;
 use tower::ServiceBuilder;
 use tower_http::{
+    compression::CompressionLayer,
     cors::{Any, CorsLayer},
     trace::TraceLayer,
 };
-use tracing::info;
+use tracing::{info, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
+mod audit_log;
+mod auth;
+mod autosave;
+mod demo;
 mod error;
+mod graphql;
+#[cfg(feature = "grpc")]
+mod grpc;
 mod handlers;
+mod metrics_history;
+mod revision;
+mod revision_cache;
+mod scenario_store;
+mod scripting;
+mod shared_engine;
+mod snapshot_store;
 mod state;
+mod webhooks;
+mod workspace_store;
 
+use audit_log::AuditLog;
+use auth::{AuthConfig, Role};
+use autosave::AutosaveConfig;
+use demo::{DemoConfig, RateLimiter};
 use error::Result;
-use handlers::{
-    blueprint, blueprint_templates, dashboard, factory, game_data, logistics, save_load,
-};
+use handlers::{dev, engine_routes, scenarios, workspaces};
+use snapshot_store::SnapshotStore;
 use state::AppState;
+use webhooks::{WebhookConfig, Webhooks};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -64,8 +89,117 @@ async fn main() -> Result<()> {
             .init();
     }
 
+    // Public demo mode: example data, no saving or loading, periodic resets
+    let demo = DemoConfig::from_env()?;
+
     // Create application state
-    let state = AppState::new();
+    let state = match &demo {
+        Some(config) => {
+            let state = AppState::demo();
+            demo::reset_demo_state(&state, config).await?;
+            demo::spawn_reset_task(state.clone(), config.clone());
+            info!(
+                "Demo mode: example '{}', reset every {} minutes, {} requests/min per client",
+                config.example,
+                config.reset_interval.as_secs() / 60,
+                config.requests_per_minute
+            );
+            state
+        }
+        None => AppState::new(),
+    };
+
+    // Snapshots and the audit log survive restarts only when their directory is set
+    let state = AppState {
+        snapshots: SnapshotStore::from_env(),
+        audit_log: AuditLog::from_env()?,
+        ..state
+    };
+
+    // Webhooks, set up before the autosave task that sends them some events
+    let state = match WebhookConfig::from_env()? {
+        Some(config) => {
+            info!(
+                "Webhooks to {} URLs for {} events",
+                config.urls.len(),
+                config.events.len()
+            );
+            let webhooks = Webhooks::start(config);
+            let mut engine = state.engine.write().await;
+            webhooks.observe(&mut engine);
+            engine.commit();
+            AppState { webhooks, ..state }
+        }
+        None => state,
+    };
+
+    // Background autosave, pointless for a demo that resets anyway
+    let state = match AutosaveConfig::from_env()? {
+        Some(config) if demo.is_none() => {
+            autosave::spawn_autosave_task(state.clone(), config.clone());
+            info!(
+                "Autosave to {} every {} seconds, keeping {} files",
+                config.dir.display(),
+                config.interval.as_secs(),
+                config.keep
+            );
+            AppState {
+                autosave: Some(config),
+                ..state
+            }
+        }
+        Some(_) => {
+            warn!("AUTOSAVE_DIR is ignored in demo mode");
+            state
+        }
+        None => state,
+    };
+
+    // API keys, required once any is configured
+    let auth = AuthConfig::from_env()?;
+    match &auth {
+        Some(config) => info!(
+            "API key authentication on: {} read-write and {} read-only keys",
+            config.count(Role::ReadWrite),
+            config.count(Role::ReadOnly)
+        ),
+        None if host != "127.0.0.1" && host != "localhost" => {
+            warn!(
+                "Listening on {} without API keys, anyone who can reach it can edit the data",
+                host
+            )
+        }
+        None => {}
+    }
+
+    // gRPC mirror of the API on its own port, built with the `grpc` feature
+    #[cfg(feature = "grpc")]
+    {
+        if demo.is_some() {
+            warn!("The gRPC server is off in demo mode");
+        } else {
+            let grpc_port: u16 = match env::var("GRPC_PORT") {
+                Ok(port) => port
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("Invalid GRPC_PORT format"))?,
+                Err(_) => grpc::DEFAULT_GRPC_PORT,
+            };
+            let grpc_addr: SocketAddr = format!("{}:{}", host, grpc_port)
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid gRPC address: {}", e))?;
+            let service = grpc::SatisflowService::new(state.clone(), auth.clone());
+            info!("gRPC server listening on {}", grpc_addr);
+            tokio::spawn(async move {
+                if let Err(e) = tonic::transport::Server::builder()
+                    .add_service(service.into_server())
+                    .serve_with_shutdown(grpc_addr, shutdown_signal())
+                    .await
+                {
+                    tracing::error!("gRPC server error: {}", e);
+                }
+            });
+        }
+    }
 
     // Configure CORS based on environment variables
     let cors_origins = env::var("CORS_ORIGINS")
@@ -87,31 +221,68 @@ async fn main() -> Result<()> {
 
         CorsLayer::new()
             .allow_origin(origins)
-            .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+            .allow_methods([
+                Method::GET,
+                Method::POST,
+                Method::PUT,
+                Method::PATCH,
+                Method::DELETE,
+            ])
             .allow_headers(Any)
+            .expose_headers([header::ETAG])
     } else {
         CorsLayer::new()
             .allow_origin(Any)
-            .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+            .allow_methods([
+                Method::GET,
+                Method::POST,
+                Method::PUT,
+                Method::PATCH,
+                Method::DELETE,
+            ])
             .allow_headers(Any)
+            .expose_headers([header::ETAG])
     };
 
-    // Build the application router
-    let app = Router::new()
-        // API routes
-        .nest("/api/factories", factory::routes())
-        .nest("/api/logistics", logistics::routes())
-        .nest("/api/dashboard", dashboard::routes())
-        .nest("/api/game-data", game_data::routes())
-        .nest("/api", save_load::routes())
-        .nest("/api", blueprint_templates::routes())
-        .nest("/api", blueprint::routes())
+    // Build the application router, with the default workspace at /api
+    let mut api = engine_routes();
+
+    // Example fixtures for frontend development, never exposed in production
+    if environment != "production" {
+        api = api.nest("/api/dev", dev::routes());
+    }
+
+    api = api
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            revision::check_revision,
+        ))
+        // Other workspaces and scenarios check revisions against their own engine
+        .nest("/api/workspaces", workspaces::routes())
+        .nest("/api/scenarios", scenarios::routes());
+
+    if let Some(config) = auth {
+        api = api.layer(middleware::from_fn_with_state(
+            config,
+            auth::require_api_key,
+        ));
+    }
+
+    if let Some(config) = &demo {
+        api = api.layer(middleware::from_fn_with_state(
+            RateLimiter::new(config.requests_per_minute),
+            demo::rate_limit,
+        ));
+    }
+
+    let app = api
         // Health check
         .route("/health", get(health_check))
         // Global middleware
         .layer(
             ServiceBuilder::new()
                 .layer(TraceLayer::new_for_http())
+                .layer(CompressionLayer::new())
                 .layer(cors),
         )
         .with_state(state);
@@ -132,10 +303,13 @@ async fn main() -> Result<()> {
         .map_err(|e| anyhow::anyhow!("Failed to bind to address: {}", e))?;
 
     // Start server with graceful shutdown
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await
-        .map_err(|e| anyhow::anyhow!("Server error: {}", e))?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await
+    .map_err(|e| anyhow::anyhow!("Server error: {}", e))?;
 
     info!("Server shutdown complete");
     Ok(())
@@ -0,0 +1,76 @@
+// crates/satisflow-server/src/metrics_history.rs
+//! Dashboard metrics over time
+//!
+//! Every write that changes the engine records the global power balance and
+//! the net rate of every item at the new revision, so users can see how their
+//! plan evolved over a session. Only the latest points are kept, in memory.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use satisflow_engine::models::MetricsPoint;
+use tokio::sync::RwLock;
+
+/// Number of points kept; older ones are dropped
+pub const DEFAULT_CAPACITY: usize = 1000;
+
+/// Ring buffer of the most recent metrics points, oldest first
+#[derive(Debug, Clone)]
+pub struct MetricsHistory {
+    points: Arc<RwLock<VecDeque<MetricsPoint>>>,
+    capacity: usize,
+}
+
+impl Default for MetricsHistory {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+}
+
+impl MetricsHistory {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            points: Arc::default(),
+            capacity,
+        }
+    }
+
+    /// Add a point, dropping the oldest one when full
+    pub async fn push(&self, point: MetricsPoint) {
+        let mut points = self.points.write().await;
+        if points.len() == self.capacity {
+            points.pop_front();
+        }
+        points.push_back(point);
+    }
+
+    /// Every recorded point, oldest first
+    pub async fn points(&self) -> Vec<MetricsPoint> {
+        self.points.read().await.iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use satisflow_engine::SatisflowEngine;
+
+    #[tokio::test]
+    async fn test_oldest_points_are_dropped() {
+        let history = MetricsHistory::with_capacity(2);
+        let mut engine = SatisflowEngine::new();
+        for name in ["Smelter", "Assembly", "Refinery"] {
+            engine.create_factory(name.to_string(), None);
+            history.push(engine.metrics_point()).await;
+        }
+
+        let revisions: Vec<u64> = history
+            .points()
+            .await
+            .iter()
+            .map(|point| point.revision)
+            .collect();
+        assert_eq!(revisions, vec![2, 3]);
+        assert!(history.points().await[0].items.is_empty());
+    }
+}
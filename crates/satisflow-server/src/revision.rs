@@ -0,0 +1,139 @@
+// crates/satisflow-server/src/revision.rs
+//! Optimistic concurrency
+//!
+//! Successful responses carry the engine revision as their `ETag`, unless the
+//! handler set one of its own. A client sending that value back in `If-Match`
+//! with a write gets `409 Conflict` instead of overwriting a change made since
+//! it read the state, e.g. in another tab.
+//!
+//! Writes go through one at a time, so nothing can slip in between checking
+//! `If-Match` and applying the change. A write that changed the engine also
+//! records a point in the dashboard metrics history, checks it for a power
+//! deficit to send to webhooks and, when it succeeded, adds an entry to the
+//! audit log.
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    audit_log::{path_entity_id, Actor, AuditEntry},
+    error::AppError,
+    state::AppState,
+};
+
+/// `ETag` value of a revision
+pub fn etag(revision: u64) -> HeaderValue {
+    HeaderValue::from_str(&format!("\"{}\"", revision)).expect("digits are a valid header value")
+}
+
+/// Whether an `If-Match` header value accepts `revision`
+///
+/// The value is `*` or a comma separated list of entity tags. Weak tags never
+/// match, as `If-Match` uses the strong comparison.
+pub fn if_match_accepts(value: &str, revision: u64) -> bool {
+    let current = format!("\"{}\"", revision);
+    value
+        .split(',')
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag == current)
+}
+
+/// Middleware adding the `ETag` header and enforcing `If-Match`
+///
+/// Reads are tagged with the revision from before they ran, so a write landing
+/// meanwhile makes the tag stale rather than letting a stale read pass.
+pub async fn check_revision(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if matches!(*request.method(), Method::GET | Method::HEAD) {
+        let revision = state.engine.snapshot().revision();
+        let response = next.run(request).await;
+        return with_etag(response, revision);
+    }
+
+    let _write = state.write_gate.lock().await;
+    let revision = state.engine.snapshot().revision();
+    if let Some(value) = if_match(request.headers()) {
+        if !if_match_accepts(value, revision) {
+            return AppError::Conflict(format!(
+                "The state changed since it was read (now at revision {}), reload and try again",
+                revision
+            ))
+            .into_response();
+        }
+    }
+
+    let method = request.method().to_string();
+    let path = request.uri().path().to_string();
+    let actor = request.extensions().get::<Actor>().cloned();
+
+    let mut response = next.run(request).await;
+    let engine = state.record_write(revision).await;
+    if engine.revision() != revision && response.status().is_success() {
+        let mut entity_id = path_entity_id(&path);
+        if entity_id.is_none() && response.status() == StatusCode::CREATED {
+            let (id, created) = created_id(response).await;
+            entity_id = id;
+            response = created;
+        }
+        let entry = AuditEntry::new(engine.revision(), method, path, entity_id, actor.as_ref());
+        if let Err(e) = state.audit_log.record(entry).await {
+            warn!("Failed to write the audit log: {}", e);
+        }
+    }
+    with_etag(response, engine.revision())
+}
+
+/// ID of the entity in a `201 Created` body, and the response to send on
+async fn created_id(response: Response) -> (Option<Uuid>, Response) {
+    let (parts, body) = response.into_parts();
+    let Ok(bytes) = to_bytes(body, usize::MAX).await else {
+        return (None, Response::from_parts(parts, Body::empty()));
+    };
+    let id = serde_json::from_slice::<serde_json::Value>(&bytes)
+        .ok()
+        .and_then(|body| body.get("id")?.as_str()?.parse().ok());
+    (id, Response::from_parts(parts, Body::from(bytes)))
+}
+
+fn if_match(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::IF_MATCH)
+        .map(|value| value.to_str().unwrap_or_default())
+}
+
+/// Tag a successful response with `revision`, unless its handler already set
+/// an `ETag` of its own
+fn with_etag(mut response: Response, revision: u64) -> Response {
+    if response.status().is_success() {
+        response
+            .headers_mut()
+            .entry(header::ETAG)
+            .or_insert_with(|| etag(revision));
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_if_match_accepts() {
+        assert!(if_match_accepts("\"3\"", 3));
+        assert!(if_match_accepts("\"1\", \"3\"", 3));
+        assert!(if_match_accepts("*", 7));
+        assert!(!if_match_accepts("\"2\"", 3));
+        assert!(!if_match_accepts("W/\"3\"", 3));
+        assert!(!if_match_accepts("", 3));
+    }
+}
@@ -0,0 +1,68 @@
+// crates/satisflow-server/src/revision_cache.rs
+//! Values computed from the engine, kept until the engine changes
+//!
+//! A cached value is tagged with the engine revision it was computed at and is
+//! fresh while the revision stays the same. Nothing needs to be invalidated by
+//! hand: every mutation bumps the revision, replacing the engine included.
+
+use std::sync::Arc;
+
+use tokio::sync::RwLock;
+
+/// Holds the last value computed from the engine and its revision
+#[derive(Debug)]
+pub struct RevisionCache<T> {
+    entry: Arc<RwLock<Option<(u64, T)>>>,
+}
+
+impl<T> Default for RevisionCache<T> {
+    fn default() -> Self {
+        Self {
+            entry: Arc::default(),
+        }
+    }
+}
+
+impl<T> Clone for RevisionCache<T> {
+    fn clone(&self) -> Self {
+        Self {
+            entry: self.entry.clone(),
+        }
+    }
+}
+
+impl<T: Clone> RevisionCache<T> {
+    /// The cached value, if it was computed at `revision`
+    pub async fn get(&self, revision: u64) -> Option<T> {
+        match &*self.entry.read().await {
+            Some((cached, value)) if *cached == revision => Some(value.clone()),
+            _ => None,
+        }
+    }
+
+    /// Cache `value`, computed at `revision`
+    pub async fn store(&self, revision: u64, value: T) {
+        *self.entry.write().await = Some((revision, value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_value_is_fresh_for_its_revision_only() {
+        let cache = RevisionCache::default();
+        assert_eq!(cache.get(0).await, None);
+
+        cache.store(3, "summary").await;
+        assert_eq!(cache.get(3).await, Some("summary"));
+        assert_eq!(cache.get(4).await, None);
+
+        // Clones share the cached value
+        let shared = cache.clone();
+        shared.store(4, "newer").await;
+        assert_eq!(cache.get(4).await, Some("newer"));
+        assert_eq!(cache.get(3).await, None);
+    }
+}
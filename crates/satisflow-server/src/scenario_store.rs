@@ -0,0 +1,79 @@
+// crates/satisflow-server/src/scenario_store.rs
+//! What-if scenarios: sandboxes forked from the engine
+//!
+//! A scenario starts as a copy of the engine state and is changed through
+//! `/api/scenarios/{id}/...` like the real state is through `/api`, without
+//! touching it. Committing a scenario replaces the real state with it.
+//! Scenarios are kept in memory only.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::Router;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::RwLock;
+
+use crate::state::AppState;
+
+/// Description of a scenario
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScenarioInfo {
+    pub id: String,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    /// Revision of the real state the scenario was forked from
+    pub base_revision: u64,
+}
+
+/// A sandbox forked from the engine
+#[derive(Clone)]
+pub struct Scenario {
+    pub info: ScenarioInfo,
+    /// State the scenario's requests are handled with
+    pub state: AppState,
+    /// Engine routes bound to `state`
+    pub router: Router,
+}
+
+/// The scenarios forked from one engine
+#[derive(Clone, Default)]
+pub struct ScenarioStore {
+    scenarios: Arc<RwLock<HashMap<String, Scenario>>>,
+}
+
+impl ScenarioStore {
+    pub async fn insert(&self, scenario: Scenario) {
+        self.scenarios
+            .write()
+            .await
+            .insert(scenario.info.id.clone(), scenario);
+    }
+
+    /// # Returns
+    ///
+    /// The scenario, or `None` if there is no scenario with this id
+    pub async fn get(&self, id: &str) -> Option<Scenario> {
+        self.scenarios.read().await.get(id).cloned()
+    }
+
+    /// Describe every scenario by creation time
+    pub async fn list(&self) -> Vec<ScenarioInfo> {
+        let mut infos: Vec<ScenarioInfo> = self
+            .scenarios
+            .read()
+            .await
+            .values()
+            .map(|scenario| scenario.info.clone())
+            .collect();
+        infos.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+        infos
+    }
+
+    /// # Returns
+    ///
+    /// The removed scenario, or `None` if there is no scenario with this id
+    pub async fn remove(&self, id: &str) -> Option<Scenario> {
+        self.scenarios.write().await.remove(id)
+    }
+}
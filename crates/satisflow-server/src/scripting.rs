@@ -0,0 +1,253 @@
+// crates/satisflow-server/src/scripting.rs
+//! User scripts
+//!
+//! Scripts are written in [Rhai](https://rhai.rs) and run against a copy of
+//! the save, bound to the `save` variable with the same shape as a JSON save
+//! file. Whatever the script evaluates to is returned as its result, so it can
+//! build custom reports; changes it makes to `save` are turned back into an
+//! engine state the caller may apply, which makes bulk edits possible:
+//!
+//! ```text
+//! for id in save.engine.factories.keys() {
+//!     let factory = save.engine.factories[id];
+//!     for line in factory.production_lines.values() {
+//!         let line = line.ProductionLineRecipe;
+//!         if line != () && "Screw" in recipe_outputs(line.recipe) {
+//!             for i in 0..line.machine_groups.len() {
+//!                 line.machine_groups[i].oc_value *= 0.9;
+//!             }
+//!             factory.production_lines[line.id] = #{ ProductionLineRecipe: line };
+//!         }
+//!     }
+//!     save.engine.factories[id] = factory;
+//! }
+//! ```
+//!
+//! Rhai loops over copies, hence the assignments back into `save`. Scripts
+//! can't reach the file system or the network, and are stopped once they run
+//! too many operations.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use rhai::{Array, Dynamic, Engine, Scope};
+use satisflow_engine::{
+    models::{recipe_info, Recipe},
+    SatisflowEngine,
+};
+use serde_json::Value;
+
+use crate::error::{AppError, Result};
+
+/// Operations a script may run before it is stopped
+const MAX_OPERATIONS: u64 = 10_000_000;
+/// Bytes in one string, so a script can't exhaust memory well within its
+/// operations; far above any name or note in a save
+const MAX_STRING_SIZE: usize = 1 << 20;
+/// Elements in one array or entries in one map, factories of a save included
+const MAX_COLLECTION_SIZE: usize = 100_000;
+/// Nested function calls
+const MAX_CALL_LEVELS: usize = 64;
+
+/// What a script produced
+#[derive(Debug)]
+pub struct ScriptOutcome {
+    /// Value the script evaluated to, `null` for none
+    pub result: Value,
+    /// Lines the script printed
+    pub output: Vec<String>,
+    /// The state with the script's edits, if it changed `save`
+    pub edited: Option<SatisflowEngine>,
+}
+
+/// Run a script against a copy of `engine`
+///
+/// # Returns
+///
+/// The outcome, or a `400 Bad Request` if the script doesn't compile, fails,
+/// runs too long or leaves `save` in a state that can't be loaded
+pub fn run(script: &str, engine: &SatisflowEngine) -> Result<ScriptOutcome> {
+    let save: Value = serde_json::from_str(
+        &engine
+            .save_to_json()
+            .map_err(|e| anyhow::anyhow!("Failed to save the state: {}", e))?,
+    )?;
+
+    let output = Rc::new(RefCell::new(Vec::new()));
+    let mut rhai = Engine::new();
+    rhai.set_max_operations(MAX_OPERATIONS);
+    rhai.set_max_string_size(MAX_STRING_SIZE);
+    rhai.set_max_array_size(MAX_COLLECTION_SIZE);
+    rhai.set_max_map_size(MAX_COLLECTION_SIZE);
+    rhai.set_max_call_levels(MAX_CALL_LEVELS);
+    let printed = output.clone();
+    rhai.on_print(move |line| printed.borrow_mut().push(line.to_string()));
+    let debugged = output.clone();
+    rhai.on_debug(move |line, _, _| debugged.borrow_mut().push(line.to_string()));
+    rhai.register_fn("recipe_outputs", recipe_outputs);
+
+    let mut scope = Scope::new();
+    scope.push("save", to_dynamic(&save)?);
+    let result: Dynamic = rhai
+        .eval_with_scope(&mut scope, script)
+        .map_err(|e| AppError::BadRequest(format!("Script failed: {}", e)))?;
+
+    let result = from_dynamic(&result)?;
+    let edited_save = from_dynamic(&scope.get_value::<Dynamic>("save").unwrap_or_default())?;
+    let edited = if edited_save == save {
+        None
+    } else {
+        let edited = SatisflowEngine::load_from_json(&edited_save.to_string())
+            .map_err(|e| AppError::BadRequest(format!("The script left an invalid save: {}", e)))?;
+        Some(edited)
+    };
+
+    let output = output.borrow().clone();
+    Ok(ScriptOutcome {
+        result,
+        output,
+        edited,
+    })
+}
+
+/// Items a recipe makes, named as in a save file
+///
+/// Unknown recipes, including custom ones, make nothing.
+fn recipe_outputs(recipe: &str) -> Array {
+    let Ok(recipe) = serde_json::from_value::<Recipe>(Value::String(recipe.to_string())) else {
+        return Array::new();
+    };
+    recipe_info(recipe)
+        .outputs
+        .iter()
+        .filter_map(|(item, _)| match serde_json::to_value(item) {
+            Ok(Value::String(name)) => Some(Dynamic::from(name)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn to_dynamic(value: &Value) -> Result<Dynamic> {
+    rhai::serde::to_dynamic(value)
+        .map_err(|e| anyhow::anyhow!("Failed to hand the save to the script: {}", e).into())
+}
+
+fn from_dynamic(value: &Dynamic) -> Result<Value> {
+    rhai::serde::from_dynamic(value)
+        .map_err(|e| AppError::BadRequest(format!("The script produced an invalid value: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use satisflow_engine::models::{production_line::ProductionLineBuilder, Item};
+
+    fn engine_with_screws() -> SatisflowEngine {
+        let mut engine = SatisflowEngine::new();
+        let factory_id = engine.create_factory("Screws".to_string(), None);
+        let line = ProductionLineBuilder::recipe("Screws", Recipe::Screw)
+            .machines(4, 100.0, 0)
+            .build()
+            .unwrap();
+        engine
+            .get_factory_mut(factory_id)
+            .unwrap()
+            .add_production_line(line);
+        engine
+    }
+
+    #[test]
+    fn test_script_report() {
+        let engine = engine_with_screws();
+        let outcome = run(
+            r#"
+                print("counting");
+                let lines = 0;
+                for factory in save.engine.factories.values() {
+                    lines += factory.production_lines.len();
+                }
+                #{ lines: lines, outputs: recipe_outputs("Screw") }
+            "#,
+            &engine,
+        )
+        .unwrap();
+
+        assert_eq!(
+            outcome.result,
+            serde_json::json!({ "lines": 1, "outputs": ["Screw"] })
+        );
+        assert_eq!(outcome.output, vec!["counting"]);
+        assert!(outcome.edited.is_none());
+    }
+
+    #[test]
+    fn test_script_bulk_edit() {
+        let engine = engine_with_screws();
+        let outcome = run(
+            r#"
+                for id in save.engine.factories.keys() {
+                    let factory = save.engine.factories[id];
+                    for line in factory.production_lines.values() {
+                        let line = line.ProductionLineRecipe;
+                        if line != () && "Screw" in recipe_outputs(line.recipe) {
+                            for i in 0..line.machine_groups.len() {
+                                line.machine_groups[i].oc_value *= 0.9;
+                            }
+                            factory.production_lines[line.id] = #{ ProductionLineRecipe: line };
+                        }
+                    }
+                    save.engine.factories[id] = factory;
+                }
+            "#,
+            &engine,
+        )
+        .unwrap();
+
+        let edited = outcome.edited.expect("the script changed the save");
+        let rate = |engine: &SatisflowEngine| {
+            engine
+                .get_all_factories()
+                .values()
+                .map(|factory| factory.production_rate(Item::Screw))
+                .sum::<f64>()
+        };
+        assert!((rate(&edited) - rate(&engine) * 0.9).abs() < 1e-6);
+        // The live state is untouched
+        assert_eq!(engine.get_all_factories().len(), 1);
+    }
+
+    #[test]
+    fn test_script_errors() {
+        let engine = SatisflowEngine::new();
+        for script in [
+            "let x = ;",
+            "undefined_function()",
+            "loop {}",
+            r#"save.version = "not a version";"#,
+        ] {
+            assert!(
+                matches!(run(script, &engine), Err(AppError::BadRequest(_))),
+                "{}",
+                script
+            );
+        }
+    }
+
+    #[test]
+    fn test_script_memory_limits() {
+        let engine = SatisflowEngine::new();
+        // Each limit stops the script long before it runs out of operations
+        for (script, error) in [
+            (r#"let s = "x"; loop { s += s; }"#, "Length of string"),
+            ("let a = [1]; loop { a += a; }", "Size of array"),
+            ("fn f(n) { f(n + 1) } f(0)", "Stack overflow"),
+        ] {
+            match run(script, &engine) {
+                Err(AppError::BadRequest(message)) => {
+                    assert!(message.contains(error), "{}", message)
+                }
+                other => panic!("{}: {:?}", script, other.map(|outcome| outcome.result)),
+            }
+        }
+    }
+}
@@ -0,0 +1,218 @@
+// crates/satisflow-server/src/shared_engine.rs
+//! The engine shared by request handlers, as immutable versions
+//!
+//! Readers take the current version and keep it for as long as they need,
+//! without holding any lock: a write never changes a version, it builds the
+//! next one on a copy and swaps it in when it commits. Writers go one at a
+//! time, and only copy the engine once they actually change it.
+//!
+//! Every published version has its calculations up to date, so readers get
+//! item balances and goal progress from `&self` without recalculating.
+
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, PoisonError, RwLock};
+
+use satisflow_engine::SatisflowEngine;
+use tokio::sync::{Mutex, MutexGuard};
+
+/// Holds the current version of the engine
+#[derive(Debug, Clone)]
+pub struct SharedEngine {
+    /// Only locked to clone or swap the pointer
+    current: Arc<RwLock<Arc<SatisflowEngine>>>,
+    writer: Arc<Mutex<()>>,
+}
+
+impl Default for SharedEngine {
+    fn default() -> Self {
+        Self::new(SatisflowEngine::new())
+    }
+}
+
+impl SharedEngine {
+    pub fn new(mut engine: SatisflowEngine) -> Self {
+        engine.update();
+        Self {
+            current: Arc::new(RwLock::new(Arc::new(engine))),
+            writer: Arc::default(),
+        }
+    }
+
+    /// The current version, which later writes leave untouched
+    pub fn snapshot(&self) -> Arc<SatisflowEngine> {
+        self.current
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Wait for the other writers, then start the next version
+    ///
+    /// Changes made through the guard are only published by
+    /// [`EngineWriteGuard::commit`], which is also when observers hear of
+    /// them; dropping the guard discards them, so a request failing halfway
+    /// leaves the state as it was and notifies nobody.
+    pub async fn write(&self) -> EngineWriteGuard<'_> {
+        let writer = self.writer.lock().await;
+        EngineWriteGuard {
+            shared: self,
+            base: self.snapshot(),
+            next: None,
+            _writer: writer,
+        }
+    }
+
+    fn publish(&self, engine: SatisflowEngine) -> Arc<SatisflowEngine> {
+        let engine = Arc::new(engine);
+        *self.current.write().unwrap_or_else(PoisonError::into_inner) = engine.clone();
+        engine
+    }
+}
+
+/// Write access to the next version of the engine
+///
+/// Reads go to the current version until the first mutable borrow copies it
+/// with [`SatisflowEngine::next_version`].
+pub struct EngineWriteGuard<'a> {
+    shared: &'a SharedEngine,
+    base: Arc<SatisflowEngine>,
+    next: Option<SatisflowEngine>,
+    _writer: MutexGuard<'a, ()>,
+}
+
+impl Deref for EngineWriteGuard<'_> {
+    type Target = SatisflowEngine;
+
+    fn deref(&self) -> &SatisflowEngine {
+        self.next.as_ref().unwrap_or(&self.base)
+    }
+}
+
+impl DerefMut for EngineWriteGuard<'_> {
+    fn deref_mut(&mut self) -> &mut SatisflowEngine {
+        self.next.get_or_insert_with(|| self.base.next_version())
+    }
+}
+
+impl EngineWriteGuard<'_> {
+    /// Recalculate the changed engine, notify its observers of the changes
+    /// and publish it as the current version
+    ///
+    /// # Returns
+    ///
+    /// The published version, to build the response from
+    pub fn commit(mut self) -> Arc<SatisflowEngine> {
+        match self.next.take() {
+            Some(mut next) => {
+                next.update();
+                next.dispatch_pending_events();
+                self.shared.publish(next)
+            }
+            None => self.base.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use satisflow_engine::models::{ExtractorType, FactoryId, Item, Purity, RawInput, RawInputId};
+
+    #[tokio::test]
+    async fn snapshots_keep_their_version_while_a_write_is_applied() {
+        let shared = SharedEngine::default();
+        let before = shared.snapshot();
+
+        let mut engine = shared.write().await;
+        let id = engine.create_factory("Smelter".to_string(), None);
+        assert!(engine.get_factory(id).is_some());
+        let during = shared.snapshot();
+        assert!(during.get_factory(id).is_none());
+        engine.commit();
+
+        assert!(before.get_factory(id).is_none());
+        assert_eq!(during.revision(), before.revision());
+        let after = shared.snapshot();
+        assert!(after.get_factory(id).is_some());
+        assert!(after.revision() > before.revision());
+    }
+
+    #[tokio::test]
+    async fn writes_without_changes_keep_the_version() {
+        let shared = SharedEngine::default();
+        let before = shared.snapshot();
+
+        let engine = shared.write().await;
+        assert_eq!(engine.revision(), before.revision());
+        engine.commit();
+
+        assert!(Arc::ptr_eq(&before, &shared.snapshot()));
+    }
+
+    #[tokio::test]
+    async fn published_versions_are_recalculated() {
+        let shared = SharedEngine::default();
+        let mut engine = shared.write().await;
+        let id = engine.create_factory("Outpost".to_string(), None);
+        let miner = RawInput::new(
+            RawInputId::new_v4(),
+            ExtractorType::MinerMk1,
+            Item::IronOre,
+            Some(Purity::Normal),
+            100.0,
+            1,
+        )
+        .unwrap();
+        engine
+            .get_factory_mut(id)
+            .unwrap()
+            .add_raw_input(miner)
+            .unwrap();
+        engine.commit();
+
+        assert_eq!(shared.snapshot().global_items()[&Item::IronOre], 60.0);
+    }
+
+    #[tokio::test]
+    async fn observers_carry_over_to_the_next_version() {
+        let shared = SharedEngine::default();
+        let events = Arc::new(std::sync::Mutex::new(0));
+        let recorded = events.clone();
+        let mut engine = shared.write().await;
+        engine.subscribe(move |_| *recorded.lock().unwrap() += 1);
+        engine.commit();
+
+        let mut engine = shared.write().await;
+        engine.create_factory("Smelter".to_string(), None);
+        assert_eq!(*events.lock().unwrap(), 0);
+        engine.commit();
+        assert_eq!(*events.lock().unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn failed_writes_are_discarded() {
+        let shared = SharedEngine::default();
+        let events = Arc::new(std::sync::Mutex::new(0));
+        let recorded = events.clone();
+        let mut engine = shared.write().await;
+        engine.subscribe(move |_| *recorded.lock().unwrap() += 1);
+        engine.commit();
+        let before = shared.snapshot();
+
+        let result: Result<(), String> = async {
+            let mut engine = shared.write().await;
+            engine.create_factory("Smelter".to_string(), None);
+            engine
+                .delete_factory(FactoryId::new_v4())
+                .map_err(|e| e.to_string())?;
+            engine.commit();
+            Ok(())
+        }
+        .await;
+
+        assert!(result.is_err());
+        assert!(Arc::ptr_eq(&before, &shared.snapshot()));
+        assert!(shared.snapshot().get_all_factories().is_empty());
+        assert_eq!(*events.lock().unwrap(), 0);
+    }
+}
@@ -0,0 +1,202 @@
+// crates/satisflow-server/src/snapshot_store.rs
+//! Labelled checkpoints of the engine state
+//!
+//! Snapshots are kept in memory, or written to `SNAPSHOT_DIR` (one JSON file
+//! per snapshot) when it is set so they survive restarts.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::env;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use satisflow_engine::SaveFileSummary;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Description of a snapshot, without its save data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotInfo {
+    pub id: Uuid,
+    pub label: String,
+    pub created_at: DateTime<Utc>,
+    pub summary: SaveFileSummary,
+}
+
+/// A snapshot and the save file it restores
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    #[serde(flatten)]
+    pub info: SnapshotInfo,
+    /// JSON string of the save file
+    pub save_data: String,
+}
+
+/// Where snapshots are kept
+#[derive(Debug, Clone)]
+pub enum SnapshotStore {
+    /// Lost when the server stops
+    Memory(Arc<RwLock<HashMap<Uuid, Snapshot>>>),
+    /// One `<id>.json` file per snapshot
+    Directory(PathBuf),
+}
+
+impl Default for SnapshotStore {
+    fn default() -> Self {
+        Self::Memory(Arc::default())
+    }
+}
+
+impl SnapshotStore {
+    /// Store snapshots in `SNAPSHOT_DIR` when it is set, in memory otherwise
+    pub fn from_env() -> Self {
+        match env::var("SNAPSHOT_DIR") {
+            Ok(dir) if !dir.trim().is_empty() => Self::Directory(PathBuf::from(dir.trim())),
+            _ => Self::default(),
+        }
+    }
+
+    /// Store for the snapshots of a workspace other than the default one
+    pub fn for_workspace(&self, id: &str) -> Self {
+        match self {
+            Self::Memory(_) => Self::default(),
+            Self::Directory(dir) => Self::Directory(dir.join("workspaces").join(id)),
+        }
+    }
+
+    pub async fn insert(&self, snapshot: Snapshot) -> anyhow::Result<()> {
+        match self {
+            Self::Memory(snapshots) => {
+                snapshots.write().await.insert(snapshot.info.id, snapshot);
+            }
+            Self::Directory(dir) => {
+                tokio::fs::create_dir_all(dir).await?;
+                let path = dir.join(format!("{}.json", snapshot.info.id));
+                tokio::fs::write(path, serde_json::to_vec(&snapshot)?).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// # Returns
+    ///
+    /// The snapshot, or `None` if there is no snapshot with this id
+    pub async fn get(&self, id: Uuid) -> anyhow::Result<Option<Snapshot>> {
+        match self {
+            Self::Memory(snapshots) => Ok(snapshots.read().await.get(&id).cloned()),
+            Self::Directory(dir) => match tokio::fs::read(dir.join(format!("{}.json", id))).await {
+                Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(e.into()),
+            },
+        }
+    }
+
+    /// Describe every snapshot, newest first
+    pub async fn list(&self) -> anyhow::Result<Vec<SnapshotInfo>> {
+        let mut infos: Vec<SnapshotInfo> = match self {
+            Self::Memory(snapshots) => snapshots
+                .read()
+                .await
+                .values()
+                .map(|snapshot| snapshot.info.clone())
+                .collect(),
+            Self::Directory(dir) => {
+                let mut infos = Vec::new();
+                let mut entries = match tokio::fs::read_dir(dir).await {
+                    Ok(entries) => entries,
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(infos),
+                    Err(e) => return Err(e.into()),
+                };
+                while let Some(entry) = entries.next_entry().await? {
+                    let path = entry.path();
+                    if path
+                        .extension()
+                        .is_some_and(|extension| extension == "json")
+                    {
+                        let snapshot: Snapshot =
+                            serde_json::from_slice(&tokio::fs::read(&path).await?)?;
+                        infos.push(snapshot.info);
+                    }
+                }
+                infos
+            }
+        };
+        infos.sort_by_key(|info| Reverse(info.created_at));
+        Ok(infos)
+    }
+
+    /// # Returns
+    ///
+    /// `false` if there is no snapshot with this id
+    pub async fn remove(&self, id: Uuid) -> anyhow::Result<bool> {
+        match self {
+            Self::Memory(snapshots) => Ok(snapshots.write().await.remove(&id).is_some()),
+            Self::Directory(dir) => {
+                match tokio::fs::remove_file(dir.join(format!("{}.json", id))).await {
+                    Ok(()) => Ok(true),
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+                    Err(e) => Err(e.into()),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(label: &str, created_at: DateTime<Utc>) -> Snapshot {
+        Snapshot {
+            info: SnapshotInfo {
+                id: Uuid::new_v4(),
+                label: label.to_string(),
+                created_at,
+                summary: SaveFileSummary {
+                    version: "0.2.0".to_string(),
+                    created_at,
+                    last_modified: created_at,
+                    factory_count: 0,
+                    logistics_count: 0,
+                    blueprint_template_count: 0,
+                },
+            },
+            save_data: "{}".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stores_keep_snapshots() {
+        let dir = env::temp_dir().join(format!("satisflow-snapshots-{}", Uuid::new_v4()));
+        for store in [
+            SnapshotStore::default(),
+            SnapshotStore::Directory(dir.clone()),
+        ] {
+            assert!(store.list().await.unwrap().is_empty());
+
+            let older = snapshot("Before refactor", Utc::now() - chrono::Duration::hours(1));
+            let newer = snapshot("After refactor", Utc::now());
+            store.insert(older.clone()).await.unwrap();
+            store.insert(newer.clone()).await.unwrap();
+
+            let labels: Vec<String> = store
+                .list()
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|info| info.label)
+                .collect();
+            assert_eq!(labels, ["After refactor", "Before refactor"]);
+            let found = store.get(older.info.id).await.unwrap().unwrap();
+            assert_eq!(found.save_data, "{}");
+
+            assert!(store.remove(older.info.id).await.unwrap());
+            assert!(!store.remove(older.info.id).await.unwrap());
+            assert!(store.get(older.info.id).await.unwrap().is_none());
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
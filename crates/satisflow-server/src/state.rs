@@ -1,11 +1,41 @@
 // crates/satisflow-server/src/state.rs
+use crate::audit_log::AuditLog;
+use crate::autosave::AutosaveConfig;
+use crate::handlers::dashboard::DashboardSummary;
+use crate::metrics_history::MetricsHistory;
+use crate::revision_cache::RevisionCache;
+use crate::scenario_store::ScenarioStore;
+use crate::shared_engine::SharedEngine;
+use crate::snapshot_store::SnapshotStore;
+use crate::webhooks::Webhooks;
+use crate::workspace_store::WorkspaceStore;
 use satisflow_engine::SatisflowEngine;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::Mutex;
 
 #[derive(Clone)]
 pub struct AppState {
-    pub engine: Arc<RwLock<SatisflowEngine>>,
+    /// Read through snapshots, see [`SharedEngine`]
+    pub engine: SharedEngine,
+    /// Public demo server: saving and loading are refused
+    pub demo_mode: bool,
+    /// Background autosave settings, `None` when autosave is off
+    pub autosave: Option<AutosaveConfig>,
+    pub snapshots: SnapshotStore,
+    /// Held by requests that may write, so `If-Match` checks can't race
+    pub write_gate: Arc<Mutex<()>>,
+    /// Workspaces besides the one held by this state
+    pub workspaces: WorkspaceStore,
+    /// Dashboard summary of the current engine revision
+    pub dashboard_summary: RevisionCache<DashboardSummary>,
+    /// Dashboard metrics recorded after each change to the engine
+    pub metrics_history: MetricsHistory,
+    /// What-if sandboxes forked from the engine
+    pub scenarios: ScenarioStore,
+    /// Every change made to the engine through the API
+    pub audit_log: AuditLog,
+    /// Events sent to the configured webhook URLs
+    pub webhooks: Webhooks,
 }
 
 impl Default for AppState {
@@ -17,7 +47,44 @@ impl Default for AppState {
 impl AppState {
     pub fn new() -> Self {
         Self {
-            engine: Arc::new(RwLock::new(SatisflowEngine::new())),
+            engine: SharedEngine::default(),
+            demo_mode: false,
+            autosave: None,
+            snapshots: SnapshotStore::default(),
+            write_gate: Arc::default(),
+            workspaces: WorkspaceStore::default(),
+            dashboard_summary: RevisionCache::default(),
+            metrics_history: MetricsHistory::default(),
+            scenarios: ScenarioStore::default(),
+            audit_log: AuditLog::default(),
+            webhooks: Webhooks::default(),
         }
     }
+
+    /// State of a public demo server
+    pub fn demo() -> Self {
+        Self {
+            demo_mode: true,
+            ..Self::new()
+        }
+    }
+
+    /// Follow up a write made under the write gate, from any API
+    ///
+    /// When the engine changed since `revision`, records a point in the
+    /// dashboard metrics history and checks it for a power deficit to send to
+    /// webhooks.
+    ///
+    /// # Returns
+    ///
+    /// The engine as the write left it
+    pub async fn record_write(&self, revision: u64) -> Arc<SatisflowEngine> {
+        let engine = self.engine.snapshot();
+        if engine.revision() != revision {
+            let point = engine.metrics_point();
+            self.webhooks.check_power(&point);
+            self.metrics_history.push(point).await;
+        }
+        engine
+    }
 }
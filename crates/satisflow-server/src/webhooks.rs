@@ -0,0 +1,368 @@
+// crates/satisflow-server/src/webhooks.rs
+//! Outbound webhooks
+//!
+//! When `WEBHOOK_URLS` is set every listed URL is sent a JSON `POST` for the
+//! events named in `WEBHOOK_EVENTS`, all of them by default, so chat bots and
+//! automations can react to planning changes. Deliveries run in the
+//! background; one that fails is retried with exponential backoff and dropped
+//! after a few attempts.
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use chrono::{DateTime, Utc};
+use satisflow_engine::{models::MetricsPoint, rate, EngineEvent, SatisflowEngine};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tokio::sync::mpsc;
+use tracing::warn;
+
+/// Attempts made to deliver an event to a URL before giving up
+pub const MAX_ATTEMPTS: u32 = 5;
+/// Wait before the first retry, doubled after every failed attempt
+pub const RETRY_DELAY: Duration = Duration::from_secs(1);
+/// Time a webhook receiver has to answer
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Something a webhook can be sent for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// A snapshot or an autosave was written
+    SaveCreated,
+    /// The power consumption went over the generation
+    PowerDeficit,
+    FactoryDeleted,
+}
+
+impl WebhookEvent {
+    pub const ALL: [WebhookEvent; 3] = [
+        WebhookEvent::SaveCreated,
+        WebhookEvent::PowerDeficit,
+        WebhookEvent::FactoryDeleted,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::SaveCreated => "save_created",
+            WebhookEvent::PowerDeficit => "power_deficit",
+            WebhookEvent::FactoryDeleted => "factory_deleted",
+        }
+    }
+}
+
+impl fmt::Display for WebhookEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for WebhookEvent {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|event| event.as_str() == s)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Unknown webhook event '{}', expected one of {}",
+                    s,
+                    Self::ALL.map(|event| event.as_str()).join(", ")
+                )
+            })
+    }
+}
+
+/// Body of a webhook request
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WebhookPayload {
+    pub event: WebhookEvent,
+    pub timestamp: DateTime<Utc>,
+    /// Workspace the event happened in, `None` for the default one
+    pub workspace: Option<String>,
+    /// Details of the event, such as the ID of the deleted factory
+    pub data: Value,
+}
+
+/// Settings of the webhooks
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookConfig {
+    pub urls: Vec<String>,
+    /// Events sent to the URLs
+    pub events: HashSet<WebhookEvent>,
+    /// Wait before the first retry of a failed delivery
+    pub retry_delay: Duration,
+}
+
+impl WebhookConfig {
+    /// Read the webhook settings from `WEBHOOK_URLS` and `WEBHOOK_EVENTS`,
+    /// both comma separated
+    ///
+    /// # Returns
+    ///
+    /// `None` unless `WEBHOOK_URLS` is set, or an error if it lists something
+    /// that isn't an HTTP URL or `WEBHOOK_EVENTS` an unknown event
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        let urls = env::var("WEBHOOK_URLS").unwrap_or_default();
+        let events = env::var("WEBHOOK_EVENTS").unwrap_or_default();
+        Self::parse(&urls, &events)
+    }
+
+    fn parse(urls: &str, events: &str) -> anyhow::Result<Option<Self>> {
+        let urls: Vec<String> = split_list(urls).map(str::to_string).collect();
+        if urls.is_empty() {
+            return Ok(None);
+        }
+        if let Some(url) = urls
+            .iter()
+            .find(|url| !url.starts_with("http://") && !url.starts_with("https://"))
+        {
+            return Err(anyhow!("Invalid webhook URL '{}' in WEBHOOK_URLS", url));
+        }
+
+        let mut events = split_list(events)
+            .map(WebhookEvent::from_str)
+            .collect::<anyhow::Result<HashSet<_>>>()?;
+        if events.is_empty() {
+            events.extend(WebhookEvent::ALL);
+        }
+
+        Ok(Some(Self {
+            urls,
+            events,
+            retry_delay: RETRY_DELAY,
+        }))
+    }
+}
+
+fn split_list(list: &str) -> impl Iterator<Item = &str> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+/// Sends the events of one workspace to the configured URLs
+///
+/// The default value sends nothing.
+#[derive(Debug, Clone, Default)]
+pub struct Webhooks {
+    sender: Option<mpsc::UnboundedSender<WebhookPayload>>,
+    events: Arc<HashSet<WebhookEvent>>,
+    workspace: Option<String>,
+    /// Whether the last checked power balance was a deficit
+    in_deficit: Arc<AtomicBool>,
+}
+
+impl Webhooks {
+    /// Start delivering events in the background
+    pub fn start(config: WebhookConfig) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let events = Arc::new(config.events.clone());
+        tokio::spawn(deliver_all(config, receiver));
+        Self {
+            sender: Some(sender),
+            events,
+            workspace: None,
+            in_deficit: Arc::default(),
+        }
+    }
+
+    /// Webhooks for a workspace other than the default one
+    pub fn for_workspace(&self, id: &str) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            events: self.events.clone(),
+            workspace: Some(id.to_string()),
+            in_deficit: Arc::default(),
+        }
+    }
+
+    /// Send an event, unless it isn't one of the configured events
+    pub fn notify(&self, event: WebhookEvent, data: Value) {
+        let Some(sender) = &self.sender else {
+            return;
+        };
+        if !self.events.contains(&event) {
+            return;
+        }
+        let payload = WebhookPayload {
+            event,
+            timestamp: Utc::now(),
+            workspace: self.workspace.clone(),
+            data,
+        };
+        // Only fails once the delivery task is gone, i.e. on shutdown
+        let _ = sender.send(payload);
+    }
+
+    /// Send the events `engine` raises itself, such as factory deletions
+    pub fn observe(&self, engine: &mut SatisflowEngine) {
+        if self.sender.is_none() {
+            return;
+        }
+        let webhooks = self.clone();
+        engine.subscribe(move |event| {
+            if let EngineEvent::FactoryDeleted(id) = event {
+                webhooks.notify(WebhookEvent::FactoryDeleted, json!({ "factory_id": id }));
+            }
+        });
+    }
+
+    /// Send a power deficit event when the power balance of a new revision
+    /// turned negative; a deficit that persists is only reported once
+    pub fn check_power(&self, point: &MetricsPoint) {
+        let in_deficit = rate::balance_sign(point.power_balance) == Ordering::Less;
+        let was_in_deficit = self.in_deficit.swap(in_deficit, AtomicOrdering::Relaxed);
+        if in_deficit && !was_in_deficit {
+            self.notify(
+                WebhookEvent::PowerDeficit,
+                json!({
+                    "revision": point.revision,
+                    "power_generation": point.power_generation,
+                    "power_consumption": point.power_consumption,
+                    "power_balance": point.power_balance,
+                }),
+            );
+        }
+    }
+}
+
+/// Deliver every event sent to `receiver` to every URL
+async fn deliver_all(config: WebhookConfig, mut receiver: mpsc::UnboundedReceiver<WebhookPayload>) {
+    let client = match reqwest::Client::builder().timeout(TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            warn!("Webhooks are off, the HTTP client failed to start: {}", e);
+            return;
+        }
+    };
+    while let Some(payload) = receiver.recv().await {
+        for url in &config.urls {
+            tokio::spawn(deliver(
+                client.clone(),
+                url.clone(),
+                payload.clone(),
+                config.retry_delay,
+            ));
+        }
+    }
+}
+
+/// Send `payload` to `url`, retrying with exponential backoff
+///
+/// # Returns
+///
+/// Whether the receiver accepted the payload
+async fn deliver(
+    client: reqwest::Client,
+    url: String,
+    payload: WebhookPayload,
+    retry_delay: Duration,
+) -> bool {
+    let mut delay = retry_delay;
+    for attempt in 1..=MAX_ATTEMPTS {
+        let error = match client.post(&url).json(&payload).send().await {
+            Ok(response) if response.status().is_success() => return true,
+            Ok(response) => format!("status {}", response.status()),
+            Err(e) => e.to_string(),
+        };
+        if attempt == MAX_ATTEMPTS {
+            warn!(
+                "Gave up sending the {} webhook to {} after {} attempts: {}",
+                payload.event, url, MAX_ATTEMPTS, error
+            );
+        } else {
+            tokio::time::sleep(delay).await;
+            delay *= 2;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    use axum::{http::StatusCode, routing::post, Json, Router};
+
+    #[test]
+    fn test_webhook_config() {
+        assert_eq!(WebhookConfig::parse(" ", "").unwrap(), None);
+
+        let config = WebhookConfig::parse("https://a.example/hook, http://b.example", "")
+            .unwrap()
+            .unwrap();
+        assert_eq!(config.urls, ["https://a.example/hook", "http://b.example"]);
+        assert_eq!(config.events.len(), WebhookEvent::ALL.len());
+
+        let config = WebhookConfig::parse("https://a.example", "factory_deleted")
+            .unwrap()
+            .unwrap();
+        assert_eq!(config.events, HashSet::from([WebhookEvent::FactoryDeleted]));
+
+        assert!(WebhookConfig::parse("ftp://a.example", "").is_err());
+        assert!(WebhookConfig::parse("https://a.example", "factory_renamed").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_webhooks_deliver_with_retries() {
+        // Receiver failing the first request of every payload
+        let requests = Arc::new(AtomicUsize::new(0));
+        let (received, mut payloads) = mpsc::unbounded_channel::<Value>();
+        let counter = requests.clone();
+        let receiver = Router::new().route(
+            "/hook",
+            post(move |Json(payload): Json<Value>| async move {
+                if counter
+                    .fetch_add(1, AtomicOrdering::SeqCst)
+                    .is_multiple_of(2)
+                {
+                    return StatusCode::SERVICE_UNAVAILABLE;
+                }
+                received.send(payload).unwrap();
+                StatusCode::NO_CONTENT
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, receiver).await });
+
+        let webhooks = Webhooks::start(WebhookConfig {
+            urls: vec![url],
+            events: HashSet::from([WebhookEvent::FactoryDeleted, WebhookEvent::PowerDeficit]),
+            retry_delay: Duration::from_millis(10),
+        });
+        let mut engine = SatisflowEngine::new();
+        webhooks.observe(&mut engine);
+        let factory_id = engine.create_factory("Smelter".to_string(), None);
+        engine.delete_factory(factory_id).unwrap();
+        // Not one of the configured events
+        webhooks.notify(WebhookEvent::SaveCreated, Value::Null);
+
+        let payload = payloads.recv().await.unwrap();
+        assert_eq!(payload["event"], "factory_deleted");
+        assert_eq!(payload["data"]["factory_id"], factory_id.to_string());
+        assert_eq!(requests.load(AtomicOrdering::SeqCst), 2);
+
+        // A deficit is reported when it starts, not while it lasts
+        let mut point = SatisflowEngine::new().metrics_point();
+        point.power_balance = -50.0;
+        webhooks.check_power(&point);
+        webhooks.check_power(&point);
+        let payload = payloads.recv().await.unwrap();
+        assert_eq!(payload["event"], "power_deficit");
+        assert_eq!(payload["data"]["power_balance"], -50.0);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(payloads.try_recv().is_err());
+        assert_eq!(requests.load(AtomicOrdering::SeqCst), 4);
+    }
+}
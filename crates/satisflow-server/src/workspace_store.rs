@@ -0,0 +1,148 @@
+// crates/satisflow-server/src/workspace_store.rs
+//! Workspaces: separate planning sessions on one server
+//!
+//! Every workspace has its own engine, members and snapshots, which serve as
+//! its save slots. The default workspace is the state served at `/api`; the
+//! others are created at runtime, reached under `/api/workspaces/{id}` and
+//! kept in memory only.
+
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+use axum::Router;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::RwLock;
+
+use crate::state::AppState;
+
+/// Id of the workspace served at `/api`
+pub const DEFAULT_WORKSPACE: &str = "default";
+
+/// Description of a workspace
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WorkspaceInfo {
+    pub id: String,
+    pub name: String,
+    /// Names of the players planning in this workspace
+    pub members: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A workspace other than the default one
+#[derive(Clone)]
+pub struct Workspace {
+    pub info: WorkspaceInfo,
+    /// State the workspace's requests are handled with
+    pub state: AppState,
+    /// Engine routes bound to `state`
+    pub router: Router,
+}
+
+/// The workspaces of a server
+#[derive(Clone)]
+pub struct WorkspaceStore {
+    default_info: Arc<RwLock<WorkspaceInfo>>,
+    /// Engine routes bound to the default workspace, built on first use
+    default_router: Arc<OnceLock<Router>>,
+    workspaces: Arc<RwLock<HashMap<String, Workspace>>>,
+}
+
+impl Default for WorkspaceStore {
+    fn default() -> Self {
+        Self {
+            default_info: Arc::new(RwLock::new(WorkspaceInfo {
+                id: DEFAULT_WORKSPACE.to_string(),
+                name: "Default".to_string(),
+                members: Vec::new(),
+                created_at: Utc::now(),
+            })),
+            default_router: Arc::default(),
+            workspaces: Arc::default(),
+        }
+    }
+}
+
+impl WorkspaceStore {
+    /// Engine routes of the default workspace
+    ///
+    /// # Arguments
+    ///
+    /// * `build` - Builds the routes the first time they are needed
+    pub fn default_router(&self, build: impl FnOnce() -> Router) -> Router {
+        self.default_router.get_or_init(build).clone()
+    }
+
+    pub async fn insert(&self, workspace: Workspace) {
+        self.workspaces
+            .write()
+            .await
+            .insert(workspace.info.id.clone(), workspace);
+    }
+
+    /// # Returns
+    ///
+    /// The workspace, or `None` for the default workspace or an unknown id
+    pub async fn get(&self, id: &str) -> Option<Workspace> {
+        self.workspaces.read().await.get(id).cloned()
+    }
+
+    /// # Returns
+    ///
+    /// The description of the workspace, the default one included, or `None`
+    /// if there is no workspace with this id
+    pub async fn info(&self, id: &str) -> Option<WorkspaceInfo> {
+        if id == DEFAULT_WORKSPACE {
+            return Some(self.default_info.read().await.clone());
+        }
+        self.get(id).await.map(|workspace| workspace.info)
+    }
+
+    /// Describe every workspace, the default one first and the others by
+    /// creation time
+    pub async fn list(&self) -> Vec<WorkspaceInfo> {
+        let mut others: Vec<WorkspaceInfo> = self
+            .workspaces
+            .read()
+            .await
+            .values()
+            .map(|workspace| workspace.info.clone())
+            .collect();
+        others.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+
+        let mut infos = vec![self.default_info.read().await.clone()];
+        infos.extend(others);
+        infos
+    }
+
+    /// Rename a workspace and replace its members
+    ///
+    /// # Returns
+    ///
+    /// The updated description, or `None` if there is no workspace with this id
+    pub async fn update(
+        &self,
+        id: &str,
+        name: String,
+        members: Vec<String>,
+    ) -> Option<WorkspaceInfo> {
+        if id == DEFAULT_WORKSPACE {
+            let mut info = self.default_info.write().await;
+            info.name = name;
+            info.members = members;
+            return Some(info.clone());
+        }
+        let mut workspaces = self.workspaces.write().await;
+        let workspace = workspaces.get_mut(id)?;
+        workspace.info.name = name;
+        workspace.info.members = members;
+        Some(workspace.info.clone())
+    }
+
+    /// # Returns
+    ///
+    /// The removed workspace, or `None` if there is no workspace with this id
+    pub async fn remove(&self, id: &str) -> Option<Workspace> {
+        self.workspaces.write().await.remove(id)
+    }
+}
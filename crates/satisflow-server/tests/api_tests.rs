@@ -1,8 +1,13 @@
 // Comprehensive API tests for satisflow-server
 mod common;
 
-use common::{assertions::*, create_test_client, create_test_server, test_data::*};
+use common::{
+    assertions::*, create_auth_test_server, create_demo_test_server, create_test_client,
+    create_test_server, game_saves, test_data::*,
+};
 use serde_json::{json, Value};
+use satisflow_server::{auth::AuthConfig, demo::DemoConfig};
+use std::time::Duration;
 use uuid::Uuid;
 
 #[tokio::test]
@@ -1004,7 +1009,7 @@ async fn test_blueprint_template_import_export() {
                 let instance_data: Value = assert_created_response(instance_response).await;
                 assert_eq!(
                     instance_data["message"],
-                    "Blueprint instance created in factory ".to_string() + &factory_id
+                    "Blueprint instance created in factory ".to_string() + factory_id.as_str()
                 );
                 assert!(instance_data.get("blueprint_id").is_some());
                 assert_eq!(instance_data["factory_id"], factory_id);
@@ -1210,6 +1215,7 @@ async fn test_preview_endpoints() {
             // Verify calculations
             assert_eq!(generator_preview_data["total_power_generation"], 562.5); // 5 * 75MW * 1.5
             assert_eq!(generator_preview_data["total_fuel_consumption"], 112.5); // 5 * 15 * 1.5
+            assert_eq!(generator_preview_data["total_water_consumption"], 337.5); // 5 * 45 * 1.5
             assert_eq!(generator_preview_data["waste_production_rate"], 0.0); // Coal generators don't produce waste
             assert!(generator_preview_data["waste_product"].is_null());
         }
@@ -1242,6 +1248,7 @@ async fn test_preview_endpoints() {
             // Verify nuclear generator calculations
             assert_eq!(nuclear_preview_data["total_power_generation"], 5000.0); // 2 * 2500MW
             assert_eq!(nuclear_preview_data["total_fuel_consumption"], 0.05); // 2 * 0.025
+            assert_eq!(nuclear_preview_data["total_water_consumption"], 480.0); // 2 * 240
             assert_eq!(nuclear_preview_data["waste_production_rate"], 0.05); // 2 * 0.025
             assert_eq!(nuclear_preview_data["waste_product"], "UraniumWaste");
         }
@@ -1769,6 +1776,7 @@ async fn test_blueprint_import_export_roundtrip() {
 
 // COMPREHENSIVE LOGISTICS TESTS - ALL TRANSPORT TYPES
 #[tokio::test]
+#[allow(unused_variables)]
 async fn test_all_transport_types() {
     let server = create_test_server().await;
     let client = create_test_client();
@@ -2414,3 +2422,4606 @@ async fn test_reset_endpoint() {
         }
     }
 }
+
+// DASHBOARD ITEM BREAKDOWN TEST
+#[tokio::test]
+async fn test_dashboard_item_breakdown() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let mut factory_ids = Vec::new();
+    for name in ["Ore Field", "Smelter"] {
+        let factory: Value = client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&json!({ "name": name }))
+            .send()
+            .await
+            .expect("Failed to create factory")
+            .json()
+            .await
+            .unwrap();
+        factory_ids.push(factory["id"].as_str().unwrap().to_string());
+    }
+
+    let logistics_response = client
+        .post(format!("{}/api/logistics", server.base_url))
+        .json(&json!({
+            "from_factory": factory_ids[0],
+            "to_factory": factory_ids[1],
+            "transport_type": "Truck",
+            "item": "IronOre",
+            "quantity_per_min": 60.0
+        }))
+        .send()
+        .await
+        .expect("Failed to create logistics");
+    assert_created_response(logistics_response).await;
+
+    let response = client
+        .get(format!(
+            "{}/api/dashboard/items/IronOre/breakdown",
+            server.base_url
+        ))
+        .send()
+        .await
+        .expect("Failed to get item breakdown");
+    let breakdown: Value = assert_json_response(response).await;
+
+    assert_eq!(breakdown["item"], "IronOre");
+    assert_eq!(breakdown["net"], 0.0);
+    assert_eq!(breakdown["state"], "balanced");
+    assert_eq!(breakdown["producers"][0]["factory_name"], "Smelter");
+    assert_eq!(breakdown["producers"][0]["source"]["type"], "LogisticsImport");
+    assert_eq!(breakdown["consumers"][0]["factory_name"], "Ore Field");
+    assert_eq!(breakdown["consumers"][0]["source"]["type"], "LogisticsExport");
+    assert_eq!(breakdown["consumers"][0]["quantity_per_min"], 60.0);
+
+    let unknown = client
+        .get(format!(
+            "{}/api/dashboard/items/NotAnItem/breakdown",
+            server.base_url
+        ))
+        .send()
+        .await
+        .expect("Failed to request unknown item breakdown");
+    assert_eq!(unknown.status().as_u16(), 400);
+}
+
+// RESOURCE NODE REGISTRY TESTS
+#[tokio::test]
+async fn test_resource_node_claims_report() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let registry: Value = assert_json_response(
+        client
+            .get(format!("{}/api/resource-nodes", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get resource nodes"),
+    )
+    .await;
+    let iron = registry
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|entry| entry["item"] == "IronOre")
+        .unwrap();
+    assert_eq!(iron["pure"], 46);
+
+    let updated: Value = assert_json_response(
+        client
+            .put(format!("{}/api/resource-nodes/IronOre", server.base_url))
+            .json(&json!({ "impure": 0, "normal": 0, "pure": 1 }))
+            .send()
+            .await
+            .expect("Failed to update resource nodes"),
+    )
+    .await;
+    assert_eq!(updated["total"], 1);
+
+    let water = client
+        .put(format!("{}/api/resource-nodes/Water", server.base_url))
+        .json(&json!({ "impure": 1, "normal": 1, "pure": 1 }))
+        .send()
+        .await
+        .expect("Failed to send water node update");
+    assert_eq!(water.status().as_u16(), 400);
+
+    let factory: Value = client
+        .post(format!("{}/api/factories", server.base_url))
+        .json(&minimal_factory_request("Iron Mine"))
+        .send()
+        .await
+        .expect("Failed to create factory")
+        .json()
+        .await
+        .unwrap();
+    let factory_id = factory["id"].as_str().unwrap().to_string();
+
+    let raw_input_response = client
+        .post(format!("{}/api/factories/{}/raw-inputs", server.base_url, factory_id))
+        .json(&json!({
+            "extractor_type": "MinerMk2",
+            "item": "IronOre",
+            "purity": "Pure",
+            "count": 2,
+            "claims_node": true
+        }))
+        .send()
+        .await
+        .expect("Failed to create raw input");
+    assert_created_response(raw_input_response).await;
+
+    let report: Value = assert_json_response(
+        client
+            .get(format!("{}/api/resource-nodes/report", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get resource node report"),
+    )
+    .await;
+    assert_eq!(report["is_valid"], false);
+    let pure_iron = report["usage"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|entry| entry["item"] == "IronOre" && entry["purity"] == "Pure")
+        .unwrap();
+    assert_eq!(pure_iron["claimed"], 2);
+    assert_eq!(pure_iron["available"], 1);
+    assert_eq!(pure_iron["over_claimed"], true);
+    assert_eq!(pure_iron["claims"][0]["factory_name"], "Iron Mine");
+}
+
+// POWER GRID TESTS
+#[tokio::test]
+async fn test_power_grids_report_per_grid_balances() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let grid_response = client
+        .post(format!("{}/api/power-grids", server.base_url))
+        .json(&json!({ "name": "North Grid" }))
+        .send()
+        .await
+        .expect("Failed to create power grid");
+    let grid: Value = assert_created_response(grid_response).await;
+    let grid_id = grid["id"].as_str().unwrap().to_string();
+
+    let blank = client
+        .post(format!("{}/api/power-grids", server.base_url))
+        .json(&json!({ "name": "  " }))
+        .send()
+        .await
+        .expect("Failed to send blank power grid");
+    assert_eq!(blank.status().as_u16(), 400);
+
+    let mut factory_ids = Vec::new();
+    for name in ["Coal Plant", "Outpost"] {
+        let factory: Value = client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&minimal_factory_request(name))
+            .send()
+            .await
+            .expect("Failed to create factory")
+            .json()
+            .await
+            .unwrap();
+        factory_ids.push(factory["id"].as_str().unwrap().to_string());
+    }
+
+    let generator_response = client
+        .post(format!("{}/api/factories/{}/power-generators", server.base_url, factory_ids[0]))
+        .json(&json!({
+            "generator_type": "Coal",
+            "fuel_type": "Coal",
+            "groups": [{ "number_of_generators": 2, "clock_speed": 100.0 }]
+        }))
+        .send()
+        .await
+        .expect("Failed to create power generator");
+    assert_created_response(generator_response).await;
+
+    let assigned: Value = assert_json_response(
+        client
+            .put(format!("{}/api/factories/{}/power-grid", server.base_url, factory_ids[0]))
+            .json(&json!({ "power_grid_id": grid_id }))
+            .send()
+            .await
+            .expect("Failed to assign power grid"),
+    )
+    .await;
+    assert_eq!(assigned["power_grid"], grid_id.as_str());
+
+    let unknown_grid = client
+        .put(format!("{}/api/factories/{}/power-grid", server.base_url, factory_ids[1]))
+        .json(&json!({ "power_grid_id": Uuid::new_v4() }))
+        .send()
+        .await
+        .expect("Failed to send unknown power grid");
+    assert_eq!(unknown_grid.status().as_u16(), 400);
+
+    let power: Value = assert_json_response(
+        client
+            .get(format!("{}/api/dashboard/power", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get power statistics"),
+    )
+    .await;
+    let grids = power["grid_stats"].as_array().unwrap();
+    assert_eq!(grids.len(), 2);
+    assert_eq!(grids[0]["grid_name"], "North Grid");
+    assert_eq!(grids[0]["generation"], 150.0);
+    assert_eq!(grids[1]["grid_id"], Value::Null);
+    assert_eq!(grids[1]["factory_ids"][0], factory_ids[1].as_str());
+    assert_eq!(power["total_generation"], 150.0);
+
+    let delete_response = client
+        .delete(format!("{}/api/power-grids/{}", server.base_url, grid_id))
+        .send()
+        .await
+        .expect("Failed to delete power grid");
+    assert_no_content(delete_response).await;
+
+    let factory: Value = assert_json_response(
+        client
+            .get(format!("{}/api/factories/{}", server.base_url, factory_ids[0]))
+            .send()
+            .await
+            .expect("Failed to get factory"),
+    )
+    .await;
+    assert_eq!(factory["power_grid"], Value::Null);
+}
+
+#[tokio::test]
+async fn test_recent_changes_feed() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let mut factory_ids = Vec::new();
+    for name in ["Smelter", "Assembly"] {
+        let created = assert_created_response(
+            client
+                .post(format!("{}/api/factories", server.base_url))
+                .json(&minimal_factory_request(name))
+                .send()
+                .await
+                .expect("Failed to create factory"),
+        )
+        .await;
+        assert!(created["created_at"].is_string());
+        factory_ids.push(created["id"].as_str().unwrap().to_string());
+    }
+
+    let updated: Value = assert_json_response(
+        client
+            .put(format!("{}/api/factories/{}", server.base_url, factory_ids[0]))
+            .json(&update_factory_request())
+            .send()
+            .await
+            .expect("Failed to update factory"),
+    )
+    .await;
+    assert_ne!(updated["updated_at"], updated["created_at"]);
+
+    let changes: Value = assert_json_response(
+        client
+            .get(format!("{}/api/recent-changes", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get recent changes"),
+    )
+    .await;
+    let changes = changes.as_array().unwrap();
+    assert_eq!(changes.len(), 2);
+    assert_eq!(changes[0]["entity"], "Factory");
+    assert_eq!(changes[0]["id"], factory_ids[0].as_str());
+    assert_eq!(changes[0]["name"], "Updated Factory");
+
+    let limited: Value = assert_json_response(
+        client
+            .get(format!("{}/api/recent-changes?limit=1", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get limited recent changes"),
+    )
+    .await;
+    assert_eq!(limited.as_array().unwrap().len(), 1);
+
+    let invalid = client
+        .get(format!("{}/api/recent-changes?limit=0", server.base_url))
+        .send()
+        .await
+        .expect("Failed to send invalid limit");
+    assert_eq!(invalid.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn test_power_storage_endpoints() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let factory = assert_created_response(
+        client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&minimal_factory_request("Coal Plant"))
+            .send()
+            .await
+            .expect("Failed to create factory"),
+    )
+    .await;
+    let factory_id = factory["id"].as_str().unwrap().to_string();
+    assert_eq!(factory["power_storage"], Value::Null);
+
+    let generator_response = client
+        .post(format!("{}/api/factories/{}/power-generators", server.base_url, factory_id))
+        .json(&json!({
+            "generator_type": "Coal",
+            "fuel_type": "Coal",
+            "groups": [{ "number_of_generators": 2, "clock_speed": 100.0 }]
+        }))
+        .send()
+        .await
+        .expect("Failed to create power generator");
+    assert_created_response(generator_response).await;
+
+    let updated: Value = assert_json_response(
+        client
+            .put(format!("{}/api/factories/{}/power-storage", server.base_url, factory_id))
+            .json(&json!({ "battery_count": 1, "stored_mwh": 50.0 }))
+            .send()
+            .await
+            .expect("Failed to set power storage"),
+    )
+    .await;
+    assert_eq!(updated["power_storage"]["capacity"], 100.0);
+    assert_eq!(updated["total_power_consumption"], 100.0);
+
+    let overfilled = client
+        .put(format!("{}/api/factories/{}/power-storage", server.base_url, factory_id))
+        .json(&json!({ "battery_count": 1, "stored_mwh": 250.0 }))
+        .send()
+        .await
+        .expect("Failed to send invalid power storage");
+    assert_eq!(overfilled.status().as_u16(), 400);
+
+    let power: Value = assert_json_response(
+        client
+            .get(format!("{}/api/dashboard/power", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get power statistics"),
+    )
+    .await;
+    assert_eq!(power["storage"]["stored_mwh"], 50.0);
+    assert_eq!(power["storage"]["charging_draw"], 100.0);
+    assert_eq!(power["storage"]["hours_until_full"], 0.5);
+    assert_eq!(power["storage"]["hours_until_depleted"], Value::Null);
+    assert_eq!(power["factory_stats"][0]["storage"]["battery_count"], 1);
+
+    let cleared: Value = assert_json_response(
+        client
+            .delete(format!("{}/api/factories/{}/power-storage", server.base_url, factory_id))
+            .send()
+            .await
+            .expect("Failed to remove power storage"),
+    )
+    .await;
+    assert_eq!(cleared["power_storage"], Value::Null);
+    assert_eq!(cleared["total_power_consumption"], 0.0);
+
+    let missing = client
+        .delete(format!("{}/api/factories/{}/power-storage", server.base_url, factory_id))
+        .send()
+        .await
+        .expect("Failed to send second removal");
+    assert_eq!(missing.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn test_pinned_dashboard() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let mut factory_ids = Vec::new();
+    for name in ["Ore Field", "Smelter", "Spaceport"] {
+        let factory = assert_created_response(
+            client
+                .post(format!("{}/api/factories", server.base_url))
+                .json(&minimal_factory_request(name))
+                .send()
+                .await
+                .expect("Failed to create factory"),
+        )
+        .await;
+        factory_ids.push(factory["id"].as_str().unwrap().to_string());
+    }
+
+    let logistics_response = client
+        .post(format!("{}/api/logistics", server.base_url))
+        .json(&json!({
+            "from_factory": factory_ids[0],
+            "to_factory": factory_ids[1],
+            "transport_type": "Truck",
+            "item": "IronOre",
+            "quantity_per_min": 60.0
+        }))
+        .send()
+        .await
+        .expect("Failed to create logistics");
+    assert_created_response(logistics_response).await;
+
+    let pinned: Value = assert_json_response(
+        client
+            .put(format!("{}/api/dashboard/pinned/factories/{}", server.base_url, factory_ids[1]))
+            .send()
+            .await
+            .expect("Failed to pin factory"),
+    )
+    .await;
+    assert_eq!(pinned["factories"][0], factory_ids[1].as_str());
+
+    let unknown = client
+        .put(format!("{}/api/dashboard/pinned/factories/{}", server.base_url, Uuid::new_v4()))
+        .send()
+        .await
+        .expect("Failed to send unknown factory pin");
+    assert_eq!(unknown.status().as_u16(), 404);
+
+    let pinned: Value = assert_json_response(
+        client
+            .put(format!("{}/api/dashboard/pinned/items/IronOre", server.base_url))
+            .send()
+            .await
+            .expect("Failed to pin item"),
+    )
+    .await;
+    assert_eq!(pinned["items"][0], "IronOre");
+
+    let dashboard: Value = assert_json_response(
+        client
+            .get(format!("{}/api/dashboard/pinned", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get pinned dashboard"),
+    )
+    .await;
+    let factories = dashboard["factories"].as_array().unwrap();
+    assert_eq!(factories.len(), 1);
+    assert_eq!(factories[0]["factory_name"], "Smelter");
+    assert_eq!(factories[0]["items"][0]["item"], "IronOre");
+    assert_eq!(factories[0]["items"][0]["balance"], 60.0);
+    assert_eq!(factories[0]["power_balance"], 0.0);
+    assert_eq!(dashboard["items"][0]["total_produced"], 60.0);
+    assert_eq!(dashboard["items"][0]["state"], "balanced");
+
+    let unpinned: Value = assert_json_response(
+        client
+            .delete(format!("{}/api/dashboard/pinned/items/IronOre", server.base_url))
+            .send()
+            .await
+            .expect("Failed to unpin item"),
+    )
+    .await;
+    assert_eq!(unpinned["items"].as_array().unwrap().len(), 0);
+
+    let not_pinned = client
+        .delete(format!("{}/api/dashboard/pinned/factories/{}", server.base_url, factory_ids[2]))
+        .send()
+        .await
+        .expect("Failed to send unpin");
+    assert_eq!(not_pinned.status().as_u16(), 404);
+
+    let delete_response = client
+        .delete(format!("{}/api/factories/{}", server.base_url, factory_ids[1]))
+        .send()
+        .await
+        .expect("Failed to delete factory");
+    assert!(delete_response.status().is_success());
+
+    let dashboard: Value = assert_json_response(
+        client
+            .get(format!("{}/api/dashboard/pinned", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get pinned dashboard"),
+    )
+    .await;
+    assert_eq!(dashboard["factories"].as_array().unwrap().len(), 0);
+}
+
+#[tokio::test]
+async fn test_dashboard_report_card() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let empty: Value = assert_json_response(
+        client
+            .get(format!("{}/api/dashboard/report-card", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get report card"),
+    )
+    .await;
+    assert_eq!(empty["summary"], "No factories yet.");
+
+    let factory = assert_created_response(
+        client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&minimal_factory_request("Smelter"))
+            .send()
+            .await
+            .expect("Failed to create factory"),
+    )
+    .await;
+    let factory_id = factory["id"].as_str().unwrap().to_string();
+
+    let line_response = client
+        .post(format!("{}/api/factories/{}/production-lines", server.base_url, factory_id))
+        .json(&json!({
+            "name": "Ingots",
+            "type": "recipe",
+            "recipe": "Iron Ingot",
+            "machine_groups": [
+                { "number_of_machine": 2, "oc_value": 100.0, "somersloop": 0 }
+            ]
+        }))
+        .send()
+        .await
+        .expect("Failed to create production line");
+    assert_created_response(line_response).await;
+
+    let report: Value = assert_json_response(
+        client
+            .get(format!("{}/api/dashboard/report-card", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get report card"),
+    )
+    .await;
+    assert_eq!(report["factory_count"], 1);
+    assert_eq!(report["top_production"][0]["item"], "IronIngot");
+    assert_eq!(report["biggest_deficits"][0]["item"], "IronOre");
+    assert_eq!(report["power_margin_percent"], Value::Null);
+    let summary = report["summary"].as_str().unwrap();
+    assert!(summary.contains("Top production: Iron Ingot 60.0/min"));
+    assert!(summary.contains("8.0 MW short"));
+
+    let per_second: Value = assert_json_response(
+        client
+            .get(format!("{}/api/dashboard/report-card?rate_unit=per_second", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get report card"),
+    )
+    .await;
+    let summary = per_second["summary"].as_str().unwrap();
+    assert!(summary.contains("Top production: Iron Ingot 1.0/s"));
+}
+
+#[tokio::test]
+async fn test_geothermal_purity_generation_range() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let factory = assert_created_response(
+        client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&minimal_factory_request("Geyser Field"))
+            .send()
+            .await
+            .expect("Failed to create factory"),
+    )
+    .await;
+    let factory_id = factory["id"].as_str().unwrap().to_string();
+
+    let generator_response = client
+        .post(format!("{}/api/factories/{}/power-generators", server.base_url, factory_id))
+        .json(&json!({
+            "generator_type": "Geothermal",
+            "purity": "Pure",
+            "groups": [{ "number_of_generators": 1, "clock_speed": 100.0 }]
+        }))
+        .send()
+        .await
+        .expect("Failed to create geothermal generator");
+    let factory = assert_created_response(generator_response).await;
+    let generator = &factory["power_generators"][0];
+    assert_eq!(generator["purity"], "Pure");
+    assert_eq!(generator["total_power_generation"], 400.0);
+    assert_eq!(generator["generation_range"]["min"], 200.0);
+    assert_eq!(generator["generation_range"]["max"], 600.0);
+
+    let coal_with_purity = client
+        .post(format!("{}/api/factories/{}/power-generators", server.base_url, factory_id))
+        .json(&json!({
+            "generator_type": "Coal",
+            "fuel_type": "Coal",
+            "purity": "Pure",
+            "groups": [{ "number_of_generators": 1, "clock_speed": 100.0 }]
+        }))
+        .send()
+        .await
+        .expect("Failed to send coal generator with purity");
+    assert_eq!(coal_with_purity.status().as_u16(), 400);
+
+    let power: Value = assert_json_response(
+        client
+            .get(format!("{}/api/dashboard/power", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get power statistics"),
+    )
+    .await;
+    assert_eq!(power["total_generation"], 400.0);
+    assert_eq!(power["generation_range"]["min"], 200.0);
+    assert_eq!(power["worst_case_balance"], 200.0);
+    assert_eq!(power["factory_stats"][0]["generation_range"]["max"], 600.0);
+}
+
+#[tokio::test]
+async fn test_nuclear_waste_report() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let factory = assert_created_response(
+        client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&minimal_factory_request("Nuclear Plant"))
+            .send()
+            .await
+            .expect("Failed to create factory"),
+    )
+    .await;
+    let factory_id = factory["id"].as_str().unwrap().to_string();
+
+    for fuel_type in ["UraniumFuelRod", "PlutoniumFuelRod"] {
+        let generator_response = client
+            .post(format!("{}/api/factories/{}/power-generators", server.base_url, factory_id))
+            .json(&json!({
+                "generator_type": "Nuclear",
+                "fuel_type": fuel_type,
+                "groups": [{ "number_of_generators": 2, "clock_speed": 100.0 }]
+            }))
+            .send()
+            .await
+            .expect("Failed to create nuclear generator");
+        assert_created_response(generator_response).await;
+    }
+
+    let report: Value = assert_json_response(
+        client
+            .get(format!("{}/api/dashboard/nuclear-waste", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get nuclear waste report"),
+    )
+    .await;
+    let factories = report["factories"].as_array().unwrap();
+    assert_eq!(factories.len(), 1);
+    assert_eq!(factories[0]["factory_name"], "Nuclear Plant");
+    let waste = factories[0]["waste"].as_array().unwrap();
+    assert_eq!(waste[0]["item"], "UraniumWaste");
+    assert_eq!(waste[1]["item"], "PlutoniumWaste");
+    assert!(waste[1]["accumulation"].as_f64().unwrap() > 0.0);
+    assert_eq!(report["totals"].as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_fuel_shortfall_warnings() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let factory = assert_created_response(
+        client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&minimal_factory_request("Coal Plant"))
+            .send()
+            .await
+            .expect("Failed to create factory"),
+    )
+    .await;
+    let factory_id = factory["id"].as_str().unwrap().to_string();
+
+    let generator_response = client
+        .post(format!("{}/api/factories/{}/power-generators", server.base_url, factory_id))
+        .json(&json!({
+            "generator_type": "Coal",
+            "fuel_type": "Coal",
+            "groups": [{ "number_of_generators": 2, "clock_speed": 100.0 }]
+        }))
+        .send()
+        .await
+        .expect("Failed to create coal generator");
+    let factory = assert_created_response(generator_response).await;
+    assert_eq!(factory["fuel_shortfalls"][0]["item"], "Coal");
+    assert_eq!(factory["fuel_shortfalls"][0]["required"], 30.0);
+    assert_eq!(factory["fuel_shortfalls"][0]["available"], 0.0);
+    assert_eq!(
+        factory["warnings"][0],
+        "Generators burn 30.00/min of Coal but only 0.00/min is available"
+    );
+
+    let power: Value = assert_json_response(
+        client
+            .get(format!("{}/api/dashboard/power", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get power statistics"),
+    )
+    .await;
+    assert_eq!(power["factory_stats"][0]["fuel_shortfalls"][0]["shortfall"], 30.0);
+    assert_eq!(
+        power["warnings"][0],
+        "Coal Plant: Generators burn 30.00/min of Coal but only 0.00/min is available"
+    );
+}
+
+#[tokio::test]
+async fn test_season_overview() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let save = |client: reqwest::Client, base_url: String| async move {
+        let response: Value = assert_json_response(
+            client
+                .get(format!("{}/api/save", base_url))
+                .send()
+                .await
+                .expect("Failed to save"),
+        )
+        .await;
+        response["save_data"].as_str().unwrap().to_string()
+    };
+
+    let week_1 = save(client.clone(), server.base_url.clone()).await;
+    assert_created_response(
+        client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&minimal_factory_request("Smelter"))
+            .send()
+            .await
+            .expect("Failed to create factory"),
+    )
+    .await;
+    let week_2 = save(client.clone(), server.base_url.clone()).await;
+
+    let request = json!({
+        "saves": [
+            { "label": "Week 1", "save_data": week_1 },
+            { "label": "Week 2", "save_data": week_2 }
+        ]
+    });
+
+    let overview: Value = assert_json_response(
+        client
+            .post(format!("{}/api/season-overview", server.base_url))
+            .json(&request)
+            .send()
+            .await
+            .expect("Failed to get season overview"),
+    )
+    .await;
+    assert_eq!(overview["factory_growth"], 1);
+    assert_eq!(overview["snapshots"][1]["label"], "Week 2");
+    assert_eq!(overview["snapshots"][1]["factory_count"], 1);
+
+    let csv_response = client
+        .post(format!("{}/api/season-overview?format=csv", server.base_url))
+        .json(&request)
+        .send()
+        .await
+        .expect("Failed to get season overview as CSV");
+    assert_eq!(csv_response.status().as_u16(), 200);
+    assert!(csv_response.headers()["content-type"]
+        .to_str()
+        .unwrap()
+        .starts_with("text/csv"));
+    let csv = csv_response.text().await.unwrap();
+    assert_eq!(csv.lines().count(), 3);
+    assert!(csv.lines().nth(2).unwrap().starts_with("Week 2,"));
+
+    let invalid = client
+        .post(format!("{}/api/season-overview", server.base_url))
+        .json(&json!({ "saves": [{ "label": "Broken", "save_data": "{}" }] }))
+        .send()
+        .await
+        .expect("Failed to send invalid season overview");
+    assert_eq!(invalid.status().as_u16(), 400);
+
+    let empty = client
+        .post(format!("{}/api/season-overview", server.base_url))
+        .json(&json!({ "saves": [] }))
+        .send()
+        .await
+        .expect("Failed to send empty season overview");
+    assert_eq!(empty.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn test_resource_inventory() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let factory = assert_created_response(
+        client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&minimal_factory_request("Overclocked"))
+            .send()
+            .await
+            .expect("Failed to create factory"),
+    )
+    .await;
+    let factory_id = factory["id"].as_str().unwrap().to_string();
+
+    let line_response = client
+        .post(format!("{}/api/factories/{}/production-lines", server.base_url, factory_id))
+        .json(&json!({
+            "name": "Ingots",
+            "type": "recipe",
+            "recipe": "Iron Ingot",
+            "machine_groups": [
+                { "number_of_machine": 2, "oc_value": 200.0, "somersloop": 1 }
+            ]
+        }))
+        .send()
+        .await
+        .expect("Failed to create production line");
+    assert_created_response(line_response).await;
+
+    let generator_response = client
+        .post(format!("{}/api/factories/{}/power-generators", server.base_url, factory_id))
+        .json(&json!({
+            "generator_type": "Coal",
+            "fuel_type": "Coal",
+            "groups": [{ "number_of_generators": 1, "clock_speed": 250.0 }]
+        }))
+        .send()
+        .await
+        .expect("Failed to create power generator");
+    assert_created_response(generator_response).await;
+
+    let inventory: Value = assert_json_response(
+        client
+            .get(format!("{}/api/dashboard/resources", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get resource inventory"),
+    )
+    .await;
+    assert_eq!(inventory["total_somersloops"], 2);
+    assert_eq!(inventory["total_power_shards"], 7);
+    let factories = inventory["factories"].as_array().unwrap();
+    assert_eq!(factories.len(), 1);
+    assert_eq!(factories[0]["production_power_shards"], 4);
+    assert_eq!(factories[0]["generator_power_shards"], 3);
+}
+
+#[tokio::test]
+async fn test_optimize_production_line_clock() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let factory = assert_created_response(
+        client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&minimal_factory_request("Smelting"))
+            .send()
+            .await
+            .expect("Failed to create factory"),
+    )
+    .await;
+    let factory_id = factory["id"].as_str().unwrap().to_string();
+
+    let factory = assert_created_response(
+        client
+            .post(format!("{}/api/factories/{}/production-lines", server.base_url, factory_id))
+            .json(&json!({
+                "name": "Ingots",
+                "type": "recipe",
+                "recipe": "Iron Ingot",
+                "machine_groups": [
+                    { "number_of_machine": 1, "oc_value": 100.0, "somersloop": 0 }
+                ]
+            }))
+            .send()
+            .await
+            .expect("Failed to create production line"),
+    )
+    .await;
+    let line_id = factory["production_lines"][0]["ProductionLineRecipe"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let optimize_url = format!(
+        "{}/api/factories/{}/production-lines/{}/optimize",
+        server.base_url, factory_id, line_id
+    );
+
+    let optimization: Value = assert_json_response(
+        client
+            .post(&optimize_url)
+            .json(&json!({ "target_rate": 100.0, "machine_count": 2 }))
+            .send()
+            .await
+            .expect("Failed to optimize production line"),
+    )
+    .await;
+    assert_eq!(optimization["item"], "IronIngot");
+    assert_eq!(optimization["number_of_machine"], 2);
+    assert!((optimization["clock_speed"].as_f64().unwrap() - 166.6667).abs() < 1e-3);
+    assert_eq!(optimization["power_shards_per_machine"], 2);
+    assert_eq!(optimization["total_power_shards"], 4);
+
+    let unreachable = client
+        .post(&optimize_url)
+        .json(&json!({ "target_rate": 1000.0, "machine_count": 1 }))
+        .send()
+        .await
+        .expect("Failed to send unreachable optimization");
+    assert_eq!(unreachable.status().as_u16(), 400);
+
+    let missing = client
+        .post(format!(
+            "{}/api/factories/{}/production-lines/{}/optimize",
+            server.base_url,
+            factory_id,
+            Uuid::new_v4()
+        ))
+        .json(&json!({ "target_rate": 30.0, "machine_count": 1 }))
+        .send()
+        .await
+        .expect("Failed to send optimization for missing line");
+    assert_eq!(missing.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn test_dev_load_example() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let loaded: Value = assert_json_response(
+        client
+            .post(format!(
+                "{}/api/dev/load-example?name=five-factory",
+                server.base_url
+            ))
+            .send()
+            .await
+            .expect("Failed to load example"),
+    )
+    .await;
+    assert_eq!(loaded["name"], "five-factory");
+    assert_eq!(loaded["factory_count"], 5);
+    assert!(loaded["logistics_count"].as_u64().unwrap() > 0);
+
+    let summary: Value = assert_json_response(
+        client
+            .get(format!("{}/api/dashboard/summary", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get dashboard summary"),
+    )
+    .await;
+    assert_eq!(summary["total_factories"], 5);
+    assert!(summary["total_production_lines"].as_u64().unwrap() > 0);
+
+    let unknown = client
+        .post(format!("{}/api/dev/load-example?name=unknown", server.base_url))
+        .send()
+        .await
+        .expect("Failed to send unknown example");
+    assert_eq!(unknown.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn test_balance_suggestions() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let factory = assert_created_response(
+        client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&minimal_factory_request("Starved Smelter"))
+            .send()
+            .await
+            .expect("Failed to create factory"),
+    )
+    .await;
+    let factory_id = factory["id"].as_str().unwrap().to_string();
+
+    let raw_input_response = client
+        .post(format!("{}/api/factories/{}/raw-inputs", server.base_url, factory_id))
+        .json(&json!({
+            "extractor_type": "MinerMk1",
+            "item": "IronOre",
+            "purity": "Normal",
+            "overclock_percent": 100.0,
+            "count": 1
+        }))
+        .send()
+        .await
+        .expect("Failed to create raw input");
+    assert_created_response(raw_input_response).await;
+
+    let line_response = client
+        .post(format!("{}/api/factories/{}/production-lines", server.base_url, factory_id))
+        .json(&json!({
+            "name": "Ingots",
+            "type": "recipe",
+            "recipe": "Iron Ingot",
+            "machine_groups": [
+                { "number_of_machine": 3, "oc_value": 100.0, "somersloop": 0 }
+            ]
+        }))
+        .send()
+        .await
+        .expect("Failed to create production line");
+    assert_created_response(line_response).await;
+
+    let suggestions: Value = assert_json_response(
+        client
+            .get(format!(
+                "{}/api/factories/{}/balance-suggestions",
+                server.base_url, factory_id
+            ))
+            .send()
+            .await
+            .expect("Failed to get balance suggestions"),
+    )
+    .await;
+    let suggestions = suggestions.as_array().unwrap();
+    assert_eq!(suggestions.len(), 1);
+    assert_eq!(suggestions[0]["production_line_name"], "Ingots");
+    assert_eq!(suggestions[0]["current_machines"], 3);
+    assert_eq!(suggestions[0]["max_machines"], 2);
+    assert_eq!(suggestions[0]["limiting_item"], "IronOre");
+    assert!((suggestions[0]["clock_multiplier"].as_f64().unwrap() - 2.0 / 3.0).abs() < 1e-4);
+
+    let missing = client
+        .get(format!(
+            "{}/api/factories/{}/balance-suggestions",
+            server.base_url,
+            Uuid::new_v4()
+        ))
+        .send()
+        .await
+        .expect("Failed to send balance suggestions for missing factory");
+    assert_eq!(missing.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn test_toggle_enabled_entries() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let factory = assert_created_response(
+        client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&minimal_factory_request("Toggle Factory"))
+            .send()
+            .await
+            .expect("Failed to create factory"),
+    )
+    .await;
+    let factory_id = factory["id"].as_str().unwrap().to_string();
+
+    let created = assert_created_response(
+        client
+            .post(format!("{}/api/factories/{}/raw-inputs", server.base_url, factory_id))
+            .json(&json!({
+                "extractor_type": "MinerMk1",
+                "item": "IronOre",
+                "purity": "Normal",
+                "overclock_percent": 100.0,
+                "count": 1
+            }))
+            .send()
+            .await
+            .expect("Failed to create raw input"),
+    )
+    .await;
+    let raw_input_id = created["raw_inputs"][0]["id"].as_str().unwrap().to_string();
+
+    let created = assert_created_response(
+        client
+            .post(format!("{}/api/factories/{}/production-lines", server.base_url, factory_id))
+            .json(&json!({
+                "name": "Ingots",
+                "type": "recipe",
+                "recipe": "Iron Ingot",
+                "machine_groups": [
+                    { "number_of_machine": 1, "oc_value": 100.0, "somersloop": 0 }
+                ]
+            }))
+            .send()
+            .await
+            .expect("Failed to create production line"),
+    )
+    .await;
+    let line = &created["production_lines"][0];
+    assert_eq!(line["ProductionLineRecipe"]["enabled"], true);
+    let line_id = line["ProductionLineRecipe"]["id"].as_str().unwrap().to_string();
+
+    let disabled = assert_json_response(
+        client
+            .patch(format!(
+                "{}/api/factories/{}/production-lines/{}/enabled",
+                server.base_url, factory_id, line_id
+            ))
+            .json(&json!({ "enabled": false }))
+            .send()
+            .await
+            .expect("Failed to disable production line"),
+    )
+    .await;
+    assert_eq!(disabled["production_lines"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        disabled["production_lines"][0]["ProductionLineRecipe"]["enabled"],
+        false
+    );
+    assert_eq!(disabled["total_power_consumption"], 5.0); // Miner Mk1 only
+    let iron_ore = disabled["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|item| item["item"] == "IronOre")
+        .unwrap();
+    assert_eq!(iron_ore["quantity"], 60.0);
+
+    let disabled = assert_json_response(
+        client
+            .patch(format!(
+                "{}/api/factories/{}/raw-inputs/{}/enabled",
+                server.base_url, factory_id, raw_input_id
+            ))
+            .json(&json!({ "enabled": false }))
+            .send()
+            .await
+            .expect("Failed to disable raw input"),
+    )
+    .await;
+    assert_eq!(disabled["raw_inputs"][0]["enabled"], false);
+    assert_eq!(disabled["total_power_consumption"], 0.0);
+
+    let missing = client
+        .patch(format!(
+            "{}/api/factories/{}/power-generators/{}/enabled",
+            server.base_url,
+            factory_id,
+            Uuid::new_v4()
+        ))
+        .json(&json!({ "enabled": false }))
+        .send()
+        .await
+        .expect("Failed to send toggle for missing generator");
+    assert_eq!(missing.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn test_demo_mode() {
+    let server = create_demo_test_server(DemoConfig {
+        example: "five-factory".to_string(),
+        reset_interval: Duration::from_millis(300),
+        requests_per_minute: 10,
+    })
+    .await;
+    let client = create_test_client();
+
+    let factories = assert_json_response(
+        client
+            .get(format!("{}/api/factories", server.base_url))
+            .send()
+            .await
+            .expect("Failed to list demo factories"),
+    )
+    .await;
+    let example_count = factories.as_array().unwrap().len();
+    assert!(example_count > 0);
+
+    let save = client
+        .get(format!("{}/api/save", server.base_url))
+        .send()
+        .await
+        .expect("Failed to send save request");
+    assert_eq!(save.status().as_u16(), 403);
+
+    assert_created_response(
+        client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&minimal_factory_request("Visitor Factory"))
+            .send()
+            .await
+            .expect("Failed to create factory"),
+    )
+    .await;
+
+    tokio::time::sleep(Duration::from_millis(700)).await;
+    let factories = assert_json_response(
+        client
+            .get(format!("{}/api/factories", server.base_url))
+            .send()
+            .await
+            .expect("Failed to list demo factories after reset"),
+    )
+    .await;
+    assert_eq!(factories.as_array().unwrap().len(), example_count);
+
+    // 4 requests so far; the limit is 10 per minute
+    let mut statuses = Vec::new();
+    for _ in 0..8 {
+        let response = client
+            .get(format!("{}/api/dashboard/summary", server.base_url))
+            .send()
+            .await
+            .expect("Failed to send summary request");
+        statuses.push(response.status().as_u16());
+    }
+    assert_eq!(statuses, vec![200, 200, 200, 200, 200, 200, 429, 429]);
+
+    let health = client
+        .get(format!("{}/health", server.base_url))
+        .send()
+        .await
+        .expect("Failed to send health check");
+    assert_eq!(health.status().as_u16(), 200);
+}
+
+#[tokio::test]
+async fn test_goals_crud_and_progress() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let factory = assert_created_response(
+        client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&minimal_factory_request("Goal Factory"))
+            .send()
+            .await
+            .expect("Failed to create factory"),
+    )
+    .await;
+    let factory_id = factory["id"].as_str().unwrap().to_string();
+
+    assert_created_response(
+        client
+            .post(format!("{}/api/factories/{}/production-lines", server.base_url, factory_id))
+            .json(&json!({
+                "name": "Ingots",
+                "type": "recipe",
+                "recipe": "Iron Ingot",
+                "machine_groups": [
+                    { "number_of_machine": 2, "oc_value": 100.0, "somersloop": 0 }
+                ]
+            }))
+            .send()
+            .await
+            .expect("Failed to create production line"),
+    )
+    .await;
+
+    let goal = assert_created_response(
+        client
+            .post(format!("{}/api/goals", server.base_url))
+            .json(&json!({
+                "item": "IronIngot",
+                "target_rate": 120.0,
+                "factory_id": factory_id
+            }))
+            .send()
+            .await
+            .expect("Failed to create goal"),
+    )
+    .await;
+    let goal_id = goal["id"].as_str().unwrap().to_string();
+    assert_eq!(goal["actual_rate"], 60.0);
+    assert_eq!(goal["completion_percent"], 50.0);
+    assert_eq!(goal["is_met"], false);
+
+    let invalid = client
+        .post(format!("{}/api/goals", server.base_url))
+        .json(&json!({ "item": "IronIngot", "target_rate": -5.0 }))
+        .send()
+        .await
+        .expect("Failed to send invalid goal");
+    assert_eq!(invalid.status().as_u16(), 400);
+
+    let updated = assert_json_response(
+        client
+            .put(format!("{}/api/goals/{}", server.base_url, goal_id))
+            .json(&json!({ "target_rate": 60.0, "global": true }))
+            .send()
+            .await
+            .expect("Failed to update goal"),
+    )
+    .await;
+    assert_eq!(updated["factory_id"], Value::Null);
+    assert_eq!(updated["is_met"], true);
+
+    let summary = assert_json_response(
+        client
+            .get(format!("{}/api/dashboard/summary", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get summary"),
+    )
+    .await;
+    assert_eq!(summary["goal_progress"][0]["goal_id"], goal_id.as_str());
+    assert_eq!(summary["goal_progress"][0]["completion_percent"], 100.0);
+
+    let deleted = client
+        .delete(format!("{}/api/goals/{}", server.base_url, goal_id))
+        .send()
+        .await
+        .expect("Failed to delete goal");
+    assert_eq!(deleted.status().as_u16(), 204);
+
+    let missing = client
+        .get(format!("{}/api/goals/{}", server.base_url, goal_id))
+        .send()
+        .await
+        .expect("Failed to get deleted goal");
+    assert_eq!(missing.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn test_tier_progress_filters_and_warns() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let recorded = assert_json_response(
+        client
+            .get(format!("{}/api/tier-progress", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get tier progress"),
+    )
+    .await;
+    assert_eq!(recorded, Value::Null);
+
+    let all_recipes = assert_json_response(
+        client
+            .get(format!("{}/api/game-data/recipes?unlocked_only=true", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get recipes"),
+    )
+    .await;
+
+    let invalid = client
+        .put(format!("{}/api/tier-progress", server.base_url))
+        .json(&json!({ "hub_tier": 42 }))
+        .send()
+        .await
+        .expect("Failed to send invalid tier progress");
+    assert_eq!(invalid.status().as_u16(), 400);
+
+    assert_json_response(
+        client
+            .put(format!("{}/api/tier-progress", server.base_url))
+            .json(&json!({ "hub_tier": 2, "mam_research": ["Caterium"] }))
+            .send()
+            .await
+            .expect("Failed to set tier progress"),
+    )
+    .await;
+
+    let unlocked = assert_json_response(
+        client
+            .get(format!("{}/api/game-data/recipes?unlocked_only=true", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get unlocked recipes"),
+    )
+    .await;
+    let unlocked = unlocked.as_array().unwrap();
+    assert!(unlocked.len() < all_recipes.as_array().unwrap().len());
+    assert!(unlocked.iter().any(|recipe| recipe["name"] == "AI Limiter"));
+    assert!(!unlocked.iter().any(|recipe| recipe["name"] == "Steel Beam"));
+
+    let machines = assert_json_response(
+        client
+            .get(format!("{}/api/game-data/machines?unlocked_only=true", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get unlocked machines"),
+    )
+    .await;
+    let machines: Vec<&str> = machines
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|machine| machine["name"].as_str().unwrap())
+        .collect();
+    assert!(machines.contains(&"Assembler"));
+    assert!(!machines.contains(&"Foundry"));
+
+    let factory = assert_created_response(
+        client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&minimal_factory_request("Early Game"))
+            .send()
+            .await
+            .expect("Failed to create factory"),
+    )
+    .await;
+    let factory_id = factory["id"].as_str().unwrap().to_string();
+
+    let with_line = assert_created_response(
+        client
+            .post(format!("{}/api/factories/{}/production-lines", server.base_url, factory_id))
+            .json(&json!({
+                "name": "Beams",
+                "type": "recipe",
+                "recipe": "Steel Beam",
+                "machine_groups": [
+                    { "number_of_machine": 1, "oc_value": 100.0, "somersloop": 0 }
+                ]
+            }))
+            .send()
+            .await
+            .expect("Failed to create production line"),
+    )
+    .await;
+    assert_eq!(with_line["production_lines"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        with_line["warnings"],
+        json!(["Steel Beam needs HUB tier 3 (completed: 2)"])
+    );
+
+    let cleared = client
+        .delete(format!("{}/api/tier-progress", server.base_url))
+        .send()
+        .await
+        .expect("Failed to clear tier progress");
+    assert_eq!(cleared.status().as_u16(), 204);
+}
+
+#[tokio::test]
+async fn test_custom_recipes() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let recipe = assert_created_response(
+        client
+            .post(format!("{}/api/game-data/custom-recipes", server.base_url))
+            .json(&json!({
+                "name": "Compressed Iron",
+                "machine": "Constructor",
+                "inputs": [{ "item": "IronIngot", "quantity": 5.0 }],
+                "outputs": [{ "item": "IronPlate", "quantity": 4.0 }],
+                "cycle_time": 3.0
+            }))
+            .send()
+            .await
+            .expect("Failed to create custom recipe"),
+    )
+    .await;
+    let recipe_id = recipe["id"].as_str().unwrap().to_string();
+
+    let duplicate = client
+        .post(format!("{}/api/game-data/custom-recipes", server.base_url))
+        .json(&json!({
+            "name": "Iron Plate",
+            "machine": "Constructor",
+            "outputs": [{ "item": "IronPlate", "quantity": 1.0 }],
+            "cycle_time": 1.0
+        }))
+        .send()
+        .await
+        .expect("Failed to send duplicate recipe");
+    assert_eq!(duplicate.status().as_u16(), 400);
+
+    let factory = assert_created_response(
+        client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&minimal_factory_request("Modded"))
+            .send()
+            .await
+            .expect("Failed to create factory"),
+    )
+    .await;
+    let factory_id = factory["id"].as_str().unwrap().to_string();
+
+    let with_line = assert_created_response(
+        client
+            .post(format!("{}/api/factories/{}/production-lines", server.base_url, factory_id))
+            .json(&json!({
+                "name": "Plates",
+                "type": "recipe",
+                "recipe": "Compressed Iron",
+                "machine_groups": [
+                    { "number_of_machine": 1, "oc_value": 100.0, "somersloop": 0 }
+                ]
+            }))
+            .send()
+            .await
+            .expect("Failed to create production line"),
+    )
+    .await;
+    let line = &with_line["production_lines"][0];
+    assert_eq!(line["ProductionLineRecipe"]["recipe"]["name"], "Compressed Iron");
+    assert_eq!(line["output_rate"][0]["quantity"], 80.0);
+
+    let in_use = client
+        .delete(format!("{}/api/game-data/custom-recipes/{}", server.base_url, recipe_id))
+        .send()
+        .await
+        .expect("Failed to send delete");
+    assert_eq!(in_use.status().as_u16(), 400);
+
+    assert_json_response(
+        client
+            .put(format!("{}/api/game-data/custom-recipes/{}", server.base_url, recipe_id))
+            .json(&json!({
+                "name": "Compressed Iron",
+                "machine": "Constructor",
+                "inputs": [{ "item": "IronIngot", "quantity": 5.0 }],
+                "outputs": [{ "item": "IronPlate", "quantity": 4.0 }],
+                "cycle_time": 6.0
+            }))
+            .send()
+            .await
+            .expect("Failed to update custom recipe"),
+    )
+    .await;
+
+    let factory = assert_json_response(
+        client
+            .get(format!("{}/api/factories/{}", server.base_url, factory_id))
+            .send()
+            .await
+            .expect("Failed to get factory"),
+    )
+    .await;
+    assert_eq!(factory["production_lines"][0]["output_rate"][0]["quantity"], 40.0);
+
+    let recipes = assert_json_response(
+        client
+            .get(format!("{}/api/game-data/custom-recipes", server.base_url))
+            .send()
+            .await
+            .expect("Failed to list custom recipes"),
+    )
+    .await;
+    assert_eq!(recipes.as_array().unwrap().len(), 1);
+    assert_eq!(recipes[0]["cycle_time"], 6.0);
+}
+
+#[tokio::test]
+async fn test_byproducts_in_line_payloads() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let factory = assert_created_response(
+        client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&minimal_factory_request("Refinery"))
+            .send()
+            .await
+            .expect("Failed to create factory"),
+    )
+    .await;
+    let factory_id = factory["id"].as_str().unwrap().to_string();
+
+    let plastic = json!({
+        "name": "Plastic",
+        "type": "recipe",
+        "recipe": "Plastic",
+        "machine_groups": [
+            { "number_of_machine": 2, "oc_value": 100.0, "somersloop": 0 },
+            { "number_of_machine": 1, "oc_value": 50.0, "somersloop": 0 }
+        ]
+    });
+
+    let preview = assert_json_response(
+        client
+            .post(format!(
+                "{}/api/factories/{}/production-lines/preview",
+                server.base_url, factory_id
+            ))
+            .json(&plastic)
+            .send()
+            .await
+            .expect("Failed to preview production line"),
+    )
+    .await;
+    assert_eq!(
+        preview["output_rate"],
+        json!([
+            { "item": "Plastic", "quantity": 50.0 },
+            { "item": "HeavyOilResidue", "quantity": 25.0 }
+        ])
+    );
+    assert_eq!(
+        preview["byproduct_rate"],
+        json!([{ "item": "HeavyOilResidue", "quantity": 25.0 }])
+    );
+
+    let factory = assert_created_response(
+        client
+            .post(format!("{}/api/factories/{}/production-lines", server.base_url, factory_id))
+            .json(&plastic)
+            .send()
+            .await
+            .expect("Failed to create production line"),
+    )
+    .await;
+    assert_eq!(
+        factory["production_lines"][0]["byproduct_rate"],
+        json!([{ "item": "HeavyOilResidue", "quantity": 25.0 }])
+    );
+    let residue = factory["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|balance| balance["item"] == "HeavyOilResidue")
+        .expect("Byproduct missing from the item balance");
+    assert_eq!(residue["quantity"], 25.0);
+}
+
+#[tokio::test]
+async fn test_diagnostics_report() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let diagnostics = assert_json_response(
+        client
+            .get(format!("{}/api/analysis/diagnostics", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get diagnostics"),
+    )
+    .await;
+    assert_eq!(diagnostics, json!([]));
+
+    let factory = assert_created_response(
+        client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&minimal_factory_request("Smelter"))
+            .send()
+            .await
+            .expect("Failed to create factory"),
+    )
+    .await;
+    let factory_id = factory["id"].as_str().unwrap().to_string();
+
+    assert_created_response(
+        client
+            .post(format!("{}/api/factories/{}/production-lines", server.base_url, factory_id))
+            .json(&json!({
+                "name": "Ingots",
+                "type": "recipe",
+                "recipe": "Iron Ingot",
+                "machine_groups": [
+                    { "number_of_machine": 2, "oc_value": 100.0, "somersloop": 0 }
+                ]
+            }))
+            .send()
+            .await
+            .expect("Failed to create production line"),
+    )
+    .await;
+
+    let diagnostics = assert_json_response(
+        client
+            .get(format!("{}/api/analysis/diagnostics", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get diagnostics"),
+    )
+    .await;
+    let diagnostics = diagnostics.as_array().unwrap();
+    assert_eq!(diagnostics.len(), 2);
+    assert_eq!(diagnostics[0]["severity"], "Error");
+    assert_eq!(diagnostics[0]["kind"], "PowerDeficit");
+    assert_eq!(diagnostics[1]["severity"], "Warning");
+    assert_eq!(diagnostics[1]["kind"], "ItemDeficit");
+    assert_eq!(diagnostics[1]["factory_id"], factory_id);
+    assert_eq!(diagnostics[1]["message"], "Smelter is short of 60.0 Iron Ore/min");
+}
+
+#[tokio::test]
+async fn test_blueprint_template_instantiate_scaled() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let template: Value = client
+        .post(format!("{}/api/blueprints/templates", server.base_url))
+        .json(&json!({
+            "name": "Steel Module",
+            "production_lines": [
+                {
+                    "name": "Steel Ingot Line",
+                    "recipe": "Steel Ingot",
+                    "machine_groups": [
+                        {
+                            "number_of_machine": 3,
+                            "oc_value": 100.0,
+                            "somersloop": 0
+                        }
+                    ]
+                }
+            ]
+        }))
+        .send()
+        .await
+        .expect("Failed to create blueprint template")
+        .json()
+        .await
+        .unwrap();
+    let template_id = template["id"].as_str().unwrap().to_string();
+
+    let factory: Value = client
+        .post(format!("{}/api/factories", server.base_url))
+        .json(&json!({ "name": "Steel Works" }))
+        .send()
+        .await
+        .expect("Failed to create factory")
+        .json()
+        .await
+        .unwrap();
+    let factory_id = factory["id"].as_str().unwrap().to_string();
+    let url = format!(
+        "{}/api/factories/{}/production-lines/from-template/{}",
+        server.base_url, factory_id, template_id
+    );
+
+    let response = client
+        .post(&url)
+        .json(&json!({ "scale": 2.5 }))
+        .send()
+        .await
+        .expect("Failed to instantiate blueprint");
+    assert_eq!(response.status(), 201);
+    let instance: Value = response.json().await.unwrap();
+    // 7.5 foundries: 7 at 100% and one at 50%
+    assert_eq!(instance["total_machines"], 8);
+    assert_eq!(instance["output_items"][0][1], 337.5);
+
+    let response = client
+        .post(&url)
+        .json(&json!({ "scale": 0.0 }))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status(), 400);
+
+    let factory: Value = client
+        .get(format!("{}/api/factories/{}", server.base_url, factory_id))
+        .send()
+        .await
+        .expect("Failed to get factory")
+        .json()
+        .await
+        .unwrap();
+    let groups = &factory["production_lines"][0]["ProductionLineBlueprint"]["production_lines"][0]
+        ["machine_groups"];
+    assert_eq!(groups[0]["number_of_machine"], 7);
+    assert_eq!(groups[1]["oc_value"], 50.0);
+}
+
+#[tokio::test]
+async fn test_blueprint_template_versions() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let template_body = |version: Option<&str>, changelog: Option<&str>| {
+        json!({
+            "name": "Motor Module",
+            "production_lines": [
+                {
+                    "name": "Rotors",
+                    "recipe": "Rotor",
+                    "machine_groups": [
+                        {
+                            "number_of_machine": 2,
+                            "oc_value": 100.0,
+                            "somersloop": 0
+                        }
+                    ]
+                }
+            ],
+            "version": version,
+            "changelog": changelog
+        })
+    };
+
+    let v1: Value = client
+        .post(format!("{}/api/blueprints/templates", server.base_url))
+        .json(&template_body(None, Some("First draft")))
+        .send()
+        .await
+        .expect("Failed to create blueprint template")
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(v1["version"], "1.0.0");
+    assert!(v1["parent_id"].is_null());
+    let v1_id = v1["id"].as_str().unwrap().to_string();
+
+    let v2: Value = client
+        .put(format!(
+            "{}/api/blueprints/templates/{}",
+            server.base_url, v1_id
+        ))
+        .json(&template_body(None, Some("More rotors")))
+        .send()
+        .await
+        .expect("Failed to update blueprint template")
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(v2["version"], "1.1.0");
+    assert_eq!(v2["parent_id"], v1_id);
+    assert_eq!(v2["changelog"], "More rotors");
+    let v2_id = v2["id"].as_str().unwrap().to_string();
+
+    let response = client
+        .put(format!(
+            "{}/api/blueprints/templates/{}",
+            server.base_url, v2_id
+        ))
+        .json(&template_body(Some("1.0.5"), None))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status(), 400);
+
+    let v3: Value = client
+        .put(format!(
+            "{}/api/blueprints/templates/{}",
+            server.base_url, v2_id
+        ))
+        .json(&template_body(Some("2.0.0"), Some("New layout")))
+        .send()
+        .await
+        .expect("Failed to update blueprint template")
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(v3["version"], "2.0.0");
+
+    let versions: Vec<Value> = client
+        .get(format!(
+            "{}/api/blueprints/templates/{}/versions",
+            server.base_url, v1_id
+        ))
+        .send()
+        .await
+        .expect("Failed to get template versions")
+        .json()
+        .await
+        .unwrap();
+    let numbers: Vec<&str> = versions
+        .iter()
+        .map(|version| version["version"].as_str().unwrap())
+        .collect();
+    assert_eq!(numbers, vec!["1.0.0", "1.1.0", "2.0.0"]);
+    assert_eq!(versions[0]["changelog"], "First draft");
+
+    let response = client
+        .get(format!(
+            "{}/api/blueprints/templates/{}/versions",
+            server.base_url,
+            Uuid::new_v4()
+        ))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status(), 404);
+}
+
+#[tokio::test]
+async fn test_blueprint_template_propagation() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let template_body = |machines: u32| {
+        json!({
+            "name": "Smelting Module",
+            "production_lines": [
+                {
+                    "name": "Ingots",
+                    "recipe": "Iron Ingot",
+                    "machine_groups": [
+                        {
+                            "number_of_machine": machines,
+                            "oc_value": 100.0,
+                            "somersloop": 0
+                        }
+                    ]
+                }
+            ]
+        })
+    };
+
+    let v1: Value = client
+        .post(format!("{}/api/blueprints/templates", server.base_url))
+        .json(&template_body(2))
+        .send()
+        .await
+        .expect("Failed to create blueprint template")
+        .json()
+        .await
+        .unwrap();
+    let v1_id = v1["id"].as_str().unwrap().to_string();
+
+    let factory: Value = client
+        .post(format!("{}/api/factories", server.base_url))
+        .json(&json!({ "name": "Foundry Row" }))
+        .send()
+        .await
+        .expect("Failed to create factory")
+        .json()
+        .await
+        .unwrap();
+    let factory_id = factory["id"].as_str().unwrap().to_string();
+
+    let instance: Value = client
+        .post(format!(
+            "{}/api/factories/{}/production-lines/from-template/{}",
+            server.base_url, factory_id, v1_id
+        ))
+        .json(&json!({}))
+        .send()
+        .await
+        .expect("Failed to instantiate blueprint")
+        .json()
+        .await
+        .unwrap();
+    let instance_id = instance["blueprint_id"].as_str().unwrap().to_string();
+
+    let v2: Value = client
+        .put(format!(
+            "{}/api/blueprints/templates/{}",
+            server.base_url, v1_id
+        ))
+        .json(&template_body(3))
+        .send()
+        .await
+        .expect("Failed to update blueprint template")
+        .json()
+        .await
+        .unwrap();
+    let v2_id = v2["id"].as_str().unwrap().to_string();
+
+    let instances: Vec<Value> = client
+        .get(format!(
+            "{}/api/blueprints/templates/{}/instances",
+            server.base_url, v2_id
+        ))
+        .send()
+        .await
+        .expect("Failed to get template instances")
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(instances.len(), 1);
+    assert_eq!(instances[0]["instance_id"], instance_id);
+    assert_eq!(instances[0]["template_id"], v1_id);
+    assert_eq!(instances[0]["up_to_date"], false);
+
+    let preview: Value = client
+        .post(format!(
+            "{}/api/blueprints/templates/{}/propagate",
+            server.base_url, v1_id
+        ))
+        .json(&json!({ "dry_run": true }))
+        .send()
+        .await
+        .expect("Failed to preview propagation")
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(preview["template_id"], v2_id);
+    assert_eq!(preview["dry_run"], true);
+    let update = &preview["factories"][0]["updates"][0];
+    assert_eq!(preview["factories"][0]["factory_id"], factory_id);
+    assert_eq!(update["from_version"], "1.0.0");
+    assert_eq!(update["to_version"], "1.1.0");
+    assert_eq!(update["machines_after"], 3);
+
+    let applied: Value = client
+        .post(format!(
+            "{}/api/blueprints/templates/{}/propagate",
+            server.base_url, v1_id
+        ))
+        .json(&json!({}))
+        .send()
+        .await
+        .expect("Failed to propagate template")
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(applied["factories"].as_array().unwrap().len(), 1);
+
+    let instances: Vec<Value> = client
+        .get(format!(
+            "{}/api/blueprints/templates/{}/instances",
+            server.base_url, v1_id
+        ))
+        .send()
+        .await
+        .expect("Failed to get template instances")
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(instances[0]["template_id"], v2_id);
+    assert_eq!(instances[0]["up_to_date"], true);
+
+    let response = client
+        .post(format!(
+            "{}/api/blueprints/templates/{}/propagate",
+            server.base_url,
+            Uuid::new_v4()
+        ))
+        .json(&json!({}))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status(), 404);
+}
+
+#[tokio::test]
+async fn test_export_csv() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    client
+        .post(format!("{}/api/factories", server.base_url))
+        .json(&json!({ "name": "Smelter, East" }))
+        .send()
+        .await
+        .expect("Failed to create factory");
+
+    let response = client
+        .get(format!(
+            "{}/api/export/csv?section=factories",
+            server.base_url
+        ))
+        .send()
+        .await
+        .expect("Failed to export factories");
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.headers()["content-type"],
+        "text/csv; charset=utf-8"
+    );
+    assert_eq!(
+        response.headers()["content-disposition"],
+        "attachment; filename=\"factories.csv\""
+    );
+    let csv = response.text().await.unwrap();
+    let rows: Vec<&str> = csv.lines().collect();
+    assert!(rows[0].starts_with("id,name,tags,archived,"));
+    assert_eq!(rows.len(), 2);
+    assert!(rows[1].contains(",\"Smelter, East\","));
+
+    for section in ["items", "logistics", "production_lines"] {
+        let response = client
+            .get(format!(
+                "{}/api/export/csv?section={}",
+                server.base_url, section
+            ))
+            .send()
+            .await
+            .expect("Failed to export section");
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.text().await.unwrap().lines().count(), 1);
+    }
+
+    let response = client
+        .get(format!("{}/api/export/csv?section=power", server.base_url))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status(), 400);
+}
+
+#[tokio::test]
+async fn test_export_markdown_report() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let factory: Value = client
+        .post(format!("{}/api/factories", server.base_url))
+        .json(&json!({ "name": "Iron Works", "description": "Plates and rods" }))
+        .send()
+        .await
+        .expect("Failed to create factory")
+        .json()
+        .await
+        .unwrap();
+    let factory_id = factory["id"].as_str().unwrap().to_string();
+
+    let response = client
+        .get(format!(
+            "{}/api/export/report.md?factory_id={}",
+            server.base_url, factory_id
+        ))
+        .send()
+        .await
+        .expect("Failed to export report");
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.headers()["content-type"],
+        "text/markdown; charset=utf-8"
+    );
+    let report = response.text().await.unwrap();
+    assert!(report.starts_with("# Iron Works\n\nPlates and rods\n"));
+
+    let report = client
+        .get(format!("{}/api/export/report.md", server.base_url))
+        .send()
+        .await
+        .expect("Failed to export report")
+        .text()
+        .await
+        .unwrap();
+    assert!(report.starts_with("# Factory report\n"));
+    assert!(report.contains("\n## Iron Works\n"));
+
+    let response = client
+        .get(format!(
+            "{}/api/export/report.md?factory_id={}",
+            server.base_url,
+            Uuid::new_v4()
+        ))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status(), 404);
+}
+
+#[tokio::test]
+async fn test_import_external_plan() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+    let plan = json!({
+        "metadata": { "name": "Plates" },
+        "request": {
+            "production": [{ "item": "Desc_IronPlate_C", "type": "perMinute", "amount": 60 }],
+            "input": [],
+            "allowedAlternateRecipes": [],
+            "blockedRecipes": []
+        }
+    });
+
+    let response = client
+        .post(format!("{}/api/import/external", server.base_url))
+        .json(&json!({ "format": "satisfactory_tools", "data": plan, "dry_run": true }))
+        .send()
+        .await
+        .expect("Failed to import plan");
+    assert_eq!(response.status(), 200);
+    let report: Value = response.json().await.unwrap();
+    assert_eq!(report["dry_run"], true);
+    assert!(report["factories"][0]["factory_id"].is_null());
+    assert_eq!(
+        report["factories"][0]["production_lines"][0]["recipe"],
+        "Iron Plate"
+    );
+    assert_eq!(report["factories"][0]["production_lines"][0]["machines"], 3);
+    assert_eq!(report["factories"][0]["production_lines"][1]["machines"], 3);
+
+    let factories: Value = client
+        .get(format!("{}/api/factories", server.base_url))
+        .send()
+        .await
+        .expect("Failed to list factories")
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(factories.as_array().unwrap().len(), 0);
+
+    let response = client
+        .post(format!("{}/api/import/external", server.base_url))
+        .json(&json!({ "format": "satisfactory_tools", "data": plan }))
+        .send()
+        .await
+        .expect("Failed to import plan");
+    assert_eq!(response.status(), 201);
+    let report: Value = response.json().await.unwrap();
+    let factory_id = report["factories"][0]["factory_id"].as_str().unwrap();
+
+    let factory: Value = client
+        .get(format!("{}/api/factories/{}", server.base_url, factory_id))
+        .send()
+        .await
+        .expect("Failed to get factory")
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(factory["name"], "Plates");
+    assert_eq!(factory["production_lines"].as_array().unwrap().len(), 2);
+
+    let response = client
+        .post(format!("{}/api/import/external", server.base_url))
+        .json(&json!({ "format": "satisfactory_calculator", "data": { "version": 1 } }))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status(), 400);
+}
+
+#[tokio::test]
+async fn test_import_game_save() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+    let upload = |dry_run: bool| {
+        reqwest::multipart::Form::new()
+            .part(
+                "file",
+                reqwest::multipart::Part::bytes(game_saves::smelter_save())
+                    .file_name("Iron Valley.sav"),
+            )
+            .text("dry_run", dry_run.to_string())
+    };
+
+    let response = client
+        .post(format!("{}/api/import/game-save", server.base_url))
+        .multipart(upload(true))
+        .send()
+        .await
+        .expect("Failed to upload save");
+    assert_eq!(response.status(), 200);
+    let report: Value = response.json().await.unwrap();
+    assert_eq!(report["save"]["header"]["session_name"], "Iron Valley");
+    assert_eq!(report["dry_run"], true);
+    assert_eq!(report["factories"][0]["factory_id"], Value::Null);
+
+    let response = client
+        .post(format!("{}/api/import/game-save", server.base_url))
+        .multipart(upload(false))
+        .send()
+        .await
+        .expect("Failed to upload save");
+    assert_eq!(response.status(), 201);
+    let report: Value = response.json().await.unwrap();
+    let factory = &report["factories"][0];
+    assert_eq!(factory["name"], "Power circuit 7");
+    assert_eq!(factory["power_grid"], "Power circuit 7");
+    assert_eq!(factory["production_lines"][0]["recipe"], "Iron Ingot");
+    assert_eq!(report["warnings"].as_array().unwrap().len(), 0);
+
+    let factory: Value = assert_json_response(
+        client
+            .get(format!(
+                "{}/api/factories/{}",
+                server.base_url,
+                factory["factory_id"].as_str().unwrap()
+            ))
+            .send()
+            .await
+            .expect("Failed to get factory"),
+    )
+    .await;
+    assert_eq!(factory["production_lines"].as_array().unwrap().len(), 1);
+
+    let response = client
+        .post(format!("{}/api/import/game-save", server.base_url))
+        .multipart(reqwest::multipart::Form::new().part(
+            "file",
+            reqwest::multipart::Part::bytes(b"not a save".to_vec()),
+        ))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status(), 400);
+
+    let response = client
+        .post(format!("{}/api/import/game-save", server.base_url))
+        .multipart(reqwest::multipart::Form::new().text("dry_run", "true"))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status(), 400);
+}
+
+#[tokio::test]
+async fn test_list_autosaves_disabled() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let response = client
+        .get(format!("{}/api/saves/autosaves", server.base_url))
+        .send()
+        .await
+        .expect("Failed to list autosaves");
+
+    assert_eq!(response.status(), 200);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["enabled"], false);
+    assert!(body.get("interval_secs").is_none());
+    assert_eq!(body["autosaves"], json!([]));
+}
+
+#[tokio::test]
+async fn test_snapshot_and_restore() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let factory: Value = client
+        .post(format!("{}/api/factories", server.base_url))
+        .json(&json!({ "name": "Before" }))
+        .send()
+        .await
+        .expect("Failed to create factory")
+        .json()
+        .await
+        .unwrap();
+    let factory_id = factory["id"].as_str().unwrap().to_string();
+
+    let response = client
+        .post(format!("{}/api/snapshots", server.base_url))
+        .json(&json!({ "label": "Before refactor" }))
+        .send()
+        .await
+        .expect("Failed to create snapshot");
+    assert_eq!(response.status(), 201);
+    let snapshot: Value = response.json().await.unwrap();
+    assert_eq!(snapshot["label"], "Before refactor");
+    assert_eq!(snapshot["summary"]["factory_count"], 1);
+    let snapshot_id = snapshot["id"].as_str().unwrap().to_string();
+
+    client
+        .delete(format!("{}/api/factories/{}", server.base_url, factory_id))
+        .send()
+        .await
+        .expect("Failed to delete factory");
+
+    let response = client
+        .post(format!(
+            "{}/api/snapshots/{}/restore",
+            server.base_url, snapshot_id
+        ))
+        .send()
+        .await
+        .expect("Failed to restore snapshot");
+    assert_eq!(response.status(), 200);
+
+    let factories: Value = client
+        .get(format!("{}/api/factories", server.base_url))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(factories.as_array().unwrap().len(), 1);
+
+    let snapshots: Value = client
+        .get(format!("{}/api/snapshots", server.base_url))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(snapshots.as_array().unwrap().len(), 1);
+
+    let response = client
+        .post(format!("{}/api/snapshots", server.base_url))
+        .json(&json!({ "label": "  " }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    let response = client
+        .post(format!(
+            "{}/api/snapshots/{}/restore",
+            server.base_url,
+            Uuid::new_v4()
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+}
+
+#[tokio::test]
+async fn test_diff_saves() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let save = |client: reqwest::Client, base_url: String| async move {
+        let body: Value = client
+            .get(format!("{}/api/save", base_url))
+            .send()
+            .await
+            .expect("Failed to save")
+            .json()
+            .await
+            .unwrap();
+        body["save_data"].as_str().unwrap().to_string()
+    };
+
+    let before = save(client.clone(), server.base_url.clone()).await;
+    client
+        .post(format!("{}/api/factories", server.base_url))
+        .json(&json!({ "name": "Steel Mill" }))
+        .send()
+        .await
+        .expect("Failed to create factory");
+    let after = save(client.clone(), server.base_url.clone()).await;
+
+    let response = client
+        .post(format!("{}/api/saves/diff", server.base_url))
+        .json(&json!({ "before": before, "after": after }))
+        .send()
+        .await
+        .expect("Failed to diff saves");
+    assert_eq!(response.status(), 200);
+    let diff: Value = response.json().await.unwrap();
+    assert_eq!(diff["factories_added"][0]["name"], "Steel Mill");
+    assert_eq!(diff["factories_removed"], json!([]));
+
+    let response = client
+        .post(format!("{}/api/saves/diff", server.base_url))
+        .json(&json!({ "before": before, "after": "not a save" }))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status(), 400);
+}
+
+#[tokio::test]
+async fn test_list_pagination_and_filters() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let mut ids = Vec::new();
+    for name in ["Charlie Smelter", "alpha Mine", "Bravo Smelter"] {
+        let factory: Value = client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&json!({ "name": name }))
+            .send()
+            .await
+            .expect("Failed to create factory")
+            .json()
+            .await
+            .unwrap();
+        ids.push(factory["id"].as_str().unwrap().to_string());
+    }
+
+    let all: Value = client
+        .get(format!("{}/api/factories", server.base_url))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let names: Vec<&str> = all
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|factory| factory["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["alpha Mine", "Bravo Smelter", "Charlie Smelter"]);
+
+    let page: Value = client
+        .get(format!(
+            "{}/api/factories?name_contains=smelter&order=desc&page=2&per_page=1",
+            server.base_url
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(page["total"], 2);
+    assert_eq!(page["total_pages"], 2);
+    assert_eq!(page["items"][0]["name"], "Bravo Smelter");
+
+    client
+        .post(format!("{}/api/logistics", server.base_url))
+        .json(&json!({
+            "from_factory": ids[1],
+            "to_factory": ids[0],
+            "transport_type": "Truck",
+            "item": "IronOre",
+            "quantity_per_min": 60.0
+        }))
+        .send()
+        .await
+        .expect("Failed to create logistics line");
+
+    let trucks: Value = client
+        .get(format!(
+            "{}/api/logistics?transport_type=truck&item=IronOre",
+            server.base_url
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(trucks.as_array().unwrap().len(), 1);
+
+    let none: Value = client
+        .get(format!("{}/api/logistics?item=CopperOre", server.base_url))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(none, json!([]));
+
+    let response = client
+        .get(format!("{}/api/factories?page=0", server.base_url))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+}
+
+#[tokio::test]
+async fn test_patch_partial_updates() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let mut factory_ids = Vec::new();
+    for name in ["Mine", "Smelter"] {
+        let factory: Value = client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&json!({ "name": name, "description": "Keep me" }))
+            .send()
+            .await
+            .expect("Failed to create factory")
+            .json()
+            .await
+            .unwrap();
+        factory_ids.push(factory["id"].as_str().unwrap().to_string());
+    }
+    let factory_url = format!("{}/api/factories/{}", server.base_url, factory_ids[1]);
+
+    let factory: Value = client
+        .patch(&factory_url)
+        .json(&json!({ "name": "Smelter East" }))
+        .send()
+        .await
+        .expect("Failed to patch factory")
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(factory["name"], "Smelter East");
+    assert_eq!(factory["description"], "Keep me");
+
+    let factory: Value = client
+        .post(format!("{}/production-lines", factory_url))
+        .json(&json!({
+            "name": "Ingots",
+            "type": "recipe",
+            "recipe": "Iron Ingot",
+            "machine_groups": [{ "number_of_machine": 2, "oc_value": 100.0, "somersloop": 0 }]
+        }))
+        .send()
+        .await
+        .expect("Failed to create production line")
+        .json()
+        .await
+        .unwrap();
+    let line_id = factory["production_lines"][0]["ProductionLineRecipe"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let line_url = format!("{}/production-lines/{}", factory_url, line_id);
+
+    let factory: Value = client
+        .patch(&line_url)
+        .json(&json!({ "machine_groups": [{ "number_of_machine": 4, "oc_value": 100.0, "somersloop": 0 }] }))
+        .send()
+        .await
+        .expect("Failed to patch production line")
+        .json()
+        .await
+        .unwrap();
+    let line = &factory["production_lines"][0];
+    assert_eq!(line["ProductionLineRecipe"]["name"], "Ingots");
+    assert_eq!(line["total_machines"], 4);
+
+    let response = client
+        .patch(&line_url)
+        .json(&json!({ "machine_groups": [] }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    let factory: Value = client
+        .post(format!("{}/power-generators", factory_url))
+        .json(&json!({
+            "generator_type": "Coal",
+            "fuel_type": "Coal",
+            "groups": [{ "number_of_generators": 2, "clock_speed": 100.0 }]
+        }))
+        .send()
+        .await
+        .expect("Failed to create power generator")
+        .json()
+        .await
+        .unwrap();
+    let generator_id = factory["power_generators"][0]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let factory: Value = client
+        .patch(format!("{}/power-generators/{}", factory_url, generator_id))
+        .json(&json!({ "groups": [{ "number_of_generators": 3, "clock_speed": 50.0 }] }))
+        .send()
+        .await
+        .expect("Failed to patch power generator")
+        .json()
+        .await
+        .unwrap();
+    let generator = &factory["power_generators"][0];
+    assert_eq!(generator["fuel_type"], "Coal");
+    assert_eq!(generator["groups"][0]["number_of_generators"], 3);
+
+    let logistics: Value = client
+        .post(format!("{}/api/logistics", server.base_url))
+        .json(&json!({
+            "from_factory": factory_ids[0],
+            "to_factory": factory_ids[1],
+            "transport_type": "Truck",
+            "item": "IronOre",
+            "quantity_per_min": 60.0,
+            "truck_id": "Ore Hauler 7"
+        }))
+        .send()
+        .await
+        .expect("Failed to create logistics line")
+        .json()
+        .await
+        .unwrap();
+    let logistics_url = format!(
+        "{}/api/logistics/{}",
+        server.base_url,
+        logistics["id"].as_str().unwrap()
+    );
+
+    let logistics: Value = client
+        .patch(&logistics_url)
+        .json(&json!({ "quantity_per_min": 90.0 }))
+        .send()
+        .await
+        .expect("Failed to patch logistics line")
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(logistics["total_quantity_per_min"], 90.0);
+    assert_eq!(logistics["items"][0]["item"], "IronOre");
+    assert_eq!(logistics["transport_details"]["Truck"]["truck_id"], "Ore Hauler 7");
+
+    let response = client
+        .patch(&logistics_url)
+        .json(&json!({ "quantity_per_min": -1.0 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+}
+
+#[tokio::test]
+async fn test_if_match_rejects_stale_writes() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let factory: Value = client
+        .post(format!("{}/api/factories", server.base_url))
+        .json(&json!({ "name": "Smelter" }))
+        .send()
+        .await
+        .expect("Failed to create factory")
+        .json()
+        .await
+        .unwrap();
+    let factory_url = format!(
+        "{}/api/factories/{}",
+        server.base_url,
+        factory["id"].as_str().unwrap()
+    );
+
+    // Two tabs read the same state
+    let response = client.get(&factory_url).send().await.unwrap();
+    let etag = response.headers()["etag"].to_str().unwrap().to_string();
+    assert!(etag.starts_with('"'));
+
+    // The first one saves, which moves the revision on
+    let response = client
+        .patch(&factory_url)
+        .header("If-Match", &etag)
+        .json(&json!({ "name": "Smelter North" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let new_etag = response.headers()["etag"].to_str().unwrap().to_string();
+    assert_ne!(new_etag, etag);
+
+    // The second one is told its copy is stale instead of clobbering it
+    let response = client
+        .put(&factory_url)
+        .header("If-Match", &etag)
+        .json(&json!({ "name": "Smelter South" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 409);
+    let response = client
+        .delete(&factory_url)
+        .header("If-Match", &etag)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 409);
+
+    let factory: Value = client
+        .get(&factory_url)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(factory["name"], "Smelter North");
+
+    // With the current revision the write goes through
+    let response = client
+        .delete(&factory_url)
+        .header("If-Match", &new_etag)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 204);
+}
+
+#[tokio::test]
+async fn test_game_data_caching_and_compression() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+    let url = format!("{}/api/game-data/recipes", server.base_url);
+
+    let response = client.get(&url).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    let etag = response.headers()["etag"].to_str().unwrap().to_string();
+    assert!(response.headers()["cache-control"]
+        .to_str()
+        .unwrap()
+        .contains("max-age"));
+
+    // The lists are static, so the tag doesn't follow the engine revision
+    client
+        .post(format!("{}/api/factories", server.base_url))
+        .json(&json!({ "name": "Smelter" }))
+        .send()
+        .await
+        .unwrap();
+    let response = client
+        .get(&url)
+        .header("If-None-Match", &etag)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 304);
+    assert!(response.bytes().await.unwrap().is_empty());
+
+    // Progress dependent lists must be revalidated
+    let response = client
+        .get(format!("{}?unlocked_only=true", url))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.headers()["cache-control"], "no-cache");
+
+    let response = client
+        .get(&url)
+        .header("Accept-Encoding", "gzip")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-encoding"], "gzip");
+}
+
+#[tokio::test]
+async fn test_api_key_roles() {
+    let server = create_auth_test_server(
+        AuthConfig::new(
+            vec!["writer-key".to_string()],
+            vec!["reader-key".to_string()],
+        )
+        .unwrap(),
+    )
+    .await;
+    let client = create_test_client();
+    let factories_url = format!("{}/api/factories", server.base_url);
+
+    let response = client.get(&factories_url).send().await.unwrap();
+    assert_eq!(response.status(), 401);
+    assert_eq!(response.headers()["www-authenticate"], "Bearer");
+    let response = client
+        .get(&factories_url)
+        .bearer_auth("guessed-key")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 401);
+
+    // Read-only keys can look but not touch
+    let response = client
+        .get(&factories_url)
+        .header("X-API-Key", "reader-key")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let response = client
+        .post(&factories_url)
+        .bearer_auth("reader-key")
+        .json(&json!({ "name": "Smelter" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 403);
+
+    let response = client
+        .post(&factories_url)
+        .bearer_auth("writer-key")
+        .json(&json!({ "name": "Smelter" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 201);
+
+    // Health checks stay open for load balancers
+    let response = client
+        .get(format!("{}/health", server.base_url))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+}
+
+#[tokio::test]
+async fn test_workspaces_are_isolated() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+    let workspaces_url = format!("{}/api/workspaces", server.base_url);
+
+    let workspace: Value = client
+        .post(&workspaces_url)
+        .json(&json!({ "name": "Co-op session", "members": ["Ada", " Ada ", "Grace"] }))
+        .send()
+        .await
+        .expect("Failed to create workspace")
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(workspace["members"], json!(["Ada", "Grace"]));
+    let workspace_url = format!("{}/{}", workspaces_url, workspace["id"].as_str().unwrap());
+
+    // A factory created in the workspace stays out of the default one
+    let response = client
+        .post(format!("{}/factories", workspace_url))
+        .json(&json!({ "name": "Shared Smelter" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 201);
+    client
+        .post(format!("{}/api/factories", server.base_url))
+        .json(&json!({ "name": "Solo Smelter" }))
+        .send()
+        .await
+        .unwrap();
+
+    let factories: Value = client
+        .get(format!("{}/factories?name_contains=smelter", workspace_url))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(factories.as_array().unwrap().len(), 1);
+    assert_eq!(factories[0]["name"], "Shared Smelter");
+
+    // The default workspace is reachable both ways
+    let factories: Value = client
+        .get(format!("{}/default/factories", workspaces_url))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(factories.as_array().unwrap().len(), 1);
+    assert_eq!(factories[0]["name"], "Solo Smelter");
+
+    let workspaces: Value = client
+        .get(&workspaces_url)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(workspaces[0]["id"], "default");
+    assert_eq!(workspaces[1]["factory_count"], 1);
+
+    let response = client
+        .delete(format!("{}/default", workspaces_url))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+    let response = client.delete(&workspace_url).send().await.unwrap();
+    assert_eq!(response.status(), 204);
+    let response = client
+        .get(format!("{}/factories", workspace_url))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+}
+
+#[tokio::test]
+async fn test_graphql_nested_query() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let mut factory_ids = Vec::new();
+    for name in ["Mine", "Smelter"] {
+        let factory: Value = client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&json!({ "name": name }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        factory_ids.push(factory["id"].as_str().unwrap().to_string());
+    }
+    client
+        .post(format!(
+            "{}/api/factories/{}/production-lines",
+            server.base_url, factory_ids[1]
+        ))
+        .json(&json!({
+            "name": "Iron Ingots",
+            "type": "recipe",
+            "recipe": "Iron Ingot",
+            "machine_groups": [{ "number_of_machine": 2, "oc_value": 100.0, "somersloop": 0 }]
+        }))
+        .send()
+        .await
+        .unwrap();
+    client
+        .post(format!("{}/api/logistics", server.base_url))
+        .json(&json!({
+            "from_factory": factory_ids[0],
+            "to_factory": factory_ids[1],
+            "transport_type": "Truck",
+            "item": "IronOre",
+            "quantity_per_min": 60.0
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    let query = r#"{
+        factories(nameContains: "smelt") {
+            name
+            productionLines { name totalMachines recipe { name machine outputs { item rate } } }
+            incomingLogistics { transportType fromFactory { name } items { item rate } }
+        }
+        dashboard { totalFactories totalLogisticsLines }
+    }"#;
+    let response: Value = client
+        .post(format!("{}/api/graphql", server.base_url))
+        .json(&json!({ "query": query }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(response.get("errors").is_none(), "{}", response);
+    let smelter = &response["data"]["factories"][0];
+    assert_eq!(smelter["name"], "Smelter");
+    let line = &smelter["productionLines"][0];
+    assert_eq!(line["totalMachines"], 2);
+    assert_eq!(line["recipe"]["name"], "Iron Ingot");
+    assert_eq!(line["recipe"]["outputs"][0]["item"], "IronIngot");
+    let incoming = &smelter["incomingLogistics"][0];
+    assert_eq!(incoming["transportType"], "Truck");
+    assert_eq!(incoming["fromFactory"]["name"], "Mine");
+    assert_eq!(incoming["items"][0]["rate"], 60.0);
+    assert_eq!(response["data"]["dashboard"]["totalFactories"], 2);
+
+    // Queries also work over GET, e.g. with a read-only API key
+    let response: Value = client
+        .get(format!("{}/api/graphql", server.base_url))
+        .query(&[("query", "{ dashboard { totalLogisticsLines } }")])
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(response["data"]["dashboard"]["totalLogisticsLines"], 1);
+}
+
+#[tokio::test]
+async fn test_dashboard_summary_follows_changes() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+    let summary = || async {
+        client
+            .get(format!("{}/api/dashboard/summary", server.base_url))
+            .send()
+            .await
+            .unwrap()
+            .json::<Value>()
+            .await
+            .unwrap()
+    };
+
+    assert_eq!(summary().await["total_factories"], 0);
+    // Served from the cache while nothing changes
+    assert_eq!(summary().await["total_factories"], 0);
+
+    client
+        .post(format!("{}/api/factories", server.base_url))
+        .json(&create_factory_request())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(summary().await["total_factories"], 1);
+
+    client
+        .post(format!("{}/api/reset", server.base_url))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(summary().await["total_factories"], 0);
+}
+
+#[tokio::test]
+async fn test_global_search() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let created = assert_created_response(
+        client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&minimal_factory_request("Motor Works"))
+            .send()
+            .await
+            .expect("Failed to create factory"),
+    )
+    .await;
+
+    let hits: Value = assert_json_response(
+        client
+            .get(format!("{}/api/search?q=MOTOR", server.base_url))
+            .send()
+            .await
+            .expect("Failed to search"),
+    )
+    .await;
+    let hits = hits.as_array().unwrap();
+    assert_eq!(hits[0]["type"], "Item");
+    assert_eq!(hits[0]["item"], "Motor");
+    let factory = hits
+        .iter()
+        .find(|hit| hit["type"] == "Factory")
+        .expect("Factory not found");
+    assert_eq!(factory["id"], created["id"]);
+    assert_eq!(factory["name"], "Motor Works");
+    assert_eq!(factory["field"], "Name");
+
+    let limited: Value = assert_json_response(
+        client
+            .get(format!("{}/api/search?q=motor&limit=1", server.base_url))
+            .send()
+            .await
+            .expect("Failed to search with limit"),
+    )
+    .await;
+    assert_eq!(limited.as_array().unwrap().len(), 1);
+
+    for query in ["q=%20", "q=motor&limit=0"] {
+        let invalid = client
+            .get(format!("{}/api/search?{}", server.base_url, query))
+            .send()
+            .await
+            .expect("Failed to send invalid search");
+        assert_eq!(invalid.status().as_u16(), 400);
+    }
+}
+
+#[tokio::test]
+async fn test_logistics_flows() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let mut factory_ids = Vec::new();
+    for name in ["Mine", "Smelter"] {
+        let created = assert_created_response(
+            client
+                .post(format!("{}/api/factories", server.base_url))
+                .json(&minimal_factory_request(name))
+                .send()
+                .await
+                .expect("Failed to create factory"),
+        )
+        .await;
+        factory_ids.push(created["id"].as_str().unwrap().to_string());
+    }
+
+    for quantity in [60.0, 30.0] {
+        assert_created_response(
+            client
+                .post(format!("{}/api/logistics", server.base_url))
+                .json(&json!({
+                    "from_factory": factory_ids[0],
+                    "to_factory": factory_ids[1],
+                    "transport_type": "Truck",
+                    "item": "IronOre",
+                    "quantity_per_min": quantity
+                }))
+                .send()
+                .await
+                .expect("Failed to create logistics"),
+        )
+        .await;
+    }
+
+    let flows: Value = assert_json_response(
+        client
+            .get(format!("{}/api/analysis/flows", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get flows"),
+    )
+    .await;
+    let nodes = flows["nodes"].as_array().unwrap();
+    assert_eq!(nodes.len(), 2);
+    assert_eq!(nodes[0]["name"], "Mine");
+    let links = flows["links"].as_array().unwrap();
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0]["source"], factory_ids[0].as_str());
+    assert_eq!(links[0]["target"], factory_ids[1].as_str());
+    assert_eq!(links[0]["item"], "IronOre");
+    assert_eq!(links[0]["transport"], "Truck");
+    assert_eq!(links[0]["value"], 90.0);
+    assert_eq!(links[0]["logistics_ids"].as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_dashboard_history_records_changes() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+    let history = |query: &'static str| {
+        let client = client.clone();
+        let url = format!("{}/api/dashboard/history{}", server.base_url, query);
+        async move {
+            client
+                .get(url)
+                .send()
+                .await
+                .unwrap()
+                .json::<Value>()
+                .await
+                .unwrap()
+        }
+    };
+
+    assert_eq!(history("").await, json!([]));
+
+    let factory = assert_created_response(
+        client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&minimal_factory_request("Smelter"))
+            .send()
+            .await
+            .expect("Failed to create factory"),
+    )
+    .await;
+    assert_created_response(
+        client
+            .post(format!(
+                "{}/api/factories/{}/production-lines",
+                server.base_url,
+                factory["id"].as_str().unwrap()
+            ))
+            .json(&json!({
+                "name": "Plates",
+                "type": "recipe",
+                "recipe": "Iron Plate",
+                "machine_groups": [
+                    { "number_of_machine": 1, "oc_value": 100.0, "somersloop": 0 }
+                ]
+            }))
+            .send()
+            .await
+            .expect("Failed to create production line"),
+    )
+    .await;
+    // Reads and failed writes don't record anything
+    client
+        .post(format!("{}/api/factories", server.base_url))
+        .json(&minimal_factory_request("  "))
+        .send()
+        .await
+        .unwrap();
+
+    let points = history("").await;
+    let points = points.as_array().unwrap();
+    assert_eq!(points.len(), 2);
+    assert!(points[0]["recorded_at"].is_string());
+    assert_eq!(points[0]["items"], json!({}));
+    assert_eq!(points[1]["items"]["IronPlate"], 20.0);
+    assert_eq!(points[1]["power_consumption"], 4.0);
+
+    let plates = history("?item=IronPlate").await;
+    assert_eq!(plates[0]["items"], json!({ "IronPlate": 0.0 }));
+    assert_eq!(plates[1]["items"], json!({ "IronPlate": 20.0 }));
+}
+
+#[tokio::test]
+async fn test_scenario_sandbox() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let real = assert_created_response(
+        client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&minimal_factory_request("Smelter"))
+            .send()
+            .await
+            .expect("Failed to create factory"),
+    )
+    .await;
+
+    let scenario = assert_created_response(
+        client
+            .post(format!("{}/api/scenarios", server.base_url))
+            .json(&json!({ "name": "  Move smelting " }))
+            .send()
+            .await
+            .expect("Failed to create scenario"),
+    )
+    .await;
+    assert_eq!(scenario["name"], "Move smelting");
+    let scenario_url = format!(
+        "{}/api/scenarios/{}",
+        server.base_url,
+        scenario["id"].as_str().unwrap()
+    );
+
+    // Changes in the sandbox leave the real state alone
+    assert_created_response(
+        client
+            .post(format!("{}/factories", scenario_url))
+            .json(&minimal_factory_request("Assembly"))
+            .send()
+            .await
+            .expect("Failed to create factory in scenario"),
+    )
+    .await;
+    let sandbox: Value = assert_json_response(
+        client
+            .get(format!("{}/factories", scenario_url))
+            .send()
+            .await
+            .expect("Failed to list scenario factories"),
+    )
+    .await;
+    assert_eq!(sandbox.as_array().unwrap().len(), 2);
+    let factories: Value = assert_json_response(
+        client
+            .get(format!("{}/api/factories", server.base_url))
+            .send()
+            .await
+            .expect("Failed to list factories"),
+    )
+    .await;
+    assert_eq!(factories.as_array().unwrap().len(), 1);
+
+    let diff: Value = assert_json_response(
+        client
+            .get(format!("{}/diff", scenario_url))
+            .send()
+            .await
+            .expect("Failed to diff scenario"),
+    )
+    .await;
+    assert_eq!(diff["factories_added"][0]["name"], "Assembly");
+
+    let committed: Value = assert_json_response(
+        client
+            .post(format!("{}/commit", scenario_url))
+            .send()
+            .await
+            .expect("Failed to commit scenario"),
+    )
+    .await;
+    assert_eq!(committed, diff);
+    let factories: Value = assert_json_response(
+        client
+            .get(format!("{}/api/factories", server.base_url))
+            .send()
+            .await
+            .expect("Failed to list factories"),
+    )
+    .await;
+    assert_eq!(factories.as_array().unwrap().len(), 2);
+    let gone = client.get(&scenario_url).send().await.unwrap();
+    assert_eq!(gone.status().as_u16(), 404);
+
+    // A scenario forked before a real change can't overwrite it
+    let stale = assert_created_response(
+        client
+            .post(format!("{}/api/scenarios", server.base_url))
+            .json(&json!({ "name": "Stale" }))
+            .send()
+            .await
+            .expect("Failed to create scenario"),
+    )
+    .await;
+    client
+        .delete(format!(
+            "{}/api/factories/{}",
+            server.base_url,
+            real["id"].as_str().unwrap()
+        ))
+        .send()
+        .await
+        .unwrap();
+    let conflict = client
+        .post(format!(
+            "{}/api/scenarios/{}/commit",
+            server.base_url,
+            stale["id"].as_str().unwrap()
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(conflict.status().as_u16(), 409);
+
+    let invalid = client
+        .post(format!("{}/api/scenarios", server.base_url))
+        .json(&json!({ "name": " " }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(invalid.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn test_storage_buffers() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let factory = assert_created_response(
+        client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&minimal_factory_request("Smelter"))
+            .send()
+            .await
+            .expect("Failed to create factory"),
+    )
+    .await;
+    let factory_url = format!(
+        "{}/api/factories/{}",
+        server.base_url,
+        factory["id"].as_str().unwrap()
+    );
+    assert_created_response(
+        client
+            .post(format!("{}/production-lines", factory_url))
+            .json(&json!({
+                "name": "Ingots",
+                "type": "recipe",
+                "recipe": "Iron Ingot",
+                "machine_groups": [
+                    { "number_of_machine": 2, "oc_value": 100.0, "somersloop": 0 }
+                ]
+            }))
+            .send()
+            .await
+            .expect("Failed to create production line"),
+    )
+    .await;
+
+    // 60 ore/min drawn from 2,040 ore
+    let updated = assert_created_response(
+        client
+            .post(format!("{}/storage-buffers", factory_url))
+            .json(&json!({ "item": "IronOre", "capacity_stacks": 24, "stored": 2040.0 }))
+            .send()
+            .await
+            .expect("Failed to create storage buffer"),
+    )
+    .await;
+    let buffer = &updated["storage_buffers"][0];
+    assert_eq!(buffer["capacity"], 12000.0);
+    assert_eq!(buffer["net_rate"], -60.0);
+    assert_eq!(buffer["minutes_until_empty"], 34.0);
+    assert!(buffer["minutes_until_full"].is_null());
+    let buffer_url = format!(
+        "{}/storage-buffers/{}",
+        factory_url,
+        buffer["buffer_id"].as_str().unwrap()
+    );
+
+    let diagnostics: Value = assert_json_response(
+        client
+            .get(format!("{}/api/analysis/diagnostics", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get diagnostics"),
+    )
+    .await;
+    assert!(diagnostics.as_array().unwrap().iter().any(|diagnostic| {
+        diagnostic["message"] == "Smelter's Iron Ore buffer empties in 34 min"
+    }));
+
+    let updated: Value = assert_json_response(
+        client
+            .put(&buffer_url)
+            .json(&json!({ "item": "IronOre", "capacity_stacks": 24, "stored": 6000.0 }))
+            .send()
+            .await
+            .expect("Failed to update storage buffer"),
+    )
+    .await;
+    assert_eq!(updated["storage_buffers"][0]["minutes_until_empty"], 100.0);
+
+    // Fluids don't fit in containers, and a buffer can't hold more than its capacity
+    for body in [
+        json!({ "item": "Water", "capacity_stacks": 24 }),
+        json!({ "item": "IronOre", "capacity_stacks": 1, "stored": 501.0 }),
+    ] {
+        let invalid = client
+            .post(format!("{}/storage-buffers", factory_url))
+            .json(&body)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(invalid.status().as_u16(), 400);
+    }
+
+    let updated: Value = assert_json_response(
+        client
+            .delete(&buffer_url)
+            .send()
+            .await
+            .expect("Failed to delete storage buffer"),
+    )
+    .await;
+    assert!(updated["storage_buffers"].as_array().unwrap().is_empty());
+    let missing = client.delete(&buffer_url).send().await.unwrap();
+    assert_eq!(missing.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn test_train_throughput() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let mut factory_ids = Vec::new();
+    for (name, x) in [("Mine", 0.0), ("Smelter", 6000.0)] {
+        let factory = assert_created_response(
+            client
+                .post(format!("{}/api/factories", server.base_url))
+                .json(&json!({ "name": name, "position": { "x": x, "y": 0.0 } }))
+                .send()
+                .await
+                .expect("Failed to create factory"),
+        )
+        .await;
+        factory_ids.push(factory["id"].as_str().unwrap().to_string());
+    }
+    let train = assert_created_response(
+        client
+            .post(format!("{}/api/logistics", server.base_url))
+            .json(&json!({
+                "from_factory": factory_ids[0],
+                "to_factory": factory_ids[1],
+                "transport_type": "Train",
+                "train_name": "Ore Express",
+                "wagons": [
+                    { "wagon_type": "Cargo", "item": "IronOre", "quantity_per_min": 2000.0 },
+                    { "wagon_type": "Fluid", "item": "Water", "quantity_per_min": 100.0 }
+                ]
+            }))
+            .send()
+            .await
+            .expect("Failed to create train"),
+    )
+    .await;
+    let url = format!(
+        "{}/api/logistics/{}/train-throughput",
+        server.base_url,
+        train["id"].as_str().unwrap()
+    );
+
+    // The route defaults to the map distance: 2 × 240 s of travel plus 2 × 27 s docking
+    let throughput: Value = assert_json_response(
+        client
+            .get(&url)
+            .send()
+            .await
+            .expect("Failed to get train throughput"),
+    )
+    .await;
+    assert_eq!(throughput["round_trip_secs"], 534.0);
+    assert_eq!(throughput["wagons"][0]["capacity_per_trip"], 16000.0);
+    assert_eq!(throughput["wagons"][0]["over_capacity"], true);
+    assert_eq!(throughput["wagons"][1]["over_capacity"], false);
+    assert_eq!(throughput["warnings"].as_array().unwrap().len(), 1);
+
+    let faster: Value = assert_json_response(
+        client
+            .get(&url)
+            .query(&[("speed", 50.0), ("station_time_secs", 30.0)])
+            .send()
+            .await
+            .expect("Failed to get train throughput"),
+    )
+    .await;
+    assert_eq!(faster["round_trip_secs"], 300.0);
+    assert_eq!(faster["wagons"][0]["max_per_min"], 3200.0);
+    assert!(faster["warnings"].as_array().unwrap().is_empty());
+
+    let invalid = client
+        .get(&url)
+        .query(&[("speed", 0.0)])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(invalid.status().as_u16(), 400);
+
+    let truck = assert_created_response(
+        client
+            .post(format!("{}/api/logistics", server.base_url))
+            .json(&json!({
+                "from_factory": factory_ids[0],
+                "to_factory": factory_ids[1],
+                "transport_type": "Truck",
+                "item": "IronOre",
+                "quantity_per_min": 60.0
+            }))
+            .send()
+            .await
+            .expect("Failed to create truck"),
+    )
+    .await;
+    let not_a_train = client
+        .get(format!(
+            "{}/api/logistics/{}/train-throughput",
+            server.base_url,
+            truck["id"].as_str().unwrap()
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(not_a_train.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn test_game_data_conveyors() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let conveyors: Value = assert_json_response(
+        client
+            .get(format!("{}/api/game-data/conveyors", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get conveyors"),
+    )
+    .await;
+    let conveyors = conveyors.as_array().unwrap();
+    assert_eq!(conveyors.len(), 12);
+    assert_eq!(conveyors[5]["name"], "Conveyor Belt Mk.6");
+    assert_eq!(conveyors[5]["items_per_min"], 1200.0);
+    assert_eq!(conveyors[11]["name"], "Conveyor Lift Mk.6");
+    assert_eq!(conveyors[11]["kind"], "Lift");
+    assert_eq!(conveyors[11]["tier"], "Mk6");
+    assert_eq!(conveyors[11]["items_per_min"], 1200.0);
+}
+
+#[tokio::test]
+async fn test_game_data_recipe_search() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let recipes: Value = assert_json_response(
+        client
+            .get(format!(
+                "{}/api/game-data/recipes/search?output=ReinforcedIronPlate&input=IronPlate&machine=Assembler",
+                server.base_url
+            ))
+            .send()
+            .await
+            .expect("Failed to search recipes"),
+    )
+    .await;
+    let names: Vec<&str> = recipes
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|recipe| recipe["name"].as_str().unwrap())
+        .collect();
+    assert!(names.contains(&"Alternate: Adhered Iron Plate"));
+    assert!(recipes.as_array().unwrap().iter().all(|recipe| {
+        recipe["machine"] == "Assembler"
+            && recipe["outputs"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|output| output["item"] == "ReinforcedIronPlate")
+    }));
+
+    let uses: Value = assert_json_response(
+        client
+            .get(format!(
+                "{}/api/game-data/items/IronPlate/uses",
+                server.base_url
+            ))
+            .send()
+            .await
+            .expect("Failed to get item uses"),
+    )
+    .await;
+    assert_eq!(uses["item"], "IronPlate");
+    assert!(uses["produced_by"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|recipe| recipe["name"] == "Iron Plate"));
+    assert!(uses["consumed_by"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|recipe| recipe["name"] == "Alternate: Adhered Iron Plate"));
+
+    let response = client
+        .get(format!(
+            "{}/api/game-data/recipes/search?output=NotAnItem",
+            server.base_url
+        ))
+        .send()
+        .await
+        .expect("Failed to search recipes");
+    assert_eq!(response.status(), 400);
+}
+
+#[tokio::test]
+async fn test_game_data_recipe_chain() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let chain: Value = assert_json_response(
+        client
+            .get(format!(
+                "{}/api/game-data/recipes/ReinforcedIronPlate/chain?depth=1",
+                server.base_url
+            ))
+            .send()
+            .await
+            .expect("Failed to get recipe chain"),
+    )
+    .await;
+    assert_eq!(chain["name"], "Reinforced Iron Plate");
+    assert_eq!(chain["machines"], 1.0);
+    let screws = &chain["inputs"][1];
+    assert_eq!(screws["item"], "Screw");
+    assert_eq!(screws["quantity_per_min"], 60.0);
+    let rods = &screws["source"]["Recipe"]["inputs"][0];
+    assert_eq!(rods["item"], "IronRod");
+    assert_eq!(rods["quantity_per_min"], 15.0);
+    assert_eq!(rods["source"], "Truncated");
+
+    let full: Value = assert_json_response(
+        client
+            .get(format!(
+                "{}/api/game-data/recipes/IronPlate/chain",
+                server.base_url
+            ))
+            .send()
+            .await
+            .expect("Failed to get recipe chain"),
+    )
+    .await;
+    assert_eq!(full["inputs"][0]["source"]["Recipe"]["inputs"][0]["source"], "Raw");
+
+    let response = client
+        .get(format!(
+            "{}/api/game-data/recipes/IronPlate/chain?depth=100",
+            server.base_url
+        ))
+        .send()
+        .await
+        .expect("Failed to get recipe chain");
+    assert_eq!(response.status(), 400);
+}
+
+#[tokio::test]
+async fn test_game_data_versions() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let data: Value = assert_json_response(
+        client
+            .get(format!("{}/api/game-data", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get game data"),
+    )
+    .await;
+    assert_eq!(data["version"], "1.2");
+    assert_eq!(data["selected"], true);
+    assert_eq!(data["available"], json!(["1.2"]));
+    assert!(!data["recipes"].as_array().unwrap().is_empty());
+
+    let response = client
+        .get(format!("{}/api/game-data?version=0.8", server.base_url))
+        .send()
+        .await
+        .expect("Failed to get game data");
+    assert_eq!(response.status(), 404);
+
+    let selected: Value = assert_json_response(
+        client
+            .put(format!("{}/api/game-data/version", server.base_url))
+            .json(&json!({ "version": "1.2" }))
+            .send()
+            .await
+            .expect("Failed to select game data"),
+    )
+    .await;
+    assert_eq!(selected["version"], "1.2");
+
+    let response = client
+        .put(format!("{}/api/game-data/version", server.base_url))
+        .json(&json!({ "version": "0.8" }))
+        .send()
+        .await
+        .expect("Failed to select game data");
+    assert_eq!(response.status(), 400);
+
+    let save: Value = assert_json_response(
+        client
+            .get(format!("{}/api/save", server.base_url))
+            .send()
+            .await
+            .expect("Failed to save"),
+    )
+    .await;
+    let save_file: Value = serde_json::from_str(save["save_data"].as_str().unwrap()).unwrap();
+    assert_eq!(save_file["game_version"], "1.2");
+}
+
+#[tokio::test]
+async fn test_game_data_ficsmas() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+    let has_candy_cane = |data: &Value| {
+        data["recipes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|recipe| recipe["name"] == "Candy Cane")
+    };
+
+    let data: Value = assert_json_response(
+        client
+            .get(format!("{}/api/game-data", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get game data"),
+    )
+    .await;
+    assert_eq!(data["ficsmas"], false);
+    assert!(!has_candy_cane(&data));
+
+    let data: Value = assert_json_response(
+        client
+            .put(format!("{}/api/game-data/ficsmas", server.base_url))
+            .json(&json!({ "enabled": true }))
+            .send()
+            .await
+            .expect("Failed to enable FICSMAS"),
+    )
+    .await;
+    assert_eq!(data["ficsmas"], true);
+    assert!(has_candy_cane(&data));
+
+    let save: Value = assert_json_response(
+        client
+            .get(format!("{}/api/save", server.base_url))
+            .send()
+            .await
+            .expect("Failed to save"),
+    )
+    .await;
+    let save_file: Value = serde_json::from_str(save["save_data"].as_str().unwrap()).unwrap();
+    assert_eq!(save_file["engine"]["ficsmas"], true);
+}
+
+#[tokio::test]
+async fn test_production_line_preview_variable_power() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let factory: Value = assert_created_response(
+        client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&json!({ "name": "Endgame" }))
+            .send()
+            .await
+            .expect("Failed to create factory"),
+    )
+    .await;
+    let factory_id = factory["id"].as_str().unwrap();
+
+    let preview: Value = assert_json_response(
+        client
+            .post(format!(
+                "{}/api/factories/{}/production-lines/preview",
+                server.base_url, factory_id
+            ))
+            .json(&json!({
+                "name": "Photons",
+                "type": "recipe",
+                "recipe": "Excited Photonic Matter",
+                "machine_groups": [
+                    { "number_of_machine": 2, "oc_value": 100.0, "somersloop": 0 }
+                ]
+            }))
+            .send()
+            .await
+            .expect("Failed to preview production line"),
+    )
+    .await;
+    // Converters draw 100 to 400 MW each
+    assert_eq!(preview["total_power_consumption"], 500.0);
+    assert_eq!(
+        preview["consumption_range"],
+        json!({ "min": 200.0, "average": 500.0, "max": 800.0 })
+    );
+    assert_eq!(preview["output_rate"][0]["item"], "ExcitedPhotonicMatter");
+}
+
+#[tokio::test]
+async fn test_production_line_preview_output_amplification() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let factory: Value = assert_created_response(
+        client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&json!({ "name": "Amplified" }))
+            .send()
+            .await
+            .expect("Failed to create factory"),
+    )
+    .await;
+    let factory_id = factory["id"].as_str().unwrap();
+
+    let preview: Value = assert_json_response(
+        client
+            .post(format!(
+                "{}/api/factories/{}/production-lines/preview",
+                server.base_url, factory_id
+            ))
+            .json(&json!({
+                "name": "Computers",
+                "type": "recipe",
+                "recipe": "Computer",
+                "machine_groups": [
+                    { "number_of_machine": 1, "oc_value": 100.0, "somersloop": 4 },
+                    { "number_of_machine": 1, "oc_value": 100.0, "somersloop": 0 }
+                ]
+            }))
+            .send()
+            .await
+            .expect("Failed to preview production line"),
+    )
+    .await;
+    // A full Manufacturer doubles its 2.5/min next to an unamplified one
+    assert_eq!(preview["output_amplification"], 1.5);
+    assert_eq!(preview["output_rate"][0]["quantity"], 7.5);
+}
+
+#[tokio::test]
+async fn test_game_data_balance() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let balance: Value = assert_json_response(
+        client
+            .get(format!("{}/api/game-data/balance", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get game balance"),
+    )
+    .await;
+    assert_eq!(balance["overclock_exponent"], 1.321928);
+    assert_eq!(balance["pure_multiplier"], 2.0);
+
+    let factory: Value = assert_created_response(
+        client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&json!({ "name": "Modded" }))
+            .send()
+            .await
+            .expect("Failed to create factory"),
+    )
+    .await;
+    let factory_id = factory["id"].as_str().unwrap();
+
+    // Power linear in clock speed; other constants keep the game's value
+    let balance: Value = assert_json_response(
+        client
+            .put(format!("{}/api/game-data/balance", server.base_url))
+            .json(&json!({ "overclock_exponent": 1.0 }))
+            .send()
+            .await
+            .expect("Failed to set game balance"),
+    )
+    .await;
+    assert_eq!(balance["overclock_exponent"], 1.0);
+    assert_eq!(balance["impure_multiplier"], 0.5);
+
+    let factory: Value = assert_created_response(
+        client
+            .post(format!("{}/api/factories/{}/production-lines", server.base_url, factory_id))
+            .json(&json!({
+                "name": "Ingots",
+                "type": "recipe",
+                "recipe": "Iron Ingot",
+                "machine_groups": [
+                    { "number_of_machine": 1, "oc_value": 200.0, "somersloop": 0 }
+                ]
+            }))
+            .send()
+            .await
+            .expect("Failed to create production line"),
+    )
+    .await;
+    assert_eq!(factory["total_power_consumption"], 8.0);
+    assert_eq!(factory["production_lines"][0]["total_power_consumption"], 8.0);
+
+    let invalid = client
+        .put(format!("{}/api/game-data/balance", server.base_url))
+        .json(&json!({ "pure_multiplier": -2.0 }))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(invalid.status(), 400);
+
+    let save: Value = assert_json_response(
+        client
+            .get(format!("{}/api/save", server.base_url))
+            .send()
+            .await
+            .expect("Failed to save"),
+    )
+    .await;
+    let save_file: Value = serde_json::from_str(save["save_data"].as_str().unwrap()).unwrap();
+    assert_eq!(save_file["engine"]["balance"]["overclock_exponent"], 1.0);
+}
+
+#[tokio::test]
+async fn test_logistics_validation_warnings() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let mut factory_ids = Vec::new();
+    for name in ["Mine", "Smelter"] {
+        let factory = assert_created_response(
+            client
+                .post(format!("{}/api/factories", server.base_url))
+                .json(&minimal_factory_request(name))
+                .send()
+                .await
+                .expect("Failed to create factory"),
+        )
+        .await;
+        factory_ids.push(factory["id"].as_str().unwrap().to_string());
+    }
+    let bus = |to_factory: &str| {
+        json!({
+            "from_factory": factory_ids[0],
+            "to_factory": to_factory,
+            "transport_type": "Bus",
+            "bus_name": "Ore Bus",
+            "conveyors": [
+                {
+                    "line_id": "CV-001",
+                    "conveyor_type": "Mk1",
+                    "item": "IronOre",
+                    "quantity_per_min": 90.0
+                }
+            ]
+        })
+    };
+
+    // An overloaded belt is still created, with a warning
+    let line = assert_created_response(
+        client
+            .post(format!("{}/api/logistics", server.base_url))
+            .json(&bus(&factory_ids[1]))
+            .send()
+            .await
+            .expect("Failed to create logistics line"),
+    )
+    .await;
+    let warnings = line["warnings"].as_array().unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].as_str().unwrap().contains("over its Mk1 limit of 60"));
+
+    let fetched: Value = assert_json_response(
+        client
+            .get(format!("{}/api/logistics/{}", server.base_url, line["id"].as_str().unwrap()))
+            .send()
+            .await
+            .expect("Failed to get logistics line"),
+    )
+    .await;
+    assert!(fetched.get("warnings").is_none());
+
+    let generator = assert_created_response(
+        client
+            .post(format!("{}/api/factories/{}/power-generators", server.base_url, factory_ids[1]))
+            .json(&json!({
+                "generator_type": "Fuel",
+                "fuel_type": "Fuel",
+                "groups": [{ "number_of_generators": 1, "clock_speed": 100.0 }]
+            }))
+            .send()
+            .await
+            .expect("Failed to create fuel generator"),
+    )
+    .await;
+    assert!(generator["warnings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|warning| warning == "Fuel is not produced in Smelter; its generators rely on imports"));
+}
+
+#[tokio::test]
+async fn test_factory_tasks() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let factory = assert_created_response(
+        client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&minimal_factory_request("Smelter"))
+            .send()
+            .await
+            .expect("Failed to create factory"),
+    )
+    .await;
+    let factory_url = format!(
+        "{}/api/factories/{}",
+        server.base_url,
+        factory["id"].as_str().unwrap()
+    );
+    let with_line = assert_created_response(
+        client
+            .post(format!("{}/production-lines", factory_url))
+            .json(&json!({
+                "name": "Ingots",
+                "type": "recipe",
+                "recipe": "Iron Ingot",
+                "machine_groups": [
+                    { "number_of_machine": 2, "oc_value": 100.0, "somersloop": 0 }
+                ]
+            }))
+            .send()
+            .await
+            .expect("Failed to create production line"),
+    )
+    .await;
+    let line_id = with_line["production_lines"][0]["ProductionLineRecipe"]["id"].clone();
+
+    let updated = assert_created_response(
+        client
+            .post(format!("{}/tasks", factory_url))
+            .json(&json!({ "text": "Build the smelters", "production_line": line_id }))
+            .send()
+            .await
+            .expect("Failed to create task"),
+    )
+    .await;
+    assert_created_response(
+        client
+            .post(format!("{}/tasks", factory_url))
+            .json(&json!({ "text": "Run the ore belt" }))
+            .send()
+            .await
+            .expect("Failed to create task"),
+    )
+    .await;
+    let task = &updated["tasks"][0];
+    assert_eq!(task["text"], "Build the smelters");
+    assert_eq!(task["done"], false);
+    assert_eq!(task["production_line"], line_id);
+    let task_url = format!("{}/tasks/{}", factory_url, task["id"].as_str().unwrap());
+
+    let updated: Value = assert_json_response(
+        client
+            .put(&task_url)
+            .json(&json!({ "text": "Build the smelters", "done": true, "production_line": line_id }))
+            .send()
+            .await
+            .expect("Failed to update task"),
+    )
+    .await;
+    assert_eq!(updated["tasks"][0]["done"], true);
+
+    let tasks: Value = assert_json_response(
+        client
+            .get(format!("{}/tasks", factory_url))
+            .send()
+            .await
+            .expect("Failed to list tasks"),
+    )
+    .await;
+    assert_eq!(tasks.as_array().unwrap().len(), 2);
+    assert_eq!(tasks[1]["text"], "Run the ore belt");
+
+    let summary: Value = assert_json_response(
+        client
+            .get(format!("{}/api/dashboard/summary", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get dashboard summary"),
+    )
+    .await;
+    assert_eq!(summary["total_tasks"], 2);
+    assert_eq!(summary["open_tasks"], 1);
+
+    // Blank text and lines of other factories are rejected
+    for body in [
+        json!({ "text": "  " }),
+        json!({ "text": "Orphan", "production_line": "00000000-0000-0000-0000-000000000009" }),
+    ] {
+        let invalid = client
+            .post(format!("{}/tasks", factory_url))
+            .json(&body)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(invalid.status().as_u16(), 400);
+    }
+
+    let updated: Value = assert_json_response(
+        client
+            .delete(&task_url)
+            .send()
+            .await
+            .expect("Failed to delete task"),
+    )
+    .await;
+    assert_eq!(updated["tasks"].as_array().unwrap().len(), 1);
+    let missing = client.delete(&task_url).send().await.unwrap();
+    assert_eq!(missing.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn test_audit_log() {
+    let server = create_auth_test_server(
+        AuthConfig::new(vec!["writer-key".to_string()], vec!["reader-key".to_string()]).unwrap(),
+    )
+    .await;
+    let client = create_test_client();
+
+    let factory = assert_created_response(
+        client
+            .post(format!("{}/api/factories", server.base_url))
+            .bearer_auth("writer-key")
+            .json(&minimal_factory_request("Smelter"))
+            .send()
+            .await
+            .expect("Failed to create factory"),
+    )
+    .await;
+    let factory_id = factory["id"].as_str().unwrap();
+    let response = client
+        .delete(format!("{}/api/factories/{}", server.base_url, factory_id))
+        .bearer_auth("writer-key")
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+
+    // Reads and rejected writes leave no trace
+    let response = client
+        .delete(format!("{}/api/factories/{}", server.base_url, factory_id))
+        .bearer_auth("writer-key")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+
+    let entries: Value = assert_json_response(
+        client
+            .get(format!("{}/api/audit", server.base_url))
+            .bearer_auth("reader-key")
+            .send()
+            .await
+            .expect("Failed to get audit log"),
+    )
+    .await;
+    let entries = entries.as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0]["method"], "POST");
+    assert_eq!(entries[0]["path"], "/api/factories");
+    assert_eq!(entries[0]["entity_id"], factory_id);
+    assert_eq!(entries[1]["method"], "DELETE");
+    assert_eq!(entries[1]["entity_id"], factory_id);
+    assert!(entries[1]["revision"].as_u64() > entries[0]["revision"].as_u64());
+    let actor = entries[1]["actor"].as_str().unwrap();
+    assert!(actor.starts_with("key "));
+    assert!(!actor.contains("writer-key"));
+
+    let since = entries[0]["timestamp"].as_str().unwrap();
+    let later: Value = assert_json_response(
+        client
+            .get(format!("{}/api/audit", server.base_url))
+            .query(&[("since", since)])
+            .bearer_auth("reader-key")
+            .send()
+            .await
+            .expect("Failed to get audit log"),
+    )
+    .await;
+    assert_eq!(later.as_array().unwrap().len(), 1);
+    assert_eq!(later[0]["method"], "DELETE");
+}
+
+#[tokio::test]
+async fn test_run_script() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let factory = assert_created_response(
+        client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&minimal_factory_request("Smelter"))
+            .send()
+            .await
+            .expect("Failed to create factory"),
+    )
+    .await;
+    let factory_id = factory["id"].as_str().unwrap();
+    let rename = r#"
+        for id in save.engine.factories.keys() {
+            save.engine.factories[id].name += " (old)";
+        }
+        save.engine.factories.len()
+    "#;
+
+    // Without `apply` the state is left alone
+    let preview: Value = assert_json_response(
+        client
+            .post(format!("{}/api/scripts/run", server.base_url))
+            .json(&json!({ "script": rename }))
+            .send()
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(preview["result"], 1);
+    assert_eq!(preview["applied"], false);
+    assert_eq!(preview["diff"]["factories_modified"].as_array().unwrap().len(), 1);
+
+    let applied: Value = assert_json_response(
+        client
+            .post(format!("{}/api/scripts/run", server.base_url))
+            .json(&json!({ "script": rename, "apply": true }))
+            .send()
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(applied["applied"], true);
+    let factory: Value = assert_json_response(
+        client
+            .get(format!("{}/api/factories/{}", server.base_url, factory_id))
+            .send()
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(factory["name"], "Smelter (old)");
+
+    let response = client
+        .post(format!("{}/api/scripts/run", server.base_url))
+        .json(&json!({ "script": "save.engine.factories[" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+}
+
+#[tokio::test]
+async fn test_factory_templates() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let factory = assert_created_response(
+        client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&minimal_factory_request("Outpost"))
+            .send()
+            .await
+            .expect("Failed to create factory"),
+    )
+    .await;
+    let factory_id = factory["id"].as_str().unwrap();
+    assert_created_response(
+        client
+            .post(format!(
+                "{}/api/factories/{}/raw-inputs",
+                server.base_url, factory_id
+            ))
+            .json(&json!({
+                "extractor_type": "MinerMk1",
+                "item": "IronOre",
+                "purity": "Normal",
+                "count": 1
+            }))
+            .send()
+            .await
+            .unwrap(),
+    )
+    .await;
+
+    let template = assert_created_response(
+        client
+            .post(format!(
+                "{}/api/factories/{}/save-as-template",
+                server.base_url, factory_id
+            ))
+            .json(&json!({ "name": "Iron outpost" }))
+            .send()
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(template["name"], "Iron outpost");
+    assert_eq!(template["raw_input_count"], 1);
+    let template_id = template["id"].as_str().unwrap();
+
+    let templates: Value = assert_json_response(
+        client
+            .get(format!("{}/api/factory-templates", server.base_url))
+            .send()
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(templates.as_array().unwrap().len(), 1);
+
+    let copy = assert_created_response(
+        client
+            .post(format!(
+                "{}/api/factory-templates/{}/instantiate",
+                server.base_url, template_id
+            ))
+            .json(&json!({ "name": "Outpost 2" }))
+            .send()
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(copy["name"], "Outpost 2");
+    assert_ne!(copy["id"], factory["id"]);
+    assert_eq!(copy["raw_inputs"].as_array().unwrap().len(), 1);
+
+    let response = client
+        .delete(format!(
+            "{}/api/factory-templates/{}",
+            server.base_url, template_id
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 204);
+    let response = client
+        .post(format!(
+            "{}/api/factory-templates/{}/instantiate",
+            server.base_url, template_id
+        ))
+        .json(&json!({ "name": "Outpost 3" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+}
+
+#[tokio::test]
+async fn test_copy_and_move_production_line() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let mut factory_ids = Vec::new();
+    for name in ["Source", "Destination"] {
+        let factory: Value = assert_created_response(
+            client
+                .post(format!("{}/api/factories", server.base_url))
+                .json(&minimal_factory_request(name))
+                .send()
+                .await
+                .expect("Failed to create factory"),
+        )
+        .await;
+        factory_ids.push(factory["id"].as_str().unwrap().to_string());
+    }
+    let (source_id, destination_id) = (&factory_ids[0], &factory_ids[1]);
+
+    let source: Value = assert_created_response(
+        client
+            .post(format!("{}/api/factories/{}/production-lines", server.base_url, source_id))
+            .json(&json!({
+                "name": "Ingots",
+                "type": "recipe",
+                "recipe": "Iron Ingot",
+                "machine_groups": [
+                    { "number_of_machine": 2, "oc_value": 100.0, "somersloop": 0 }
+                ]
+            }))
+            .send()
+            .await
+            .expect("Failed to create production line"),
+    )
+    .await;
+    let line_id = source["production_lines"][0]["ProductionLineRecipe"]["id"].as_str().unwrap().to_string();
+
+    let copied: Value = assert_created_response(
+        client
+            .post(format!(
+                "{}/api/factories/{}/production-lines/{}/copy-to/{}",
+                server.base_url, source_id, line_id, destination_id
+            ))
+            .send()
+            .await
+            .expect("Failed to copy production line"),
+    )
+    .await;
+    assert_eq!(copied["production_lines"].as_array().unwrap().len(), 1);
+    assert_ne!(copied["production_lines"][0]["ProductionLineRecipe"]["id"], line_id.as_str());
+
+    let moved: Value = assert_json_response(
+        client
+            .post(format!(
+                "{}/api/factories/{}/production-lines/{}/move-to/{}",
+                server.base_url, source_id, line_id, destination_id
+            ))
+            .send()
+            .await
+            .expect("Failed to move production line"),
+    )
+    .await;
+    assert_eq!(moved["production_lines"].as_array().unwrap().len(), 2);
+
+    // The line is no longer in the source
+    let response = client
+        .post(format!(
+            "{}/api/factories/{}/production-lines/{}/move-to/{}",
+            server.base_url, source_id, line_id, destination_id
+        ))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status(), 404);
+
+    let response = client
+        .post(format!(
+            "{}/api/factories/{}/production-lines/{}/move-to/{}",
+            server.base_url, destination_id, line_id, destination_id
+        ))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status(), 400);
+}
+
+#[tokio::test]
+async fn test_factory_delete_preview() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let mut factory_ids = Vec::new();
+    for name in ["Mine", "Smelter"] {
+        let factory: Value = assert_created_response(
+            client
+                .post(format!("{}/api/factories", server.base_url))
+                .json(&minimal_factory_request(name))
+                .send()
+                .await
+                .expect("Failed to create factory"),
+        )
+        .await;
+        factory_ids.push(factory["id"].as_str().unwrap().to_string());
+    }
+    let (mine_id, smelter_id) = (&factory_ids[0], &factory_ids[1]);
+
+    assert_created_response(
+        client
+            .post(format!("{}/api/factories/{}/production-lines", server.base_url, smelter_id))
+            .json(&json!({
+                "name": "Ingots",
+                "type": "recipe",
+                "recipe": "Iron Ingot",
+                "machine_groups": [
+                    { "number_of_machine": 2, "oc_value": 100.0, "somersloop": 0 }
+                ]
+            }))
+            .send()
+            .await
+            .expect("Failed to create production line"),
+    )
+    .await;
+
+    let logistics: Value = assert_created_response(
+        client
+            .post(format!("{}/api/logistics", server.base_url))
+            .json(&json!({
+                "from_factory": mine_id,
+                "to_factory": smelter_id,
+                "transport_type": "Truck",
+                "item": "IronOre",
+                "quantity_per_min": 60.0,
+                "truck_id": "TRK-1"
+            }))
+            .send()
+            .await
+            .expect("Failed to create logistics line"),
+    )
+    .await;
+
+    let preview: Value = assert_json_response(
+        client
+            .get(format!("{}/api/factories/{}/delete-preview", server.base_url, mine_id))
+            .send()
+            .await
+            .expect("Failed to preview delete"),
+    )
+    .await;
+    assert_eq!(preview["name"], "Mine");
+    assert_eq!(preview["logistics"][0]["id"], logistics["id"]);
+    assert_eq!(preview["deficits"][0]["factory_id"], smelter_id.as_str());
+    assert_eq!(preview["deficits"][0]["item"], "IronOre");
+    assert_eq!(preview["deficits"][0]["balance_after"], -60.0);
+
+    let response = client
+        .get(format!("{}/api/factories/{}/delete-preview", server.base_url, Uuid::new_v4()))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status(), 404);
+}
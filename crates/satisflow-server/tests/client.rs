@@ -0,0 +1,334 @@
+//! Integration tests for the `satisflow-client` crate:
+//! - Every client method runs against a live server, so any drift between the
+//!   client DTOs and the handlers fails deserialization here.
+//! - Errors: the server's `{error,status}` body surfaces as `ClientError::Api`.
+
+mod common;
+
+use common::create_test_server;
+use satisflow_client::{
+    types::{
+        CreateFactory, CreateGoal, CreateLogistics, CreatePowerGenerator, CreateProductionLine,
+        CreateStorageBuffer, CreateTask, GeneratorGroupRequest, LogisticsTransport,
+        MachineGroupRequest, TrainWagonRequest, UpdateFactory,
+    },
+    SatisflowClient,
+};
+use satisflow_engine::{
+    models::{FactoryId, GeneratorType, Item, TrainSchedule},
+    CsvSection,
+};
+
+#[tokio::test]
+async fn client_covers_factory_and_dashboard_endpoints() {
+    let server = create_test_server().await;
+    let client = SatisflowClient::new(format!("{}/", server.base_url));
+    assert_eq!(client.base_url(), server.base_url);
+
+    client.health().await.unwrap();
+
+    let smelter = client
+        .create_factory(
+            &CreateFactory::new("Smelter")
+                .with_description("Iron smelting")
+                .with_tag("North"),
+        )
+        .await
+        .unwrap();
+    assert_eq!(smelter.name, "Smelter");
+    assert_eq!(smelter.description.as_deref(), Some("Iron smelting"));
+    assert_eq!(smelter.tags, vec!["North".to_string()]);
+
+    let assembly = client
+        .create_factory(&CreateFactory::new("Assembly").with_notes("Needs ingots"))
+        .await
+        .unwrap();
+
+    let renamed = client
+        .update_factory(
+            smelter.id,
+            &UpdateFactory {
+                name: Some("Iron Smelter".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(renamed.name, "Iron Smelter");
+    assert_eq!(renamed.description.as_deref(), Some("Iron smelting"));
+
+    let with_line = client
+        .create_production_line(
+            smelter.id,
+            &CreateProductionLine::recipe(
+                "Ingots",
+                "Iron Ingot",
+                vec![MachineGroupRequest::new(2, 100.0, 0)],
+            ),
+        )
+        .await
+        .unwrap();
+    assert_eq!(with_line.production_lines.len(), 1);
+    assert_eq!(with_line.production_lines[0].total_machines, 2);
+    assert_eq!(with_line.total_power_consumption, 8.0);
+
+    let with_buffer = client
+        .create_storage_buffer(
+            smelter.id,
+            &CreateStorageBuffer {
+                item: Item::IronIngot,
+                capacity_stacks: 48,
+                stored: 1200.0,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        with_buffer.storage_buffers[0].minutes_until_full,
+        Some(60.0)
+    );
+
+    let with_task = client
+        .create_task(
+            smelter.id,
+            &CreateTask {
+                text: "Build the smelters".to_string(),
+                done: false,
+                production_line: Some(with_line.production_lines[0].production_line.id()),
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(with_task.tasks.len(), 1);
+    assert_eq!(with_task.tasks[0].text, "Build the smelters");
+
+    let with_generator = client
+        .create_power_generator(
+            smelter.id,
+            &CreatePowerGenerator {
+                generator_type: GeneratorType::Coal,
+                fuel_type: Some(Item::Coal),
+                purity: None,
+                groups: vec![GeneratorGroupRequest {
+                    number_of_generators: 1,
+                    clock_speed: 100.0,
+                }],
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(with_generator.power_generators.len(), 1);
+    assert_eq!(with_generator.total_power_generation, 75.0);
+    assert!(!with_generator.fuel_shortfalls.is_empty());
+
+    let generator_id = with_generator.power_generators[0].power_generator.id;
+    let disabled = client
+        .set_power_generator_enabled(smelter.id, generator_id, false)
+        .await
+        .unwrap();
+    assert!(!disabled.power_generators[0].power_generator.enabled);
+    assert_eq!(disabled.total_power_generation, 0.0);
+    client
+        .set_power_generator_enabled(smelter.id, generator_id, true)
+        .await
+        .unwrap();
+
+    let line_id = with_line.production_lines[0].production_line.id();
+    let disabled = client
+        .set_production_line_enabled(smelter.id, line_id, false)
+        .await
+        .unwrap();
+    assert!(!disabled.production_lines[0].production_line.is_enabled());
+    assert_eq!(disabled.total_power_consumption, 0.0);
+    client
+        .set_production_line_enabled(smelter.id, line_id, true)
+        .await
+        .unwrap();
+
+    let logistics = client
+        .create_logistics(&CreateLogistics {
+            from_factory: smelter.id,
+            to_factory: assembly.id,
+            transport: LogisticsTransport::Truck {
+                item: Item::IronIngot,
+                quantity_per_min: 30.0,
+                truck_id: None,
+            },
+        })
+        .await
+        .unwrap();
+    assert_eq!(logistics.transport_type, "Truck");
+    assert_eq!(logistics.total_quantity_per_min, 30.0);
+    assert_eq!(
+        client.get_logistics(logistics.id).await.unwrap().id,
+        logistics.id
+    );
+    assert_eq!(client.list_logistics().await.unwrap().len(), 1);
+
+    let train = client
+        .create_logistics(&CreateLogistics {
+            from_factory: smelter.id,
+            to_factory: assembly.id,
+            transport: LogisticsTransport::Train {
+                train_name: Some("Ingot Express".into()),
+                wagons: vec![TrainWagonRequest {
+                    wagon_id: None,
+                    wagon_type: "Cargo".into(),
+                    item: Item::IronIngot,
+                    quantity_per_min: 1000.0,
+                }],
+            },
+        })
+        .await
+        .unwrap();
+    // 32 stacks of 100 ingots every 5 minutes
+    let schedule = TrainSchedule::new(3000.0).with_station_time(30.0);
+    let throughput = client
+        .train_throughput(train.id, Some(&schedule))
+        .await
+        .unwrap();
+    assert_eq!(throughput.wagons[0].max_per_min, 640.0);
+    assert!(throughput.is_over_capacity());
+    // Neither factory is on the map, so the distance must be given
+    assert!(client.train_throughput(train.id, None).await.is_err());
+    client.delete_logistics(train.id).await.unwrap();
+
+    let factories = client.list_factories().await.unwrap();
+    assert_eq!(factories.len(), 2);
+    let north = client.list_factories_with_tag("north").await.unwrap();
+    assert_eq!(north.len(), 1);
+    assert_eq!(north[0].id, smelter.id);
+    let groups = client.tag_groups().await.unwrap();
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[0].tag.as_deref(), Some("North"));
+    assert_eq!(groups[1].factory_ids, vec![assembly.id]);
+    let assembly = client.get_factory(assembly.id).await.unwrap();
+    assert!(assembly
+        .items
+        .iter()
+        .any(|item| item.item == Item::IronIngot && item.quantity == 30.0));
+
+    let goal = client
+        .create_goal(&CreateGoal::for_factory(Item::IronIngot, 120.0, smelter.id))
+        .await
+        .unwrap();
+    assert_eq!(goal.actual_rate, 60.0);
+    assert_eq!(goal.completion_percent, 50.0);
+    assert!(!goal.is_met);
+    let global_goal = client
+        .create_goal(&CreateGoal::global(Item::IronIngot, 60.0))
+        .await
+        .unwrap();
+    assert!(global_goal.is_met);
+    let goals = client.list_goals().await.unwrap();
+    assert_eq!(goals.len(), 2);
+    assert_eq!(goals[0].goal.id, goal.goal.id);
+    client.delete_goal(global_goal.goal.id).await.unwrap();
+
+    let summary = client.dashboard_summary().await.unwrap();
+    assert_eq!(summary.total_factories, 2);
+    assert_eq!(summary.total_production_lines, 1);
+    assert_eq!(summary.total_logistics_lines, 1);
+    assert_eq!(summary.net_power, 67.0);
+    assert_eq!(summary.total_tasks, 1);
+    assert_eq!(summary.open_tasks, 1);
+    assert_eq!(summary.goal_progress.len(), 1);
+    assert_eq!(summary.goal_progress[0].goal_id, goal.goal.id);
+
+    let balances = client.item_balances().await.unwrap();
+    assert!(balances
+        .iter()
+        .any(|balance| balance.item == Item::IronIngot));
+
+    let power = client.power_statistics().await.unwrap();
+    assert_eq!(power.total_generation, 75.0);
+    assert_eq!(power.factory_stats.len(), 2);
+    assert!(!power.warnings.is_empty());
+
+    let report_card = client.report_card().await.unwrap();
+    assert_eq!(report_card.report.factory_count, 2);
+    assert!(!report_card.summary.is_empty());
+
+    let inventory = client.resource_inventory().await.unwrap();
+    assert_eq!(inventory.total_somersloops, 0);
+    assert_eq!(inventory.total_power_shards, 0);
+
+    let history = client.metrics_history(Some(Item::IronIngot)).await.unwrap();
+    let latest = history.last().unwrap();
+    assert_eq!(latest.power_balance, 67.0);
+    assert_eq!(latest.items.get(&Item::IronIngot), Some(&60.0));
+    assert_eq!(latest.items.len(), 1);
+    assert!(history[0].revision < latest.revision);
+
+    let flows = client.flows().await.unwrap();
+    assert_eq!(flows.nodes.len(), 2);
+    assert_eq!(flows.links[0].value, 30.0);
+    let hits = client.search("iron smelter", Some(1)).await.unwrap();
+    assert_eq!(hits[0].name, "Iron Smelter");
+
+    let logistics_csv = client.export_csv(CsvSection::Logistics).await.unwrap();
+    assert_eq!(logistics_csv.lines().count(), 2);
+    let report = client.markdown_report(Some(smelter.id)).await.unwrap();
+    assert!(report.starts_with("# Iron Smelter\n"));
+    assert!(client
+        .markdown_report(None)
+        .await
+        .unwrap()
+        .starts_with("# Factory report"));
+
+    let changes = client.recent_changes(Some(2)).await.unwrap();
+    assert_eq!(changes.len(), 2);
+    assert!(client.recent_changes(None).await.unwrap().len() >= 4);
+
+    client.delete_logistics(logistics.id).await.unwrap();
+    client.delete_factory(assembly.id).await.unwrap();
+    assert_eq!(client.list_factories().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn client_round_trips_saves() {
+    let server = create_test_server().await;
+    let client = SatisflowClient::new(&server.base_url);
+
+    client
+        .create_factory(&CreateFactory::new("Saved Factory"))
+        .await
+        .unwrap();
+
+    let save = client.save(None).await.unwrap();
+    assert_eq!(save.summary.factory_count, 1);
+    assert!(save.downgrade_report.is_none());
+
+    let downgraded = client.save(Some("0.1.0")).await.unwrap();
+    assert!(downgraded.downgrade_report.is_some());
+
+    assert!(!client.reset().await.unwrap().is_empty());
+    assert!(client.list_factories().await.unwrap().is_empty());
+
+    let loaded = client.load(&save.save_data).await.unwrap();
+    assert_eq!(loaded.summary.factory_count, 1);
+    assert_eq!(
+        client.list_factories().await.unwrap()[0].name,
+        "Saved Factory"
+    );
+}
+
+#[tokio::test]
+async fn client_surfaces_api_errors() {
+    let server = create_test_server().await;
+    let client = SatisflowClient::new(&server.base_url);
+
+    let error = client.get_factory(FactoryId::new_v4()).await.unwrap_err();
+    assert!(error.is_not_found());
+    assert_eq!(error.status(), Some(404));
+
+    let error = client
+        .create_factory(&CreateFactory::new("   "))
+        .await
+        .unwrap_err();
+    assert_eq!(error.status(), Some(400));
+    assert!(!error.to_string().is_empty());
+
+    let error = client.recent_changes(Some(0)).await.unwrap_err();
+    assert_eq!(error.status(), Some(400));
+}
@@ -5,48 +5,80 @@
 //! whitespace validation for factories, cascade behaviour across logistics, and
 //! payload builders for each transport type (truck, bus, train) so tests can
 //! focus on behaviour instead of JSON boilerplate.
-use axum::Router;
+use axum::{middleware, Router};
 use satisflow_server::{
-    handlers::{
-        blueprint, blueprint_templates, dashboard, factory, game_data, logistics, save_load,
-    },
+    auth::{self, AuthConfig},
+    demo::{self, DemoConfig, RateLimiter},
+    handlers::{dev, engine_routes, scenarios, workspaces},
+    revision,
     state::AppState,
 };
 use std::net::SocketAddr;
 use tokio::net::TcpListener;
 use tower::ServiceBuilder;
 use tower_http::{
+    compression::CompressionLayer,
     cors::{Any, CorsLayer},
     trace::TraceLayer,
 };
 
 /// Minimal wrapper around the spawned Axum server.
 pub struct TestServer {
-    pub addr: SocketAddr,
     pub base_url: String,
 }
 
 /// Create a test server with the full routing tree used by integration tests.
 pub async fn create_test_server() -> TestServer {
-    // Create application state
     let state = AppState::new();
+    spawn_server(api_router(&state), state).await
+}
+
+/// Create a demo-mode test server, already reset to the configured example.
+#[allow(dead_code)]
+pub async fn create_demo_test_server(config: DemoConfig) -> TestServer {
+    let state = AppState::demo();
+    demo::reset_demo_state(&state, &config).await.unwrap();
+    demo::spawn_reset_task(state.clone(), config.clone());
+
+    let api = api_router(&state).layer(middleware::from_fn_with_state(
+        RateLimiter::new(config.requests_per_minute),
+        demo::rate_limit,
+    ));
+    spawn_server(api, state).await
+}
 
-    // Build the application router (same as main.rs)
-    let app = Router::new()
-        // API routes
-        .nest("/api/factories", factory::routes())
-        .nest("/api/logistics", logistics::routes())
-        .nest("/api/dashboard", dashboard::routes())
-        .nest("/api/game-data", game_data::routes())
-        .nest("/api", save_load::routes())
-        .nest("/api", blueprint::routes())
-        .nest("/api", blueprint_templates::routes())
+/// Create a test server that requires API keys.
+#[allow(dead_code)]
+pub async fn create_auth_test_server(config: AuthConfig) -> TestServer {
+    let state = AppState::new();
+    let api = api_router(&state).layer(middleware::from_fn_with_state(
+        config,
+        auth::require_api_key,
+    ));
+    spawn_server(api, state).await
+}
+
+/// API routes (same as main.rs)
+fn api_router(state: &AppState) -> Router<AppState> {
+    engine_routes()
+        .nest("/api/dev", dev::routes())
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            revision::check_revision,
+        ))
+        .nest("/api/workspaces", workspaces::routes())
+        .nest("/api/scenarios", scenarios::routes())
+}
+
+async fn spawn_server(api: Router<AppState>, state: AppState) -> TestServer {
+    let app = api
         // Health check
         .route("/health", axum::routing::get(|| async { "OK" }))
         // Global middleware
         .layer(
             ServiceBuilder::new()
                 .layer(TraceLayer::new_for_http())
+                .layer(CompressionLayer::new())
                 .layer(
                     CorsLayer::new()
                         .allow_origin(Any)
@@ -54,6 +86,7 @@ pub async fn create_test_server() -> TestServer {
                             axum::http::Method::GET,
                             axum::http::Method::POST,
                             axum::http::Method::PUT,
+                            axum::http::Method::PATCH,
                             axum::http::Method::DELETE,
                         ])
                         .allow_headers(Any),
@@ -68,13 +101,19 @@ pub async fn create_test_server() -> TestServer {
 
     // Start server in background
     tokio::spawn(async move {
-        axum::serve(listener, app).await.unwrap();
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .unwrap();
     });
 
-    TestServer { addr, base_url }
+    TestServer { base_url }
 }
 
 /// Construct a reqwest client configured for integration testing.
+#[allow(dead_code)]
 pub fn create_test_client() -> reqwest::Client {
     reqwest::Client::builder()
         .redirect(reqwest::redirect::Policy::none())
@@ -88,6 +127,7 @@ pub mod test_data {
     use uuid::Uuid;
 
     /// Fully populated factory creation payload for metadata-based flows.
+    #[allow(dead_code)]
     pub fn create_factory_request() -> serde_json::Value {
         json!({
             "name": "Test Factory",
@@ -97,11 +137,13 @@ pub mod test_data {
     }
 
     /// Minimal factory payload exercising the "name only" happy path.
+    #[allow(dead_code)]
     pub fn minimal_factory_request(name: &str) -> serde_json::Value {
         json!({ "name": name })
     }
 
     /// Standard factory update payload including optional fields.
+    #[allow(dead_code)]
     pub fn update_factory_request() -> serde_json::Value {
         json!({
             "name": "Updated Factory",
@@ -111,11 +153,13 @@ pub mod test_data {
     }
 
     /// Update payload dedicated to manipulating the notes field.
+    #[allow(dead_code)]
     pub fn update_factory_notes_request(notes: &str) -> serde_json::Value {
         json!({ "notes": notes })
     }
 
     /// Negative factory payload that forces blank-name validation.
+    #[allow(dead_code)]
     pub fn invalid_factory_request() -> serde_json::Value {
         json!({
             "name": "", // Empty name should be invalid
@@ -124,6 +168,7 @@ pub mod test_data {
     }
 
     /// Factory payload with user-supplied notes to test trimming behaviour.
+    #[allow(dead_code)]
     pub fn factory_with_notes_request(name: &str, notes: &str) -> serde_json::Value {
         json!({
             "name": name,
@@ -132,11 +177,12 @@ pub mod test_data {
     }
 
     /// Truck payload using generated identifiers to validate defaults.
+    #[allow(dead_code)]
     pub fn truck_logistics_request(
         from_factory: Uuid,
         to_factory: Uuid,
         item: &str,
-        quantity_per_min: f32,
+        quantity_per_min: f64,
     ) -> serde_json::Value {
         json!({
             "from_factory": from_factory,
@@ -148,11 +194,12 @@ pub mod test_data {
     }
 
     /// Truck payload providing an explicit identifier to validate parsing.
+    #[allow(dead_code)]
     pub fn truck_logistics_with_id_request(
         from_factory: Uuid,
         to_factory: Uuid,
         item: &str,
-        quantity_per_min: f32,
+        quantity_per_min: f64,
         truck_id: &str,
     ) -> serde_json::Value {
         json!({
@@ -166,6 +213,7 @@ pub mod test_data {
     }
 
     /// Bus payload with no segments, used for validation failure.
+    #[allow(dead_code)]
     pub fn empty_bus_logistics_request(from_factory: Uuid, to_factory: Uuid) -> serde_json::Value {
         json!({
             "from_factory": from_factory,
@@ -177,6 +225,7 @@ pub mod test_data {
     }
 
     /// Mixed conveyor/pipeline bus payload for aggregation assertions.
+    #[allow(dead_code)]
     pub fn mixed_bus_logistics_request(from_factory: Uuid, to_factory: Uuid) -> serde_json::Value {
         json!({
             "from_factory": from_factory,
@@ -202,7 +251,70 @@ pub mod test_data {
         })
     }
 
+    /// Bus climbing to an upper floor: a belt feeding two lifts, the second
+    /// one faster than its tier allows.
+    #[allow(dead_code)]
+    pub fn bus_with_lifts_request(from_factory: Uuid, to_factory: Uuid) -> serde_json::Value {
+        json!({
+            "from_factory": from_factory,
+            "to_factory": to_factory,
+            "transport_type": "Bus",
+            "bus_name": "Riser",
+            "conveyors": [
+                {
+                    "conveyor_type": "Mk6",
+                    "item": "Coal",
+                    "quantity_per_min": 1200.0
+                }
+            ],
+            "lifts": [
+                {
+                    "lift_id": "LF-007",
+                    "lift_type": "Mk6",
+                    "item": "Coal",
+                    "quantity_per_min": 1200.0
+                },
+                {
+                    "lift_type": "Mk5",
+                    "item": "Coal",
+                    "quantity_per_min": 900.0
+                }
+            ]
+        })
+    }
+
+    /// Bus pumping water up a tower; the second pipeline lacks the pumps for its rise.
+    #[allow(dead_code)]
+    pub fn bus_with_pumped_pipelines_request(
+        from_factory: Uuid,
+        to_factory: Uuid,
+    ) -> serde_json::Value {
+        json!({
+            "from_factory": from_factory,
+            "to_factory": to_factory,
+            "transport_type": "Bus",
+            "bus_name": "Water Tower",
+            "pipelines": [
+                {
+                    "pipeline_type": "Mk2",
+                    "item": "Water",
+                    "quantity_per_min": 600.0,
+                    "pumps_mk2": 1,
+                    "vertical_rise": 70.0
+                },
+                {
+                    "pipeline_type": "Mk1",
+                    "item": "Water",
+                    "quantity_per_min": 300.0,
+                    "pumps_mk1": 1,
+                    "vertical_rise": 45.0
+                }
+            ]
+        })
+    }
+
     /// Bus payload containing whitespace-only name to trigger defaulting.
+    #[allow(dead_code)]
     pub fn bus_with_whitespace_name_request(
         from_factory: Uuid,
         to_factory: Uuid,
@@ -223,6 +335,7 @@ pub mod test_data {
     }
 
     /// Bus payload containing zero-throughput pipeline to trigger validation.
+    #[allow(dead_code)]
     pub fn bus_with_zero_pipeline_request(
         from_factory: Uuid,
         to_factory: Uuid,
@@ -242,12 +355,13 @@ pub mod test_data {
     }
 
     /// Train payload helper for type-specific wagon construction.
+    #[allow(dead_code)]
     pub fn train_logistics_request(
         from_factory: Uuid,
         to_factory: Uuid,
         wagon_type: &str,
         item: &str,
-        quantity_per_min: f32,
+        quantity_per_min: f64,
     ) -> serde_json::Value {
         json!({
             "from_factory": from_factory,
@@ -266,6 +380,7 @@ pub mod test_data {
     }
 
     /// Train payload with no wagons used to assert minimum elements.
+    #[allow(dead_code)]
     pub fn train_empty_wagons_request(from_factory: Uuid, to_factory: Uuid) -> serde_json::Value {
         json!({
             "from_factory": from_factory,
@@ -277,6 +392,7 @@ pub mod test_data {
     }
 
     /// Negative logistics payload pointing to unknown factories and zero flow.
+    #[allow(dead_code)]
     pub fn invalid_logistics_request() -> serde_json::Value {
         json!({
             "from_factory": Uuid::new_v4(), // Non-existent factory
@@ -286,268 +402,124 @@ pub mod test_data {
             "quantity_per_min": 0.0
         })
     }
+}
 
-    /// Drone transport payload for testing aerial logistics.
-    pub fn drone_logistics_request(
-        from_factory: Uuid,
-        to_factory: Uuid,
-        item: &str,
-        quantity_per_min: f32,
-    ) -> serde_json::Value {
-        json!({
-            "from_factory": from_factory,
-            "to_factory": to_factory,
-            "transport_type": "Drone",
-            "item": item,
-            "quantity_per_min": quantity_per_min
-        })
-    }
-
-    /// Drone transport with explicit identifier.
-    pub fn drone_logistics_with_id_request(
-        from_factory: Uuid,
-        to_factory: Uuid,
-        item: &str,
-        quantity_per_min: f32,
-        drone_id: &str,
-    ) -> serde_json::Value {
-        json!({
-            "from_factory": from_factory,
-            "to_factory": to_factory,
-            "transport_type": "Drone",
-            "item": item,
-            "quantity_per_min": quantity_per_min,
-            "drone_id": drone_id
-        })
-    }
-
-    /// Blueprint template creation payload.
-    pub fn create_blueprint_template_request() -> serde_json::Value {
-        json!({
-            "name": "Test Blueprint Template",
-            "description": "A test blueprint for integration testing",
-            "production_lines": [
-                {
-                    "name": "Iron Ingot Production",
-                    "description": "Basic iron ingot production",
-                    "recipe": "IronIngot",
-                    "machine_groups": [
-                        {
-                            "number_of_machine": 4,
-                            "oc_value": 100.0,
-                            "somersloop": 0
-                        }
-                    ]
-                }
-            ]
-        })
-    }
-
-    /// Complex blueprint template with multiple production lines.
-    pub fn complex_blueprint_template_request() -> serde_json::Value {
-        json!({
-            "name": "Motor Production Complex",
-            "description": "Complete motor production from raw materials",
-            "production_lines": [
-                {
-                    "name": "Iron Ingot Production",
-                    "description": "Smelts iron ore into ingots",
-                    "recipe": "IronIngot",
-                    "machine_groups": [
-                        {
-                            "number_of_machine": 6,
-                            "oc_value": 100.0,
-                            "somersloop": 0
-                        }
-                    ]
-                },
-                {
-                    "name": "Screw Production",
-                    "description": "Creates screws from iron rods",
-                    "recipe": "Screw",
-                    "machine_groups": [
-                        {
-                            "number_of_machine": 3,
-                            "oc_value": 150.0,
-                            "somersloop": 0
-                        }
-                    ]
-                },
-                {
-                    "name": "Motor Assembly",
-                    "description": "Assembles motors from components",
-                    "recipe": "Motor",
-                    "machine_groups": [
-                        {
-                            "number_of_machine": 2,
-                            "oc_value": 100.0,
-                            "somersloop": 1
-                        }
-                    ]
-                }
-            ]
-        })
-    }
-
-    /// Invalid blueprint template (empty name).
-    pub fn invalid_blueprint_template_request() -> serde_json::Value {
-        json!({
-            "name": "",
-            "description": "Invalid template with empty name",
-            "production_lines": []
-        })
-    }
-
-    /// Blueprint template with invalid overclock value.
-    pub fn blueprint_with_invalid_oc_request() -> serde_json::Value {
-        json!({
-            "name": "Invalid OC Blueprint",
-            "production_lines": [
-                {
-                    "name": "Overclocked Line",
-                    "recipe": "IronIngot",
-                    "machine_groups": [
-                        {
-                            "number_of_machine": 1,
-                            "oc_value": 300.0,
-                            "somersloop": 0
-                        }
-                    ]
-                }
-            ]
-        })
-    }
-
-    /// Blueprint template with zero machines.
-    pub fn blueprint_with_zero_machines_request() -> serde_json::Value {
-        json!({
-            "name": "Zero Machines Blueprint",
-            "production_lines": [
-                {
-                    "name": "Empty Line",
-                    "recipe": "IronIngot",
-                    "machine_groups": [
-                        {
-                            "number_of_machine": 0,
-                            "oc_value": 100.0,
-                            "somersloop": 0
-                        }
-                    ]
-                }
-            ]
-        })
-    }
-
-    /// Blueprint template with invalid recipe name.
-    pub fn blueprint_with_invalid_recipe_request() -> serde_json::Value {
-        json!({
-            "name": "Invalid Recipe Blueprint",
-            "production_lines": [
-                {
-                    "name": "Invalid Line",
-                    "recipe": "NonExistentRecipe12345",
-                    "machine_groups": [
-                        {
-                            "number_of_machine": 1,
-                            "oc_value": 100.0,
-                            "somersloop": 0
-                        }
-                    ]
-                }
-            ]
-        })
-    }
-
-    /// Valid blueprint JSON string for import testing.
-    pub fn valid_blueprint_json() -> &'static str {
-        r#"{
-            "id": "550e8400-e29b-41d4-a716-446655440000",
-            "name": "Test Blueprint",
-            "description": "Blueprint for testing import",
-            "production_lines": [
-                {
-                    "id": "550e8400-e29b-41d4-a716-446655440001",
-                    "name": "Iron Ingot Line",
-                    "description": "Basic iron ingot production",
-                    "recipe": "IronIngot",
-                    "machine_groups": [
-                        {
-                            "number_of_machine": 4,
-                            "oc_value": 100.0,
-                            "somersloop": 0
-                        }
-                    ]
-                }
-            ]
-        }"#
-    }
-
-    /// Invalid blueprint JSON string.
-    pub fn invalid_blueprint_json() -> &'static str {
-        r#"{ invalid json }"#
-    }
-
-    /// Blueprint JSON with invalid overclock.
-    pub fn blueprint_json_with_invalid_oc() -> &'static str {
-        r#"{
-            "id": "550e8400-e29b-41d4-a716-446655440000",
-            "name": "Invalid OC Blueprint",
-            "production_lines": [
-                {
-                    "id": "550e8400-e29b-41d4-a716-446655440001",
-                    "name": "Bad Line",
-                    "recipe": "IronIngot",
-                    "machine_groups": [
-                        {
-                            "number_of_machine": 1,
-                            "oc_value": 300.0,
-                            "somersloop": 0
-                        }
-                    ]
-                }
-            ]
-        }"#
-    }
-
-    /// Save data request for testing save/load.
-    pub fn save_data_request() -> serde_json::Value {
-        json!({
-            "save_data": r#"{
-                "version": "0.1.0",
-                "created_at": "2025-01-01T00:00:00Z",
-                "last_modified": "2025-01-01T00:00:00Z",
-                "game_version": "1.2",
-                "engine": {
-                    "factories": {},
-                    "logistics_lines": {},
-                    "blueprint_templates": {}
-                }
-            }"#
-        })
-    }
-
-    /// Invalid save data request (malformed JSON).
-    pub fn invalid_save_data_request() -> serde_json::Value {
-        json!({
-            "save_data": "{ invalid json }"
-        })
-    }
-
-    /// Future version save data (should fail to load).
-    pub fn future_version_save_data_request() -> serde_json::Value {
-        json!({
-            "save_data": r#"{
-                "version": "999.0.0",
-                "created_at": "2025-01-01T00:00:00Z",
-                "last_modified": "2025-01-01T00:00:00Z",
-                "game_version": "1.2",
-                "engine": {
-                    "factories": {},
-                    "logistics_lines": {},
-                    "blueprint_templates": {}
-                }
-            }"#
-        })
+/// Satisfactory game saves for the import endpoint
+pub mod game_saves {
+    use flate2::{write::ZlibEncoder, Compression};
+    use std::io::Write;
+
+    #[allow(dead_code)]
+    fn string(out: &mut Vec<u8>, text: &str) {
+        out.extend((text.len() as i32 + 1).to_le_bytes());
+        out.extend(text.as_bytes());
+        out.push(0);
+    }
+
+    /// A Satisfactory 1.0 save holding a smelter making iron ingots at 50%,
+    /// wired to power circuit 7
+    #[allow(dead_code)]
+    pub fn smelter_save() -> Vec<u8> {
+        let smelter = "Persistent_Level:PersistentLevel.Build_SmelterMk1_C_1";
+        let mut headers = Vec::new();
+        headers.extend(2i32.to_le_bytes());
+        headers.extend(0i32.to_le_bytes());
+        string(
+            &mut headers,
+            "/Script/FactoryGame.FGPowerConnectionComponent",
+        );
+        string(&mut headers, "Persistent_Level");
+        string(&mut headers, &format!("{}.PowerInput", smelter));
+        string(&mut headers, smelter);
+        headers.extend(1i32.to_le_bytes());
+        string(
+            &mut headers,
+            "/Game/FactoryGame/Buildable/Factory/SmelterMk1/Build_SmelterMk1.Build_SmelterMk1_C",
+        );
+        string(&mut headers, "Persistent_Level");
+        string(&mut headers, smelter);
+        headers.extend(1i32.to_le_bytes());
+        headers.extend([0u8; 40]);
+        headers.extend(0i32.to_le_bytes());
+
+        let mut connection = Vec::new();
+        string(&mut connection, "mCircuitID");
+        string(&mut connection, "IntProperty");
+        connection.extend(4i32.to_le_bytes());
+        connection.extend(0i32.to_le_bytes());
+        connection.push(0);
+        connection.extend(7i32.to_le_bytes());
+        string(&mut connection, "None");
+
+        let mut building = Vec::new();
+        string(&mut building, "");
+        string(&mut building, "");
+        building.extend(0i32.to_le_bytes());
+        let mut recipe = Vec::new();
+        string(&mut recipe, "");
+        string(
+            &mut recipe,
+            "/Game/FactoryGame/Recipes/Smelter/Recipe_IngotIron.Recipe_IngotIron_C",
+        );
+        string(&mut building, "mCurrentRecipe");
+        string(&mut building, "ObjectProperty");
+        building.extend((recipe.len() as i32).to_le_bytes());
+        building.extend(0i32.to_le_bytes());
+        building.push(0);
+        building.extend(recipe);
+        string(&mut building, "mCurrentPotential");
+        string(&mut building, "FloatProperty");
+        building.extend(4i32.to_le_bytes());
+        building.extend(0i32.to_le_bytes());
+        building.push(0);
+        building.extend(0.5f32.to_le_bytes());
+        string(&mut building, "None");
+
+        let mut objects = Vec::new();
+        objects.extend(2i32.to_le_bytes());
+        for data in [connection, building] {
+            objects.extend(46i32.to_le_bytes());
+            objects.extend(0i32.to_le_bytes());
+            objects.extend((data.len() as i32).to_le_bytes());
+            objects.extend(data);
+        }
+
+        // No partition grids or sublevels, then the persistent level
+        let mut world = vec![0u8; 8];
+        world.extend((headers.len() as i64).to_le_bytes());
+        world.extend(headers);
+        world.extend((objects.len() as i64).to_le_bytes());
+        world.extend(objects);
+        world.extend(0i32.to_le_bytes());
+        let mut body = (world.len() as i64).to_le_bytes().to_vec();
+        body.extend(world);
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&body).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let mut save = Vec::new();
+        for value in [14i32, 46, 365_306] {
+            save.extend(value.to_le_bytes());
+        }
+        for text in ["Iron Valley", "Persistent_Level", "", "Iron Valley"] {
+            string(&mut save, text);
+        }
+        save.extend(3_600i32.to_le_bytes());
+        save.extend(621_355_968_000_000_000i64.to_le_bytes());
+        save.push(0);
+        save.extend(40i32.to_le_bytes());
+        string(&mut save, "");
+        save.extend(0i32.to_le_bytes());
+        save.extend(0x9E2A_83C1u32.to_le_bytes());
+        save.extend(0x2222_2222u32.to_le_bytes());
+        save.extend(131_072i64.to_le_bytes());
+        save.push(3);
+        for _ in 0..2 {
+            save.extend((compressed.len() as i64).to_le_bytes());
+            save.extend((body.len() as i64).to_le_bytes());
+        }
+        save.extend(compressed);
+        save
     }
 }
 
@@ -558,6 +530,7 @@ pub mod assertions {
 
     /// Assert that a response carries the expected status code and include an
     /// informative panic message when it does not.
+    #[allow(dead_code)]
     pub async fn assert_status(response: Response, expected_status: u16) {
         assert_eq!(
             response.status().as_u16(),
@@ -569,6 +542,7 @@ pub mod assertions {
     }
 
     /// Obtain a 200 OK JSON payload, panicking otherwise.
+    #[allow(dead_code)]
     pub async fn assert_json_response(response: Response) -> Value {
         let status = response.status();
         assert_eq!(status.as_u16(), 200);
@@ -576,6 +550,7 @@ pub mod assertions {
     }
 
     /// Obtain a 201 Created JSON payload, panicking otherwise.
+    #[allow(dead_code)]
     pub async fn assert_created_response(response: Response) -> Value {
         let status = response.status();
         assert_eq!(status.as_u16(), 201);
@@ -583,22 +558,26 @@ pub mod assertions {
     }
 
     /// Convenience helper asserting a 204 No Content status.
+    #[allow(dead_code)]
     pub async fn assert_no_content(response: Response) {
         assert_status(response, 204).await;
     }
 
     /// Convenience helper asserting a 404 Not Found status.
+    #[allow(dead_code)]
     pub async fn assert_not_found(response: Response) {
         assert_status(response, 404).await;
     }
 
     /// Convenience helper asserting a 400 Bad Request status.
+    #[allow(dead_code)]
     pub async fn assert_bad_request(response: Response) {
         assert_status(response, 400).await;
     }
 
     /// Convenience helper asserting either 400 Bad Request or 422 Unprocessable Entity.
     /// Use this for validation errors that may be caught at different layers.
+    #[allow(dead_code)]
     pub async fn assert_bad_request_or_unprocessable(response: Response) {
         let status = response.status().as_u16();
         assert!(
@@ -607,15 +586,4 @@ pub mod assertions {
             status
         );
     }
-
-    /// Assert that a JSON error payload contains a descriptive substring.
-    pub fn assert_contains_error(json: &Value, expected_error: &str) {
-        let error = json.get("error").and_then(|e| e.as_str()).unwrap_or("");
-        assert!(
-            error.contains(expected_error),
-            "Expected error to contain '{}', got '{}'",
-            expected_error,
-            error
-        );
-    }
 }
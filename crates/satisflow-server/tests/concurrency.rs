@@ -0,0 +1,193 @@
+//! Concurrency integration tests for the shared engine state:
+//! - Snapshots: many readers alongside periodic writers on `AppState`.
+//! - HTTP: dashboard reads stay consistent while factories are being created.
+//!
+//! These interleave real tasks, so they check the invariants on whatever
+//! schedules the runtime happens to produce rather than on every schedule.
+
+mod common;
+
+use common::{
+    assertions::assert_json_response, create_test_client, create_test_server,
+    test_data::minimal_factory_request,
+};
+use satisflow_server::state::AppState;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tokio::{task::JoinHandle, time::Instant};
+
+const WRITERS: usize = 4;
+const WRITES_PER_WRITER: usize = 10;
+const READERS: usize = 8;
+const TOTAL_FACTORIES: usize = WRITERS * WRITES_PER_WRITER;
+/// How long readers keep waiting for the writers' last factory
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Join the writers first so a failed write is reported as such, then the
+/// readers, which give up at their deadline instead of hanging the test
+async fn join_all(writers: Vec<JoinHandle<()>>, readers: Vec<JoinHandle<()>>) {
+    for handle in writers {
+        handle.await.expect("Writer panicked");
+    }
+    for handle in readers {
+        handle.await.expect("Reader panicked");
+    }
+}
+
+/// Readers take snapshots the way GET handlers do, writers build the next
+/// version the way POST handlers do; readers must never observe a half-applied
+/// write.
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn state_survives_concurrent_reads_with_periodic_writes() {
+    let state = AppState::new();
+    let mut writers = Vec::new();
+    let mut readers = Vec::new();
+
+    for writer in 0..WRITERS {
+        let state = state.clone();
+        writers.push(tokio::spawn(async move {
+            for i in 0..WRITES_PER_WRITER {
+                let mut engine = state.engine.write().await;
+                let id = engine.create_factory(format!("Factory {}-{}", writer, i), None);
+                engine.pin_factory(id).unwrap();
+                engine.update();
+                engine.commit();
+                tokio::task::yield_now().await;
+            }
+        }));
+    }
+
+    for _ in 0..READERS {
+        let state = state.clone();
+        readers.push(tokio::spawn(async move {
+            let deadline = Instant::now() + READ_TIMEOUT;
+            let mut last_count = 0;
+            while last_count < TOTAL_FACTORIES {
+                assert!(
+                    Instant::now() < deadline,
+                    "Saw {} of {} factories before the deadline",
+                    last_count,
+                    TOTAL_FACTORIES
+                );
+                let engine = state.engine.snapshot();
+                let count = engine.get_all_factories().len();
+                assert!(count >= last_count);
+                assert_eq!(engine.pinned().factories.len(), count);
+                assert_eq!(engine.report_card().factory_count, count);
+                assert_eq!(engine.recent_changes(usize::MAX).len(), count);
+                last_count = count;
+                drop(engine);
+                tokio::task::yield_now().await;
+            }
+        }));
+    }
+
+    join_all(writers, readers).await;
+
+    let engine = state.engine.snapshot();
+    assert_eq!(engine.get_all_factories().len(), TOTAL_FACTORIES);
+}
+
+/// Reads dashboard endpoints while other clients create factories and lines.
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn dashboard_reads_during_concurrent_writes() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+    let mut writers = Vec::new();
+    let mut readers = Vec::new();
+
+    for writer in 0..WRITERS {
+        let client = client.clone();
+        let base_url = server.base_url.clone();
+        writers.push(tokio::spawn(async move {
+            for i in 0..WRITES_PER_WRITER {
+                let factory: Value = client
+                    .post(format!("{}/api/factories", base_url))
+                    .json(&minimal_factory_request(&format!(
+                        "Factory {}-{}",
+                        writer, i
+                    )))
+                    .send()
+                    .await
+                    .expect("Failed to create factory")
+                    .json()
+                    .await
+                    .unwrap();
+                let factory_id = factory["id"].as_str().unwrap();
+
+                let response = client
+                    .post(format!(
+                        "{}/api/factories/{}/production-lines",
+                        base_url, factory_id
+                    ))
+                    .json(&json!({
+                        "name": "Ingots",
+                        "type": "recipe",
+                        "recipe": "Iron Ingot",
+                        "machine_groups": [
+                            { "number_of_machine": 1, "oc_value": 100.0, "somersloop": 0 }
+                        ]
+                    }))
+                    .send()
+                    .await
+                    .expect("Failed to create production line");
+                assert_eq!(response.status().as_u16(), 201);
+            }
+        }));
+    }
+
+    for _ in 0..READERS {
+        let client = client.clone();
+        let base_url = server.base_url.clone();
+        readers.push(tokio::spawn(async move {
+            let deadline = Instant::now() + READ_TIMEOUT;
+            let mut last_count = 0;
+            while last_count < TOTAL_FACTORIES {
+                assert!(
+                    Instant::now() < deadline,
+                    "Saw {} of {} factories before the deadline",
+                    last_count,
+                    TOTAL_FACTORIES
+                );
+                let summary: Value = assert_json_response(
+                    client
+                        .get(format!("{}/api/dashboard/summary", base_url))
+                        .send()
+                        .await
+                        .expect("Failed to get dashboard summary"),
+                )
+                .await;
+                let count = summary["total_factories"].as_u64().unwrap() as usize;
+                assert!(count >= last_count);
+                assert!(summary["total_production_lines"].as_u64().unwrap() as usize <= count);
+                last_count = count;
+
+                for endpoint in ["power", "report-card", "items"] {
+                    let response = client
+                        .get(format!("{}/api/dashboard/{}", base_url, endpoint))
+                        .send()
+                        .await
+                        .expect("Failed to get dashboard endpoint");
+                    assert_eq!(response.status().as_u16(), 200);
+                }
+            }
+        }));
+    }
+
+    join_all(writers, readers).await;
+
+    let summary: Value = assert_json_response(
+        client
+            .get(format!("{}/api/dashboard/summary", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get dashboard summary"),
+    )
+    .await;
+    assert_eq!(summary["total_factories"], TOTAL_FACTORIES);
+    assert_eq!(summary["total_production_lines"], TOTAL_FACTORIES);
+    assert_eq!(
+        summary["total_power_consumption"],
+        TOTAL_FACTORIES as f64 * 4.0
+    );
+}
@@ -3,11 +3,17 @@
 //! - Validate: reject blank names with contract-compliant errors.
 //! - Update: ensure notes trimming applies during edits.
 //! - Delete: verify logistics lines cascade when factories are removed.
+//! - Dependencies: report upstream and downstream factories before deleting.
+//! - Merge: combine factories and rewire their logistics.
+//! - Split: carve production lines into a new factory linked by buses.
+//! - Clone: copy a factory under fresh IDs, optionally with tasks for its logistics.
+//! - Tags: group factories, archive them, and filter the list by either.
+//! - Internal transfers: document in-factory buses without touching balances.
 
 mod common;
 
 use common::{
-    assertions::{assert_json_response, assert_no_content},
+    assertions::{assert_created_response, assert_json_response, assert_no_content},
     create_test_client, create_test_server,
     test_data::{
         create_factory_request, factory_with_notes_request, minimal_factory_request,
@@ -204,3 +210,542 @@ async fn factory_delete_cascades_logistics() {
     let list: Value = assert_json_response(list_response).await;
     assert!(list.as_array().unwrap().is_empty());
 }
+
+/// Dependency query shows the blast radius of a factory: upstream suppliers,
+/// downstream consumers, and the items exchanged with each.
+#[tokio::test]
+async fn factory_dependencies_report_linked_factories() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let mut ids = Vec::new();
+    for name in ["Mine", "Smelter", "Assembler"] {
+        let factory: Value = client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&minimal_factory_request(name))
+            .send()
+            .await
+            .expect("Failed to create factory")
+            .json()
+            .await
+            .unwrap();
+        ids.push(
+            factory["id"]
+                .as_str()
+                .and_then(|id| Uuid::parse_str(id).ok())
+                .unwrap(),
+        );
+    }
+
+    for (from, to, item) in [(ids[0], ids[1], "IronOre"), (ids[1], ids[2], "IronPlate")] {
+        let response = client
+            .post(format!("{}/api/logistics", server.base_url))
+            .json(&truck_logistics_request(from, to, item, 60.0))
+            .send()
+            .await
+            .expect("Failed to create logistics line");
+        assert_eq!(response.status().as_u16(), 201);
+    }
+
+    let response = client
+        .get(format!(
+            "{}/api/factories/{}/dependencies",
+            server.base_url, ids[1]
+        ))
+        .send()
+        .await
+        .expect("Failed to fetch dependencies");
+    let body: Value = assert_json_response(response).await;
+
+    let dependencies = body["dependencies"].as_array().unwrap();
+    assert_eq!(dependencies.len(), 1);
+    assert_eq!(dependencies[0]["factory_name"], "Mine");
+    assert_eq!(dependencies[0]["items"][0]["item"], "IronOre");
+    assert_eq!(dependencies[0]["total_quantity_per_min"], 60.0);
+
+    let dependents = body["dependents"].as_array().unwrap();
+    assert_eq!(dependents.len(), 1);
+    assert_eq!(dependents[0]["factory_name"], "Assembler");
+    assert_eq!(dependents[0]["items"][0]["item"], "IronPlate");
+
+    let missing = client
+        .get(format!(
+            "{}/api/factories/{}/dependencies",
+            server.base_url,
+            Uuid::new_v4()
+        ))
+        .send()
+        .await
+        .expect("Failed to fetch dependencies for missing factory");
+    assert_eq!(missing.status().as_u16(), 404);
+}
+
+/// Merge previews the combined factory, then replaces the sources with a single
+/// factory whose external logistics are rewired and internal ones dropped.
+#[tokio::test]
+async fn factory_merge_combines_and_rewires_logistics() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let mut ids = Vec::new();
+    for name in ["Mine", "Smelter", "Outside"] {
+        let factory: Value = client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&minimal_factory_request(name))
+            .send()
+            .await
+            .expect("Failed to create factory")
+            .json()
+            .await
+            .unwrap();
+        ids.push(
+            factory["id"]
+                .as_str()
+                .and_then(|id| Uuid::parse_str(id).ok())
+                .unwrap(),
+        );
+    }
+
+    let mut logistics_ids = Vec::new();
+    for (from, to) in [(ids[0], ids[1]), (ids[1], ids[2])] {
+        let logistics: Value = client
+            .post(format!("{}/api/logistics", server.base_url))
+            .json(&truck_logistics_request(from, to, "IronOre", 60.0))
+            .send()
+            .await
+            .expect("Failed to create logistics line")
+            .json()
+            .await
+            .unwrap();
+        logistics_ids.push(logistics["id"].as_str().unwrap().to_string());
+    }
+
+    let merge_request = serde_json::json!({
+        "factory_ids": [ids[0], ids[1]],
+        "name": "Iron Works"
+    });
+
+    let preview_response = client
+        .post(format!("{}/api/factories/merge/preview", server.base_url))
+        .json(&merge_request)
+        .send()
+        .await
+        .expect("Failed to preview merge");
+    let preview: Value = assert_json_response(preview_response).await;
+    assert_eq!(preview["name"], "Iron Works");
+    assert_eq!(preview["internalized_logistics"][0], logistics_ids[0]);
+    assert_eq!(preview["external_logistics"][0], logistics_ids[1]);
+
+    let merge_response = client
+        .post(format!("{}/api/factories/merge", server.base_url))
+        .json(&merge_request)
+        .send()
+        .await
+        .expect("Failed to merge factories");
+    assert_eq!(merge_response.status().as_u16(), 201);
+    let merged: Value = merge_response.json().await.unwrap();
+    assert_eq!(merged["name"], "Iron Works");
+
+    let list: Value = assert_json_response(
+        client
+            .get(format!("{}/api/factories", server.base_url))
+            .send()
+            .await
+            .expect("Failed to list factories"),
+    )
+    .await;
+    assert_eq!(list.as_array().unwrap().len(), 2);
+
+    let logistics: Value = assert_json_response(
+        client
+            .get(format!("{}/api/logistics", server.base_url))
+            .send()
+            .await
+            .expect("Failed to list logistics"),
+    )
+    .await;
+    let logistics = logistics.as_array().unwrap();
+    assert_eq!(logistics.len(), 1);
+    assert_eq!(logistics[0]["from_factory"], merged["id"]);
+
+    let invalid = client
+        .post(format!("{}/api/factories/merge", server.base_url))
+        .json(&serde_json::json!({ "factory_ids": [ids[2]], "name": "Solo" }))
+        .send()
+        .await
+        .expect("Failed to send invalid merge");
+    assert_eq!(invalid.status().as_u16(), 400);
+}
+
+/// Splits production lines into new factories, with and without boundary buses.
+#[tokio::test]
+async fn factory_split_moves_lines_and_creates_buses() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let factory: Value = client
+        .post(format!("{}/api/factories", server.base_url))
+        .json(&minimal_factory_request("Iron Works"))
+        .send()
+        .await
+        .expect("Failed to create factory")
+        .json()
+        .await
+        .unwrap();
+    let factory_id = factory["id"].as_str().unwrap().to_string();
+
+    let mut factory = Value::Null;
+    for (name, recipe) in [("Ingots", "Iron Ingot"), ("Plates", "Iron Plate")] {
+        factory = client
+            .post(format!(
+                "{}/api/factories/{}/production-lines",
+                server.base_url, factory_id
+            ))
+            .json(&serde_json::json!({
+                "name": name,
+                "type": "recipe",
+                "recipe": recipe,
+                "machine_groups": [
+                    { "number_of_machine": 2, "oc_value": 100.0, "somersloop": 0 }
+                ]
+            }))
+            .send()
+            .await
+            .expect("Failed to create production line")
+            .json()
+            .await
+            .unwrap();
+    }
+    let plates_id = factory["production_lines"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|line| &line["ProductionLineRecipe"])
+        .find(|line| line["name"] == "Plates")
+        .map(|line| line["id"].clone())
+        .unwrap();
+
+    let split_response = client
+        .post(format!(
+            "{}/api/factories/{}/split",
+            server.base_url, factory_id
+        ))
+        .json(&serde_json::json!({
+            "production_line_ids": [plates_id],
+            "name": "Plate Shop"
+        }))
+        .send()
+        .await
+        .expect("Failed to split factory");
+    assert_eq!(split_response.status().as_u16(), 201);
+    let split: Value = split_response.json().await.unwrap();
+    assert_eq!(split["new_factory"]["name"], "Plate Shop");
+    assert_eq!(
+        split["source_factory"]["production_lines"]
+            .as_array()
+            .unwrap()
+            .len(),
+        1
+    );
+    assert_eq!(split["logistics_ids"].as_array().unwrap().len(), 2);
+
+    let logistics: Value = assert_json_response(
+        client
+            .get(format!("{}/api/logistics", server.base_url))
+            .send()
+            .await
+            .expect("Failed to list logistics"),
+    )
+    .await;
+    let logistics = logistics.as_array().unwrap();
+    assert_eq!(logistics.len(), 2);
+    assert!(logistics.iter().all(|line| line["transport_type"] == "Bus"));
+
+    let invalid = client
+        .post(format!(
+            "{}/api/factories/{}/split",
+            server.base_url, factory_id
+        ))
+        .json(&serde_json::json!({ "production_line_ids": [], "name": "Empty" }))
+        .send()
+        .await
+        .expect("Failed to send invalid split");
+    assert_eq!(invalid.status().as_u16(), 400);
+
+    // Without buses the remaining flows are left for the player to connect
+    let ingots_id =
+        split["source_factory"]["production_lines"][0]["ProductionLineRecipe"]["id"].clone();
+    let split_response = client
+        .post(format!(
+            "{}/api/factories/{}/split",
+            server.base_url, factory_id
+        ))
+        .json(&serde_json::json!({
+            "production_line_ids": [ingots_id],
+            "name": "Smelter",
+            "create_buses": false
+        }))
+        .send()
+        .await
+        .expect("Failed to split factory");
+    assert_eq!(split_response.status().as_u16(), 201);
+    let split: Value = split_response.json().await.unwrap();
+    assert!(split["logistics_ids"].as_array().unwrap().is_empty());
+}
+
+/// Copies a factory's lines under new IDs and turns its outbound logistics into tasks.
+#[tokio::test]
+async fn factory_clone_copies_lines_and_logistics_tasks() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let mut ids = Vec::new();
+    for name in ["Smelter", "Depot"] {
+        let factory: Value = client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&minimal_factory_request(name))
+            .send()
+            .await
+            .expect("Failed to create factory")
+            .json()
+            .await
+            .unwrap();
+        ids.push(Uuid::parse_str(factory["id"].as_str().unwrap()).unwrap());
+    }
+    let (smelter_id, depot_id) = (ids[0], ids[1]);
+
+    let source: Value = client
+        .post(format!(
+            "{}/api/factories/{}/production-lines",
+            server.base_url, smelter_id
+        ))
+        .json(&serde_json::json!({
+            "name": "Ingots",
+            "type": "recipe",
+            "recipe": "Iron Ingot",
+            "machine_groups": [
+                { "number_of_machine": 2, "oc_value": 100.0, "somersloop": 0 }
+            ]
+        }))
+        .send()
+        .await
+        .expect("Failed to create production line")
+        .json()
+        .await
+        .unwrap();
+    client
+        .post(format!("{}/api/logistics", server.base_url))
+        .json(&truck_logistics_request(
+            smelter_id,
+            depot_id,
+            "IronIngot",
+            60.0,
+        ))
+        .send()
+        .await
+        .expect("Failed to create logistics");
+
+    let clone_response = client
+        .post(format!(
+            "{}/api/factories/{}/clone",
+            server.base_url, smelter_id
+        ))
+        .json(&serde_json::json!({ "name": "Smelter 2", "clone_logistics": true }))
+        .send()
+        .await
+        .expect("Failed to clone factory");
+    assert_eq!(clone_response.status().as_u16(), 201);
+    let clone: Value = clone_response.json().await.unwrap();
+    assert_eq!(clone["name"], "Smelter 2");
+    assert_ne!(clone["id"], source["id"]);
+    let line = &clone["production_lines"][0]["ProductionLineRecipe"];
+    assert_eq!(line["name"], "Ingots");
+    assert_ne!(
+        line["id"],
+        source["production_lines"][0]["ProductionLineRecipe"]["id"]
+    );
+
+    let logistics: Value = assert_json_response(
+        client
+            .get(format!("{}/api/logistics", server.base_url))
+            .send()
+            .await
+            .expect("Failed to list logistics"),
+    )
+    .await;
+    let logistics = logistics.as_array().unwrap();
+    assert_eq!(logistics.len(), 1);
+    assert!(logistics
+        .iter()
+        .all(|line| line["from_factory"] != clone["id"] && line["to_factory"] != clone["id"]));
+    let tasks = clone["tasks"].as_array().unwrap();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0]["done"], false);
+    assert!(tasks[0]["text"].as_str().unwrap().ends_with("to Depot"));
+
+    let missing = client
+        .post(format!(
+            "{}/api/factories/{}/clone",
+            server.base_url,
+            Uuid::new_v4()
+        ))
+        .json(&serde_json::json!({ "name": "Ghost" }))
+        .send()
+        .await
+        .expect("Failed to send clone of missing factory");
+    assert_eq!(missing.status().as_u16(), 404);
+}
+
+/// Tags and archives factories, then filters the list and groups the dashboard by tag.
+#[tokio::test]
+async fn factory_tags_and_archive_filter_the_list() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let north: Value = assert_created_response(
+        client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&serde_json::json!({
+                "name": "North Smelter",
+                "tags": ["North", " Phase 1 ", "north", ""]
+            }))
+            .send()
+            .await
+            .expect("Failed to create tagged factory"),
+    )
+    .await;
+    assert_eq!(north["tags"], serde_json::json!(["North", "Phase 1"]));
+    assert_eq!(north["archived"], false);
+
+    let old: Value = assert_created_response(
+        client
+            .post(format!("{}/api/factories", server.base_url))
+            .json(&minimal_factory_request("Old Outpost"))
+            .send()
+            .await
+            .expect("Failed to create factory"),
+    )
+    .await;
+    let old: Value = assert_json_response(
+        client
+            .put(format!(
+                "{}/api/factories/{}",
+                server.base_url,
+                old["id"].as_str().unwrap()
+            ))
+            .json(&serde_json::json!({ "tags": ["north"], "archived": true }))
+            .send()
+            .await
+            .expect("Failed to archive factory"),
+    )
+    .await;
+    assert_eq!(old["archived"], true);
+
+    let list = |query: &'static str| {
+        let client = client.clone();
+        let url = format!("{}/api/factories{}", server.base_url, query);
+        async move {
+            let factories: Value =
+                assert_json_response(client.get(url).send().await.expect("Failed to list")).await;
+            factories.as_array().unwrap().len()
+        }
+    };
+    assert_eq!(list("").await, 2);
+    assert_eq!(list("?tag=NORTH").await, 2);
+    assert_eq!(list("?tag=phase%201").await, 1);
+    assert_eq!(list("?archived=false").await, 1);
+    assert_eq!(list("?tag=north&archived=true").await, 1);
+    assert_eq!(list("?tag=south").await, 0);
+
+    let groups: Value = assert_json_response(
+        client
+            .get(format!("{}/api/dashboard/tags", server.base_url))
+            .send()
+            .await
+            .expect("Failed to get tag groups"),
+    )
+    .await;
+    assert_eq!(groups[0]["tag"], "North");
+    assert_eq!(groups[0]["factory_ids"].as_array().unwrap().len(), 2);
+    assert_eq!(groups[0]["archived_factories"], 1);
+    assert_eq!(groups[1]["tag"], "Phase 1");
+}
+
+/// Adds, updates and removes an internal transfer without affecting item balances.
+#[tokio::test]
+async fn factory_internal_transfers_round_trip() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let factory: Value = client
+        .post(format!("{}/api/factories", server.base_url))
+        .json(&minimal_factory_request("Main Base"))
+        .send()
+        .await
+        .expect("Failed to create factory")
+        .json()
+        .await
+        .unwrap();
+    let factory_id = factory["id"].as_str().unwrap().to_string();
+    let transfers_url = format!(
+        "{}/api/factories/{}/internal-transfers",
+        server.base_url, factory_id
+    );
+
+    let created = client
+        .post(&transfers_url)
+        .json(&serde_json::json!({
+            "item": "IronPlate",
+            "quantity_per_min": 120.0,
+            "from_area": " Smelting ",
+            "to_area": "Assembly"
+        }))
+        .send()
+        .await
+        .expect("Failed to create internal transfer");
+    assert_eq!(created.status().as_u16(), 201);
+    let factory: Value = created.json().await.unwrap();
+    let transfer = &factory["internal_transfers"][0];
+    assert_eq!(transfer["from_area"], "Smelting");
+    assert!(factory["items"].as_array().unwrap().is_empty());
+    let transfer_id = transfer["id"].as_str().unwrap().to_string();
+
+    let updated: Value = assert_json_response(
+        client
+            .put(format!("{}/{}", transfers_url, transfer_id))
+            .json(&serde_json::json!({
+                "item": "IronPlate",
+                "quantity_per_min": 240.0,
+                "from_area": "Smelting",
+                "to_area": "Assembly"
+            }))
+            .send()
+            .await
+            .expect("Failed to update internal transfer"),
+    )
+    .await;
+    assert_eq!(updated["internal_transfers"][0]["quantity_per_min"], 240.0);
+
+    let invalid = client
+        .post(&transfers_url)
+        .json(&serde_json::json!({
+            "item": "IronPlate",
+            "quantity_per_min": 60.0,
+            "from_area": "Assembly",
+            "to_area": "Assembly"
+        }))
+        .send()
+        .await
+        .expect("Failed to send invalid internal transfer");
+    assert_eq!(invalid.status().as_u16(), 400);
+
+    let deleted: Value = assert_json_response(
+        client
+            .delete(format!("{}/{}", transfers_url, transfer_id))
+            .send()
+            .await
+            .expect("Failed to delete internal transfer"),
+    )
+    .await;
+    assert!(deleted["internal_transfers"].as_array().unwrap().is_empty());
+}
@@ -0,0 +1,292 @@
+//! Integration tests for the gRPC service, built with the `grpc` feature:
+//! - Factories, logistics lines and saves round-trip through a live server.
+//! - Writes record dashboard metrics and send webhooks like REST writes.
+//! - Errors map to gRPC codes; read-only API keys can't write.
+#![cfg(feature = "grpc")]
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use axum::{http::StatusCode, routing::post, Json, Router};
+use satisflow_engine::{
+    models::{
+        production_line::{MachineGroup, ProductionLine, ProductionLineRecipe},
+        ProductionLineId, Recipe,
+    },
+    SatisflowEngine,
+};
+use satisflow_server::{
+    auth::AuthConfig,
+    grpc::{
+        proto::{
+            create_logistics_line_request::Transport, satisflow_client::SatisflowClient,
+            CreateFactoryRequest, CreateLogisticsLineRequest, DeleteFactoryRequest,
+            GetFactoryRequest, ListFactoriesRequest, ListLogisticsRequest, LoadRequest,
+            SaveRequest, SingleItemTransport, Tags, UpdateFactoryRequest,
+        },
+        SatisflowService,
+    },
+    state::AppState,
+    webhooks::{WebhookConfig, WebhookEvent, Webhooks},
+};
+use serde_json::Value;
+use tokio::{net::TcpListener, sync::mpsc};
+use tonic::{
+    transport::{server::TcpIncoming, Channel, Server},
+    Code, Request,
+};
+
+/// Serve `state` over gRPC on a free port and connect a client to it
+async fn spawn_grpc(state: AppState, auth: Option<AuthConfig>) -> SatisflowClient<Channel> {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let incoming = TcpIncoming::from_listener(listener, true, None).unwrap();
+    let service = SatisflowService::new(state, auth);
+    tokio::spawn(async move {
+        Server::builder()
+            .add_service(service.into_server())
+            .serve_with_incoming(incoming)
+            .await
+            .unwrap();
+    });
+    SatisflowClient::connect(format!("http://{}", addr))
+        .await
+        .unwrap()
+}
+
+fn factory_request(name: &str) -> CreateFactoryRequest {
+    CreateFactoryRequest {
+        name: name.to_string(),
+        description: None,
+        notes: None,
+        tags: vec!["iron".to_string()],
+        archived: false,
+    }
+}
+
+#[tokio::test]
+async fn grpc_covers_factories_logistics_and_saves() {
+    let state = AppState::new();
+    let mut client = spawn_grpc(state.clone(), None).await;
+
+    let mine = client
+        .create_factory(factory_request("Mine"))
+        .await
+        .unwrap()
+        .into_inner();
+    let smelter = client
+        .create_factory(factory_request("Smelter"))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(mine.name, "Mine");
+    assert_eq!(mine.tags, vec!["iron"]);
+
+    let renamed = client
+        .update_factory(UpdateFactoryRequest {
+            id: smelter.id.clone(),
+            name: Some("Iron Smelter".to_string()),
+            tags: Some(Tags { tags: Vec::new() }),
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(renamed.name, "Iron Smelter");
+    assert!(renamed.tags.is_empty());
+
+    let tagged = client
+        .list_factories(ListFactoriesRequest {
+            tag: Some("iron".to_string()),
+            name_contains: None,
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(tagged.factories.len(), 1);
+    assert_eq!(tagged.factories[0].id, mine.id);
+
+    let line = client
+        .create_logistics_line(CreateLogisticsLineRequest {
+            from_factory: mine.id.clone(),
+            to_factory: smelter.id.clone(),
+            transport: Some(Transport::Truck(SingleItemTransport {
+                item: "IronOre".to_string(),
+                quantity_per_min: 60.0,
+                vehicle_id: None,
+            })),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(line.transport_type, "Truck");
+    assert_eq!(line.items[0].item, "IronOre");
+    assert_eq!(line.total_quantity_per_min, 60.0);
+
+    let smelter = client
+        .get_factory(GetFactoryRequest {
+            id: smelter.id.clone(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    let ore = smelter
+        .items
+        .iter()
+        .find(|rate| rate.item == "IronOre")
+        .unwrap();
+    assert_eq!(ore.rate, 60.0);
+
+    // Both APIs share the engine
+    assert_eq!(state.engine.snapshot().get_all_logistics().len(), 1);
+
+    // Each write recorded a dashboard metrics point, as it would over REST
+    let points = state.metrics_history.points().await;
+    assert_eq!(points.len(), 4);
+    assert_eq!(
+        points.last().unwrap().revision,
+        state.engine.snapshot().revision()
+    );
+
+    let save = client
+        .save(SaveRequest::default())
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(save.summary.unwrap().factory_count, 2);
+
+    let mut other = spawn_grpc(AppState::new(), None).await;
+    let loaded = other
+        .load(LoadRequest {
+            save_data: save.save_data,
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(loaded.summary.unwrap().logistics_count, 1);
+    let lines = other
+        .list_logistics(ListLogisticsRequest {
+            transport_type: Some("truck".to_string()),
+            item: Some("IronOre".to_string()),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(lines.lines.len(), 1);
+    assert_eq!(lines.lines[0].id, line.id);
+
+    client
+        .delete_factory(DeleteFactoryRequest {
+            id: mine.id.clone(),
+        })
+        .await
+        .unwrap();
+    let missing = client
+        .get_factory(GetFactoryRequest { id: mine.id })
+        .await
+        .unwrap_err();
+    assert_eq!(missing.code(), Code::NotFound);
+
+    let invalid = client
+        .get_factory(GetFactoryRequest {
+            id: "not-a-uuid".to_string(),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(invalid.code(), Code::InvalidArgument);
+
+    let unknown_item = client
+        .list_logistics(ListLogisticsRequest {
+            transport_type: None,
+            item: Some("Unobtainium".to_string()),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(unknown_item.code(), Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn grpc_writes_send_power_deficit_webhooks() {
+    let (received, mut payloads) = mpsc::unbounded_channel::<Value>();
+    let receiver = Router::new().route(
+        "/hook",
+        post(move |Json(payload): Json<Value>| async move {
+            received.send(payload).unwrap();
+            StatusCode::NO_CONTENT
+        }),
+    );
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, receiver).await });
+    let state = AppState {
+        webhooks: Webhooks::start(WebhookConfig {
+            urls: vec![url],
+            events: HashSet::from([WebhookEvent::PowerDeficit]),
+            retry_delay: Duration::from_millis(10),
+        }),
+        ..AppState::new()
+    };
+    let mut client = spawn_grpc(state, None).await;
+
+    // Smelters without any generator to power them
+    let mut engine = SatisflowEngine::new();
+    let smelter = engine.create_factory("Smelter".to_string(), None);
+    let mut ingots = ProductionLineRecipe::new(
+        ProductionLineId::new_v4(),
+        "Ingots".to_string(),
+        None,
+        Recipe::IronIngot,
+    );
+    ingots
+        .add_machine_group(MachineGroup::new(2, 100.0, 0))
+        .unwrap();
+    engine
+        .get_factory_mut(smelter)
+        .unwrap()
+        .add_production_line(ProductionLine::ProductionLineRecipe(ingots));
+    client
+        .load(LoadRequest {
+            save_data: engine.save_to_json().unwrap(),
+        })
+        .await
+        .unwrap();
+
+    let payload = tokio::time::timeout(Duration::from_secs(10), payloads.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(payload["event"], "power_deficit");
+    assert_eq!(payload["data"]["power_consumption"], 8.0);
+}
+
+#[tokio::test]
+async fn grpc_checks_api_keys() {
+    let config = AuthConfig::new(vec!["writer".to_string()], vec!["reader".to_string()]).unwrap();
+    let mut client = spawn_grpc(AppState::new(), Some(config)).await;
+
+    let anonymous = client
+        .list_factories(ListFactoriesRequest::default())
+        .await
+        .unwrap_err();
+    assert_eq!(anonymous.code(), Code::Unauthenticated);
+
+    let mut request = Request::new(factory_request("Mine"));
+    request
+        .metadata_mut()
+        .insert("authorization", "Bearer reader".parse().unwrap());
+    let read_only = client.create_factory(request).await.unwrap_err();
+    assert_eq!(read_only.code(), Code::PermissionDenied);
+
+    let mut request = Request::new(factory_request("Mine"));
+    request
+        .metadata_mut()
+        .insert("x-api-key", "writer".parse().unwrap());
+    client.create_factory(request).await.unwrap();
+
+    let mut request = Request::new(ListFactoriesRequest::default());
+    request
+        .metadata_mut()
+        .insert("authorization", "Bearer reader".parse().unwrap());
+    let listed = client.list_factories(request).await.unwrap().into_inner();
+    assert_eq!(listed.factories.len(), 1);
+}
@@ -1,20 +1,22 @@
 //! Logistics integration tests covering the transport checklist for trucks, buses,
 //! and trains: identifier defaults, throughput validation, payload aggregation,
-//! and error handling for malformed requests. Each case doubles as executable
-//! documentation of the expected behaviour.
+//! route estimates from factory map positions, and error handling for malformed
+//! requests. Each case doubles as executable documentation of the expected
+//! behaviour.
 
 mod common;
 
 use common::{
     create_test_client, create_test_server,
     test_data::{
+        bus_with_lifts_request, bus_with_pumped_pipelines_request,
         bus_with_whitespace_name_request, bus_with_zero_pipeline_request, minimal_factory_request,
         mixed_bus_logistics_request, train_empty_wagons_request, train_logistics_request,
         truck_logistics_request, truck_logistics_with_id_request,
     },
 };
 use reqwest::Client;
-use serde_json::Value;
+use serde_json::{json, Value};
 use uuid::Uuid;
 
 /// Convenience helper to create a factory and return its identifier for wiring
@@ -62,13 +64,10 @@ async fn logistics_truck_assigns_default_identifier() {
     assert_eq!(items[0]["quantity_per_min"], 180.0);
     assert_eq!(payload["total_quantity_per_min"], 180.0);
 
-    let details = payload["transport_details"]
-        .as_str()
-        .expect("Missing transport details");
-    let details_json: Value = serde_json::from_str(details).expect("Transport details not JSON");
-    assert_eq!(details_json["truck_id"], "TRK-001");
-    assert_eq!(details_json["item"], "IronPlate");
-    assert_eq!(details_json["quantity_per_min"], 180.0);
+    assert_eq!(
+        payload["transport_details"],
+        json!({ "Truck": { "truck_id": "TRK-001" } })
+    );
 }
 
 /// Truck validation case confirming non-positive throughput is rejected with a
@@ -127,12 +126,7 @@ async fn logistics_truck_respects_custom_identifier() {
     let payload: Value = response.json().await.unwrap();
     assert_eq!(payload["transport_id"], "TRK-777");
 
-    let details = payload["transport_details"]
-        .as_str()
-        .expect("Missing transport details");
-    let details_json: Value = serde_json::from_str(details).expect("Transport details not JSON");
-    assert_eq!(details_json["truck_id"], "TRK-777");
-    assert_eq!(details_json["quantity_per_min"], 90.0);
+    assert_eq!(payload["transport_details"]["Truck"]["truck_id"], "TRK-777");
 }
 
 /// Mixed bus scenario verifying totals across conveyors and pipelines.
@@ -166,6 +160,95 @@ async fn logistics_bus_mixed_payload_totals_items() {
     assert_eq!(items[1]["quantity_per_min"], 480.0);
 }
 
+/// Lift segments keep their own identifiers and are checked against their tier.
+#[tokio::test]
+async fn logistics_bus_lifts_have_own_identifiers() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let from_id = create_factory(&client, &server.base_url, "Ground Floor").await;
+    let to_id = create_factory(&client, &server.base_url, "Roof").await;
+
+    let response = client
+        .post(format!("{}/api/logistics", server.base_url))
+        .json(&bus_with_lifts_request(from_id, to_id))
+        .send()
+        .await
+        .expect("Failed to create bus with lifts");
+
+    assert_eq!(response.status().as_u16(), 201);
+    let payload: Value = response.json().await.unwrap();
+    assert_eq!(payload["total_quantity_per_min"], 3300.0);
+    assert_eq!(
+        payload["transport_details"]["Bus"]["lift_ids"],
+        json!(["LF-007", "LF-002"])
+    );
+
+    let diagnostics: Value = client
+        .get(format!("{}/api/analysis/diagnostics", server.base_url))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let over: Vec<&str> = diagnostics
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|diagnostic| diagnostic["kind"] == "CapacityExceeded")
+        .map(|diagnostic| diagnostic["message"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        over,
+        vec!["Riser lift 2 carries 900.0 Coal/min, over its Mk5 limit of 780"]
+    );
+}
+
+/// Pipelines record their pumps and rise, and warn when they can't lift the fluid.
+#[tokio::test]
+async fn logistics_bus_pipeline_head_lift() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let from_id = create_factory(&client, &server.base_url, "Well").await;
+    let to_id = create_factory(&client, &server.base_url, "Tower").await;
+
+    let response = client
+        .post(format!("{}/api/logistics", server.base_url))
+        .json(&bus_with_pumped_pipelines_request(from_id, to_id))
+        .send()
+        .await
+        .expect("Failed to create bus with pumps");
+
+    assert_eq!(response.status().as_u16(), 201);
+    let payload: Value = response.json().await.unwrap();
+    assert_eq!(
+        payload["transport_details"]["Bus"]["pipeline_ids"],
+        json!(["PL-001", "PL-002"])
+    );
+
+    let diagnostics: Value = client
+        .get(format!("{}/api/analysis/diagnostics", server.base_url))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let warnings: Vec<&str> = diagnostics
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|diagnostic| diagnostic["kind"] == "InsufficientHeadLift")
+        .map(|diagnostic| diagnostic["message"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        warnings,
+        vec!["Water Tower pipeline 2 rises 45.0 m, above the 30.0 m its pipe and pumps lift Water"]
+    );
+}
+
 /// Bus validation confirming that at least one conveyor or pipeline segment is
 /// required.
 #[tokio::test]
@@ -337,3 +420,227 @@ async fn logistics_rejects_unknown_item() {
         error_text
     );
 }
+
+/// Naming configuration path: a custom train pattern is applied to generated
+/// names, and invalid patterns are rejected without touching the others.
+#[tokio::test]
+async fn logistics_train_uses_configured_naming_pattern() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let response = client
+        .put(format!("{}/api/logistics/naming", server.base_url))
+        .json(&serde_json::json!({ "train": "{prefix}-Alpha-{n:02}" }))
+        .send()
+        .await
+        .expect("Failed to update naming patterns");
+    assert_eq!(response.status().as_u16(), 200);
+    let naming: Value = response.json().await.unwrap();
+    assert_eq!(naming["train"], "{prefix}-Alpha-{n:02}");
+    assert_eq!(naming["truck"], "{prefix}-{n:03}");
+
+    let rejected = client
+        .put(format!("{}/api/logistics/naming", server.base_url))
+        .json(&serde_json::json!({ "bus": "{prefix}-North-{n}", "truck": "TRUCK" }))
+        .send()
+        .await
+        .expect("Failed to send invalid naming patterns");
+    assert_eq!(rejected.status().as_u16(), 400);
+
+    let naming: Value = client
+        .get(format!("{}/api/logistics/naming", server.base_url))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(naming["bus"], "Bus {n}");
+
+    let from_id = create_factory(&client, &server.base_url, "Rail Source").await;
+    let to_id = create_factory(&client, &server.base_url, "Rail Sink").await;
+
+    let response = client
+        .post(format!("{}/api/logistics", server.base_url))
+        .json(&serde_json::json!({
+            "from_factory": from_id,
+            "to_factory": to_id,
+            "transport_type": "Train",
+            "wagons": [
+                {
+                    "wagon_type": "Cargo",
+                    "item": "IronPlate",
+                    "quantity_per_min": 120.0
+                }
+            ]
+        }))
+        .send()
+        .await
+        .expect("Failed to create train logistics");
+    assert_eq!(response.status().as_u16(), 201);
+    let payload: Value = response.json().await.unwrap();
+    assert_eq!(payload["transport_name"], "TRN-Alpha-01");
+}
+
+/// Reverse path swapping source and destination while keeping the line id.
+#[tokio::test]
+async fn logistics_reverse_swaps_direction() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let from_id = create_factory(&client, &server.base_url, "Source").await;
+    let to_id = create_factory(&client, &server.base_url, "Sink").await;
+
+    let created: Value = client
+        .post(format!("{}/api/logistics", server.base_url))
+        .json(&truck_logistics_request(from_id, to_id, "IronPlate", 60.0))
+        .send()
+        .await
+        .expect("Failed to create truck logistics")
+        .json()
+        .await
+        .unwrap();
+
+    let response = client
+        .post(format!(
+            "{}/api/logistics/{}/reverse",
+            server.base_url,
+            created["id"].as_str().unwrap()
+        ))
+        .send()
+        .await
+        .expect("Failed to reverse logistics");
+    assert_eq!(response.status().as_u16(), 200);
+    let reversed: Value = response.json().await.unwrap();
+    assert_eq!(reversed["id"], created["id"]);
+    assert_eq!(reversed["from_factory"], to_id.to_string());
+    assert_eq!(reversed["to_factory"], from_id.to_string());
+
+    let missing = client
+        .post(format!(
+            "{}/api/logistics/{}/reverse",
+            server.base_url,
+            Uuid::new_v4()
+        ))
+        .send()
+        .await
+        .expect("Failed request to reverse missing line");
+    assert_eq!(missing.status().as_u16(), 404);
+}
+
+/// Split path dividing a mixed bus into per-item lines, keeping the original id
+/// for the first item and narrowing transport details for each line.
+#[tokio::test]
+async fn logistics_split_creates_per_item_lines() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let from_id = create_factory(&client, &server.base_url, "Bus Hub").await;
+    let to_id = create_factory(&client, &server.base_url, "Bus Drop").await;
+
+    let created: Value = client
+        .post(format!("{}/api/logistics", server.base_url))
+        .json(&mixed_bus_logistics_request(from_id, to_id))
+        .send()
+        .await
+        .expect("Failed to create bus logistics")
+        .json()
+        .await
+        .unwrap();
+    let original_id = created["id"].as_str().unwrap();
+
+    let response = client
+        .post(format!(
+            "{}/api/logistics/{}/split",
+            server.base_url, original_id
+        ))
+        .send()
+        .await
+        .expect("Failed to split logistics");
+    assert_eq!(response.status().as_u16(), 200);
+    let lines: Value = response.json().await.unwrap();
+    let lines = lines.as_array().expect("Split should return an array");
+    assert_eq!(lines.len(), 2);
+
+    assert_eq!(lines[0]["id"], original_id);
+    assert_eq!(lines[0]["items"].as_array().unwrap().len(), 1);
+    assert_eq!(lines[0]["items"][0]["item"], "IronPlate");
+    let details = &lines[0]["transport_details"]["Bus"];
+    assert_eq!(details["conveyor_ids"], json!(["CV-101"]));
+    assert!(details["pipeline_ids"].as_array().unwrap().is_empty());
+
+    assert_eq!(lines[1]["items"][0]["item"], "Water");
+    assert_eq!(lines[1]["transport_name"], "Hybrid Bus Route - Water");
+
+    // Single-item lines are rejected
+    let again = client
+        .post(format!(
+            "{}/api/logistics/{}/split",
+            server.base_url, original_id
+        ))
+        .send()
+        .await
+        .expect("Failed request to split single-item line");
+    assert_eq!(again.status().as_u16(), 400);
+}
+
+/// Placing both factories on the map adds a travel estimate to vehicle lines.
+#[tokio::test]
+async fn logistics_route_estimate_uses_factory_positions() {
+    let server = create_test_server().await;
+    let client = create_test_client();
+
+    let from_id = create_factory(&client, &server.base_url, "Mine").await;
+    let to_id = create_factory(&client, &server.base_url, "Smelter").await;
+
+    let created: Value = client
+        .post(format!("{}/api/logistics", server.base_url))
+        .json(&truck_logistics_request(from_id, to_id, "IronOre", 120.0))
+        .send()
+        .await
+        .expect("Failed to create truck logistics")
+        .json()
+        .await
+        .unwrap();
+    assert!(created["route"].is_null());
+
+    for (id, y) in [(from_id, 0.0), (to_id, 840.0)] {
+        let factory: Value = client
+            .put(format!("{}/api/factories/{}", server.base_url, id))
+            .json(&serde_json::json!({ "position": { "x": 0.0, "y": y } }))
+            .send()
+            .await
+            .expect("Failed to place factory")
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(factory["position"]["y"], y);
+    }
+
+    let logistics: Value = client
+        .get(format!(
+            "{}/api/logistics/{}",
+            server.base_url,
+            created["id"].as_str().unwrap()
+        ))
+        .send()
+        .await
+        .expect("Failed to get logistics")
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(logistics["route"]["distance"], 840.0);
+    assert_eq!(logistics["route"]["round_trip_secs"], 144.0);
+    assert_eq!(logistics["route"]["cargo_per_trip"][0]["quantity"], 288.0);
+
+    let cleared: Value = client
+        .put(format!("{}/api/factories/{}", server.base_url, to_id))
+        .json(&serde_json::json!({ "clear_position": true }))
+        .send()
+        .await
+        .expect("Failed to clear position")
+        .json()
+        .await
+        .unwrap();
+    assert!(cleared["position"].is_null());
+}
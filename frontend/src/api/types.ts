@@ -8,6 +8,7 @@
 // Item type - string union of all Satisfactory items
 // Matches crates/satisflow-engine/src/models/items.rs Item enum
 export type Item =
+  | "ActualSnow"
   | "AILimiter"
   | "AdaptiveControlUnit"
   | "AIExpansionServer"
@@ -31,9 +32,11 @@ export type Item =
   | "Biomass"
   | "BlackPowder"
   | "BladeRunners"
+  | "BlueFICSMASOrnament"
   | "BluePowerSlug"
   | "BoomBox"
   | "Cable"
+  | "CandyCane"
   | "CateriumIngot"
   | "CateriumOre"
   | "Chainsaw"
@@ -45,6 +48,7 @@ export type Item =
   | "Computer"
   | "Concrete"
   | "CoolingSystem"
+  | "CopperFICSMASOrnament"
   | "CopperIngot"
   | "CopperOre"
   | "CopperPowder"
@@ -52,6 +56,7 @@ export type Item =
   | "CrudeOil"
   | "CrystalOscillator"
   | "DarkMatterCrystal"
+  | "DarkMatterResidue"
   | "Diamonds"
   | "ElectromagneticControlRod"
   | "EmptyCanister"
@@ -59,10 +64,18 @@ export type Item =
   | "EncasedIndustrialBeam"
   | "EncasedPlutoniumCell"
   | "EncasedUraniumCell"
+  | "ExcitedPhotonicMatter"
   | "ExplosiveRebar"
   | "Fabric"
+  | "FancyFireworks"
   | "FicsiteIngot"
   | "FicsiteTrigon"
+  | "FICSMASBow"
+  | "FICSMASDecoration"
+  | "FICSMASGift"
+  | "FICSMASOrnamentBundle"
+  | "FICSMASTreeBranch"
+  | "FICSMASWonderStar"
   | "Ficsonium"
   | "FicsoniumFuelRod"
   | "FactoryCart"
@@ -84,6 +97,7 @@ export type Item =
   | "HomingRifleAmmo"
   | "HoverPack"
   | "IodineInfusedFilter"
+  | "IronFICSMASOrnament"
   | "IronIngot"
   | "IronOre"
   | "IronPlate"
@@ -100,6 +114,7 @@ export type Item =
   | "ModularFrame"
   | "Motor"
   | "Mycelia"
+  | "NeuralQuantumProcessor"
   | "NitricAcid"
   | "NitrogenGas"
   | "Nobelisk"
@@ -138,21 +153,26 @@ export type Item =
   | "Quickwire"
   | "RadioControlUnit"
   | "RawQuartz"
+  | "ReanimatedSam"
   | "RebarGun"
+  | "RedFICSMASOrnament"
   | "ReinforcedIronPlate"
   | "Rifle"
   | "RifleAmmo"
   | "Rotor"
   | "Rubber"
   | "Sam"
+  | "SamFluctuator"
   | "Screw"
   | "ShatterRebar"
   | "Silica"
   | "SingularityCell"
   | "SmartPlating"
   | "SmokelessPowder"
+  | "Snowball"
   | "Somersloop"
   | "SolidBiofuel"
+  | "SparklyFireworks"
   | "Stator"
   | "SteelBeam"
   | "SteelIngot"
@@ -162,6 +182,8 @@ export type Item =
   | "Sulfur"
   | "SulfuricAcid"
   | "Supercomputer"
+  | "SuperpositionOscillator"
+  | "SweetFireworks"
   | "ThermalPropulsionRocket"
   | "TimeCrystal"
   | "TurboMotor"
@@ -291,12 +313,21 @@ export interface FactoryResponse {
   production_lines: ProductionLineResponse[];
   raw_inputs: RawInputResponse[];
   power_generators: PowerGeneratorResponse[];
+  tasks: FactoryTask[];
   items: ItemQuantity[];
   total_power_consumption: number;
   total_power_generation: number;
   power_balance: number;
 }
 
+// Entry of a factory's to-do checklist
+export interface FactoryTask {
+  id: string;
+  text: string;
+  done: boolean;
+  production_line: string | null;
+}
+
 // Production line response (tagged union)
 export type ProductionLineResponse =
   | {
@@ -368,6 +399,14 @@ export interface PowerGeneratorResponse {
   waste_product: Item | null;
 }
 
+// Labels saved with a logistics line, in segment order
+export type TransportDetails =
+  | { Note: string }
+  | { Truck: { truck_id: string } }
+  | { Drone: { drone_id: string } }
+  | { Bus: { conveyor_ids: string[]; lift_ids: string[]; pipeline_ids: string[] } }
+  | { Train: { wagon_ids: string[] } };
+
 // Logistics response
 export interface LogisticsResponse {
   id: string;
@@ -376,9 +415,11 @@ export interface LogisticsResponse {
   transport_type: TransportType;
   transport_id: string;
   transport_name: string | null;
-  transport_details: string;
+  transport_details: TransportDetails;
   items: ItemFlow[];
   total_quantity_per_min: number;
+  // Only on creation: problems the line was created with anyway
+  warnings?: string[];
 }
 
 // Dashboard summary response
@@ -389,6 +430,58 @@ export interface DashboardSummary {
   total_power_consumption: number;
   total_power_generation: number;
   net_power: number;
+  total_tasks: number;
+  open_tasks: number;
+}
+
+// Audit log entry, one per change to the state
+export interface AuditEntry {
+  revision: number;
+  timestamp: string;
+  method: string;
+  path: string;
+  entity_id: string | null;
+  actor: string | null;
+}
+
+// Factory template without the factory it holds
+export interface FactoryTemplateSummary {
+  id: string;
+  name: string;
+  description: string | null;
+  created_at: string;
+  production_line_count: number;
+  raw_input_count: number;
+  power_generator_count: number;
+}
+
+// Result of GET /api/factories/{id}/delete-preview
+export interface FactoryDeletePreview {
+  factory_id: string;
+  name: string;
+  logistics: {
+    id: string;
+    from_factory: string;
+    to_factory: string;
+    transport_name: string | null;
+    items: ItemFlow[];
+  }[];
+  goals: string[];
+  deficits: {
+    factory_id: string;
+    factory_name: string;
+    item: Item;
+    balance_before: number;
+    balance_after: number;
+  }[];
+}
+
+// Result of POST /api/scripts/run
+export interface ScriptResult {
+  result: unknown;
+  output: string[];
+  diff: Record<string, unknown>;
+  applied: boolean;
 }
 
 // Item balance response
@@ -715,8 +808,10 @@ export interface RawInputPreviewRequest {
 // Production line preview response
 export interface ProductionLinePreviewResponse {
   total_power_consumption: number;
+  consumption_range: { min: number; average: number; max: number };
   total_machines: number;
   total_somersloop: number;
+  output_amplification: number;
   input_rate: ItemQuantity[];
   output_rate: ItemQuantity[];
 }
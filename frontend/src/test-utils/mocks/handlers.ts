@@ -47,7 +47,7 @@ const mockLogistics: LogisticsResponse[] = [
     transport_type: 'Truck',
     transport_id: 'TRUCK-001',
     transport_name: 'Iron Transport',
-    transport_details: 'Truck transporting iron plates',
+    transport_details: { Note: 'Truck transporting iron plates' },
     items: [{ item: 'IronPlate', quantity_per_min: 60 }],
     total_quantity_per_min: 60,
   },
@@ -237,11 +237,9 @@ export const handlers = [
           transport_type: 'Truck',
           transport_id: payload.truck_id || `Truck-${newIndex}`,
           transport_name: null,
-          transport_details: JSON.stringify({
-            item: payload.item,
-            quantity_per_min: payload.quantity_per_min,
-            truck_id: payload.truck_id,
-          }),
+          transport_details: {
+            Truck: { truck_id: payload.truck_id || `Truck-${newIndex}` },
+          },
           items: quantity > 0 && payload.item
             ? [{ item: payload.item, quantity_per_min: quantity }]
             : [],
@@ -256,11 +254,9 @@ export const handlers = [
           transport_type: 'Drone',
           transport_id: payload.drone_id || `Drone-${newIndex}`,
           transport_name: null,
-          transport_details: JSON.stringify({
-            item: payload.item,
-            quantity_per_min: payload.quantity_per_min,
-            drone_id: payload.drone_id,
-          }),
+          transport_details: {
+            Drone: { drone_id: payload.drone_id || `Drone-${newIndex}` },
+          },
           items: quantity > 0 && payload.item
             ? [{ item: payload.item, quantity_per_min: quantity }]
             : [],
@@ -289,11 +285,13 @@ export const handlers = [
           transport_type: 'Bus',
           transport_id: `Bus-${newIndex}`,
           transport_name: payload.bus_name || null,
-          transport_details: JSON.stringify({
-            bus_name: payload.bus_name,
-            conveyors: conveyors,
-            pipelines: pipelines,
-          }),
+          transport_details: {
+            Bus: {
+              conveyor_ids: conveyors.map((entry, index) => entry.line_id || `CV-${index + 1}`),
+              lift_ids: [],
+              pipeline_ids: pipelines.map((entry, index) => entry.pipeline_id || `PL-${index + 1}`),
+            },
+          },
           items: flows,
           total_quantity_per_min: total,
         })
@@ -315,10 +313,11 @@ export const handlers = [
           transport_type: 'Train',
           transport_id: `Train-${newIndex}`,
           transport_name: payload.train_name || null,
-          transport_details: JSON.stringify({
-            train_name: payload.train_name,
-            wagons,
-          }),
+          transport_details: {
+            Train: {
+              wagon_ids: wagons.map((wagon, index) => wagon.wagon_id || `WG-${index + 1}`),
+            },
+          },
           items: flows,
           total_quantity_per_min: total,
         })
@@ -72,7 +72,7 @@ export function createMockLogistics(overrides: any = {}) {
     transport_type: 'Truck',
     transport_id: 'TRUCK-001',
     transport_name: 'Test Transport',
-    transport_details: 'Test logistics line',
+    transport_details: { Note: 'Test logistics line' },
     items: [
       {
         item: 'IronPlate',